@@ -30,7 +30,7 @@ use frame_support::{
 use frame_system::RawOrigin;
 use pallet_bridge::{EnsureOrigin, Get};
 use sp_arithmetic::traits::Saturating;
-use sp_runtime::traits::AccountIdConversion;
+use sp_runtime::traits::{AccountIdConversion, Zero};
 use sp_std::vec;
 
 const MAXIMUM_ISSURANCE: u32 = 20_000;
@@ -76,7 +76,51 @@ benchmarks! {
 
 		let resource_id :bridge::ResourceId= T::NativeTokenResourceId::get();
 
+		// the mint path: the limits are checked, but the transfer is neither delayed nor paused
+		Pallet::<T>::set_transfer_limit(
+			RawOrigin::Root.into(),
+			resource_id,
+			Some(TransferLimit {
+				inbound_cap: 1000u32.into(),
+				outbound_cap: 1000u32.into(),
+				max_transfer: 50u32.into(),
+				delay_threshold: 50u32.into(),
+			}),
+		)?;
+
 	}:_(RawOrigin::Signed(sender),to_account,50u32.into(),resource_id)
+	verify{
+		assert!(!DelayedTransfers::<T>::contains_key(0));
+	}
+
+	transfer_paused{
+
+		let sender = PalletId(*b"litry/bg").into_account_truncating();
+
+		let default_balance =
+		T::Currency::minimum_balance().saturating_mul(MAXIMUM_ISSURANCE.into());
+		let _ = T::Currency::deposit_creating(&sender, default_balance);
+
+		let to_account:T::AccountId = create_user::<T>("to",1u32,2u32);
+
+		let resource_id :bridge::ResourceId= T::NativeTokenResourceId::get();
+
+		// the delay and pause path: the transfer exceeds the inbound cap
+		Pallet::<T>::set_transfer_limit(
+			RawOrigin::Root.into(),
+			resource_id,
+			Some(TransferLimit {
+				inbound_cap: 0u32.into(),
+				outbound_cap: 0u32.into(),
+				max_transfer: 50u32.into(),
+				delay_threshold: 0u32.into(),
+			}),
+		)?;
+
+	}:transfer(RawOrigin::Signed(sender),to_account,50u32.into(),resource_id)
+	verify{
+		assert!(DelayedTransfers::<T>::contains_key(0));
+	}
 
 	set_maximum_issuance{
 		let origin = T::SetMaximumIssuanceOrigin::try_successful_origin().expect("SetMaximumIssuanceOrigin has no successful origin required for the benchmark");
@@ -92,6 +136,52 @@ benchmarks! {
 	verify{
 		assert_eq!(<ExternalBalances<T>>::get(),external_balances);
 	}
+
+	set_transfer_limit{
+		let resource_id :bridge::ResourceId= T::NativeTokenResourceId::get();
+		let limit = TransferLimit {
+			inbound_cap: 1000u32.into(),
+			outbound_cap: 1000u32.into(),
+			max_transfer: 100u32.into(),
+			delay_threshold: 50u32.into(),
+		};
+	}:_(RawOrigin::Root,resource_id,Some(limit.clone()))
+	verify{
+		assert_eq!(TransferLimits::<T>::get(resource_id),Some(limit));
+	}
+
+	execute_delayed_transfer{
+		let sender:T::AccountId = create_user::<T>("sender",0u32,1u32);
+		let to_account:T::AccountId = create_user::<T>("to",1u32,2u32);
+		DelayedTransfers::<T>::insert(0, DelayedTransfer {
+			to: to_account,
+			amount: 50u32.into(),
+			rid: T::NativeTokenResourceId::get(),
+			received_at: frame_system::Pallet::<T>::block_number(),
+			execute_after: frame_system::Pallet::<T>::block_number(),
+		});
+	}:_(RawOrigin::Signed(sender),0)
+	verify{
+		assert!(!DelayedTransfers::<T>::contains_key(0));
+	}
+
+	cancel_delayed_transfer{
+		let to_account:T::AccountId = create_user::<T>("to",1u32,2u32);
+		let resource_id :bridge::ResourceId= T::NativeTokenResourceId::get();
+		// the amount is still in the inbound volume and released again
+		Pallet::<T>::record_volume(resource_id, TransferDirection::Inbound, 50u32.into());
+		DelayedTransfers::<T>::insert(0, DelayedTransfer {
+			to: to_account,
+			amount: 50u32.into(),
+			rid: resource_id,
+			received_at: frame_system::Pallet::<T>::block_number(),
+			execute_after: frame_system::Pallet::<T>::block_number(),
+		});
+	}:_(RawOrigin::Root,0)
+	verify{
+		assert!(!DelayedTransfers::<T>::contains_key(0));
+		assert!(TransferVolumes::<T>::get(resource_id, TransferDirection::Inbound).current.is_zero());
+	}
 }
 
 impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
@@ -14,6 +14,22 @@
 // You should have received a copy of the GNU General Public License
 // along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
 
+//! Transfers of the native token over ChainBridge
+//!
+//! Besides the caps of the total issuance (`MaximumIssuance`, `ExternalBalances`), the bridge
+//! committee can limit the transfers of each resource with a `TransferLimit`:
+//! - a single transfer can't exceed `max_transfer`
+//! - the outbound transfers within the rolling `RateLimitWindow` can't exceed `outbound_cap`
+//! - the inbound transfers above `delay_threshold` are delayed by `TransferDelay`, during which the
+//!   committee can cancel them
+//! - when the inbound transfers within the rolling `RateLimitWindow` exceed `inbound_cap`, the
+//!   transfer is delayed and the bridge is paused via `CircuitBreaker`, i.e. the relayers can't
+//!   vote, no transfer leaves the chain and no delayed transfer executes, until the extrinsics are
+//!   unblocked in the extrinsic filter.
+//!
+//! The rolling window is approximated by the volume of the current window plus the share of
+//! the previous window which still overlaps with it.
+
 // Ensure we're `no_std` when compiling for Wasm.
 #![cfg_attr(not(feature = "std"), no_std)]
 
@@ -37,8 +53,12 @@ pub mod pallet {
 		transactional,
 	};
 	use frame_system::pallet_prelude::*;
+	use pallet_extrinsic_filter::BlockExtrinsics;
 	use pallet_parachain_staking::IssuanceAdapter;
-	use sp_runtime::traits::{BadOrigin, CheckedAdd, CheckedSub};
+	use sp_runtime::{
+		traits::{BadOrigin, CheckedAdd, CheckedSub, One, Saturating, Zero},
+		Perbill,
+	};
 	use sp_std::vec::Vec;
 
 	pub use pallet_bridge as bridge;
@@ -49,6 +69,53 @@ pub mod pallet {
 		<T as frame_system::Config>::AccountId,
 	>>::Balance;
 
+	/// Direction of a bridge transfer, seen from this chain
+	#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug, TypeInfo)]
+	pub enum TransferDirection {
+		Inbound,
+		Outbound,
+	}
+
+	/// The limits of the bridge transfers of a resource
+	#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+	pub struct TransferLimit<Balance> {
+		/// the maximum inbound volume per rolling window, above which the bridge is paused
+		pub inbound_cap: Balance,
+		/// the maximum outbound volume per rolling window, the outbound transfers above it are
+		/// rejected: unlike an inbound transfer, which has already left the other chain, they
+		/// can simply fail, and nothing has left this chain that would require a pause
+		pub outbound_cap: Balance,
+		/// the maximum amount of a single transfer
+		pub max_transfer: Balance,
+		/// the inbound transfers above it are delayed
+		pub delay_threshold: Balance,
+	}
+
+	/// The transfer volume of the current and of the previous window
+	#[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+	pub struct TransferVolume<Balance, BlockNumber> {
+		pub window_start: BlockNumber,
+		pub current: Balance,
+		pub previous: Balance,
+	}
+
+	/// An inbound transfer which can be executed from `execute_after` on
+	#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+	pub struct DelayedTransfer<AccountId, Balance, BlockNumber> {
+		pub to: AccountId,
+		pub amount: Balance,
+		pub rid: ResourceId,
+		/// the block the amount was added to the inbound volume in
+		pub received_at: BlockNumber,
+		pub execute_after: BlockNumber,
+	}
+
+	type DelayedTransferOf<T> = DelayedTransfer<
+		<T as frame_system::Config>::AccountId,
+		bridge::BalanceOf<T>,
+		<T as frame_system::Config>::BlockNumber,
+	>;
+
 	const STORAGE_VERSION: StorageVersion = StorageVersion::new(0);
 
 	#[pallet::pallet]
@@ -81,6 +148,18 @@ pub mod pallet {
 		// In parachain local decimal format
 		type ExternalTotalIssuance: Get<bridge::BalanceOf<Self>>;
 
+		/// The length of the rolling window of the transfer volume caps
+		#[pallet::constant]
+		type RateLimitWindow: Get<Self::BlockNumber>;
+
+		/// How long the inbound transfers above `delay_threshold` are delayed
+		#[pallet::constant]
+		type TransferDelay: Get<Self::BlockNumber>;
+
+		/// Pauses the bridge when the inbound volume exceeds `inbound_cap`, see
+		/// `TransferLimit::outbound_cap` for the outbound volume
+		type CircuitBreaker: BlockExtrinsics;
+
 		/// Weight information for extrinsics in this pallet.
 		type WeightInfo: WeightInfo;
 	}
@@ -92,6 +171,21 @@ pub mod pallet {
 		MaximumIssuanceChanged { old_value: BalanceOf<T> },
 		/// A certain amount of native tokens was minted
 		NativeTokenMinted { to: T::AccountId, amount: BalanceOf<T> },
+		/// The transfer limit of a resource was set or removed
+		TransferLimitSet { rid: ResourceId, limit: Option<TransferLimit<BalanceOf<T>>> },
+		/// An inbound transfer was delayed
+		TransferDelayed {
+			id: u64,
+			to: T::AccountId,
+			amount: BalanceOf<T>,
+			execute_after: T::BlockNumber,
+		},
+		/// A delayed transfer was executed
+		DelayedTransferExecuted { id: u64 },
+		/// A delayed transfer was cancelled by the committee
+		DelayedTransferCancelled { id: u64 },
+		/// The bridge was paused as the inbound volume of a resource exceeded its cap
+		BridgePaused { rid: ResourceId, volume: BalanceOf<T> },
 	}
 
 	#[pallet::error]
@@ -100,6 +194,14 @@ pub mod pallet {
 		InvalidResourceId,
 		ReachMaximumSupply,
 		OverFlow,
+		/// The amount exceeds `max_transfer` of the resource
+		TransferTooLarge,
+		/// The outbound volume would exceed `outbound_cap` of the resource
+		RateLimitExceeded,
+		/// No delayed transfer with the id was found
+		DelayedTransferDoesNotExist,
+		/// The delay of the transfer hasn't passed yet
+		DelayedTransferNotReady,
 	}
 
 	#[pallet::storage]
@@ -132,6 +234,31 @@ pub mod pallet {
 	pub type MaximumIssuance<T: Config> =
 		StorageValue<_, bridge::BalanceOf<T>, ValueQuery, T::DefaultMaximumIssuance>;
 
+	#[pallet::storage]
+	#[pallet::getter(fn transfer_limits)]
+	pub type TransferLimits<T: Config> =
+		StorageMap<_, Twox64Concat, ResourceId, TransferLimit<bridge::BalanceOf<T>>>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn transfer_volumes)]
+	pub type TransferVolumes<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		ResourceId,
+		Twox64Concat,
+		TransferDirection,
+		TransferVolume<bridge::BalanceOf<T>, T::BlockNumber>,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn delayed_transfers)]
+	pub type DelayedTransfers<T: Config> = StorageMap<_, Twox64Concat, u64, DelayedTransferOf<T>>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn next_delayed_transfer_id)]
+	pub type NextDelayedTransferId<T: Config> = StorageValue<_, u64, ValueQuery>;
+
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		/// Transfers some amount of the native token to some recipient on a (whitelisted)
@@ -149,6 +276,12 @@ pub mod pallet {
 			ensure!(T::TransferNativeMembers::contains(&source), BadOrigin);
 			let resource_id = T::NativeTokenResourceId::get();
 
+			if let Some(limit) = TransferLimits::<T>::get(resource_id) {
+				ensure!(amount <= limit.max_transfer, Error::<T>::TransferTooLarge);
+				let volume = Self::record_volume(resource_id, TransferDirection::Outbound, amount);
+				ensure!(volume <= limit.outbound_cap, Error::<T>::RateLimitExceeded);
+			}
+
 			let external_balances =
 				<ExternalBalances<T>>::get().checked_add(&amount).ok_or(Error::<T>::OverFlow)?;
 			<ExternalBalances<T>>::put(external_balances);
@@ -158,7 +291,9 @@ pub mod pallet {
 
 		/// Executes a simple currency transfer using the bridge account as the source
 		#[pallet::call_index(1)]
-		#[pallet::weight(<T as Config>::WeightInfo::transfer())]
+		#[pallet::weight(
+			<T as Config>::WeightInfo::transfer().max(<T as Config>::WeightInfo::transfer_paused())
+		)]
 		pub fn transfer(
 			origin: OriginFor<T>,
 			to: T::AccountId,
@@ -166,26 +301,26 @@ pub mod pallet {
 			rid: ResourceId,
 		) -> DispatchResult {
 			T::BridgeOrigin::ensure_origin(origin)?;
-
-			let total_issuance = <T as bridge::Config>::Currency::total_issuance();
-			let new_issuance = total_issuance.checked_add(&amount).ok_or(Error::<T>::OverFlow)?;
-			if new_issuance > MaximumIssuance::<T>::get() {
-				return Err(Error::<T>::ReachMaximumSupply.into())
+			// only the native token is minted, a transfer of another resource mustn't count
+			// towards the volume, pause the bridge or be delayed
+			ensure!(rid == T::NativeTokenResourceId::get(), Error::<T>::InvalidResourceId);
+
+			if let Some(limit) = TransferLimits::<T>::get(rid) {
+				ensure!(amount <= limit.max_transfer, Error::<T>::TransferTooLarge);
+				let volume = Self::record_volume(rid, TransferDirection::Inbound, amount);
+				if volume > limit.inbound_cap {
+					// failing here would revert the pause, so the transfer is delayed instead
+					// and left for the committee to cancel
+					Self::pause_bridge();
+					Self::deposit_event(Event::BridgePaused { rid, volume });
+					return Self::delay_transfer(to, amount, rid)
+				}
+				if amount > limit.delay_threshold {
+					return Self::delay_transfer(to, amount, rid)
+				}
 			}
-			if rid == T::NativeTokenResourceId::get() {
-				let external_balances = <ExternalBalances<T>>::get()
-					.checked_sub(&amount)
-					.ok_or(Error::<T>::OverFlow)?;
-				// ERC20 LIT mint
-				<T as bridge::Config>::Currency::mint_into(&to, amount)?;
-				// There is a Balances.Deposit event but many other extrinsics can
-				// trigger that event, use `NativeTokenMinted` for easier tracking
-				Self::deposit_event(Event::NativeTokenMinted { to, amount });
-				<ExternalBalances<T>>::put(external_balances);
-			} else {
-				return Err(Error::<T>::InvalidResourceId.into())
-			}
-			Ok(())
+
+			Self::mint(to, amount)
 		}
 
 		#[pallet::call_index(2)]
@@ -212,9 +347,154 @@ pub mod pallet {
 			<ExternalBalances<T>>::put(external_balances);
 			Ok(())
 		}
+
+		/// Sets or removes the transfer limit of a resource
+		#[pallet::call_index(4)]
+		#[pallet::weight(<T as Config>::WeightInfo::set_transfer_limit())]
+		pub fn set_transfer_limit(
+			origin: OriginFor<T>,
+			rid: ResourceId,
+			limit: Option<TransferLimit<bridge::BalanceOf<T>>>,
+		) -> DispatchResult {
+			<T as bridge::Config>::BridgeCommitteeOrigin::ensure_origin(origin)?;
+			TransferLimits::<T>::set(rid, limit.clone());
+			Self::deposit_event(Event::TransferLimitSet { rid, limit });
+			Ok(())
+		}
+
+		/// Executes a delayed transfer after its delay, can be called by anyone
+		#[pallet::call_index(5)]
+		#[pallet::weight(<T as Config>::WeightInfo::execute_delayed_transfer())]
+		pub fn execute_delayed_transfer(origin: OriginFor<T>, id: u64) -> DispatchResult {
+			ensure_signed(origin)?;
+			let transfer =
+				DelayedTransfers::<T>::get(id).ok_or(Error::<T>::DelayedTransferDoesNotExist)?;
+			ensure!(
+				<frame_system::Pallet<T>>::block_number() >= transfer.execute_after,
+				Error::<T>::DelayedTransferNotReady
+			);
+			DelayedTransfers::<T>::remove(id);
+
+			Self::mint(transfer.to, transfer.amount)?;
+			Self::deposit_event(Event::DelayedTransferExecuted { id });
+			Ok(())
+		}
+
+		/// Cancels a delayed transfer, nothing is minted and its amount is removed from the
+		/// inbound volume again
+		#[pallet::call_index(6)]
+		#[pallet::weight(<T as Config>::WeightInfo::cancel_delayed_transfer())]
+		pub fn cancel_delayed_transfer(origin: OriginFor<T>, id: u64) -> DispatchResult {
+			<T as bridge::Config>::BridgeCommitteeOrigin::ensure_origin(origin)?;
+			let transfer =
+				DelayedTransfers::<T>::take(id).ok_or(Error::<T>::DelayedTransferDoesNotExist)?;
+			Self::release_volume(
+				transfer.rid,
+				TransferDirection::Inbound,
+				transfer.amount,
+				transfer.received_at,
+			);
+			Self::deposit_event(Event::DelayedTransferCancelled { id });
+			Ok(())
+		}
 	}
 
-	impl<T: Config> Pallet<T> {}
+	impl<T: Config> Pallet<T> {
+		/// Mints the inbound transfer, within the caps of the total issuance
+		fn mint(to: T::AccountId, amount: bridge::BalanceOf<T>) -> DispatchResult {
+			let total_issuance = <T as bridge::Config>::Currency::total_issuance();
+			let new_issuance = total_issuance.checked_add(&amount).ok_or(Error::<T>::OverFlow)?;
+			if new_issuance > MaximumIssuance::<T>::get() {
+				return Err(Error::<T>::ReachMaximumSupply.into())
+			}
+			let external_balances =
+				<ExternalBalances<T>>::get().checked_sub(&amount).ok_or(Error::<T>::OverFlow)?;
+			// ERC20 LIT mint
+			<T as bridge::Config>::Currency::mint_into(&to, amount)?;
+			// There is a Balances.Deposit event but many other extrinsics can
+			// trigger that event, use `NativeTokenMinted` for easier tracking
+			Self::deposit_event(Event::NativeTokenMinted { to, amount });
+			<ExternalBalances<T>>::put(external_balances);
+			Ok(())
+		}
+
+		/// Adds `amount` to the volume of the current window, returns the volume of the rolling
+		/// window ending now
+		pub(crate) fn record_volume(
+			rid: ResourceId,
+			direction: TransferDirection,
+			amount: bridge::BalanceOf<T>,
+		) -> bridge::BalanceOf<T> {
+			let now = <frame_system::Pallet<T>>::block_number();
+			let window = T::RateLimitWindow::get().max(One::one());
+			let window_start = now - now % window;
+
+			TransferVolumes::<T>::mutate(rid, direction, |volume| {
+				if volume.window_start != window_start {
+					volume.previous = if volume.window_start + window == window_start {
+						volume.current
+					} else {
+						Zero::zero()
+					};
+					volume.current = Zero::zero();
+					volume.window_start = window_start;
+				}
+				volume.current = volume.current.saturating_add(amount);
+				// the share of the previous window which overlaps with the rolling window
+				let overlap = Perbill::from_rational(window - (now - window_start), window);
+				volume.current.saturating_add(overlap * volume.previous)
+			})
+		}
+
+		/// Removes `amount` recorded at block `recorded_at` from the volume again, nothing is
+		/// removed once that block has left the rolling window
+		fn release_volume(
+			rid: ResourceId,
+			direction: TransferDirection,
+			amount: bridge::BalanceOf<T>,
+			recorded_at: T::BlockNumber,
+		) {
+			let window = T::RateLimitWindow::get().max(One::one());
+			let window_start = recorded_at - recorded_at % window;
+
+			TransferVolumes::<T>::mutate(rid, direction, |volume| {
+				if volume.window_start == window_start {
+					volume.current = volume.current.saturating_sub(amount);
+				} else if volume.window_start == window_start + window {
+					volume.previous = volume.previous.saturating_sub(amount);
+				}
+			})
+		}
+
+		fn delay_transfer(
+			to: T::AccountId,
+			amount: bridge::BalanceOf<T>,
+			rid: ResourceId,
+		) -> DispatchResult {
+			let id = NextDelayedTransferId::<T>::get();
+			NextDelayedTransferId::<T>::put(id.checked_add(1).ok_or(Error::<T>::OverFlow)?);
+			let received_at = <frame_system::Pallet<T>>::block_number();
+			let execute_after = received_at.saturating_add(T::TransferDelay::get());
+
+			DelayedTransfers::<T>::insert(
+				id,
+				DelayedTransfer { to: to.clone(), amount, rid, received_at, execute_after },
+			);
+			Self::deposit_event(Event::TransferDelayed { id, to, amount, execute_after });
+			Ok(())
+		}
+
+		/// Blocks the extrinsics which move funds over the bridge, they have to be unblocked
+		/// in the extrinsic filter to resume
+		fn pause_bridge() {
+			let bridge_name = <bridge::Pallet<T> as PalletInfoAccess>::name().as_bytes().to_vec();
+			let name = <Self as PalletInfoAccess>::name().as_bytes().to_vec();
+			T::CircuitBreaker::block(bridge_name.clone(), Some(b"acknowledge_proposal".to_vec()));
+			T::CircuitBreaker::block(bridge_name, Some(b"eval_vote_state".to_vec()));
+			T::CircuitBreaker::block(name.clone(), Some(b"transfer_native".to_vec()));
+			T::CircuitBreaker::block(name, Some(b"execute_delayed_transfer".to_vec()));
+		}
+	}
 
 	impl<T: Config> IssuanceAdapter<BalanceOf<T>> for Pallet<T> {
 		fn adapted_total_issuance() -> BalanceOf<T> {
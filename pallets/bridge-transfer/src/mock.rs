@@ -18,7 +18,7 @@
 
 use frame_support::{
 	ord_parameter_types, parameter_types,
-	traits::{ConstU32, ConstU64, SortedMembers},
+	traits::{ConstU32, ConstU64, Everything, SortedMembers},
 	PalletId,
 };
 use frame_system::{self as system, EnsureSignedBy};
@@ -48,6 +48,7 @@ frame_support::construct_runtime!(
 		Bridge: bridge::{Pallet, Call, Storage, Event<T>},
 		BridgeTransfer: bridge_transfer::{Pallet, Call, Storage, Event<T>},
 		Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent},
+		ExtrinsicFilter: pallet_extrinsic_filter::{Pallet, Call, Storage, Event<T>},
 	}
 );
 
@@ -56,7 +57,7 @@ parameter_types! {
 }
 
 impl frame_system::Config for Test {
-	type BaseCallFilter = frame_support::traits::Everything;
+	type BaseCallFilter = ExtrinsicFilter;
 	type RuntimeOrigin = RuntimeOrigin;
 	type RuntimeCall = RuntimeCall;
 	type Index = u64;
@@ -158,6 +159,18 @@ impl Config for Test {
 	type NativeTokenResourceId = NativeTokenResourceId;
 	type DefaultMaximumIssuance = MaximumIssuance;
 	type ExternalTotalIssuance = ExternalTotalIssuance;
+	type RateLimitWindow = ConstU64<10>;
+	type TransferDelay = ConstU64<5>;
+	type CircuitBreaker = ExtrinsicFilter;
+	type WeightInfo = ();
+}
+
+impl pallet_extrinsic_filter::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type UpdateOrigin = frame_system::EnsureRoot<Self::AccountId>;
+	type NormalModeFilter = Everything;
+	type SafeModeFilter = Everything;
+	type TestModeFilter = Everything;
 	type WeightInfo = ();
 }
 
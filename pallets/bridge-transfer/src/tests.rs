@@ -19,13 +19,14 @@
 use super::{
 	bridge,
 	mock::{
-		assert_events, balances, new_test_ext, Balances, Bridge, BridgeTransfer,
+		assert_events, balances, new_test_ext, Balances, Bridge, BridgeTransfer, ExtrinsicFilter,
 		NativeTokenResourceId, ProposalLifetime, RuntimeCall, RuntimeEvent, RuntimeOrigin, Test,
 		TreasuryAccount, ENDOWED_BALANCE, MAXIMUM_ISSURANCE, RELAYER_A, RELAYER_B, RELAYER_C,
 	},
 	*,
 };
 use frame_support::{assert_noop, assert_ok};
+use sp_runtime::traits::Dispatchable;
 
 use hex_literal::hex;
 
@@ -372,3 +373,324 @@ fn set_maximum_issuance_fails_with_unprivileged_origin() {
 		assert_eq!(pallet::MaximumIssuance::<Test>::get(), mock::MaximumIssuance::get());
 	});
 }
+
+fn setup_transfer_native(dest_id: bridge::BridgeChainId) {
+	assert_ok!(Bridge::update_fee(RuntimeOrigin::root(), dest_id, 10));
+	assert_ok!(Bridge::whitelist_chain(RuntimeOrigin::root(), dest_id));
+}
+
+fn set_native_transfer_limit(inbound_cap: u64, outbound_cap: u64, delay_threshold: u64) {
+	assert_ok!(BridgeTransfer::set_transfer_limit(
+		RuntimeOrigin::root(),
+		NativeTokenResourceId::get(),
+		Some(TransferLimit { inbound_cap, outbound_cap, max_transfer: 500, delay_threshold }),
+	));
+}
+
+#[test]
+fn set_transfer_limit_works() {
+	new_test_ext().execute_with(|| {
+		let rid = NativeTokenResourceId::get();
+		let limit = TransferLimit {
+			inbound_cap: 1000,
+			outbound_cap: 1000,
+			max_transfer: 500,
+			delay_threshold: 200,
+		};
+		assert_noop!(
+			BridgeTransfer::set_transfer_limit(
+				RuntimeOrigin::signed(RELAYER_A),
+				rid,
+				Some(limit.clone())
+			),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_ok!(BridgeTransfer::set_transfer_limit(
+			RuntimeOrigin::root(),
+			rid,
+			Some(limit.clone())
+		));
+		assert_eq!(BridgeTransfer::transfer_limits(rid), Some(limit.clone()));
+		assert_events(vec![RuntimeEvent::BridgeTransfer(Event::TransferLimitSet {
+			rid,
+			limit: Some(limit),
+		})]);
+
+		assert_ok!(BridgeTransfer::set_transfer_limit(RuntimeOrigin::root(), rid, None));
+		assert_eq!(BridgeTransfer::transfer_limits(rid), None);
+	})
+}
+
+#[test]
+fn transfer_of_other_resource_ignores_limits() {
+	new_test_ext().execute_with(|| {
+		let asset = bridge::derive_resource_id(0, &bridge::hashing::blake2_128(b"an asset"));
+		assert_ok!(BridgeTransfer::set_transfer_limit(
+			RuntimeOrigin::root(),
+			asset,
+			Some(TransferLimit {
+				inbound_cap: 0,
+				outbound_cap: 0,
+				max_transfer: 500,
+				delay_threshold: 0
+			}),
+		));
+
+		// it would otherwise pause the bridge and be delayed
+		assert_noop!(
+			BridgeTransfer::transfer(
+				RuntimeOrigin::signed(Bridge::account_id()),
+				RELAYER_B,
+				100,
+				asset,
+			),
+			Error::<Test>::InvalidResourceId
+		);
+		assert!(BridgeTransfer::delayed_transfers(0).is_none());
+	})
+}
+
+#[test]
+fn transfer_fails_above_max_transfer() {
+	new_test_ext().execute_with(|| {
+		setup_transfer_native(0);
+		set_native_transfer_limit(1000, 1000, 500);
+
+		assert_noop!(
+			BridgeTransfer::transfer(
+				RuntimeOrigin::signed(Bridge::account_id()),
+				RELAYER_B,
+				501,
+				NativeTokenResourceId::get(),
+			),
+			Error::<Test>::TransferTooLarge
+		);
+		assert_noop!(
+			BridgeTransfer::transfer_native(RuntimeOrigin::signed(RELAYER_A), 501, vec![1], 0),
+			Error::<Test>::TransferTooLarge
+		);
+	})
+}
+
+#[test]
+fn transfer_native_fails_above_outbound_cap() {
+	new_test_ext().execute_with(|| {
+		setup_transfer_native(0);
+		set_native_transfer_limit(1000, 150, 500);
+
+		assert_ok!(BridgeTransfer::transfer_native(
+			RuntimeOrigin::signed(RELAYER_A),
+			100,
+			vec![1],
+			0
+		));
+		assert_noop!(
+			BridgeTransfer::transfer_native(RuntimeOrigin::signed(RELAYER_A), 100, vec![1], 0),
+			Error::<Test>::RateLimitExceeded
+		);
+
+		// the next window still overlaps with 90% of the previous one: 100 * 90% + 100 > 150
+		frame_system::Pallet::<Test>::set_block_number(11);
+		assert_noop!(
+			BridgeTransfer::transfer_native(RuntimeOrigin::signed(RELAYER_A), 100, vec![1], 0),
+			Error::<Test>::RateLimitExceeded
+		);
+		// 100 * 40% + 100 <= 150
+		frame_system::Pallet::<Test>::set_block_number(16);
+		assert_ok!(BridgeTransfer::transfer_native(
+			RuntimeOrigin::signed(RELAYER_A),
+			100,
+			vec![1],
+			0
+		));
+	})
+}
+
+#[test]
+fn transfer_above_delay_threshold_is_delayed() {
+	new_test_ext().execute_with(|| {
+		set_native_transfer_limit(1000, 1000, 100);
+
+		assert_ok!(BridgeTransfer::transfer(
+			RuntimeOrigin::signed(Bridge::account_id()),
+			RELAYER_B,
+			200,
+			NativeTokenResourceId::get(),
+		));
+		assert_eq!(Balances::free_balance(RELAYER_B), 0);
+		assert_events(vec![RuntimeEvent::BridgeTransfer(Event::TransferDelayed {
+			id: 0,
+			to: RELAYER_B,
+			amount: 200,
+			execute_after: 6,
+		})]);
+
+		assert_noop!(
+			BridgeTransfer::execute_delayed_transfer(RuntimeOrigin::signed(RELAYER_C), 0),
+			Error::<Test>::DelayedTransferNotReady
+		);
+		frame_system::Pallet::<Test>::set_block_number(6);
+		assert_ok!(BridgeTransfer::execute_delayed_transfer(RuntimeOrigin::signed(RELAYER_C), 0));
+		assert_eq!(Balances::free_balance(RELAYER_B), 200);
+		assert!(BridgeTransfer::delayed_transfers(0).is_none());
+		assert_events(vec![
+			RuntimeEvent::BridgeTransfer(Event::NativeTokenMinted { to: RELAYER_B, amount: 200 }),
+			RuntimeEvent::BridgeTransfer(Event::DelayedTransferExecuted { id: 0 }),
+		]);
+
+		// below the threshold it's minted right away
+		assert_ok!(BridgeTransfer::transfer(
+			RuntimeOrigin::signed(Bridge::account_id()),
+			RELAYER_B,
+			100,
+			NativeTokenResourceId::get(),
+		));
+		assert_eq!(Balances::free_balance(RELAYER_B), 300);
+	})
+}
+
+#[test]
+fn cancel_delayed_transfer_works() {
+	new_test_ext().execute_with(|| {
+		set_native_transfer_limit(1000, 1000, 100);
+		assert_ok!(BridgeTransfer::transfer(
+			RuntimeOrigin::signed(Bridge::account_id()),
+			RELAYER_B,
+			200,
+			NativeTokenResourceId::get(),
+		));
+
+		assert_noop!(
+			BridgeTransfer::cancel_delayed_transfer(RuntimeOrigin::signed(RELAYER_A), 0),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		let rid = NativeTokenResourceId::get();
+		assert_eq!(BridgeTransfer::transfer_volumes(rid, TransferDirection::Inbound).current, 200);
+		assert_ok!(BridgeTransfer::cancel_delayed_transfer(RuntimeOrigin::root(), 0));
+		assert_events(vec![RuntimeEvent::BridgeTransfer(Event::DelayedTransferCancelled {
+			id: 0,
+		})]);
+		// the cancelled amount doesn't count towards the inbound cap
+		assert_eq!(BridgeTransfer::transfer_volumes(rid, TransferDirection::Inbound).current, 0);
+
+		frame_system::Pallet::<Test>::set_block_number(6);
+		assert_noop!(
+			BridgeTransfer::execute_delayed_transfer(RuntimeOrigin::signed(RELAYER_C), 0),
+			Error::<Test>::DelayedTransferDoesNotExist
+		);
+		assert_eq!(Balances::free_balance(RELAYER_B), 0);
+	})
+}
+
+#[test]
+fn cancel_delayed_transfer_releases_volume_of_previous_window() {
+	new_test_ext().execute_with(|| {
+		let rid = NativeTokenResourceId::get();
+		set_native_transfer_limit(1000, 1000, 100);
+		assert_ok!(BridgeTransfer::transfer(
+			RuntimeOrigin::signed(Bridge::account_id()),
+			RELAYER_B,
+			200,
+			rid,
+		));
+
+		// the next window
+		frame_system::Pallet::<Test>::set_block_number(12);
+		assert_ok!(BridgeTransfer::transfer(
+			RuntimeOrigin::signed(Bridge::account_id()),
+			RELAYER_B,
+			50,
+			rid,
+		));
+		let volume = BridgeTransfer::transfer_volumes(rid, TransferDirection::Inbound);
+		assert_eq!((volume.current, volume.previous), (50, 200));
+
+		assert_ok!(BridgeTransfer::cancel_delayed_transfer(RuntimeOrigin::root(), 0));
+		let volume = BridgeTransfer::transfer_volumes(rid, TransferDirection::Inbound);
+		assert_eq!((volume.current, volume.previous), (50, 0));
+
+		// once the window of the transfer has left the rolling window, nothing is released
+		assert_ok!(BridgeTransfer::transfer(
+			RuntimeOrigin::signed(Bridge::account_id()),
+			RELAYER_B,
+			200,
+			rid,
+		));
+		frame_system::Pallet::<Test>::set_block_number(32);
+		assert_ok!(BridgeTransfer::transfer(
+			RuntimeOrigin::signed(Bridge::account_id()),
+			RELAYER_B,
+			50,
+			rid,
+		));
+		assert_ok!(BridgeTransfer::cancel_delayed_transfer(RuntimeOrigin::root(), 1));
+		let volume = BridgeTransfer::transfer_volumes(rid, TransferDirection::Inbound);
+		assert_eq!((volume.current, volume.previous), (50, 0));
+	})
+}
+
+#[test]
+fn transfer_above_inbound_cap_pauses_bridge() {
+	new_test_ext().execute_with(|| {
+		setup_transfer_native(0);
+		set_native_transfer_limit(100, 1000, 500);
+
+		assert_ok!(BridgeTransfer::transfer(
+			RuntimeOrigin::signed(Bridge::account_id()),
+			RELAYER_B,
+			60,
+			NativeTokenResourceId::get(),
+		));
+		assert_ok!(BridgeTransfer::transfer(
+			RuntimeOrigin::signed(Bridge::account_id()),
+			RELAYER_B,
+			60,
+			NativeTokenResourceId::get(),
+		));
+		// the second transfer is delayed
+		assert_eq!(Balances::free_balance(RELAYER_B), 60);
+		assert!(BridgeTransfer::delayed_transfers(0).is_some());
+		assert!(frame_system::Pallet::<Test>::events().iter().any(|e| e.event ==
+			RuntimeEvent::BridgeTransfer(Event::BridgePaused {
+				rid: NativeTokenResourceId::get(),
+				volume: 120
+			})));
+
+		for (pallet_name, function_name) in [
+			("Bridge", "acknowledge_proposal"),
+			("Bridge", "eval_vote_state"),
+			("BridgeTransfer", "transfer_native"),
+			("BridgeTransfer", "execute_delayed_transfer"),
+		] {
+			assert!(ExtrinsicFilter::blocked_extrinsics((
+				pallet_name.as_bytes().to_vec(),
+				function_name.as_bytes().to_vec()
+			))
+			.is_some());
+		}
+
+		let call: RuntimeCall =
+			crate::Call::transfer_native { amount: 100, recipient: vec![1], dest_id: 0 }.into();
+		assert_noop!(
+			call.dispatch(RuntimeOrigin::signed(RELAYER_A)),
+			frame_system::Error::<Test>::CallFiltered
+		);
+		frame_system::Pallet::<Test>::set_block_number(6);
+		let call: RuntimeCall = crate::Call::execute_delayed_transfer { id: 0 }.into();
+		assert_noop!(
+			call.dispatch(RuntimeOrigin::signed(RELAYER_C)),
+			frame_system::Error::<Test>::CallFiltered
+		);
+
+		// the committee can still cancel it, which releases its volume
+		assert_ok!(BridgeTransfer::cancel_delayed_transfer(RuntimeOrigin::root(), 0));
+		assert_eq!(
+			BridgeTransfer::transfer_volumes(
+				NativeTokenResourceId::get(),
+				TransferDirection::Inbound
+			)
+			.current,
+			60
+		);
+	})
+}
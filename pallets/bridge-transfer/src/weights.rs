@@ -14,11 +14,20 @@
 // You should have received a copy of the GNU General Public License
 // along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
 
-//! Autogenerated weights for pallet_bridge_transfer
+//! Weights for pallet_bridge_transfer
 //!
-//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 4.0.0-dev
+//! EDITED BY HAND, see the TODO below, after a run of the substrate benchmark CLI
+//! VERSION 4.0.0-dev:
 //! DATE: 2022-09-29, STEPS: `20`, REPEAT: 50, LOW RANGE: `[]`, HIGH RANGE: `[]`
 //! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("litentry-dev"), DB CACHE: 20
+//!
+//! TODO: regenerate this file with the command below, it was edited by hand since. Only
+//! `set_maximum_issuance` and `set_external_balances` are unchanged. `transfer_native` and
+//! `transfer` (the mint path) keep the weight measured before the transfer limits, with the
+//! storage accesses of the limits added by hand. `transfer_paused` (the delay and pause path),
+//! `set_transfer_limit`, `execute_delayed_transfer` and `cancel_delayed_transfer` were NOT
+//! benchmarked, they're given the measured weight of `transfer`, `set_external_balances`,
+//! `transfer` and `set_maximum_issuance` with their own storage accesses.
 
 // Executed Command:
 // ./target/release/litentry-collator
@@ -49,8 +58,12 @@ use sp_std::marker::PhantomData;
 pub trait WeightInfo {
 	fn transfer_native() -> Weight;
 	fn transfer() -> Weight;
+	fn transfer_paused() -> Weight;
 	fn set_maximum_issuance() -> Weight;
 	fn set_external_balances() -> Weight;
+	fn set_transfer_limit() -> Weight;
+	fn execute_delayed_transfer() -> Weight;
+	fn cancel_delayed_transfer() -> Weight;
 }
 
 /// Weights for pallet_bridge_transfer using the Litentry node and recommended hardware.
@@ -61,18 +74,32 @@ impl<T: frame_system::Config> WeightInfo for LitentryWeight<T> {
 	// Storage: ChainBridge BridgeFee (r:1 w:0)
 	// Storage: System Account (r:2 w:2)
 	// Storage: ChainBridge BridgeEvents (r:1 w:1)
+	// Storage: BridgeTransfer TransferLimits (r:1 w:0)
+	// Storage: BridgeTransfer TransferVolumes (r:1 w:1)
 	fn transfer_native() -> Weight {
 		Weight::from_ref_time(59_963_000 as u64)
-			.saturating_add(T::DbWeight::get().reads(6 as u64))
-			.saturating_add(T::DbWeight::get().writes(5 as u64))
+			.saturating_add(T::DbWeight::get().reads(8 as u64))
+			.saturating_add(T::DbWeight::get().writes(6 as u64))
 	}
+	// Storage: BridgeTransfer TransferLimits (r:1 w:0)
+	// Storage: BridgeTransfer TransferVolumes (r:1 w:1)
 	// Storage: BridgeTransfer MaximumIssuance (r:1 w:0)
 	// Storage: BridgeTransfer ExternalBalances (r:1 w:1)
 	// Storage: System Account (r:1 w:1)
 	fn transfer() -> Weight {
 		Weight::from_ref_time(29_134_000 as u64)
-			.saturating_add(T::DbWeight::get().reads(3 as u64))
-			.saturating_add(T::DbWeight::get().writes(2 as u64))
+			.saturating_add(T::DbWeight::get().reads(5 as u64))
+			.saturating_add(T::DbWeight::get().writes(3 as u64))
+	}
+	// Storage: BridgeTransfer TransferLimits (r:1 w:0)
+	// Storage: BridgeTransfer TransferVolumes (r:1 w:1)
+	// Storage: ExtrinsicFilter BlockedExtrinsics (r:4 w:4)
+	// Storage: BridgeTransfer NextDelayedTransferId (r:1 w:1)
+	// Storage: BridgeTransfer DelayedTransfers (r:0 w:1)
+	fn transfer_paused() -> Weight {
+		Weight::from_ref_time(29_134_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(7 as u64))
+			.saturating_add(T::DbWeight::get().writes(7 as u64))
 	}
 	// Storage: BridgeTransfer MaximumIssuance (r:1 w:1)
 	fn set_maximum_issuance() -> Weight {
@@ -85,6 +112,27 @@ impl<T: frame_system::Config> WeightInfo for LitentryWeight<T> {
 		Weight::from_ref_time(3_987_000 as u64)
 			.saturating_add(T::DbWeight::get().writes(1 as u64))
 	}
+	// Storage: BridgeTransfer TransferLimits (r:0 w:1)
+	fn set_transfer_limit() -> Weight {
+		Weight::from_ref_time(3_987_000 as u64)
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: BridgeTransfer DelayedTransfers (r:1 w:1)
+	// Storage: BridgeTransfer MaximumIssuance (r:1 w:0)
+	// Storage: BridgeTransfer ExternalBalances (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn execute_delayed_transfer() -> Weight {
+		Weight::from_ref_time(29_134_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(4 as u64))
+			.saturating_add(T::DbWeight::get().writes(3 as u64))
+	}
+	// Storage: BridgeTransfer DelayedTransfers (r:1 w:1)
+	// Storage: BridgeTransfer TransferVolumes (r:1 w:1)
+	fn cancel_delayed_transfer() -> Weight {
+		Weight::from_ref_time(16_170_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(2 as u64))
+			.saturating_add(T::DbWeight::get().writes(2 as u64))
+	}
 }
 
 // For backwards compatibility and tests
@@ -94,18 +142,32 @@ impl WeightInfo for () {
 	// Storage: ChainBridge BridgeFee (r:1 w:0)
 	// Storage: System Account (r:2 w:2)
 	// Storage: ChainBridge BridgeEvents (r:1 w:1)
+	// Storage: BridgeTransfer TransferLimits (r:1 w:0)
+	// Storage: BridgeTransfer TransferVolumes (r:1 w:1)
 	fn transfer_native() -> Weight {
 		Weight::from_ref_time(59_963_000 as u64)
-			.saturating_add(RocksDbWeight::get().reads(6 as u64))
-			.saturating_add(RocksDbWeight::get().writes(5 as u64))
+			.saturating_add(RocksDbWeight::get().reads(8 as u64))
+			.saturating_add(RocksDbWeight::get().writes(6 as u64))
 	}
+	// Storage: BridgeTransfer TransferLimits (r:1 w:0)
+	// Storage: BridgeTransfer TransferVolumes (r:1 w:1)
 	// Storage: BridgeTransfer MaximumIssuance (r:1 w:0)
 	// Storage: BridgeTransfer ExternalBalances (r:1 w:1)
 	// Storage: System Account (r:1 w:1)
 	fn transfer() -> Weight {
 		Weight::from_ref_time(29_134_000 as u64)
-			.saturating_add(RocksDbWeight::get().reads(3 as u64))
-			.saturating_add(RocksDbWeight::get().writes(2 as u64))
+			.saturating_add(RocksDbWeight::get().reads(5 as u64))
+			.saturating_add(RocksDbWeight::get().writes(3 as u64))
+	}
+	// Storage: BridgeTransfer TransferLimits (r:1 w:0)
+	// Storage: BridgeTransfer TransferVolumes (r:1 w:1)
+	// Storage: ExtrinsicFilter BlockedExtrinsics (r:4 w:4)
+	// Storage: BridgeTransfer NextDelayedTransferId (r:1 w:1)
+	// Storage: BridgeTransfer DelayedTransfers (r:0 w:1)
+	fn transfer_paused() -> Weight {
+		Weight::from_ref_time(29_134_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(7 as u64))
+			.saturating_add(RocksDbWeight::get().writes(7 as u64))
 	}
 	// Storage: BridgeTransfer MaximumIssuance (r:1 w:1)
 	fn set_maximum_issuance() -> Weight {
@@ -118,4 +180,25 @@ impl WeightInfo for () {
 		Weight::from_ref_time(3_987_000 as u64)
 			.saturating_add(RocksDbWeight::get().writes(1 as u64))
 	}
+	// Storage: BridgeTransfer TransferLimits (r:0 w:1)
+	fn set_transfer_limit() -> Weight {
+		Weight::from_ref_time(3_987_000 as u64)
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	// Storage: BridgeTransfer DelayedTransfers (r:1 w:1)
+	// Storage: BridgeTransfer MaximumIssuance (r:1 w:0)
+	// Storage: BridgeTransfer ExternalBalances (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn execute_delayed_transfer() -> Weight {
+		Weight::from_ref_time(29_134_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(4 as u64))
+			.saturating_add(RocksDbWeight::get().writes(3 as u64))
+	}
+	// Storage: BridgeTransfer DelayedTransfers (r:1 w:1)
+	// Storage: BridgeTransfer TransferVolumes (r:1 w:1)
+	fn cancel_delayed_transfer() -> Weight {
+		Weight::from_ref_time(16_170_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(2 as u64))
+			.saturating_add(RocksDbWeight::get().writes(2 as u64))
+	}
 }
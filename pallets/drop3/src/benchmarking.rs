@@ -20,7 +20,10 @@ use super::*;
 
 #[allow(unused)]
 use crate::Pallet as Drop3;
-use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use frame_benchmarking::{
+	account, benchmarks, impl_benchmark_test_suite, whitelisted_caller, BenchmarkError,
+	BenchmarkResult,
+};
 use frame_support::traits::{Currency, EnsureOrigin};
 use frame_system::RawOrigin;
 use sp_runtime::traits::One;
@@ -203,6 +206,90 @@ benchmarks! {
 		assert_event::<T>(Event::RewardSent { to: to.clone(), amount }.into());
 		assert_eq!(T::Currency::free_balance(&to), amount + T::Currency::minimum_balance());
 	}
+
+	set_reward_claim {
+		let caller = create_default_caller::<T>();
+		let (id, _) = create_default_proposal::<T>(caller.clone());
+		let amount = T::Currency::minimum_balance().saturating_mul(TRANSFER_ED_MULTIPLIER.into());
+		let requirement = VCRequirement::Schema(0);
+	}: _(RawOrigin::Signed(caller), id, requirement.clone(), amount)
+	verify {
+		assert_last_event::<T>(Event::RewardClaimSet { id, requirement, amount }.into());
+	}
+
+	claim_reward {
+		let caller = create_default_caller::<T>();
+		let admin: T::AccountId = whitelisted_caller();
+		let origin = T::SetAdminOrigin::try_successful_origin()
+			.expect("SetAdminOrigin has no successful origin required for the benchmark");
+		assert!(Drop3::<T>::set_admin(origin, admin.clone()).is_ok());
+		let (id, _) = create_default_proposal::<T>(caller.clone());
+		let amount = T::Currency::minimum_balance().saturating_mul(TRANSFER_ED_MULTIPLIER.into());
+		// requiring a schema is the worst case, it's looked up in the VC registry too
+		let requirement = VCRequirement::Schema(0);
+		assert!(Drop3::<T>::set_reward_claim(RawOrigin::Signed(caller).into(), id, requirement, amount).is_ok());
+		assert!(Drop3::<T>::approve_reward_pool(RawOrigin::Signed(admin.clone()).into(), id).is_ok());
+		run_to_block::<T>(1u32.into());
+		assert!(Drop3::<T>::start_reward_pool(RawOrigin::Signed(admin).into(), id).is_ok());
+
+		let to: T::AccountId = account("to", 0, SEED);
+		let _ = T::Currency::deposit_creating(&to, T::Currency::minimum_balance());
+		let vc_index = H256::from_low_u64_be(1);
+		let vc = ActiveVC {
+			subject: to.clone(),
+			assertion: Assertion::A1,
+			hash: H256::from_low_u64_be(2),
+			schema: Some(0),
+		};
+		T::VCRegistry::add_active_vc(vc_index, vc);
+		if T::VCRegistry::active_vc(&vc_index).is_none() {
+			// the runtime has no VC registry, so no reward can be claimed
+			return Err(BenchmarkError::Override(
+				BenchmarkResult::from_weight(T::BlockWeights::get().max_block)
+			));
+		}
+	}: _(RawOrigin::Signed(to.clone()), id, vc_index)
+	verify {
+		assert_last_event::<T>(Event::RewardClaimed { id, who: to.clone(), vc_index, amount }.into());
+		assert_eq!(T::Currency::free_balance(&to), amount + T::Currency::minimum_balance());
+	}
+
+	set_merkle_root {
+		let caller = create_default_caller::<T>();
+		let (id, _) = create_default_proposal::<T>(caller.clone());
+		let root = H256::from_low_u64_be(1);
+	}: _(RawOrigin::Signed(caller), id, root)
+	verify {
+		assert_last_event::<T>(Event::MerkleRootSet { id, root }.into());
+	}
+
+	claim_airdrop {
+		let n in 0 .. T::MaximumMerkleProofLength::get();
+		let caller = create_default_caller::<T>();
+		let admin: T::AccountId = whitelisted_caller();
+		let origin = T::SetAdminOrigin::try_successful_origin()
+			.expect("SetAdminOrigin has no successful origin required for the benchmark");
+		assert!(Drop3::<T>::set_admin(origin, admin.clone()).is_ok());
+		let (id, _) = create_default_proposal::<T>(caller.clone());
+
+		let to: T::AccountId = account("to", 0, SEED);
+		let _ = T::Currency::deposit_creating(&to, T::Currency::minimum_balance());
+		let amount = T::Currency::minimum_balance().saturating_mul(TRANSFER_ED_MULTIPLIER.into());
+		let index = 1u32;
+		// the leaf is the leftmost one of a tree with depth `n`
+		let proof: Vec<H256> = (0..n).map(|i| H256::from_low_u64_be(i as u64 + 1)).collect();
+		let root = proof.iter().fold(Drop3::<T>::merkle_leaf(index, &to, amount), |node, sibling| {
+			Drop3::<T>::merkle_node(&node, sibling)
+		});
+		assert!(Drop3::<T>::set_merkle_root(RawOrigin::Signed(caller).into(), id, root).is_ok());
+		assert!(Drop3::<T>::approve_reward_pool(RawOrigin::Signed(admin.clone()).into(), id).is_ok());
+		run_to_block::<T>(1u32.into());
+		assert!(Drop3::<T>::start_reward_pool(RawOrigin::Signed(admin).into(), id).is_ok());
+	}: _(RawOrigin::Signed(to.clone()), id, index, amount, proof)
+	verify {
+		assert_last_event::<T>(Event::AirdropClaimed { id, index, who: to.clone(), amount }.into());
+		assert_eq!(T::Currency::free_balance(&to), amount + T::Currency::minimum_balance());
+	}
 }
 
 impl_benchmark_test_suite!(Drop3, crate::mock::new_test_ext(), crate::mock::Test,);
@@ -25,6 +25,15 @@
 //!     where the remaining amount will be unreserved.
 //!  4. when the pool is actively running, the owner can send the reward to any other user,
 //!     the amount will be deducted directly from reserved balance.
+//!  5. alternatively, the owner can require a VC with a given assertion or schema before the
+//!     approval, then any holder of an active VC with it can claim a fixed amount once, see
+//!     `set_reward_claim` and `claim_reward`. The claim records of a closed pool are removed
+//!     in `on_idle`, in batches which fit the remaining weight of the block.
+//!  6. to airdrop to many users, the owner can commit the merkle root of the `(index, account,
+//!     amount)` leaves before the approval, then each user claims the own leaf with a merkle
+//!     proof, see `set_merkle_root` and `claim_airdrop`. The unclaimed amount is unreserved when
+//!     the pool is closed, which the owner can only do after `end_at`, the claimed leaves are
+//!     forgotten in `on_idle` like the claim records above.
 //!
 //! Some notes:
 //!  - the admin account can only be set by SetAdminOrigin, which will be bound at runtime.
@@ -49,12 +58,15 @@ mod tests;
 mod benchmarking;
 pub mod weights;
 
+use core_primitives::{ActiveVC, Assertion, SchemaIndex, VCRegistryLookup};
 use frame_support::{
 	pallet_prelude::*,
 	traits::{BalanceStatus, Currency, ReservableCurrency, StorageVersion},
 };
 use frame_system::pallet_prelude::*;
 pub use pallet::*;
+use sp_core::H256;
+use sp_io::hashing::keccak_256;
 use sp_runtime::{
 	traits::{AtLeast32BitUnsigned, Bounded, Saturating, Zero},
 	Percent,
@@ -90,9 +102,51 @@ pub struct RewardPool<PoolId, BoundedString, AccountId, Balance, BlockNumber> {
 	approved: bool,
 }
 
+/// the first byte hashed for a leaf of the airdrop tree, it's different from the one of an
+/// inner node so that a node can't be claimed as a leaf
+pub const MERKLE_LEAF_TAG: u8 = 0;
+/// the first byte hashed for an inner node of the airdrop tree
+pub const MERKLE_NODE_TAG: u8 = 1;
+
+/// the VC which is required to claim the reward of a pool
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub enum VCRequirement {
+	/// a VC requested for this assertion
+	Assertion(Assertion),
+	/// a VC issued under this schema of VCManagement
+	Schema(SchemaIndex),
+}
+
+impl VCRequirement {
+	/// whether `vc` meets the requirement
+	pub fn is_met_by<AccountId>(&self, vc: &ActiveVC<AccountId>) -> bool {
+		match self {
+			VCRequirement::Assertion(assertion) => vc.assertion == *assertion,
+			VCRequirement::Schema(schema) => vc.schema == Some(*schema),
+		}
+	}
+}
+
+/// the reward of a pool which can be claimed by the holders of a VC
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct RewardClaim<Balance> {
+	// the VC which is required to claim
+	requirement: VCRequirement,
+	// amount of token sent for each claim
+	amount: Balance,
+}
+
 type BalanceOf<T> =
 	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
+type RewardPoolOf<T> = RewardPool<
+	<T as Config>::PoolId,
+	BoundedVec<u8, <T as Config>::MaximumNameLength>,
+	<T as frame_system::Config>::AccountId,
+	BalanceOf<T>,
+	<T as frame_system::Config>::BlockNumber,
+>;
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
@@ -137,6 +191,13 @@ pub mod pallet {
 		/// The maximum length a on-chain string can be
 		#[pallet::constant]
 		type MaximumNameLength: Get<u32>;
+
+		/// The VCs which can be presented to claim a reward
+		type VCRegistry: VCRegistryLookup<Self::AccountId>;
+
+		/// The maximum length of a merkle proof to claim an airdrop
+		#[pallet::constant]
+		type MaximumMerkleProofLength: Get<u32>;
 	}
 
 	/// The reward pool admin account
@@ -176,6 +237,48 @@ pub mod pallet {
 	pub type RewardPoolOwners<T: Config> =
 		StorageMap<_, Blake2_128Concat, T::PoolId, T::AccountId, OptionQuery>;
 
+	/// Map for PoolId <> RewardClaim, only the pools with it can be claimed from
+	#[pallet::storage]
+	#[pallet::getter(fn reward_claims)]
+	pub type RewardClaims<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::PoolId, RewardClaim<BalanceOf<T>>, OptionQuery>;
+
+	/// The hashes of the VCs which were used to claim from a pool
+	#[pallet::storage]
+	#[pallet::getter(fn claimed_vcs)]
+	pub type ClaimedVCs<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, T::PoolId, Blake2_128Concat, H256, (), OptionQuery>;
+
+	/// The closed reward pools whose claim records are still to be removed, see `on_idle`
+	#[pallet::storage]
+	#[pallet::getter(fn closed_reward_pools)]
+	pub type ClosedRewardPools<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::PoolId, (), OptionQuery>;
+
+	/// Map for PoolId <> merkle root of the airdrop leaves
+	#[pallet::storage]
+	#[pallet::getter(fn merkle_roots)]
+	pub type MerkleRoots<T: Config> = StorageMap<_, Blake2_128Concat, T::PoolId, H256, OptionQuery>;
+
+	/// Bitmap of the claimed airdrop leaves, each word holds 32 leaf indexes
+	#[pallet::storage]
+	#[pallet::getter(fn claimed_airdrop_bitmap)]
+	pub type ClaimedAirdropBitmap<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, T::PoolId, Twox64Concat, u32, u32, ValueQuery>;
+
+	/// The accounts which claimed from a pool
+	#[pallet::storage]
+	#[pallet::getter(fn claimed_accounts)]
+	pub type ClaimedAccounts<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::PoolId,
+		Blake2_128Concat,
+		T::AccountId,
+		(),
+		OptionQuery,
+	>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
@@ -197,6 +300,17 @@ pub mod pallet {
 		RewardPoolProposed { id: T::PoolId, name: Vec<u8>, owner: T::AccountId },
 		/// An \[ amount \] of reward was sent to \[ to \]
 		RewardSent { to: T::AccountId, amount: BalanceOf<T> },
+		/// The holders of a VC which meets the \[ requirement \] can claim an \[ amount \] from
+		/// the reward pool with \[ id \]
+		RewardClaimSet { id: T::PoolId, requirement: VCRequirement, amount: BalanceOf<T> },
+		/// An \[ amount \] of reward was claimed by \[ who \] from the reward pool with \[ id \],
+		/// presenting the VC with \[ vc_index \]
+		RewardClaimed { id: T::PoolId, who: T::AccountId, vc_index: H256, amount: BalanceOf<T> },
+		/// The merkle \[ root \] of the airdrop was set for the reward pool with \[ id \]
+		MerkleRootSet { id: T::PoolId, root: H256 },
+		/// An \[ amount \] of airdrop was claimed by \[ who \] with the leaf \[ index \] from the
+		/// reward pool with \[ id \]
+		AirdropClaimed { id: T::PoolId, index: u32, who: T::AccountId, amount: BalanceOf<T> },
 	}
 
 	#[pallet::error]
@@ -231,6 +345,39 @@ pub mod pallet {
 		UnexpectedUnMovedAmount,
 		/// Error when no vacant PoolId can be acquired
 		NoVacantPoolId,
+		/// Error when the claimed amount is 0 or more than `total` of the reward pool
+		InvalidClaimAmount,
+		/// Error when claiming from a reward pool which doesn't require a VC
+		RewardPoolNotClaimable,
+		/// Error when the VC doesn't exist or isn't active
+		VCNotActive,
+		/// Error when the VC was issued to another account
+		RequireVCSubject,
+		/// Error when the VC doesn't meet the requirement of the reward pool
+		VCRequirementMismatch,
+		/// Error when the VC was already used to claim from the reward pool
+		VCAlreadyClaimed,
+		/// Error when the account already claimed from the reward pool
+		AccountAlreadyClaimed,
+		/// Error when the merkle root of a reward pool was already set
+		MerkleRootAlreadySet,
+		/// Error when claiming an airdrop from a reward pool without merkle root
+		NoMerkleRoot,
+		/// Error when the merkle proof is longer than MaximumMerkleProofLength
+		MerkleProofTooLong,
+		/// Error when the merkle proof doesn't lead to the merkle root
+		InvalidMerkleProof,
+		/// Error when the airdrop leaf was already claimed
+		AirdropAlreadyClaimed,
+		/// Error when the owner closes an airdrop before `end_at`
+		RewardPoolNotEnded,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_idle(_n: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+			Self::clear_closed_reward_pool(remaining_weight)
+		}
 	}
 
 	#[pallet::call]
@@ -343,7 +490,8 @@ pub mod pallet {
 		/// Close a reward pool, can be called by admin or reward pool owner
 		///
 		/// Note here `approved` state is not required, which gives the owner a
-		/// chance to close it before the admin evaluates the proposal
+		/// chance to close it before the admin evaluates the proposal.
+		/// Once an airdrop is approved, the owner can only close it after `end_at`
 		#[pallet::call_index(5)]
 		#[pallet::weight(<T as pallet::Config>::WeightInfo::close_reward_pool())]
 		pub fn close_reward_pool(
@@ -356,6 +504,13 @@ pub mod pallet {
 				Some(sender.clone()) == Self::admin() || sender == pool.owner,
 				Error::<T>::RequireAdminOrRewardPoolOwner
 			);
+			if Some(sender) != Self::admin() && pool.approved && MerkleRoots::<T>::contains_key(id)
+			{
+				ensure!(
+					<frame_system::Pallet<T>>::block_number() > pool.end_at,
+					Error::<T>::RewardPoolNotEnded
+				);
+			}
 
 			Self::unreserve_and_close_reward_pool(id)
 		}
@@ -419,43 +574,136 @@ pub mod pallet {
 			RewardPools::<T>::try_mutate(id, |pool| {
 				let mut p = pool.take().ok_or(Error::<T>::NoSuchRewardPool)?;
 				ensure!(sender == p.owner, Error::<T>::RequireRewardPoolOwner);
-				ensure!(p.approved, Error::<T>::RewardPoolUnapproved);
-				ensure!(p.started, Error::<T>::RewardPoolStopped);
-
-				let now = <frame_system::Pallet<T>>::block_number();
-				ensure!(now >= p.start_at, Error::<T>::RewardPoolRanTooEarly);
-				ensure!(now <= p.end_at, Error::<T>::RewardPoolRanTooLate);
-
-				// ensure the remaining amount of a pool >= amount to be sent
-				ensure!(p.remain >= amount, Error::<T>::InsufficientRemain);
-				// ensure the reserved balance of the pool owner >= amount of a pool
-				// theoretically this should be always true, even when one account has multiple
-				// reward pools and the balance is reserved/unreserved here and there.
-				//
-				// If it somehow fails, it implies somewhere the code logic is wrong or the the
-				// reserved balance was (unexpectedly) unreserved
-				//
-				// we don't care so much about the comparison between reserved balance and
-				// pool.remain, the worst case is pool.remain is higher than reserved balance when
-				// unreserving, which is not so bad as up to 'reserved balance' will be unreserved
-				// anyway.
-				ensure!(
-					T::Currency::reserved_balance(&p.owner) >= amount,
-					Error::<T>::InsufficientReservedBalance
-				);
-				// do the transfer from the reserved balance
-				// we shall make sure the correct amount is included in event even when
-				// unmoved is non-zero (which should not happen)
-				let unmoved =
-					T::Currency::repatriate_reserved(&p.owner, &to, amount, BalanceStatus::Free)?;
-				ensure!(unmoved == Zero::zero(), Error::<T>::UnexpectedUnMovedAmount);
-				let actual_moved = amount - unmoved;
-				p.remain = p.remain.saturating_sub(actual_moved);
+				Self::transfer_reward(&mut p, &to, amount)?;
 				*pool = Some(p);
-				Self::deposit_event(Event::RewardSent { to, amount: actual_moved });
 				Ok(().into())
 			})
 		}
+
+		/// Require a VC which meets `requirement` to claim `amount` from a reward pool
+		/// must be called by reward pool owner, before the pool is approved so that
+		/// the admin can evaluate it
+		///
+		/// A VC is issued under a schema if VCManagement records the schema for its assertion
+		#[pallet::call_index(8)]
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::set_reward_claim())]
+		pub fn set_reward_claim(
+			origin: OriginFor<T>,
+			id: T::PoolId,
+			requirement: VCRequirement,
+			amount: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+			let pool = RewardPools::<T>::get(id).ok_or(Error::<T>::NoSuchRewardPool)?;
+			ensure!(sender == pool.owner, Error::<T>::RequireRewardPoolOwner);
+			ensure!(!pool.approved, Error::<T>::RewardPoolAlreadyApproved);
+			ensure!(amount > Zero::zero() && amount <= pool.total, Error::<T>::InvalidClaimAmount);
+
+			RewardClaims::<T>::insert(id, RewardClaim { requirement: requirement.clone(), amount });
+			Self::deposit_event(Event::RewardClaimSet { id, requirement, amount });
+			Ok(().into())
+		}
+
+		/// Claim the reward of a pool by presenting the VC with `vc_index`, it must be active,
+		/// issued to the caller and meet the requirement of the pool.
+		/// Each VC and each account can only claim once from a pool.
+		#[pallet::call_index(9)]
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::claim_reward())]
+		pub fn claim_reward(
+			origin: OriginFor<T>,
+			id: T::PoolId,
+			vc_index: H256,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+			let claim = RewardClaims::<T>::get(id).ok_or(Error::<T>::RewardPoolNotClaimable)?;
+
+			let vc = T::VCRegistry::active_vc(&vc_index).ok_or(Error::<T>::VCNotActive)?;
+			ensure!(vc.subject == sender, Error::<T>::RequireVCSubject);
+			ensure!(claim.requirement.is_met_by(&vc), Error::<T>::VCRequirementMismatch);
+			ensure!(!ClaimedVCs::<T>::contains_key(id, vc.hash), Error::<T>::VCAlreadyClaimed);
+			ensure!(
+				!ClaimedAccounts::<T>::contains_key(id, &sender),
+				Error::<T>::AccountAlreadyClaimed
+			);
+
+			RewardPools::<T>::try_mutate(id, |pool| -> DispatchResultWithPostInfo {
+				let mut p = pool.take().ok_or(Error::<T>::NoSuchRewardPool)?;
+				Self::transfer_reward(&mut p, &sender, claim.amount)?;
+				*pool = Some(p);
+				Ok(().into())
+			})?;
+
+			ClaimedVCs::<T>::insert(id, vc.hash, ());
+			ClaimedAccounts::<T>::insert(id, &sender, ());
+			Self::deposit_event(Event::RewardClaimed {
+				id,
+				who: sender,
+				vc_index,
+				amount: claim.amount,
+			});
+			Ok(().into())
+		}
+
+		/// Commit the merkle root of the `(index, account, amount)` leaves of an airdrop,
+		/// see `merkle_leaf` and `merkle_node` for how the tree is built.
+		/// must be called by reward pool owner, before the pool is approved so that
+		/// the admin can evaluate it, and only once
+		#[pallet::call_index(10)]
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::set_merkle_root())]
+		pub fn set_merkle_root(
+			origin: OriginFor<T>,
+			id: T::PoolId,
+			root: H256,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+			let pool = RewardPools::<T>::get(id).ok_or(Error::<T>::NoSuchRewardPool)?;
+			ensure!(sender == pool.owner, Error::<T>::RequireRewardPoolOwner);
+			ensure!(!pool.approved, Error::<T>::RewardPoolAlreadyApproved);
+			ensure!(!MerkleRoots::<T>::contains_key(id), Error::<T>::MerkleRootAlreadySet);
+
+			MerkleRoots::<T>::insert(id, root);
+			Self::deposit_event(Event::MerkleRootSet { id, root });
+			Ok(().into())
+		}
+
+		/// Claim the airdrop leaf `(index, caller, amount)` of a reward pool with the
+		/// merkle `proof`, the sibling hashes from the leaf up to the root
+		#[pallet::call_index(11)]
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::claim_airdrop(T::MaximumMerkleProofLength::get()))]
+		pub fn claim_airdrop(
+			origin: OriginFor<T>,
+			id: T::PoolId,
+			index: u32,
+			amount: BalanceOf<T>,
+			proof: Vec<H256>,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+			let root = MerkleRoots::<T>::get(id).ok_or(Error::<T>::NoMerkleRoot)?;
+			ensure!(
+				proof.len() <= T::MaximumMerkleProofLength::get() as usize,
+				Error::<T>::MerkleProofTooLong
+			);
+			let (word, bit) = (index / 32, 1u32 << (index % 32));
+			ensure!(
+				ClaimedAirdropBitmap::<T>::get(id, word) & bit == 0,
+				Error::<T>::AirdropAlreadyClaimed
+			);
+			let leaf = Self::merkle_leaf(index, &sender, amount);
+			let computed =
+				proof.iter().fold(leaf, |node, sibling| Self::merkle_node(&node, sibling));
+			ensure!(computed == root, Error::<T>::InvalidMerkleProof);
+
+			RewardPools::<T>::try_mutate(id, |pool| -> DispatchResultWithPostInfo {
+				let mut p = pool.take().ok_or(Error::<T>::NoSuchRewardPool)?;
+				Self::transfer_reward(&mut p, &sender, amount)?;
+				*pool = Some(p);
+				Ok(().into())
+			})?;
+
+			ClaimedAirdropBitmap::<T>::mutate(id, word, |bits| *bits |= bit);
+			Self::deposit_event(Event::AirdropClaimed { id, index, who: sender, amount });
+			Ok(().into())
+		}
 	}
 
 	impl<T: Config> Pallet<T> {
@@ -466,6 +714,12 @@ pub mod pallet {
 			// we don't care if reserved balance is less than pool.remain
 			let _ = T::Currency::unreserve(&pool.owner, pool.remain);
 			RewardPoolOwners::<T>::remove(pool.id);
+			// the claim records can be too many to be removed here, see `on_idle`
+			let has_claims = RewardClaims::<T>::take(pool.id).is_some();
+			let has_airdrop = MerkleRoots::<T>::take(pool.id).is_some();
+			if has_claims || has_airdrop {
+				ClosedRewardPools::<T>::insert(pool.id, ());
+			}
 
 			Self::deposit_event(Event::RewardPoolRemoved {
 				id: pool.id,
@@ -476,6 +730,101 @@ pub mod pallet {
 			Ok(().into())
 		}
 
+		/// remove the claim records of a closed reward pool, as many as `remaining_weight`
+		/// allows, the pool is forgotten once all of them are removed
+		fn clear_closed_reward_pool(remaining_weight: Weight) -> Weight {
+			let db = T::DbWeight::get();
+			// reading the pool and forgetting it once cleared
+			let base = db.reads_writes(1, 1);
+			let per_record = db.writes(1);
+			if remaining_weight.any_lt(base.saturating_add(per_record)) {
+				return Weight::zero()
+			}
+			let id = match ClosedRewardPools::<T>::iter_keys().next() {
+				Some(id) => id,
+				None => return db.reads(1),
+			};
+
+			let limit = remaining_weight
+				.saturating_sub(base)
+				.ref_time()
+				.checked_div(per_record.ref_time())
+				.unwrap_or(u64::MAX)
+				.min(u32::MAX as u64) as u32;
+			// the limit is on the records iterated in the backend
+			let vcs = ClaimedVCs::<T>::clear_prefix(id, limit, None);
+			let mut removed = vcs.loops;
+			if vcs.maybe_cursor.is_none() && removed < limit {
+				let accounts = ClaimedAccounts::<T>::clear_prefix(id, limit - removed, None);
+				removed = removed.saturating_add(accounts.loops);
+				if accounts.maybe_cursor.is_none() && removed < limit {
+					let bitmap = ClaimedAirdropBitmap::<T>::clear_prefix(id, limit - removed, None);
+					removed = removed.saturating_add(bitmap.loops);
+					if bitmap.maybe_cursor.is_none() {
+						ClosedRewardPools::<T>::remove(id);
+					}
+				}
+			}
+			base.saturating_add(per_record.saturating_mul(removed.into()))
+		}
+
+		/// transfer an amount of reserved balance of the pool owner to `to`, if the pool is
+		/// running and has enough remaining
+		fn transfer_reward(
+			p: &mut RewardPoolOf<T>,
+			to: &T::AccountId,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			ensure!(p.approved, Error::<T>::RewardPoolUnapproved);
+			ensure!(p.started, Error::<T>::RewardPoolStopped);
+
+			let now = <frame_system::Pallet<T>>::block_number();
+			ensure!(now >= p.start_at, Error::<T>::RewardPoolRanTooEarly);
+			ensure!(now <= p.end_at, Error::<T>::RewardPoolRanTooLate);
+
+			// ensure the remaining amount of a pool >= amount to be sent
+			ensure!(p.remain >= amount, Error::<T>::InsufficientRemain);
+			// ensure the reserved balance of the pool owner >= amount of a pool
+			// theoretically this should be always true, even when one account has multiple
+			// reward pools and the balance is reserved/unreserved here and there.
+			//
+			// If it somehow fails, it implies somewhere the code logic is wrong or the the
+			// reserved balance was (unexpectedly) unreserved
+			//
+			// we don't care so much about the comparison between reserved balance and
+			// pool.remain, the worst case is pool.remain is higher than reserved balance when
+			// unreserving, which is not so bad as up to 'reserved balance' will be unreserved
+			// anyway.
+			ensure!(
+				T::Currency::reserved_balance(&p.owner) >= amount,
+				Error::<T>::InsufficientReservedBalance
+			);
+			// do the transfer from the reserved balance
+			// we shall make sure the correct amount is included in event even when
+			// unmoved is non-zero (which should not happen)
+			let unmoved =
+				T::Currency::repatriate_reserved(&p.owner, to, amount, BalanceStatus::Free)?;
+			ensure!(unmoved == Zero::zero(), Error::<T>::UnexpectedUnMovedAmount);
+			let actual_moved = amount - unmoved;
+			p.remain = p.remain.saturating_sub(actual_moved);
+			Self::deposit_event(Event::RewardSent { to: to.clone(), amount: actual_moved });
+			Ok(())
+		}
+
+		/// the hash of an airdrop leaf, i.e. keccak256 of the scale encoded
+		/// `(MERKLE_LEAF_TAG, index, account, amount)`
+		pub fn merkle_leaf(index: u32, account: &T::AccountId, amount: BalanceOf<T>) -> H256 {
+			keccak_256(&(MERKLE_LEAF_TAG, index, account, amount).encode()).into()
+		}
+
+		/// the hash of an inner node of the airdrop tree, i.e. keccak256 of `MERKLE_NODE_TAG`
+		/// followed by the children, they're sorted so that the proof doesn't need to tell
+		/// the position of the siblings
+		pub fn merkle_node(a: &H256, b: &H256) -> H256 {
+			let (left, right) = if a <= b { (a, b) } else { (b, a) };
+			keccak_256(&[&[MERKLE_NODE_TAG][..], left.as_bytes(), right.as_bytes()].concat()).into()
+		}
+
 		pub fn get_sorted_pool_ids() -> Vec<T::PoolId> {
 			let mut ids = RewardPools::<T>::iter_keys().collect::<Vec<T::PoolId>>();
 			ids.sort();
@@ -491,8 +840,11 @@ pub mod pallet {
 				})
 			}
 
-			// otherwise find the vacant id from the beginning
-			let sorted_ids = Self::get_sorted_pool_ids();
+			// otherwise find the vacant id from the beginning, an id is only vacant once the
+			// claim records of its closed pool are removed
+			let mut sorted_ids = Self::get_sorted_pool_ids();
+			sorted_ids.extend(ClosedRewardPools::<T>::iter_keys());
+			sorted_ids.sort();
 			for (idx, id) in sorted_ids.iter().enumerate() {
 				let expected_id: T::PoolId = (idx as u64).checked_add(1u64).unwrap().into();
 				if id != &expected_id {
@@ -15,9 +15,11 @@
 // along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
 
 use crate as pallet_drop3;
+use core_primitives::{ActiveVC, Assertion, SchemaIndex, VCRegistryLookup};
 use frame_support::{
 	ord_parameter_types, parameter_types,
 	traits::{ConstU128, ConstU16, ConstU32},
+	weights::constants::RocksDbWeight,
 };
 use frame_system as system;
 use frame_system::EnsureSignedBy;
@@ -27,7 +29,7 @@ use sp_runtime::{
 	traits::{BlakeTwo256, IdentityLookup},
 	Percent,
 };
-use sp_std::vec;
+use sp_std::{cell::RefCell, collections::btree_map::BTreeMap, vec};
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
 type Block = frame_system::mocking::MockBlock<Test>;
@@ -56,7 +58,7 @@ impl system::Config for Test {
 	type BaseCallFilter = frame_support::traits::Everything;
 	type BlockWeights = ();
 	type BlockLength = ();
-	type DbWeight = ();
+	type DbWeight = RocksDbWeight;
 	type RuntimeOrigin = RuntimeOrigin;
 	type RuntimeCall = RuntimeCall;
 	type Index = u64;
@@ -99,6 +101,48 @@ ord_parameter_types! {
 	pub const One: u64 = 1;
 }
 
+thread_local! {
+	static ACTIVE_VCS: RefCell<BTreeMap<H256, ActiveVC<u64>>> = RefCell::new(BTreeMap::new());
+}
+
+// a VC registry where the VCs are added by the tests
+pub struct MockVCRegistry;
+
+impl MockVCRegistry {
+	pub(crate) fn add_vc(index: H256, subject: u64, assertion: Assertion, hash: H256) {
+		Self::insert_vc(index, ActiveVC { subject, assertion, hash, schema: None })
+	}
+
+	pub(crate) fn add_vc_with_schema(
+		index: H256,
+		subject: u64,
+		assertion: Assertion,
+		hash: H256,
+		schema: SchemaIndex,
+	) {
+		Self::insert_vc(index, ActiveVC { subject, assertion, hash, schema: Some(schema) })
+	}
+
+	pub(crate) fn remove_vc(index: &H256) {
+		ACTIVE_VCS.with(|vcs| vcs.borrow_mut().remove(index));
+	}
+
+	fn insert_vc(index: H256, vc: ActiveVC<u64>) {
+		ACTIVE_VCS.with(|vcs| vcs.borrow_mut().insert(index, vc));
+	}
+}
+
+impl VCRegistryLookup<u64> for MockVCRegistry {
+	fn active_vc(index: &H256) -> Option<ActiveVC<u64>> {
+		ACTIVE_VCS.with(|vcs| vcs.borrow().get(index).cloned())
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn add_active_vc(index: H256, vc: ActiveVC<u64>) {
+		Self::insert_vc(index, vc)
+	}
+}
+
 impl pallet_drop3::Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type PoolId = PoolId;
@@ -107,6 +151,8 @@ impl pallet_drop3::Config for Test {
 	type WeightInfo = ();
 	type SlashPercent = SlashPercent;
 	type MaximumNameLength = ConstU32<16>;
+	type VCRegistry = MockVCRegistry;
+	type MaximumMerkleProofLength = ConstU32<4>;
 }
 
 // propose a default reward pool with the given id
@@ -143,6 +189,7 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 	let mut ext = sp_io::TestExternalities::new(t);
 	ext.execute_with(|| {
 		System::set_block_number(1);
+		ACTIVE_VCS.with(|vcs| vcs.borrow_mut().clear());
 		// set 1 as admin account
 		let _ = Drop3::set_admin(RuntimeOrigin::signed(1), 1);
 	});
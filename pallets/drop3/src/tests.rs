@@ -14,11 +14,16 @@
 // You should have received a copy of the GNU General Public License
 // along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
 
-use crate::{mock::*, Error};
+use crate::{mock::*, Error, VCRequirement};
+use codec::Encode;
+use core_primitives::Assertion;
 use frame_support::{
 	assert_noop, assert_ok,
-	traits::{Currency, ReservableCurrency},
+	traits::{Currency, Get, Hooks, ReservableCurrency},
+	weights::Weight,
 };
+use sp_core::H256;
+use sp_io::hashing::keccak_256;
 
 #[test]
 fn set_admin_works() {
@@ -570,3 +575,610 @@ fn send_reward_fails_with_insufficient_remain() {
 		);
 	});
 }
+
+// propose the pool 1 owned by 3, which requires a VC with `A1` to claim 10,
+// then approve and start it
+fn setup_claimable_reward_pool() {
+	let _ = Balances::deposit_creating(&3, 100);
+	let _ = Balances::deposit_creating(&4, 5);
+	let _ = Balances::deposit_creating(&5, 5);
+
+	assert_ok!(Drop3::propose_reward_pool(RuntimeOrigin::signed(3), b"test".to_vec(), 100, 1, 3));
+	assert_ok!(Drop3::set_reward_claim(
+		RuntimeOrigin::signed(3),
+		1,
+		VCRequirement::Assertion(Assertion::A1),
+		10
+	));
+	assert_ok!(Drop3::approve_reward_pool(RuntimeOrigin::signed(1), 1));
+	assert_ok!(Drop3::start_reward_pool(RuntimeOrigin::signed(3), 1));
+}
+
+#[test]
+fn set_reward_claim_works() {
+	new_test_ext().execute_with(|| {
+		let _ = Balances::deposit_creating(&3, 100);
+		assert_ok!(Drop3::propose_reward_pool(
+			RuntimeOrigin::signed(3),
+			b"test".to_vec(),
+			100,
+			1,
+			3
+		));
+		assert_ok!(Drop3::set_reward_claim(
+			RuntimeOrigin::signed(3),
+			1,
+			VCRequirement::Assertion(Assertion::A1),
+			10
+		));
+		assert!(Drop3::reward_claims(1).is_some());
+		System::assert_last_event(RuntimeEvent::Drop3(crate::Event::RewardClaimSet {
+			id: 1,
+			requirement: VCRequirement::Assertion(Assertion::A1),
+			amount: 10,
+		}));
+	});
+}
+
+#[test]
+fn set_reward_claim_fails() {
+	new_test_ext().execute_with(|| {
+		let _ = Balances::deposit_creating(&3, 100);
+		assert_ok!(Drop3::propose_reward_pool(
+			RuntimeOrigin::signed(3),
+			b"test".to_vec(),
+			100,
+			1,
+			3
+		));
+		assert_noop!(
+			Drop3::set_reward_claim(
+				RuntimeOrigin::signed(4),
+				1,
+				VCRequirement::Assertion(Assertion::A1),
+				10
+			),
+			Error::<Test>::RequireRewardPoolOwner
+		);
+		assert_noop!(
+			Drop3::set_reward_claim(
+				RuntimeOrigin::signed(3),
+				1,
+				VCRequirement::Assertion(Assertion::A1),
+				0
+			),
+			Error::<Test>::InvalidClaimAmount
+		);
+		assert_noop!(
+			Drop3::set_reward_claim(
+				RuntimeOrigin::signed(3),
+				1,
+				VCRequirement::Assertion(Assertion::A1),
+				101
+			),
+			Error::<Test>::InvalidClaimAmount
+		);
+		assert_ok!(Drop3::approve_reward_pool(RuntimeOrigin::signed(1), 1));
+		assert_noop!(
+			Drop3::set_reward_claim(
+				RuntimeOrigin::signed(3),
+				1,
+				VCRequirement::Assertion(Assertion::A1),
+				10
+			),
+			Error::<Test>::RewardPoolAlreadyApproved
+		);
+	});
+}
+
+#[test]
+fn claim_reward_works() {
+	new_test_ext().execute_with(|| {
+		setup_claimable_reward_pool();
+		MockVCRegistry::add_vc(
+			H256::from_low_u64_be(1),
+			4,
+			Assertion::A1,
+			H256::from_low_u64_be(2),
+		);
+
+		assert_ok!(Drop3::claim_reward(RuntimeOrigin::signed(4), 1, H256::from_low_u64_be(1)));
+		assert_eq!(Balances::free_balance(4), 15);
+		assert_eq!(Balances::reserved_balance(3), 90);
+		assert_eq!(Drop3::reward_pools(1).unwrap().remain, 90);
+		assert!(Drop3::claimed_vcs(1, H256::from_low_u64_be(2)).is_some());
+		assert!(Drop3::claimed_accounts(1, 4).is_some());
+		System::assert_last_event(RuntimeEvent::Drop3(crate::Event::RewardClaimed {
+			id: 1,
+			who: 4,
+			vc_index: H256::from_low_u64_be(1),
+			amount: 10,
+		}));
+	});
+}
+
+#[test]
+fn claim_reward_fails_with_invalid_vc() {
+	new_test_ext().execute_with(|| {
+		setup_claimable_reward_pool();
+		MockVCRegistry::add_vc(
+			H256::from_low_u64_be(1),
+			4,
+			Assertion::A1,
+			H256::from_low_u64_be(2),
+		);
+		MockVCRegistry::add_vc(
+			H256::from_low_u64_be(3),
+			4,
+			Assertion::A6,
+			H256::from_low_u64_be(4),
+		);
+
+		assert_noop!(
+			Drop3::claim_reward(RuntimeOrigin::signed(4), 1, H256::from_low_u64_be(5)),
+			Error::<Test>::VCNotActive
+		);
+		assert_noop!(
+			Drop3::claim_reward(RuntimeOrigin::signed(5), 1, H256::from_low_u64_be(1)),
+			Error::<Test>::RequireVCSubject
+		);
+		assert_noop!(
+			Drop3::claim_reward(RuntimeOrigin::signed(4), 1, H256::from_low_u64_be(3)),
+			Error::<Test>::VCRequirementMismatch
+		);
+
+		// e.g. the VC was disabled or revoked
+		MockVCRegistry::remove_vc(&H256::from_low_u64_be(1));
+		assert_noop!(
+			Drop3::claim_reward(RuntimeOrigin::signed(4), 1, H256::from_low_u64_be(1)),
+			Error::<Test>::VCNotActive
+		);
+	});
+}
+
+#[test]
+fn claim_reward_fails_when_already_claimed() {
+	new_test_ext().execute_with(|| {
+		setup_claimable_reward_pool();
+		MockVCRegistry::add_vc(
+			H256::from_low_u64_be(1),
+			4,
+			Assertion::A1,
+			H256::from_low_u64_be(2),
+		);
+		// another VC for the same account
+		MockVCRegistry::add_vc(
+			H256::from_low_u64_be(3),
+			4,
+			Assertion::A1,
+			H256::from_low_u64_be(4),
+		);
+		// the same VC content registered again for another account
+		MockVCRegistry::add_vc(
+			H256::from_low_u64_be(5),
+			5,
+			Assertion::A1,
+			H256::from_low_u64_be(2),
+		);
+
+		assert_ok!(Drop3::claim_reward(RuntimeOrigin::signed(4), 1, H256::from_low_u64_be(1)));
+		assert_noop!(
+			Drop3::claim_reward(RuntimeOrigin::signed(4), 1, H256::from_low_u64_be(1)),
+			Error::<Test>::VCAlreadyClaimed
+		);
+		assert_noop!(
+			Drop3::claim_reward(RuntimeOrigin::signed(4), 1, H256::from_low_u64_be(3)),
+			Error::<Test>::AccountAlreadyClaimed
+		);
+		assert_noop!(
+			Drop3::claim_reward(RuntimeOrigin::signed(5), 1, H256::from_low_u64_be(5)),
+			Error::<Test>::VCAlreadyClaimed
+		);
+	});
+}
+
+#[test]
+fn claim_reward_fails_with_unclaimable_or_stopped_pool() {
+	new_test_ext().execute_with(|| {
+		let _ = Balances::deposit_creating(&3, 100);
+		let _ = Balances::deposit_creating(&4, 5);
+		MockVCRegistry::add_vc(
+			H256::from_low_u64_be(1),
+			4,
+			Assertion::A1,
+			H256::from_low_u64_be(2),
+		);
+
+		assert_ok!(Drop3::propose_reward_pool(
+			RuntimeOrigin::signed(3),
+			b"test".to_vec(),
+			100,
+			1,
+			3
+		));
+		assert_ok!(Drop3::approve_reward_pool(RuntimeOrigin::signed(1), 1));
+		assert_ok!(Drop3::start_reward_pool(RuntimeOrigin::signed(3), 1));
+		assert_noop!(
+			Drop3::claim_reward(RuntimeOrigin::signed(4), 1, H256::from_low_u64_be(1)),
+			Error::<Test>::RewardPoolNotClaimable
+		);
+
+		assert_ok!(Drop3::propose_reward_pool(
+			RuntimeOrigin::signed(3),
+			b"test".to_vec(),
+			100,
+			1,
+			3
+		));
+		assert_ok!(Drop3::set_reward_claim(
+			RuntimeOrigin::signed(3),
+			2,
+			VCRequirement::Assertion(Assertion::A1),
+			10
+		));
+		assert_noop!(
+			Drop3::claim_reward(RuntimeOrigin::signed(4), 2, H256::from_low_u64_be(1)),
+			Error::<Test>::RewardPoolUnapproved
+		);
+	});
+}
+
+#[test]
+fn claim_reward_with_schema_works() {
+	new_test_ext().execute_with(|| {
+		let _ = Balances::deposit_creating(&3, 100);
+		let _ = Balances::deposit_creating(&4, 5);
+		assert_ok!(Drop3::propose_reward_pool(
+			RuntimeOrigin::signed(3),
+			b"test".to_vec(),
+			100,
+			1,
+			3
+		));
+		assert_ok!(Drop3::set_reward_claim(
+			RuntimeOrigin::signed(3),
+			1,
+			VCRequirement::Schema(7),
+			10
+		));
+		assert_ok!(Drop3::approve_reward_pool(RuntimeOrigin::signed(1), 1));
+		assert_ok!(Drop3::start_reward_pool(RuntimeOrigin::signed(3), 1));
+
+		// the assertion doesn't matter, but the schema does
+		MockVCRegistry::add_vc(
+			H256::from_low_u64_be(1),
+			4,
+			Assertion::A1,
+			H256::from_low_u64_be(2),
+		);
+		MockVCRegistry::add_vc_with_schema(
+			H256::from_low_u64_be(3),
+			4,
+			Assertion::A1,
+			H256::from_low_u64_be(4),
+			8,
+		);
+		MockVCRegistry::add_vc_with_schema(
+			H256::from_low_u64_be(5),
+			4,
+			Assertion::A6,
+			H256::from_low_u64_be(6),
+			7,
+		);
+
+		assert_noop!(
+			Drop3::claim_reward(RuntimeOrigin::signed(4), 1, H256::from_low_u64_be(1)),
+			Error::<Test>::VCRequirementMismatch
+		);
+		assert_noop!(
+			Drop3::claim_reward(RuntimeOrigin::signed(4), 1, H256::from_low_u64_be(3)),
+			Error::<Test>::VCRequirementMismatch
+		);
+		assert_ok!(Drop3::claim_reward(RuntimeOrigin::signed(4), 1, H256::from_low_u64_be(5)));
+		assert_eq!(Balances::free_balance(4), 15);
+	});
+}
+
+#[test]
+fn close_reward_pool_removes_claims_when_idle() {
+	let mut ext = new_test_ext();
+	ext.execute_with(|| {
+		setup_claimable_reward_pool();
+		MockVCRegistry::add_vc(
+			H256::from_low_u64_be(1),
+			4,
+			Assertion::A1,
+			H256::from_low_u64_be(2),
+		);
+		MockVCRegistry::add_vc(
+			H256::from_low_u64_be(3),
+			5,
+			Assertion::A1,
+			H256::from_low_u64_be(4),
+		);
+		assert_ok!(Drop3::claim_reward(RuntimeOrigin::signed(4), 1, H256::from_low_u64_be(1)));
+		assert_ok!(Drop3::claim_reward(RuntimeOrigin::signed(5), 1, H256::from_low_u64_be(3)));
+
+		assert_ok!(Drop3::close_reward_pool(RuntimeOrigin::signed(3), 1));
+		assert!(Drop3::reward_claims(1).is_none());
+		assert!(Drop3::closed_reward_pools(1).is_some());
+		// the claims are kept until there's weight left in a block
+		assert!(Drop3::claimed_vcs(1, H256::from_low_u64_be(2)).is_some());
+		assert!(Drop3::claimed_accounts(1, 4).is_some());
+	});
+	// the records are only removed in batches from the backend, as in the next blocks
+	ext.commit_all().unwrap();
+
+	ext.execute_with(|| {
+		// not even enough weight for one record
+		let db = <Test as frame_system::Config>::DbWeight::get();
+		assert_eq!(Drop3::on_idle(1, db.reads_writes(1, 1)), Weight::zero());
+		assert!(Drop3::closed_reward_pools(1).is_some());
+
+		// enough weight for 3 of the 4 records
+		let weight = db.reads_writes(1, 1).saturating_add(db.writes(3));
+		assert_eq!(Drop3::on_idle(1, weight), weight);
+		assert!(Drop3::claimed_vcs(1, H256::from_low_u64_be(2)).is_none());
+		assert!(Drop3::claimed_vcs(1, H256::from_low_u64_be(4)).is_none());
+		assert!(Drop3::closed_reward_pools(1).is_some());
+
+		Drop3::on_idle(2, Weight::MAX);
+		assert!(Drop3::claimed_accounts(1, 4).is_none());
+		assert!(Drop3::claimed_accounts(1, 5).is_none());
+		assert!(Drop3::closed_reward_pools(1).is_none());
+	});
+}
+
+#[test]
+fn closed_reward_pool_id_is_not_reused_until_cleared() {
+	new_test_ext().execute_with(|| {
+		setup_claimable_reward_pool();
+		MockVCRegistry::add_vc(
+			H256::from_low_u64_be(1),
+			4,
+			Assertion::A1,
+			H256::from_low_u64_be(2),
+		);
+		assert_ok!(Drop3::claim_reward(RuntimeOrigin::signed(4), 1, H256::from_low_u64_be(1)));
+		assert_ok!(Drop3::close_reward_pool(RuntimeOrigin::signed(3), 1));
+
+		// make the id wrap, 2 is the only vacant id while the claims of 1 are kept
+		crate::CurrentMaxPoolId::<Test>::put(PoolId::max_value());
+		propose_default_reward_pool(3, false);
+		assert_ok!(Drop3::propose_reward_pool(
+			RuntimeOrigin::signed(3),
+			b"test".to_vec(),
+			10,
+			1,
+			3
+		));
+		assert_eq!(Drop3::get_sorted_pool_ids(), vec![2, 3]);
+
+		Drop3::on_idle(1, Weight::MAX);
+		assert!(Drop3::closed_reward_pools(1).is_none());
+		assert_ok!(Drop3::propose_reward_pool(
+			RuntimeOrigin::signed(3),
+			b"test".to_vec(),
+			10,
+			1,
+			3
+		));
+		assert_eq!(Drop3::get_sorted_pool_ids(), vec![1, 2, 3]);
+	});
+}
+
+// the merkle tree of the airdrop leaves (0, 4, 10), (1, 5, 20), (33, 6, 30), (3, 7, 40)
+// returns the root and the proof of each leaf
+fn airdrop_tree() -> (H256, Vec<Vec<H256>>) {
+	let leaves = vec![
+		Drop3::merkle_leaf(0, &4, 10),
+		Drop3::merkle_leaf(1, &5, 20),
+		Drop3::merkle_leaf(33, &6, 30),
+		Drop3::merkle_leaf(3, &7, 40),
+	];
+	let left = Drop3::merkle_node(&leaves[0], &leaves[1]);
+	let right = Drop3::merkle_node(&leaves[2], &leaves[3]);
+	let root = Drop3::merkle_node(&left, &right);
+	let proofs = vec![
+		vec![leaves[1], right],
+		vec![leaves[0], right],
+		vec![leaves[3], left],
+		vec![leaves[2], left],
+	];
+	(root, proofs)
+}
+
+// propose the pool 1 owned by 3 with the airdrop_tree, which runs from block 1 to 3,
+// then approve and start it
+fn setup_airdrop_reward_pool() -> Vec<Vec<H256>> {
+	let _ = Balances::deposit_creating(&3, 100);
+	for who in 4..8 {
+		let _ = Balances::deposit_creating(&who, 5);
+	}
+	let (root, proofs) = airdrop_tree();
+
+	assert_ok!(Drop3::propose_reward_pool(RuntimeOrigin::signed(3), b"test".to_vec(), 100, 1, 3));
+	assert_ok!(Drop3::set_merkle_root(RuntimeOrigin::signed(3), 1, root));
+	assert_ok!(Drop3::approve_reward_pool(RuntimeOrigin::signed(1), 1));
+	assert_ok!(Drop3::start_reward_pool(RuntimeOrigin::signed(3), 1));
+	proofs
+}
+
+#[test]
+fn merkle_hashes_are_domain_separated() {
+	new_test_ext().execute_with(|| {
+		let leaf = Drop3::merkle_leaf(0, &4, 10);
+		assert_eq!(leaf, H256::from(keccak_256(&(0u8, 0u32, 4u64, 10u128).encode())));
+
+		let other = Drop3::merkle_leaf(1, &5, 20);
+		let (left, right) = if leaf <= other { (leaf, other) } else { (other, leaf) };
+		let mut node = vec![1u8];
+		node.extend_from_slice(left.as_bytes());
+		node.extend_from_slice(right.as_bytes());
+		assert_eq!(Drop3::merkle_node(&leaf, &other), H256::from(keccak_256(&node)));
+		assert_eq!(Drop3::merkle_node(&other, &leaf), H256::from(keccak_256(&node)));
+	});
+}
+
+#[test]
+fn set_merkle_root_works() {
+	new_test_ext().execute_with(|| {
+		let _ = Balances::deposit_creating(&3, 100);
+		let (root, _) = airdrop_tree();
+		assert_ok!(Drop3::propose_reward_pool(
+			RuntimeOrigin::signed(3),
+			b"test".to_vec(),
+			100,
+			1,
+			3
+		));
+
+		assert_noop!(
+			Drop3::set_merkle_root(RuntimeOrigin::signed(4), 1, root),
+			Error::<Test>::RequireRewardPoolOwner
+		);
+		assert_ok!(Drop3::set_merkle_root(RuntimeOrigin::signed(3), 1, root));
+		assert_eq!(Drop3::merkle_roots(1), Some(root));
+		System::assert_last_event(RuntimeEvent::Drop3(crate::Event::MerkleRootSet { id: 1, root }));
+		assert_noop!(
+			Drop3::set_merkle_root(RuntimeOrigin::signed(3), 1, H256::default()),
+			Error::<Test>::MerkleRootAlreadySet
+		);
+	});
+}
+
+#[test]
+fn set_merkle_root_fails_with_approved_pool() {
+	new_test_ext().execute_with(|| {
+		let _ = Balances::deposit_creating(&3, 100);
+		let (root, _) = airdrop_tree();
+		assert_ok!(Drop3::propose_reward_pool(
+			RuntimeOrigin::signed(3),
+			b"test".to_vec(),
+			100,
+			1,
+			3
+		));
+		assert_ok!(Drop3::approve_reward_pool(RuntimeOrigin::signed(1), 1));
+
+		assert_noop!(
+			Drop3::set_merkle_root(RuntimeOrigin::signed(3), 1, root),
+			Error::<Test>::RewardPoolAlreadyApproved
+		);
+	});
+}
+
+#[test]
+fn claim_airdrop_works() {
+	new_test_ext().execute_with(|| {
+		let proofs = setup_airdrop_reward_pool();
+
+		assert_ok!(Drop3::claim_airdrop(RuntimeOrigin::signed(4), 1, 0, 10, proofs[0].clone()));
+		assert_ok!(Drop3::claim_airdrop(RuntimeOrigin::signed(6), 1, 33, 30, proofs[2].clone()));
+		System::assert_last_event(RuntimeEvent::Drop3(crate::Event::AirdropClaimed {
+			id: 1,
+			index: 33,
+			who: 6,
+			amount: 30,
+		}));
+		assert_eq!(Balances::free_balance(4), 15);
+		assert_eq!(Balances::free_balance(6), 35);
+		assert_eq!(Balances::reserved_balance(3), 60);
+		assert_eq!(Drop3::reward_pools(1).unwrap().remain, 60);
+		assert_eq!(Drop3::claimed_airdrop_bitmap(1, 0), 1);
+		assert_eq!(Drop3::claimed_airdrop_bitmap(1, 1), 2);
+	});
+}
+
+#[test]
+fn claim_airdrop_fails_with_invalid_leaf_or_proof() {
+	new_test_ext().execute_with(|| {
+		let proofs = setup_airdrop_reward_pool();
+
+		// wrong amount
+		assert_noop!(
+			Drop3::claim_airdrop(RuntimeOrigin::signed(4), 1, 0, 20, proofs[0].clone()),
+			Error::<Test>::InvalidMerkleProof
+		);
+		// wrong account
+		assert_noop!(
+			Drop3::claim_airdrop(RuntimeOrigin::signed(5), 1, 0, 10, proofs[0].clone()),
+			Error::<Test>::InvalidMerkleProof
+		);
+		// wrong proof
+		assert_noop!(
+			Drop3::claim_airdrop(RuntimeOrigin::signed(4), 1, 0, 10, proofs[1].clone()),
+			Error::<Test>::InvalidMerkleProof
+		);
+		assert_noop!(
+			Drop3::claim_airdrop(RuntimeOrigin::signed(4), 1, 0, 10, vec![H256::default(); 5]),
+			Error::<Test>::MerkleProofTooLong
+		);
+		assert_noop!(
+			Drop3::claim_airdrop(RuntimeOrigin::signed(4), 2, 0, 10, proofs[0].clone()),
+			Error::<Test>::NoMerkleRoot
+		);
+	});
+}
+
+#[test]
+fn claim_airdrop_fails_when_already_claimed() {
+	new_test_ext().execute_with(|| {
+		let proofs = setup_airdrop_reward_pool();
+
+		assert_ok!(Drop3::claim_airdrop(RuntimeOrigin::signed(5), 1, 1, 20, proofs[1].clone()));
+		assert_noop!(
+			Drop3::claim_airdrop(RuntimeOrigin::signed(5), 1, 1, 20, proofs[1].clone()),
+			Error::<Test>::AirdropAlreadyClaimed
+		);
+	});
+}
+
+#[test]
+fn claim_airdrop_fails_after_end_at() {
+	new_test_ext().execute_with(|| {
+		let proofs = setup_airdrop_reward_pool();
+
+		System::set_block_number(4);
+		assert_noop!(
+			Drop3::claim_airdrop(RuntimeOrigin::signed(4), 1, 0, 10, proofs[0].clone()),
+			Error::<Test>::RewardPoolRanTooLate
+		);
+	});
+}
+
+#[test]
+fn close_airdrop_reward_pool_unreserves_unclaimed() {
+	new_test_ext().execute_with(|| {
+		let proofs = setup_airdrop_reward_pool();
+		assert_ok!(Drop3::claim_airdrop(RuntimeOrigin::signed(4), 1, 0, 10, proofs[0].clone()));
+
+		assert_noop!(
+			Drop3::close_reward_pool(RuntimeOrigin::signed(3), 1),
+			Error::<Test>::RewardPoolNotEnded
+		);
+
+		System::set_block_number(4);
+		assert_ok!(Drop3::close_reward_pool(RuntimeOrigin::signed(3), 1));
+		assert_eq!(Balances::free_balance(3), 90);
+		assert_eq!(Balances::reserved_balance(3), 0);
+		assert!(Drop3::merkle_roots(1).is_none());
+		// the claimed leaves are forgotten when there's weight left in a block
+		assert_eq!(Drop3::claimed_airdrop_bitmap(1, 0), 1);
+		assert!(Drop3::closed_reward_pools(1).is_some());
+
+		Drop3::on_idle(4, Weight::MAX);
+		assert_eq!(Drop3::claimed_airdrop_bitmap(1, 0), 0);
+		assert!(Drop3::closed_reward_pools(1).is_none());
+	});
+}
+
+#[test]
+fn admin_can_close_airdrop_reward_pool_before_end_at() {
+	new_test_ext().execute_with(|| {
+		let _ = setup_airdrop_reward_pool();
+
+		assert_ok!(Drop3::close_reward_pool(RuntimeOrigin::signed(1), 1));
+		assert_eq!(Balances::free_balance(3), 100);
+	});
+}
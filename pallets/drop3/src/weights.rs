@@ -19,6 +19,16 @@
 //! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 4.0.0-dev
 //! DATE: 2021-12-30, STEPS: `20`, REPEAT: 50, LOW RANGE: `[]`, HIGH RANGE: `[]`
 //! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("dev"), DB CACHE: 20
+//!
+//! TODO: `set_reward_claim` and `claim_reward` were NOT benchmarked, they're given the measured
+//! weight of `approve_reward_pool` and `send_reward` plus the reads and writes of the storage
+//! they access in addition, the proof size of that storage isn't included. `close_reward_pool`
+//! and `reject_reward_pool` were NOT re-benchmarked after keeping the closed pools with claims,
+//! their storage accesses were updated by hand. `set_merkle_root` and `claim_airdrop` were NOT
+//! benchmarked either, they're given the measured weight of `approve_reward_pool` and
+//! `send_reward` in the same way, and each proof element of `claim_airdrop` is charged 65 times
+//! the weight per byte of `frame_system::remark_with_event`, which hashes its input. Re-run the
+//! command below.
 
 // Executed Command:
 // ./target/release/litentry-collator
@@ -54,6 +64,10 @@ pub trait WeightInfo {
 	fn close_reward_pool() -> Weight;
 	fn propose_reward_pool(n: u32, ) -> Weight;
 	fn send_reward() -> Weight;
+	fn set_reward_claim() -> Weight;
+	fn claim_reward() -> Weight;
+	fn set_merkle_root() -> Weight;
+	fn claim_airdrop(n: u32, ) -> Weight;
 }
 
 /// Weights for pallet_drop3 using the Litentry node and recommended hardware.
@@ -76,10 +90,13 @@ impl<T: frame_system::Config> WeightInfo for LitentryWeight<T> {
 	// Storage: Drop3 RewardPools (r:1 w:1)
 	// Storage: System Account (r:1 w:1)
 	// Storage: Drop3 RewardPoolOwners (r:0 w:1)
+	// Storage: Drop3 RewardClaims (r:1 w:1)
+	// Storage: Drop3 ClosedRewardPools (r:0 w:1)
+	// Storage: Drop3 MerkleRoots (r:1 w:1)
 	fn reject_reward_pool() -> Weight {
 		Weight::from_ref_time(112_000_000 as u64)
-			.saturating_add(T::DbWeight::get().reads(3 as u64))
-			.saturating_add(T::DbWeight::get().writes(3 as u64))
+			.saturating_add(T::DbWeight::get().reads(5 as u64))
+			.saturating_add(T::DbWeight::get().writes(6 as u64))
 	}
 	// Storage: Drop3 RewardPools (r:1 w:1)
 	// Storage: Drop3 Admin (r:1 w:0)
@@ -99,10 +116,13 @@ impl<T: frame_system::Config> WeightInfo for LitentryWeight<T> {
 	// Storage: Drop3 Admin (r:1 w:0)
 	// Storage: System Account (r:1 w:1)
 	// Storage: Drop3 RewardPoolOwners (r:0 w:1)
+	// Storage: Drop3 RewardClaims (r:1 w:1)
+	// Storage: Drop3 ClosedRewardPools (r:0 w:1)
+	// Storage: Drop3 MerkleRoots (r:1 w:1)
 	fn close_reward_pool() -> Weight {
 		Weight::from_ref_time(68_000_000 as u64)
-			.saturating_add(T::DbWeight::get().reads(3 as u64))
-			.saturating_add(T::DbWeight::get().writes(3 as u64))
+			.saturating_add(T::DbWeight::get().reads(5 as u64))
+			.saturating_add(T::DbWeight::get().writes(6 as u64))
 	}
 	// Storage: System Account (r:1 w:1)
 	// Storage: Drop3 CurrentMaxPoolId (r:1 w:1)
@@ -122,6 +142,43 @@ impl<T: frame_system::Config> WeightInfo for LitentryWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(3 as u64))
 			.saturating_add(T::DbWeight::get().writes(3 as u64))
 	}
+	// Storage: Drop3 RewardPools (r:1 w:0)
+	// Storage: Drop3 RewardClaims (r:0 w:1)
+	fn set_reward_claim() -> Weight {
+		Weight::from_ref_time(38_000_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: Drop3 RewardClaims (r:1 w:0)
+	// Storage: VCManagement VCRegistry (r:1 w:0)
+	// Storage: VCManagement AssertionSchemas (r:1 w:0)
+	// Storage: VCManagement SchemaRegistry (r:1 w:0)
+	// Storage: Drop3 ClaimedVCs (r:1 w:1)
+	// Storage: Drop3 ClaimedAccounts (r:1 w:1)
+	// Storage: Drop3 RewardPools (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	fn claim_reward() -> Weight {
+		Weight::from_ref_time(84_000_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(9 as u64))
+			.saturating_add(T::DbWeight::get().writes(5 as u64))
+	}
+	// Storage: Drop3 RewardPools (r:1 w:0)
+	// Storage: Drop3 MerkleRoots (r:1 w:1)
+	fn set_merkle_root() -> Weight {
+		Weight::from_ref_time(38_000_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(2 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: Drop3 MerkleRoots (r:1 w:0)
+	// Storage: Drop3 ClaimedAirdropBitmap (r:1 w:1)
+	// Storage: Drop3 RewardPools (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	fn claim_airdrop(n: u32, ) -> Weight {
+		Weight::from_ref_time(84_000_000 as u64)
+			.saturating_add(Weight::from_ref_time(193_245 as u64).saturating_mul(n as u64))
+			.saturating_add(T::DbWeight::get().reads(5 as u64))
+			.saturating_add(T::DbWeight::get().writes(4 as u64))
+	}
 }
 
 // For backwards compatibility and tests
@@ -143,10 +200,13 @@ impl WeightInfo for () {
 	// Storage: Drop3 RewardPools (r:1 w:1)
 	// Storage: System Account (r:1 w:1)
 	// Storage: Drop3 RewardPoolOwners (r:0 w:1)
+	// Storage: Drop3 RewardClaims (r:1 w:1)
+	// Storage: Drop3 ClosedRewardPools (r:0 w:1)
+	// Storage: Drop3 MerkleRoots (r:1 w:1)
 	fn reject_reward_pool() -> Weight {
 		Weight::from_ref_time(112_000_000 as u64)
-			.saturating_add(RocksDbWeight::get().reads(3 as u64))
-			.saturating_add(RocksDbWeight::get().writes(3 as u64))
+			.saturating_add(RocksDbWeight::get().reads(5 as u64))
+			.saturating_add(RocksDbWeight::get().writes(6 as u64))
 	}
 	// Storage: Drop3 RewardPools (r:1 w:1)
 	// Storage: Drop3 Admin (r:1 w:0)
@@ -166,10 +226,13 @@ impl WeightInfo for () {
 	// Storage: Drop3 Admin (r:1 w:0)
 	// Storage: System Account (r:1 w:1)
 	// Storage: Drop3 RewardPoolOwners (r:0 w:1)
+	// Storage: Drop3 RewardClaims (r:1 w:1)
+	// Storage: Drop3 ClosedRewardPools (r:0 w:1)
+	// Storage: Drop3 MerkleRoots (r:1 w:1)
 	fn close_reward_pool() -> Weight {
 		Weight::from_ref_time(68_000_000 as u64)
-			.saturating_add(RocksDbWeight::get().reads(3 as u64))
-			.saturating_add(RocksDbWeight::get().writes(3 as u64))
+			.saturating_add(RocksDbWeight::get().reads(5 as u64))
+			.saturating_add(RocksDbWeight::get().writes(6 as u64))
 	}
 	// Storage: System Account (r:1 w:1)
 	// Storage: Drop3 CurrentMaxPoolId (r:1 w:1)
@@ -189,4 +252,41 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(3 as u64))
 			.saturating_add(RocksDbWeight::get().writes(3 as u64))
 	}
+	// Storage: Drop3 RewardPools (r:1 w:0)
+	// Storage: Drop3 RewardClaims (r:0 w:1)
+	fn set_reward_claim() -> Weight {
+		Weight::from_ref_time(38_000_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(1 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	// Storage: Drop3 RewardClaims (r:1 w:0)
+	// Storage: VCManagement VCRegistry (r:1 w:0)
+	// Storage: VCManagement AssertionSchemas (r:1 w:0)
+	// Storage: VCManagement SchemaRegistry (r:1 w:0)
+	// Storage: Drop3 ClaimedVCs (r:1 w:1)
+	// Storage: Drop3 ClaimedAccounts (r:1 w:1)
+	// Storage: Drop3 RewardPools (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	fn claim_reward() -> Weight {
+		Weight::from_ref_time(84_000_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(9 as u64))
+			.saturating_add(RocksDbWeight::get().writes(5 as u64))
+	}
+	// Storage: Drop3 RewardPools (r:1 w:0)
+	// Storage: Drop3 MerkleRoots (r:1 w:1)
+	fn set_merkle_root() -> Weight {
+		Weight::from_ref_time(38_000_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(2 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	// Storage: Drop3 MerkleRoots (r:1 w:0)
+	// Storage: Drop3 ClaimedAirdropBitmap (r:1 w:1)
+	// Storage: Drop3 RewardPools (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	fn claim_airdrop(n: u32, ) -> Weight {
+		Weight::from_ref_time(84_000_000 as u64)
+			.saturating_add(Weight::from_ref_time(193_245 as u64).saturating_mul(n as u64))
+			.saturating_add(RocksDbWeight::get().reads(5 as u64))
+			.saturating_add(RocksDbWeight::get().writes(4 as u64))
+	}
 }
@@ -57,6 +57,10 @@
 //! - whitelisting is not supported
 //!
 //! All dispatchables in this pallet must come from a priviledged origin.
+//!
+//! Other pallets can block extrinsics too via `BlockExtrinsics`, e.g. a circuit breaker
+//! that pauses the bridge upon anomalous volume. Unblocking still requires the priviledged
+//! origin.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
@@ -94,6 +98,17 @@ pub enum OperationalMode {
 	Test,
 }
 
+/// Block extrinsics without going through the priviledged origin, for other pallets
+pub trait BlockExtrinsics {
+	/// Block the given extrinsics, it's a no-op if they are already blocked
+	fn block(pallet_name_bytes: Vec<u8>, function_name_bytes: Option<Vec<u8>>);
+}
+
+/// Blocks nothing, for runtimes without extrinsic filter
+impl BlockExtrinsics for () {
+	fn block(_pallet_name_bytes: Vec<u8>, _function_name_bytes: Option<Vec<u8>>) {}
+}
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
@@ -252,6 +267,19 @@ pub mod pallet {
 		}
 	}
 
+	impl<T: Config> BlockExtrinsics for Pallet<T> {
+		fn block(pallet_name_bytes: Vec<u8>, function_name_bytes: Option<Vec<u8>>) {
+			let key = (pallet_name_bytes.clone(), function_name_bytes.clone().unwrap_or_default());
+			if !BlockedExtrinsics::<T>::contains_key(&key) {
+				BlockedExtrinsics::<T>::insert(key, ());
+				Self::deposit_event(Event::ExtrinsicsBlocked {
+					pallet_name_bytes,
+					function_name_bytes,
+				});
+			}
+		}
+	}
+
 	impl<T: Config> Contains<T::RuntimeCall> for Pallet<T>
 	where
 		<T as frame_system::Config>::RuntimeCall: GetCallMetadata,
@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
 
-use crate::{mock::*, Error};
+use crate::{mock::*, BlockExtrinsics, Error};
 use frame_support::{assert_noop, assert_ok};
 use sp_runtime::traits::Dispatchable;
 
@@ -498,3 +498,32 @@ fn blocked_extrinsic_is_retained_upon_mode_switch() {
 		assert_ok!(call.dispatch(RuntimeOrigin::none()));
 	});
 }
+
+#[test]
+fn block_from_other_pallet_works() {
+	new_test_ext().execute_with(|| {
+		let call: RuntimeCall = pallet_timestamp::Call::set { now: 100 }.into();
+
+		<ExtrinsicFilter as BlockExtrinsics>::block(b"Timestamp".to_vec(), Some(b"set".to_vec()));
+		System::assert_last_event(RuntimeEvent::ExtrinsicFilter(crate::Event::ExtrinsicsBlocked {
+			pallet_name_bytes: b"Timestamp".to_vec(),
+			function_name_bytes: Some(b"set".to_vec()),
+		}));
+		assert_noop!(
+			call.clone().dispatch(RuntimeOrigin::none()),
+			frame_system::Error::<Test>::CallFiltered
+		);
+
+		// blocking again is a no-op
+		System::reset_events();
+		<ExtrinsicFilter as BlockExtrinsics>::block(b"Timestamp".to_vec(), Some(b"set".to_vec()));
+		assert!(System::events().is_empty());
+
+		assert_ok!(ExtrinsicFilter::unblock_extrinsics(
+			RuntimeOrigin::root(),
+			b"Timestamp".to_vec(),
+			Some(b"set".to_vec()),
+		));
+		assert_ok!(call.dispatch(RuntimeOrigin::none()));
+	});
+}
@@ -107,6 +107,21 @@ benchmarks! {
 		assert_last_event::<T>(Event::SetUserShieldingKeyRequested{ shard }.into());
 	}
 
+	// Benchmark `remove_identity`. There are no worst conditions. The benchmark showed that
+	// execution time is constant irrespective of encrypted_data size.
+	remove_identity {
+		let caller: T::AccountId =  frame_benchmarking::account("TEST_A", 0u32, USER_SEED);
+		let shard = H256::from_slice(&TEST8_MRENCLAVE);
+		let encrypted_did = vec![1u8; 2048];
+		let encrypted_validation_data = vec![1u8; 2048];
+		let encrypted_web3networks = vec![1u8; 2048];
+		let nonce = UserShieldingKeyNonceType::default();
+		IdentityManagement::<T>::link_identity(RawOrigin::Signed(caller.clone()).into(), shard, caller.clone(), encrypted_did.clone(), encrypted_validation_data, encrypted_web3networks, nonce)?;
+	}: _(RawOrigin::Signed(caller), shard, encrypted_did)
+	verify {
+		assert_last_event::<T>(Event::RemoveIdentityRequested{ shard }.into());
+	}
+
 	// Benchmark `user_shielding_key_set`. There are no worst conditions. The benchmark showed that
 	// execution time is constant irrespective of encrypted_data size.
 	user_shielding_key_set {
@@ -156,6 +171,18 @@ benchmarks! {
 		assert_last_event::<T>(Event::IdentityActivated { account, identity, req_ext_hash }.into());
 	}
 
+	// Benchmark `identity_removed`. There are no worst conditions. The benchmark showed that
+	// execution time is constant irrespective of encrypted_data size.
+	identity_removed {
+		let req_ext_hash = H256::default();
+		let identity = AesOutput::default();
+		let call_origin = T::TEECallOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let account: T::AccountId =  frame_benchmarking::account("TEST_A", 0u32, USER_SEED);
+	}: _<T::RuntimeOrigin>(call_origin, account.clone(), identity.clone(), req_ext_hash)
+	verify {
+		assert_last_event::<T>(Event::IdentityRemoved { account, identity, req_ext_hash }.into());
+	}
+
 	// Benchmark `some_error`. There are no worst conditions. The benchmark showed that
 	// execution time is constant irrespective of encrypted_data size.
 	some_error {
@@ -89,6 +89,9 @@ pub mod pallet {
 		ActivateIdentityRequested {
 			shard: ShardIdentifier,
 		},
+		RemoveIdentityRequested {
+			shard: ShardIdentifier,
+		},
 		SetUserShieldingKeyRequested {
 			shard: ShardIdentifier,
 		},
@@ -119,6 +122,11 @@ pub mod pallet {
 			identity: AesOutput,
 			req_ext_hash: H256,
 		},
+		IdentityRemoved {
+			account: T::AccountId,
+			identity: AesOutput,
+			req_ext_hash: H256,
+		},
 		// event errors caused by processing in TEE
 		// copied from core_primitives::IMPError, we use events instead of pallet::errors,
 		// see https://github.com/litentry/litentry-parachain/issues/1275
@@ -145,6 +153,11 @@ pub mod pallet {
 			detail: ErrorDetail,
 			req_ext_hash: H256,
 		},
+		RemoveIdentityFailed {
+			account: Option<T::AccountId>,
+			detail: ErrorDetail,
+			req_ext_hash: H256,
+		},
 		ImportScheduledEnclaveFailed,
 		UnclassifiedError {
 			account: Option<T::AccountId>,
@@ -271,6 +284,19 @@ pub mod pallet {
 			Ok(().into())
 		}
 
+		/// Remove an identity from the IDGraph, so that it can be linked to another account
+		#[pallet::call_index(6)]
+		#[pallet::weight(<T as Config>::WeightInfo::remove_identity())]
+		pub fn remove_identity(
+			origin: OriginFor<T>,
+			shard: ShardIdentifier,
+			encrypted_identity: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			let _ = T::ExtrinsicWhitelistOrigin::ensure_origin(origin)?;
+			Self::deposit_event(Event::RemoveIdentityRequested { shard });
+			Ok(().into())
+		}
+
 		/// ---------------------------------------------------
 		/// The following extrinsics are supposed to be called by TEE only
 		/// ---------------------------------------------------
@@ -332,6 +358,19 @@ pub mod pallet {
 			Ok(Pays::No.into())
 		}
 
+		#[pallet::call_index(35)]
+		#[pallet::weight(<T as Config>::WeightInfo::identity_removed())]
+		pub fn identity_removed(
+			origin: OriginFor<T>,
+			account: T::AccountId,
+			identity: AesOutput,
+			req_ext_hash: H256,
+		) -> DispatchResultWithPostInfo {
+			let _ = T::TEECallOrigin::ensure_origin(origin)?;
+			Self::deposit_event(Event::IdentityRemoved { account, identity, req_ext_hash });
+			Ok(Pays::No.into())
+		}
+
 		#[pallet::call_index(34)]
 		#[pallet::weight(<T as Config>::WeightInfo::some_error())]
 		pub fn some_error(
@@ -362,6 +401,12 @@ pub mod pallet {
 						detail,
 						req_ext_hash,
 					}),
+				IMPError::RemoveIdentityFailed(detail) =>
+					Self::deposit_event(Event::RemoveIdentityFailed {
+						account,
+						detail,
+						req_ext_hash,
+					}),
 				IMPError::ImportScheduledEnclaveFailed =>
 					Self::deposit_event(Event::ImportScheduledEnclaveFailed),
 				IMPError::UnclassifiedError(detail) =>
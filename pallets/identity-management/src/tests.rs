@@ -138,6 +138,22 @@ fn activate_identity_works() {
 	});
 }
 
+#[test]
+fn remove_identity_works() {
+	new_test_ext().execute_with(|| {
+		let alice: SystemAccountId = test_utils::get_signer(ALICE_PUBKEY);
+		let shard: ShardIdentifier = H256::from_slice(&TEST8_MRENCLAVE);
+		assert_ok!(IdentityManagement::remove_identity(
+			RuntimeOrigin::signed(alice),
+			shard,
+			vec![1u8; 2048]
+		));
+		System::assert_last_event(RuntimeEvent::IdentityManagement(
+			crate::Event::RemoveIdentityRequested { shard },
+		));
+	});
+}
+
 #[test]
 #[cfg(feature = "skip-ias-check")]
 fn tee_callback_with_registered_enclave_works() {
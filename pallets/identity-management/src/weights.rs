@@ -52,11 +52,13 @@ pub trait WeightInfo {
 	fn link_identity() -> Weight;
 	fn deactivate_identity() -> Weight;
 	fn activate_identity() -> Weight;
+	fn remove_identity() -> Weight;
 	fn set_user_shielding_key() -> Weight;
 	fn user_shielding_key_set() -> Weight;
 	fn identity_linked() -> Weight;
 	fn identity_deactivated() -> Weight;
 	fn identity_activated() -> Weight;
+	fn identity_removed() -> Weight;
 	fn some_error() -> Weight;
 }
 
@@ -96,6 +98,12 @@ impl<T: frame_system::Config> WeightInfo for LitentryWeight<T> {
 	}
 	// Storage: IMPExtrinsicWhitelist GroupControlOn (r:1 w:0)
 	// Proof Skipped: IMPExtrinsicWhitelist GroupControlOn (max_values: Some(1), max_size: None, mode: Measured)
+	fn remove_identity() -> Weight {
+		Weight::from_ref_time(8_129_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+	}
+	// Storage: IMPExtrinsicWhitelist GroupControlOn (r:1 w:0)
+	// Proof Skipped: IMPExtrinsicWhitelist GroupControlOn (max_values: Some(1), max_size: None, mode: Measured)
 	fn set_user_shielding_key() -> Weight {
 		Weight::from_ref_time(8_250_000 as u64)
 			.saturating_add(T::DbWeight::get().reads(1 as u64))
@@ -126,6 +134,12 @@ impl<T: frame_system::Config> WeightInfo for LitentryWeight<T> {
 	}
 	// Storage: Teerex EnclaveIndex (r:1 w:0)
 	// Proof Skipped: Teerex EnclaveIndex (max_values: None, max_size: None, mode: Measured)
+	fn identity_removed() -> Weight {
+		Weight::from_ref_time(10_294_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+	}
+	// Storage: Teerex EnclaveIndex (r:1 w:0)
+	// Proof Skipped: Teerex EnclaveIndex (max_values: None, max_size: None, mode: Measured)
 	fn some_error() -> Weight {
 		Weight::from_ref_time(10_322_000 as u64)
 			.saturating_add(T::DbWeight::get().reads(1 as u64))
@@ -167,6 +181,12 @@ impl WeightInfo for () {
 	}
 	// Storage: IMPExtrinsicWhitelist GroupControlOn (r:1 w:0)
 	// Proof Skipped: IMPExtrinsicWhitelist GroupControlOn (max_values: Some(1), max_size: None, mode: Measured)
+	fn remove_identity() -> Weight {
+		Weight::from_ref_time(8_129_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(1 as u64))
+	}
+	// Storage: IMPExtrinsicWhitelist GroupControlOn (r:1 w:0)
+	// Proof Skipped: IMPExtrinsicWhitelist GroupControlOn (max_values: Some(1), max_size: None, mode: Measured)
 	fn set_user_shielding_key() -> Weight {
 		Weight::from_ref_time(8_250_000 as u64)
 			.saturating_add(RocksDbWeight::get().reads(1 as u64))
@@ -197,6 +217,12 @@ impl WeightInfo for () {
 	}
 	// Storage: Teerex EnclaveIndex (r:1 w:0)
 	// Proof Skipped: Teerex EnclaveIndex (max_values: None, max_size: None, mode: Measured)
+	fn identity_removed() -> Weight {
+		Weight::from_ref_time(10_294_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(1 as u64))
+	}
+	// Storage: Teerex EnclaveIndex (r:1 w:0)
+	// Proof Skipped: Teerex EnclaveIndex (max_values: None, max_size: None, mode: Measured)
 	fn some_error() -> Weight {
 		Weight::from_ref_time(10_322_000 as u64)
 			.saturating_add(RocksDbWeight::get().reads(1 as u64))
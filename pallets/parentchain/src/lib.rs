@@ -5,7 +5,10 @@ pub use pallet::*;
 #[frame_support::pallet]
 pub mod pallet {
 	use crate::weights::WeightInfo;
-	use frame_support::{pallet_prelude::*, sp_runtime::traits::Header};
+	use frame_support::{
+		pallet_prelude::*,
+		sp_runtime::traits::{Header, Zero},
+	};
 	use frame_system::pallet_prelude::*;
 
 	#[pallet::pallet]
@@ -16,6 +19,9 @@ pub mod pallet {
 	#[pallet::config]
 	pub trait Config: frame_system::Config {
 		type WeightInfo: WeightInfo;
+		/// The hash of every block whose number is a multiple of it is kept in `Checkpoints`.
+		#[pallet::constant]
+		type CheckpointInterval: Get<Self::BlockNumber>;
 	}
 
 	/// The current block number being processed. Set by `set_block`.
@@ -33,6 +39,27 @@ pub mod pallet {
 	#[pallet::getter(fn block_hash)]
 	pub(super) type BlockHash<T: Config> = StorageValue<_, T::Hash, ValueQuery>;
 
+	/// The last block, which storage proofs of the parentchain can be verified against, as
+	/// its header hashes to `BlockHash`. Set by `set_block`.
+	#[pallet::storage]
+	#[pallet::unbounded]
+	#[pallet::getter(fn last_header)]
+	pub(super) type LastHeader<T: Config> = StorageValue<_, T::Header, OptionQuery>;
+
+	/// Hashes of the blocks whose number is a multiple of `CheckpointInterval`, they're set
+	/// from the headers the light client imported and so can be used to verify the blocks a
+	/// parentchain node reports. Set by `set_block`.
+	#[pallet::storage]
+	#[pallet::getter(fn checkpoint)]
+	pub(super) type Checkpoints<T: Config> =
+		StorageMap<_, Twox64Concat, T::BlockNumber, T::Hash, OptionQuery>;
+
+	/// The number of the first block in `Checkpoints`, the blocks imported before the
+	/// checkpoints were introduced have none.
+	#[pallet::storage]
+	#[pallet::getter(fn first_checkpoint)]
+	pub(super) type FirstCheckpoint<T: Config> = StorageValue<_, T::BlockNumber, OptionQuery>;
+
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
 
@@ -45,6 +72,16 @@ pub mod pallet {
 			<Number<T>>::put(header.number());
 			<ParentHash<T>>::put(header.parent_hash());
 			<BlockHash<T>>::put(header.hash());
+
+			let number = *header.number();
+			let interval = T::CheckpointInterval::get();
+			if !interval.is_zero() && (number % interval).is_zero() {
+				<Checkpoints<T>>::insert(number, header.hash());
+				if <FirstCheckpoint<T>>::get().is_none() {
+					<FirstCheckpoint<T>>::put(number);
+				}
+			}
+			<LastHeader<T>>::put(header);
 			Ok(())
 		}
 	}
@@ -56,8 +56,13 @@ frame_support::construct_runtime!(
 	}
 );
 
+parameter_types! {
+	pub const CheckpointInterval: u32 = 10;
+}
+
 impl Config for Test {
 	type WeightInfo = ();
+	type CheckpointInterval = CheckpointInterval;
 }
 
 parameter_types! {
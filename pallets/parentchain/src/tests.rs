@@ -45,6 +45,7 @@ fn verify_storage_works() {
 		assert_eq!(Parentchain::block_number(), block_number);
 		assert_eq!(Parentchain::parent_hash(), parent_hash);
 		assert_eq!(Parentchain::block_hash(), hash);
+		assert_eq!(Parentchain::last_header().map(|h| h.hash()), Some(hash));
 	})
 }
 
@@ -63,3 +64,27 @@ fn non_root_account_errs() {
 		assert_err!(Parentchain::set_block(RuntimeOrigin::signed(root), header), BadOrigin);
 	})
 }
+
+#[test]
+fn checkpoints_are_kept_at_interval() {
+	let header = |number| -> Header {
+		HeaderT::new(
+			number,
+			Default::default(),
+			Default::default(),
+			H256::from_low_u64_be(number as u64),
+			Default::default(),
+		)
+	};
+
+	new_test_ext().execute_with(|| {
+		for number in 15..=30 {
+			assert_ok!(Parentchain::set_block(RuntimeOrigin::root(), header(number)));
+		}
+		assert_eq!(Parentchain::first_checkpoint(), Some(20));
+		assert_eq!(Parentchain::checkpoint(20), Some(header(20).hash()));
+		assert_eq!(Parentchain::checkpoint(30), Some(header(30).hash()));
+		assert_eq!(Parentchain::checkpoint(25), None);
+		assert_eq!(Parentchain::checkpoint(10), None);
+	})
+}
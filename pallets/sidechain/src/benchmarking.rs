@@ -23,6 +23,7 @@ use super::*;
 
 use crate::Pallet as Sidechain;
 use frame_benchmarking::{account, benchmarks};
+use frame_support::BoundedVec;
 use frame_system::RawOrigin;
 use test_utils::ias::setups::*;
 
@@ -47,15 +48,32 @@ fn add_enclaves_to_registry<T: Config>(accounts: &[T::AccountId]) {
 benchmarks! {
 	// Benchmark `confirm_imported_sidechain_block` with the worst possible conditions:
 	// * sender enclave is registered
+	// * all the other `MaxConfirmingEnclaves` confirmed the block already, so that the sender
+	//   finalizes it
 	confirm_imported_sidechain_block {
-		let accounts: Vec<T::AccountId> = generate_accounts::<T>(1);
+		let accounts: Vec<T::AccountId> =
+			generate_accounts::<T>(T::MaxConfirmingEnclaves::get().max(1));
 		add_enclaves_to_registry::<T>(&accounts);
 
 		let shard: ShardIdentifier = H256::from_slice(&TEST4_SETUP.mrenclave);
 		let hash: H256 = [2; 32].into();
+		let state_root: H256 = [3; 32].into();
 		let block_number = 1;
 		let next_finalization_candidate_block_number = 20;
-	}: _(RawOrigin::Signed(accounts[0].clone()), shard, block_number, next_finalization_candidate_block_number, hash)
+
+		let confirmation = SidechainBlockConfirmation { block_number, block_header_hash: hash, state_root };
+		let confirmations: Vec<_> = accounts
+			.iter()
+			.skip(1)
+			.map(|enclave| CandidateConfirmation {
+				enclave: enclave.clone(),
+				confirmation,
+				next_finalization_candidate_block_number,
+				confirmed_at: frame_system::Pallet::<T>::block_number(),
+			})
+			.collect();
+		<CandidateConfirmations<T>>::insert(shard, BoundedVec::try_from(confirmations).unwrap());
+	}: _(RawOrigin::Signed(accounts[0].clone()), shard, block_number, next_finalization_candidate_block_number, hash, state_root)
 	verify {
 		assert_latest_worker_update::<T>(&accounts[0], &shard)
 	}
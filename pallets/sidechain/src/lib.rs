@@ -17,12 +17,14 @@ limitations under the License.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use codec::Encode;
-use frame_support::dispatch::DispatchResultWithPostInfo;
+use codec::{Decode, Encode};
+use frame_support::{dispatch::DispatchResultWithPostInfo, RuntimeDebug};
 use frame_system::{self};
 use pallet_teerex::Pallet as Teerex;
+use scale_info::TypeInfo;
 use sidechain_primitives::SidechainBlockConfirmation;
 use sp_core::H256;
+use sp_runtime::{traits::Saturating, PerThing, Percent, SaturatedConversion};
 use sp_std::{prelude::*, str};
 use teerex_primitives::ShardIdentifier;
 
@@ -32,16 +34,30 @@ pub use crate::weights::WeightInfo;
 pub type AccountId<T> = <T as frame_system::Config>::AccountId;
 pub type ShardBlockNumber = (ShardIdentifier, u64);
 
+/// The confirmation of the finalization candidate of a shard by an enclave.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct CandidateConfirmation<AccountId, BlockNumber> {
+	pub enclave: AccountId,
+	pub confirmation: SidechainBlockConfirmation,
+	/// the next finalization candidate the enclave proposed
+	pub next_finalization_candidate_block_number: u64,
+	/// the parachain block it was confirmed at
+	pub confirmed_at: BlockNumber,
+}
+
 pub use pallet::*;
 
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
-	use frame_support::pallet_prelude::*;
+	use frame_support::{pallet_prelude::*, traits::StorageVersion};
 	use frame_system::pallet_prelude::*;
 
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T>(PhantomData<T>);
 
@@ -52,6 +68,19 @@ pub mod pallet {
 	pub trait Config: frame_system::Config + pallet_teerex::Config {
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 		type WeightInfo: WeightInfo;
+		/// The share of the registered enclaves with the MRENCLAVE of a shard which have to
+		/// confirm the same block before it's finalized. It's rounded up to the next number of
+		/// enclaves, e.g. 66% is 2 of 2 enclaves, 2 of 3 and 3 of 4, but at least one.
+		#[pallet::constant]
+		type FinalizationQuorum: Get<Percent>;
+		/// The max number of enclaves whose confirmations of the finalization candidate of a
+		/// shard are kept, it caps the required confirmations too.
+		#[pallet::constant]
+		type MaxConfirmingEnclaves: Get<u32>;
+		/// The number of parachain blocks after which a confirmation expires, so that the
+		/// confirmations of enclaves which went silent or were unregistered don't count forever.
+		#[pallet::constant]
+		type ConfirmationTimeout: Get<Self::BlockNumber>;
 	}
 
 	#[pallet::event]
@@ -59,6 +88,13 @@ pub mod pallet {
 	pub enum Event<T: Config> {
 		ProposedSidechainBlock(T::AccountId, H256),
 		FinalizedSidechainBlock(T::AccountId, H256),
+		/// An enclave confirmed the finalization candidate, but it's not finalized yet.
+		/// [enclave, block header hash, confirmations, required confirmations]
+		ConfirmedSidechainBlock(T::AccountId, H256, u32, u32),
+		/// An enclave confirmed two different blocks, or states, as the finalization candidate.
+		/// Only its last confirmation is counted.
+		/// [enclave, shard, block number]
+		EquivocatedSidechainBlockConfirmation(T::AccountId, ShardIdentifier, u64),
 	}
 
 	// Enclave index of the worker that recently committed an update.
@@ -77,9 +113,24 @@ pub mod pallet {
 	pub type SidechainBlockFinalizationCandidate<T: Config> =
 		StorageMap<_, Blake2_128Concat, ShardIdentifier, u64, ValueQuery>;
 
+	// The unexpired confirmations of the finalization candidate of a shard, at most one of each
+	// enclave. They are removed when the block is finalized.
+	#[pallet::storage]
+	#[pallet::getter(fn candidate_confirmations)]
+	pub type CandidateConfirmations<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		ShardIdentifier,
+		BoundedVec<CandidateConfirmation<T::AccountId, T::BlockNumber>, T::MaxConfirmingEnclaves>,
+		ValueQuery,
+	>;
+
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		/// The integritee worker calls this function for every imported sidechain_block.
+		///
+		/// The block is finalized once `FinalizationQuorum` of the registered enclaves with the
+		/// same MRENCLAVE confirmed the same block header hash and state root.
 		#[pallet::call_index(0)]
 		#[pallet::weight((<T as Config>::WeightInfo::confirm_imported_sidechain_block(), DispatchClass::Normal, Pays::Yes))]
 		pub fn confirm_imported_sidechain_block(
@@ -88,8 +139,10 @@ pub mod pallet {
 			block_number: u64,
 			next_finalization_candidate_block_number: u64,
 			block_header_hash: H256,
+			state_root: H256,
 		) -> DispatchResultWithPostInfo {
-			let confirmation = SidechainBlockConfirmation { block_number, block_header_hash };
+			let confirmation =
+				SidechainBlockConfirmation { block_number, block_header_hash, state_root };
 
 			let sender = ensure_signed(origin)?;
 			Teerex::<T>::ensure_registered_enclave(&sender)?;
@@ -101,16 +154,11 @@ pub mod pallet {
 				pallet_teerex::Error::<T>::WrongMrenclaveForShard
 			);
 
-			// Simple logic for now: only accept blocks from first registered enclave.
-			if sender_index != 1 {
-				log::debug!(
-					"Ignore block confirmation from registered enclave with index {:?}",
-					sender_index
-				);
+			// the block was finalized with the confirmations of the other enclaves already
+			if <LatestSidechainBlockConfirmation<T>>::get(shard_id) == confirmation {
 				return Ok(().into())
 			}
 
-			let block_number = confirmation.block_number;
 			let finalization_candidate_block_number =
 				<SidechainBlockFinalizationCandidate<T>>::try_get(shard_id).unwrap_or(1);
 
@@ -123,10 +171,58 @@ pub mod pallet {
 				<Error<T>>::InvalidNextFinalizationCandidateBlockNumber
 			);
 
-			<SidechainBlockFinalizationCandidate<T>>::insert(
-				shard_id,
+			let now = <frame_system::Pallet<T>>::block_number();
+			let mut confirmations = <CandidateConfirmations<T>>::get(shard_id);
+			confirmations
+				.retain(|c| now.saturating_sub(c.confirmed_at) < T::ConfirmationTimeout::get());
+			let candidate_confirmation = CandidateConfirmation {
+				enclave: sender.clone(),
+				confirmation,
 				next_finalization_candidate_block_number,
-			);
+				confirmed_at: now,
+			};
+			match confirmations.iter_mut().find(|c| c.enclave == sender) {
+				Some(c) if c.confirmation == confirmation => return Ok(().into()),
+				Some(c) => {
+					log::warn!(
+						"Enclave {:?} confirmed conflicting blocks {:?} of shard {:?}",
+						sender_index,
+						block_number,
+						shard_id
+					);
+					Self::deposit_event(Event::EquivocatedSidechainBlockConfirmation(
+						sender.clone(),
+						shard_id,
+						block_number,
+					));
+					*c = candidate_confirmation;
+				},
+				None => confirmations
+					.try_push(candidate_confirmation)
+					.map_err(|_| <Error<T>>::TooManyConfirmations)?,
+			}
+
+			// the next candidate is the nearest one proposed, so that no block is skipped
+			let (count, next_candidate) = confirmations
+				.iter()
+				.filter(|c| c.confirmation == confirmation)
+				.fold((0u32, u64::MAX), |(count, next), c| {
+					(count.saturating_add(1), next.min(c.next_finalization_candidate_block_number))
+				});
+			let required = Self::required_confirmations(&shard_id);
+			if count < required {
+				<CandidateConfirmations<T>>::insert(shard_id, confirmations);
+				Self::deposit_event(Event::ConfirmedSidechainBlock(
+					sender,
+					block_header_hash,
+					count,
+					required,
+				));
+				return Ok(().into())
+			}
+
+			<SidechainBlockFinalizationCandidate<T>>::insert(shard_id, next_candidate);
+			<CandidateConfirmations<T>>::remove(shard_id);
 
 			Self::finalize_block(shard_id, confirmation, &sender, sender_index);
 			Ok(().into())
@@ -139,10 +235,22 @@ pub mod pallet {
 		ReceivedUnexpectedSidechainBlock,
 		/// The value for the next finalization candidate is invalid.
 		InvalidNextFinalizationCandidateBlockNumber,
+		/// `MaxConfirmingEnclaves` confirmed the finalization candidate already.
+		TooManyConfirmations,
 	}
 }
 
 impl<T: Config> Pallet<T> {
+	/// The number of confirmations needed to finalize a block of `shard_id`
+	pub fn required_confirmations(shard_id: &ShardIdentifier) -> u32 {
+		let enclaves: u32 =
+			Teerex::<T>::mrenclave_enclave_count(shard_id.to_fixed_bytes()).saturated_into();
+		T::FinalizationQuorum::get()
+			.mul_ceil(enclaves)
+			.min(T::MaxConfirmingEnclaves::get())
+			.max(1)
+	}
+
 	fn finalize_block(
 		shard_id: ShardIdentifier,
 		confirmation: SidechainBlockConfirmation,
@@ -162,6 +270,7 @@ impl<T: Config> Pallet<T> {
 }
 
 mod benchmarking;
+pub mod migrations;
 #[cfg(test)]
 mod mock;
 #[cfg(all(test, not(feature = "skip-ias-check")))]
@@ -0,0 +1,89 @@
+/*
+Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+	http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+*/
+
+//! Storage migrations of pallet-sidechain.
+
+use crate::{Config, LatestSidechainBlockConfirmation, Pallet};
+use codec::{Decode, Encode};
+use frame_support::{
+	traits::{Get, GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+	weights::Weight,
+};
+use sidechain_primitives::SidechainBlockConfirmation;
+use sp_core::H256;
+use sp_std::marker::PhantomData;
+#[cfg(feature = "try-runtime")]
+use sp_std::vec::Vec;
+
+mod v0 {
+	use super::*;
+
+	// `SidechainBlockConfirmation` before the state root was introduced
+	#[derive(Encode, Decode)]
+	pub struct SidechainBlockConfirmation {
+		pub block_number: u64,
+		pub block_header_hash: H256,
+	}
+}
+
+/// Adds `state_root` to the entries of `LatestSidechainBlockConfirmation`.
+///
+/// The state roots of the blocks finalized so far are unknown, they are migrated with a zero
+/// state root.
+pub struct MigrateToV1<T>(PhantomData<T>);
+
+impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+	fn on_runtime_upgrade() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() != 0 {
+			return T::DbWeight::get().reads(1)
+		}
+
+		let mut count: u64 = 0;
+		LatestSidechainBlockConfirmation::<T>::translate_values::<v0::SidechainBlockConfirmation, _>(
+			|old| {
+				count += 1;
+				Some(SidechainBlockConfirmation {
+					block_number: old.block_number,
+					block_header_hash: old.block_header_hash,
+					state_root: H256::zero(),
+				})
+			},
+		);
+		StorageVersion::new(1).put::<Pallet<T>>();
+
+		T::DbWeight::get().reads_writes(count + 1, count + 1)
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+		Ok((LatestSidechainBlockConfirmation::<T>::iter_keys().count() as u64).encode())
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+		let count = u64::decode(&mut &state[..]).map_err(|_| "invalid pre-upgrade state")?;
+		frame_support::ensure!(
+			Pallet::<T>::on_chain_storage_version() == 1,
+			"storage version not updated"
+		);
+		frame_support::ensure!(
+			LatestSidechainBlockConfirmation::<T>::iter_values().count() as u64 == count,
+			"LatestSidechainBlockConfirmation entries lost"
+		);
+		Ok(())
+	}
+}
@@ -26,6 +26,7 @@ use sp_keyring::AccountKeyring;
 use sp_runtime::{
 	generic,
 	traits::{BlakeTwo256, IdentifyAccount, IdentityLookup, Verify},
+	Percent,
 };
 
 pub type Signature = sp_runtime::MultiSignature;
@@ -140,9 +141,18 @@ parameter_types! {
 	pub const MaxSilenceTime: u64 = 172_800_000; // 48h
 }
 
+parameter_types! {
+	pub const FinalizationQuorum: Percent = Percent::from_percent(66);
+	pub const MaxConfirmingEnclaves: u32 = 2;
+	pub const ConfirmationTimeout: BlockNumber = 10;
+}
+
 impl Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type WeightInfo = ();
+	type FinalizationQuorum = FinalizationQuorum;
+	type MaxConfirmingEnclaves = MaxConfirmingEnclaves;
+	type ConfirmationTimeout = ConfirmationTimeout;
 }
 
 // This function basically just builds a genesis storage key/value store according to
@@ -15,8 +15,19 @@ limitations under the License.
 
 */
 
-use crate::{mock::*, Error, Event as SidechainEvent, Teerex};
-use frame_support::{assert_err, assert_ok, dispatch::DispatchResultWithPostInfo};
+use crate::{
+	migrations::MigrateToV1, mock::*, Error, Event as SidechainEvent,
+	LatestSidechainBlockConfirmation, Teerex,
+};
+use codec::Encode;
+use frame_support::{
+	assert_err, assert_ok,
+	dispatch::DispatchResultWithPostInfo,
+	storage::unhashed,
+	traits::{Get, GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+};
+use pallet_teerex::MrenclaveEnclaveCount;
+use sidechain_primitives::SidechainBlockConfirmation;
 use sp_core::H256;
 use teerex_primitives::MrSigner;
 use test_utils::ias::consts::*;
@@ -44,7 +55,8 @@ fn confirm_imported_sidechain_block_invalid_next_finalization_candidate() {
 				shard7,
 				block_number,
 				block_number,
-				hash
+				hash,
+				H256::default()
 			),
 			Error::<Test>::InvalidNextFinalizationCandidateBlockNumber,
 		);
@@ -69,7 +81,8 @@ fn confirm_imported_sidechain_block_works_for_correct_shard() {
 			shard7,
 			block_number,
 			next_finalization_block_candidate,
-			hash
+			hash,
+			H256::default()
 		));
 
 		let expected_event =
@@ -96,7 +109,8 @@ fn confirm_imported_sidechain_block_from_shard_neq_mrenclave_errs() {
 				shard4,
 				block_number,
 				block_number,
-				hash
+				hash,
+				H256::default()
 			),
 			pallet_teerex::Error::<Test>::WrongMrenclaveForShard
 		);
@@ -182,16 +196,178 @@ fn confirm_imported_sidechain_block_outdated() {
 }
 
 #[test]
-fn dont_process_confirmation_of_second_registered_enclave() {
+fn finalize_sidechain_block_with_quorum_of_enclaves() {
 	new_test_ext().execute_with(|| {
 		Timestamp::set_timestamp(TEST7_TIMESTAMP);
 		let shard7 = H256::from_slice(&TEST7_MRENCLAVE);
+		let hash = H256::random();
+		let state_root = H256::random();
 
+		// TEST5, TEST6 and TEST7 have the same MRENCLAVE
 		register_enclave(TEST7_SIGNER_PUB, TEST7_CERT, 1);
 		register_enclave(TEST6_SIGNER_PUB, TEST6_CERT, 2);
+		register_enclave(TEST5_SIGNER_PUB, TEST5_CERT, 3);
+		assert_eq!(Sidechain::required_confirmations(&shard7), 2);
 
-		assert_ok!(confirm_block(shard7, TEST6_SIGNER_PUB, 1, 2, H256::default(), false));
+		assert_ok!(confirm_block(shard7, TEST7_SIGNER_PUB, 1, 3, hash, state_root, false));
 		assert_eq!(Sidechain::latest_sidechain_block_confirmation(shard7).block_number, 0);
+		let expected_event = RuntimeEvent::Sidechain(SidechainEvent::ConfirmedSidechainBlock(
+			get_signer(TEST7_SIGNER_PUB),
+			hash,
+			1,
+			2,
+		));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+
+		// the enclave with index 2 finalizes the block, with the nearest next candidate
+		assert_ok!(confirm_block(shard7, TEST6_SIGNER_PUB, 1, 2, hash, state_root, true));
+		assert_eq!(Sidechain::latest_sidechain_block_confirmation(shard7).block_number, 1);
+		assert_eq!(Sidechain::latest_sidechain_block_confirmation(shard7).state_root, state_root);
+		assert_eq!(Sidechain::sidechain_block_finalization_candidate(shard7), 2);
+		assert!(Sidechain::candidate_confirmations(shard7).is_empty());
+
+		// a late confirmation of the finalized block is ignored
+		assert_ok!(confirm_block(shard7, TEST5_SIGNER_PUB, 1, 3, hash, state_root, false));
+		assert!(Sidechain::candidate_confirmations(shard7).is_empty());
+	})
+}
+
+#[test]
+fn required_confirmations_is_rounded_up() {
+	new_test_ext().execute_with(|| {
+		let shard7 = H256::from_slice(&TEST7_MRENCLAVE);
+		let required = |enclaves: u64| {
+			MrenclaveEnclaveCount::<Test>::insert(TEST7_MRENCLAVE, enclaves);
+			Sidechain::required_confirmations(&shard7)
+		};
+
+		// 66% of the enclaves, at least one
+		assert_eq!(required(0), 1);
+		assert_eq!(required(1), 1);
+		assert_eq!(required(2), 2);
+		assert_eq!(required(3), 2);
+		// but at most `MaxConfirmingEnclaves`, 3 of 4 otherwise
+		assert_eq!(required(4), 2);
+		assert_eq!(required(100), 2);
+	})
+}
+
+#[test]
+fn dont_finalize_sidechain_block_with_different_state_roots() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(TEST7_TIMESTAMP);
+		let shard7 = H256::from_slice(&TEST7_MRENCLAVE);
+		let hash = H256::random();
+
+		register_enclave(TEST7_SIGNER_PUB, TEST7_CERT, 1);
+		register_enclave(TEST6_SIGNER_PUB, TEST6_CERT, 2);
+		register_enclave(TEST5_SIGNER_PUB, TEST5_CERT, 3);
+
+		assert_ok!(confirm_block(shard7, TEST7_SIGNER_PUB, 1, 2, hash, H256::random(), false));
+		assert_ok!(confirm_block(shard7, TEST6_SIGNER_PUB, 1, 2, hash, H256::random(), false));
+		assert_eq!(Sidechain::latest_sidechain_block_confirmation(shard7).block_number, 0);
+		assert_eq!(Sidechain::sidechain_block_finalization_candidate(shard7), 0);
+		assert_eq!(Sidechain::candidate_confirmations(shard7).len(), 2);
+	})
+}
+
+#[test]
+fn conflicting_sidechain_block_confirmation_is_reported() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(TEST7_TIMESTAMP);
+		let shard7 = H256::from_slice(&TEST7_MRENCLAVE);
+		let hash = H256::random();
+		let state_root = H256::random();
+		let other_state_root = H256::random();
+
+		register_enclave(TEST7_SIGNER_PUB, TEST7_CERT, 1);
+		register_enclave(TEST6_SIGNER_PUB, TEST6_CERT, 2);
+		register_enclave(TEST5_SIGNER_PUB, TEST5_CERT, 3);
+
+		assert_ok!(confirm_block(shard7, TEST6_SIGNER_PUB, 1, 2, hash, state_root, false));
+		// the same confirmation again is fine
+		assert_ok!(confirm_block(shard7, TEST6_SIGNER_PUB, 1, 2, hash, state_root, false));
+		let expected_event =
+			RuntimeEvent::Sidechain(SidechainEvent::EquivocatedSidechainBlockConfirmation(
+				get_signer(TEST6_SIGNER_PUB),
+				shard7,
+				1,
+			));
+		assert!(!System::events().iter().any(|a| a.event == expected_event));
+
+		assert_ok!(confirm_block(shard7, TEST6_SIGNER_PUB, 1, 2, hash, other_state_root, false));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+
+		// only the last confirmation counts
+		assert_ok!(confirm_block(shard7, TEST7_SIGNER_PUB, 1, 2, hash, state_root, false));
+		assert_eq!(Sidechain::latest_sidechain_block_confirmation(shard7).block_number, 0);
+		assert_ok!(confirm_block(shard7, TEST7_SIGNER_PUB, 1, 2, hash, other_state_root, true));
+		assert_eq!(Sidechain::latest_sidechain_block_confirmation(shard7).block_number, 1);
+		assert_eq!(
+			Sidechain::latest_sidechain_block_confirmation(shard7).state_root,
+			other_state_root
+		);
+	})
+}
+
+#[test]
+fn sidechain_block_confirmations_expire() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(TEST7_TIMESTAMP);
+		let shard7 = H256::from_slice(&TEST7_MRENCLAVE);
+		let hash = H256::random();
+		let state_root = H256::random();
+
+		register_enclave(TEST7_SIGNER_PUB, TEST7_CERT, 1);
+		register_enclave(TEST6_SIGNER_PUB, TEST6_CERT, 2);
+		register_enclave(TEST5_SIGNER_PUB, TEST5_CERT, 3);
+
+		assert_ok!(confirm_block(shard7, TEST6_SIGNER_PUB, 1, 2, hash, state_root, false));
+		assert_ok!(confirm_block(shard7, TEST7_SIGNER_PUB, 1, 2, hash, H256::random(), false));
+		// `MaxConfirmingEnclaves` is 2
+		assert_err!(
+			confirm_block(shard7, TEST5_SIGNER_PUB, 1, 2, hash, state_root, false),
+			Error::<Test>::TooManyConfirmations
+		);
+
+		System::set_block_number(1 + ConfirmationTimeout::get());
+		assert_ok!(confirm_block(shard7, TEST5_SIGNER_PUB, 1, 2, hash, state_root, false));
+		// the confirmation of TEST6 expired
+		assert_eq!(Sidechain::candidate_confirmations(shard7).len(), 1);
+		assert_eq!(Sidechain::latest_sidechain_block_confirmation(shard7).block_number, 0);
+
+		assert_ok!(confirm_block(shard7, TEST6_SIGNER_PUB, 1, 2, hash, state_root, true));
+		assert_eq!(Sidechain::latest_sidechain_block_confirmation(shard7).block_number, 1);
+	})
+}
+
+#[test]
+fn migrate_to_v1_adds_state_root() {
+	new_test_ext().execute_with(|| {
+		let shard7 = H256::from_slice(&TEST7_MRENCLAVE);
+		let hash = H256::random();
+
+		// the encoding of the v0 `SidechainBlockConfirmation`
+		unhashed::put_raw(
+			&LatestSidechainBlockConfirmation::<Test>::hashed_key_for(shard7),
+			&(5u64, hash).encode(),
+		);
+		StorageVersion::new(0).put::<Sidechain>();
+
+		MigrateToV1::<Test>::on_runtime_upgrade();
+		assert_eq!(Sidechain::on_chain_storage_version(), 1);
+		assert_eq!(
+			Sidechain::latest_sidechain_block_confirmation(shard7),
+			SidechainBlockConfirmation {
+				block_number: 5,
+				block_header_hash: hash,
+				state_root: H256::zero()
+			}
+		);
+
+		// it's not applied twice
+		MigrateToV1::<Test>::on_runtime_upgrade();
+		assert_eq!(Sidechain::latest_sidechain_block_confirmation(shard7).block_number, 5);
 	})
 }
 
@@ -226,6 +402,7 @@ fn confirm_block7(
 		block_number,
 		next_finalized_block_number,
 		block_header_hash,
+		H256::default(),
 		check_for_event,
 	)
 }
@@ -236,6 +413,7 @@ fn confirm_block(
 	block_number: u64,
 	next_finalized_block_number: u64,
 	block_header_hash: H256,
+	state_root: H256,
 	check_for_event: bool,
 ) -> DispatchResultWithPostInfo {
 	let signer7 = get_signer(signer_pub_key);
@@ -246,6 +424,7 @@ fn confirm_block(
 		block_number,
 		next_finalized_block_number,
 		block_header_hash,
+		state_root,
 	)?;
 
 	if check_for_event {
@@ -36,6 +36,9 @@
 //! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 4.0.0-dev
 //! DATE: 2022-12-27, STEPS: `20`, REPEAT: 50, LOW RANGE: `[]`, HIGH RANGE: `[]`
 //! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("rococo-dev"), DB CACHE: 20
+//!
+//! TODO: `confirm_imported_sidechain_block` was NOT re-benchmarked after the finalization by a
+//! quorum of enclaves, its storage accesses were updated by hand. Re-run the command below.
 
 // Executed Command:
 // ./target/release/litentry-collator
@@ -75,13 +78,15 @@ pub struct LitentryWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for LitentryWeight<T> {
 	// Storage: Teerex EnclaveIndex (r:1 w:0)
 	// Storage: Teerex EnclaveRegistry (r:1 w:0)
+	// Storage: Sidechain LatestSidechainBlockConfirmation (r:1 w:1)
 	// Storage: Sidechain SidechainBlockFinalizationCandidate (r:1 w:1)
+	// Storage: Sidechain CandidateConfirmations (r:1 w:1)
+	// Storage: Teerex MrenclaveEnclaveCount (r:1 w:0)
 	// Storage: Sidechain WorkerForShard (r:0 w:1)
-	// Storage: Sidechain LatestSidechainBlockConfirmation (r:0 w:1)
 	fn confirm_imported_sidechain_block() -> Weight {
 		Weight::from_ref_time(34_686_000 as u64)
-			.saturating_add(T::DbWeight::get().reads(3 as u64))
-			.saturating_add(T::DbWeight::get().writes(3 as u64))
+			.saturating_add(T::DbWeight::get().reads(6 as u64))
+			.saturating_add(T::DbWeight::get().writes(4 as u64))
 	}
 }
 
@@ -89,13 +94,15 @@ impl<T: frame_system::Config> WeightInfo for LitentryWeight<T> {
 impl WeightInfo for () {
 	// Storage: Teerex EnclaveIndex (r:1 w:0)
 	// Storage: Teerex EnclaveRegistry (r:1 w:0)
+	// Storage: Sidechain LatestSidechainBlockConfirmation (r:1 w:1)
 	// Storage: Sidechain SidechainBlockFinalizationCandidate (r:1 w:1)
+	// Storage: Sidechain CandidateConfirmations (r:1 w:1)
+	// Storage: Teerex MrenclaveEnclaveCount (r:1 w:0)
 	// Storage: Sidechain WorkerForShard (r:0 w:1)
-	// Storage: Sidechain LatestSidechainBlockConfirmation (r:0 w:1)
 	fn confirm_imported_sidechain_block() -> Weight {
 		Weight::from_ref_time(34_686_000 as u64)
-			.saturating_add(RocksDbWeight::get().reads(3 as u64))
-			.saturating_add(RocksDbWeight::get().writes(3 as u64))
+			.saturating_add(RocksDbWeight::get().reads(6 as u64))
+			.saturating_add(RocksDbWeight::get().writes(4 as u64))
 	}
 }
 
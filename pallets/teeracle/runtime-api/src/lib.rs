@@ -0,0 +1,28 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+//! Runtime API of the teeracle pallet, for consumers of the exchange rates.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use teeracle_primitives::{AggregatedExchangeRate, TradingPairString};
+
+sp_api::decl_runtime_apis! {
+	pub trait TeeracleApi {
+		/// The exchange rate of `trading_pair` aggregated over its data sources, `None` if it
+		/// was never reported.
+		fn aggregated_exchange_rate(trading_pair: TradingPairString) -> Option<AggregatedExchangeRate>;
+	}
+}
@@ -78,9 +78,31 @@ benchmarks! {
 		let mrenclave = Teerex::<T>::enclave(1).unwrap().mr_enclave;
 		Teeracle::<T>::add_to_whitelist(RawOrigin::Root.into(), data_source.clone(), mrenclave).unwrap();
 
-	}: _(RawOrigin::Signed(signer), data_source.clone(), trading_pair.clone(), Some(rate))
+		// worst case: the reports of the other enclaves are aggregated too, and the oldest
+		// observation is dropped to make room for the new one
+		let reports: Vec<_> = (1..T::MaxExchangeRateReports::get())
+			.map(|i| {
+				let mut enclave = [0u8; 32];
+				enclave[..4].copy_from_slice(&i.to_le_bytes());
+				let report = ExchangeRateReport {
+					rate,
+					timestamp: TEST4_SETUP.timestamp,
+					enclave: enclave.into(),
+				};
+				(data_source.clone(), report)
+			})
+			.collect();
+		ExchangeRateReports::<T>::insert(&trading_pair, BoundedVec::truncate_from(reports));
+		let observations: Vec<_> = (1..=T::MaxExchangeRateObservations::get() as u64)
+			.rev()
+			.map(|i| (TEST4_SETUP.timestamp - i, rate))
+			.collect();
+		ExchangeRateObservations::<T>::insert(&trading_pair, BoundedVec::truncate_from(observations));
+
+	}: _(RawOrigin::Signed(signer), data_source.clone(), trading_pair.clone(), Some(rate), TEST4_SETUP.timestamp)
 	verify {
-		assert_eq!(Teeracle::<T>::exchange_rate(trading_pair, data_source), U32F32::from_num(43.65));
+		assert_eq!(Teeracle::<T>::exchange_rate(trading_pair.clone(), data_source), U32F32::from_num(43.65));
+		assert_eq!(Teeracle::<T>::aggregated_exchange_rate(&trading_pair).unwrap().median, U32F32::from_num(43.65));
 	}
 
 	update_oracle {
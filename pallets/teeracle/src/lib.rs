@@ -28,11 +28,18 @@
 //! The teeracle pallet provides functions for:
 //!
 //! - Setting exchange rates.
+//! - Aggregating the exchange rates of a trading pair over its data sources.
 #![cfg_attr(not(feature = "std"), no_std)]
 pub use crate::weights::WeightInfo;
+use frame_support::{traits::Get, BoundedVec};
 pub use pallet::*;
+use sp_runtime::SaturatedConversion;
+use sp_std::{collections::btree_map::BTreeMap, prelude::*};
 pub use substrate_fixed::types::U32F32;
-use teeracle_primitives::{DataSource, MAX_ORACLE_DATA_NAME_LEN};
+use teeracle_primitives::{
+	AggregatedExchangeRate, DataSource, ExchangeRate, ExchangeRateReport, TradingPairString,
+	MAX_ORACLE_DATA_NAME_LEN,
+};
 
 const MAX_TRADING_PAIR_LEN: usize = 11;
 const MAX_SOURCE_LEN: usize = 40;
@@ -40,7 +47,7 @@ const MAX_SOURCE_LEN: usize = 40;
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
-	use frame_support::{pallet_prelude::*, BoundedVec, WeakBoundedVec};
+	use frame_support::{pallet_prelude::*, WeakBoundedVec};
 	use frame_system::pallet_prelude::*;
 	use sp_std::prelude::*;
 	use teeracle_primitives::*;
@@ -63,6 +70,26 @@ pub mod pallet {
 
 		#[pallet::constant]
 		type MaxOracleBlobLen: Get<u32>;
+
+		/// The max age of an exchange rate report in milliseconds, older reports are ignored
+		/// and an aggregated exchange rate without newer reports is stale.
+		#[pallet::constant]
+		type MaxExchangeRateAge: Get<u64>;
+
+		/// The period in milliseconds over which the time weighted average exchange rate is
+		/// taken.
+		#[pallet::constant]
+		type ExchangeRateTwapPeriod: Get<u64>;
+
+		/// Max number of reports, one per data source and enclave, kept for a trading pair.
+		#[pallet::constant]
+		type MaxExchangeRateReports: Get<u32>;
+
+		/// Max number of aggregated medians kept for the time weighted average of a trading
+		/// pair, when it's reached the oldest one is dropped and the average is taken over a
+		/// shorter period.
+		#[pallet::constant]
+		type MaxExchangeRateObservations: Get<u32>;
 	}
 
 	/// Exchange rates chain's cryptocurrency/currency (trading pair) from different sources
@@ -78,6 +105,34 @@ pub mod pallet {
 		ValueQuery,
 	>;
 
+	/// The latest report of each data source and enclave, per trading pair
+	#[pallet::storage]
+	#[pallet::getter(fn exchange_rate_reports)]
+	pub(super) type ExchangeRateReports<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		TradingPairString,
+		BoundedVec<(DataSource, ExchangeRateReport<T::AccountId>), T::MaxExchangeRateReports>,
+		ValueQuery,
+	>;
+
+	/// The aggregated medians of a trading pair with the timestamp from which they were in
+	/// effect, oldest first, over the last `ExchangeRateTwapPeriod`
+	#[pallet::storage]
+	pub(super) type ExchangeRateObservations<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		TradingPairString,
+		BoundedVec<(u64, ExchangeRate), T::MaxExchangeRateObservations>,
+		ValueQuery,
+	>;
+
+	/// The exchange rates aggregated over the reports, read it with
+	/// `Pallet::aggregated_exchange_rate` to know whether it's stale
+	#[pallet::storage]
+	pub(super) type AggregatedExchangeRates<T: Config> =
+		StorageMap<_, Blake2_128Concat, TradingPairString, AggregatedExchangeRate, OptionQuery>;
+
 	#[pallet::storage]
 	#[pallet::getter(fn oracle_data)]
 	pub(super) type OracleData<T> = StorageDoubleMap<
@@ -114,6 +169,9 @@ pub mod pallet {
 		OracleUpdated(OracleDataName, DataSource),
 		AddedToWhitelist(DataSource, [u8; 32]),
 		RemovedFromWhitelist(DataSource, [u8; 32]),
+		/// The exchange rate of trading pair was aggregated over the reports.
+		/// \[trading_pair], [aggregated value\]
+		ExchangeRateAggregated(TradingPairString, AggregatedExchangeRate),
 	}
 
 	#[pallet::error]
@@ -127,6 +185,10 @@ pub mod pallet {
 		OracleDataNameStringTooLong,
 		DataSourceStringTooLong,
 		OracleBlobTooBig,
+		/// The exchange rate is older than `MaxExchangeRateAge`.
+		StaleExchangeRate,
+		/// The trading pair already has `MaxExchangeRateReports` reports which aren't stale.
+		TooManyExchangeRateReports,
 	}
 
 	#[pallet::hooks]
@@ -206,6 +268,8 @@ pub mod pallet {
 			Ok(().into())
 		}
 
+		/// `timestamp` is the unix timestamp in milliseconds at which the enclave fetched
+		/// `new_value`, a timestamp in the future is taken as the current one.
 		#[pallet::call_index(3)]
 		#[pallet::weight(<T as Config>::WeightInfo::update_exchange_rate())]
 		pub fn update_exchange_rate(
@@ -213,10 +277,11 @@ pub mod pallet {
 			data_source: DataSource,
 			trading_pair: TradingPairString,
 			new_value: Option<ExchangeRate>,
+			timestamp: u64,
 		) -> DispatchResultWithPostInfo {
 			let sender = ensure_signed(origin)?;
 			<pallet_teerex::Pallet<T>>::ensure_registered_enclave(&sender)?;
-			let sender_index = <pallet_teerex::Pallet<T>>::enclave_index(sender);
+			let sender_index = <pallet_teerex::Pallet<T>>::enclave_index(&sender);
 			let sender_enclave = <pallet_teerex::Pallet<T>>::enclave(sender_index)
 				.ok_or(pallet_teerex::Error::<T>::EmptyEnclaveRegistry)?;
 			// Todo: Never checks data source len
@@ -228,21 +293,51 @@ pub mod pallet {
 				Self::is_whitelisted(&data_source, sender_enclave.mr_enclave),
 				<Error<T>>::ReleaseNotWhitelisted
 			);
-			if new_value.is_none() || new_value == Some(U32F32::from_num(0)) {
-				log::info!("Delete exchange rate : {:?}", new_value);
-				ExchangeRates::<T>::mutate_exists(&trading_pair, &data_source, |rate| *rate = None);
-				Self::deposit_event(Event::ExchangeRateDeleted(data_source, trading_pair));
-			} else {
-				log::info!("Update exchange rate : {:?}", new_value);
-				ExchangeRates::<T>::mutate_exists(&trading_pair, &data_source, |rate| {
-					*rate = new_value
-				});
-				Self::deposit_event(Event::ExchangeRateUpdated(
-					data_source,
-					trading_pair,
-					new_value,
-				));
+			let now: u64 = <timestamp::Pallet<T>>::get().saturated_into();
+			let max_age = T::MaxExchangeRateAge::get();
+			// the stale reports are removed first, so that they don't take the place of new ones
+			let mut reports = ExchangeRateReports::<T>::get(&trading_pair);
+			reports.retain(|(_, report)| now.saturating_sub(report.timestamp) <= max_age);
+			let is_sender_report = |(source, report): &(DataSource, ExchangeRateReport<_>)| {
+				*source == data_source && report.enclave == sender
+			};
+			match new_value {
+				Some(rate) if rate != U32F32::from_num(0) => {
+					let timestamp = timestamp.min(now);
+					ensure!(
+						now.saturating_sub(timestamp) <= max_age,
+						Error::<T>::StaleExchangeRate
+					);
+					let report = ExchangeRateReport { rate, timestamp, enclave: sender.clone() };
+					match reports.iter_mut().find(|entry| is_sender_report(entry)) {
+						Some(entry) => entry.1 = report,
+						None => reports
+							.try_push((data_source.clone(), report))
+							.map_err(|_| Error::<T>::TooManyExchangeRateReports)?,
+					}
+					log::info!("Update exchange rate : {:?}", new_value);
+					ExchangeRates::<T>::mutate_exists(&trading_pair, &data_source, |rate| {
+						*rate = new_value
+					});
+					Self::deposit_event(Event::ExchangeRateUpdated(
+						data_source,
+						trading_pair.clone(),
+						new_value,
+					));
+				},
+				_ => {
+					log::info!("Delete exchange rate : {:?}", new_value);
+					reports.retain(|entry| !is_sender_report(entry));
+					ExchangeRates::<T>::mutate_exists(&trading_pair, &data_source, |rate| {
+						*rate = None
+					});
+					Self::deposit_event(Event::ExchangeRateDeleted(
+						data_source,
+						trading_pair.clone(),
+					));
+				},
 			}
+			Self::aggregate_exchange_rate(&trading_pair, reports, now);
 			Ok(().into())
 		}
 	}
@@ -251,6 +346,146 @@ impl<T: Config> Pallet<T> {
 	fn is_whitelisted(data_source: &DataSource, mrenclave: [u8; 32]) -> bool {
 		Self::whitelist(data_source).contains(&mrenclave)
 	}
+
+	/// The report of `enclave` for `data_source` and `trading_pair`, if it has one.
+	pub fn exchange_rate_report(
+		trading_pair: &TradingPairString,
+		data_source: &DataSource,
+		enclave: &T::AccountId,
+	) -> Option<ExchangeRateReport<T::AccountId>> {
+		Self::exchange_rate_reports(trading_pair)
+			.into_iter()
+			.find(|(source, report)| source == data_source && report.enclave == *enclave)
+			.map(|(_, report)| report)
+	}
+
+	/// The aggregated exchange rate of `trading_pair` with its time weighted average until
+	/// now, it's stale when its newest report is older than `MaxExchangeRateAge`.
+	pub fn aggregated_exchange_rate(
+		trading_pair: &TradingPairString,
+	) -> Option<AggregatedExchangeRate> {
+		let now: u64 = <timestamp::Pallet<T>>::get().saturated_into();
+		AggregatedExchangeRates::<T>::get(trading_pair).map(|mut aggregated| {
+			aggregated.stale =
+				now.saturating_sub(aggregated.timestamp) > T::MaxExchangeRateAge::get();
+			if let Some(twap) = time_weighted_average(
+				&ExchangeRateObservations::<T>::get(trading_pair),
+				now,
+				T::ExchangeRateTwapPeriod::get(),
+			) {
+				aggregated.twap = twap;
+			}
+			aggregated
+		})
+	}
+
+	/// Stores `reports` of `trading_pair`, which mustn't be stale, and aggregates them.
+	/// Without any report left the previous value is kept, until it becomes stale.
+	fn aggregate_exchange_rate(
+		trading_pair: &TradingPairString,
+		reports: BoundedVec<
+			(DataSource, ExchangeRateReport<T::AccountId>),
+			T::MaxExchangeRateReports,
+		>,
+		now: u64,
+	) {
+		let mut rates_by_source: BTreeMap<&DataSource, Vec<ExchangeRate>> = BTreeMap::new();
+		let mut newest = 0;
+		for (source, report) in reports.iter() {
+			newest = newest.max(report.timestamp);
+			rates_by_source.entry(source).or_default().push(report.rate);
+		}
+
+		// the median of each source first, so that a source with more enclaves doesn't weigh more
+		let source_rates: Vec<ExchangeRate> =
+			rates_by_source.into_values().filter_map(median).collect();
+		let sources = source_rates.len() as u32;
+		if reports.is_empty() {
+			ExchangeRateReports::<T>::remove(trading_pair);
+		} else {
+			ExchangeRateReports::<T>::insert(trading_pair, reports);
+		}
+		let median = match median(source_rates) {
+			Some(median) => median,
+			None => return,
+		};
+
+		let observations = ExchangeRateObservations::<T>::mutate(trading_pair, |observations| {
+			record_observation(observations, median, now, T::ExchangeRateTwapPeriod::get());
+			observations.clone()
+		});
+		let twap = time_weighted_average(&observations, now, T::ExchangeRateTwapPeriod::get())
+			.unwrap_or(median);
+		let aggregated =
+			AggregatedExchangeRate { median, twap, sources, timestamp: newest, stale: false };
+		AggregatedExchangeRates::<T>::insert(trading_pair, &aggregated);
+		Self::deposit_event(Event::ExchangeRateAggregated(trading_pair.clone(), aggregated));
+	}
+}
+
+/// The median of `rates`, the mean of the middle two for an even number of rates.
+fn median(mut rates: Vec<ExchangeRate>) -> Option<ExchangeRate> {
+	rates.sort();
+	let mid = rates.len() / 2;
+	match rates.len() {
+		0 => None,
+		n if n % 2 == 1 => Some(rates[mid]),
+		_ => {
+			let two = U32F32::from_num(2);
+			Some(rates[mid - 1] / two + rates[mid] / two)
+		},
+	}
+}
+
+/// Records that `median` is in effect from `now` on. The observations which ended before the
+/// last `period` are dropped, and the oldest one if there's no room left.
+fn record_observation<S: Get<u32>>(
+	observations: &mut BoundedVec<(u64, ExchangeRate), S>,
+	median: ExchangeRate,
+	now: u64,
+	period: u64,
+) {
+	// an observation never goes back in time, even if `now` did
+	let now = observations.last().map_or(now, |(timestamp, _)| now.max(*timestamp));
+	if let Some(last) = observations.iter_mut().last().filter(|(timestamp, _)| *timestamp == now) {
+		last.1 = median;
+		return
+	}
+	let window_start = now.saturating_sub(period);
+	while observations.get(1).map_or(false, |(timestamp, _)| *timestamp <= window_start) {
+		observations.remove(0);
+	}
+	if !observations.is_empty() && observations.len() >= S::get() as usize {
+		observations.remove(0);
+	}
+	// it only fails with a bound of 0, then there's no average
+	let _ = observations.try_push((now, median));
+}
+
+/// The average of the medians of `observations` weighted by how long each of them was in
+/// effect during the last `period` until `now`, `None` without observations.
+fn time_weighted_average(
+	observations: &[(u64, ExchangeRate)],
+	now: u64,
+	period: u64,
+) -> Option<ExchangeRate> {
+	let window_start = now.saturating_sub(period);
+	let mut weighted_sum: u128 = 0;
+	let mut duration: u64 = 0;
+	for (i, (timestamp, median)) in observations.iter().enumerate() {
+		let start = (*timestamp).max(window_start);
+		let end = observations.get(i + 1).map_or(now, |(next, _)| *next).min(now);
+		if end > start {
+			let elapsed = end - start;
+			weighted_sum = weighted_sum.saturating_add(median.to_bits() as u128 * elapsed as u128);
+			duration += elapsed;
+		}
+	}
+	match duration {
+		// all medians came at once, the latest one is in effect
+		0 => observations.last().map(|(_, median)| *median),
+		_ => Some(U32F32::from_bits((weighted_sum / duration as u128) as u64)),
+	}
 }
 
 mod benchmarking;
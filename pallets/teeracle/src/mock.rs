@@ -125,6 +125,10 @@ parameter_types! {
 	pub const MaxSilenceTime: u64 = 172_800_000; // 48h
 	pub const MaxWhitelistedReleases: u32 = 10;
 	pub const MaxOracleBlobLen: u32 = 4096;
+	pub const MaxExchangeRateAge: u64 = 172_800_000; // 48h
+	pub const ExchangeRateTwapPeriod: u64 = 86_400_000; // 24h
+	pub const MaxExchangeRateReports: u32 = 3;
+	pub const MaxExchangeRateObservations: u32 = 4;
 }
 
 impl pallet_teerex::Config for Test {
@@ -141,6 +145,10 @@ impl Config for Test {
 	type WeightInfo = ();
 	type MaxWhitelistedReleases = MaxWhitelistedReleases;
 	type MaxOracleBlobLen = MaxOracleBlobLen;
+	type MaxExchangeRateAge = MaxExchangeRateAge;
+	type ExchangeRateTwapPeriod = ExchangeRateTwapPeriod;
+	type MaxExchangeRateReports = MaxExchangeRateReports;
+	type MaxExchangeRateObservations = MaxExchangeRateObservations;
 }
 
 // This function basically just builds a genesis storage key/value store according to
@@ -17,19 +17,22 @@
 #![allow(dead_code, unused_imports, const_item_mutation)]
 
 use crate::{mock::*, ExchangeRates};
-use frame_support::{assert_err, assert_noop, assert_ok};
+use frame_support::{
+	assert_err, assert_noop, assert_ok, dispatch::DispatchResultWithPostInfo, traits::Get,
+};
 use hex_literal::hex;
 use pallet_teerex::Error;
 use sp_runtime::DispatchError::BadOrigin;
 use substrate_fixed::types::U32F32;
 use teeracle_primitives::*;
 use test_utils::ias::consts::{
-	TEST4_CERT, TEST4_MRENCLAVE, TEST4_SIGNER_PUB, TEST4_TIMESTAMP, TEST5_MRENCLAVE,
-	TEST5_SIGNER_PUB, TEST8_MRENCLAVE, URL,
+	TEST4_CERT, TEST4_MRENCLAVE, TEST4_SIGNER_PUB, TEST4_TIMESTAMP, TEST5_CERT, TEST5_MRENCLAVE,
+	TEST5_SIGNER_PUB, TEST5_TIMESTAMP, TEST8_MRENCLAVE, URL,
 };
 
 const COINGECKO_SRC: &str = "https://api.coingecko.com";
 const COINMARKETCAP_SRC: &str = "https://coinmarketcap.com/";
+const BINANCE_SRC: &str = "https://api.binance.com";
 
 const DOT_USD_TRADING_PAIR: &str = "DOT/USD";
 
@@ -58,7 +61,8 @@ fn update_exchange_rate_dot_dollars_ok(src: &str, rate: Option<U32F32>) {
 		RuntimeOrigin::signed(signer),
 		src.to_owned(),
 		DOT_USD_TRADING_PAIR.to_owned(),
-		rate
+		rate,
+		TEST4_TIMESTAMP
 	));
 }
 
@@ -198,7 +202,8 @@ fn update_exchange_rate_from_not_registered_enclave_fails() {
 				RuntimeOrigin::signed(signer),
 				COINGECKO_SRC.to_owned(),
 				DOT_USD_TRADING_PAIR.to_owned(),
-				Some(rate)
+				Some(rate),
+				TEST4_TIMESTAMP
 			),
 			Error::<Test>::EnclaveIsNotRegistered
 		);
@@ -240,7 +245,8 @@ fn update_exchange_rate_from_not_whitelisted_oracle_fails() {
 				RuntimeOrigin::signed(signer),
 				COINGECKO_SRC.to_owned(),
 				DOT_USD_TRADING_PAIR.to_owned(),
-				Some(rate)
+				Some(rate),
+				TEST4_TIMESTAMP
 			),
 			crate::Error::<Test>::ReleaseNotWhitelisted
 		);
@@ -285,13 +291,339 @@ fn update_exchange_rate_with_too_long_trading_pair_fails() {
 				RuntimeOrigin::signed(signer),
 				COINGECKO_SRC.to_owned(),
 				too_long_trading_pair,
-				rate
+				rate,
+				TEST4_TIMESTAMP
 			),
 			crate::Error::<Test>::TradingPairStringTooLong
 		);
 	})
 }
 
+fn update_exchange_rate_dot_dollars_by(
+	signer_pub: &[u8; 32],
+	src: &str,
+	rate: f64,
+	timestamp: u64,
+) -> DispatchResultWithPostInfo {
+	Teeracle::update_exchange_rate(
+		RuntimeOrigin::signed(get_signer(signer_pub)),
+		src.to_owned(),
+		DOT_USD_TRADING_PAIR.to_owned(),
+		Some(U32F32::from_num(rate)),
+		timestamp,
+	)
+}
+
+#[test]
+fn update_exchange_rate_aggregates_over_sources() {
+	new_test_ext().execute_with(|| {
+		register_enclave_and_add_oracle_to_whitelist_ok(COINGECKO_SRC);
+		let mrenclave = Teerex::enclave(1).unwrap().mr_enclave;
+		assert_ok!(Teeracle::add_to_whitelist(
+			RuntimeOrigin::root(),
+			COINMARKETCAP_SRC.to_owned(),
+			mrenclave
+		));
+		assert_ok!(Teeracle::add_to_whitelist(
+			RuntimeOrigin::root(),
+			BINANCE_SRC.to_owned(),
+			mrenclave
+		));
+		let pair = DOT_USD_TRADING_PAIR.to_owned();
+		assert_eq!(Teeracle::aggregated_exchange_rate(&pair), None);
+
+		assert_ok!(update_exchange_rate_dot_dollars_by(
+			TEST4_SIGNER_PUB,
+			COINGECKO_SRC,
+			10.0,
+			TEST4_TIMESTAMP
+		));
+		assert_ok!(update_exchange_rate_dot_dollars_by(
+			TEST4_SIGNER_PUB,
+			COINMARKETCAP_SRC,
+			20.0,
+			TEST4_TIMESTAMP
+		));
+		assert_eq!(Teeracle::aggregated_exchange_rate(&pair).unwrap().median, U32F32::from_num(15));
+		assert_ok!(update_exchange_rate_dot_dollars_by(
+			TEST4_SIGNER_PUB,
+			BINANCE_SRC,
+			60.0,
+			TEST4_TIMESTAMP
+		));
+		let expected = AggregatedExchangeRate {
+			median: U32F32::from_num(20),
+			// no time passed since the first report, the latest median is in effect
+			twap: U32F32::from_num(20),
+			sources: 3,
+			timestamp: TEST4_TIMESTAMP,
+			stale: false,
+		};
+		assert_eq!(Teeracle::aggregated_exchange_rate(&pair), Some(expected.clone()));
+		let expected_event =
+			RuntimeEvent::Teeracle(crate::Event::ExchangeRateAggregated(pair.clone(), expected));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+
+		// only the median of 20 was in effect until now
+		let now = TEST4_TIMESTAMP + ExchangeRateTwapPeriod::get() / 2;
+		Timestamp::set_timestamp(now);
+		assert_ok!(update_exchange_rate_dot_dollars_by(TEST4_SIGNER_PUB, COINGECKO_SRC, 70.0, now));
+		let aggregated = Teeracle::aggregated_exchange_rate(&pair).unwrap();
+		assert_eq!(aggregated.median, U32F32::from_num(60));
+		assert_eq!(aggregated.twap, U32F32::from_num(20));
+		assert_eq!(aggregated.timestamp, now);
+
+		// deleting a rate removes its report
+		update_exchange_rate_dot_dollars_ok(BINANCE_SRC, None);
+		assert_eq!(
+			Teeracle::exchange_rate_report(
+				&pair,
+				&BINANCE_SRC.to_owned(),
+				&get_signer(TEST4_SIGNER_PUB)
+			),
+			None
+		);
+		assert_eq!(Teeracle::aggregated_exchange_rate(&pair).unwrap().median, U32F32::from_num(45));
+	})
+}
+
+#[test]
+fn update_exchange_rate_aggregates_over_enclaves_of_a_source() {
+	new_test_ext().execute_with(|| {
+		register_enclave_and_add_oracle_to_whitelist_ok(COINGECKO_SRC);
+		let mrenclave = Teerex::enclave(1).unwrap().mr_enclave;
+		assert_ok!(Teeracle::add_to_whitelist(
+			RuntimeOrigin::root(),
+			COINMARKETCAP_SRC.to_owned(),
+			mrenclave
+		));
+
+		Timestamp::set_timestamp(TEST5_TIMESTAMP);
+		assert_ok!(Teerex::register_enclave(
+			RuntimeOrigin::signed(get_signer(TEST5_SIGNER_PUB)),
+			TEST5_CERT.to_vec(),
+			URL.to_vec(),
+			None,
+			None,
+		));
+		assert_ok!(Teeracle::add_to_whitelist(
+			RuntimeOrigin::root(),
+			COINGECKO_SRC.to_owned(),
+			TEST5_MRENCLAVE
+		));
+
+		assert_ok!(update_exchange_rate_dot_dollars_by(
+			TEST4_SIGNER_PUB,
+			COINGECKO_SRC,
+			10.0,
+			TEST5_TIMESTAMP
+		));
+		assert_ok!(update_exchange_rate_dot_dollars_by(
+			TEST5_SIGNER_PUB,
+			COINGECKO_SRC,
+			30.0,
+			TEST5_TIMESTAMP
+		));
+		assert_ok!(update_exchange_rate_dot_dollars_by(
+			TEST4_SIGNER_PUB,
+			COINMARKETCAP_SRC,
+			100.0,
+			TEST5_TIMESTAMP
+		));
+
+		// a source with more enclaves doesn't weigh more
+		let aggregated =
+			Teeracle::aggregated_exchange_rate(&DOT_USD_TRADING_PAIR.to_owned()).unwrap();
+		assert_eq!(aggregated.median, U32F32::from_num(60));
+		assert_eq!(aggregated.sources, 2);
+	})
+}
+
+#[test]
+fn aggregated_exchange_rate_becomes_stale() {
+	new_test_ext().execute_with(|| {
+		register_enclave_and_add_oracle_to_whitelist_ok(COINGECKO_SRC);
+		let pair = DOT_USD_TRADING_PAIR.to_owned();
+
+		// a timestamp in the future is taken as the current one
+		assert_ok!(update_exchange_rate_dot_dollars_by(
+			TEST4_SIGNER_PUB,
+			COINGECKO_SRC,
+			10.0,
+			TEST4_TIMESTAMP + 1000
+		));
+		assert_eq!(Teeracle::aggregated_exchange_rate(&pair).unwrap().timestamp, TEST4_TIMESTAMP);
+
+		let now = TEST4_TIMESTAMP + MaxExchangeRateAge::get() + 1;
+		Timestamp::set_timestamp(now);
+		let aggregated = Teeracle::aggregated_exchange_rate(&pair).unwrap();
+		assert!(aggregated.stale);
+		assert_eq!(aggregated.median, U32F32::from_num(10));
+
+		assert_noop!(
+			update_exchange_rate_dot_dollars_by(
+				TEST4_SIGNER_PUB,
+				COINGECKO_SRC,
+				20.0,
+				TEST4_TIMESTAMP
+			),
+			crate::Error::<Test>::StaleExchangeRate
+		);
+		assert_ok!(update_exchange_rate_dot_dollars_by(TEST4_SIGNER_PUB, COINGECKO_SRC, 20.0, now));
+		let aggregated = Teeracle::aggregated_exchange_rate(&pair).unwrap();
+		assert!(!aggregated.stale);
+		assert_eq!(aggregated.median, U32F32::from_num(20));
+	})
+}
+
+#[test]
+fn twap_weighs_medians_by_the_time_they_were_in_effect() {
+	new_test_ext().execute_with(|| {
+		register_enclave_and_add_oracle_to_whitelist_ok(COINGECKO_SRC);
+		let pair = DOT_USD_TRADING_PAIR.to_owned();
+		let period = ExchangeRateTwapPeriod::get();
+
+		assert_ok!(update_exchange_rate_dot_dollars_by(
+			TEST4_SIGNER_PUB,
+			COINGECKO_SRC,
+			10.0,
+			TEST4_TIMESTAMP
+		));
+		let now = TEST4_TIMESTAMP + period / 2;
+		Timestamp::set_timestamp(now);
+		assert_ok!(update_exchange_rate_dot_dollars_by(TEST4_SIGNER_PUB, COINGECKO_SRC, 30.0, now));
+		assert_eq!(Teeracle::aggregated_exchange_rate(&pair).unwrap().twap, U32F32::from_num(10));
+
+		// 10 and 30 were in effect for half of the period each
+		Timestamp::set_timestamp(TEST4_TIMESTAMP + period);
+		assert_eq!(Teeracle::aggregated_exchange_rate(&pair).unwrap().twap, U32F32::from_num(20));
+
+		// 10 went out of the period
+		Timestamp::set_timestamp(TEST4_TIMESTAMP + period * 3 / 2);
+		assert_eq!(Teeracle::aggregated_exchange_rate(&pair).unwrap().twap, U32F32::from_num(30));
+	})
+}
+
+#[test]
+fn older_report_does_not_move_the_aggregated_rate_back_in_time() {
+	new_test_ext().execute_with(|| {
+		register_enclave_and_add_oracle_to_whitelist_ok(COINGECKO_SRC);
+		let mrenclave = Teerex::enclave(1).unwrap().mr_enclave;
+		assert_ok!(Teeracle::add_to_whitelist(
+			RuntimeOrigin::root(),
+			COINMARKETCAP_SRC.to_owned(),
+			mrenclave
+		));
+		let pair = DOT_USD_TRADING_PAIR.to_owned();
+		let period = ExchangeRateTwapPeriod::get();
+
+		let now = TEST4_TIMESTAMP + period / 2;
+		Timestamp::set_timestamp(now);
+		assert_ok!(update_exchange_rate_dot_dollars_by(TEST4_SIGNER_PUB, COINGECKO_SRC, 10.0, now));
+		// fetched before the previous report, but reported after it
+		assert_ok!(update_exchange_rate_dot_dollars_by(
+			TEST4_SIGNER_PUB,
+			COINMARKETCAP_SRC,
+			30.0,
+			TEST4_TIMESTAMP
+		));
+
+		let aggregated = Teeracle::aggregated_exchange_rate(&pair).unwrap();
+		assert_eq!(aggregated.median, U32F32::from_num(20));
+		assert_eq!(aggregated.timestamp, now);
+		assert_eq!(
+			crate::ExchangeRateObservations::<Test>::get(&pair).into_inner(),
+			vec![(now, U32F32::from_num(20))]
+		);
+
+		Timestamp::set_timestamp(now + period);
+		assert_eq!(Teeracle::aggregated_exchange_rate(&pair).unwrap().twap, U32F32::from_num(20));
+	})
+}
+
+#[test]
+fn exchange_rate_observations_are_bounded() {
+	new_test_ext().execute_with(|| {
+		register_enclave_and_add_oracle_to_whitelist_ok(COINGECKO_SRC);
+		let pair = DOT_USD_TRADING_PAIR.to_owned();
+
+		for i in 0..6 {
+			let now = TEST4_TIMESTAMP + i * 1000;
+			Timestamp::set_timestamp(now);
+			assert_ok!(update_exchange_rate_dot_dollars_by(
+				TEST4_SIGNER_PUB,
+				COINGECKO_SRC,
+				(i + 1) as f64,
+				now
+			));
+		}
+
+		let observations = crate::ExchangeRateObservations::<Test>::get(&pair);
+		assert_eq!(observations.len() as u32, MaxExchangeRateObservations::get());
+		// the oldest ones were dropped
+		assert_eq!(observations[0], (TEST4_TIMESTAMP + 2000, U32F32::from_num(3)));
+	})
+}
+
+#[test]
+fn update_exchange_rate_with_too_many_reports_fails() {
+	new_test_ext().execute_with(|| {
+		register_enclave_and_add_oracle_to_whitelist_ok(COINGECKO_SRC);
+		let mrenclave = Teerex::enclave(1).unwrap().mr_enclave;
+		for src in [COINMARKETCAP_SRC, BINANCE_SRC] {
+			assert_ok!(Teeracle::add_to_whitelist(
+				RuntimeOrigin::root(),
+				src.to_owned(),
+				mrenclave
+			));
+		}
+		Timestamp::set_timestamp(TEST5_TIMESTAMP);
+		assert_ok!(Teerex::register_enclave(
+			RuntimeOrigin::signed(get_signer(TEST5_SIGNER_PUB)),
+			TEST5_CERT.to_vec(),
+			URL.to_vec(),
+			None,
+			None,
+		));
+		assert_ok!(Teeracle::add_to_whitelist(
+			RuntimeOrigin::root(),
+			COINGECKO_SRC.to_owned(),
+			TEST5_MRENCLAVE
+		));
+
+		for src in [COINGECKO_SRC, COINMARKETCAP_SRC, BINANCE_SRC] {
+			assert_ok!(update_exchange_rate_dot_dollars_by(
+				TEST4_SIGNER_PUB,
+				src,
+				10.0,
+				TEST5_TIMESTAMP
+			));
+		}
+		assert_noop!(
+			update_exchange_rate_dot_dollars_by(
+				TEST5_SIGNER_PUB,
+				COINGECKO_SRC,
+				10.0,
+				TEST5_TIMESTAMP
+			),
+			crate::Error::<Test>::TooManyExchangeRateReports
+		);
+		// an enclave can still update its own report
+		assert_ok!(update_exchange_rate_dot_dollars_by(
+			TEST4_SIGNER_PUB,
+			COINGECKO_SRC,
+			20.0,
+			TEST5_TIMESTAMP
+		));
+
+		// stale reports make room for new ones
+		let now = TEST5_TIMESTAMP + MaxExchangeRateAge::get() + 1;
+		Timestamp::set_timestamp(now);
+		assert_ok!(update_exchange_rate_dot_dollars_by(TEST5_SIGNER_PUB, COINGECKO_SRC, 30.0, now));
+		assert_eq!(Teeracle::exchange_rate_reports(DOT_USD_TRADING_PAIR.to_owned()).len(), 1);
+	})
+}
+
 #[test]
 fn add_to_whitelist_works() {
 	new_test_ext().execute_with(|| {
@@ -19,6 +19,10 @@
 //! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 4.0.0-dev
 //! DATE: 2022-12-27, STEPS: `20`, REPEAT: 50, LOW RANGE: `[]`, HIGH RANGE: `[]`
 //! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("rococo-dev"), DB CACHE: 20
+//!
+//! TODO: `update_exchange_rate` was NOT re-benchmarked after the aggregation of the bounded
+//! reports and the time weighted average, its storage accesses were updated by hand. Re-run
+//! the command below.
 
 // Executed Command:
 // ./target/release/litentry-collator
@@ -60,10 +64,13 @@ impl<T: frame_system::Config> WeightInfo for LitentryWeight<T> {
 	// Storage: Teerex EnclaveRegistry (r:1 w:0)
 	// Storage: Teeracle Whitelists (r:1 w:0)
 	// Storage: Teeracle ExchangeRates (r:1 w:1)
+	// Storage: Teeracle ExchangeRateReports (r:1 w:1)
+	// Storage: Teeracle ExchangeRateObservations (r:1 w:1)
+	// Storage: Teeracle AggregatedExchangeRates (r:0 w:1)
 	fn update_exchange_rate() -> Weight {
 		Weight::from_ref_time(44_063_000 as u64)
-			.saturating_add(T::DbWeight::get().reads(4 as u64))
-			.saturating_add(T::DbWeight::get().writes(1 as u64))
+			.saturating_add(T::DbWeight::get().reads(6 as u64))
+			.saturating_add(T::DbWeight::get().writes(4 as u64))
 	}
 	// Storage: Teerex EnclaveIndex (r:1 w:0)
 	// Storage: Teerex EnclaveRegistry (r:1 w:0)
@@ -94,10 +101,13 @@ impl WeightInfo for () {
 	// Storage: Teerex EnclaveRegistry (r:1 w:0)
 	// Storage: Teeracle Whitelists (r:1 w:0)
 	// Storage: Teeracle ExchangeRates (r:1 w:1)
+	// Storage: Teeracle ExchangeRateReports (r:1 w:1)
+	// Storage: Teeracle ExchangeRateObservations (r:1 w:1)
+	// Storage: Teeracle AggregatedExchangeRates (r:0 w:1)
 	fn update_exchange_rate() -> Weight {
 		Weight::from_ref_time(44_063_000 as u64)
-			.saturating_add(RocksDbWeight::get().reads(4 as u64))
-			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+			.saturating_add(RocksDbWeight::get().reads(6 as u64))
+			.saturating_add(RocksDbWeight::get().writes(4 as u64))
 	}
 	// Storage: Teerex EnclaveIndex (r:1 w:0)
 	// Storage: Teerex EnclaveRegistry (r:1 w:0)
@@ -59,11 +59,14 @@ const DATA_LENGTH_LIMIT: usize = 100;
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
-	use frame_support::pallet_prelude::*;
+	use frame_support::{pallet_prelude::*, traits::StorageVersion};
 	use frame_system::pallet_prelude::*;
 
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T>(PhantomData<T>);
 
@@ -122,6 +125,13 @@ pub mod pallet {
 	#[pallet::getter(fn enclave_count)]
 	pub type EnclaveCount<T: Config> = StorageValue<_, u64, ValueQuery>;
 
+	// The number of registered enclaves with each MRENCLAVE, so that it needn't be counted
+	// from the registry.
+	#[pallet::storage]
+	#[pallet::getter(fn mrenclave_enclave_count)]
+	pub type MrenclaveEnclaveCount<T: Config> =
+		StorageMap<_, Blake2_128Concat, MrEnclave, u64, ValueQuery>;
+
 	#[pallet::storage]
 	#[pallet::getter(fn quoting_enclave)]
 	pub type QuotingEnclaveRegistry<T: Config> = StorageValue<_, QuotingEnclave, ValueQuery>;
@@ -596,6 +606,8 @@ pub mod pallet {
 			for index in <EnclaveRegistry<T>>::iter_keys() {
 				let mut enclave =
 					<EnclaveRegistry<T>>::get(index).ok_or(Error::<T>::EmptyEnclaveRegistry)?;
+				Self::dec_mrenclave_enclave_count(&enclave.mr_enclave);
+				<MrenclaveEnclaveCount<T>>::mutate(new_mrenclave, |c| *c = c.saturating_add(1));
 				enclave.mr_enclave = new_mrenclave;
 				<EnclaveRegistry<T>>::insert(index, enclave);
 			}
@@ -650,7 +662,11 @@ impl<T: Config> Pallet<T> {
 	) -> DispatchResultWithPostInfo {
 		let enclave_idx = if <EnclaveIndex<T>>::contains_key(sender) {
 			log::info!("Updating already registered enclave");
-			<EnclaveIndex<T>>::get(sender)
+			let enclave_idx = <EnclaveIndex<T>>::get(sender);
+			if let Some(old) = <EnclaveRegistry<T>>::get(enclave_idx) {
+				Self::dec_mrenclave_enclave_count(&old.mr_enclave);
+			}
+			enclave_idx
 		} else {
 			let enclaves_count = Self::enclave_count()
 				.checked_add(1)
@@ -660,10 +676,17 @@ impl<T: Config> Pallet<T> {
 			enclaves_count
 		};
 
+		<MrenclaveEnclaveCount<T>>::mutate(enclave.mr_enclave, |c| *c = c.saturating_add(1));
 		<EnclaveRegistry<T>>::insert(enclave_idx, enclave);
 		Ok(().into())
 	}
 
+	fn dec_mrenclave_enclave_count(mr_enclave: &MrEnclave) {
+		<MrenclaveEnclaveCount<T>>::mutate_exists(mr_enclave, |c| {
+			*c = c.and_then(|c| c.checked_sub(1)).filter(|c| *c > 0)
+		});
+	}
+
 	fn remove_enclave(sender: &T::AccountId) -> DispatchResultWithPostInfo {
 		ensure!(<EnclaveIndex<T>>::contains_key(sender), <Error<T>>::EnclaveIsNotRegistered);
 		let index_to_remove = <EnclaveIndex<T>>::take(sender);
@@ -673,6 +696,10 @@ impl<T: Config> Pallet<T> {
 			.checked_sub(1)
 			.ok_or("[Teerex]: Underflow removing an enclave from the registry")?;
 
+		if let Some(enclave) = <EnclaveRegistry<T>>::get(index_to_remove) {
+			Self::dec_mrenclave_enclave_count(&enclave.mr_enclave);
+		}
+
 		Self::swap_and_pop(index_to_remove, new_enclaves_count + 1)?;
 		<EnclaveCount<T>>::put(new_enclaves_count);
 
@@ -855,6 +882,7 @@ impl<T: Config> OnTimestampSet<T::Moment> for Pallet<T> {
 }
 
 mod benchmarking;
+pub mod migrations;
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
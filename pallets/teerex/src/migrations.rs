@@ -0,0 +1,68 @@
+/*
+Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+	http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+
+*/
+
+//! Storage migrations of pallet-teerex.
+
+use crate::{Config, EnclaveRegistry, MrenclaveEnclaveCount, Pallet};
+use frame_support::{
+	traits::{Get, GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+	weights::Weight,
+};
+use sp_std::marker::PhantomData;
+#[cfg(feature = "try-runtime")]
+use {codec::Encode, sp_std::vec::Vec};
+
+/// Counts the registered enclaves of each MRENCLAVE into `MrenclaveEnclaveCount`.
+pub struct MigrateToV1<T>(PhantomData<T>);
+
+impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+	fn on_runtime_upgrade() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() != 0 {
+			return T::DbWeight::get().reads(1)
+		}
+
+		let mut count: u64 = 0;
+		for enclave in EnclaveRegistry::<T>::iter_values() {
+			count += 1;
+			MrenclaveEnclaveCount::<T>::mutate(enclave.mr_enclave, |c| *c = c.saturating_add(1));
+		}
+		StorageVersion::new(1).put::<Pallet<T>>();
+
+		T::DbWeight::get().reads_writes(2 * count + 1, count + 1)
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+		Ok((EnclaveRegistry::<T>::iter_keys().count() as u64).encode())
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+		use codec::Decode;
+
+		let count = u64::decode(&mut &state[..]).map_err(|_| "invalid pre-upgrade state")?;
+		frame_support::ensure!(
+			Pallet::<T>::on_chain_storage_version() == 1,
+			"storage version not updated"
+		);
+		frame_support::ensure!(
+			MrenclaveEnclaveCount::<T>::iter_values().sum::<u64>() == count,
+			"MrenclaveEnclaveCount doesn't add up to the registered enclaves"
+		);
+		Ok(())
+	}
+}
@@ -1026,3 +1026,85 @@ fn publish_hash_with_too_much_data_fails() {
 		);
 	})
 }
+
+#[test]
+fn mrenclave_enclave_count_works() {
+	new_test_ext().execute_with(|| {
+		Timestamp::set_timestamp(TEST7_TIMESTAMP);
+
+		// TEST5, TEST6 and TEST7 have the same MRENCLAVE
+		for (signer, cert) in [
+			(TEST5_SIGNER_PUB, TEST5_CERT),
+			(TEST6_SIGNER_PUB, TEST6_CERT),
+			(TEST7_SIGNER_PUB, TEST7_CERT),
+		] {
+			assert_ok!(Teerex::register_enclave(
+				RuntimeOrigin::signed(get_signer(signer)),
+				cert.to_vec(),
+				URL.to_vec(),
+				None,
+				None,
+			));
+		}
+		assert_eq!(Teerex::mrenclave_enclave_count(TEST7_MRENCLAVE), 3);
+
+		// registering again doesn't count twice
+		assert_ok!(Teerex::register_enclave(
+			RuntimeOrigin::signed(get_signer(TEST7_SIGNER_PUB)),
+			TEST7_CERT.to_vec(),
+			URL.to_vec(),
+			None,
+			None,
+		));
+		assert_eq!(Teerex::mrenclave_enclave_count(TEST7_MRENCLAVE), 3);
+
+		assert_ok!(Teerex::unregister_enclave(RuntimeOrigin::signed(get_signer(TEST6_SIGNER_PUB))));
+		assert_eq!(Teerex::mrenclave_enclave_count(TEST7_MRENCLAVE), 2);
+
+		// an enclave updated with another MRENCLAVE moves to its count
+		let signer5 = get_signer(TEST5_SIGNER_PUB);
+		let mut enclave = Teerex::enclave(Teerex::enclave_index(&signer5)).unwrap();
+		enclave.mr_enclave = TEST4_MRENCLAVE;
+		assert_ok!(Teerex::add_enclave(&signer5, &enclave));
+		assert_eq!(Teerex::mrenclave_enclave_count(TEST7_MRENCLAVE), 1);
+		assert_eq!(Teerex::mrenclave_enclave_count(TEST4_MRENCLAVE), 1);
+		assert_eq!(Teerex::enclave_count(), 2);
+
+		assert_ok!(Teerex::unregister_enclave(RuntimeOrigin::signed(signer5)));
+		assert!(!crate::MrenclaveEnclaveCount::<Test>::contains_key(TEST4_MRENCLAVE));
+	})
+}
+
+#[test]
+fn migrate_to_v1_counts_enclaves_by_mrenclave() {
+	use crate::{migrations::MigrateToV1, MrenclaveEnclaveCount, Pallet};
+	use frame_support::traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion};
+	use test_utils::ias::TestEnclave;
+
+	new_test_ext().execute_with(|| {
+		let enclave = |signer: &[u8; 32], mr_enclave: [u8; 32]| {
+			Enclave::<AccountId, Vec<u8>>::test_enclave(get_signer(signer))
+				.with_mr_enclave(mr_enclave)
+		};
+		for (index, e) in [
+			enclave(TEST5_SIGNER_PUB, TEST5_MRENCLAVE),
+			enclave(TEST6_SIGNER_PUB, TEST6_MRENCLAVE),
+			enclave(TEST4_SIGNER_PUB, TEST4_MRENCLAVE),
+		]
+		.iter()
+		.enumerate()
+		{
+			EnclaveRegistry::<Test>::insert(index as u64 + 1, e);
+		}
+		StorageVersion::new(0).put::<Pallet<Test>>();
+
+		MigrateToV1::<Test>::on_runtime_upgrade();
+		assert_eq!(Pallet::<Test>::on_chain_storage_version(), 1);
+		assert_eq!(MrenclaveEnclaveCount::<Test>::get(TEST5_MRENCLAVE), 2);
+		assert_eq!(MrenclaveEnclaveCount::<Test>::get(TEST4_MRENCLAVE), 1);
+
+		// it's not applied twice
+		MigrateToV1::<Test>::on_runtime_upgrade();
+		assert_eq!(MrenclaveEnclaveCount::<Test>::get(TEST5_MRENCLAVE), 2);
+	})
+}
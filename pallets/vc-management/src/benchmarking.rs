@@ -22,7 +22,7 @@ use crate::Pallet as VCManagement;
 #[allow(unused)]
 use core_primitives::{AesOutput, ErrorDetail, VCMPError};
 use frame_benchmarking::{benchmarks, impl_benchmark_test_suite, BenchmarkError};
-use frame_support::traits::EnsureOrigin;
+use frame_support::traits::{EnsureOrigin, Get, Hooks};
 use frame_system::RawOrigin;
 use sp_core::H256;
 use sp_std::vec;
@@ -201,6 +201,20 @@ benchmarks! {
 		assert_last_event::<T>(Event::SchemaRevoked { account, shard, index: 0 }.into())
 	}
 
+	// Benchmark `set_assertion_schema`. There are no worst conditions.
+	set_assertion_schema {
+		let account: T::AccountId =  frame_benchmarking::account("TEST_A", 0u32, USER_SEED);
+		VCManagement::<T>::set_admin(RawOrigin::Root.into(), account.clone())?;
+		let id: Vec<u8> = vec![1, 2, 3, 4];
+		let content: Vec<u8> = vec![5, 6, 7, 8];
+		let shard = H256::from_slice(&TEST8_MRENCLAVE);
+		VCManagement::<T>::add_schema(RawOrigin::Signed(account.clone()).into(), shard, id, content)?;
+		let assertion = Assertion::A1;
+	}: _(RawOrigin::Signed(account), assertion.clone(), Some(0))
+	verify {
+		assert_last_event::<T>(Event::AssertionSchemaSet { assertion, index: Some(0) }.into())
+	}
+
 	// Benchmark `add_vc_registry_item`. There are no worst conditions. The benchmark showed that
 	// execution time is constant irrespective of encrypted_data size.
 	add_vc_registry_item {
@@ -241,6 +255,39 @@ benchmarks! {
 	verify {
 		assert_last_event::<T>(Event::VCRegistryCleared.into())
 	}
+
+	// Benchmark `renew_vc`. There are no worst conditions. The benchmark showed that
+	// execution time is constant irrespective of encrypted_data size.
+	renew_vc {
+		let account: T::AccountId =  frame_benchmarking::account("TEST_A", 0u32, USER_SEED);
+		let shard = H256::from_slice(&TEST8_MRENCLAVE);
+		let assertion = Assertion::A1;
+		let tee_origin = T::TEECallOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		VCManagement::<T>::vc_issued(tee_origin, account.clone(), assertion.clone(), VC_INDEX, VC_HASH, AesOutput::default(), H256::default())?;
+	}: _(RawOrigin::Signed(account.clone()), shard, VC_INDEX, assertion.clone())
+	verify {
+		assert_last_event::<T>(Event::VCRenewRequested { account, shard, index: VC_INDEX, assertion }.into())
+	}
+
+	// Benchmark the expiry of `x` VCs in `on_initialize`, worst case is a full queue.
+	expire_vc {
+		let x in 0..T::MaxExpiriesPerBlock::get();
+		let account: T::AccountId =  frame_benchmarking::account("TEST_A", 0u32, USER_SEED);
+		VCManagement::<T>::set_admin(RawOrigin::Root.into(), account.clone())?;
+		let assertion = Assertion::A1;
+		let mut expires_at = frame_system::Pallet::<T>::block_number();
+		for i in 0..x {
+			let seed = USER_SEED - i;
+			let index: H256 = convert_u32_array_to_u8_array([seed; 8]).into();
+			VCManagement::<T>::add_vc_registry_item(RawOrigin::Signed(account.clone()).into(), index, account.clone(), assertion.clone(), VC_HASH)?;
+			expires_at = VCManagement::<T>::vc_registry(index).and_then(|c| c.expires_at).unwrap();
+		}
+	}: {
+		VCManagement::<T>::on_initialize(expires_at);
+	}
+	verify {
+		assert!(VCManagement::<T>::expiry_queue(expires_at).is_empty());
+	}
 }
 
 #[cfg(test)]
@@ -30,12 +30,14 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+pub mod migrations;
 pub mod weights;
 
 pub use crate::weights::WeightInfo;
 
 use core_primitives::{
-	AesOutput, Assertion, SchemaIndex, ShardIdentifier, SCHEMA_CONTENT_LEN, SCHEMA_ID_LEN,
+	ActiveVC, AesOutput, Assertion, SchemaIndex, ShardIdentifier, VCRegistryLookup,
+	SCHEMA_CONTENT_LEN, SCHEMA_ID_LEN,
 };
 pub use pallet::*;
 use sp_core::H256;
@@ -55,9 +57,17 @@ pub mod pallet {
 	use core_primitives::{ErrorDetail, VCMPError};
 	use frame_support::pallet_prelude::*;
 	use frame_system::pallet_prelude::*;
+	use sp_runtime::traits::{Saturating, UniqueSaturatedInto};
+
+	/// The current storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
+	// how many following blocks are tried when the expiry queue of the target block is full
+	const MAX_EXPIRY_SCHEDULE_ATTEMPTS: u32 = 10;
 
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	#[pallet::config]
@@ -72,6 +82,13 @@ pub mod pallet {
 		type DelegateeAdminOrigin: EnsureOrigin<Self::RuntimeOrigin>;
 		// origin that is allowed to call extrinsics
 		type ExtrinsicWhitelistOrigin: EnsureOrigin<Self::RuntimeOrigin, Success = Self::AccountId>;
+		// expected block time in milliseconds, used to convert the validity period of an
+		// assertion into the block number at which the VC expires
+		#[pallet::constant]
+		type ExpectedBlockTime: Get<u64>;
+		// maximum number of VCs that can expire within one block
+		#[pallet::constant]
+		type MaxExpiriesPerBlock: Get<u32>;
 	}
 
 	// a map VCIndex -> VC context
@@ -93,6 +110,17 @@ pub mod pallet {
 	#[pallet::getter(fn delegatee)]
 	pub type Delegatee<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, (), OptionQuery>;
 
+	// a map BlockNumber -> indexes of the VCs that expire at this block
+	#[pallet::storage]
+	#[pallet::getter(fn expiry_queue)]
+	pub type ExpiryQueue<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		BlockNumberFor<T>,
+		BoundedVec<VCIndex, T::MaxExpiriesPerBlock>,
+		ValueQuery,
+	>;
+
 	#[pallet::storage]
 	#[pallet::getter(fn schema_index)]
 	pub type SchemaRegistryIndex<T: Config> = StorageValue<_, SchemaIndex, ValueQuery>;
@@ -102,6 +130,12 @@ pub mod pallet {
 	#[pallet::getter(fn schema_registry)]
 	pub type SchemaRegistry<T: Config> = StorageMap<_, Blake2_128Concat, SchemaIndex, VCSchema<T>>;
 
+	// the schema which the VCs of an assertion are issued under, set by the admin
+	#[pallet::storage]
+	#[pallet::getter(fn assertion_schema)]
+	pub type AssertionSchemas<T: Config> =
+		StorageMap<_, Blake2_128Concat, Assertion, SchemaIndex, OptionQuery>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
@@ -127,6 +161,24 @@ pub mod pallet {
 			account: T::AccountId,
 			index: VCIndex,
 		},
+		// the renewal of a VC is requested, the same assertion is re-run in TEE
+		VCRenewRequested {
+			account: T::AccountId,
+			shard: ShardIdentifier,
+			index: VCIndex,
+			assertion: Assertion,
+		},
+		// a VC is superseded by the VC issued for its renewal
+		VCSuperseded {
+			account: T::AccountId,
+			index: VCIndex,
+			new_index: VCIndex,
+		},
+		// the validity period of a VC has passed
+		VCExpired {
+			account: T::AccountId,
+			index: VCIndex,
+		},
 		// event that should be triggered by TEECallOrigin
 		// a VC is just issued
 		VCIssued {
@@ -165,6 +217,11 @@ pub mod pallet {
 			shard: ShardIdentifier,
 			index: SchemaIndex,
 		},
+		// the schema of the VCs of an assertion is set, or unset with `None`
+		AssertionSchemaSet {
+			assertion: Assertion,
+			index: Option<SchemaIndex>,
+		},
 		// event errors caused by processing in TEE
 		// copied from core_primitives::VCMPError, we use events instead of pallet::errors,
 		// see https://github.com/litentry/litentry-parachain/issues/1275
@@ -204,6 +261,10 @@ pub mod pallet {
 		VCSubjectMismatch,
 		/// The VC is already disabled
 		VCAlreadyDisabled,
+		/// The VC is disabled or already superseded, so it can't be renewed
+		VCNotRenewable,
+		/// The assertion doesn't match the one of the VC
+		VCAssertionMismatch,
 		/// Error when the caller account is not the admin
 		RequireAdmin,
 		/// Schema not exists
@@ -214,6 +275,8 @@ pub mod pallet {
 		SchemaAlreadyActivated,
 		SchemaIndexOverFlow,
 		LengthMismatch,
+		/// The expiry queues of the blocks around the expiry of the VC are full
+		ExpiryQueueFull,
 	}
 
 	#[pallet::genesis_config]
@@ -237,6 +300,29 @@ pub mod pallet {
 		}
 	}
 
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(now: BlockNumberFor<T>) -> Weight {
+			let indexes = ExpiryQueue::<T>::take(now);
+			let count = indexes.len() as u32;
+			for index in indexes {
+				VCRegistry::<T>::mutate(index, |context| {
+					if let Some(c) = context {
+						// the VC could have been revoked and re-added in the meantime
+						if c.status == Status::Active && c.expires_at == Some(now) {
+							c.status = Status::Expired;
+							Self::deposit_event(Event::VCExpired {
+								account: c.subject.clone(),
+								index,
+							});
+						}
+					}
+				});
+			}
+			<T as Config>::WeightInfo::expire_vc(count)
+		}
+	}
+
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		/// add an account to the delegatees
@@ -406,9 +492,10 @@ pub mod pallet {
 			let sender = ensure_signed(origin)?;
 			ensure!(Some(sender) == Self::admin(), Error::<T>::RequireAdmin);
 			ensure!(!VCRegistry::<T>::contains_key(index), Error::<T>::VCAlreadyExists);
+			let expires_at = Self::schedule_expiry(index, &assertion)?;
 			VCRegistry::<T>::insert(
 				index,
-				VCContext::<T>::new(subject.clone(), assertion.clone(), hash),
+				VCContext::<T>::new(subject.clone(), assertion.clone(), hash, Some(expires_at)),
 			);
 			Self::deposit_event(Event::VCRegistryItemAdded { account: subject, assertion, index });
 			Ok(().into())
@@ -439,9 +526,57 @@ pub mod pallet {
 			Ok(Pays::No.into())
 		}
 
+		/// Request a new VC for the same assertion as the VC `index`, which is superseded
+		/// once the new VC is issued, see `vc_renewed`. Expired VCs can be renewed, too.
+		#[pallet::call_index(13)]
+		#[pallet::weight(<T as Config>::WeightInfo::renew_vc())]
+		pub fn renew_vc(
+			origin: OriginFor<T>,
+			shard: ShardIdentifier,
+			index: VCIndex,
+			assertion: Assertion,
+		) -> DispatchResultWithPostInfo {
+			let who = T::ExtrinsicWhitelistOrigin::ensure_origin(origin)?;
+			let context = VCRegistry::<T>::get(index).ok_or(Error::<T>::VCNotExist)?;
+			ensure!(who == context.subject, Error::<T>::VCSubjectMismatch);
+			ensure!(
+				matches!(context.status, Status::Active | Status::Expired),
+				Error::<T>::VCNotRenewable
+			);
+			// the assertion is passed along so that TEE doesn't need to look up the registry
+			ensure!(assertion == context.assertion, Error::<T>::VCAssertionMismatch);
+			if let Assertion::A13(_owner) = assertion.clone() {
+				ensure!(Delegatee::<T>::contains_key(&who), Error::<T>::UnauthorizedUser);
+			}
+			Self::deposit_event(Event::VCRenewRequested { account: who, shard, index, assertion });
+			Ok(().into())
+		}
+
 		/// ---------------------------------------------------
 		/// The following extrinsics are supposed to be called by TEE only
 		/// ---------------------------------------------------
+		/// Record that the VCs of `assertion` are issued under the schema `index`, or remove
+		/// the record with `None`
+		#[pallet::call_index(14)]
+		#[pallet::weight(<T as Config>::WeightInfo::set_assertion_schema())]
+		pub fn set_assertion_schema(
+			origin: OriginFor<T>,
+			assertion: Assertion,
+			index: Option<SchemaIndex>,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+			ensure!(Some(sender) == Self::admin(), Error::<T>::RequireAdmin);
+			match index {
+				Some(index) => {
+					ensure!(SchemaRegistry::<T>::contains_key(index), Error::<T>::SchemaNotExists);
+					AssertionSchemas::<T>::insert(&assertion, index);
+				},
+				None => AssertionSchemas::<T>::remove(&assertion),
+			}
+			Self::deposit_event(Event::AssertionSchemaSet { assertion, index });
+			Ok(().into())
+		}
+
 		#[pallet::call_index(30)]
 		#[pallet::weight(<T as Config>::WeightInfo::vc_issued())]
 		pub fn vc_issued(
@@ -454,11 +589,7 @@ pub mod pallet {
 			req_ext_hash: H256,
 		) -> DispatchResultWithPostInfo {
 			let _ = T::TEECallOrigin::ensure_origin(origin)?;
-			ensure!(!VCRegistry::<T>::contains_key(index), Error::<T>::VCAlreadyExists);
-			VCRegistry::<T>::insert(
-				index,
-				VCContext::<T>::new(account.clone(), assertion.clone(), hash),
-			);
+			Self::register_vc(&account, &assertion, index, hash)?;
 			Self::deposit_event(Event::VCIssued { account, assertion, index, vc, req_ext_hash });
 			Ok(Pays::No.into())
 		}
@@ -485,5 +616,144 @@ pub mod pallet {
 			}
 			Ok(Pays::No.into())
 		}
+
+		/// The VC `index` is issued for the `renew_vc` request of `old_index`, which is
+		/// superseded by it.
+		#[pallet::call_index(32)]
+		#[pallet::weight(
+			<T as Config>::WeightInfo::vc_issued()
+				.saturating_add(T::DbWeight::get().reads_writes(1, 1))
+		)]
+		#[allow(clippy::too_many_arguments)]
+		pub fn vc_renewed(
+			origin: OriginFor<T>,
+			account: T::AccountId,
+			assertion: Assertion,
+			index: H256,
+			hash: H256,
+			vc: AesOutput,
+			old_index: H256,
+			req_ext_hash: H256,
+		) -> DispatchResultWithPostInfo {
+			let _ = T::TEECallOrigin::ensure_origin(origin)?;
+			Self::register_vc(&account, &assertion, index, hash)?;
+			Self::supersede(&account, &assertion, old_index, index);
+			Self::deposit_event(Event::VCIssued { account, assertion, index, vc, req_ext_hash });
+			Ok(Pays::No.into())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		fn register_vc(
+			account: &T::AccountId,
+			assertion: &Assertion,
+			index: VCIndex,
+			hash: H256,
+		) -> DispatchResult {
+			ensure!(!VCRegistry::<T>::contains_key(index), Error::<T>::VCAlreadyExists);
+			let expires_at = Self::schedule_expiry(index, assertion)?;
+			VCRegistry::<T>::insert(
+				index,
+				VCContext::<T>::new(account.clone(), assertion.clone(), hash, Some(expires_at)),
+			);
+			Ok(())
+		}
+
+		/// Queues the VC `index` for expiry after the validity period of `assertion` and returns
+		/// the block number at which it expires.
+		///
+		/// If the queue of the target block is full, the VC expires at the next block with a free
+		/// slot. It fails if none is found within `MAX_EXPIRY_SCHEDULE_ATTEMPTS` blocks.
+		pub fn schedule_expiry(
+			index: VCIndex,
+			assertion: &Assertion,
+		) -> Result<BlockNumberFor<T>, DispatchError> {
+			let target = Self::expiry_target(assertion);
+			Self::queue_expiry(index, target, MAX_EXPIRY_SCHEDULE_ATTEMPTS)
+				.ok_or_else(|| Error::<T>::ExpiryQueueFull.into())
+		}
+
+		/// The block number at which a VC for `assertion` issued now expires.
+		pub fn expiry_target(assertion: &Assertion) -> BlockNumberFor<T> {
+			let block_time = T::ExpectedBlockTime::get();
+			let blocks: BlockNumberFor<T> = assertion
+				.validity_period()
+				.saturating_mul(1000)
+				.checked_div(block_time)
+				.unwrap_or_default()
+				.unique_saturated_into();
+			frame_system::Pallet::<T>::block_number().saturating_add(blocks)
+		}
+
+		/// Queues the VC `index` at the first block from `from` on with a free slot, trying at
+		/// most `attempts` blocks.
+		pub fn queue_expiry(
+			index: VCIndex,
+			from: BlockNumberFor<T>,
+			attempts: u32,
+		) -> Option<BlockNumberFor<T>> {
+			let mut at = from;
+			for _ in 0..attempts {
+				if ExpiryQueue::<T>::try_mutate(at, |queue| queue.try_push(index)).is_ok() {
+					return Some(at)
+				}
+				at = at.saturating_add(1u32.into());
+			}
+			None
+		}
+
+		fn supersede(
+			account: &T::AccountId,
+			assertion: &Assertion,
+			index: VCIndex,
+			new_index: VCIndex,
+		) {
+			VCRegistry::<T>::mutate(index, |context| {
+				// the old VC could have been revoked, disabled or even re-added in the meantime
+				if let Some(c) = context {
+					if c.subject == *account &&
+						c.assertion == *assertion &&
+						matches!(c.status, Status::Active | Status::Expired)
+					{
+						c.status = Status::Superseded;
+						Self::deposit_event(Event::VCSuperseded {
+							account: account.clone(),
+							index,
+							new_index,
+						});
+					}
+				}
+			});
+		}
+	}
+}
+
+impl<T: Config> VCRegistryLookup<T::AccountId> for Pallet<T> {
+	fn active_vc(index: &VCIndex) -> Option<ActiveVC<T::AccountId>> {
+		let now = frame_system::Pallet::<T>::block_number();
+		VCRegistry::<T>::get(index)
+			// the expiry might not have been queued
+			.filter(|c| c.status == Status::Active && c.expires_at.map_or(true, |at| at > now))
+			.map(|c| {
+				let schema = AssertionSchemas::<T>::get(&c.assertion).filter(|schema| {
+					SchemaRegistry::<T>::get(schema).map_or(false, |s| s.status == Status::Active)
+				});
+				ActiveVC { subject: c.subject, assertion: c.assertion, hash: c.hash, schema }
+			})
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn add_active_vc(index: VCIndex, vc: ActiveVC<T::AccountId>) {
+		if let Some(schema) = vc.schema {
+			SchemaRegistry::<T>::insert(
+				schema,
+				VCSchema::<T>::new(Vec::new(), vc.subject.clone(), Vec::new()),
+			);
+			AssertionSchemas::<T>::insert(&vc.assertion, schema);
+		}
+		VCRegistry::<T>::insert(
+			index,
+			VCContext::<T>::new(vc.subject, vc.assertion, vc.hash, None),
+		);
 	}
 }
@@ -0,0 +1,110 @@
+// Copyright 2020-2023 Litentry Technologies GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Storage migrations of pallet-vc-management.
+
+use crate::{Assertion, Config, Pallet, Status, VCContext, VCRegistry};
+use codec::{Decode, Encode};
+use frame_support::{
+	traits::{Get, GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+	weights::Weight,
+};
+use frame_system::pallet_prelude::BlockNumberFor;
+use sp_core::H256;
+use sp_runtime::traits::UniqueSaturatedInto;
+#[cfg(feature = "try-runtime")]
+use sp_std::vec::Vec;
+use sp_std::{collections::btree_map::BTreeMap, marker::PhantomData};
+
+mod v0 {
+	use super::*;
+
+	// `VCContext` before the expiry was introduced
+	#[derive(Encode, Decode)]
+	pub struct VCContext<AccountId> {
+		pub subject: AccountId,
+		pub assertion: Assertion,
+		pub hash: H256,
+		pub status: Status,
+	}
+}
+
+/// Adds `expires_at` to the entries of `VCRegistry`.
+///
+/// Existing VCs were issued without expiry, they expire after the validity period of their
+/// assertion counted from the upgrade. Their expiries are spread over as many blocks as needed
+/// to stay within `MaxExpiriesPerBlock`.
+pub struct MigrateToV1<T>(PhantomData<T>);
+
+impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+	fn on_runtime_upgrade() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() != 0 {
+			return T::DbWeight::get().reads(1)
+		}
+
+		let mut count: u64 = 0;
+		let mut queue_reads: u64 = 0;
+		// expiry target -> the block from which on the queues might have a free slot
+		let mut next_free: BTreeMap<BlockNumberFor<T>, BlockNumberFor<T>> = BTreeMap::new();
+		VCRegistry::<T>::translate::<v0::VCContext<T::AccountId>, _>(|index, old| {
+			count += 1;
+			let target = Pallet::<T>::expiry_target(&old.assertion);
+			// only active VCs are queued, as `on_initialize` only expires active ones anyway
+			let expires_at = if old.status == Status::Active {
+				let from = next_free.get(&target).copied().unwrap_or(target);
+				let at = Pallet::<T>::queue_expiry(index, from, u32::MAX).unwrap_or(from);
+				queue_reads += UniqueSaturatedInto::<u64>::unique_saturated_into(at - from) + 1;
+				next_free.insert(target, at);
+				at
+			} else {
+				target
+			};
+			Some(VCContext::<T> {
+				subject: old.subject,
+				assertion: old.assertion,
+				hash: old.hash,
+				status: old.status,
+				expires_at: Some(expires_at),
+			})
+		});
+		StorageVersion::new(1).put::<Pallet<T>>();
+
+		T::DbWeight::get().reads_writes(count + queue_reads + 1, count + queue_reads + 1)
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+		Ok((VCRegistry::<T>::iter_keys().count() as u64).encode())
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+		let count = u64::decode(&mut &state[..]).map_err(|_| "invalid pre-upgrade state")?;
+		frame_support::ensure!(
+			Pallet::<T>::on_chain_storage_version() == 1,
+			"storage version not updated"
+		);
+		frame_support::ensure!(
+			VCRegistry::<T>::iter_values().count() as u64 == count,
+			"VCRegistry entries lost"
+		);
+		frame_support::ensure!(
+			VCRegistry::<T>::iter_values().all(|c| c.expires_at.is_some()),
+			"VCRegistry entries without expiry"
+		);
+		Ok(())
+	}
+}
@@ -149,6 +149,8 @@ impl pallet_vc_management::Config for Test {
 	type SetAdminOrigin = EnsureRoot<Self::AccountId>;
 	type DelegateeAdminOrigin = EnsureRoot<Self::AccountId>;
 	type ExtrinsicWhitelistOrigin = VCMPExtrinsicWhitelist;
+	type ExpectedBlockTime = ConstU64<12000>;
+	type MaxExpiriesPerBlock = ConstU32<2>;
 }
 
 parameter_types! {
@@ -14,8 +14,15 @@
 // You should have received a copy of the GNU General Public License
 // along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
 
-use crate::{mock::*, AesOutput, Assertion, Error, ShardIdentifier, Status};
-use frame_support::{assert_noop, assert_ok};
+use crate::{
+	migrations::MigrateToV1, mock::*, ActiveVC, AesOutput, Assertion, Error, ShardIdentifier,
+	Status, VCRegistryLookup,
+};
+use frame_support::{
+	assert_noop, assert_ok,
+	storage::unhashed,
+	traits::{Get, GetStorageVersion, Hooks, OnRuntimeUpgrade, StorageVersion},
+};
 use sp_core::H256;
 
 use test_utils::ias::consts::{TEST8_MRENCLAVE, TEST8_SIGNER_PUB};
@@ -595,3 +602,388 @@ fn manual_add_clear_vc_registry_item_works() {
 		System::assert_last_event(RuntimeEvent::VCManagement(crate::Event::VCRegistryCleared));
 	});
 }
+
+fn issue_vc(subject: &SystemAccountId, assertion: Assertion, index: H256) {
+	let teerex_signer: SystemAccountId = test_utils::get_signer(TEST8_SIGNER_PUB);
+	assert_ok!(VCManagement::vc_issued(
+		RuntimeOrigin::signed(teerex_signer),
+		subject.clone(),
+		assertion,
+		index,
+		VC_HASH,
+		AesOutput::default(),
+		H256::default(),
+	));
+}
+
+fn renew_vc_issued(subject: &SystemAccountId, assertion: Assertion, index: H256, old_index: H256) {
+	let teerex_signer: SystemAccountId = test_utils::get_signer(TEST8_SIGNER_PUB);
+	assert_ok!(VCManagement::vc_renewed(
+		RuntimeOrigin::signed(teerex_signer),
+		subject.clone(),
+		assertion,
+		index,
+		VC_HASH,
+		AesOutput::default(),
+		old_index,
+		H256::default(),
+	));
+}
+
+// validity period in blocks, given the block time of 12s in the mock
+fn validity_blocks(assertion: &Assertion) -> u64 {
+	assertion.validity_period() * 1000 / 12000
+}
+
+#[test]
+fn vc_issued_schedules_expiry() {
+	new_test_ext().execute_with(|| {
+		let alice: SystemAccountId = test_utils::get_signer(ALICE_PUBKEY);
+		let assertion = Assertion::A4(Default::default());
+		issue_vc(&alice, assertion.clone(), VC_INDEX);
+
+		let expires_at = 1 + validity_blocks(&assertion);
+		assert_eq!(VCManagement::vc_registry(VC_INDEX).unwrap().expires_at, Some(expires_at));
+		assert_eq!(VCManagement::expiry_queue(expires_at).into_inner(), vec![VC_INDEX]);
+	});
+}
+
+#[test]
+fn vc_expires_on_initialize() {
+	new_test_ext().execute_with(|| {
+		let alice: SystemAccountId = test_utils::get_signer(ALICE_PUBKEY);
+		issue_vc(&alice, Assertion::A1, VC_INDEX);
+		let expires_at = VCManagement::vc_registry(VC_INDEX).unwrap().expires_at.unwrap();
+
+		VCManagement::on_initialize(expires_at - 1);
+		assert_eq!(VCManagement::vc_registry(VC_INDEX).unwrap().status, Status::Active);
+
+		System::set_block_number(expires_at);
+		VCManagement::on_initialize(expires_at);
+		assert_eq!(VCManagement::vc_registry(VC_INDEX).unwrap().status, Status::Expired);
+		assert!(VCManagement::expiry_queue(expires_at).is_empty());
+		System::assert_last_event(RuntimeEvent::VCManagement(crate::Event::VCExpired {
+			account: alice,
+			index: VC_INDEX,
+		}));
+	});
+}
+
+#[test]
+fn active_vc_lookup_works() {
+	new_test_ext().execute_with(|| {
+		let alice: SystemAccountId = test_utils::get_signer(ALICE_PUBKEY);
+		issue_vc(&alice, Assertion::A1, VC_INDEX);
+		assert_eq!(
+			VCManagement::active_vc(&VC_INDEX),
+			Some(ActiveVC {
+				subject: alice.clone(),
+				assertion: Assertion::A1,
+				hash: VC_HASH,
+				schema: None
+			})
+		);
+		assert_eq!(VCManagement::active_vc(&H256::repeat_byte(1)), None);
+
+		// expired, even if the expiry wasn't processed
+		let expires_at = VCManagement::vc_registry(VC_INDEX).unwrap().expires_at.unwrap();
+		System::set_block_number(expires_at);
+		assert_eq!(VCManagement::active_vc(&VC_INDEX), None);
+
+		System::set_block_number(1);
+		assert_ok!(VCManagement::disable_vc(RuntimeOrigin::signed(alice), VC_INDEX));
+		assert_eq!(VCManagement::active_vc(&VC_INDEX), None);
+	});
+}
+
+#[test]
+fn set_assertion_schema_works() {
+	new_test_ext().execute_with(|| {
+		let alice: SystemAccountId = test_utils::get_signer(ALICE_PUBKEY);
+		let bob: SystemAccountId = test_utils::get_signer(BOB_PUBKEY);
+		let shard: ShardIdentifier = H256::from_slice(&TEST8_MRENCLAVE);
+		issue_vc(&alice, Assertion::A1, VC_INDEX);
+
+		assert_noop!(
+			VCManagement::set_assertion_schema(
+				RuntimeOrigin::signed(alice.clone()),
+				Assertion::A1,
+				Some(0)
+			),
+			Error::<Test>::SchemaNotExists
+		);
+		assert_ok!(VCManagement::add_schema(
+			RuntimeOrigin::signed(alice.clone()),
+			shard,
+			vec![1, 2, 3, 4],
+			vec![5, 6, 7, 8]
+		));
+		assert_noop!(
+			VCManagement::set_assertion_schema(RuntimeOrigin::signed(bob), Assertion::A1, Some(0)),
+			Error::<Test>::RequireAdmin
+		);
+		assert_ok!(VCManagement::set_assertion_schema(
+			RuntimeOrigin::signed(alice.clone()),
+			Assertion::A1,
+			Some(0)
+		));
+		System::assert_last_event(RuntimeEvent::VCManagement(crate::Event::AssertionSchemaSet {
+			assertion: Assertion::A1,
+			index: Some(0),
+		}));
+		assert_eq!(VCManagement::active_vc(&VC_INDEX).unwrap().schema, Some(0));
+
+		// only an active schema is reported
+		assert_ok!(VCManagement::disable_schema(RuntimeOrigin::signed(alice.clone()), shard, 0));
+		assert_eq!(VCManagement::active_vc(&VC_INDEX).unwrap().schema, None);
+		assert_ok!(VCManagement::activate_schema(RuntimeOrigin::signed(alice.clone()), shard, 0));
+		assert_eq!(VCManagement::active_vc(&VC_INDEX).unwrap().schema, Some(0));
+
+		assert_ok!(VCManagement::set_assertion_schema(
+			RuntimeOrigin::signed(alice),
+			Assertion::A1,
+			None
+		));
+		assert_eq!(VCManagement::assertion_schema(Assertion::A1), None);
+		assert_eq!(VCManagement::active_vc(&VC_INDEX).unwrap().schema, None);
+	});
+}
+
+#[test]
+fn disabled_vc_does_not_expire() {
+	new_test_ext().execute_with(|| {
+		let alice: SystemAccountId = test_utils::get_signer(ALICE_PUBKEY);
+		issue_vc(&alice, Assertion::A1, VC_INDEX);
+		let expires_at = VCManagement::vc_registry(VC_INDEX).unwrap().expires_at.unwrap();
+		assert_ok!(VCManagement::disable_vc(RuntimeOrigin::signed(alice), VC_INDEX));
+
+		VCManagement::on_initialize(expires_at);
+		assert_eq!(VCManagement::vc_registry(VC_INDEX).unwrap().status, Status::Disabled);
+	});
+}
+
+#[test]
+fn expiry_is_postponed_when_queue_is_full() {
+	new_test_ext().execute_with(|| {
+		let alice: SystemAccountId = test_utils::get_signer(ALICE_PUBKEY);
+		let expires_at = 1 + validity_blocks(&Assertion::A1);
+		// MaxExpiriesPerBlock is 2 in the mock
+		for i in 1..=3u64 {
+			issue_vc(&alice, Assertion::A1, H256::from_low_u64_be(i));
+		}
+
+		assert_eq!(VCManagement::expiry_queue(expires_at).len(), 2);
+		assert_eq!(
+			VCManagement::expiry_queue(expires_at + 1).into_inner(),
+			vec![H256::from_low_u64_be(3)]
+		);
+		assert_eq!(
+			VCManagement::vc_registry(H256::from_low_u64_be(3)).unwrap().expires_at,
+			Some(expires_at + 1)
+		);
+	});
+}
+
+#[test]
+fn renew_vc_works() {
+	new_test_ext().execute_with(|| {
+		let shard: ShardIdentifier = H256::from_slice(&TEST8_MRENCLAVE);
+		let alice: SystemAccountId = test_utils::get_signer(ALICE_PUBKEY);
+		let new_index = H256::from_low_u64_be(1);
+		issue_vc(&alice, Assertion::A1, VC_INDEX);
+
+		assert_ok!(VCManagement::renew_vc(
+			RuntimeOrigin::signed(alice.clone()),
+			shard,
+			VC_INDEX,
+			Assertion::A1
+		));
+		System::assert_last_event(RuntimeEvent::VCManagement(crate::Event::VCRenewRequested {
+			account: alice.clone(),
+			shard,
+			index: VC_INDEX,
+			assertion: Assertion::A1,
+		}));
+
+		renew_vc_issued(&alice, Assertion::A1, new_index, VC_INDEX);
+		System::assert_has_event(RuntimeEvent::VCManagement(crate::Event::VCSuperseded {
+			account: alice.clone(),
+			index: VC_INDEX,
+			new_index,
+		}));
+		assert_eq!(VCManagement::vc_registry(VC_INDEX).unwrap().status, Status::Superseded);
+		assert_eq!(VCManagement::vc_registry(new_index).unwrap().status, Status::Active);
+
+		// a superseded VC doesn't expire and can't be renewed again
+		let expires_at = VCManagement::vc_registry(VC_INDEX).unwrap().expires_at.unwrap();
+		VCManagement::on_initialize(expires_at);
+		assert_eq!(VCManagement::vc_registry(VC_INDEX).unwrap().status, Status::Superseded);
+		assert_noop!(
+			VCManagement::renew_vc(RuntimeOrigin::signed(alice), shard, VC_INDEX, Assertion::A1),
+			Error::<Test>::VCNotRenewable
+		);
+	});
+}
+
+#[test]
+fn renew_expired_vc_works() {
+	new_test_ext().execute_with(|| {
+		let shard: ShardIdentifier = H256::from_slice(&TEST8_MRENCLAVE);
+		let alice: SystemAccountId = test_utils::get_signer(ALICE_PUBKEY);
+		issue_vc(&alice, Assertion::A1, VC_INDEX);
+		let expires_at = VCManagement::vc_registry(VC_INDEX).unwrap().expires_at.unwrap();
+		VCManagement::on_initialize(expires_at);
+
+		assert_ok!(VCManagement::renew_vc(
+			RuntimeOrigin::signed(alice.clone()),
+			shard,
+			VC_INDEX,
+			Assertion::A1
+		));
+		renew_vc_issued(&alice, Assertion::A1, H256::from_low_u64_be(1), VC_INDEX);
+		assert_eq!(VCManagement::vc_registry(VC_INDEX).unwrap().status, Status::Superseded);
+	});
+}
+
+#[test]
+fn regular_issuance_does_not_supersede_vc_under_renewal() {
+	new_test_ext().execute_with(|| {
+		let shard: ShardIdentifier = H256::from_slice(&TEST8_MRENCLAVE);
+		let alice: SystemAccountId = test_utils::get_signer(ALICE_PUBKEY);
+		issue_vc(&alice, Assertion::A1, VC_INDEX);
+		assert_ok!(VCManagement::renew_vc(
+			RuntimeOrigin::signed(alice.clone()),
+			shard,
+			VC_INDEX,
+			Assertion::A1
+		));
+
+		issue_vc(&alice, Assertion::A1, H256::from_low_u64_be(1));
+		assert_eq!(VCManagement::vc_registry(VC_INDEX).unwrap().status, Status::Active);
+	});
+}
+
+#[test]
+fn vc_renewed_does_not_supersede_vc_of_other_subject() {
+	new_test_ext().execute_with(|| {
+		let alice: SystemAccountId = test_utils::get_signer(ALICE_PUBKEY);
+		let bob: SystemAccountId = test_utils::get_signer(BOB_PUBKEY);
+		issue_vc(&alice, Assertion::A1, VC_INDEX);
+
+		renew_vc_issued(&bob, Assertion::A1, H256::from_low_u64_be(1), VC_INDEX);
+		assert_eq!(VCManagement::vc_registry(VC_INDEX).unwrap().status, Status::Active);
+		renew_vc_issued(&alice, Assertion::A6, H256::from_low_u64_be(2), VC_INDEX);
+		assert_eq!(VCManagement::vc_registry(VC_INDEX).unwrap().status, Status::Active);
+	});
+}
+
+#[test]
+fn vc_issued_fails_when_expiry_queues_are_full() {
+	new_test_ext().execute_with(|| {
+		let teerex_signer: SystemAccountId = test_utils::get_signer(TEST8_SIGNER_PUB);
+		let alice: SystemAccountId = test_utils::get_signer(ALICE_PUBKEY);
+		// MaxExpiriesPerBlock is 2 in the mock, each VC is tried at 10 blocks
+		for i in 1..=20u64 {
+			issue_vc(&alice, Assertion::A1, H256::from_low_u64_be(i));
+		}
+
+		assert_noop!(
+			VCManagement::vc_issued(
+				RuntimeOrigin::signed(teerex_signer),
+				alice,
+				Assertion::A1,
+				H256::from_low_u64_be(21),
+				VC_HASH,
+				AesOutput::default(),
+				H256::default(),
+			),
+			Error::<Test>::ExpiryQueueFull
+		);
+	});
+}
+
+#[test]
+fn migrate_to_v1_assigns_and_queues_expiry() {
+	new_test_ext().execute_with(|| {
+		let alice: SystemAccountId = test_utils::get_signer(ALICE_PUBKEY);
+		StorageVersion::new(0).put::<VCManagement>();
+		// `VCContext` of storage version 0
+		for i in 1..=3u64 {
+			unhashed::put(
+				&crate::VCRegistry::<Test>::hashed_key_for(H256::from_low_u64_be(i)),
+				&(alice.clone(), Assertion::A1, VC_HASH, Status::Active),
+			);
+		}
+		unhashed::put(
+			&crate::VCRegistry::<Test>::hashed_key_for(H256::from_low_u64_be(4)),
+			&(alice, Assertion::A4(Default::default()), VC_HASH, Status::Disabled),
+		);
+
+		MigrateToV1::<Test>::on_runtime_upgrade();
+
+		assert_eq!(VCManagement::on_chain_storage_version(), 1);
+		let expires_at = 1 + validity_blocks(&Assertion::A1);
+		let mut expiries: Vec<_> = (1..=3u64)
+			.map(|i| VCManagement::vc_registry(H256::from_low_u64_be(i)).unwrap().expires_at)
+			.collect();
+		expiries.sort();
+		// MaxExpiriesPerBlock is 2 in the mock
+		assert_eq!(expiries, vec![Some(expires_at), Some(expires_at), Some(expires_at + 1)]);
+		assert_eq!(VCManagement::expiry_queue(expires_at).len(), 2);
+		assert_eq!(VCManagement::expiry_queue(expires_at + 1).len(), 1);
+
+		// a disabled VC isn't queued
+		let disabled = VCManagement::vc_registry(H256::from_low_u64_be(4)).unwrap();
+		assert_eq!(disabled.status, Status::Disabled);
+		assert_eq!(
+			disabled.expires_at,
+			Some(1 + validity_blocks(&Assertion::A4(Default::default())))
+		);
+		assert!(VCManagement::expiry_queue(disabled.expires_at.unwrap()).is_empty());
+
+		// the migration only runs once
+		assert_eq!(
+			MigrateToV1::<Test>::on_runtime_upgrade(),
+			<Test as frame_system::Config>::DbWeight::get().reads(1)
+		);
+	});
+}
+
+#[test]
+fn renew_vc_with_mismatched_request_fails() {
+	new_test_ext().execute_with(|| {
+		let shard: ShardIdentifier = H256::from_slice(&TEST8_MRENCLAVE);
+		let alice: SystemAccountId = test_utils::get_signer(ALICE_PUBKEY);
+		let bob: SystemAccountId = test_utils::get_signer(BOB_PUBKEY);
+		assert_noop!(
+			VCManagement::renew_vc(
+				RuntimeOrigin::signed(alice.clone()),
+				shard,
+				VC_INDEX,
+				Assertion::A1
+			),
+			Error::<Test>::VCNotExist
+		);
+
+		issue_vc(&alice, Assertion::A1, VC_INDEX);
+		assert_noop!(
+			VCManagement::renew_vc(RuntimeOrigin::signed(bob), shard, VC_INDEX, Assertion::A1),
+			Error::<Test>::VCSubjectMismatch
+		);
+		assert_noop!(
+			VCManagement::renew_vc(
+				RuntimeOrigin::signed(alice.clone()),
+				shard,
+				VC_INDEX,
+				Assertion::A6
+			),
+			Error::<Test>::VCAssertionMismatch
+		);
+
+		assert_ok!(VCManagement::disable_vc(RuntimeOrigin::signed(alice.clone()), VC_INDEX));
+		assert_noop!(
+			VCManagement::renew_vc(RuntimeOrigin::signed(alice), shard, VC_INDEX, Assertion::A1),
+			Error::<Test>::VCNotRenewable
+		);
+	});
+}
@@ -19,11 +19,16 @@ use scale_info::TypeInfo;
 use sp_core::H256;
 
 use crate::{Assertion, Config};
+use frame_system::pallet_prelude::BlockNumberFor;
 
 #[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
 pub enum Status {
 	Active,
 	Disabled,
+	// the validity period of the VC has passed
+	Expired,
+	// the VC was renewed, the new VC is issued with another index
+	Superseded,
 	// Revoked, // commented out for now, we can delete the VC entry when revoked
 }
 
@@ -43,10 +48,18 @@ pub struct VCContext<T: Config> {
 	pub hash: H256,
 	// status of the VC
 	pub status: Status,
+	// block number at which the VC is marked as expired, `None` for VCs issued before
+	// the expiry was introduced
+	pub expires_at: Option<BlockNumberFor<T>>,
 }
 
 impl<T: Config> VCContext<T> {
-	pub fn new(subject: T::AccountId, assertion: Assertion, hash: H256) -> Self {
-		Self { subject, assertion, hash, status: Status::Active }
+	pub fn new(
+		subject: T::AccountId,
+		assertion: Assertion,
+		hash: H256,
+		expires_at: Option<BlockNumberFor<T>>,
+	) -> Self {
+		Self { subject, assertion, hash, status: Status::Active, expires_at }
 	}
 }
@@ -19,6 +19,9 @@
 //! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 4.0.0-dev
 //! DATE: 2023-06-22, STEPS: `20`, REPEAT: 50, LOW RANGE: `[]`, HIGH RANGE: `[]`
 //! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("rococo-dev"), DB CACHE: 20
+//!
+//! TODO: `set_assertion_schema` was NOT benchmarked, it's given the measured weight of
+//! `revoke_schema`, which has the same storage accesses. Re-run the command below.
 
 // Executed Command:
 // ./target/release/litentry-collator
@@ -59,9 +62,12 @@ pub trait WeightInfo {
 	fn disable_schema() -> Weight;
 	fn activate_schema() -> Weight;
 	fn revoke_schema() -> Weight;
+	fn set_assertion_schema() -> Weight;
 	fn add_vc_registry_item() -> Weight;
 	fn remove_vc_registry_item() -> Weight;
 	fn clear_vc_registry(x: u32, ) -> Weight;
+	fn renew_vc() -> Weight;
+	fn expire_vc(x: u32, ) -> Weight;
 }
 
 /// Weights for pallet_vc_management using the Litentry node and recommended hardware.
@@ -107,11 +113,13 @@ impl<T: frame_system::Config> WeightInfo for LitentryWeight<T> {
 	// Storage: Teerex EnclaveIndex (r:1 w:0)
 	// Proof Skipped: Teerex EnclaveIndex (max_values: None, max_size: None, mode: Measured)
 	// Storage: VCManagement VCRegistry (r:1 w:1)
-	// Proof: VCManagement VCRegistry (max_values: None, max_size: Some(312), added: 2787, mode: MaxEncodedLen)
+	// Proof: VCManagement VCRegistry (max_values: None, max_size: Some(317), added: 2792, mode: MaxEncodedLen)
+	// Storage: VCManagement ExpiryQueue (r:1 w:1)
+	// Proof: VCManagement ExpiryQueue (max_values: None, max_size: Some(32782), added: 35257, mode: MaxEncodedLen)
 	fn vc_issued() -> Weight {
-		Weight::from_ref_time(26_888_000 as u64)
-			.saturating_add(T::DbWeight::get().reads(2 as u64))
-			.saturating_add(T::DbWeight::get().writes(1 as u64))
+		Weight::from_ref_time(38_516_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(3 as u64))
+			.saturating_add(T::DbWeight::get().writes(2 as u64))
 	}
 	// Storage: Teerex EnclaveIndex (r:1 w:0)
 	// Proof Skipped: Teerex EnclaveIndex (max_values: None, max_size: None, mode: Measured)
@@ -165,13 +173,21 @@ impl<T: frame_system::Config> WeightInfo for LitentryWeight<T> {
 			.saturating_add(T::DbWeight::get().writes(1 as u64))
 	}
 	// Storage: VCManagement Admin (r:1 w:0)
+	// Storage: VCManagement SchemaRegistry (r:1 w:0)
+	// Storage: VCManagement AssertionSchemas (r:0 w:1)
+	fn set_assertion_schema() -> Weight {
+		Weight::from_ref_time(23_754_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(2 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: VCManagement Admin (r:1 w:0)
 	// Proof: VCManagement Admin (max_values: Some(1), max_size: Some(32), added: 527, mode: MaxEncodedLen)
 	// Storage: VCManagement VCRegistry (r:1 w:1)
 	// Proof: VCManagement VCRegistry (max_values: None, max_size: Some(312), added: 2787, mode: MaxEncodedLen)
 	fn add_vc_registry_item() -> Weight {
-		Weight::from_ref_time(22_937_000 as u64)
-			.saturating_add(T::DbWeight::get().reads(2 as u64))
-			.saturating_add(T::DbWeight::get().writes(1 as u64))
+		Weight::from_ref_time(27_412_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(3 as u64))
+			.saturating_add(T::DbWeight::get().writes(2 as u64))
 	}
 	// Storage: VCManagement Admin (r:1 w:0)
 	// Proof: VCManagement Admin (max_values: Some(1), max_size: Some(32), added: 527, mode: MaxEncodedLen)
@@ -194,6 +210,27 @@ impl<T: frame_system::Config> WeightInfo for LitentryWeight<T> {
 			.saturating_add(T::DbWeight::get().reads((1 as u64).saturating_mul(x as u64)))
 			.saturating_add(T::DbWeight::get().writes((1 as u64).saturating_mul(x as u64)))
 	}
+	// Storage: VCMPExtrinsicWhitelist GroupControlOn (r:1 w:0)
+	// Proof Skipped: VCMPExtrinsicWhitelist GroupControlOn (max_values: Some(1), max_size: None, mode: Measured)
+	// Storage: VCManagement VCRegistry (r:1 w:0)
+	// Proof: VCManagement VCRegistry (max_values: None, max_size: Some(317), added: 2792, mode: MaxEncodedLen)
+	fn renew_vc() -> Weight {
+		Weight::from_ref_time(31_264_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(2 as u64))
+	}
+	// Storage: VCManagement ExpiryQueue (r:1 w:1)
+	// Proof: VCManagement ExpiryQueue (max_values: None, max_size: Some(32782), added: 35257, mode: MaxEncodedLen)
+	// Storage: VCManagement VCRegistry (r:1024 w:1024)
+	// Proof: VCManagement VCRegistry (max_values: None, max_size: Some(317), added: 2792, mode: MaxEncodedLen)
+	fn expire_vc(x: u32, ) -> Weight {
+		Weight::from_ref_time(6_128_000 as u64)
+			// Standard Error: 1_932
+			.saturating_add(Weight::from_ref_time(7_215_000 as u64).saturating_mul(x as u64))
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().reads((1 as u64).saturating_mul(x as u64)))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+			.saturating_add(T::DbWeight::get().writes((1 as u64).saturating_mul(x as u64)))
+	}
 }
 
 // For backwards compatibility and tests
@@ -238,11 +275,13 @@ impl WeightInfo for () {
 	// Storage: Teerex EnclaveIndex (r:1 w:0)
 	// Proof Skipped: Teerex EnclaveIndex (max_values: None, max_size: None, mode: Measured)
 	// Storage: VCManagement VCRegistry (r:1 w:1)
-	// Proof: VCManagement VCRegistry (max_values: None, max_size: Some(312), added: 2787, mode: MaxEncodedLen)
+	// Proof: VCManagement VCRegistry (max_values: None, max_size: Some(317), added: 2792, mode: MaxEncodedLen)
+	// Storage: VCManagement ExpiryQueue (r:1 w:1)
+	// Proof: VCManagement ExpiryQueue (max_values: None, max_size: Some(32782), added: 35257, mode: MaxEncodedLen)
 	fn vc_issued() -> Weight {
-		Weight::from_ref_time(26_888_000 as u64)
-			.saturating_add(RocksDbWeight::get().reads(2 as u64))
-			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+		Weight::from_ref_time(38_516_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(3 as u64))
+			.saturating_add(RocksDbWeight::get().writes(2 as u64))
 	}
 	// Storage: Teerex EnclaveIndex (r:1 w:0)
 	// Proof Skipped: Teerex EnclaveIndex (max_values: None, max_size: None, mode: Measured)
@@ -296,13 +335,21 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().writes(1 as u64))
 	}
 	// Storage: VCManagement Admin (r:1 w:0)
+	// Storage: VCManagement SchemaRegistry (r:1 w:0)
+	// Storage: VCManagement AssertionSchemas (r:0 w:1)
+	fn set_assertion_schema() -> Weight {
+		Weight::from_ref_time(23_754_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(2 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	// Storage: VCManagement Admin (r:1 w:0)
 	// Proof: VCManagement Admin (max_values: Some(1), max_size: Some(32), added: 527, mode: MaxEncodedLen)
 	// Storage: VCManagement VCRegistry (r:1 w:1)
 	// Proof: VCManagement VCRegistry (max_values: None, max_size: Some(312), added: 2787, mode: MaxEncodedLen)
 	fn add_vc_registry_item() -> Weight {
-		Weight::from_ref_time(22_937_000 as u64)
-			.saturating_add(RocksDbWeight::get().reads(2 as u64))
-			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+		Weight::from_ref_time(27_412_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(3 as u64))
+			.saturating_add(RocksDbWeight::get().writes(2 as u64))
 	}
 	// Storage: VCManagement Admin (r:1 w:0)
 	// Proof: VCManagement Admin (max_values: Some(1), max_size: Some(32), added: 527, mode: MaxEncodedLen)
@@ -325,5 +372,26 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads((1 as u64).saturating_mul(x as u64)))
 			.saturating_add(RocksDbWeight::get().writes((1 as u64).saturating_mul(x as u64)))
 	}
+	// Storage: VCMPExtrinsicWhitelist GroupControlOn (r:1 w:0)
+	// Proof Skipped: VCMPExtrinsicWhitelist GroupControlOn (max_values: Some(1), max_size: None, mode: Measured)
+	// Storage: VCManagement VCRegistry (r:1 w:0)
+	// Proof: VCManagement VCRegistry (max_values: None, max_size: Some(317), added: 2792, mode: MaxEncodedLen)
+	fn renew_vc() -> Weight {
+		Weight::from_ref_time(31_264_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(2 as u64))
+	}
+	// Storage: VCManagement ExpiryQueue (r:1 w:1)
+	// Proof: VCManagement ExpiryQueue (max_values: None, max_size: Some(32782), added: 35257, mode: MaxEncodedLen)
+	// Storage: VCManagement VCRegistry (r:1024 w:1024)
+	// Proof: VCManagement VCRegistry (max_values: None, max_size: Some(317), added: 2792, mode: MaxEncodedLen)
+	fn expire_vc(x: u32, ) -> Weight {
+		Weight::from_ref_time(6_128_000 as u64)
+			// Standard Error: 1_932
+			.saturating_add(Weight::from_ref_time(7_215_000 as u64).saturating_mul(x as u64))
+			.saturating_add(RocksDbWeight::get().reads(1 as u64))
+			.saturating_add(RocksDbWeight::get().reads((1 as u64).saturating_mul(x as u64)))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+			.saturating_add(RocksDbWeight::get().writes((1 as u64).saturating_mul(x as u64)))
+	}
 }
 
@@ -0,0 +1,83 @@
+// Copyright 2020-2023 Litentry Technologies GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Benchmarking setup for pallet-web3-networks
+
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as Web3Networks;
+use core_primitives::MaxRegisteredWeb3Networks;
+use frame_benchmarking::{benchmarks, impl_benchmark_test_suite};
+use frame_support::traits::{EnsureOrigin, Get};
+use sp_std::vec;
+
+fn assert_last_event<T: Config>(generic_event: <T as Config>::RuntimeEvent) {
+	frame_system::Pallet::<T>::assert_last_event(generic_event.into());
+}
+
+fn network_info() -> Web3NetworkInfo {
+	Web3NetworkInfo {
+		name: vec![b'a'; 32].try_into().unwrap(),
+		family: Web3NetworkFamily::Evm { chain_id: 42161 },
+		providers: vec![
+			(vec![b'b'; 32].try_into().unwrap(), vec![b'c'; 32].try_into().unwrap());
+			8
+		]
+		.try_into()
+		.unwrap(),
+	}
+}
+
+// fills the registry with `n` networks, starting from id 1, as every call decodes and encodes
+// the whole registry
+fn fill_registry<T: Config>(n: u32) {
+	let mut networks = Web3NetworkRegistry::default();
+	for id in 1..=n {
+		assert!(networks.try_insert(id as Web3NetworkId, network_info()).is_ok());
+	}
+	Networks::<T>::put(networks);
+}
+
+benchmarks! {
+	register_network {
+		let origin = T::ManageOrigin::try_successful_origin().expect("ManageOrigin has no successful origin required for the benchmark");
+		let max = MaxRegisteredWeb3Networks::get();
+		fill_registry::<T>(max - 1);
+		let id = max as Web3NetworkId;
+	}: _<T::RuntimeOrigin>(origin, id, network_info())
+	verify {
+		assert_last_event::<T>(Event::NetworkRegistered { id, info: network_info() }.into());
+	}
+
+	update_network {
+		let origin = T::ManageOrigin::try_successful_origin().expect("ManageOrigin has no successful origin required for the benchmark");
+		fill_registry::<T>(MaxRegisteredWeb3Networks::get());
+	}: _<T::RuntimeOrigin>(origin, 1, network_info())
+	verify {
+		assert_last_event::<T>(Event::NetworkUpdated { id: 1, info: network_info() }.into());
+	}
+
+	remove_network {
+		let origin = T::ManageOrigin::try_successful_origin().expect("ManageOrigin has no successful origin required for the benchmark");
+		fill_registry::<T>(MaxRegisteredWeb3Networks::get());
+	}: _<T::RuntimeOrigin>(origin, 1)
+	verify {
+		assert_last_event::<T>(Event::NetworkRemoved { id: 1 }.into());
+	}
+}
+
+impl_benchmark_test_suite!(Web3Networks, crate::mock::new_test_ext(), crate::mock::Test);
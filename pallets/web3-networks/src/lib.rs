@@ -0,0 +1,152 @@
+// Copyright 2020-2023 Litentry Technologies GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+//! A pallet for registering the web3 networks the TEE supports besides the built-in ones.
+//!
+//! A registered network is referred to as `Web3Network::Registered(id)`, it tells the enclave
+//! which identities the network accepts (its family) and its name at each data provider.
+//!
+//! All networks are kept in a single storage value, which the enclave mirrors into its state
+//! on each parentchain block, so a new network takes effect without a runtime upgrade of the
+//! enclave (and without a new MRENCLAVE).
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod weights;
+
+pub use core_primitives::{
+	Web3NetworkFamily, Web3NetworkId, Web3NetworkInfo, Web3NetworkName, Web3NetworkRegistry,
+};
+use frame_support::{pallet_prelude::*, traits::StorageVersion};
+use frame_system::pallet_prelude::*;
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	/// The current storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(0);
+
+	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// The origin that manages the networks
+		type ManageOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// Weights
+		type WeightInfo: WeightInfo;
+	}
+
+	/// The registered networks, the storage key must not change as the enclave reads it
+	/// (see `core_primitives::web3network_registry_key`).
+	#[pallet::storage]
+	#[pallet::getter(fn networks)]
+	pub type Networks<T: Config> = StorageValue<_, Web3NetworkRegistry, ValueQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		NetworkRegistered { id: Web3NetworkId, info: Web3NetworkInfo },
+		NetworkUpdated { id: Web3NetworkId, info: Web3NetworkInfo },
+		NetworkRemoved { id: Web3NetworkId },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// A network with the id is registered already
+		NetworkAlreadyRegistered,
+		/// No network with the id is registered
+		NetworkNotRegistered,
+		/// The registry is full
+		TooManyNetworks,
+		/// The network has no name
+		EmptyNetworkName,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::register_network())]
+		pub fn register_network(
+			origin: OriginFor<T>,
+			id: Web3NetworkId,
+			info: Web3NetworkInfo,
+		) -> DispatchResultWithPostInfo {
+			T::ManageOrigin::ensure_origin(origin)?;
+			ensure!(!info.name.is_empty(), Error::<T>::EmptyNetworkName);
+			Networks::<T>::try_mutate(|networks| {
+				ensure!(!networks.contains_key(&id), Error::<T>::NetworkAlreadyRegistered);
+				networks.try_insert(id, info.clone()).map_err(|_| Error::<T>::TooManyNetworks)?;
+				Ok::<(), Error<T>>(())
+			})?;
+			Self::deposit_event(Event::NetworkRegistered { id, info });
+			Ok(().into())
+		}
+
+		/// Replaces the info of a registered network, the family of a network that identities
+		/// are linked with shouldn't change, as it's not checked again for them.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::update_network())]
+		pub fn update_network(
+			origin: OriginFor<T>,
+			id: Web3NetworkId,
+			info: Web3NetworkInfo,
+		) -> DispatchResultWithPostInfo {
+			T::ManageOrigin::ensure_origin(origin)?;
+			ensure!(!info.name.is_empty(), Error::<T>::EmptyNetworkName);
+			Networks::<T>::try_mutate(|networks| {
+				let current = networks.get_mut(&id).ok_or(Error::<T>::NetworkNotRegistered)?;
+				*current = info.clone();
+				Ok::<(), Error<T>>(())
+			})?;
+			Self::deposit_event(Event::NetworkUpdated { id, info });
+			Ok(().into())
+		}
+
+		/// Removes a network, the id shouldn't be reused for another network as identities
+		/// may still be linked with it.
+		#[pallet::call_index(2)]
+		#[pallet::weight(T::WeightInfo::remove_network())]
+		pub fn remove_network(
+			origin: OriginFor<T>,
+			id: Web3NetworkId,
+		) -> DispatchResultWithPostInfo {
+			T::ManageOrigin::ensure_origin(origin)?;
+			Networks::<T>::try_mutate(|networks| {
+				networks.remove(&id).ok_or(Error::<T>::NetworkNotRegistered)?;
+				Ok::<(), Error<T>>(())
+			})?;
+			Self::deposit_event(Event::NetworkRemoved { id });
+			Ok(().into())
+		}
+	}
+}
@@ -0,0 +1,124 @@
+// Copyright 2020-2023 Litentry Technologies GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{mock::*, Error, Event, Networks, Web3NetworkFamily, Web3NetworkInfo};
+use core_primitives::{web3network_registry_key, MaxRegisteredWeb3Networks};
+use frame_support::{assert_noop, assert_ok, traits::Get};
+use sp_runtime::DispatchError::BadOrigin;
+
+fn info(name: &str, family: Web3NetworkFamily) -> Web3NetworkInfo {
+	Web3NetworkInfo {
+		name: name.as_bytes().to_vec().try_into().unwrap(),
+		family,
+		providers: vec![(
+			b"achainable".to_vec().try_into().unwrap(),
+			name.to_lowercase().into_bytes().try_into().unwrap(),
+		)]
+		.try_into()
+		.unwrap(),
+	}
+}
+
+fn arbitrum() -> Web3NetworkInfo {
+	info("Arbitrum", Web3NetworkFamily::Evm { chain_id: 42161 })
+}
+
+#[test]
+fn register_network_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Web3Networks::register_network(RuntimeOrigin::signed(1), 1, arbitrum()));
+		assert_eq!(Web3Networks::networks().get(&1), Some(&arbitrum()));
+		System::assert_last_event(RuntimeEvent::Web3Networks(Event::NetworkRegistered {
+			id: 1,
+			info: arbitrum(),
+		}));
+
+		let moonbeam = info("Moonbeam", Web3NetworkFamily::Evm { chain_id: 1284 });
+		assert_noop!(
+			Web3Networks::register_network(RuntimeOrigin::signed(1), 1, moonbeam.clone()),
+			Error::<Test>::NetworkAlreadyRegistered
+		);
+		assert_noop!(
+			Web3Networks::register_network(RuntimeOrigin::signed(2), 2, moonbeam),
+			BadOrigin
+		);
+		assert_noop!(
+			Web3Networks::register_network(
+				RuntimeOrigin::signed(1),
+				2,
+				info("", Web3NetworkFamily::Other)
+			),
+			Error::<Test>::EmptyNetworkName
+		);
+	});
+}
+
+#[test]
+fn register_network_fails_when_full() {
+	new_test_ext().execute_with(|| {
+		let max = <MaxRegisteredWeb3Networks as Get<u32>>::get() as u16;
+		for id in 0..max {
+			assert_ok!(Web3Networks::register_network(RuntimeOrigin::signed(1), id, arbitrum()));
+		}
+		assert_noop!(
+			Web3Networks::register_network(RuntimeOrigin::signed(1), max, arbitrum()),
+			Error::<Test>::TooManyNetworks
+		);
+	});
+}
+
+#[test]
+fn update_network_works() {
+	new_test_ext().execute_with(|| {
+		let astar = info("Astar", Web3NetworkFamily::Substrate { ss58_prefix: 5 });
+		assert_noop!(
+			Web3Networks::update_network(RuntimeOrigin::signed(1), 1, astar.clone()),
+			Error::<Test>::NetworkNotRegistered
+		);
+		assert_ok!(Web3Networks::register_network(RuntimeOrigin::signed(1), 1, arbitrum()));
+		assert_noop!(
+			Web3Networks::update_network(RuntimeOrigin::signed(2), 1, astar.clone()),
+			BadOrigin
+		);
+		assert_ok!(Web3Networks::update_network(RuntimeOrigin::signed(1), 1, astar.clone()));
+		assert_eq!(Web3Networks::networks().get(&1), Some(&astar));
+		System::assert_last_event(RuntimeEvent::Web3Networks(Event::NetworkUpdated {
+			id: 1,
+			info: astar,
+		}));
+	});
+}
+
+#[test]
+fn remove_network_works() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Web3Networks::remove_network(RuntimeOrigin::signed(1), 1),
+			Error::<Test>::NetworkNotRegistered
+		);
+		assert_ok!(Web3Networks::register_network(RuntimeOrigin::signed(1), 1, arbitrum()));
+		assert_noop!(Web3Networks::remove_network(RuntimeOrigin::signed(2), 1), BadOrigin);
+		assert_ok!(Web3Networks::remove_network(RuntimeOrigin::signed(1), 1));
+		assert!(Web3Networks::networks().is_empty());
+		System::assert_last_event(RuntimeEvent::Web3Networks(Event::NetworkRemoved { id: 1 }));
+	});
+}
+
+// the enclave reads the registry by the key
+#[test]
+fn storage_key_matches() {
+	assert_eq!(Networks::<Test>::hashed_key(), web3network_registry_key());
+}
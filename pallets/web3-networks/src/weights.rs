@@ -0,0 +1,102 @@
+// Copyright 2020-2023 Litentry Technologies GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Weights for pallet_web3_networks
+//!
+//! TODO: these weights are NOT benchmarked yet, replace them with the output of
+//!
+//! ./target/release/litentry-collator benchmark pallet --chain=rococo-dev --execution=wasm \
+//!   --db-cache=20 --wasm-execution=compiled --pallet=pallet_web3_networks --extrinsic=* \
+//!   --heap-pages=4096 --steps=20 --repeat=50 --header=./LICENSE_HEADER \
+//!   --template=./templates/benchmark/pallet-weight-template.hbs \
+//!   --output=./pallets/web3-networks/src/weights.rs
+//!
+//! Every call decodes and encodes the whole registry, so the execution time is a
+//! conservative estimate for a full registry, and the proof size is the max encoded size of
+//! the registry.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(clippy::unnecessary_cast)]
+
+use codec::MaxEncodedLen;
+use core_primitives::Web3NetworkRegistry;
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_web3_networks.
+pub trait WeightInfo {
+	fn register_network() -> Weight;
+	fn update_network() -> Weight;
+	fn remove_network() -> Weight;
+}
+
+// the registry is read and written as a whole
+fn registry_proof_size() -> Weight {
+	Weight::from_proof_size(Web3NetworkRegistry::max_encoded_len() as u64)
+}
+
+/// Weights for pallet_web3_networks using the Litentry node and recommended hardware.
+pub struct LitentryWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for LitentryWeight<T> {
+	// Storage: Web3Networks Networks (r:1 w:1)
+	fn register_network() -> Weight {
+		Weight::from_ref_time(100_000_000 as u64)
+			.saturating_add(registry_proof_size())
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: Web3Networks Networks (r:1 w:1)
+	fn update_network() -> Weight {
+		Weight::from_ref_time(100_000_000 as u64)
+			.saturating_add(registry_proof_size())
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: Web3Networks Networks (r:1 w:1)
+	fn remove_network() -> Weight {
+		Weight::from_ref_time(100_000_000 as u64)
+			.saturating_add(registry_proof_size())
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	// Storage: Web3Networks Networks (r:1 w:1)
+	fn register_network() -> Weight {
+		Weight::from_ref_time(100_000_000 as u64)
+			.saturating_add(registry_proof_size())
+			.saturating_add(RocksDbWeight::get().reads(1 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	// Storage: Web3Networks Networks (r:1 w:1)
+	fn update_network() -> Weight {
+		Weight::from_ref_time(100_000_000 as u64)
+			.saturating_add(registry_proof_size())
+			.saturating_add(RocksDbWeight::get().reads(1 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	// Storage: Web3Networks Networks (r:1 w:1)
+	fn remove_network() -> Weight {
+		Weight::from_ref_time(100_000_000 as u64)
+			.saturating_add(registry_proof_size())
+			.saturating_add(RocksDbWeight::get().reads(1 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+}
@@ -17,7 +17,7 @@
 // This file includes the predefined rulesets and the corresponding parameters
 // when requesting VCs.
 
-use crate::{AccountId, BoundedWeb3Network, Web3Network};
+use crate::{AccountId, BoundedWeb3Network, Web3Network, Web3NetworkRegistry};
 use codec::{Decode, Encode, MaxEncodedLen};
 use scale_info::TypeInfo;
 use sp_runtime::{traits::ConstU32, BoundedVec};
@@ -189,8 +189,11 @@ impl Assertion {
 	//
 	// returns a vector of `Web3Network` guarantees it's a subnet of
 	// the broader `Web3Network` (see network.rs)
-	pub fn get_supported_web3networks(&self) -> Vec<Web3Network> {
-		match self {
+	//
+	// a registered network is only supported if it's registered with a name at the data
+	// provider the assertion is built with, see `data_provider`
+	pub fn get_supported_web3networks(&self, registry: &Web3NetworkRegistry) -> Vec<Web3Network> {
+		let networks = match self {
 			// LIT holder, not including `LitentryRococo` as it's not supported by any data provider
 			Self::A4(..) => vec![Web3Network::Litentry, Web3Network::Litmus, Web3Network::Ethereum],
 			// DOT holder
@@ -205,10 +208,51 @@ impl Assertion {
 			Self::Achainable(a) => vec![a.chain()],
 			// we don't care about any specific web3 network
 			_ => vec![],
+		};
+		networks
+			.into_iter()
+			.filter(|network| match network {
+				Web3Network::Registered(id) => self
+					.data_provider()
+					.and_then(|provider| registry.get(id)?.provider_name(provider))
+					.is_some(),
+				_ => true,
+			})
+			.collect()
+	}
+
+	// The data provider that resolves the networks of the assertion which are given by the
+	// user, i.e. that may be registered ones
+	pub fn data_provider(&self) -> Option<&'static str> {
+		match self {
+			Self::A8(..) | Self::Achainable(..) => Some("achainable"),
+			_ => None,
+		}
+	}
+
+	// Given an assertion enum type, retrieve how long (in seconds) a VC issued for it stays valid.
+	//
+	// Assertions over balances reflect a state that can change any time, so they expire much
+	// earlier than the ones over (mostly) immutable history or identity data.
+	pub fn validity_period(&self) -> u64 {
+		match self {
+			// token holders
+			Self::A4(..) | Self::A7(..) | Self::A10(..) | Self::A11(..) => 30 * SECS_PER_DAY,
+			// social and on-chain activities
+			Self::A2(..) |
+			Self::A3(..) |
+			Self::A6 |
+			Self::A8(..) |
+			Self::A14 |
+			Self::Achainable(..) => 90 * SECS_PER_DAY,
+			// identity verification and one-off events
+			_ => 365 * SECS_PER_DAY,
 		}
 	}
 }
 
+pub const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
 pub const ASSERTION_FROM_DATE: [&str; 14] = [
 	"2017-01-01",
 	"2017-07-01",
@@ -225,3 +269,60 @@ pub const ASSERTION_FROM_DATE: [&str; 14] = [
 	"2023-01-01",
 	"2023-07-01",
 ];
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Web3NetworkFamily, Web3NetworkInfo};
+
+	fn registry() -> Web3NetworkRegistry {
+		let info = |provider: &[u8]| Web3NetworkInfo {
+			name: b"Arbitrum".to_vec().try_into().unwrap(),
+			family: Web3NetworkFamily::Evm { chain_id: 42161 },
+			providers: vec![(
+				provider.to_vec().try_into().unwrap(),
+				b"arbitrum".to_vec().try_into().unwrap(),
+			)]
+			.try_into()
+			.unwrap(),
+		};
+		let mut registry = Web3NetworkRegistry::new();
+		registry.try_insert(1, info(b"achainable")).unwrap();
+		registry.try_insert(2, info(b"evm")).unwrap();
+		registry
+	}
+
+	#[test]
+	fn get_supported_web3networks_works() {
+		let registry = registry();
+		let networks = vec![
+			Web3Network::Ethereum,
+			Web3Network::Registered(1),
+			Web3Network::Registered(2),
+			Web3Network::Registered(3),
+		];
+		let a8 = Assertion::A8(networks.try_into().unwrap());
+		assert_eq!(
+			a8.get_supported_web3networks(&registry),
+			vec![Web3Network::Ethereum, Web3Network::Registered(1)]
+		);
+
+		let basic = |chain| {
+			Assertion::Achainable(AchainableParams::Basic(AchainableBasic {
+				name: b"Account found on {chain}".to_vec().try_into().unwrap(),
+				chain,
+			}))
+		};
+		assert_eq!(
+			basic(Web3Network::Registered(1)).get_supported_web3networks(&registry),
+			vec![Web3Network::Registered(1)]
+		);
+		assert!(basic(Web3Network::Registered(2))
+			.get_supported_web3networks(&registry)
+			.is_empty());
+		assert_eq!(
+			Assertion::A7(Default::default()).get_supported_web3networks(&registry),
+			vec![Web3Network::Polkadot]
+		);
+	}
+}
@@ -50,6 +50,13 @@ pub enum ErrorDetail {
 	VerifyEvmSignatureFailed,
 	RecoverEvmAddressFailed,
 	Web3NetworkOutOfBounds,
+	// the signed message isn't valid at the moment, e.g. it has expired
+	ExpiredMessage,
+	VerifyBitcoinSignatureFailed,
+	VerifySolanaSignatureFailed,
+	// the exported IDGraph can't be decrypted with the user shielding key, it's not of the user,
+	// or it's not signed by an enclave registered on the parentchain
+	InvalidIDGraphExport,
 }
 
 // We could have used Into<ErrorDetail>, but we want it to be more explicit, similar to `into_iter`
@@ -92,6 +99,9 @@ pub enum IMPError {
 	// should be unreached, but just to be on the safe side
 	// we should classify the error if we ever get this
 	UnclassifiedError(ErrorDetail),
+
+	// appended to keep the SCALE indices of the variants above
+	RemoveIdentityFailed(ErrorDetail),
 }
 
 impl frame_support::traits::PalletError for IMPError {
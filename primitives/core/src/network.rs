@@ -16,7 +16,7 @@
 
 use codec::{Decode, Encode, MaxEncodedLen};
 use scale_info::TypeInfo;
-use sp_runtime::{traits::ConstU32, BoundedVec};
+use sp_runtime::{traits::ConstU32, BoundedBTreeMap, BoundedVec};
 use sp_std::{hash::Hash, vec::Vec};
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
@@ -24,6 +24,47 @@ use strum_macros::EnumIter;
 pub const MAX_WEB3NETWORK_LEN: u32 = 128;
 pub type BoundedWeb3Network = BoundedVec<Web3Network, ConstU32<MAX_WEB3NETWORK_LEN>>;
 
+/// The id of a network registered on-chain in `pallet-web3-networks`
+pub type Web3NetworkId = u16;
+pub type Web3NetworkName = BoundedVec<u8, ConstU32<32>>;
+pub type MaxRegisteredWeb3Networks = ConstU32<64>;
+pub type MaxWeb3NetworkProviders = ConstU32<8>;
+
+/// All registered networks, it's kept in a single storage value so that the enclave reads
+/// them with one storage proof on each parentchain block.
+pub type Web3NetworkRegistry =
+	BoundedBTreeMap<Web3NetworkId, Web3NetworkInfo, MaxRegisteredWeb3Networks>;
+
+/// The storage key of the registry, `Networks` of the `Web3Networks` pallet instance
+pub fn web3network_registry_key() -> [u8; 32] {
+	frame_support::storage::storage_prefix(b"Web3Networks", b"Networks")
+}
+
+/// What identities a network accepts
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub enum Web3NetworkFamily {
+	Substrate { ss58_prefix: u16 },
+	Evm { chain_id: u64 },
+	Other,
+}
+
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct Web3NetworkInfo {
+	pub name: Web3NetworkName,
+	pub family: Web3NetworkFamily,
+	/// (data provider, the name of the network at the provider), e.g. ("achainable", "arbitrum")
+	pub providers: BoundedVec<(Web3NetworkName, Web3NetworkName), MaxWeb3NetworkProviders>,
+}
+
+impl Web3NetworkInfo {
+	pub fn provider_name(&self, provider: &str) -> Option<&[u8]> {
+		self.providers
+			.iter()
+			.find(|(p, _)| p.as_slice() == provider.as_bytes())
+			.map(|(_, name)| name.as_slice())
+	}
+}
+
 /// supported web3 networks
 /// use a flattened style to avoid overly nested structure like:
 /// {
@@ -31,6 +72,9 @@ pub type BoundedWeb3Network = BoundedVec<Web3Network, ConstU32<MAX_WEB3NETWORK_L
 ///   Evm(EvmNetwork),
 /// }
 /// TODO: theoretically this should the the union of the supported networks of all data providers
+///
+/// The variants but `Registered` are built in, networks added later are registered on-chain
+/// and referred to by their id, so that adding one needs neither a runtime nor an enclave upgrade.
 #[derive(
 	Encode,
 	Decode,
@@ -59,6 +103,15 @@ pub enum Web3Network {
 	// evm
 	Ethereum,
 	Bsc,
+
+	// bitcoin
+	Bitcoin,
+
+	// solana
+	Solana,
+
+	// see `Web3NetworkRegistry`
+	Registered(Web3NetworkId),
 }
 
 impl Web3Network {
@@ -75,10 +128,51 @@ impl Web3Network {
 	pub fn is_evm(&self) -> bool {
 		matches!(self, Self::Ethereum | Self::Bsc)
 	}
+
+	pub fn is_bitcoin(&self) -> bool {
+		matches!(self, Self::Bitcoin)
+	}
+
+	pub fn is_solana(&self) -> bool {
+		matches!(self, Self::Solana)
+	}
+
+	pub fn is_registered(&self) -> bool {
+		matches!(self, Self::Registered(..))
+	}
+
+	/// The family of a built-in network, or of a registered one in `registry`.
+	pub fn family(&self, registry: &Web3NetworkRegistry) -> Option<Web3NetworkFamily> {
+		let family = match self {
+			Self::Polkadot => Web3NetworkFamily::Substrate { ss58_prefix: 0 },
+			Self::Kusama => Web3NetworkFamily::Substrate { ss58_prefix: 2 },
+			Self::Litentry => Web3NetworkFamily::Substrate { ss58_prefix: 31 },
+			Self::Litmus => Web3NetworkFamily::Substrate { ss58_prefix: 131 },
+			Self::LitentryRococo => Web3NetworkFamily::Substrate { ss58_prefix: 42 },
+			Self::Khala => Web3NetworkFamily::Substrate { ss58_prefix: 30 },
+			Self::SubstrateTestnet => Web3NetworkFamily::Substrate { ss58_prefix: 42 },
+			Self::Ethereum => Web3NetworkFamily::Evm { chain_id: 1 },
+			Self::Bsc => Web3NetworkFamily::Evm { chain_id: 56 },
+			Self::Bitcoin | Self::Solana => Web3NetworkFamily::Other,
+			Self::Registered(id) => return registry.get(id).map(|info| info.family.clone()),
+		};
+		Some(family)
+	}
+
+	/// Like `is_substrate`, but also true for the registered substrate networks.
+	pub fn is_substrate_in(&self, registry: &Web3NetworkRegistry) -> bool {
+		matches!(self.family(registry), Some(Web3NetworkFamily::Substrate { .. }))
+	}
+
+	/// Like `is_evm`, but also true for the registered EVM networks.
+	pub fn is_evm_in(&self, registry: &Web3NetworkRegistry) -> bool {
+		matches!(self.family(registry), Some(Web3NetworkFamily::Evm { .. }))
+	}
 }
 
+/// All built-in networks
 pub fn all_web3networks() -> Vec<Web3Network> {
-	Web3Network::iter().collect()
+	Web3Network::iter().filter(|n| !n.is_registered()).collect()
 }
 
 pub fn all_substrate_web3networks() -> Vec<Web3Network> {
@@ -89,6 +183,14 @@ pub fn all_evm_web3networks() -> Vec<Web3Network> {
 	Web3Network::iter().filter(|n| n.is_evm()).collect()
 }
 
+pub fn all_bitcoin_web3networks() -> Vec<Web3Network> {
+	Web3Network::iter().filter(|n| n.is_bitcoin()).collect()
+}
+
+pub fn all_solana_web3networks() -> Vec<Web3Network> {
+	Web3Network::iter().filter(|n| n.is_solana()).collect()
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -114,6 +216,9 @@ mod tests {
 					Web3Network::SubstrateTestnet => false,
 					Web3Network::Ethereum => true,
 					Web3Network::Bsc => true,
+					Web3Network::Bitcoin => false,
+					Web3Network::Solana => false,
+					Web3Network::Registered(..) => false,
 				}
 			)
 		})
@@ -134,8 +239,59 @@ mod tests {
 					Web3Network::SubstrateTestnet => true,
 					Web3Network::Ethereum => false,
 					Web3Network::Bsc => false,
+					Web3Network::Bitcoin => false,
+					Web3Network::Solana => false,
+					Web3Network::Registered(..) => false,
 				}
 			)
 		})
 	}
+
+	#[test]
+	fn is_bitcoin_works() {
+		Web3Network::iter().for_each(|network| {
+			assert_eq!(network.is_bitcoin(), network == Web3Network::Bitcoin);
+		})
+	}
+
+	#[test]
+	fn is_solana_works() {
+		Web3Network::iter().for_each(|network| {
+			assert_eq!(network.is_solana(), network == Web3Network::Solana);
+		})
+	}
+
+	#[test]
+	fn family_works() {
+		let mut registry = Web3NetworkRegistry::new();
+		let info = Web3NetworkInfo {
+			name: b"Arbitrum".to_vec().try_into().unwrap(),
+			family: Web3NetworkFamily::Evm { chain_id: 42161 },
+			providers: vec![(
+				b"achainable".to_vec().try_into().unwrap(),
+				b"arbitrum".to_vec().try_into().unwrap(),
+			)]
+			.try_into()
+			.unwrap(),
+		};
+		registry.try_insert(1, info.clone()).unwrap();
+
+		// the built-in ones don't depend on the registry
+		all_web3networks().into_iter().for_each(|network| {
+			assert_eq!(network.is_substrate_in(&registry), network.is_substrate());
+			assert_eq!(network.is_evm_in(&registry), network.is_evm());
+		});
+		assert!(!all_web3networks().contains(&Web3Network::Registered(0)));
+
+		let network = Web3Network::Registered(1);
+		assert_eq!(network.family(&registry), Some(Web3NetworkFamily::Evm { chain_id: 42161 }));
+		assert!(network.is_evm_in(&registry) && !network.is_evm());
+		assert_eq!(info.provider_name("achainable"), Some(&b"arbitrum"[..]));
+		assert_eq!(info.provider_name("evm"), None);
+
+		// not registered
+		let network = Web3Network::Registered(2);
+		assert_eq!(network.family(&registry), None);
+		assert!(!network.is_substrate_in(&registry) && !network.is_evm_in(&registry));
+	}
 }
@@ -20,6 +20,8 @@
 #[cfg(feature = "std")]
 extern crate std;
 
+use crate::Assertion;
+use sp_core::H256;
 use sp_runtime::{traits::ConstU32, BoundedVec};
 
 // vc schema
@@ -34,3 +36,34 @@ pub type SchemaIdString = BoundedVec<u8, MaxIdLength>;
 
 pub type MaxContentLength = ConstU32<SCHEMA_CONTENT_LEN>;
 pub type SchemaContentString = BoundedVec<u8, MaxContentLength>;
+
+/// An active VC registered on-chain
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ActiveVC<AccountId> {
+	/// the account the VC was issued to
+	pub subject: AccountId,
+	/// the assertion the VC was requested for
+	pub assertion: Assertion,
+	/// the hash of the VC
+	pub hash: H256,
+	/// the schema the VCs of `assertion` are issued under, if it's known and active
+	pub schema: Option<SchemaIndex>,
+}
+
+/// Read access to the VCs registered on-chain, for pallets which gate an action on holding one.
+pub trait VCRegistryLookup<AccountId> {
+	/// The VC with `index`, `None` unless it's active.
+	fn active_vc(index: &H256) -> Option<ActiveVC<AccountId>>;
+
+	/// Registers an active VC, with its schema if any, so that the benchmarks of the consumers
+	/// can look it up.
+	#[cfg(feature = "runtime-benchmarks")]
+	fn add_active_vc(_index: H256, _vc: ActiveVC<AccountId>) {}
+}
+
+/// No VC is active, for runtimes without a VC registry
+impl<AccountId> VCRegistryLookup<AccountId> for () {
+	fn active_vc(_index: &H256) -> Option<ActiveVC<AccountId>> {
+		None
+	}
+}
@@ -29,4 +29,6 @@ use serde::{Deserialize, Serialize};
 pub struct SidechainBlockConfirmation {
 	pub block_number: u64,
 	pub block_header_hash: H256,
+	/// The state of the shard after the block was imported
+	pub state_root: H256,
 }
@@ -1,6 +1,8 @@
 //!Primitives for teeracle
 #![cfg_attr(not(feature = "std"), no_std)]
+use codec::{Decode, Encode};
 use common_primitives::PalletString;
+use scale_info::TypeInfo;
 use substrate_fixed::types::U32F32;
 
 pub const MAX_ORACLE_DATA_NAME_LEN: usize = 40;
@@ -10,3 +12,28 @@ pub type TradingPairString = PalletString;
 pub type MarketDataSourceString = PalletString;
 pub type OracleDataName = PalletString;
 pub type DataSource = PalletString;
+
+/// An exchange rate reported by an enclave for a data source
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo)]
+pub struct ExchangeRateReport<AccountId> {
+	pub rate: ExchangeRate,
+	/// unix timestamp in milliseconds at which the enclave fetched the rate
+	pub timestamp: u64,
+	pub enclave: AccountId,
+}
+
+/// The exchange rate of a trading pair aggregated over its data sources
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo)]
+pub struct AggregatedExchangeRate {
+	/// median over the data sources, the rate of each source being the median of the
+	/// reports of its enclaves
+	pub median: ExchangeRate,
+	/// time weighted average of `median` over the TWAP period until it was read
+	pub twap: ExchangeRate,
+	/// the number of data sources with a report which wasn't stale
+	pub sources: u32,
+	/// the timestamp of the newest report
+	pub timestamp: u64,
+	/// whether the newest report is older than the max age, it's set when the value is read
+	pub stale: bool,
+}
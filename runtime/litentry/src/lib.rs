@@ -775,6 +775,9 @@ parameter_types! {
 	pub const ExternalTotalIssuance: Balance = 100_000_000 * DOLLARS;
 	// bridge::derive_resource_id(1, &bridge::hashing::blake2_128(b"LIT"));
 	pub const NativeTokenResourceId: [u8; 32] = hex_literal::hex!("00000000000000000000000000000063a7e2be78898ba83824b0c0cc8dfb6001");
+	// the rolling window of the bridge transfer volume caps
+	pub const BridgeRateLimitWindow: BlockNumber = DAYS;
+	pub const BridgeTransferDelay: BlockNumber = 6 * HOURS;
 }
 
 // allow anyone to call transfer_native
@@ -802,6 +805,9 @@ impl pallet_bridge_transfer::Config for Runtime {
 	type NativeTokenResourceId = NativeTokenResourceId;
 	type DefaultMaximumIssuance = MaximumIssuance;
 	type ExternalTotalIssuance = ExternalTotalIssuance;
+	type RateLimitWindow = BridgeRateLimitWindow;
+	type TransferDelay = BridgeTransferDelay;
+	type CircuitBreaker = ExtrinsicFilter;
 	type WeightInfo = weights::pallet_bridge_transfer::WeightInfo<Runtime>;
 }
 
@@ -817,6 +823,8 @@ impl pallet_drop3::Config for Runtime {
 	type WeightInfo = weights::pallet_drop3::WeightInfo<Runtime>;
 	type SlashPercent = SlashPercent;
 	type MaximumNameLength = ConstU32<16>;
+	type VCRegistry = ();
+	type MaximumMerkleProofLength = ConstU32<20>;
 }
 
 impl pallet_extrinsic_filter::Config for Runtime {
@@ -828,6 +836,12 @@ impl pallet_extrinsic_filter::Config for Runtime {
 	type WeightInfo = weights::pallet_extrinsic_filter::WeightInfo<Runtime>;
 }
 
+impl pallet_web3_networks::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type ManageOrigin = EnsureRootOrHalfCouncil;
+	type WeightInfo = weights::pallet_web3_networks::WeightInfo<Runtime>;
+}
+
 impl runtime_common::BaseRuntimeRequirements for Runtime {}
 
 impl runtime_common::ParaRuntimeRequirements for Runtime {}
@@ -899,6 +913,7 @@ construct_runtime! {
 		Drop3: pallet_drop3 = 62,
 		ExtrinsicFilter: pallet_extrinsic_filter = 63,
 		AssetManager: pallet_asset_manager = 64,
+		Web3Networks: pallet_web3_networks = 65,
 
 		// TMP
 		Sudo: pallet_sudo = 255,
@@ -961,7 +976,9 @@ impl Contains<RuntimeCall> for NormalModeFilter {
 			// Identity
 			RuntimeCall::ParachainIdentity(_) |
 			// Balance
-			RuntimeCall::Balances(_)
+			RuntimeCall::Balances(_) |
+			// Web3Networks
+			RuntimeCall::Web3Networks(_)
 		)
 	}
 }
@@ -982,6 +999,7 @@ mod benches {
 		[pallet_multisig, Multisig]
 		[pallet_drop3, Drop3]
 		[pallet_extrinsic_filter, ExtrinsicFilter]
+		[pallet_web3_networks, Web3Networks]
 		[pallet_scheduler, Scheduler]
 		[pallet_preimage, Preimage]
 		[pallet_session, SessionBench::<Runtime>]
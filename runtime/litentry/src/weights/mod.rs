@@ -37,3 +37,4 @@ pub mod pallet_session;
 pub mod pallet_timestamp;
 pub mod pallet_treasury;
 pub mod pallet_utility;
+pub mod pallet_web3_networks;
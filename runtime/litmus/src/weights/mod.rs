@@ -43,3 +43,4 @@ pub mod pallet_timestamp;
 pub mod pallet_treasury;
 pub mod pallet_utility;
 pub mod pallet_vc_management;
+pub mod pallet_web3_networks;
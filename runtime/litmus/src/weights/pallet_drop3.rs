@@ -21,6 +21,16 @@
 //! WORST CASE MAP SIZE: `1000000`
 //! HOSTNAME: `parachain-benchmark`, CPU: `Intel(R) Xeon(R) Platinum 8259CL CPU @ 2.50GHz`
 //! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("litmus-dev"), DB CACHE: 20
+//!
+//! TODO: `set_reward_claim` and `claim_reward` were NOT benchmarked, they're given the measured
+//! weight of `approve_reward_pool` and `send_reward` plus the reads and writes of the storage
+//! they access in addition, the proof size of that storage isn't included. `close_reward_pool`
+//! and `reject_reward_pool` were NOT re-benchmarked after keeping the closed pools with claims,
+//! their storage accesses were updated by hand. `set_merkle_root` and `claim_airdrop` were NOT
+//! benchmarked either, they're given the measured weight of `approve_reward_pool` and
+//! `send_reward` in the same way, and each proof element of `claim_airdrop` is charged 65 times
+//! the weight per byte of `frame_system::remark_with_event`, which hashes its input. Re-run the
+//! command below.
 
 // Executed Command:
 // ./litentry-collator
@@ -82,6 +92,12 @@ impl<T: frame_system::Config> pallet_drop3::WeightInfo for WeightInfo<T> {
 	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
 	/// Storage: Drop3 RewardPoolOwners (r:0 w:1)
 	/// Proof Skipped: Drop3 RewardPoolOwners (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Drop3 RewardClaims (r:1 w:1)
+	/// Proof Skipped: Drop3 RewardClaims (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Drop3 ClosedRewardPools (r:0 w:1)
+	/// Proof Skipped: Drop3 ClosedRewardPools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Drop3 MerkleRoots (r:1 w:1)
+	/// Proof Skipped: Drop3 MerkleRoots (max_values: None, max_size: None, mode: Measured)
 	fn reject_reward_pool() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `429`
@@ -89,8 +105,8 @@ impl<T: frame_system::Config> pallet_drop3::WeightInfo for WeightInfo<T> {
 		// Minimum execution time: 67_328 nanoseconds.
 		Weight::from_ref_time(68_498_000)
 			.saturating_add(Weight::from_proof_size(6860))
-			.saturating_add(T::DbWeight::get().reads(3))
-			.saturating_add(T::DbWeight::get().writes(3))
+			.saturating_add(T::DbWeight::get().reads(5))
+			.saturating_add(T::DbWeight::get().writes(6))
 	}
 	/// Storage: Drop3 RewardPools (r:1 w:1)
 	/// Proof Skipped: Drop3 RewardPools (max_values: None, max_size: None, mode: Measured)
@@ -128,6 +144,12 @@ impl<T: frame_system::Config> pallet_drop3::WeightInfo for WeightInfo<T> {
 	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
 	/// Storage: Drop3 RewardPoolOwners (r:0 w:1)
 	/// Proof Skipped: Drop3 RewardPoolOwners (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Drop3 RewardClaims (r:1 w:1)
+	/// Proof Skipped: Drop3 RewardClaims (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Drop3 ClosedRewardPools (r:0 w:1)
+	/// Proof Skipped: Drop3 ClosedRewardPools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Drop3 MerkleRoots (r:1 w:1)
+	/// Proof Skipped: Drop3 MerkleRoots (max_values: None, max_size: None, mode: Measured)
 	fn close_reward_pool() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `429`
@@ -135,8 +157,8 @@ impl<T: frame_system::Config> pallet_drop3::WeightInfo for WeightInfo<T> {
 		// Minimum execution time: 41_638 nanoseconds.
 		Weight::from_ref_time(42_432_000)
 			.saturating_add(Weight::from_proof_size(6860))
-			.saturating_add(T::DbWeight::get().reads(3))
-			.saturating_add(T::DbWeight::get().writes(3))
+			.saturating_add(T::DbWeight::get().reads(5))
+			.saturating_add(T::DbWeight::get().writes(6))
 	}
 	/// Storage: System Account (r:1 w:1)
 	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
@@ -173,4 +195,46 @@ impl<T: frame_system::Config> pallet_drop3::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().reads(3))
 			.saturating_add(T::DbWeight::get().writes(3))
 	}
+	/// Storage: Drop3 RewardPools (r:1 w:0)
+	/// Storage: Drop3 RewardClaims (r:0 w:1)
+	fn set_reward_claim() -> Weight {
+		Weight::from_ref_time(23_347_000)
+			.saturating_add(Weight::from_proof_size(3562))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// Storage: Drop3 RewardClaims (r:1 w:0)
+	/// Storage: VCManagement VCRegistry (r:1 w:0)
+	/// Storage: VCManagement AssertionSchemas (r:1 w:0)
+	/// Storage: VCManagement SchemaRegistry (r:1 w:0)
+	/// Storage: Drop3 ClaimedVCs (r:1 w:1)
+	/// Storage: Drop3 ClaimedAccounts (r:1 w:1)
+	/// Storage: Drop3 RewardPools (r:1 w:1)
+	/// Storage: System Account (r:2 w:2)
+	fn claim_reward() -> Weight {
+		Weight::from_ref_time(43_924_000)
+			.saturating_add(Weight::from_proof_size(8226))
+			.saturating_add(T::DbWeight::get().reads(9))
+			.saturating_add(T::DbWeight::get().writes(5))
+	}
+	/// Storage: Drop3 RewardPools (r:1 w:0)
+	/// Storage: Drop3 MerkleRoots (r:1 w:1)
+	fn set_merkle_root() -> Weight {
+		Weight::from_ref_time(23_347_000)
+			.saturating_add(Weight::from_proof_size(3562))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// Storage: Drop3 MerkleRoots (r:1 w:0)
+	/// Storage: Drop3 ClaimedAirdropBitmap (r:1 w:1)
+	/// Storage: Drop3 RewardPools (r:1 w:1)
+	/// Storage: System Account (r:2 w:2)
+	/// The range of component `n` is `[0, 20]`.
+	fn claim_airdrop(n: u32, ) -> Weight {
+		Weight::from_ref_time(43_924_000)
+			.saturating_add(Weight::from_proof_size(8226))
+			.saturating_add(Weight::from_ref_time(195_780).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(5))
+			.saturating_add(T::DbWeight::get().writes(4))
+	}
 }
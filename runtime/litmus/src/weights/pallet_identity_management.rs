@@ -106,6 +106,17 @@ impl<T: frame_system::Config> pallet_identity_management::WeightInfo for WeightI
 	}
 	/// Storage: IMPExtrinsicWhitelist GroupControlOn (r:1 w:0)
 	/// Proof Skipped: IMPExtrinsicWhitelist GroupControlOn (max_values: Some(1), max_size: None, mode: Measured)
+	fn remove_identity() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `76`
+		//  Estimated: `571`
+		// Minimum execution time: 15_330 nanoseconds.
+		Weight::from_ref_time(15_787_000)
+			.saturating_add(Weight::from_proof_size(571))
+			.saturating_add(T::DbWeight::get().reads(1))
+	}
+	/// Storage: IMPExtrinsicWhitelist GroupControlOn (r:1 w:0)
+	/// Proof Skipped: IMPExtrinsicWhitelist GroupControlOn (max_values: Some(1), max_size: None, mode: Measured)
 	fn set_user_shielding_key() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `76`
@@ -161,6 +172,17 @@ impl<T: frame_system::Config> pallet_identity_management::WeightInfo for WeightI
 	}
 	/// Storage: Teerex EnclaveIndex (r:1 w:0)
 	/// Proof Skipped: Teerex EnclaveIndex (max_values: None, max_size: None, mode: Measured)
+	fn identity_removed() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `242`
+		//  Estimated: `2717`
+		// Minimum execution time: 20_171 nanoseconds.
+		Weight::from_ref_time(20_838_000)
+			.saturating_add(Weight::from_proof_size(2717))
+			.saturating_add(T::DbWeight::get().reads(1))
+	}
+	/// Storage: Teerex EnclaveIndex (r:1 w:0)
+	/// Proof Skipped: Teerex EnclaveIndex (max_values: None, max_size: None, mode: Measured)
 	fn some_error() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `242`
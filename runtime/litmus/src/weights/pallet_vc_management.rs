@@ -21,6 +21,9 @@
 //! WORST CASE MAP SIZE: `1000000`
 //! HOSTNAME: `parachain-benchmark`, CPU: `Intel(R) Xeon(R) Platinum 8259CL CPU @ 2.50GHz`
 //! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("litmus-dev"), DB CACHE: 20
+//!
+//! TODO: `set_assertion_schema` was NOT benchmarked, it's given the measured weight of
+//! `revoke_schema`, which has the same storage accesses. Re-run the command below.
 
 // Executed Command:
 // ./litentry-collator
@@ -113,16 +116,18 @@ impl<T: frame_system::Config> pallet_vc_management::WeightInfo for WeightInfo<T>
 	/// Storage: Teerex EnclaveIndex (r:1 w:0)
 	/// Proof Skipped: Teerex EnclaveIndex (max_values: None, max_size: None, mode: Measured)
 	/// Storage: VCManagement VCRegistry (r:1 w:1)
-	/// Proof: VCManagement VCRegistry (max_values: None, max_size: Some(312), added: 2787, mode: MaxEncodedLen)
+	/// Proof: VCManagement VCRegistry (max_values: None, max_size: Some(317), added: 2792, mode: MaxEncodedLen)
+	/// Storage: VCManagement ExpiryQueue (r:1 w:1)
+	/// Proof: VCManagement ExpiryQueue (max_values: None, max_size: Some(32782), added: 35257, mode: MaxEncodedLen)
 	fn vc_issued() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `248`
 		//  Estimated: `5510`
 		// Minimum execution time: 27_255 nanoseconds.
-		Weight::from_ref_time(28_362_000)
-			.saturating_add(Weight::from_proof_size(5510))
-			.saturating_add(T::DbWeight::get().reads(2))
-			.saturating_add(T::DbWeight::get().writes(1))
+		Weight::from_ref_time(40_125_000)
+			.saturating_add(Weight::from_proof_size(46111))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(2))
 	}
 	/// Storage: Teerex EnclaveIndex (r:1 w:0)
 	/// Proof Skipped: Teerex EnclaveIndex (max_values: None, max_size: None, mode: Measured)
@@ -206,18 +211,29 @@ impl<T: frame_system::Config> pallet_vc_management::WeightInfo for WeightInfo<T>
 			.saturating_add(T::DbWeight::get().writes(1))
 	}
 	/// Storage: VCManagement Admin (r:1 w:0)
+	/// Storage: VCManagement SchemaRegistry (r:1 w:0)
+	/// Storage: VCManagement AssertionSchemas (r:0 w:1)
+	fn set_assertion_schema() -> Weight {
+		Weight::from_ref_time(22_383_000)
+			.saturating_add(Weight::from_proof_size(5623))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// Storage: VCManagement Admin (r:1 w:0)
 	/// Proof: VCManagement Admin (max_values: Some(1), max_size: Some(32), added: 527, mode: MaxEncodedLen)
 	/// Storage: VCManagement VCRegistry (r:1 w:1)
-	/// Proof: VCManagement VCRegistry (max_values: None, max_size: Some(312), added: 2787, mode: MaxEncodedLen)
+	/// Proof: VCManagement VCRegistry (max_values: None, max_size: Some(317), added: 2792, mode: MaxEncodedLen)
+	/// Storage: VCManagement ExpiryQueue (r:1 w:1)
+	/// Proof: VCManagement ExpiryQueue (max_values: None, max_size: Some(32782), added: 35257, mode: MaxEncodedLen)
 	fn add_vc_registry_item() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `61`
 		//  Estimated: `3314`
 		// Minimum execution time: 20_999 nanoseconds.
-		Weight::from_ref_time(21_896_000)
-			.saturating_add(Weight::from_proof_size(3314))
-			.saturating_add(T::DbWeight::get().reads(2))
-			.saturating_add(T::DbWeight::get().writes(1))
+		Weight::from_ref_time(26_318_000)
+			.saturating_add(Weight::from_proof_size(38576))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(2))
 	}
 	/// Storage: VCManagement Admin (r:1 w:0)
 	/// Proof: VCManagement Admin (max_values: Some(1), max_size: Some(32), added: 527, mode: MaxEncodedLen)
@@ -252,4 +268,37 @@ impl<T: frame_system::Config> pallet_vc_management::WeightInfo for WeightInfo<T>
 			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(x.into())))
 			.saturating_add(Weight::from_proof_size(2787).saturating_mul(x.into()))
 	}
+	/// Storage: VCMPExtrinsicWhitelist GroupControlOn (r:1 w:0)
+	/// Proof Skipped: VCMPExtrinsicWhitelist GroupControlOn (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: VCManagement VCRegistry (r:1 w:0)
+	/// Proof: VCManagement VCRegistry (max_values: None, max_size: Some(317), added: 2792, mode: MaxEncodedLen)
+	fn renew_vc() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `445`
+		//  Estimated: `6707`
+		// Minimum execution time: 30_871 nanoseconds.
+		Weight::from_ref_time(32_046_000)
+			.saturating_add(Weight::from_proof_size(6707))
+			.saturating_add(T::DbWeight::get().reads(2))
+	}
+	/// Storage: VCManagement ExpiryQueue (r:1 w:1)
+	/// Proof: VCManagement ExpiryQueue (max_values: None, max_size: Some(32782), added: 35257, mode: MaxEncodedLen)
+	/// Storage: VCManagement VCRegistry (r:1024 w:1024)
+	/// Proof: VCManagement VCRegistry (max_values: None, max_size: Some(317), added: 2792, mode: MaxEncodedLen)
+	/// The range of component `x` is `[0, 1024]`.
+	fn expire_vc(x: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `151 + x * (129 ±0)`
+		//  Estimated: `35257 + x * (2792 ±0)`
+		// Minimum execution time: 5_902 nanoseconds.
+		Weight::from_ref_time(6_417_372)
+			.saturating_add(Weight::from_proof_size(35257))
+			// Standard Error: 2_018
+			.saturating_add(Weight::from_ref_time(7_384_219).saturating_mul(x.into()))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(x.into())))
+			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(x.into())))
+			.saturating_add(Weight::from_proof_size(2792).saturating_mul(x.into()))
+	}
 }
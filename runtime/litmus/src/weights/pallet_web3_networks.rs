@@ -0,0 +1,63 @@
+// Copyright 2020-2023 Litentry Technologies GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Weights for `pallet_web3_networks`
+//!
+//! TODO: these weights are NOT benchmarked yet, replace them with the output of
+//!
+//! ./litentry-collator benchmark pallet --chain=litmus-dev --execution=wasm --db-cache=20 \
+//!   --wasm-execution=compiled --pallet=pallet_web3_networks --extrinsic=* --heap-pages=4096 \
+//!   --steps=20 --repeat=50 --header=./LICENSE_HEADER \
+//!   --output=./runtime/litmus/src/weights/pallet_web3_networks.rs
+//!
+//! Every call decodes and encodes the whole registry, so the execution time is a
+//! conservative estimate for a full registry, and the proof size is the max encoded size of
+//! the registry.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::Weight};
+use pallet_web3_networks::Web3NetworkRegistry;
+use codec::MaxEncodedLen;
+use sp_std::marker::PhantomData;
+
+/// Weight functions for `pallet_web3_networks`.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> pallet_web3_networks::WeightInfo for WeightInfo<T> {
+	/// Storage: Web3Networks Networks (r:1 w:1)
+	fn register_network() -> Weight {
+		Weight::from_ref_time(100_000_000)
+			.saturating_add(Weight::from_proof_size(Web3NetworkRegistry::max_encoded_len() as u64))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// Storage: Web3Networks Networks (r:1 w:1)
+	fn update_network() -> Weight {
+		Weight::from_ref_time(100_000_000)
+			.saturating_add(Weight::from_proof_size(Web3NetworkRegistry::max_encoded_len() as u64))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// Storage: Web3Networks Networks (r:1 w:1)
+	fn remove_network() -> Weight {
+		Weight::from_ref_time(100_000_000)
+			.saturating_add(Weight::from_proof_size(Web3NetworkRegistry::max_encoded_len() as u64))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+}
@@ -135,6 +135,11 @@ pub type Executive = frame_executive::Executive<
 	// it was reverse order before.
 	// See the comment before collation related pallets too.
 	AllPalletsWithSystem,
+	(
+		pallet_vc_management::migrations::MigrateToV1<Runtime>,
+		pallet_teerex::migrations::MigrateToV1<Runtime>,
+		pallet_sidechain::migrations::MigrateToV1<Runtime>,
+	),
 >;
 
 impl_opaque_keys! {
@@ -814,6 +819,9 @@ parameter_types! {
 	pub const ExternalTotalIssuance: Balance = 100_000_000 * DOLLARS;
 	// bridge::derive_resource_id(1, &bridge::hashing::blake2_128(b"LIT"));
 	pub const NativeTokenResourceId: [u8; 32] = hex!("00000000000000000000000000000063a7e2be78898ba83824b0c0cc8dfb6001");
+	// the rolling window of the bridge transfer volume caps
+	pub const BridgeRateLimitWindow: BlockNumber = DAYS;
+	pub const BridgeTransferDelay: BlockNumber = 6 * HOURS;
 }
 
 pub struct TransferNativeAnyone;
@@ -840,6 +848,9 @@ impl pallet_bridge_transfer::Config for Runtime {
 	type NativeTokenResourceId = NativeTokenResourceId;
 	type DefaultMaximumIssuance = MaximumIssuance;
 	type ExternalTotalIssuance = ExternalTotalIssuance;
+	type RateLimitWindow = BridgeRateLimitWindow;
+	type TransferDelay = BridgeTransferDelay;
+	type CircuitBreaker = ExtrinsicFilter;
 	type WeightInfo = weights::pallet_bridge_transfer::WeightInfo<Runtime>;
 }
 
@@ -855,6 +866,8 @@ impl pallet_drop3::Config for Runtime {
 	type WeightInfo = weights::pallet_drop3::WeightInfo<Runtime>;
 	type SlashPercent = SlashPercent;
 	type MaximumNameLength = ConstU32<16>;
+	type VCRegistry = VCManagement;
+	type MaximumMerkleProofLength = ConstU32<20>;
 }
 
 impl pallet_extrinsic_filter::Config for Runtime {
@@ -887,9 +900,19 @@ impl pallet_teerex::Config for Runtime {
 	type SetAdminOrigin = EnsureRootOrHalfCouncil;
 }
 
+parameter_types! {
+	// two thirds of the enclaves of a shard
+	pub const SidechainFinalizationQuorum: Percent = Percent::from_percent(66);
+	pub const SidechainMaxConfirmingEnclaves: u32 = 32;
+	pub const SidechainConfirmationTimeout: BlockNumber = 10 * MINUTES;
+}
+
 impl pallet_sidechain::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type WeightInfo = weights::pallet_sidechain::WeightInfo<Runtime>;
+	type FinalizationQuorum = SidechainFinalizationQuorum;
+	type MaxConfirmingEnclaves = SidechainMaxConfirmingEnclaves;
+	type ConfirmationTimeout = SidechainConfirmationTimeout;
 }
 
 impl pallet_teeracle::Config for Runtime {
@@ -897,6 +920,12 @@ impl pallet_teeracle::Config for Runtime {
 	type WeightInfo = weights::pallet_teeracle::WeightInfo<Runtime>;
 	type MaxWhitelistedReleases = ConstU32<10>;
 	type MaxOracleBlobLen = ConstU32<4096>;
+	// the enclaves update the exchange rates once a day by default
+	type MaxExchangeRateAge = ConstU64<172_800_000>; // 48h
+	type ExchangeRateTwapPeriod = ConstU64<86_400_000>; // 24h
+	type MaxExchangeRateReports = ConstU32<64>;
+	// one median every 30 minutes over the TWAP period
+	type MaxExchangeRateObservations = ConstU32<48>;
 }
 
 impl pallet_identity_management::Config for Runtime {
@@ -919,6 +948,8 @@ impl pallet_vc_management::Config for Runtime {
 	type SetAdminOrigin = EnsureRootOrHalfCouncil;
 	type DelegateeAdminOrigin = EnsureRootOrAllCouncil;
 	type ExtrinsicWhitelistOrigin = VCMPExtrinsicWhitelist;
+	type ExpectedBlockTime = ConstU64<SLOT_DURATION>;
+	type MaxExpiriesPerBlock = ConstU32<1024>;
 }
 
 impl pallet_group::Config<VCMPExtrinsicWhitelistInstance> for Runtime {
@@ -926,6 +957,12 @@ impl pallet_group::Config<VCMPExtrinsicWhitelistInstance> for Runtime {
 	type GroupManagerOrigin = EnsureRootOrAllCouncil;
 }
 
+impl pallet_web3_networks::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type ManageOrigin = EnsureRootOrHalfCouncil;
+	type WeightInfo = weights::pallet_web3_networks::WeightInfo<Runtime>;
+}
+
 impl runtime_common::BaseRuntimeRequirements for Runtime {}
 
 impl runtime_common::ParaRuntimeRequirements for Runtime {}
@@ -1002,6 +1039,7 @@ construct_runtime! {
 		VCManagement: pallet_vc_management = 66,
 		IMPExtrinsicWhitelist: pallet_group::<Instance1> = 67,
 		VCMPExtrinsicWhitelist: pallet_group::<Instance2> = 68,
+		Web3Networks: pallet_web3_networks = 69,
 
 		// TEE
 		Teerex: pallet_teerex = 90,
@@ -1073,6 +1111,7 @@ impl Contains<RuntimeCall> for NormalModeFilter {
 			// IMP and VCMP
 			RuntimeCall::IdentityManagement(_) |
 			RuntimeCall::VCManagement(_) |
+			RuntimeCall::Web3Networks(_) |
 			// TEE pallets
 			RuntimeCall::Teerex(_) |
 			RuntimeCall::Sidechain(_) |
@@ -1118,6 +1157,7 @@ mod benches {
 		[cumulus_pallet_xcmp_queue, XcmpQueue]
 		[pallet_identity_management, IdentityManagement]
 		[pallet_vc_management, VCManagement]
+		[pallet_web3_networks, Web3Networks]
 		[pallet_teerex, Teerex]
 		[pallet_sidechain, Sidechain]
 		[pallet_teeracle, Teeracle]
@@ -1183,6 +1223,14 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_teeracle_runtime_api::TeeracleApi<Block> for Runtime {
+		fn aggregated_exchange_rate(
+			trading_pair: pallet_teeracle_runtime_api::TradingPairString,
+		) -> Option<pallet_teeracle_runtime_api::AggregatedExchangeRate> {
+			Teeracle::aggregated_exchange_rate(&trading_pair)
+		}
+	}
+
 	impl sp_session::SessionKeys<Block> for Runtime {
 		fn decode_session_keys(
 			encoded: Vec<u8>,
@@ -21,6 +21,10 @@
 //! WORST CASE MAP SIZE: `1000000`
 //! HOSTNAME: `parachain-benchmark`, CPU: `Intel(R) Xeon(R) Platinum 8259CL CPU @ 2.50GHz`
 //! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("rococo-dev"), DB CACHE: 20
+//!
+//! TODO: `update_exchange_rate` was NOT re-benchmarked after the aggregation of the bounded
+//! reports and the time weighted average, its storage accesses were updated by hand. Re-run
+//! the command below.
 
 // Executed Command:
 // ./litentry-collator
@@ -56,6 +60,12 @@ impl<T: frame_system::Config> pallet_teeracle::WeightInfo for WeightInfo<T> {
 	/// Proof Skipped: Teeracle Whitelists (max_values: None, max_size: None, mode: Measured)
 	/// Storage: Teeracle ExchangeRates (r:1 w:1)
 	/// Proof Skipped: Teeracle ExchangeRates (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Teeracle ExchangeRateReports (r:1 w:1)
+	/// Proof Skipped: Teeracle ExchangeRateReports (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Teeracle ExchangeRateObservations (r:1 w:1)
+	/// Proof Skipped: Teeracle ExchangeRateObservations (max_values: None, max_size: None, mode: Measured)
+	/// Storage: Teeracle AggregatedExchangeRates (r:0 w:1)
+	/// Proof Skipped: Teeracle AggregatedExchangeRates (max_values: None, max_size: None, mode: Measured)
 	fn update_exchange_rate() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `3465`
@@ -63,8 +73,8 @@ impl<T: frame_system::Config> pallet_teeracle::WeightInfo for WeightInfo<T> {
 		// Minimum execution time: 47_419 nanoseconds.
 		Weight::from_ref_time(48_899_000)
 			.saturating_add(Weight::from_proof_size(23760))
-			.saturating_add(T::DbWeight::get().reads(4))
-			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(T::DbWeight::get().reads(6))
+			.saturating_add(T::DbWeight::get().writes(4))
 	}
 	/// Storage: Teerex EnclaveIndex (r:1 w:0)
 	/// Proof Skipped: Teerex EnclaveIndex (max_values: None, max_size: None, mode: Measured)
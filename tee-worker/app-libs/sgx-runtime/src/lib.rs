@@ -42,7 +42,11 @@ pub use evm::{
 use frame_system::EnsureRoot;
 
 use core::convert::{TryFrom, TryInto};
-use frame_support::{traits::ConstU32, weights::ConstantMultiplier};
+use frame_support::{
+	traits::{ConstU32, Get},
+	weights::ConstantMultiplier,
+};
+use litentry_primitives::{web3network_registry_key, Web3NetworkRegistry};
 use pallet_transaction_payment::CurrencyAdapter;
 use sp_api::impl_runtime_apis;
 use sp_core::OpaqueMetadata;
@@ -139,7 +143,7 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_name: create_runtime_str!("node-template"),
 	impl_name: create_runtime_str!("node-template"),
 	authoring_version: 1,
-	spec_version: 1,
+	spec_version: 2,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
 	transaction_version: 1,
@@ -266,12 +270,25 @@ impl pallet_sudo::Config for Runtime {
 
 impl pallet_parentchain::Config for Runtime {
 	type WeightInfo = ();
+	// about 40 minutes of parentchain blocks, it bounds how precisely the native data
+	// provider can find the block of a date
+	type CheckpointInterval = ConstU32<200>;
+}
+
+/// The web3 networks registered on the parentchain, the executor mirrors them into the state
+/// on each parentchain block.
+pub struct ParentchainWeb3Networks;
+impl Get<Web3NetworkRegistry> for ParentchainWeb3Networks {
+	fn get() -> Web3NetworkRegistry {
+		frame_support::storage::unhashed::get(&web3network_registry_key()).unwrap_or_default()
+	}
 }
 
 impl pallet_imt::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type ManageOrigin = EnsureRoot<AccountId>;
 	type MaxIDGraphLength = ConstU32<64>;
+	type Web3NetworkRegistry = ParentchainWeb3Networks;
 }
 
 // The plain sgx-runtime without the `evm-pallet`
@@ -3,12 +3,12 @@ use super::*;
 use crate::Runtime;
 use frame_support::{traits::OnRuntimeUpgrade, weights::Weight};
 
-// This is just an example of how to write a custom migration
-// It has no effect on tee-worker running
+// Run when the `spec_version` changes, see `Stf::on_runtime_upgrade`
 // For more details, see: https://docs.substrate.io/maintain/runtime-upgrades/#storage-migration
 pub struct Upgrade;
 impl OnRuntimeUpgrade for Upgrade {
 	fn on_runtime_upgrade() -> Weight {
 		pallet_imt::migrations::migrate_to_v1::<Runtime, IdentityManagement>()
+			.saturating_add(pallet_imt::migrations::migrate_to_v3::<Runtime, IdentityManagement>())
 	}
 }
@@ -90,6 +90,8 @@ pub enum StfError {
 	DeactivateIdentityFailed(ErrorDetail),
 	#[display(fmt = "ActivateIdentityFailed: {:?}", _0)]
 	ActivateIdentityFailed(ErrorDetail),
+	#[display(fmt = "RemoveIdentityFailed: {:?}", _0)]
+	RemoveIdentityFailed(ErrorDetail),
 	#[display(fmt = "RequestVCFailed: {:?} {:?}", _0, _1)]
 	RequestVCFailed(Assertion, ErrorDetail),
 	SetScheduledMrEnclaveFailed,
@@ -118,6 +120,7 @@ impl From<IMPError> for StfError {
 			IMPError::LinkIdentityFailed(d) => StfError::LinkIdentityFailed(d),
 			IMPError::DeactivateIdentityFailed(d) => StfError::DeactivateIdentityFailed(d),
 			IMPError::ActivateIdentityFailed(d) => StfError::ActivateIdentityFailed(d),
+			IMPError::RemoveIdentityFailed(d) => StfError::RemoveIdentityFailed(d),
 			_ => StfError::UnclassifiedError,
 		}
 	}
@@ -141,6 +144,7 @@ impl StfError {
 			StfError::LinkIdentityFailed(d) => IMPError::LinkIdentityFailed(d.clone()),
 			StfError::DeactivateIdentityFailed(d) => IMPError::DeactivateIdentityFailed(d.clone()),
 			StfError::ActivateIdentityFailed(d) => IMPError::ActivateIdentityFailed(d.clone()),
+			StfError::RemoveIdentityFailed(d) => IMPError::RemoveIdentityFailed(d.clone()),
 			_ => IMPError::UnclassifiedError(ErrorDetail::StfError(ErrorString::truncate_from(
 				format!("{:?}", self).as_bytes().to_vec(),
 			))),
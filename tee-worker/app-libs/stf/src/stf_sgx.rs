@@ -35,6 +35,7 @@ use itp_stf_primitives::types::ShardIdentifier;
 use itp_storage::storage_value_key;
 use itp_types::{OpaqueCall, H256};
 use itp_utils::stringify::account_id_to_string;
+use litentry_primitives::web3network_registry_key;
 use log::*;
 use sp_runtime::traits::StaticLookup;
 use std::{fmt::Debug, format, prelude::v1::*, sync::Arc, vec};
@@ -117,8 +118,9 @@ where
 	}
 
 	fn storage_hashes_to_update_on_block() -> Vec<Vec<u8>> {
-		// Get all shards that are currently registered.
-		vec![shards_key_hash()]
+		// Get all shards that are currently registered, and the registered web3 networks,
+		// they are mirrored into every shard.
+		vec![shards_key_hash(), web3network_registry_key().to_vec()]
 	}
 }
 
@@ -38,8 +38,9 @@ use itp_stf_primitives::types::{AccountId, KeyPair, ShardIdentifier};
 pub use itp_types::{OpaqueCall, H256};
 use itp_utils::stringify::account_id_to_string;
 pub use litentry_primitives::{
-	aes_encrypt_default, all_evm_web3networks, all_substrate_web3networks, AesOutput, Assertion,
-	ErrorDetail, IMPError, Identity, ParentchainAccountId, ParentchainBlockNumber,
+	aes_encrypt_default, all_bitcoin_web3networks, all_evm_web3networks, all_solana_web3networks,
+	all_substrate_web3networks, AesOutput, Assertion, ErrorDetail, IMPError, Identity,
+	IdentityExport, LinkProof, ParentchainAccountId, ParentchainBlockNumber, SignedIDGraphExport,
 	UserShieldingKeyNonceType, UserShieldingKeyType, VCMPError, ValidationData, Web3Network,
 };
 use log::*;
@@ -135,11 +136,45 @@ pub enum TrustedCall {
 		Vec<Web3Network>,
 		H256,
 	),
+	remove_identity(Identity, Identity, Identity, H256),
+	// the IDGraph of `who` is signed by the enclave and returned encrypted with the user shielding
+	// key, see `ExportIDGraphResponse`
+	export_id_graph(Identity, Identity, H256),
+	// the `SignedIDGraphExport` of `export_id_graph`, it must be encrypted with the same user
+	// shielding key as the one set in this shard
+	import_id_graph(Identity, Identity, AesOutput, H256),
+	// the VC is issued about the pairwise-pseudonymous DID of `who` towards the given verifier
+	// (e.g. its origin) and registered in the `VCRegistry` under the same pseudonym
+	request_pairwise_vc(Identity, Identity, Assertion, Vec<u8>, H256),
+	// the renewal of the VC with the given index, see `VCManagement::renew_vc`
+	renew_vc(Identity, Identity, Assertion, H256, H256),
 
 	// the following trusted calls should not be requested directly from external
 	// they are guarded by the signature check (either root or enclave_signer_account)
-	link_identity_callback(Identity, Identity, Identity, Vec<Web3Network>, H256),
-	request_vc_callback(Identity, Identity, Assertion, H256, H256, Vec<u8>, H256),
+	link_identity_callback(
+		Identity,
+		Identity,
+		Identity,
+		Vec<Web3Network>,
+		bool,
+		Option<LinkProof>,
+		H256,
+	),
+	export_id_graph_callback(Identity, Identity, SignedIDGraphExport, H256),
+	import_id_graph_callback(Identity, Identity, Vec<IdentityExport>, H256),
+	// the optional `AccountId` is the pseudonym a pairwise VC is registered under, the optional
+	// `H256` the index of the VC that is renewed
+	request_vc_callback(
+		Identity,
+		Identity,
+		Assertion,
+		H256,
+		H256,
+		Vec<u8>,
+		Option<AccountId>,
+		Option<H256>,
+		H256,
+	),
 	handle_imp_error(Identity, Option<Identity>, IMPError, H256),
 	handle_vcmp_error(Identity, Option<Identity>, VCMPError, H256),
 	send_erroneous_parentchain_call(Identity),
@@ -169,7 +204,14 @@ impl TrustedCall {
 			TrustedCall::set_identity_networks(sender_identity, ..) => sender_identity,
 			TrustedCall::set_user_shielding_key_with_networks(sender_identity, ..) =>
 				sender_identity,
+			TrustedCall::remove_identity(sender_identity, ..) => sender_identity,
+			TrustedCall::export_id_graph(sender_identity, ..) => sender_identity,
+			TrustedCall::import_id_graph(sender_identity, ..) => sender_identity,
+			TrustedCall::request_pairwise_vc(sender_identity, ..) => sender_identity,
+			TrustedCall::renew_vc(sender_identity, ..) => sender_identity,
 			TrustedCall::link_identity_callback(sender_identity, ..) => sender_identity,
+			TrustedCall::export_id_graph_callback(sender_identity, ..) => sender_identity,
+			TrustedCall::import_id_graph_callback(sender_identity, ..) => sender_identity,
 			TrustedCall::request_vc_callback(sender_identity, ..) => sender_identity,
 			TrustedCall::handle_imp_error(sender_identity, ..) => sender_identity,
 			TrustedCall::handle_vcmp_error(sender_identity, ..) => sender_identity,
@@ -512,6 +554,8 @@ where
 				let web3networks = match who {
 					Identity::Substrate(..) => all_substrate_web3networks(),
 					Identity::Evm(..) => all_evm_web3networks(),
+					Identity::Bitcoin(..) => all_bitcoin_web3networks(),
+					Identity::Solana(..) => all_solana_web3networks(),
 					_ => vec![],
 				};
 				Self::handle_set_user_shielding_key(
@@ -663,7 +707,57 @@ where
 				rpc_response_value = res.encode();
 				Ok(())
 			},
-			TrustedCall::link_identity_callback(signer, who, identity, web3networks, hash) => {
+			TrustedCall::remove_identity(signer, who, identity, hash) => {
+				debug!("remove_identity, who: {}", account_id_to_string(&who));
+				let account = SgxParentchainTypeConverter::convert(
+					who.to_account_id().ok_or(Self::Error::InvalidAccount)?,
+				);
+				let call_index = node_metadata_repo
+					.get_from_metadata(|m| m.identity_removed_call_indexes())??;
+
+				let key = Self::remove_identity_internal(
+					signer.to_account_id().ok_or(Self::Error::InvalidAccount)?,
+					who,
+					identity.clone(),
+				)
+				.map_err(|e| {
+					debug!("pushing error event ... error: {}", e);
+					add_call_from_imp_error(
+						calls,
+						node_metadata_repo,
+						Some(account.clone()),
+						e.to_imp_error(),
+						hash,
+					);
+					e
+				})?;
+
+				debug!("pushing identity_removed event ...");
+				calls.push(OpaqueCall::from_tuple(&(
+					call_index,
+					account.clone(),
+					aes_encrypt_default(&key, &identity.encode()),
+					hash,
+				)));
+
+				debug!("populating identity_removed rpc reponse ...");
+				let res = RemoveIdentityResponse {
+					account,
+					identity: aes_encrypt_default(&key, &identity.encode()),
+					req_ext_hash: hash,
+				};
+				rpc_response_value = res.encode();
+				Ok(())
+			},
+			TrustedCall::link_identity_callback(
+				signer,
+				who,
+				identity,
+				web3networks,
+				contract_wallet,
+				proof,
+				hash,
+			) => {
 				debug!("link_identity_callback, who: {}", account_id_to_string(&who));
 				let account = SgxParentchainTypeConverter::convert(
 					who.to_account_id().ok_or(Self::Error::InvalidAccount)?,
@@ -676,6 +770,8 @@ where
 					who.clone(),
 					identity.clone(),
 					web3networks,
+					contract_wallet,
+					proof,
 				)
 				.map_err(|e| {
 					debug!("pushing error event ... error: {}", e);
@@ -707,6 +803,120 @@ where
 				rpc_response_value = res.encode();
 				Ok(())
 			},
+			TrustedCall::export_id_graph(signer, who, hash) => {
+				debug!("export_id_graph, who: {}", account_id_to_string(&who));
+				let account = SgxParentchainTypeConverter::convert(
+					who.to_account_id().ok_or(Self::Error::InvalidAccount)?,
+				);
+				Self::export_id_graph_internal(
+					signer.to_account_id().ok_or(Self::Error::InvalidAccount)?,
+					who,
+					top_hash,
+					hash,
+					shard,
+				)
+				.map_err(|e| {
+					add_call_from_imp_error(
+						calls,
+						node_metadata_repo,
+						Some(account),
+						e.to_imp_error(),
+						hash,
+					);
+					e
+				})?;
+				// see `RpcResponder::update_status_event` why it's set to `true.encode()` here
+				rpc_response_value = true.encode();
+				Ok(())
+			},
+			TrustedCall::export_id_graph_callback(signer, who, signed, hash) => {
+				debug!("export_id_graph_callback, who: {}", account_id_to_string(&who));
+				let account = SgxParentchainTypeConverter::convert(
+					who.to_account_id().ok_or(Self::Error::InvalidAccount)?,
+				);
+				let key = Self::export_id_graph_callback_internal(
+					signer.to_account_id().ok_or(Self::Error::InvalidAccount)?,
+					who,
+				)
+				.map_err(|e| {
+					debug!("pushing error event ... error: {}", e);
+					add_call_from_imp_error(
+						calls,
+						node_metadata_repo,
+						Some(account.clone()),
+						e.to_imp_error(),
+						hash,
+					);
+					e
+				})?;
+
+				debug!("populating export_id_graph rpc reponse ...");
+				let res = ExportIDGraphResponse {
+					account,
+					export: aes_encrypt_default(&key, &signed.encode()),
+					req_ext_hash: hash,
+				};
+				rpc_response_value = res.encode();
+				Ok(())
+			},
+			TrustedCall::import_id_graph(signer, who, export, hash) => {
+				debug!("import_id_graph, who: {}", account_id_to_string(&who));
+				let account = SgxParentchainTypeConverter::convert(
+					who.to_account_id().ok_or(Self::Error::InvalidAccount)?,
+				);
+				Self::import_id_graph_internal(
+					signer.to_account_id().ok_or(Self::Error::InvalidAccount)?,
+					who,
+					export,
+					top_hash,
+					hash,
+					shard,
+				)
+				.map_err(|e| {
+					add_call_from_imp_error(
+						calls,
+						node_metadata_repo,
+						Some(account),
+						e.to_imp_error(),
+						hash,
+					);
+					e
+				})?;
+				// see `RpcResponder::update_status_event` why it's set to `true.encode()` here
+				rpc_response_value = true.encode();
+				Ok(())
+			},
+			TrustedCall::import_id_graph_callback(signer, who, identities, hash) => {
+				debug!("import_id_graph_callback, who: {}", account_id_to_string(&who));
+				let account = SgxParentchainTypeConverter::convert(
+					who.to_account_id().ok_or(Self::Error::InvalidAccount)?,
+				);
+				let (key, imported) = Self::import_id_graph_callback_internal(
+					signer.to_account_id().ok_or(Self::Error::InvalidAccount)?,
+					who,
+					identities,
+				)
+				.map_err(|e| {
+					debug!("pushing error event ... error: {}", e);
+					add_call_from_imp_error(
+						calls,
+						node_metadata_repo,
+						Some(account.clone()),
+						e.to_imp_error(),
+						hash,
+					);
+					e
+				})?;
+
+				debug!("populating import_id_graph rpc reponse ...");
+				let res = ImportIDGraphResponse {
+					account,
+					identities: aes_encrypt_default(&key, &imported.encode()),
+					req_ext_hash: hash,
+				};
+				rpc_response_value = res.encode();
+				Ok(())
+			},
 			TrustedCall::request_vc(signer, who, assertion, hash) => {
 				debug!(
 					"request_vc, who: {}, assertion: {:?}",
@@ -721,6 +931,77 @@ where
 					signer.to_account_id().ok_or(Self::Error::InvalidAccount)?,
 					who,
 					assertion,
+					None,
+					None,
+					top_hash,
+					hash,
+					shard,
+				)
+				.map_err(|e| {
+					debug!("pushing error event ... error: {}", e);
+					add_call_from_vcmp_error(
+						calls,
+						node_metadata_repo,
+						Some(account),
+						e.to_vcmp_error(),
+						hash,
+					);
+					e
+				})?;
+				rpc_response_value = true.encode();
+				Ok(())
+			},
+			TrustedCall::request_pairwise_vc(signer, who, assertion, verifier, hash) => {
+				debug!(
+					"request_pairwise_vc, who: {}, assertion: {:?}",
+					account_id_to_string(&who),
+					assertion
+				);
+
+				let account = SgxParentchainTypeConverter::convert(
+					who.to_account_id().ok_or(Self::Error::InvalidAccount)?,
+				);
+				Self::request_vc_internal(
+					signer.to_account_id().ok_or(Self::Error::InvalidAccount)?,
+					who,
+					assertion,
+					Some(verifier),
+					None,
+					top_hash,
+					hash,
+					shard,
+				)
+				.map_err(|e| {
+					debug!("pushing error event ... error: {}", e);
+					add_call_from_vcmp_error(
+						calls,
+						node_metadata_repo,
+						Some(account),
+						e.to_vcmp_error(),
+						hash,
+					);
+					e
+				})?;
+				rpc_response_value = true.encode();
+				Ok(())
+			},
+			TrustedCall::renew_vc(signer, who, assertion, index, hash) => {
+				debug!(
+					"renew_vc, who: {}, index: {:?}, assertion: {:?}",
+					account_id_to_string(&who),
+					index,
+					assertion
+				);
+
+				let account = SgxParentchainTypeConverter::convert(
+					who.to_account_id().ok_or(Self::Error::InvalidAccount)?,
+				);
+				Self::request_vc_internal(
+					signer.to_account_id().ok_or(Self::Error::InvalidAccount)?,
+					who,
+					assertion,
+					None,
+					Some(index),
 					top_hash,
 					hash,
 					shard,
@@ -746,6 +1027,8 @@ where
 				vc_index,
 				vc_hash,
 				vc_payload,
+				pseudonym,
+				renewed_index,
 				hash,
 			) => {
 				debug!(
@@ -756,9 +1039,6 @@ where
 				let account = SgxParentchainTypeConverter::convert(
 					who.to_account_id().ok_or(Self::Error::InvalidAccount)?,
 				);
-				let call_index =
-					node_metadata_repo.get_from_metadata(|m| m.vc_issued_call_indexes())??;
-
 				let key = Self::request_vc_callback_internal(
 					signer.to_account_id().ok_or(Self::Error::InvalidAccount)?,
 					who,
@@ -776,15 +1056,41 @@ where
 					e
 				})?;
 
-				calls.push(OpaqueCall::from_tuple(&(
-					call_index,
-					account.clone(),
-					assertion.clone(),
-					vc_index,
-					vc_hash,
-					aes_encrypt_default(&key, &vc_payload),
-					hash,
-				)));
+				// a pairwise VC must not be linked to `who` on the parachain
+				let registry_account = match pseudonym {
+					Some(pseudonym) => SgxParentchainTypeConverter::convert(pseudonym),
+					None => account.clone(),
+				};
+				let vc = aes_encrypt_default(&key, &vc_payload);
+				match renewed_index {
+					Some(old_index) => {
+						let call_index = node_metadata_repo
+							.get_from_metadata(|m| m.vc_renewed_call_indexes())??;
+						calls.push(OpaqueCall::from_tuple(&(
+							call_index,
+							registry_account,
+							assertion.clone(),
+							vc_index,
+							vc_hash,
+							vc,
+							old_index,
+							hash,
+						)));
+					},
+					None => {
+						let call_index = node_metadata_repo
+							.get_from_metadata(|m| m.vc_issued_call_indexes())??;
+						calls.push(OpaqueCall::from_tuple(&(
+							call_index,
+							registry_account,
+							assertion.clone(),
+							vc_index,
+							vc_hash,
+							vc,
+							hash,
+						)));
+					},
+				}
 				let res = RequestVCResponse {
 					account,
 					assertion,
@@ -865,6 +1171,13 @@ where
 			TrustedCall::set_identity_networks(..) => debug!("No storage updates needed..."),
 			TrustedCall::set_user_shielding_key_with_networks(..) =>
 				debug!("No storage updates needed..."),
+			TrustedCall::remove_identity(..) => debug!("No storage updates needed..."),
+			TrustedCall::export_id_graph(..) => debug!("No storage updates needed..."),
+			TrustedCall::import_id_graph(..) => debug!("No storage updates needed..."),
+			TrustedCall::request_pairwise_vc(..) => debug!("No storage updates needed..."),
+			TrustedCall::renew_vc(..) => debug!("No storage updates needed..."),
+			TrustedCall::export_id_graph_callback(..) => debug!("No storage updates needed..."),
+			TrustedCall::import_id_graph_callback(..) => debug!("No storage updates needed..."),
 			TrustedCall::handle_imp_error(..) => debug!("No storage updates needed..."),
 			TrustedCall::handle_vcmp_error(..) => debug!("No storage updates needed..."),
 			TrustedCall::send_erroneous_parentchain_call(..) =>
@@ -23,18 +23,19 @@ use crate::{
 	trusted_call_rpc_response::SetUserShieldingKeyResponse,
 	AccountId, IdentityManagement, Runtime, StfError, StfResult, UserShieldingKeys,
 };
-use frame_support::{dispatch::UnfilteredDispatchable, ensure};
-use ita_sgx_runtime::RuntimeOrigin;
+use frame_support::{dispatch::UnfilteredDispatchable, ensure, traits::Get};
+use ita_sgx_runtime::{ParentchainWeb3Networks, RuntimeOrigin};
 use itp_node_api::metadata::NodeMetadataTrait;
 use itp_stf_primitives::types::ShardIdentifier;
 use itp_utils::stringify::account_id_to_string;
 use lc_stf_task_sender::{
 	stf_task_sender::{SendStfRequest, StfRequestSender},
-	AssertionBuildRequest, IdentityVerificationRequest, RequestType,
+	AssertionBuildRequest, IDGraphExportRequest, IDGraphImportRequest, IdentityVerificationRequest,
+	RequestType,
 };
 use litentry_primitives::{
-	Assertion, ErrorDetail, Identity, IdentityNetworkTuple, UserShieldingKeyType, ValidationData,
-	Web3Network,
+	aes_decrypt, Assertion, ErrorDetail, Identity, IdentityExport, IdentityNetworkTuple, LinkProof,
+	SignedIDGraphExport, UserShieldingKeyType, ValidationData, Web3Network,
 };
 use log::*;
 use std::{sync::Arc, vec::Vec};
@@ -197,10 +198,32 @@ impl TrustedCallSigned {
 		Ok(key)
 	}
 
+	pub fn remove_identity_internal(
+		signer: AccountId,
+		who: Identity,
+		identity: Identity,
+	) -> StfResult<UserShieldingKeyType> {
+		ensure!(
+			ensure_enclave_signer_or_self(&signer, who.to_account_id()),
+			StfError::RemoveIdentityFailed(ErrorDetail::UnauthorizedSigner)
+		);
+		let key = IdentityManagement::user_shielding_keys(&who)
+			.ok_or(StfError::RemoveIdentityFailed(ErrorDetail::UserShieldingKeyNotFound))?;
+
+		IMTCall::remove_identity { who, identity }
+			.dispatch_bypass_filter(RuntimeOrigin::root())
+			.map_err(|e| StfError::RemoveIdentityFailed(e.into()))?;
+
+		Ok(key)
+	}
+
+	#[allow(clippy::too_many_arguments)]
 	pub fn request_vc_internal(
 		signer: AccountId,
 		who: Identity,
 		assertion: Assertion,
+		verifier: Option<Vec<u8>>,
+		renewed_index: Option<H256>,
 		top_hash: H256,
 		req_ext_hash: H256,
 		shard: &ShardIdentifier,
@@ -220,7 +243,8 @@ impl TrustedCallSigned {
 		);
 
 		let id_graph = IMT::get_id_graph(&who, usize::MAX);
-		let assertion_networks = assertion.get_supported_web3networks();
+		let assertion_networks =
+			assertion.get_supported_web3networks(&ParentchainWeb3Networks::get());
 		let identities: Vec<IdentityNetworkTuple> = id_graph
 			.into_iter()
 			.filter(|item| item.1.is_active())
@@ -242,6 +266,8 @@ impl TrustedCallSigned {
 			who,
 			assertion: assertion.clone(),
 			identities,
+			verifier,
+			renewed_index,
 			top_hash,
 			req_ext_hash,
 		}
@@ -258,6 +284,8 @@ impl TrustedCallSigned {
 		who: Identity,
 		identity: Identity,
 		web3networks: Vec<Web3Network>,
+		contract_wallet: bool,
+		proof: Option<LinkProof>,
 	) -> StfResult<UserShieldingKeyType> {
 		// important! The signer has to be enclave_signer_account, as this TrustedCall can only be constructed internally
 		ensure_enclave_signer(&signer)
@@ -266,13 +294,137 @@ impl TrustedCallSigned {
 		let key = IdentityManagement::user_shielding_keys(&who)
 			.ok_or(StfError::LinkIdentityFailed(ErrorDetail::UserShieldingKeyNotFound))?;
 
-		IMTCall::link_identity { who, identity, web3networks }
-			.dispatch_bypass_filter(RuntimeOrigin::root())
-			.map_err(|e| StfError::LinkIdentityFailed(e.into()))?;
+		IMTCall::link_identity {
+			who: who.clone(),
+			identity: identity.clone(),
+			web3networks,
+			contract_wallet,
+		}
+		.dispatch_bypass_filter(RuntimeOrigin::root())
+		.map_err(|e| StfError::LinkIdentityFailed(e.into()))?;
+
+		if let Some(proof) = proof {
+			IMTCall::set_link_proof { who, identity, proof }
+				.dispatch_bypass_filter(RuntimeOrigin::root())
+				.map_err(|e| StfError::LinkIdentityFailed(e.into()))?;
+		}
+
+		Ok(key)
+	}
+
+	pub fn export_id_graph_internal(
+		signer: AccountId,
+		who: Identity,
+		top_hash: H256,
+		req_ext_hash: H256,
+		shard: &ShardIdentifier,
+	) -> StfResult<()> {
+		ensure!(
+			ensure_enclave_signer_or_self(&signer, who.to_account_id()),
+			StfError::LinkIdentityFailed(ErrorDetail::UnauthorizedSigner)
+		);
+		ensure!(
+			IdentityManagement::user_shielding_keys(&who).is_some(),
+			StfError::LinkIdentityFailed(ErrorDetail::UserShieldingKeyNotFound)
+		);
+
+		// it's signed by the stf-task receiver, which has access to the enclave signer
+		let request: RequestType = IDGraphExportRequest {
+			shard: *shard,
+			export: IdentityManagement::export_id_graph(&who),
+			who,
+			top_hash,
+			req_ext_hash,
+		}
+		.into();
+		StfRequestSender::new()
+			.send_stf_request(request)
+			.map_err(|_| StfError::LinkIdentityFailed(ErrorDetail::SendStfRequestFailed))
+	}
+
+	pub fn export_id_graph_callback_internal(
+		signer: AccountId,
+		who: Identity,
+	) -> StfResult<UserShieldingKeyType> {
+		// important! The signer has to be enclave_signer_account, as this TrustedCall can only be constructed internally
+		ensure_enclave_signer(&signer)
+			.map_err(|_| StfError::LinkIdentityFailed(ErrorDetail::UnauthorizedSigner))?;
+
+		let key = IdentityManagement::user_shielding_keys(&who)
+			.ok_or(StfError::LinkIdentityFailed(ErrorDetail::UserShieldingKeyNotFound))?;
 
 		Ok(key)
 	}
 
+	pub fn import_id_graph_internal(
+		signer: AccountId,
+		who: Identity,
+		export: AesOutput,
+		top_hash: H256,
+		req_ext_hash: H256,
+		shard: &ShardIdentifier,
+	) -> StfResult<()> {
+		ensure!(
+			ensure_enclave_signer_or_self(&signer, who.to_account_id()),
+			StfError::LinkIdentityFailed(ErrorDetail::UnauthorizedSigner)
+		);
+
+		let key = IdentityManagement::user_shielding_keys(&who)
+			.ok_or(StfError::LinkIdentityFailed(ErrorDetail::UserShieldingKeyNotFound))?;
+
+		// the signature of the export and the link proofs are verified by the stf-task receiver
+		let export = aes_decrypt(&key, &export)
+			.and_then(|data| SignedIDGraphExport::decode(&mut data.as_slice()).ok())
+			.filter(|signed| signed.export.who == who)
+			.ok_or(StfError::LinkIdentityFailed(ErrorDetail::InvalidIDGraphExport))?;
+
+		let request: RequestType =
+			IDGraphImportRequest { shard: *shard, who, export, key, top_hash, req_ext_hash }.into();
+		StfRequestSender::new()
+			.send_stf_request(request)
+			.map_err(|_| StfError::LinkIdentityFailed(ErrorDetail::SendStfRequestFailed))
+	}
+
+	// links the identities whose proofs are verified, those which can't be linked in this shard,
+	// e.g. as they are linked to another account already, are skipped. Each identity is imported
+	// with a single call, which writes nothing if it fails, so a skipped one leaves no trace.
+	// Returns the imported identities.
+	pub fn import_id_graph_callback_internal(
+		signer: AccountId,
+		who: Identity,
+		identities: Vec<IdentityExport>,
+	) -> StfResult<(UserShieldingKeyType, Vec<Identity>)> {
+		// important! The signer has to be enclave_signer_account, as this TrustedCall can only be constructed internally
+		ensure_enclave_signer(&signer)
+			.map_err(|_| StfError::LinkIdentityFailed(ErrorDetail::UnauthorizedSigner))?;
+
+		let key = IdentityManagement::user_shielding_keys(&who)
+			.ok_or(StfError::LinkIdentityFailed(ErrorDetail::UserShieldingKeyNotFound))?;
+
+		let mut imported = Vec::new();
+		for item in identities {
+			let proof = match item.proof {
+				Some(proof) => proof,
+				None => continue,
+			};
+			match (IMTCall::import_identity {
+				who: who.clone(),
+				identity: item.identity.clone(),
+				web3networks: item.web3networks,
+				contract_wallet: item.contract_wallet,
+				status: item.status,
+				proof,
+			})
+			.dispatch_bypass_filter(RuntimeOrigin::root())
+			{
+				Ok(_) => imported.push(item.identity),
+				Err(err) => warn!("skip importing {:?}: {:?}", item.identity, err.error),
+			}
+		}
+
+		Ok((key, imported))
+	}
+
 	pub fn request_vc_callback_internal(
 		signer: AccountId,
 		who: Identity,
@@ -289,3 +441,211 @@ impl TrustedCallSigned {
 		Ok(key)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::ENCLAVE_ACCOUNT_KEY;
+	use itp_sgx_externalities::{SgxExternalities, SgxExternalitiesTrait};
+	use itp_storage::storage_value_key;
+	use lc_stf_task_sender::stf_task_sender::init_stf_task_sender_storage;
+	use litentry_primitives::{
+		aes_encrypt_default, IDGraphExport, IdentityStatus, LitentryMultiSignature,
+		Web3CommonValidationData, Web3ValidationData,
+	};
+	use sp_core::{sr25519, Pair};
+	use sp_keyring::AccountKeyring;
+
+	const KEY: UserShieldingKeyType = [7u8; 32];
+
+	fn enclave() -> AccountId {
+		AccountKeyring::Eve.to_account_id()
+	}
+
+	fn who() -> Identity {
+		AccountKeyring::Alice.public().into()
+	}
+
+	fn identity(seed: u8) -> Identity {
+		sr25519::Pair::from_seed(&[seed; 32]).public().into()
+	}
+
+	// the proof isn't verified again by the callback, so its content doesn't matter
+	fn identity_export(identity: Identity, proof: bool) -> IdentityExport {
+		IdentityExport {
+			identity,
+			web3networks: vec![Web3Network::Litentry],
+			status: IdentityStatus::Inactive,
+			contract_wallet: false,
+			proof: proof.then(|| LinkProof {
+				shard: Default::default(),
+				mrenclave: [0u8; 32],
+				sidechain_nonce: 0,
+				key_nonce: [0u8; 12],
+				web3networks: vec![Web3Network::Litentry],
+				validation_data: Web3ValidationData::Substrate(Web3CommonValidationData {
+					message: Default::default(),
+					signature: LitentryMultiSignature::Sr25519(sr25519::Signature([0u8; 64])),
+				}),
+			}),
+		}
+	}
+
+	fn new_state() -> SgxExternalities {
+		let mut state = SgxExternalities::default();
+		state.execute_with(|| {
+			sp_io::storage::set(
+				&storage_value_key("Sudo", ENCLAVE_ACCOUNT_KEY),
+				&enclave().encode(),
+			);
+			IMTCall::set_user_shielding_key {
+				who: who(),
+				key: KEY,
+				networks: vec![Web3Network::Litentry],
+			}
+			.dispatch_bypass_filter(RuntimeOrigin::root())
+			.unwrap();
+		});
+		state
+	}
+
+	#[test]
+	fn import_id_graph_callback_internal_works() {
+		new_state().execute_with(|| {
+			let linked = identity(3);
+			IMTCall::link_identity {
+				who: identity(4),
+				identity: linked.clone(),
+				web3networks: vec![Web3Network::Litentry],
+				contract_wallet: false,
+			}
+			.dispatch_bypass_filter(RuntimeOrigin::root())
+			.unwrap();
+
+			let identities = vec![
+				identity_export(identity(1), true),
+				// no proof
+				identity_export(identity(2), false),
+				// linked to another account already
+				identity_export(linked.clone(), true),
+				// the prime identity
+				identity_export(who(), true),
+				// a substrate identity can't be linked with an evm network
+				IdentityExport {
+					web3networks: vec![Web3Network::Ethereum],
+					..identity_export(identity(5), true)
+				},
+			];
+			let (key, imported) =
+				TrustedCallSigned::import_id_graph_callback_internal(enclave(), who(), identities)
+					.unwrap();
+			assert_eq!(key, KEY);
+			assert_eq!(imported, vec![identity(1)]);
+
+			let context = IdentityManagement::id_graphs(who(), identity(1)).unwrap();
+			assert_eq!(context.status, IdentityStatus::Inactive);
+			assert!(IdentityManagement::link_proofs(who(), identity(1)).is_some());
+
+			// nothing is left of the skipped identities
+			for skipped in [identity(2), linked, identity(5)] {
+				assert!(IdentityManagement::id_graphs(who(), &skipped).is_none());
+				assert!(IdentityManagement::link_proofs(who(), &skipped).is_none());
+			}
+		});
+	}
+
+	#[test]
+	fn import_id_graph_callback_internal_requires_enclave_signer() {
+		new_state().execute_with(|| {
+			assert_eq!(
+				TrustedCallSigned::import_id_graph_callback_internal(
+					AccountKeyring::Alice.to_account_id(),
+					who(),
+					vec![identity_export(identity(1), true)],
+				),
+				Err(StfError::LinkIdentityFailed(ErrorDetail::UnauthorizedSigner))
+			);
+			assert!(IdentityManagement::id_graphs(who(), identity(1)).is_none());
+		});
+	}
+
+	// the requests are tested together, as they share the global stf task sender
+	#[test]
+	fn id_graph_export_and_import_send_requests() {
+		let receiver = init_stf_task_sender_storage().unwrap();
+		let shard = ShardIdentifier::default();
+		new_state().execute_with(|| {
+			TrustedCallSigned::export_id_graph_internal(
+				who().to_account_id().unwrap(),
+				who(),
+				H256::default(),
+				H256::default(),
+				&shard,
+			)
+			.unwrap();
+			match receiver.try_recv().unwrap() {
+				RequestType::IDGraphExport(req) => {
+					assert_eq!(req.who, who());
+					assert_eq!(req.export, IdentityManagement::export_id_graph(&who()));
+				},
+				r => panic!("unexpected request {:?}", r),
+			}
+
+			let signed = SignedIDGraphExport {
+				export: IDGraphExport {
+					who: who(),
+					identities: vec![identity_export(identity(1), true)],
+				},
+				signer: enclave(),
+				signature: vec![],
+			};
+			TrustedCallSigned::import_id_graph_internal(
+				who().to_account_id().unwrap(),
+				who(),
+				aes_encrypt_default(&KEY, &signed.encode()),
+				H256::default(),
+				H256::default(),
+				&shard,
+			)
+			.unwrap();
+			match receiver.try_recv().unwrap() {
+				RequestType::IDGraphImport(req) => {
+					assert_eq!(req.who, who());
+					assert_eq!(req.export, signed);
+					assert_eq!(req.key, KEY);
+				},
+				r => panic!("unexpected request {:?}", r),
+			}
+
+			// encrypted with another key
+			assert_eq!(
+				TrustedCallSigned::import_id_graph_internal(
+					who().to_account_id().unwrap(),
+					who(),
+					aes_encrypt_default(&[0u8; 32], &signed.encode()),
+					H256::default(),
+					H256::default(),
+					&shard,
+				),
+				Err(StfError::LinkIdentityFailed(ErrorDetail::InvalidIDGraphExport))
+			);
+			// the export of another identity
+			let other = SignedIDGraphExport {
+				export: IDGraphExport { who: identity(1), identities: vec![] },
+				..signed
+			};
+			assert_eq!(
+				TrustedCallSigned::import_id_graph_internal(
+					who().to_account_id().unwrap(),
+					who(),
+					aes_encrypt_default(&KEY, &other.encode()),
+					H256::default(),
+					H256::default(),
+					&shard,
+				),
+				Err(StfError::LinkIdentityFailed(ErrorDetail::InvalidIDGraphExport))
+			);
+			assert!(receiver.try_recv().is_err());
+		});
+	}
+}
@@ -52,6 +52,29 @@ pub(crate) struct ActivateIdentityResponse {
 	pub req_ext_hash: H256,
 }
 
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct RemoveIdentityResponse {
+	pub account: AccountId,
+	pub identity: AesOutput,
+	pub req_ext_hash: H256,
+}
+
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ExportIDGraphResponse {
+	pub account: AccountId,
+	// the encoded `SignedIDGraphExport`
+	pub export: AesOutput,
+	pub req_ext_hash: H256,
+}
+
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ImportIDGraphResponse {
+	pub account: AccountId,
+	// the imported identities, the others were skipped
+	pub identities: AesOutput,
+	pub req_ext_hash: H256,
+}
+
 #[derive(Encode, Decode, Clone, Debug, PartialEq, Eq)]
 pub(crate) struct SetIdentityNetworksResponse {
 	pub req_ext_hash: H256,
@@ -0,0 +1,44 @@
+// Copyright 2020-2023 Litentry Technologies GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{command_utils::get_chain_api, Cli, CliError, CliResult, CliResultOk};
+use itp_node_api::api_client::PalletTeerexApi;
+use lc_credentials::verify_credential;
+use std::fs;
+
+#[derive(Parser)]
+pub struct VerifyCredentialCommand {
+	/// Path to the JSON file of the credential
+	path: String,
+}
+
+impl VerifyCredentialCommand {
+	pub(crate) fn run(&self, cli: &Cli) -> CliResult {
+		let vc = fs::read_to_string(&self.path)
+			.map_err(|e| CliError::BaseOp { msg: format!("failed to read credential: {}", e) })?;
+
+		let chain_api = get_chain_api(cli);
+		let enclaves = chain_api
+			.all_enclaves(None)
+			.map_err(|e| CliError::BaseOp { msg: format!("failed to get enclaves: {:?}", e) })?;
+
+		let credential = verify_credential(&vc, &enclaves)
+			.map_err(|e| CliError::BaseOp { msg: format!("invalid credential: {}", e) })?;
+		println!("[+] Credential {} is valid until {}", credential.id, credential.valid_until);
+
+		Ok(CliResultOk::None)
+	}
+}
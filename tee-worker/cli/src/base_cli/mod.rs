@@ -23,6 +23,7 @@ use crate::{
 		litentry::{
 			link_identity::LinkIdentityCommand, set_heartbeat_timeout::SetHeartbeatTimeoutCommand,
 			set_user_shielding_key::SetUserShieldingKeyCommand,
+			verify_credential::VerifyCredentialCommand,
 		},
 		shield_funds::ShieldFundsCommand,
 		transfer::TransferCommand,
@@ -92,6 +93,9 @@ pub enum BaseCommand {
 
 	/// Set heartbeat timeout storage
 	SetHeartbeatTimeout(SetHeartbeatTimeoutCommand),
+
+	/// verify the proof of a verifiable credential against the enclave registry
+	VerifyCredential(VerifyCredentialCommand),
 }
 
 impl BaseCommand {
@@ -112,6 +116,7 @@ impl BaseCommand {
 			BaseCommand::SetUserShieldingKey(cmd) => cmd.run(cli),
 			BaseCommand::LinkIdentity(cmd) => cmd.run(cli),
 			BaseCommand::SetHeartbeatTimeout(cmd) => cmd.run(cli),
+			BaseCommand::VerifyCredential(cmd) => cmd.run(cli),
 		}
 	}
 }
@@ -18,19 +18,29 @@ use crate::{error::Error, Enclave, EnclaveResult};
 use codec::Encode;
 use frame_support::ensure;
 use itp_enclave_api_ffi as ffi;
+use itp_types::ShardIdentifier;
 use lc_data_providers::DataProviderConfig;
 use sgx_types::*;
 
 /// Trait to run a stf task handling thread inside the enclave.
 pub trait StfTaskHandler {
-	fn run_stf_task_handler(&self, data_provider_config: DataProviderConfig) -> EnclaveResult<()>;
+	fn run_stf_task_handler(
+		&self,
+		shard: &ShardIdentifier,
+		data_provider_config: DataProviderConfig,
+	) -> EnclaveResult<()>;
 }
 
 impl StfTaskHandler for Enclave {
-	fn run_stf_task_handler(&self, data_provider_config: DataProviderConfig) -> EnclaveResult<()> {
+	fn run_stf_task_handler(
+		&self,
+		shard: &ShardIdentifier,
+		data_provider_config: DataProviderConfig,
+	) -> EnclaveResult<()> {
 		let mut retval = sgx_status_t::SGX_SUCCESS;
 
-		let data_provider_config_enc = data_provider_config.encode();
+		// the configuration is bound to the shard it's set up for
+		let data_provider_config_enc = (shard, data_provider_config).encode();
 
 		let result = unsafe {
 			ffi::run_stf_task_handler(
@@ -38,6 +38,18 @@ pub enum EnclaveMetric {
 	TopPoolSizeDecrement,
 	ExchangeRateOracle(ExchangeRateOracleMetric),
 	// OracleMetric(OracleMetric<MetricsInfo>),
+	StfTaskPool(StfTaskPoolMetric),
+}
+
+/// A snapshot of the worker pool processing the stf tasks.
+#[derive(Encode, Decode, Debug, Default, Clone, PartialEq, Eq)]
+pub struct StfTaskPoolMetric {
+	pub queued: u64,
+	pub in_flight: u64,
+	pub processed: u64,
+	/// number of times a dispatch had to wait for a full worker queue
+	pub throttled: u64,
+	pub panicked: u64,
 }
 
 #[derive(Encode, Decode, Debug)]
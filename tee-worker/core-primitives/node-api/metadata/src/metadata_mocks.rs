@@ -55,15 +55,19 @@ pub struct NodeMetadataMock {
 	imp_link_identity: u8,
 	imp_deactivate_identity: u8,
 	imp_activate_identity: u8,
+	imp_remove_identity: u8,
 	imp_user_shielding_key_set: u8,
 	imp_identity_linked: u8,
 	imp_identity_deactivated: u8,
 	imp_identity_activated: u8,
+	imp_identity_removed: u8,
 	imp_some_error: u8,
 	// VCMP
 	vcmp_module: u8,
 	vcmp_request_vc: u8,
+	vcmp_renew_vc: u8,
 	vcmp_vc_issued: u8,
+	vcmp_vc_renewed: u8,
 	vcmp_some_error: u8,
 
 	utility_module: u8,
@@ -102,15 +106,19 @@ impl NodeMetadataMock {
 			imp_link_identity: 1u8,
 			imp_deactivate_identity: 2u8,
 			imp_activate_identity: 3u8,
+			imp_remove_identity: 5u8,
 			imp_user_shielding_key_set: 4u8,
 			imp_identity_linked: 6u8,
 			imp_identity_deactivated: 7u8,
 			imp_identity_activated: 7u8,
+			imp_identity_removed: 8u8,
 			imp_some_error: 9u8,
 
 			vcmp_module: 66u8,
 			vcmp_request_vc: 0u8,
+			vcmp_renew_vc: 13u8,
 			vcmp_vc_issued: 3u8,
+			vcmp_vc_renewed: 32u8,
 			vcmp_some_error: 9u8,
 
 			utility_module: 80u8,
@@ -200,6 +208,10 @@ impl IMPCallIndexes for NodeMetadataMock {
 		Ok([self.imp_module, self.imp_activate_identity])
 	}
 
+	fn remove_identity_call_indexes(&self) -> Result<[u8; 2]> {
+		Ok([self.imp_module, self.imp_remove_identity])
+	}
+
 	fn user_shielding_key_set_call_indexes(&self) -> Result<[u8; 2]> {
 		Ok([self.imp_module, self.imp_user_shielding_key_set])
 	}
@@ -216,6 +228,10 @@ impl IMPCallIndexes for NodeMetadataMock {
 		Ok([self.imp_module, self.imp_identity_activated])
 	}
 
+	fn identity_removed_call_indexes(&self) -> Result<[u8; 2]> {
+		Ok([self.imp_module, self.imp_identity_removed])
+	}
+
 	fn imp_some_error_call_indexes(&self) -> Result<[u8; 2]> {
 		Ok([self.imp_module, self.imp_some_error])
 	}
@@ -226,10 +242,18 @@ impl VCMPCallIndexes for NodeMetadataMock {
 		Ok([self.vcmp_module, self.vcmp_request_vc])
 	}
 
+	fn renew_vc_call_indexes(&self) -> Result<[u8; 2]> {
+		Ok([self.vcmp_module, self.vcmp_renew_vc])
+	}
+
 	fn vc_issued_call_indexes(&self) -> Result<[u8; 2]> {
 		Ok([self.vcmp_module, self.vcmp_vc_issued])
 	}
 
+	fn vc_renewed_call_indexes(&self) -> Result<[u8; 2]> {
+		Ok([self.vcmp_module, self.vcmp_vc_renewed])
+	}
+
 	fn vcmp_some_error_call_indexes(&self) -> Result<[u8; 2]> {
 		Ok([self.vcmp_module, self.vcmp_some_error])
 	}
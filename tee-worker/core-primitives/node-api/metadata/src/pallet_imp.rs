@@ -25,10 +25,12 @@ pub trait IMPCallIndexes {
 	fn link_identity_call_indexes(&self) -> Result<[u8; 2]>;
 	fn deactivate_identity_call_indexes(&self) -> Result<[u8; 2]>;
 	fn activate_identity_call_indexes(&self) -> Result<[u8; 2]>;
+	fn remove_identity_call_indexes(&self) -> Result<[u8; 2]>;
 	fn user_shielding_key_set_call_indexes(&self) -> Result<[u8; 2]>;
 	fn identity_linked_call_indexes(&self) -> Result<[u8; 2]>;
 	fn identity_deactivated_call_indexes(&self) -> Result<[u8; 2]>;
 	fn identity_activated_call_indexes(&self) -> Result<[u8; 2]>;
+	fn identity_removed_call_indexes(&self) -> Result<[u8; 2]>;
 	fn imp_some_error_call_indexes(&self) -> Result<[u8; 2]>;
 }
 
@@ -49,6 +51,10 @@ impl IMPCallIndexes for NodeMetadata {
 		self.call_indexes(IMP, "activate_identity")
 	}
 
+	fn remove_identity_call_indexes(&self) -> Result<[u8; 2]> {
+		self.call_indexes(IMP, "remove_identity")
+	}
+
 	fn user_shielding_key_set_call_indexes(&self) -> Result<[u8; 2]> {
 		self.call_indexes(IMP, "user_shielding_key_set")
 	}
@@ -65,6 +71,10 @@ impl IMPCallIndexes for NodeMetadata {
 		self.call_indexes(IMP, "identity_activated")
 	}
 
+	fn identity_removed_call_indexes(&self) -> Result<[u8; 2]> {
+		self.call_indexes(IMP, "identity_removed")
+	}
+
 	fn imp_some_error_call_indexes(&self) -> Result<[u8; 2]> {
 		self.call_indexes(IMP, "some_error")
 	}
@@ -22,8 +22,12 @@ const VCMP: &str = "VCManagement";
 pub trait VCMPCallIndexes {
 	fn request_vc_call_indexes(&self) -> Result<[u8; 2]>;
 
+	fn renew_vc_call_indexes(&self) -> Result<[u8; 2]>;
+
 	fn vc_issued_call_indexes(&self) -> Result<[u8; 2]>;
 
+	fn vc_renewed_call_indexes(&self) -> Result<[u8; 2]>;
+
 	fn vcmp_some_error_call_indexes(&self) -> Result<[u8; 2]>;
 }
 
@@ -32,10 +36,18 @@ impl VCMPCallIndexes for NodeMetadata {
 		self.call_indexes(VCMP, "request_vc")
 	}
 
+	fn renew_vc_call_indexes(&self) -> Result<[u8; 2]> {
+		self.call_indexes(VCMP, "renew_vc")
+	}
+
 	fn vc_issued_call_indexes(&self) -> Result<[u8; 2]> {
 		self.call_indexes(VCMP, "vc_issued")
 	}
 
+	fn vc_renewed_call_indexes(&self) -> Result<[u8; 2]> {
+		self.call_indexes(VCMP, "vc_renewed")
+	}
+
 	fn vcmp_some_error_call_indexes(&self) -> Result<[u8; 2]> {
 		self.call_indexes(VCMP, "some_error")
 	}
@@ -48,6 +48,7 @@ pub mod files {
 	pub const LIGHT_CLIENT_DB_PATH: &str = "light_client_db.bin";
 	// litentry
 	pub const SCHEDULED_ENCLAVE_FILE: &str = "scheduled_enclave_sealed.bin";
+	pub const DATA_PROVIDER_CONFIG_FILE: &str = "data_provider_config_sealed.bin";
 
 	pub const RA_DUMP_CERT_DER_FILE: &str = "ra_dump_cert.der";
 
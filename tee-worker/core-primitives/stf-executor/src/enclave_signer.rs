@@ -134,4 +134,8 @@ where
 		debug!("	[EnclaveSigner] VC pubkey: {:?}", enclave_call_signing_key.public().to_vec());
 		Ok((enclave_account, enclave_call_signing_key.sign(payload).0.to_vec()))
 	}
+
+	fn get_mrenclave(&self) -> Result<[u8; 32]> {
+		Ok(self.ocall_api.get_mrenclave_of_self()?.m)
+	}
 }
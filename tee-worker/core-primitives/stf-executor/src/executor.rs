@@ -183,12 +183,13 @@ where
 			.get_multiple_storages_verified(storage_hashes, header)
 			.map(into_map)?;
 
-		// Update parentchain block on all states.
+		// Update the global storages and the parentchain block on all states.
 		// TODO: Investigate if this is still necessary. We load and clone the entire state here,
 		// which scales badly for increasing state size.
 		let shards = self.state_handler.list_shards()?;
 		for shard_id in shards {
 			let (state_lock, mut state) = self.state_handler.load_for_mutation(&shard_id)?;
+			Stf::apply_state_diff(&mut state, state_diff_update.clone().into());
 			match Stf::update_parentchain_block(&mut state, header.clone()) {
 				Ok(_) => {
 					self.state_handler.write_after_mutation(state, state_lock, &shard_id)?;
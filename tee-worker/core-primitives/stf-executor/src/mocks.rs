@@ -133,6 +133,10 @@ impl StfEnclaveSigning for StfEnclaveSignerMock {
 	fn sign_vc_with_self(&self, _payload: &[u8]) -> Result<(AccountId, Vec<u8>)> {
 		Ok((self.signer.public().into(), [0u8; 32].to_vec()))
 	}
+
+	fn get_mrenclave(&self) -> Result<[u8; 32]> {
+		Ok(self.mr_enclave)
+	}
 }
 
 /// GetState mock
@@ -44,6 +44,8 @@ pub trait StfEnclaveSigning {
 
 	// litentry
 	fn sign_vc_with_self(&self, payload: &[u8]) -> Result<(AccountId, Vec<u8>)>;
+
+	fn get_mrenclave(&self) -> Result<[u8; 32]>;
 }
 
 /// Proposes a state update to `Externalities`.
@@ -73,6 +73,15 @@ pub trait HandleState {
 	/// Use in cases where the previous state is of no interest. Otherwise use `load_for_mutation` and `write_after_mutation`.
 	fn reset(&self, state: Self::StateT, shard: &ShardIdentifier) -> Result<Self::HashType>;
 
+	/// Revert the state of a shard to a previous snapshot, identified by its state hash.
+	///
+	/// All snapshots newer than the one reverted to are discarded.
+	fn revert_to(
+		&self,
+		shard: &ShardIdentifier,
+		state_hash: &Self::HashType,
+	) -> Result<Self::StateT>;
+
 	// litentry
 	/// Migrate state from old shard to new shard
 	fn migrate_shard(
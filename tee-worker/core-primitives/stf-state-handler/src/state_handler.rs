@@ -230,6 +230,26 @@ where
 		let (state, _) = self.load_cloned(&old_shard)?;
 		self.reset(state, &new_shard)
 	}
+
+	fn revert_to(
+		&self,
+		shard: &ShardIdentifier,
+		state_hash: &Self::HashType,
+	) -> Result<Self::StateT> {
+		let mut states_map_lock = self.states_map_lock.write().map_err(|_| Error::LockPoisoning)?;
+		let state = self
+			.state_snapshot_repository
+			.write()
+			.map_err(|_| Error::LockPoisoning)?
+			.revert_to(shard, state_hash)?;
+
+		let reverted_state_hash = state.hash();
+		states_map_lock.insert(*shard, (state.clone(), reverted_state_hash));
+		drop(states_map_lock);
+
+		self.state_observer.queue_state_update(*shard, state.clone())?;
+		Ok(state)
+	}
 }
 
 impl<Repository, StateObserver, StateInitializer> QueryShardState
@@ -405,6 +425,32 @@ mod tests {
 		assert_eq!(state_without_diff, loaded_state);
 	}
 
+	#[test]
+	fn revert_to_replaces_current_state_and_updates_observer() {
+		let shard_id = ShardIdentifier::default();
+		let state_observer = Arc::new(TestStateObserver::default());
+		let state_initializer = Arc::new(TestStateInitializer::new(Default::default()));
+		let state_handler = Arc::new(TestStateHandler::new(
+			default_repository(),
+			state_observer.clone(),
+			state_initializer,
+		));
+		let initial_hash = state_handler.initialize_shard(shard_id).unwrap();
+		let (initial_state, _) = state_handler.load_cloned(&shard_id).unwrap();
+
+		state_handler.reset(create_state(4u64), &shard_id).unwrap();
+		let reverted_state = state_handler.revert_to(&shard_id, &initial_hash).unwrap();
+
+		let (loaded_state, loaded_hash) = state_handler.load_cloned(&shard_id).unwrap();
+		assert_eq!(initial_state, reverted_state);
+		assert_eq!(initial_state, loaded_state);
+		assert_eq!(initial_hash, loaded_hash);
+
+		let observer_updates = state_observer.queued_updates.read().unwrap().clone();
+		assert_eq!(3, observer_updates.len());
+		assert_eq!((shard_id, initial_state), observer_updates[2]);
+	}
+
 	fn default_state_handler() -> Arc<TestStateHandler> {
 		let state_observer = Arc::new(TestStateObserver::default());
 		let state_initializer = Arc::new(TestStateInitializer::new(Default::default()));
@@ -66,6 +66,10 @@ fn key_hash<K: Encode>(key: &K, hasher: &StorageHasher) -> Vec<u8> {
 		StorageHasher::Blake2_256 => sp_core::blake2_256(&encoded_key).to_vec(),
 		StorageHasher::Twox128 => sp_core::twox_128(&encoded_key).to_vec(),
 		StorageHasher::Twox256 => sp_core::twox_256(&encoded_key).to_vec(),
-		StorageHasher::Twox64Concat => sp_core::twox_64(&encoded_key).to_vec(),
+		StorageHasher::Twox64Concat => sp_core::twox_64(&encoded_key)
+			.iter()
+			.chain(encoded_key.iter())
+			.cloned()
+			.collect(),
 	}
 }
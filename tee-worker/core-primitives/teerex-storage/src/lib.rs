@@ -22,6 +22,8 @@ impl StoragePrefix for TeeRexStorage {
 pub trait TeerexStorageKeys {
 	fn enclave_count() -> Vec<u8>;
 	fn enclave(index: u64) -> Vec<u8>;
+	/// `account` is the raw enclave account, an `AccountId32` encodes as its 32 bytes.
+	fn enclave_index(account: &[u8; 32]) -> Vec<u8>;
 }
 
 impl<S: StoragePrefix> TeerexStorageKeys for S {
@@ -32,4 +34,8 @@ impl<S: StoragePrefix> TeerexStorageKeys for S {
 	fn enclave(index: u64) -> Vec<u8> {
 		storage_map_key(Self::prefix(), "EnclaveRegistry", &index, &StorageHasher::Blake2_128Concat)
 	}
+
+	fn enclave_index(account: &[u8; 32]) -> Vec<u8> {
+		storage_map_key(Self::prefix(), "EnclaveIndex", account, &StorageHasher::Blake2_128Concat)
+	}
 }
@@ -36,11 +36,12 @@ use std::{collections::HashMap, format, vec::Vec};
 #[derive(Default)]
 pub struct HandleStateMock {
 	state_map: RwLock<HashMap<ShardIdentifier, StfState>>,
+	state_history: RwLock<HashMap<ShardIdentifier, Vec<StfState>>>,
 }
 
 impl HandleStateMock {
 	pub fn from_shard(shard: ShardIdentifier) -> Result<Self> {
-		let state_handler = HandleStateMock { state_map: Default::default() };
+		let state_handler = HandleStateMock::default();
 		state_handler.initialize_shard(shard)?;
 		Ok(state_handler)
 	}
@@ -105,6 +106,12 @@ impl HandleState for HandleStateMock {
 		shard: &ShardIdentifier,
 	) -> Result<Self::HashType> {
 		state_lock.insert(*shard, state.clone());
+		self.state_history
+			.write()
+			.unwrap()
+			.entry(*shard)
+			.or_default()
+			.push(state.clone());
 		Ok(state.hash())
 	}
 
@@ -112,6 +119,25 @@ impl HandleState for HandleStateMock {
 		let write_lock = self.state_map.write().unwrap();
 		self.write_after_mutation(state, write_lock, shard)
 	}
+
+	fn revert_to(
+		&self,
+		shard: &ShardIdentifier,
+		state_hash: &Self::HashType,
+	) -> Result<Self::StateT> {
+		let mut state_lock = self.state_map.write().unwrap();
+		let mut history_lock = self.state_history.write().unwrap();
+		let history = history_lock.entry(*shard).or_default();
+		let position = history
+			.iter()
+			.rposition(|state| state.hash() == *state_hash)
+			.ok_or_else(|| Error::StateNotFoundInRepository(format!("{:?}", state_hash)))?;
+		history.truncate(position + 1);
+
+		let state = history[position].clone();
+		state_lock.insert(*shard, state.clone());
+		Ok(state)
+	}
 }
 
 impl QueryShardState for HandleStateMock {
@@ -150,6 +150,15 @@ where
 			Err(_) => return Box::pin(ready(Err(ClientError::BadFormat.into()))),
 		};
 
+		self.submit_trusted_operation(trusted_operation, shard, submission_mode)
+	}
+
+	fn submit_trusted_operation(
+		&self,
+		trusted_operation: TrustedOperation,
+		shard: ShardIdentifier,
+		submission_mode: TopSubmissionMode,
+	) -> PoolFuture<TxHash<TopPool>, RpcError> {
 		// apply top filter - return error if this specific type of trusted operation
 		// is not allowed by the filter
 		if !self.top_filter.filter(&trusted_operation) {
@@ -274,6 +283,17 @@ where
 		self.process_top(ext, shard, TopSubmissionMode::Submit)
 	}
 
+	fn resubmit_top(
+		&self,
+		operation: TrustedOperation,
+		shard: ShardIdentifier,
+	) -> PoolFuture<TxHash<TopPool>, RpcError> {
+		if !matches!(self.state_facade.shard_exists(&shard), Ok(true)) {
+			return Box::pin(ready(Err(ClientError::InvalidShard.into())))
+		}
+		self.submit_trusted_operation(operation, shard, TopSubmissionMode::Submit)
+	}
+
 	/// Get hash of TrustedOperation
 	fn hash_of(&self, xt: &TrustedOperation) -> TxHash<TopPool> {
 		self.top_pool.hash_of(xt)
@@ -33,6 +33,7 @@ use itp_test::mock::{
 	shielding_crypto_mock::ShieldingCryptoMock,
 };
 use itp_top_pool::mocks::trusted_operation_pool_mock::TrustedOperationPoolMock;
+use jsonrpc_core::futures::executor;
 use sgx_crypto_helper::{rsa3072::Rsa3072KeyPair, RsaKeyPair};
 use sp_core::H256;
 use std::sync::Arc;
@@ -123,6 +124,28 @@ fn submitting_indirect_call_works() {
 	assert_eq!(1, author.get_pending_trusted_calls(shard_id()).len());
 }
 
+#[test]
+fn resubmitting_decrypted_operation_inserts_in_pool() {
+	let (author, top_pool, _) = create_author_with_filter(AllowAllTopsFilter);
+	let trusted_operation = TrustedOperation::direct_call(trusted_call_signed());
+
+	executor::block_on(author.resubmit_top(trusted_operation, shard_id())).unwrap();
+
+	assert_eq!(1, top_pool.get_last_submitted_transactions().len());
+	assert_eq!(1, author.get_pending_trusted_calls(shard_id()).len());
+}
+
+#[test]
+fn resubmitting_filtered_operation_returns_error() {
+	let (author, top_pool, _) = create_author_with_filter(GettersOnlyFilter);
+	let trusted_operation = TrustedOperation::direct_call(trusted_call_signed());
+
+	let result = executor::block_on(author.resubmit_top(trusted_operation, shard_id()));
+
+	assert!(result.is_err());
+	assert!(top_pool.get_last_submitted_transactions().is_empty());
+}
+
 fn create_author_with_filter<F: Filter<Value = TrustedOperation>>(
 	filter: F,
 ) -> (TestAuthor<F>, Arc<TrustedOperationPoolMock>, ShieldingCryptoMock) {
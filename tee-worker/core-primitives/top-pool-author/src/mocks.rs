@@ -28,7 +28,7 @@ use crate::{
 	error::Result,
 	traits::{AuthorApi, OnBlockImported},
 };
-use codec::Decode;
+use codec::{Decode, Encode};
 use ita_stf::{
 	hash::{Hash, TrustedOperationOrHash},
 	Getter, TrustedGetterSigned, TrustedOperation,
@@ -98,6 +98,14 @@ impl AuthorApi<H256, H256> for AuthorApiMock<H256, H256> {
 		Box::pin(ready(Ok(H256::default())))
 	}
 
+	fn resubmit_top(
+		&self,
+		operation: TrustedOperation,
+		shard: ShardIdentifier,
+	) -> PoolFuture<H256, RpcError> {
+		self.submit_top(operation.encode(), shard)
+	}
+
 	fn hash_of(&self, xt: &TrustedOperation) -> H256 {
 		xt.hash()
 	}
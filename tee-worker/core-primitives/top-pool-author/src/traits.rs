@@ -63,6 +63,14 @@ pub trait AuthorApi<Hash, BlockHash> {
 		executed_calls: Vec<(hash::TrustedOperationOrHash<Hash>, bool)>,
 	) -> Vec<hash::TrustedOperationOrHash<Hash>>;
 
+	/// Submit a trusted operation that was already decrypted before, e.g. the operation of a
+	/// retracted sidechain block.
+	fn resubmit_top(
+		&self,
+		operation: TrustedOperation,
+		shard: ShardIdentifier,
+	) -> PoolFuture<Hash, RpcError>;
+
 	/// Submit an extrinsic to watch.
 	///
 	/// See [`TrustedOperationStatus`](sp_transaction_pool::TrustedOperationStatus) for details on transaction
@@ -68,6 +68,9 @@ pub type DeactivateIdentityFn = (CallIndex, DeactivateIdentityParams);
 pub type ActivateIdentityParams = (ShardIdentifier, Vec<u8>);
 pub type ActivateIdentityFn = (CallIndex, DeactivateIdentityParams);
 
+pub type RemoveIdentityParams = (ShardIdentifier, Vec<u8>);
+pub type RemoveIdentityFn = (CallIndex, RemoveIdentityParams);
+
 // pallet VCMP
 pub type RequestVCParams = (ShardIdentifier, Assertion);
 pub type RequestVCFn = (CallIndex, RequestVCParams);
@@ -166,6 +169,23 @@ pub enum WorkerResponse<V: Encode + Decode> {
 	ChainStorageKeys(Vec<Vec<u8>>),                         // (storage_keys)
 }
 
+/// Health of a worker, as returned by the `system_health` rpc method.
+#[derive(Encode, Decode, Clone, Debug, Default, PartialEq, Eq)]
+pub struct WorkerHealth {
+	/// Number of other validateers registered on the parentchain.
+	pub peers: u32,
+	/// Whether there are still sidechain blocks waiting in the import queue.
+	pub is_syncing: bool,
+	/// Whether this worker is expected to have peers, i.e. is not the only validateer.
+	pub should_have_peers: bool,
+	/// (number, hash) of the last imported parentchain block.
+	pub last_parentchain_block: Option<(BlockNumber, BlockHash)>,
+	/// (shard, number, hash) of the last imported sidechain block of each shard.
+	pub last_sidechain_blocks: Vec<(ShardIdentifier, SidechainBlockNumber, H256)>,
+	/// Number of pending trusted operations in the top pool, across all shards.
+	pub top_pool_size: u32,
+}
+
 impl From<WorkerResponse<Vec<u8>>> for StorageEntry<Vec<u8>> {
 	fn from(response: WorkerResponse<Vec<u8>>) -> Self {
 		match response {
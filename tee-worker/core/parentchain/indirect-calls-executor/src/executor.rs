@@ -285,8 +285,11 @@ mod test {
 	use itp_stf_primitives::types::AccountId;
 	use itp_test::mock::shielding_crypto_mock::ShieldingCryptoMock;
 	use itp_top_pool_author::mocks::AuthorApiMock;
-	use itp_types::{Block, CallWorkerFn, Request, ShardIdentifier, ShieldFundsFn};
-	use sp_core::{ed25519, Pair};
+	use itp_types::{
+		Block, CallWorkerFn, RemoveIdentityFn, Request, ShardIdentifier, ShieldFundsFn,
+	};
+	use litentry_primitives::{ErrorDetail, IMPError, Identity, IdentityString};
+	use sp_core::{crypto::AccountId32, ed25519, Pair};
 	use sp_runtime::{MultiAddress, MultiSignature, OpaqueExtrinsic};
 	use std::assert_matches::assert_matches;
 	use substrate_api_client::ExtrinsicParams;
@@ -416,6 +419,102 @@ mod test {
 		assert_ne!(call.0, zero_root_call);
 	}
 
+	#[test]
+	fn remove_identity_call_can_be_added_to_pool_successfully() {
+		let _ = env_logger::builder().is_test(true).try_init();
+
+		let mr_enclave = [34u8; 32];
+		let (indirect_calls_executor, top_pool_author, shielding_key_repo) =
+			test_fixtures(mr_enclave, NodeMetadataMock::new());
+		let shielding_key = shielding_key_repo.retrieve_key().unwrap();
+
+		let encrypted_identity = shielding_key.encrypt(&twitter_identity().encode()).unwrap();
+		let opaque_extrinsic = OpaqueExtrinsic::from_bytes(
+			remove_identity_unchecked_extrinsic(encrypted_identity).encode().as_slice(),
+		)
+		.unwrap();
+		let parentchain_block = ParentchainBlockBuilder::default()
+			.with_extrinsics(vec![opaque_extrinsic])
+			.build();
+
+		indirect_calls_executor
+			.execute_indirect_calls_in_extrinsics(&parentchain_block)
+			.unwrap();
+
+		let trusted_call_signed = pending_indirect_call(&top_pool_author, &shielding_key);
+		assert!(trusted_call_signed.verify_signature(&mr_enclave, &shard_id()));
+		let expected_who: Identity = AccountId32::new([1u8; 32]).into();
+		assert_matches!(
+			trusted_call_signed.call,
+			TrustedCall::remove_identity(_, who, identity, _)
+				if who == expected_who && identity == twitter_identity()
+		);
+	}
+
+	#[test]
+	fn remove_identity_call_with_invalid_identity_submits_error() {
+		let _ = env_logger::builder().is_test(true).try_init();
+
+		let (indirect_calls_executor, top_pool_author, shielding_key_repo) =
+			test_fixtures([35u8; 32], NodeMetadataMock::new());
+		let shielding_key = shielding_key_repo.retrieve_key().unwrap();
+
+		// can't be decrypted with the shielding key
+		let opaque_extrinsic = OpaqueExtrinsic::from_bytes(
+			remove_identity_unchecked_extrinsic(vec![1u8, 2u8]).encode().as_slice(),
+		)
+		.unwrap();
+		let parentchain_block = ParentchainBlockBuilder::default()
+			.with_extrinsics(vec![opaque_extrinsic])
+			.build();
+
+		indirect_calls_executor
+			.execute_indirect_calls_in_extrinsics(&parentchain_block)
+			.unwrap();
+
+		let trusted_call_signed = pending_indirect_call(&top_pool_author, &shielding_key);
+		assert_matches!(
+			trusted_call_signed.call,
+			TrustedCall::handle_imp_error(
+				_,
+				_,
+				IMPError::RemoveIdentityFailed(ErrorDetail::ImportError),
+				_
+			)
+		);
+	}
+
+	fn pending_indirect_call(
+		top_pool_author: &TestTopPoolAuthor,
+		shielding_key: &ShieldingCryptoMock,
+	) -> TrustedCallSigned {
+		let pending_tops = top_pool_author.pending_tops(shard_id()).unwrap();
+		assert_eq!(1, pending_tops.len());
+		let decrypted_extrinsic = shielding_key.decrypt(&pending_tops[0]).unwrap();
+		let decoded_operation =
+			TrustedOperation::decode(&mut decrypted_extrinsic.as_slice()).unwrap();
+		assert_matches!(decoded_operation, TrustedOperation::indirect_call(_));
+		decoded_operation.to_call().cloned().unwrap()
+	}
+
+	fn twitter_identity() -> Identity {
+		Identity::Twitter(IdentityString::try_from(b"alice".to_vec()).unwrap())
+	}
+
+	fn remove_identity_unchecked_extrinsic(
+		encrypted_identity: Vec<u8>,
+	) -> ParentchainUncheckedExtrinsic<RemoveIdentityFn> {
+		let dummy_metadata = NodeMetadataMock::new();
+		let remove_identity_indexes = dummy_metadata.remove_identity_call_indexes().unwrap();
+
+		ParentchainUncheckedExtrinsic::<RemoveIdentityFn>::new_signed(
+			(remove_identity_indexes, (shard_id(), encrypted_identity)),
+			MultiAddress::Address32([1u8; 32]),
+			MultiSignature::Ed25519(default_signature()),
+			default_extrinsic_params().signed_extra(),
+		)
+	}
+
 	fn shield_funds_unchecked_extrinsic(
 		shielding_key: &ShieldingCryptoMock,
 	) -> ParentchainUncheckedExtrinsic<ShieldFundsFn> {
@@ -19,8 +19,8 @@ use crate::{
 	error::Result,
 	indirect_calls::{
 		ActivateIdentityArgs, CallWorkerArgs, DeactivateIdentityArgs, LinkIdentityArgs,
-		RemoveScheduledEnclaveArgs, RequestVCArgs, SetUserShieldingKeyArgs, ShiedFundsArgs,
-		UpdateScheduledEnclaveArgs,
+		RemoveIdentityArgs, RemoveScheduledEnclaveArgs, RenewVCArgs, RequestVCArgs,
+		SetUserShieldingKeyArgs, ShiedFundsArgs, UpdateScheduledEnclaveArgs,
 	},
 	parentchain_extrinsic_parser::ParseExtrinsic,
 	IndirectDispatch, IndirectExecutor,
@@ -110,10 +110,18 @@ where
 			let args = decode_and_log_error::<ActivateIdentityArgs>(call_args)?;
 			let hashed_extrinsic = xt.hashed_extrinsic;
 			Some(IndirectCall::ActivateIdentity(args, address, hashed_extrinsic))
+		} else if index == metadata.remove_identity_call_indexes().ok()? {
+			let args = decode_and_log_error::<RemoveIdentityArgs>(call_args)?;
+			let hashed_extrinsic = xt.hashed_extrinsic;
+			Some(IndirectCall::RemoveIdentity(args, address, hashed_extrinsic))
 		} else if index == metadata.request_vc_call_indexes().ok()? {
 			let args = decode_and_log_error::<RequestVCArgs>(call_args)?;
 			let hashed_extrinsic = xt.hashed_extrinsic;
 			Some(IndirectCall::RequestVC(args, address, hashed_extrinsic))
+		} else if index == metadata.renew_vc_call_indexes().ok()? {
+			let args = decode_and_log_error::<RenewVCArgs>(call_args)?;
+			let hashed_extrinsic = xt.hashed_extrinsic;
+			Some(IndirectCall::RenewVC(args, address, hashed_extrinsic))
 		} else if index == metadata.update_scheduled_enclave().ok()? {
 			let args = decode_and_log_error::<UpdateScheduledEnclaveArgs>(call_args)?;
 			Some(IndirectCall::UpdateScheduledEnclave(args))
@@ -140,7 +148,9 @@ pub enum IndirectCall {
 	LinkIdentity(LinkIdentityArgs, Option<MultiAddress<AccountId32, ()>>, H256),
 	DeactivateIdentity(DeactivateIdentityArgs, Option<MultiAddress<AccountId32, ()>>, H256),
 	ActivateIdentity(ActivateIdentityArgs, Option<MultiAddress<AccountId32, ()>>, H256),
+	RemoveIdentity(RemoveIdentityArgs, Option<MultiAddress<AccountId32, ()>>, H256),
 	RequestVC(RequestVCArgs, Option<MultiAddress<AccountId32, ()>>, H256),
+	RenewVC(RenewVCArgs, Option<MultiAddress<AccountId32, ()>>, H256),
 	UpdateScheduledEnclave(UpdateScheduledEnclaveArgs),
 	RemoveScheduledEnclave(RemoveScheduledEnclaveArgs),
 	BatchAll(Vec<IndirectCall>),
@@ -161,8 +171,12 @@ impl<Executor: IndirectExecutor> IndirectDispatch<Executor> for IndirectCall {
 				deactivate_identity.dispatch(executor, (address.clone(), *hash)),
 			IndirectCall::ActivateIdentity(activate_identity, address, hash) =>
 				activate_identity.dispatch(executor, (address.clone(), *hash)),
+			IndirectCall::RemoveIdentity(remove_identity, address, hash) =>
+				remove_identity.dispatch(executor, (address.clone(), *hash)),
 			IndirectCall::RequestVC(request_vc, address, hash) =>
 				request_vc.dispatch(executor, (address.clone(), *hash, block)),
+			IndirectCall::RenewVC(renew_vc, address, hash) =>
+				renew_vc.dispatch(executor, (address.clone(), *hash, block)),
 			IndirectCall::UpdateScheduledEnclave(update_enclave_args) =>
 				update_enclave_args.dispatch(executor, ()),
 			IndirectCall::RemoveScheduledEnclave(remove_enclave_args) =>
@@ -223,10 +237,18 @@ fn parse_batch_all<NodeMetadata: NodeMetadataTrait>(
 			let args = decode_and_log_error::<ActivateIdentityArgs>(call_args)?;
 			let hashed_extrinsic = hash;
 			calls.push(IndirectCall::ActivateIdentity(args, address.clone(), hashed_extrinsic))
+		} else if index == metadata.remove_identity_call_indexes().ok()? {
+			let args = decode_and_log_error::<RemoveIdentityArgs>(call_args)?;
+			let hashed_extrinsic = hash;
+			calls.push(IndirectCall::RemoveIdentity(args, address.clone(), hashed_extrinsic))
 		} else if index == metadata.request_vc_call_indexes().ok()? {
 			let args = decode_and_log_error::<RequestVCArgs>(call_args)?;
 			let hashed_extrinsic = hash;
 			calls.push(IndirectCall::RequestVC(args, address.clone(), hashed_extrinsic))
+		} else if index == metadata.renew_vc_call_indexes().ok()? {
+			let args = decode_and_log_error::<RenewVCArgs>(call_args)?;
+			let hashed_extrinsic = hash;
+			calls.push(IndirectCall::RenewVC(args, address.clone(), hashed_extrinsic))
 		} else if index == metadata.update_scheduled_enclave().ok()? {
 			let args = decode_and_log_error::<UpdateScheduledEnclaveArgs>(call_args)?;
 			calls.push(IndirectCall::UpdateScheduledEnclave(args))
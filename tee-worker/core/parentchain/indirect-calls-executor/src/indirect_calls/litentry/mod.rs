@@ -17,6 +17,7 @@
 pub mod activate_identity;
 pub mod deactivate_identity;
 pub mod link_identity;
+pub mod remove_identity;
 pub mod request_vc;
 pub mod scheduled_enclave;
 pub mod set_user_shielding_key;
@@ -44,6 +44,7 @@ impl RequestVCArgs {
 		executor: &Executor,
 		address: Option<MultiAddress<AccountId32, ()>>,
 		hash: H256,
+		renewed_index: Option<H256>,
 	) -> Result<()> {
 		if let Some(address) = address {
 			let account = AccountIdLookup::lookup(address)?;
@@ -55,12 +56,21 @@ impl RequestVCArgs {
 
 			let enclave_account_id = executor.get_enclave_account()?;
 
-			let trusted_call = TrustedCall::request_vc(
-				enclave_account_id.into(),
-				account.into(),
-				self.assertion.clone(),
-				hash,
-			);
+			let trusted_call = match renewed_index {
+				Some(index) => TrustedCall::renew_vc(
+					enclave_account_id.into(),
+					account.into(),
+					self.assertion.clone(),
+					index,
+					hash,
+				),
+				None => TrustedCall::request_vc(
+					enclave_account_id.into(),
+					account.into(),
+					self.assertion.clone(),
+					hash,
+				),
+			};
 			let signed_trusted_call = executor.sign_call_with_self(&trusted_call, &self.shard)?;
 			let trusted_operation = TrustedOperation::indirect_call(signed_trusted_call);
 
@@ -69,16 +79,19 @@ impl RequestVCArgs {
 		}
 		Ok(())
 	}
-}
-impl<Executor: IndirectExecutor> IndirectDispatch<Executor> for RequestVCArgs {
-	type Args = (Option<MultiAddress<AccountId32, ()>>, H256, u32);
-	fn dispatch(&self, executor: &Executor, args: Self::Args) -> Result<()> {
-		let (address, hash, _block) = args;
+
+	fn dispatch_request<Executor: IndirectExecutor>(
+		&self,
+		executor: &Executor,
+		address: Option<MultiAddress<AccountId32, ()>>,
+		hash: H256,
+		renewed_index: Option<H256>,
+	) -> Result<()> {
 		let e = Error::VCMPHandlingError(VCMPError::RequestVCFailed(
 			self.assertion.clone(),
 			ErrorDetail::ImportError,
 		));
-		if self.internal_dispatch(executor, address, hash).is_err() {
+		if self.internal_dispatch(executor, address, hash, renewed_index).is_err() {
 			if let Err(internal_e) =
 				executor.submit_trusted_call_from_error(self.shard, None, &e, hash)
 			{
@@ -89,3 +102,35 @@ impl<Executor: IndirectExecutor> IndirectDispatch<Executor> for RequestVCArgs {
 		Ok(())
 	}
 }
+
+impl<Executor: IndirectExecutor> IndirectDispatch<Executor> for RequestVCArgs {
+	type Args = (Option<MultiAddress<AccountId32, ()>>, H256, u32);
+	fn dispatch(&self, executor: &Executor, args: Self::Args) -> Result<()> {
+		let (address, hash, _block) = args;
+		self.dispatch_request(executor, address, hash, None)
+	}
+}
+
+/// Renewal of the VC `index`, the parachain supersedes it once the VC for `assertion` is issued.
+#[derive(Debug, Clone, Encode, Decode, Eq, PartialEq)]
+pub struct RenewVCArgs {
+	shard: ShardIdentifier,
+	index: H256,
+	assertion: Assertion,
+}
+
+impl<Executor: IndirectExecutor> IndirectDispatch<Executor> for RenewVCArgs {
+	type Args = (Option<MultiAddress<AccountId32, ()>>, H256, u32);
+	fn dispatch(&self, executor: &Executor, args: Self::Args) -> Result<()> {
+		debug!("indirect call Renew VC, index: {:?}, assertion: {:?}", self.index, self.assertion);
+		let (address, hash, _block) = args;
+		// the renewal is a regular request of the same assertion, the parachain has checked that
+		// `assertion` matches the VC `index`
+		RequestVCArgs { shard: self.shard, assertion: self.assertion.clone() }.dispatch_request(
+			executor,
+			address,
+			hash,
+			Some(self.index),
+		)
+	}
+}
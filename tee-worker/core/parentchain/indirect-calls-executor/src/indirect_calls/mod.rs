@@ -24,7 +24,8 @@ pub use litentry::{
 	activate_identity::ActivateIdentityArgs,
 	deactivate_identity::DeactivateIdentityArgs,
 	link_identity::LinkIdentityArgs,
-	request_vc::RequestVCArgs,
+	remove_identity::RemoveIdentityArgs,
+	request_vc::{RenewVCArgs, RequestVCArgs},
 	scheduled_enclave::{RemoveScheduledEnclaveArgs, UpdateScheduledEnclaveArgs},
 	set_user_shielding_key::SetUserShieldingKeyArgs,
 };
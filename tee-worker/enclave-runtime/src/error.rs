@@ -30,6 +30,7 @@ pub enum Error {
 	ChainStorage(itp_ocall_api::Error),
 	ExtrinsicsFactory(itp_extrinsics_factory::error::Error),
 	StfTaskReceiver(lc_stf_task_receiver::Error),
+	DataProviderConfig(lc_data_providers::Error),
 	IO(std::io::Error),
 	LightClient(itc_parentchain::light_client::error::Error),
 	NodeMetadataProvider(itp_node_api::metadata::provider::Error),
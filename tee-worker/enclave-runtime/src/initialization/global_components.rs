@@ -25,7 +25,7 @@ use crate::{
 		parachain::FullParachainHandler, solochain::FullSolochainHandler,
 	},
 	ocall::OcallApi,
-	rpc::rpc_response_channel::RpcResponseChannel,
+	rpc::{head_subscriptions::HeadSubscriptions, rpc_response_channel::RpcResponseChannel},
 	tls_ra::seal_handler::SealHandler,
 };
 use ita_sgx_runtime::Runtime;
@@ -85,7 +85,9 @@ use its_primitives::{
 use its_sidechain::{
 	aura::block_importer::BlockImporter as SidechainBlockImporter,
 	block_composer::BlockComposer,
-	consensus_common::{BlockImportConfirmationHandler, BlockImportQueueWorker, PeerBlockSync},
+	consensus_common::{
+		BlockImportConfirmationHandler, BlockImportQueueWorker, PeerBlockSync, SidechainForkTrees,
+	},
 };
 use sgx_crypto_helper::rsa3072::Rsa3072KeyPair;
 use sgx_tstd::vec::Vec;
@@ -127,6 +129,7 @@ pub type EnclaveRpcWsHandler =
 	RpcWsHandler<RpcWatchExtractor<Hash>, EnclaveRpcConnectionRegistry, Hash>;
 pub type EnclaveWebSocketServer = TungsteniteWsServer<EnclaveRpcWsHandler, FromFileConfigProvider>;
 pub type EnclaveRpcResponder = RpcResponder<EnclaveRpcConnectionRegistry, Hash, RpcResponseChannel>;
+pub type EnclaveHeadSubscriptions = HeadSubscriptions<EnclaveRpcResponder>;
 pub type EnclaveSidechainApi = SidechainApi<ParentchainBlock>;
 
 // Parentchain types
@@ -192,6 +195,7 @@ pub type EnclaveSidechainBlockImporter = SidechainBlockImporter<
 	EnclaveTopPoolAuthor,
 	EnclaveTriggeredParentchainBlockImportDispatcher,
 >;
+pub type EnclaveSidechainForkTrees = SidechainForkTrees<SignedSidechainBlock>;
 pub type EnclaveSidechainBlockImportQueue = ImportQueue<SignedSidechainBlock>;
 pub type EnclaveBlockImportConfirmationHandler = BlockImportConfirmationHandler<
 	ParentchainBlock,
@@ -288,6 +292,10 @@ pub static GLOBAL_FULL_PARACHAIN_HANDLER_COMPONENT: ComponentContainer<FullParac
 pub static GLOBAL_RPC_WS_HANDLER_COMPONENT: ComponentContainer<EnclaveRpcWsHandler> =
 	ComponentContainer::new("rpc_ws_handler");
 
+/// Subscriptions to new sidechain headers.
+pub static GLOBAL_HEAD_SUBSCRIPTIONS_COMPONENT: ComponentContainer<EnclaveHeadSubscriptions> =
+	ComponentContainer::new("head_subscriptions");
+
 /// Sidechain import queue.
 pub static GLOBAL_SIDECHAIN_IMPORT_QUEUE_COMPONENT: ComponentContainer<
 	EnclaveSidechainBlockImportQueue,
@@ -303,6 +311,10 @@ pub static GLOBAL_SIDECHAIN_BLOCK_COMPOSER_COMPONENT: ComponentContainer<
 	EnclaveSidechainBlockComposer,
 > = ComponentContainer::new("sidechain_block_composer");
 
+/// Sidechain fork trees - competing sidechain blocks of each shard.
+pub static GLOBAL_SIDECHAIN_FORK_TREES_COMPONENT: ComponentContainer<EnclaveSidechainForkTrees> =
+	ComponentContainer::new("sidechain_fork_trees");
+
 /// Sidechain block syncer.
 pub static GLOBAL_SIDECHAIN_BLOCK_SYNCER_COMPONENT: ComponentContainer<
 	EnclaveSidechainBlockSyncer,
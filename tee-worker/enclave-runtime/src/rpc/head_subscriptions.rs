@@ -0,0 +1,285 @@
+// Copyright 2020-2023 Litentry Technologies GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Subscriptions to new sidechain headers (`chain_subscribeAllHeads`).
+//!
+//! A subscription is a watched rpc connection in the connection registry, keyed by the
+//! subscription id. Each new header is pushed by setting the response value and sending a
+//! `Submitted` status update, which keeps the connection in the registry for the next one.
+//!
+//! The connection is only put into the registry once the subscription response was sent, so
+//! a subscriber that can't be reached yet is kept for a grace period before it's dropped.
+
+use codec::Encode;
+use itc_direct_rpc_server::SendRpcResponse;
+use itp_time_utils::duration_now;
+use itp_types::{ShardIdentifier, SidechainBlockNumber, TrustedOperationStatus, H256};
+use its_primitives::{traits::Header as HeaderTrait, types::header::SidechainHeader};
+use log::*;
+use sp_core::blake2_256;
+use std::{
+	collections::BTreeMap,
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc, SgxRwLock as RwLock,
+	},
+	time::Duration,
+	vec::Vec,
+};
+
+/// How long a new subscription may stay unreachable before it's dropped.
+const REGISTRATION_GRACE_PERIOD: Duration = Duration::from_secs(30);
+
+struct Subscriber {
+	id: H256,
+	subscribed_at: Duration,
+	/// whether a header was ever pushed to it, i.e. its connection was registered
+	reached: bool,
+}
+
+pub struct HeadSubscriptions<Responder> {
+	responder: Arc<Responder>,
+	subscribers: RwLock<Vec<Subscriber>>,
+	last_notified: RwLock<BTreeMap<ShardIdentifier, SidechainBlockNumber>>,
+	nonce: AtomicU64,
+}
+
+impl<Responder> HeadSubscriptions<Responder>
+where
+	Responder: SendRpcResponse<Hash = H256>,
+{
+	pub fn new(responder: Arc<Responder>) -> Self {
+		Self {
+			responder,
+			subscribers: Default::default(),
+			last_notified: Default::default(),
+			nonce: AtomicU64::new(0),
+		}
+	}
+
+	/// Register a new subscription and return its id, which is also the hash
+	/// under which the rpc connection is watched.
+	pub fn subscribe(&self) -> H256 {
+		let nonce = self.nonce.fetch_add(1, Ordering::Relaxed);
+		let now = duration_now();
+		let id: H256 =
+			blake2_256(&(b"chain_subscribeAllHeads", nonce, now.as_nanos()).encode()).into();
+
+		match self.subscribers.write() {
+			Ok(mut subscribers) =>
+				subscribers.push(Subscriber { id, subscribed_at: now, reached: false }),
+			Err(e) => error!("failed to add head subscription: {:?}", e),
+		}
+		id
+	}
+
+	pub fn subscriber_count(&self) -> usize {
+		self.subscribers.read().map(|s| s.len()).unwrap_or_default()
+	}
+
+	/// Push `header` to all subscribers, unless a header with the same or a higher block number
+	/// was already sent for this shard. Subscribers whose connection is gone are dropped.
+	pub fn notify(&self, header: &SidechainHeader) {
+		self.notify_at(header, duration_now())
+	}
+
+	fn notify_at(&self, header: &SidechainHeader, now: Duration) {
+		match self.last_notified.write() {
+			Ok(mut last_notified) => {
+				let number = header.block_number();
+				match last_notified.get(&header.shard_id()) {
+					Some(last) if *last >= number => return,
+					_ => {
+						last_notified.insert(header.shard_id(), number);
+					},
+				}
+			},
+			Err(e) => {
+				error!("failed to acquire head subscriptions lock: {:?}", e);
+				return
+			},
+		}
+
+		let mut subscribers = match self.subscribers.write() {
+			Ok(s) => s,
+			Err(e) => {
+				error!("failed to acquire head subscriptions lock: {:?}", e);
+				return
+			},
+		};
+
+		let encoded_header = header.encode();
+		subscribers.retain_mut(|subscriber| {
+			let id = subscriber.id;
+			let result = self.responder.set_value(id, encoded_header.clone()).and_then(|_| {
+				self.responder.update_status_event(id, TrustedOperationStatus::Submitted)
+			});
+			match result {
+				Ok(()) => {
+					subscriber.reached = true;
+					true
+				},
+				// the connection might not be registered yet
+				Err(_)
+					if !subscriber.reached
+						&& now.saturating_sub(subscriber.subscribed_at)
+							< REGISTRATION_GRACE_PERIOD =>
+					true,
+				Err(e) => {
+					debug!("drop head subscription {:?}: {:?}", id, e);
+					false
+				},
+			}
+		});
+	}
+}
+
+#[cfg(feature = "test")]
+pub mod tests {
+	use super::*;
+	use itc_direct_rpc_server::{DirectRpcError, DirectRpcResult};
+	use std::{collections::BTreeSet, sync::SgxMutex as Mutex, vec};
+
+	/// Only reaches the registered connections, and records the headers pushed to them.
+	#[derive(Default)]
+	struct RegistryResponderMock {
+		registered: Mutex<BTreeSet<H256>>,
+		values: Mutex<Vec<(H256, Vec<u8>)>>,
+	}
+
+	impl RegistryResponderMock {
+		fn register(&self, id: H256) {
+			self.registered.lock().unwrap().insert(id);
+		}
+
+		fn close(&self, id: H256) {
+			self.registered.lock().unwrap().remove(&id);
+		}
+
+		fn values_of(&self, id: H256) -> Vec<Vec<u8>> {
+			let values = self.values.lock().unwrap();
+			values.iter().filter(|(h, _)| *h == id).map(|(_, v)| v.clone()).collect()
+		}
+
+		fn ensure_registered(&self, id: H256) -> DirectRpcResult<()> {
+			if self.registered.lock().unwrap().contains(&id) {
+				Ok(())
+			} else {
+				Err(DirectRpcError::InvalidConnectionHash)
+			}
+		}
+	}
+
+	impl SendRpcResponse for RegistryResponderMock {
+		type Hash = H256;
+
+		fn update_status_event(
+			&self,
+			hash: H256,
+			_status_update: TrustedOperationStatus,
+		) -> DirectRpcResult<()> {
+			self.ensure_registered(hash)
+		}
+
+		fn send_state(&self, hash: H256, _state_encoded: Vec<u8>) -> DirectRpcResult<()> {
+			self.ensure_registered(hash)
+		}
+
+		fn set_value(&self, hash: H256, encoded_value: Vec<u8>) -> DirectRpcResult<()> {
+			self.ensure_registered(hash)?;
+			self.values.lock().unwrap().push((hash, encoded_value));
+			Ok(())
+		}
+
+		fn swap_hash(&self, _old_hash: H256, _new_hash: H256) -> DirectRpcResult<()> {
+			Ok(())
+		}
+	}
+
+	fn header(shard: u8, block_number: SidechainBlockNumber) -> SidechainHeader {
+		SidechainHeader {
+			block_number,
+			shard_id: ShardIdentifier::repeat_byte(shard),
+			..Default::default()
+		}
+	}
+
+	fn setup() -> (Arc<RegistryResponderMock>, HeadSubscriptions<RegistryResponderMock>) {
+		let responder = Arc::new(RegistryResponderMock::default());
+		(responder.clone(), HeadSubscriptions::new(responder))
+	}
+
+	pub fn notify_pushes_header_to_all_subscribers() {
+		let (responder, subscriptions) = setup();
+		let (first, second) = (subscriptions.subscribe(), subscriptions.subscribe());
+		assert_ne!(first, second);
+		responder.register(first);
+		responder.register(second);
+
+		subscriptions.notify(&header(1, 1));
+
+		assert_eq!(responder.values_of(first), vec![header(1, 1).encode()]);
+		assert_eq!(responder.values_of(second), vec![header(1, 1).encode()]);
+		assert_eq!(subscriptions.subscriber_count(), 2);
+	}
+
+	pub fn notify_skips_headers_that_are_not_newer() {
+		let (responder, subscriptions) = setup();
+		let id = subscriptions.subscribe();
+		responder.register(id);
+
+		subscriptions.notify(&header(1, 2));
+		subscriptions.notify(&header(1, 2));
+		subscriptions.notify(&header(1, 1));
+		// another shard has its own block numbers
+		subscriptions.notify(&header(2, 1));
+
+		assert_eq!(responder.values_of(id), vec![header(1, 2).encode(), header(2, 1).encode()]);
+	}
+
+	pub fn subscriber_is_kept_until_its_connection_is_registered() {
+		let (responder, subscriptions) = setup();
+		let id = subscriptions.subscribe();
+
+		// the subscription response hasn't been sent yet
+		subscriptions.notify(&header(1, 1));
+		assert_eq!(subscriptions.subscriber_count(), 1);
+
+		responder.register(id);
+		subscriptions.notify(&header(1, 2));
+		assert_eq!(responder.values_of(id), vec![header(1, 2).encode()]);
+	}
+
+	pub fn unreachable_subscriber_is_dropped_after_grace_period() {
+		let (_responder, subscriptions) = setup();
+		subscriptions.subscribe();
+
+		let later = duration_now() + REGISTRATION_GRACE_PERIOD + Duration::from_secs(1);
+		subscriptions.notify_at(&header(1, 1), later);
+		assert_eq!(subscriptions.subscriber_count(), 0);
+	}
+
+	pub fn subscriber_with_closed_connection_is_dropped() {
+		let (responder, subscriptions) = setup();
+		let id = subscriptions.subscribe();
+		responder.register(id);
+		subscriptions.notify(&header(1, 1));
+
+		responder.close(id);
+		subscriptions.notify(&header(1, 2));
+		assert_eq!(subscriptions.subscriber_count(), 0);
+	}
+}
@@ -15,5 +15,6 @@
 
 */
 
+pub mod head_subscriptions;
 pub mod rpc_response_channel;
 pub mod worker_api_direct;
@@ -20,35 +20,60 @@ use crate::{
 		generate_dcap_ra_extrinsic_from_quote_internal,
 		generate_ias_ra_extrinsic_from_der_cert_internal,
 	},
+	initialization::global_components::{
+		GLOBAL_HEAD_SUBSCRIPTIONS_COMPONENT, GLOBAL_OCALL_API_COMPONENT,
+		GLOBAL_SHIELDING_KEY_REPOSITORY_COMPONENT, GLOBAL_SIDECHAIN_IMPORT_QUEUE_COMPONENT,
+		GLOBAL_STATE_HANDLER_COMPONENT, GLOBAL_TOP_POOL_AUTHOR_COMPONENT,
+	},
 	utils::get_validator_accessor_from_solo_or_parachain,
 };
-use codec::Encode;
+use codec::{Decode, Encode};
 use core::result::Result;
-use ita_sgx_runtime::{Runtime, System};
-use itc_parentchain::light_client::{concurrent_access::ValidatorAccess, ExtrinsicSender};
+use ita_sgx_runtime::{Runtime, Sudo, System, VERSION};
+use itc_parentchain::light_client::{
+	concurrent_access::ValidatorAccess, ExtrinsicSender, LightClientState,
+};
+use itp_component_container::ComponentGetter;
+use itp_import_queue::PeekQueue;
 use itp_primitives_cache::{GetPrimitives, GLOBAL_PRIMITIVES_CACHE};
 use itp_rpc::RpcReturnValue;
-use itp_sgx_crypto::key_repository::AccessPubkey;
+use itp_sgx_crypto::{
+	key_repository::{AccessKey, AccessPubkey},
+	ShieldingCryptoDecrypt,
+};
 use itp_sgx_externalities::SgxExternalitiesTrait;
 use itp_stf_executor::getter_executor::ExecuteGetter;
 use itp_stf_primitives::types::AccountId;
-use itp_stf_state_handler::handle_state::HandleState;
+use itp_stf_state_handler::{handle_state::HandleState, query_shard_state::QueryShardState};
 use itp_top_pool_author::traits::AuthorApi;
 use itp_types::{
-	DirectRequestStatus, Index, MrEnclave, Request, ShardIdentifier, SidechainBlockNumber, H256,
+	parentchain::BlockNumber as ParentchainBlockNumber, DirectRequestStatus, Index, MrEnclave,
+	Request, ShardIdentifier, SidechainBlockNumber, TrustedOperationStatus, WorkerHealth, H256,
 };
 use itp_utils::{FromHexPrefixed, ToHexPrefixed};
-use its_primitives::types::block::SignedBlock;
-use its_sidechain::rpc_handler::{
-	direct_top_pool_api, direct_top_pool_api::decode_shard_from_base58, import_block_api,
+use its_primitives::{
+	traits::{Block as SidechainBlockTrait, Header as SidechainHeaderTrait},
+	types::block::{Block as SidechainBlock, SignedBlock},
+};
+use its_sidechain::{
+	rpc_handler::{
+		direct_top_pool_api, direct_top_pool_api::decode_shard_from_base58, import_block_api,
+	},
+	state::LastBlockExt,
+	validateer_fetch::ValidateerFetch,
 };
 use jsonrpc_core::{serde_json::json, IoHandler, Params, Value};
+use lc_data_providers::{update_data_provider_config, SignedDataProviderConfig};
 use lc_scheduled_enclave::{ScheduledEnclaveUpdater, GLOBAL_SCHEDULED_ENCLAVE};
+use litentry_primitives::Identity;
 use log::debug;
 use sgx_crypto_helper::rsa3072::Rsa3072PubKey;
-use sp_runtime::OpaqueExtrinsic;
+use sp_runtime::{traits::Header as HeaderTrait, OpaqueExtrinsic};
 use std::{borrow::ToOwned, format, str, string::String, sync::Arc, vec::Vec};
 
+const SYSTEM_NAME: &str = "litentry-worker";
+const SYSTEM_VERSION: &str = env!("CARGO_PKG_VERSION");
+
 fn compute_hex_encoded_return_error(error_msg: &str) -> String {
 	RpcReturnValue::from_error_message(error_msg).to_hex()
 }
@@ -108,6 +133,7 @@ where
 
 	// author_getNextNonce
 	let state_storage = state.clone();
+	let state_data_provider_config = state.clone();
 
 	let author_get_next_nonce: &str = "author_getNextNonce";
 	io.add_sync_method(author_get_next_nonce, move |params: Params| {
@@ -196,10 +222,29 @@ where
 	});
 
 	// chain_subscribeAllHeads
+	// The connection is watched under the returned subscription id, every new sidechain
+	// header is then streamed as SCALE encoded `value` of the response.
 	let chain_subscribe_all_heads_name: &str = "chain_subscribeAllHeads";
 	io.add_sync_method(chain_subscribe_all_heads_name, |_: Params| {
-		let parsed = "world";
-		Ok(Value::String(format!("hello, {}", parsed)))
+		let json_value = match GLOBAL_HEAD_SUBSCRIPTIONS_COMPONENT.get() {
+			Ok(head_subscriptions) => {
+				let subscription_id = head_subscriptions.subscribe();
+				RpcReturnValue {
+					do_watch: true,
+					value: subscription_id.encode(),
+					status: DirectRequestStatus::TrustedOperationStatus(
+						TrustedOperationStatus::Submitted,
+						subscription_id,
+					),
+				}
+				.to_hex()
+			},
+			Err(e) => {
+				let error_msg: String = format!("Could not subscribe to heads due to: {:?}", e);
+				compute_hex_encoded_return_error(error_msg.as_str())
+			},
+		};
+		Ok(json!(json_value))
 	});
 
 	// state_getMetadata
@@ -213,8 +258,8 @@ where
 	// state_getRuntimeVersion
 	let state_get_runtime_version_name: &str = "state_getRuntimeVersion";
 	io.add_sync_method(state_get_runtime_version_name, |_: Params| {
-		let parsed = "world";
-		Ok(Value::String(format!("hello, {}", parsed)))
+		let json_value = RpcReturnValue::new(VERSION.encode(), false, DirectRequestStatus::Ok);
+		Ok(json!(json_value.to_hex()))
 	});
 
 	// state_executeGetter
@@ -376,25 +421,48 @@ where
 		});
 	}
 
+	// state_updateDataProviderConfig
+	// params: shard (base58), hex encoded `SignedDataProviderConfig` encrypted with the shielding key
+	let update_data_provider_config_name: &str = "state_updateDataProviderConfig";
+	io.add_sync_method(update_data_provider_config_name, move |params: Params| {
+		let state = match state_data_provider_config.as_ref() {
+			Some(state) => state,
+			None =>
+				return Ok(json!(compute_hex_encoded_return_error(
+					"state_updateDataProviderConfig is not available"
+				))),
+		};
+		match update_data_provider_config_inner(state.as_ref(), params) {
+			Ok(()) =>
+				Ok(json!(RpcReturnValue::new(vec![], false, DirectRequestStatus::Ok).to_hex())),
+			Err(error) => {
+				let error_msg = format!("Failed to update data provider config: {}", error);
+				Ok(json!(compute_hex_encoded_return_error(error_msg.as_str())))
+			},
+		}
+	});
+
 	// system_health
 	let state_health_name: &str = "system_health";
 	io.add_sync_method(state_health_name, |_: Params| {
-		let parsed = "world";
-		Ok(Value::String(format!("hello, {}", parsed)))
+		let json_value =
+			RpcReturnValue::new(get_worker_health().encode(), false, DirectRequestStatus::Ok);
+		Ok(json!(json_value.to_hex()))
 	});
 
 	// system_name
 	let state_name_name: &str = "system_name";
 	io.add_sync_method(state_name_name, |_: Params| {
-		let parsed = "world";
-		Ok(Value::String(format!("hello, {}", parsed)))
+		let json_value = RpcReturnValue::new(SYSTEM_NAME.encode(), false, DirectRequestStatus::Ok);
+		Ok(json!(json_value.to_hex()))
 	});
 
 	// system_version
 	let state_version_name: &str = "system_version";
 	io.add_sync_method(state_version_name, |_: Params| {
-		let parsed = "world";
-		Ok(Value::String(format!("hello, {}", parsed)))
+		let json_value =
+			RpcReturnValue::new(SYSTEM_VERSION.encode(), false, DirectRequestStatus::Ok);
+		Ok(json!(json_value.to_hex()))
 	});
 
 	// returns all rpcs methods
@@ -406,6 +474,89 @@ where
 	io
 }
 
+/// Collect the health of this worker. The rpc server is started before the parentchain and
+/// sidechain components are initialized, so every part is optional and missing parts are left
+/// at their default value.
+fn get_worker_health() -> WorkerHealth {
+	let top_pool_author = GLOBAL_TOP_POOL_AUTHOR_COMPONENT.get().ok();
+	let import_queue_size = GLOBAL_SIDECHAIN_IMPORT_QUEUE_COMPONENT
+		.get()
+		.ok()
+		.map(|import_queue| import_queue.peek_queue_size().unwrap_or_default());
+	let state_handler = GLOBAL_STATE_HANDLER_COMPONENT.get().ok();
+
+	let maybe_parentchain_header = get_validator_accessor_from_solo_or_parachain()
+		.ok()
+		.and_then(|v| v.execute_on_validator(|v| v.latest_finalized_header()).ok());
+	let parentchain = maybe_parentchain_header.map(|header| {
+		let validateer_count = GLOBAL_OCALL_API_COMPONENT
+			.get()
+			.ok()
+			.and_then(|ocall_api| ocall_api.validateer_count(&header).ok())
+			.unwrap_or_default();
+		(*header.number(), header.hash(), validateer_count)
+	});
+
+	worker_health(
+		top_pool_author.as_deref(),
+		import_queue_size,
+		state_handler.as_deref(),
+		parentchain,
+	)
+}
+
+/// `parentchain` is the (number, hash) of the last finalized parentchain block and the
+/// number of validateers registered at it.
+fn worker_health<A, S>(
+	top_pool_author: Option<&A>,
+	import_queue_size: Option<usize>,
+	state_handler: Option<&S>,
+	parentchain: Option<(ParentchainBlockNumber, H256, u64)>,
+) -> WorkerHealth
+where
+	A: AuthorApi<H256, H256>,
+	S: HandleState + QueryShardState,
+	S::StateT: LastBlockExt<SidechainBlock>,
+{
+	let mut health = WorkerHealth::default();
+
+	if let Some(top_pool_author) = top_pool_author {
+		let top_pool_size: usize = top_pool_author
+			.get_shards()
+			.into_iter()
+			.map(|shard| top_pool_author.pending_tops(shard).map(|t| t.len()).unwrap_or_default())
+			.sum();
+		health.top_pool_size = top_pool_size.try_into().unwrap_or(u32::MAX);
+	}
+
+	health.is_syncing = import_queue_size.unwrap_or_default() > 0;
+
+	if let Some(state_handler) = state_handler {
+		health.last_sidechain_blocks = state_handler
+			.list_shards()
+			.unwrap_or_default()
+			.into_iter()
+			.filter_map(|shard| {
+				let last_block = state_handler
+					.execute_on_current(&shard, |state, _| {
+						LastBlockExt::<SidechainBlock>::get_last_block(state)
+					})
+					.ok()
+					.flatten()?;
+				Some((shard, last_block.header().block_number(), last_block.hash()))
+			})
+			.collect();
+	}
+
+	if let Some((number, hash, validateer_count)) = parentchain {
+		health.last_parentchain_block = Some((number, hash));
+		health.peers = validateer_count.saturating_sub(1).try_into().unwrap_or(u32::MAX);
+		health.should_have_peers = validateer_count > 1;
+	}
+
+	health
+}
+
 fn execute_getter_inner<G: ExecuteGetter>(
 	getter_executor: &G,
 	params: Params,
@@ -425,6 +576,46 @@ fn execute_getter_inner<G: ExecuteGetter>(
 	Ok(getter_result)
 }
 
+// The update must be signed by the sudo account of the shard.
+fn update_data_provider_config_inner<S>(state: &S, params: Params) -> Result<(), String>
+where
+	S: HandleState,
+	S::StateT: SgxExternalitiesTrait,
+{
+	let (shard_str, encrypted_config) =
+		params.parse::<(String, String)>().map_err(|e| format!("{:?}", e))?;
+	let shard = decode_shard_from_base58(shard_str.as_str())?;
+	let encrypted_config =
+		itp_utils::hex::decode_hex(encrypted_config).map_err(|e| format!("{:?}", e))?;
+
+	let shielding_key = GLOBAL_SHIELDING_KEY_REPOSITORY_COMPONENT
+		.get()
+		.map_err(|e| format!("{:?}", e))?
+		.retrieve_key()
+		.map_err(|e| format!("{:?}", e))?;
+	let decrypted_config = shielding_key
+		.decrypt(encrypted_config.as_slice())
+		.map_err(|e| format!("{:?}", e))?;
+	let signed_config = SignedDataProviderConfig::decode(&mut decrypted_config.as_slice())
+		.map_err(|e| format!("{:?}", e))?;
+	if signed_config.shard != shard {
+		return Err("Config is signed for another shard".into())
+	}
+
+	let (mut shard_state, _) = state.load_cloned(&shard).map_err(|e| format!("{:?}", e))?;
+	let root: AccountId = shard_state
+		.execute_with(Sudo::key)
+		.ok_or_else(|| "No root account".to_string())?;
+	if signed_config.signer != Identity::Substrate(root.into()) {
+		return Err("Signer is not the root account of the shard".into())
+	}
+	if !signed_config.verify_signature() {
+		return Err("Invalid signature".into())
+	}
+
+	update_data_provider_config(shard, signed_config.config).map_err(|e| format!("{:?}", e))
+}
+
 fn forward_dcap_quote_inner(params: Params) -> Result<OpaqueExtrinsic, String> {
 	let hex_encoded_params = params.parse::<Vec<String>>().map_err(|e| format!("{:?}", e))?;
 
@@ -495,7 +686,10 @@ where
 #[cfg(feature = "test")]
 pub mod tests {
 	use super::*;
-	use std::string::ToString;
+	use itp_test::mock::handle_state_mock::HandleStateMock;
+	use itp_top_pool_author::mocks::AuthorApiMock;
+	use jsonrpc_core::futures::executor;
+	use std::{string::ToString, vec};
 
 	pub fn test_given_io_handler_methods_then_retrieve_all_names_as_string() {
 		let mut io = IoHandler::new();
@@ -511,4 +705,45 @@ pub mod tests {
 			assert!(method_string.contains(method_name));
 		}
 	}
+
+	pub fn test_worker_health_without_components_is_default() {
+		let health =
+			worker_health::<AuthorApiMock<H256, H256>, HandleStateMock>(None, None, None, None);
+		assert_eq!(health, WorkerHealth::default());
+	}
+
+	pub fn test_worker_health_collects_all_components() {
+		let shard = ShardIdentifier::default();
+		let top_pool_author = AuthorApiMock::<H256, H256>::default();
+		executor::block_on(top_pool_author.submit_top(vec![1u8], shard)).unwrap();
+		executor::block_on(top_pool_author.submit_top(vec![2u8], shard)).unwrap();
+		let state_handler = HandleStateMock::from_shard(shard).unwrap();
+
+		let health = worker_health(
+			Some(&top_pool_author),
+			Some(3),
+			Some(&state_handler),
+			Some((10, H256::repeat_byte(1), 3)),
+		);
+
+		assert_eq!(health.top_pool_size, 2);
+		assert!(health.is_syncing);
+		// no sidechain block has been imported yet
+		assert!(health.last_sidechain_blocks.is_empty());
+		assert_eq!(health.last_parentchain_block, Some((10, H256::repeat_byte(1))));
+		assert_eq!(health.peers, 2);
+		assert!(health.should_have_peers);
+	}
+
+	pub fn test_worker_health_of_single_validateer_has_no_peers() {
+		let health = worker_health::<AuthorApiMock<H256, H256>, HandleStateMock>(
+			None,
+			Some(0),
+			None,
+			Some((10, H256::repeat_byte(1), 1)),
+		);
+		assert!(!health.is_syncing);
+		assert_eq!(health.peers, 0);
+		assert!(!health.should_have_peers);
+	}
 }
@@ -15,47 +15,39 @@
 // along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
 
 use crate::utils::DecodeRaw;
+use codec::Encode;
 use itp_component_container::ComponentGetter;
-use itp_sgx_crypto::key_repository::AccessKey;
-use lc_data_providers::{DataProviderConfig, GLOBAL_DATA_PROVIDER_CONFIG};
+use itp_sgx_crypto::{key_repository::AccessKey, Aes};
+use itp_types::ShardIdentifier;
+use lc_data_providers::{init_data_provider_config, DataProviderConfig};
 use lc_stf_task_receiver::{run_stf_task_receiver, StfTaskContext};
 use log::*;
 use sgx_types::sgx_status_t;
+use sp_core::blake2_256;
 use std::sync::Arc;
 
 use crate::{
 	error::{Error, Result},
 	initialization::global_components::{
 		EnclaveStfEnclaveSigner, GLOBAL_OCALL_API_COMPONENT,
-		GLOBAL_SHIELDING_KEY_REPOSITORY_COMPONENT, GLOBAL_STATE_OBSERVER_COMPONENT,
-		GLOBAL_TOP_POOL_AUTHOR_COMPONENT,
+		GLOBAL_SHIELDING_KEY_REPOSITORY_COMPONENT, GLOBAL_STATE_KEY_REPOSITORY_COMPONENT,
+		GLOBAL_STATE_OBSERVER_COMPONENT, GLOBAL_TOP_POOL_AUTHOR_COMPONENT,
 	},
 	GLOBAL_STATE_HANDLER_COMPONENT,
 };
 
 #[no_mangle]
 pub unsafe extern "C" fn run_stf_task_handler(dpc: *const u8, dpc_size: usize) -> sgx_status_t {
-	let data_provider_config = match DataProviderConfig::decode_raw(dpc, dpc_size) {
-		Ok(data_provider_config) => data_provider_config,
-		Err(e) => return Error::Codec(e).into(),
-	};
+	let (shard, data_provider_config) =
+		match <(ShardIdentifier, DataProviderConfig)>::decode_raw(dpc, dpc_size) {
+			Ok(decoded) => decoded,
+			Err(e) => return Error::Codec(e).into(),
+		};
 
-	match GLOBAL_DATA_PROVIDER_CONFIG.write() {
-		Ok(mut dpc) => {
-			dpc.set_twitter_official_url(data_provider_config.twitter_official_url);
-			dpc.set_twitter_litentry_url(data_provider_config.twitter_litentry_url);
-			dpc.set_twitter_auth_token_v2(data_provider_config.twitter_auth_token_v2);
-			dpc.set_discord_official_url(data_provider_config.discord_official_url);
-			dpc.set_discord_litentry_url(data_provider_config.discord_litentry_url);
-			dpc.set_discord_auth_token(data_provider_config.discord_auth_token);
-			dpc.set_achainable_url(data_provider_config.achainable_url);
-			dpc.set_achainable_auth_key(data_provider_config.achainable_auth_key);
-			dpc.set_credential_endpoint(data_provider_config.credential_endpoint);
-		},
-		Err(e) => {
-			error!("Error while setting data provider config: {:?}", e);
-			return Error::MutexAccess.into()
-		},
+	// the sealed config of the shard takes precedence unless the passed one has a higher version
+	if let Err(e) = init_data_provider_config(shard, data_provider_config) {
+		error!("Error while setting data provider config: {:?}", e);
+		return Error::DataProviderConfig(e).into()
 	}
 
 	if let Err(e) = run_stf_task_handler_internal() {
@@ -78,6 +70,7 @@ fn run_stf_task_handler_internal() -> Result<()> {
 	let shielding_key_repository = GLOBAL_SHIELDING_KEY_REPOSITORY_COMPONENT.get()?;
 	#[allow(clippy::unwrap_used)]
 	let shielding_key = shielding_key_repository.retrieve_key().unwrap();
+	let state_key = GLOBAL_STATE_KEY_REPOSITORY_COMPONENT.get()?.retrieve_key()?;
 
 	let ocall_api = GLOBAL_OCALL_API_COMPONENT.get()?;
 	let stf_enclave_signer = Arc::new(EnclaveStfEnclaveSigner::new(
@@ -93,7 +86,16 @@ fn run_stf_task_handler_internal() -> Result<()> {
 		stf_enclave_signer,
 		state_handler,
 		ocall_api,
+		pairwise_secret(&state_key),
 	);
 
 	run_stf_task_receiver(Arc::new(stf_task_context)).map_err(Error::StfTaskReceiver)
 }
+
+/// The secret of the pairwise-pseudonymous subject DIDs.
+///
+/// It's derived from the sealed state key, which is provisioned to all workers, so that every
+/// worker issues the same pseudonym to a given verifier.
+fn pairwise_secret(state_key: &Aes) -> [u8; 32] {
+	blake2_256(&(b"litentry/pairwise-secret", state_key).encode())
+}
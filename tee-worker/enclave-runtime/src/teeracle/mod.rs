@@ -38,6 +38,7 @@ use ita_oracle::{
 use itp_component_container::ComponentGetter;
 use itp_extrinsics_factory::CreateExtrinsics;
 use itp_node_api::metadata::{pallet_teeracle::TeeracleCallIndexes, provider::AccessNodeMetadata};
+use itp_time_utils::now_as_millis;
 use itp_types::OpaqueCall;
 use itp_utils::write_slice_and_whitespace_pad;
 use log::*;
@@ -239,6 +240,8 @@ where
 	let (rate, base_url) = oracle
 		.get_exchange_rate(trading_pair.clone())
 		.map_err(|e| Error::Other(e.into()))?;
+	// the time at which the rate was fetched, so that the parentchain can tell a stale one
+	let timestamp = now_as_millis();
 
 	let source_base_url = base_url.as_str();
 
@@ -261,6 +264,7 @@ where
 		source_base_url.as_bytes().to_vec(),
 		trading_pair.key().as_bytes().to_vec(),
 		Some(rate),
+		timestamp,
 	));
 
 	Ok(call)
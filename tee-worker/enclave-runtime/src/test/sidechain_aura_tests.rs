@@ -116,6 +116,7 @@ pub fn produce_sidechain_block_and_import_it() {
 		top_pool_author.clone(),
 		parentchain_block_import_trigger.clone(),
 		ocall_api.clone(),
+		Arc::new(Default::default()),
 	));
 	let block_composer = Arc::new(TestBlockComposer::new(signer.clone(), state_key_repo.clone()));
 	let proposer_environment =
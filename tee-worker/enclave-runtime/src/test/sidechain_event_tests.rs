@@ -104,6 +104,7 @@ pub fn ensure_events_get_reset_upon_block_proposal() {
 		top_pool_author.clone(),
 		parentchain_block_import_trigger.clone(),
 		ocall_api.clone(),
+		Arc::new(Default::default()),
 	));
 	let block_composer = Arc::new(TestBlockComposer::new(signer.clone(), state_key_repo.clone()));
 	let proposer_environment =
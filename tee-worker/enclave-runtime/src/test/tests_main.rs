@@ -116,6 +116,14 @@ pub extern "C" fn test_main_entrance() -> size_t {
 		test_retrieve_event_count,
 		test_reset_events,
 		rpc::worker_api_direct::tests::test_given_io_handler_methods_then_retrieve_all_names_as_string,
+		rpc::worker_api_direct::tests::test_worker_health_without_components_is_default,
+		rpc::worker_api_direct::tests::test_worker_health_collects_all_components,
+		rpc::worker_api_direct::tests::test_worker_health_of_single_validateer_has_no_peers,
+		rpc::head_subscriptions::tests::notify_pushes_header_to_all_subscribers,
+		rpc::head_subscriptions::tests::notify_skips_headers_that_are_not_newer,
+		rpc::head_subscriptions::tests::subscriber_is_kept_until_its_connection_is_registered,
+		rpc::head_subscriptions::tests::unreachable_subscriber_is_dropped_after_grace_period,
+		rpc::head_subscriptions::tests::subscriber_with_closed_connection_is_dropped,
 		handle_state_mock::tests::initialized_shards_list_is_empty,
 		handle_state_mock::tests::shard_exists_after_inserting,
 		handle_state_mock::tests::from_shard_works,
@@ -18,7 +18,9 @@
 use crate::{
 	error::Result,
 	initialization::global_components::{
-		GLOBAL_OCALL_API_COMPONENT, GLOBAL_SIDECHAIN_BLOCK_COMPOSER_COMPONENT,
+		EnclaveOCallApi, EnclaveStateHandler, EnclaveTopPoolAuthor,
+		GLOBAL_HEAD_SUBSCRIPTIONS_COMPONENT, GLOBAL_OCALL_API_COMPONENT,
+		GLOBAL_SIDECHAIN_BLOCK_COMPOSER_COMPONENT, GLOBAL_SIDECHAIN_FORK_TREES_COMPONENT,
 		GLOBAL_SIDECHAIN_IMPORT_QUEUE_WORKER_COMPONENT, GLOBAL_SIGNING_KEY_REPOSITORY_COMPONENT,
 		GLOBAL_STATE_HANDLER_COMPONENT, GLOBAL_TOP_POOL_AUTHOR_COMPONENT,
 	},
@@ -45,17 +47,26 @@ use itp_sgx_crypto::key_repository::AccessKey;
 use itp_sgx_externalities::SgxExternalities;
 use itp_stf_state_handler::{handle_state::HandleState, query_shard_state::QueryShardState};
 use itp_time_utils::duration_now;
+use itp_top_pool_author::traits::AuthorApi;
 use itp_types::{Block, OpaqueCall, H256};
 use its_primitives::{
 	traits::{
-		Block as SidechainBlockTrait, Header as HeaderTrait, ShardIdentifierFor, SignedBlock,
+		Block as SidechainBlockTrait, BlockData, Header as HeaderTrait, ShardIdentifierFor,
+		SignedBlock,
+	},
+	types::{
+		block::{Block as SidechainBlock, SignedBlock as SignedSidechainBlock},
+		header::SidechainHeader,
 	},
-	types::block::SignedBlock as SignedSidechainBlock,
 };
 use its_sidechain::{
 	aura::{proposer_factory::ProposerFactory, Aura, SlotClaimStrategy},
-	consensus_common::{Environment, Error as ConsensusError, ProcessBlockImportQueue},
+	consensus_common::{
+		finalize_confirmed_blocks, Environment, Error as ConsensusError, ProcessBlockImportQueue,
+		RetainedOperation,
+	},
 	slots::{yield_next_slot, LastSlot, PerShardSlotWorkerScheduler, SlotInfo},
+	state::LastBlockExt,
 	validateer_fetch::ValidateerFetch,
 };
 use lc_scheduled_enclave::{ScheduledEnclaveUpdater, GLOBAL_SCHEDULED_ENCLAVE};
@@ -132,8 +143,18 @@ fn execute_top_pool_trusted_calls_internal() -> Result<()> {
 
 	let state_handler = GLOBAL_STATE_HANDLER_COMPONENT.get()?;
 
+	// Stream the headers of imported sidechain blocks to the `chain_subscribeAllHeads` subscribers.
+	notify_head_subscribers(last_imported_sidechain_headers(state_handler.as_ref()));
+
 	let ocall_api = GLOBAL_OCALL_API_COMPONENT.get()?;
 
+	// Only blocks whose confirmation is part of a finalized parentchain block are final.
+	finalize_parentchain_confirmed_blocks(
+		ocall_api.as_ref(),
+		state_handler.as_ref(),
+		&current_parentchain_header,
+	);
+
 	let authority = GLOBAL_SIGNING_KEY_REPOSITORY_COMPONENT.get()?.retrieve_key()?;
 
 	match yield_next_slot(
@@ -152,7 +173,7 @@ fn execute_top_pool_trusted_calls_internal() -> Result<()> {
 
 			let shards = state_handler.list_shards()?;
 			let env = ProposerFactory::<Block, _, _, _>::new(
-				top_pool_author,
+				top_pool_author.clone(),
 				stf_executor,
 				block_composer,
 			);
@@ -166,11 +187,17 @@ fn execute_top_pool_trusted_calls_internal() -> Result<()> {
 					env,
 					shards,
 					GLOBAL_SCHEDULED_ENCLAVE.clone(),
-					state_handler,
+					state_handler.clone(),
 				)?;
 
 			debug!("Aura executed successfully");
 
+			let produced_headers: Vec<SidechainHeader> =
+				blocks.iter().map(|b| b.block().header().clone()).collect();
+
+			track_produced_blocks(&blocks, state_handler.as_ref(), top_pool_author.as_ref());
+			notify_head_subscribers(produced_headers);
+
 			// Drop lock as soon as we don't need it anymore.
 			drop(_enclave_write_lock);
 
@@ -292,6 +319,93 @@ where
 	Ok(())
 }
 
+/// Track the blocks we authored as canonical heads in the fork trees, so that competing blocks
+/// of other validateers can be recognized as forks. The executed operations are retained, to be
+/// re-submitted in case a block gets retracted.
+fn track_produced_blocks(
+	blocks: &[SignedSidechainBlock],
+	state_handler: &EnclaveStateHandler,
+	top_pool_author: &EnclaveTopPoolAuthor,
+) {
+	let fork_trees = match GLOBAL_SIDECHAIN_FORK_TREES_COMPONENT.get() {
+		Ok(fork_trees) => fork_trees,
+		Err(e) => {
+			warn!("Failed to get sidechain fork trees: {:?}", e);
+			return
+		},
+	};
+
+	for block in blocks {
+		let shard = block.block().header().shard_id();
+		let top_hashes = block.block().block_data().signed_top_hashes();
+		let operations: Vec<RetainedOperation> = top_pool_author
+			.get_pending_trusted_calls(shard)
+			.into_iter()
+			.map(|operation| (top_pool_author.hash_of(&operation), operation))
+			.filter(|(hash, _)| top_hashes.contains(hash))
+			.map(|(hash, operation)| (hash, operation.encode()))
+			.collect();
+
+		let result = state_handler
+			.execute_on_current(&shard, |_, state_hash| state_hash)
+			.map_err(|e| ConsensusError::Other(format!("{:?}", e).into()))
+			.and_then(|state_hash| fork_trees.on_canonical_block(block.clone(), state_hash))
+			.and_then(|_| {
+				fork_trees.with_fork_tree(&shard, |tree| {
+					tree.retain_operations(&block.hash(), operations)
+				})
+			});
+		if let Err(e) = result {
+			warn!("Failed to track produced sidechain block in fork tree: {:?}", e);
+		}
+	}
+}
+
+/// Finalize the sidechain blocks whose import confirmation is part of the finalized
+/// `parentchain_header`.
+fn finalize_parentchain_confirmed_blocks(
+	ocall_api: &EnclaveOCallApi,
+	state_handler: &EnclaveStateHandler,
+	parentchain_header: &<Block as BlockTrait>::Header,
+) {
+	let result = GLOBAL_SIDECHAIN_FORK_TREES_COMPONENT
+		.get()
+		.map_err(|e| ConsensusError::Other(format!("{:?}", e).into()))
+		.and_then(|fork_trees| {
+			let shards = state_handler
+				.list_shards()
+				.map_err(|e| ConsensusError::Other(format!("{:?}", e).into()))?;
+			finalize_confirmed_blocks(fork_trees.as_ref(), ocall_api, &shards, parentchain_header)
+		});
+	if let Err(e) = result {
+		warn!("Failed to finalize confirmed sidechain blocks: {:?}", e);
+	}
+}
+
+fn last_imported_sidechain_headers(state_handler: &EnclaveStateHandler) -> Vec<SidechainHeader> {
+	state_handler
+		.list_shards()
+		.unwrap_or_default()
+		.iter()
+		.filter_map(|shard| {
+			state_handler
+				.execute_on_current(shard, |state, _| {
+					LastBlockExt::<SidechainBlock>::get_last_block(state)
+				})
+				.ok()
+				.flatten()
+				.map(|block| block.header().clone())
+		})
+		.collect()
+}
+
+fn notify_head_subscribers(headers: Vec<SidechainHeader>) {
+	match GLOBAL_HEAD_SUBSCRIPTIONS_COMPONENT.get() {
+		Ok(head_subscriptions) => headers.iter().for_each(|h| head_subscriptions.notify(h)),
+		Err(e) => warn!("Failed to notify head subscribers: {:?}", e),
+	}
+}
+
 fn log_remaining_slot_duration<B: BlockTrait<Hash = H256>>(
 	slot_info: &SlotInfo<B>,
 	stage_name: &str,
@@ -20,12 +20,14 @@ compile_error!("feature \"std\" and feature \"sgx\" cannot be enabled at the sam
 #[cfg(all(not(feature = "std"), feature = "sgx"))]
 extern crate sgx_tstd as std;
 
-use crate::*;
+use crate::{
+	holding_time::{resolve_holding_time, HoldingQuery, HoldingTime},
+	*,
+};
 use lc_data_providers::{
-	achainable::{AchainableClient, AchainableHolder, ParamsBasicTypeWithAmountHolding},
-	vec_to_string, WBTC_TOKEN_ADDRESS,
+	achainable::AchainableClient, evm::EvmRpcClient, get_assertion_sources, vec_to_string,
+	AssertionSource, WBTC_TOKEN_ADDRESS,
 };
-use std::string::ToString;
 
 const VC_A10_SUBJECT_DESCRIPTION: &str =
 	"The length of time a user continues to hold a particular token (with particular threshold of token amount)";
@@ -39,40 +41,35 @@ pub fn build(req: &AssertionBuildRequest, min_balance: ParameterString) -> Resul
 		Error::RequestVCFailed(Assertion::A10(min_balance.clone()), ErrorDetail::ParseError)
 	})?;
 
-	let mut client = AchainableClient::new();
-	let identities = transpose_identity(&req.identities);
-	let addresses = identities
-		.into_iter()
-		.flat_map(|(_, addresses)| addresses)
-		.collect::<Vec<String>>();
-
-	let mut is_hold = false;
-	let mut optimal_hold_index = 0;
-	for (index, date) in ASSERTION_FROM_DATE.iter().enumerate() {
-		if is_hold {
-			break
-		}
-
-		for address in &addresses {
-			let holding = ParamsBasicTypeWithAmountHolding::new(
-				&Web3Network::Ethereum,
-				q_min_balance.to_string(),
-				date.to_string(),
-				Some(WBTC_TOKEN_ADDRESS.into()),
-			);
-
-			let is_wbtc_holder = client.is_holder(address, holding).map_err(|e| {
-				error!("Assertion A10 request is_holder error: {:?}", e);
-				Error::RequestVCFailed(Assertion::A10(min_balance.clone()), e.into_error_detail())
-			})?;
-			if is_wbtc_holder {
-				optimal_hold_index = index;
-				is_hold = true;
-
-				break
-			}
-		}
+	let queries = vec![(
+		HoldingQuery {
+			network: Web3Network::Ethereum,
+			token: Some(WBTC_TOKEN_ADDRESS.into()),
+			amount: q_min_balance.clone(),
+		},
+		transpose_identity(&req.identities)
+			.into_iter()
+			.flat_map(|(_, addresses)| addresses)
+			.collect(),
+	)];
+	let source = get_assertion_sources()
+		.map_err(|e| {
+			Error::RequestVCFailed(Assertion::A10(min_balance.clone()), e.into_error_detail())
+		})?
+		.a10;
+	let holding_time = match source {
+		AssertionSource::Achainable => resolve_holding_time(source, AchainableClient::new, queries),
+		AssertionSource::Native => resolve_holding_time(source, EvmRpcClient::new, queries),
 	}
+	.map_err(|e| {
+		error!("Assertion A10 request is_holder error: {:?}", e);
+		Error::RequestVCFailed(Assertion::A10(min_balance.clone()), e.into_error_detail())
+	})?;
+	info!(
+		"Assertion A10 cost {} provider calls, {} cached",
+		holding_time.provider_calls, holding_time.cache_hits
+	);
+	let HoldingTime { is_hold, optimal_hold_index, .. } = holding_time;
 
 	match Credential::new(&req.who, &req.shard) {
 		Ok(mut credential_unsigned) => {
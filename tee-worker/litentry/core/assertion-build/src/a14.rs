@@ -25,7 +25,7 @@ use crate::sgx_reexport_prelude::*;
 
 use crate::*;
 use blake2_rfc::blake2b::Blake2b;
-use http::header::{AUTHORIZATION, CONNECTION};
+use http::header::CONNECTION;
 use http_req::response::Headers;
 use itc_rest_client::{
 	error::Error as RestClientError,
@@ -33,7 +33,11 @@ use itc_rest_client::{
 	rest_client::RestClient,
 	RestPath, RestPost,
 };
-use lc_data_providers::{build_client, GLOBAL_DATA_PROVIDER_CONFIG};
+use lc_data_providers::{
+	build_client, get_assertion_sources, get_provider_config,
+	native::{NativeClient, ParentchainAnchor},
+	AssertionSource, DataProvider,
+};
 use rust_base58::ToBase58;
 use serde::{Deserialize, Serialize};
 use ss58_registry::Ss58AddressFormat;
@@ -106,23 +110,19 @@ pub struct A14Client {
 	client: RestClient<HttpClient<DefaultSend>>,
 }
 
-impl Default for A14Client {
-	fn default() -> Self {
-		Self::new()
-	}
-}
-
 impl A14Client {
-	pub fn new() -> Self {
+	pub fn new() -> Result<Self> {
 		let mut headers = Headers::new();
 		headers.insert(CONNECTION.as_str(), "close");
-		headers.insert(
-			AUTHORIZATION.as_str(),
-			GLOBAL_DATA_PROVIDER_CONFIG.read().unwrap().achainable_auth_key.clone().as_str(),
-		);
+		// the label endpoint is not part of the achainable endpoints, but shares its auth
+		get_provider_config(DataProvider::Achainable)
+			.map_err(|e| Error::RequestVCFailed(Assertion::A14, e.into_error_detail()))?
+			.auth
+			.apply(&mut headers);
 		let client =
-			build_client("https://label-production.graph.tdf-labs.io/v1/run/label/a719e99c-1f9b-432e-8f1d-cb3de0f14dde", headers);
-		A14Client { client }
+			build_client("https://label-production.graph.tdf-labs.io/v1/run/label/a719e99c-1f9b-432e-8f1d-cb3de0f14dde", headers)
+				.map_err(|e| Error::RequestVCFailed(Assertion::A14, e.into_error_detail()))?;
+		Ok(A14Client { client })
 	}
 
 	pub fn send_request(&mut self, data: &A14Data) -> Result<A14Response> {
@@ -139,9 +139,52 @@ impl A14Client {
 	}
 }
 
-pub fn build(req: &AssertionBuildRequest) -> Result<Credential> {
+pub fn build(req: &AssertionBuildRequest, anchor: Option<ParentchainAnchor>) -> Result<Credential> {
 	debug!("Assertion A14 build, who: {:?}", account_id_to_string(&req.who));
 
+	let source = get_assertion_sources()
+		.map_err(|e| Error::RequestVCFailed(Assertion::A14, e.into_error_detail()))?
+		.a14;
+	let value = match source {
+		AssertionSource::Achainable => has_voted_achainable(req)?,
+		AssertionSource::Native => has_voted_native(req, anchor)?,
+	};
+
+	match Credential::new(&req.who, &req.shard) {
+		Ok(mut credential_unsigned) => {
+			// add subject info
+			credential_unsigned.add_subject_info(VC_A14_SUBJECT_DESCRIPTION, VC_A14_SUBJECT_TYPE);
+
+			// add assertion
+			credential_unsigned.add_assertion_a14(value);
+			Ok(credential_unsigned)
+		},
+		Err(e) => {
+			error!("Generate unsigned credential failed {:?}", e);
+			Err(Error::RequestVCFailed(Assertion::A14, e.into_error_detail()))
+		},
+	}
+}
+
+fn has_voted_native(
+	req: &AssertionBuildRequest,
+	anchor: Option<ParentchainAnchor>,
+) -> Result<bool> {
+	let mut client = NativeClient::new(anchor);
+	for identity in &req.identities {
+		if let Identity::Substrate(address) = identity.0 {
+			let has_voted = client
+				.has_voted(Web3Network::Polkadot, &AccountId::from(address))
+				.map_err(|e| Error::RequestVCFailed(Assertion::A14, e.into_error_detail()))?;
+			if has_voted {
+				return Ok(true)
+			}
+		}
+	}
+	Ok(false)
+}
+
+fn has_voted_achainable(req: &AssertionBuildRequest) -> Result<bool> {
 	// achainable expects polkadot addresses (those start with 1...)
 	let mut polkadot_addresses = vec![];
 	for identity in &req.identities {
@@ -153,7 +196,7 @@ pub fn build(req: &AssertionBuildRequest) -> Result<Credential> {
 	}
 
 	let mut value = false;
-	let mut client = A14Client::new();
+	let mut client = A14Client::new()?;
 
 	for address in polkadot_addresses {
 		let data = A14Data {
@@ -173,19 +216,5 @@ pub fn build(req: &AssertionBuildRequest) -> Result<Credential> {
 			break
 		}
 	}
-
-	match Credential::new(&req.who, &req.shard) {
-		Ok(mut credential_unsigned) => {
-			// add subject info
-			credential_unsigned.add_subject_info(VC_A14_SUBJECT_DESCRIPTION, VC_A14_SUBJECT_TYPE);
-
-			// add assertion
-			credential_unsigned.add_assertion_a14(value);
-			Ok(credential_unsigned)
-		},
-		Err(e) => {
-			error!("Generate unsigned credential failed {:?}", e);
-			Err(Error::RequestVCFailed(Assertion::A14, e.into_error_detail()))
-		},
-	}
+	Ok(value)
 }
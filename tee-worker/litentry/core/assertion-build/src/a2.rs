@@ -85,10 +85,8 @@ mod tests {
 
 	#[test]
 	fn build_a2_works() {
-		GLOBAL_DATA_PROVIDER_CONFIG
-			.write()
-			.unwrap()
-			.set_discord_litentry_url("http://localhost:19527".to_string());
+		GLOBAL_DATA_PROVIDER_CONFIG.write().unwrap().discord_litentry.endpoints =
+			vec!["http://localhost:19527".to_string()];
 		let guild_id_u: u64 = 919848390156767232;
 		let guild_id_vec: Vec<u8> = format!("{}", guild_id_u).as_bytes().to_vec();
 
@@ -104,6 +102,8 @@ mod tests {
 			who: AccountId::from([0; 32]).into(),
 			assertion: Assertion::A2(guild_id.clone()),
 			identities,
+			verifier: None,
+			renewed_index: None,
 			top_hash: Default::default(),
 			req_ext_hash: Default::default(),
 		};
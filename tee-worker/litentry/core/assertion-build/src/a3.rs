@@ -113,10 +113,8 @@ mod tests {
 
 	#[test]
 	fn build_a3_works() {
-		GLOBAL_DATA_PROVIDER_CONFIG
-			.write()
-			.unwrap()
-			.set_discord_litentry_url("http://localhost:19527".to_string());
+		GLOBAL_DATA_PROVIDER_CONFIG.write().unwrap().discord_litentry.endpoints =
+			vec!["http://localhost:19527".to_string()];
 		let guild_id_u: u64 = 919848390156767232;
 		let channel_id_u: u64 = 919848392035794945;
 		let role_id_u: u64 = 1034083718425493544;
@@ -140,6 +138,8 @@ mod tests {
 			who: AccountId::from([0; 32]).into(),
 			assertion: Assertion::A3(guild_id.clone(), channel_id.clone(), role_id.clone()),
 			identities,
+			verifier: None,
+			renewed_index: None,
 			top_hash: Default::default(),
 			req_ext_hash: Default::default(),
 		};
@@ -63,30 +63,43 @@ extern crate sgx_tstd as std;
 /// - `from_date` with >= op, nor
 /// - `value` is false but the `from_date` is something other than 2017-01-01.
 ///  
-use crate::*;
+use crate::{
+	holding_time::{resolve_holding_time, HoldingQuery, HoldingTime},
+	*,
+};
 use lc_data_providers::{
-	achainable::{AchainableClient, AchainableHolder, ParamsBasicTypeWithAmountHolding},
-	vec_to_string, LIT_TOKEN_ADDRESS,
+	achainable::AchainableClient,
+	evm::EvmRpcClient,
+	get_assertion_sources,
+	native::{is_native_network, NativeClient, ParentchainAnchor},
+	vec_to_string, AssertionSource, LIT_TOKEN_ADDRESS,
 };
-use std::string::ToString;
+
+/// Whether A4 queries Achainable for some networks of `identities` when built from `source`:
+/// the native source only covers the parentchain network and the LIT token on Ethereum.
+pub fn queries_achainable(source: AssertionSource, identities: &[IdentityNetworkTuple]) -> bool {
+	source == AssertionSource::Achainable
+		|| identities
+			.iter()
+			.flat_map(|(_, networks)| networks)
+			.any(|network| !is_native_network(network) && *network != Web3Network::Ethereum)
+}
 
 const VC_A4_SUBJECT_DESCRIPTION: &str =
 	"The length of time a user continues to hold a particular token (with particular threshold of token amount)";
 const VC_A4_SUBJECT_TYPE: &str = "LIT Holding Time";
 
-pub fn build(req: &AssertionBuildRequest, min_balance: ParameterString) -> Result<Credential> {
+pub fn build(
+	req: &AssertionBuildRequest,
+	min_balance: ParameterString,
+	anchor: Option<ParentchainAnchor>,
+) -> Result<Credential> {
 	debug!("Assertion A4 build, who: {:?}", account_id_to_string(&req.who));
 
 	let q_min_balance = vec_to_string(min_balance.to_vec()).map_err(|_| {
 		Error::RequestVCFailed(Assertion::A4(min_balance.clone()), ErrorDetail::ParseError)
 	})?;
 
-	let mut client = AchainableClient::new();
-	let identities = transpose_identity(&req.identities);
-
-	let mut is_hold = false;
-	let mut optimal_hold_index = usize::MAX;
-
 	// If both Substrate and Evm networks meet the conditions, take the interval with the longest holding time.
 	// Here's an example:
 	//
@@ -102,58 +115,63 @@ pub fn build(req: &AssertionBuildRequest, min_balance: ParameterString) -> Resul
 	//    to_date: >= 2023-03-30 (now)
 	//    value: true
 	// ]
-	for (network, addresses) in identities {
-		// If found query result is the optimal solution, i.e optimal_hold_index = 0, (2017-01-01)
-		// there is no need to query other networks.
-		if optimal_hold_index == 0 {
-			break
-		}
-
-		let token =
-			if network == Web3Network::Ethereum { Some(LIT_TOKEN_ADDRESS.into()) } else { None };
-
-		let addresses: Vec<String> = addresses.into_iter().collect();
-		for (index, date) in ASSERTION_FROM_DATE.iter().enumerate() {
-			for address in &addresses {
-				let holding = ParamsBasicTypeWithAmountHolding::new(
-					&network,
-					q_min_balance.to_string(),
-					date.to_string(),
-					token.clone(),
-				);
-				let is_amount_holder = client.is_holder(address, holding).map_err(|e| {
-					error!("Assertion A4 request is_holder error: {:?}", e);
-					Error::RequestVCFailed(
-						Assertion::A4(min_balance.clone()),
-						e.into_error_detail(),
-					)
-				})?;
-
-				if is_amount_holder {
-					if index < optimal_hold_index {
-						optimal_hold_index = index;
-					}
-
-					is_hold = true;
-
-					break
-				}
-			}
-		}
-
-		// TODO:
-		// There's an issue for this: https://github.com/litentry/litentry-parachain/issues/1655
-		//
-		// There is a problem here, because TDF does not support mixed network types,
-		// It is need to request TDF 2 (substrate+evm networks) * 14 (ASSERTION_FROM_DATE) * addresses http requests.
-		// If TDF can handle mixed network type, and even supports from_date array,
-		// so that ideally, up to one http request can yield results.
-	}
-
-	// If is_hold is false, then the optimal_hold_index is always 0 (2017-01-01)
-	if !is_hold {
-		optimal_hold_index = 0;
-	}
+	//
+	// TDF does not support mixed network types, so each network is still queried separately,
+	// see https://github.com/litentry/litentry-parachain/issues/1655
+	let queries: Vec<(HoldingQuery, Vec<String>)> = transpose_identity(&req.identities)
+		.into_iter()
+		.map(|(network, addresses)| {
+			let token = if network == Web3Network::Ethereum {
+				Some(LIT_TOKEN_ADDRESS.into())
+			} else {
+				None
+			};
+			(HoldingQuery { network, token, amount: q_min_balance.clone() }, addresses)
+		})
+		.collect();
+
+	// with the native source, the substrate networks are read from storage and the LIT token
+	// on Ethereum through the evm rpc
+	let native = get_assertion_sources()
+		.map_err(|e| {
+			Error::RequestVCFailed(Assertion::A4(min_balance.clone()), e.into_error_detail())
+		})?
+		.a4 == AssertionSource::Native;
+	let (native_queries, other_queries): (Vec<_>, Vec<_>) = queries
+		.into_iter()
+		.partition(|(query, _)| native && is_native_network(&query.network));
+	let (evm_queries, achainable_queries): (Vec<_>, Vec<_>) = other_queries
+		.into_iter()
+		.partition(|(query, _)| native && query.network == Web3Network::Ethereum);
+
+	let holding_time = resolve_holding_time(
+		AssertionSource::Achainable,
+		AchainableClient::new,
+		achainable_queries,
+	)
+	.and_then(|mut holding_time| {
+		let native_holding_time = resolve_holding_time(
+			AssertionSource::Native,
+			move || NativeClient::new(anchor.clone()),
+			native_queries,
+		)?;
+		holding_time.merge_with(native_holding_time);
+		holding_time.merge_with(resolve_holding_time(
+			AssertionSource::Native,
+			EvmRpcClient::new,
+			evm_queries,
+		)?);
+		Ok(holding_time)
+	})
+	.map_err(|e| {
+		error!("Assertion A4 request is_holder error: {:?}", e);
+		Error::RequestVCFailed(Assertion::A4(min_balance.clone()), e.into_error_detail())
+	})?;
+	info!(
+		"Assertion A4 cost {} provider calls, {} cached",
+		holding_time.provider_calls, holding_time.cache_hits
+	);
+	let HoldingTime { is_hold, optimal_hold_index, .. } = holding_time;
 
 	match Credential::new(&req.who, &req.shard) {
 		Ok(mut credential_unsigned) => {
@@ -20,59 +20,58 @@ compile_error!("feature \"std\" and feature \"sgx\" cannot be enabled at the sam
 #[cfg(all(not(feature = "std"), feature = "sgx"))]
 extern crate sgx_tstd as std;
 
-use crate::*;
+use crate::{
+	holding_time::{resolve_holding_time, HoldingQuery, HoldingTime},
+	*,
+};
 use lc_data_providers::{
-	achainable::{AchainableClient, AchainableHolder, ParamsBasicTypeWithAmountHolding},
-	vec_to_string,
+	achainable::AchainableClient,
+	get_assertion_sources,
+	native::{NativeClient, ParentchainAnchor},
+	vec_to_string, AssertionSource,
 };
-use std::string::ToString;
 
 const VC_A7_SUBJECT_DESCRIPTION: &str =
 	"The length of time a user continues to hold a particular token (with particular threshold of token amount)";
 const VC_A7_SUBJECT_TYPE: &str = "DOT Holding Time";
 
-pub fn build(req: &AssertionBuildRequest, min_balance: ParameterString) -> Result<Credential> {
+pub fn build(
+	req: &AssertionBuildRequest,
+	min_balance: ParameterString,
+	anchor: Option<ParentchainAnchor>,
+) -> Result<Credential> {
 	debug!("Assertion A7 build, who: {:?}", account_id_to_string(&req.who),);
 
 	let q_min_balance = vec_to_string(min_balance.to_vec()).map_err(|_| {
 		Error::RequestVCFailed(Assertion::A7(min_balance.clone()), ErrorDetail::ParseError)
 	})?;
 
-	let mut client = AchainableClient::new();
-	let identities: Vec<(Web3Network, Vec<String>)> = transpose_identity(&req.identities);
-	let addresses = identities
-		.into_iter()
-		.flat_map(|(_, addresses)| addresses)
-		.collect::<Vec<String>>();
-
-	let mut is_hold = false;
-	let mut optimal_hold_index = 0_usize;
-	for (index, date) in ASSERTION_FROM_DATE.iter().enumerate() {
-		if is_hold {
-			break
-		}
-
-		for address in &addresses {
-			let holding = ParamsBasicTypeWithAmountHolding::new(
-				&Web3Network::Polkadot,
-				q_min_balance.to_string(),
-				date.to_string(),
-				None,
-			);
-
-			let is_polkadot_holder = client.is_holder(address, holding).map_err(|e| {
-				error!("Assertion A7 request is_holder error: {:?}", e);
-				Error::RequestVCFailed(Assertion::A7(min_balance.clone()), e.into_error_detail())
-			})?;
-
-			if is_polkadot_holder {
-				optimal_hold_index = index;
-				is_hold = true;
-
-				break
-			}
-		}
+	let queries = vec![(
+		HoldingQuery { network: Web3Network::Polkadot, token: None, amount: q_min_balance.clone() },
+		transpose_identity(&req.identities)
+			.into_iter()
+			.flat_map(|(_, addresses)| addresses)
+			.collect(),
+	)];
+	let source = get_assertion_sources()
+		.map_err(|e| {
+			Error::RequestVCFailed(Assertion::A7(min_balance.clone()), e.into_error_detail())
+		})?
+		.a7;
+	let holding_time = match source {
+		AssertionSource::Achainable => resolve_holding_time(source, AchainableClient::new, queries),
+		AssertionSource::Native =>
+			resolve_holding_time(source, move || NativeClient::new(anchor.clone()), queries),
 	}
+	.map_err(|e| {
+		error!("Assertion A7 request is_holder error: {:?}", e);
+		Error::RequestVCFailed(Assertion::A7(min_balance.clone()), e.into_error_detail())
+	})?;
+	info!(
+		"Assertion A7 cost {} provider calls, {} cached",
+		holding_time.provider_calls, holding_time.cache_hits
+	);
+	let HoldingTime { is_hold, optimal_hold_index, .. } = holding_time;
 
 	match Credential::new(&req.who, &req.shard) {
 		Ok(mut credential_unsigned) => {
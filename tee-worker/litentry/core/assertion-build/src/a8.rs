@@ -26,10 +26,10 @@ use lc_data_providers::achainable::{AchainableAccountTotalTransactions, Achainab
 const VC_A8_SUBJECT_DESCRIPTION: &str = "Gets the range of number of transactions a user has made for a specific token on all supported networks (invalid transactions are also counted)";
 const VC_A8_SUBJECT_TYPE: &str = "EVM/Substrate Transaction Count";
 
-pub fn build(req: &AssertionBuildRequest) -> Result<Credential> {
+pub fn build(req: &AssertionBuildRequest, registry: &Web3NetworkRegistry) -> Result<Credential> {
 	debug!("Assertion A8 build, who: {:?}", account_id_to_string(&req.who),);
 
-	let mut client = AchainableClient::new();
+	let mut client = AchainableClient::with_registry(registry.clone());
 	let mut total_txs: u64 = 0;
 
 	let identities: Vec<(Web3Network, Vec<String>)> = transpose_identity(&req.identities);
@@ -39,9 +39,7 @@ pub fn build(req: &AssertionBuildRequest) -> Result<Credential> {
 
 		let txs = client.total_transactions(&network, &addresses).map_err(|e| {
 			error!("Assertion A8 query total_transactions error: {:?}", e);
-			let bounded_web3networks =
-				req.assertion.get_supported_web3networks().try_into().unwrap();
-			Error::RequestVCFailed(Assertion::A8(bounded_web3networks), e.into_error_detail())
+			Error::RequestVCFailed(req.assertion.clone(), e.into_error_detail())
 		})?;
 
 		total_txs += txs;
@@ -49,7 +47,7 @@ pub fn build(req: &AssertionBuildRequest) -> Result<Credential> {
 	debug!("Assertion A8 total_transactions: {}", total_txs);
 
 	let networks = if networks_set.is_empty() {
-		req.assertion.get_supported_web3networks()
+		req.assertion.get_supported_web3networks(registry)
 	} else {
 		networks_set.into_iter().collect::<Vec<Web3Network>>()
 	};
@@ -64,11 +62,7 @@ pub fn build(req: &AssertionBuildRequest) -> Result<Credential> {
 		},
 		Err(e) => {
 			error!("Generate unsigned credential failed {:?}", e);
-			// It should never fail because `req.assertion.get_supported_web3networks()`
-			// returns the vector which is converted from a BoundedVec
-			let bounded_web3networks =
-				req.assertion.get_supported_web3networks().try_into().unwrap();
-			Err(Error::RequestVCFailed(Assertion::A8(bounded_web3networks), e.into_error_detail()))
+			Err(Error::RequestVCFailed(req.assertion.clone(), e.into_error_detail()))
 		},
 	}
 }
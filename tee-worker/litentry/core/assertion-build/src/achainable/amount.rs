@@ -94,7 +94,11 @@ const BALANCE_OVER_AMOUNT: &str = "Balance over {amount}";
 ///        ]
 /// }
 ///
-pub fn build_amount(req: &AssertionBuildRequest, param: AchainableAmount) -> Result<Credential> {
+pub fn build_amount(
+	req: &AssertionBuildRequest,
+	param: AchainableAmount,
+	registry: &Web3NetworkRegistry,
+) -> Result<Credential> {
 	debug!("Assertion Achainable build_amount, who: {:?}", account_id_to_string(&req.who));
 	let identities = transpose_identity(&req.identities);
 	let addresses = identities
@@ -103,7 +107,7 @@ pub fn build_amount(req: &AssertionBuildRequest, param: AchainableAmount) -> Res
 		.collect::<Vec<String>>();
 
 	let achainable_param = AchainableParams::Amount(param.clone());
-	let flag = request_achainable(addresses, achainable_param.clone())?;
+	let flag = request_achainable(addresses, achainable_param.clone(), registry)?;
 	match Credential::new(&req.who, &req.shard) {
 		Ok(mut credential_unsigned) => {
 			let (desc, subtype, content) =
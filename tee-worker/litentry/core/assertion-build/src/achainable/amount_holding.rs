@@ -29,6 +29,7 @@ const VC_SUBJECT_TYPE: &str = "Amount holding";
 pub fn build_amount_holding(
 	req: &AssertionBuildRequest,
 	param: AchainableAmountHolding,
+	registry: &Web3NetworkRegistry,
 ) -> Result<Credential> {
 	debug!("Assertion Achainable build_amount_holding, who: {:?}", account_id_to_string(&req.who));
 
@@ -39,7 +40,7 @@ pub fn build_amount_holding(
 		.collect::<Vec<String>>();
 
 	let achainable_param = AchainableParams::AmountHolding(param.clone());
-	let flag = request_achainable(addresses, achainable_param.clone())?;
+	let flag = request_achainable(addresses, achainable_param.clone(), registry)?;
 	match Credential::new(&req.who, &req.shard) {
 		Ok(mut credential_unsigned) => {
 			credential_unsigned.add_subject_info(VC_SUBJECT_DESCRIPTION, VC_SUBJECT_TYPE);
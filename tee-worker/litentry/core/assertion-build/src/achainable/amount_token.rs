@@ -25,6 +25,7 @@ use crate::{achainable::request_achainable, *};
 pub fn build_amount_token(
 	req: &AssertionBuildRequest,
 	param: AchainableAmountToken,
+	registry: &Web3NetworkRegistry,
 ) -> Result<Credential> {
 	debug!("Assertion Achainable build_amount_token, who: {:?}", account_id_to_string(&req.who));
 
@@ -35,7 +36,7 @@ pub fn build_amount_token(
 		.collect::<Vec<String>>();
 
 	let achainable_param = AchainableParams::AmountToken(param);
-	let _flag = request_achainable(addresses, achainable_param.clone())?;
+	let _flag = request_achainable(addresses, achainable_param.clone(), registry)?;
 	match Credential::new(&req.who, &req.shard) {
 		Ok(mut _credential_unsigned) => Ok(_credential_unsigned),
 		Err(e) => {
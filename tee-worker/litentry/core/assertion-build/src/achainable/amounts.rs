@@ -22,7 +22,11 @@ extern crate sgx_tstd as std;
 
 use crate::{achainable::request_achainable, *};
 
-pub fn build_amounts(req: &AssertionBuildRequest, param: AchainableAmounts) -> Result<Credential> {
+pub fn build_amounts(
+	req: &AssertionBuildRequest,
+	param: AchainableAmounts,
+	registry: &Web3NetworkRegistry,
+) -> Result<Credential> {
 	debug!("Assertion Achainable build_amounts, who: {:?}", account_id_to_string(&req.who));
 
 	let identities = transpose_identity(&req.identities);
@@ -32,7 +36,7 @@ pub fn build_amounts(req: &AssertionBuildRequest, param: AchainableAmounts) -> R
 		.collect::<Vec<String>>();
 
 	let achainable_param = AchainableParams::Amounts(param);
-	let _flag = request_achainable(addresses, achainable_param.clone())?;
+	let _flag = request_achainable(addresses, achainable_param.clone(), registry)?;
 	match Credential::new(&req.who, &req.shard) {
 		Ok(mut _credential_unsigned) => Ok(_credential_unsigned),
 		Err(e) => {
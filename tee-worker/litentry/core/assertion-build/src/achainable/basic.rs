@@ -43,7 +43,11 @@ use std::string::ToString;
 ///         }
 /// }
 ///
-pub fn build_basic(req: &AssertionBuildRequest, param: AchainableBasic) -> Result<Credential> {
+pub fn build_basic(
+	req: &AssertionBuildRequest,
+	param: AchainableBasic,
+	registry: &Web3NetworkRegistry,
+) -> Result<Credential> {
 	debug!("Assertion Achainable build_basic, who: {:?}", account_id_to_string(&req.who));
 
 	let identities = transpose_identity(&req.identities);
@@ -55,7 +59,8 @@ pub fn build_basic(req: &AssertionBuildRequest, param: AchainableBasic) -> Resul
 	let achainable_param = AchainableParams::Basic(param.clone());
 	check_uniswap_v23_user_inputs(&achainable_param, &param)?;
 
-	let (v2_user, v3_user) = request_uniswap_v2_or_v3_user(addresses, achainable_param.clone())?;
+	let (v2_user, v3_user) =
+		request_uniswap_v2_or_v3_user(addresses, achainable_param.clone(), registry)?;
 	match Credential::new(&req.who, &req.shard) {
 		Ok(mut credential_unsigned) => {
 			let (desc, subtype) = get_uniswap_v23_info();
@@ -25,6 +25,7 @@ use crate::{achainable::request_achainable, *};
 pub fn build_between_percents(
 	req: &AssertionBuildRequest,
 	param: AchainableBetweenPercents,
+	registry: &Web3NetworkRegistry,
 ) -> Result<Credential> {
 	debug!(
 		"Assertion Achainable build_between_percents, who: {:?}",
@@ -38,7 +39,7 @@ pub fn build_between_percents(
 		.collect::<Vec<String>>();
 
 	let achainable_param = AchainableParams::BetweenPercents(param.clone());
-	let _flag = request_achainable(addresses, achainable_param)?;
+	let _flag = request_achainable(addresses, achainable_param, registry)?;
 	match Credential::new(&req.who, &req.shard) {
 		Ok(mut _credential_unsigned) => Ok(_credential_unsigned),
 		Err(e) => {
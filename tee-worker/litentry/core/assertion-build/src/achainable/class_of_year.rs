@@ -59,6 +59,7 @@ const VC_SUBJECT_TYPE: &str = "Account Class Of Year";
 pub fn build_class_of_year(
 	req: &AssertionBuildRequest,
 	param: AchainableClassOfYear,
+	registry: &Web3NetworkRegistry,
 ) -> Result<Credential> {
 	debug!("Assertion Achainable build_class_of_year, who: {:?}", account_id_to_string(&req.who));
 	let identities = transpose_identity(&req.identities);
@@ -68,7 +69,8 @@ pub fn build_class_of_year(
 		.collect::<Vec<String>>();
 
 	let achainable_param = AchainableParams::ClassOfYear(param);
-	let (ret, created_date) = request_achainable_classofyear(addresses, achainable_param.clone())?;
+	let (ret, created_date) =
+		request_achainable_classofyear(addresses, achainable_param.clone(), registry)?;
 	match Credential::new(&req.who, &req.shard) {
 		Ok(mut credential_unsigned) => {
 			credential_unsigned.add_subject_info(VC_SUBJECT_DESCRIPTION, VC_SUBJECT_TYPE);
@@ -26,6 +26,7 @@ use lc_credentials::Credential;
 pub fn build_date_interval(
 	req: &AssertionBuildRequest,
 	param: AchainableDateInterval,
+	registry: &Web3NetworkRegistry,
 ) -> Result<Credential> {
 	debug!("Assertion Achainable build_date_interval, who: {:?}", account_id_to_string(&req.who));
 
@@ -36,7 +37,7 @@ pub fn build_date_interval(
 		.collect::<Vec<String>>();
 
 	let achainable_param = AchainableParams::DateInterval(param.clone());
-	let _flag = request_achainable(addresses, achainable_param)?;
+	let _flag = request_achainable(addresses, achainable_param, registry)?;
 	match Credential::new(&req.who, &req.shard) {
 		Ok(mut _credential_unsigned) => Ok(_credential_unsigned),
 		Err(e) => {
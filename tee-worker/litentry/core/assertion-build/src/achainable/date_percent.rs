@@ -25,6 +25,7 @@ use crate::{achainable::request_achainable, *};
 pub fn build_date_percent(
 	req: &AssertionBuildRequest,
 	param: AchainableDatePercent,
+	registry: &Web3NetworkRegistry,
 ) -> Result<Credential> {
 	debug!("Assertion Achainable build_date_percent, who: {:?}", account_id_to_string(&req.who));
 
@@ -35,7 +36,7 @@ pub fn build_date_percent(
 		.collect::<Vec<String>>();
 
 	let achainable_param = AchainableParams::DatePercent(param.clone());
-	let _flag = request_achainable(addresses, achainable_param)?;
+	let _flag = request_achainable(addresses, achainable_param, registry)?;
 	match Credential::new(&req.who, &req.shard) {
 		Ok(mut _credential_unsigned) => Ok(_credential_unsigned),
 		Err(e) => {
@@ -43,25 +43,33 @@ pub mod date_interval;
 pub mod date_percent;
 pub mod token;
 
-pub fn build(req: &AssertionBuildRequest, param: AchainableParams) -> Result<Credential> {
+pub fn build(
+	req: &AssertionBuildRequest,
+	param: AchainableParams,
+	registry: &Web3NetworkRegistry,
+) -> Result<Credential> {
 	match param {
-		AchainableParams::AmountHolding(param) => build_amount_holding(req, param),
-		AchainableParams::AmountToken(param) => build_amount_token(req, param),
-		AchainableParams::Amount(param) => build_amount(req, param),
-		AchainableParams::Amounts(param) => build_amounts(req, param),
-		AchainableParams::Basic(param) => build_basic(req, param),
-		AchainableParams::BetweenPercents(param) => build_between_percents(req, param),
-		AchainableParams::ClassOfYear(param) => build_class_of_year(req, param),
-		AchainableParams::DateInterval(param) => build_date_interval(req, param),
-		AchainableParams::DatePercent(param) => build_date_percent(req, param),
-		AchainableParams::Date(param) => build_date(req, param),
-		AchainableParams::Token(param) => build_token(req, param),
+		AchainableParams::AmountHolding(param) => build_amount_holding(req, param, registry),
+		AchainableParams::AmountToken(param) => build_amount_token(req, param, registry),
+		AchainableParams::Amount(param) => build_amount(req, param, registry),
+		AchainableParams::Amounts(param) => build_amounts(req, param, registry),
+		AchainableParams::Basic(param) => build_basic(req, param, registry),
+		AchainableParams::BetweenPercents(param) => build_between_percents(req, param, registry),
+		AchainableParams::ClassOfYear(param) => build_class_of_year(req, param, registry),
+		AchainableParams::DateInterval(param) => build_date_interval(req, param, registry),
+		AchainableParams::DatePercent(param) => build_date_percent(req, param, registry),
+		AchainableParams::Date(param) => build_date(req, param, registry),
+		AchainableParams::Token(param) => build_token(req, param, registry),
 	}
 }
 
-pub fn request_achainable(addresses: Vec<String>, param: AchainableParams) -> Result<bool> {
-	let request_param = Params::try_from(param.clone())?;
-	let mut client: AchainableClient = AchainableClient::new();
+pub fn request_achainable(
+	addresses: Vec<String>,
+	param: AchainableParams,
+	registry: &Web3NetworkRegistry,
+) -> Result<bool> {
+	let request_param = Params::new(param.clone(), registry)?;
+	let mut client = AchainableClient::with_registry(registry.clone());
 
 	for address in &addresses {
 		let ret = client.query_system_label(address, request_param.clone()).map_err(|e| {
@@ -81,9 +89,10 @@ pub fn request_achainable(addresses: Vec<String>, param: AchainableParams) -> Re
 pub fn request_uniswap_v2_or_v3_user(
 	addresses: Vec<String>,
 	param: AchainableParams,
+	registry: &Web3NetworkRegistry,
 ) -> Result<(bool, bool)> {
-	let _request_param = Params::try_from(param.clone())?;
-	let mut client: AchainableClient = AchainableClient::new();
+	let _request_param = Params::new(param.clone(), registry)?;
+	let mut client = AchainableClient::with_registry(registry.clone());
 
 	let mut v2_user = false;
 	let mut v3_user = false;
@@ -104,9 +113,10 @@ const INVALID_CLASS_OF_YEAR: &str = "Invalid";
 pub fn request_achainable_classofyear(
 	addresses: Vec<String>,
 	param: AchainableParams,
+	registry: &Web3NetworkRegistry,
 ) -> Result<(bool, String)> {
-	let request_param = Params::try_from(param.clone())?;
-	let mut client: AchainableClient = AchainableClient::new();
+	let request_param = Params::new(param.clone(), registry)?;
+	let mut client = AchainableClient::with_registry(registry.clone());
 
 	let mut longest_created_year = INVALID_CLASS_OF_YEAR.into();
 	for address in &addresses {
@@ -22,7 +22,11 @@ extern crate sgx_tstd as std;
 
 use crate::{achainable::request_achainable, *};
 
-pub fn build_token(req: &AssertionBuildRequest, param: AchainableToken) -> Result<Credential> {
+pub fn build_token(
+	req: &AssertionBuildRequest,
+	param: AchainableToken,
+	registry: &Web3NetworkRegistry,
+) -> Result<Credential> {
 	debug!("Assertion Achainable build_token, who: {:?}", account_id_to_string(&req.who));
 
 	let identities = transpose_identity(&req.identities);
@@ -32,7 +36,7 @@ pub fn build_token(req: &AssertionBuildRequest, param: AchainableToken) -> Resul
 		.collect::<Vec<String>>();
 
 	let achainable_param = AchainableParams::Token(param.clone());
-	let _flag = request_achainable(addresses, achainable_param)?;
+	let _flag = request_achainable(addresses, achainable_param, registry)?;
 	match Credential::new(&req.who, &req.shard) {
 		Ok(mut _credential_unsigned) => Ok(_credential_unsigned),
 		Err(e) => {
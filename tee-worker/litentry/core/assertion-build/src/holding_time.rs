@@ -0,0 +1,359 @@
+// Copyright 2020-2023 Litentry Technologies GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Resolves how long a set of addresses has been holding an amount of a token, shared by
+//! A4/A7/A10/A11.
+//!
+//! The data provider only answers "did `address` hold `amount` since `date`", so finding the
+//! holding time means walking `ASSERTION_FROM_DATE`. Holding since a date implies holding
+//! since every later date, so a binary search over the ladder needs at most 4 instead of 14
+//! requests per address, and the following addresses only need to be checked for earlier
+//! dates. The responses are cached per source for a short time as the same holders tend to
+//! request several VCs in a row.
+
+use crate::*;
+use itp_time_utils::now_as_secs;
+use lazy_static::lazy_static;
+use lc_data_providers::{
+	achainable::{AchainableHolder, ParamsBasicTypeWithAmountHolding},
+	AssertionSource, Error as DataProviderError,
+};
+use std::{collections::BTreeMap, string::ToString};
+
+#[cfg(feature = "std")]
+use std::sync::Mutex;
+#[cfg(feature = "sgx")]
+use std::sync::SgxMutex as Mutex;
+
+/// How long a provider response is reused.
+pub const HOLDING_CACHE_TTL_SECS: u64 = 600;
+/// Upper bound of the cached responses, expired entries are evicted once it's reached.
+pub const HOLDING_CACHE_MAX_ENTRIES: usize = 10_000;
+
+// (source, address, chain, token, amount, date)
+type CacheKey = (AssertionSource, String, String, Option<String>, String, String);
+
+lazy_static! {
+	// key -> (is holder, time of the response in secs)
+	static ref HOLDING_CACHE: Mutex<BTreeMap<CacheKey, (bool, u64)>> = Mutex::new(BTreeMap::new());
+}
+
+/// The token and amount the addresses of `network` are checked for.
+#[derive(Clone, Debug)]
+pub struct HoldingQuery {
+	pub network: Web3Network,
+	pub token: Option<String>,
+	pub amount: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HoldingTime {
+	pub is_hold: bool,
+	/// Index of the earliest date in `ASSERTION_FROM_DATE` the amount has been held since,
+	/// always 0 if `is_hold` is false.
+	pub optimal_hold_index: usize,
+	/// Requests sent to the data provider.
+	pub provider_calls: u32,
+	/// Responses taken from the cache.
+	pub cache_hits: u32,
+}
+
+impl HoldingTime {
+	/// Combines the holding times resolved by different providers.
+	pub fn merge_with(&mut self, other: HoldingTime) {
+		let hold_index = if other.is_hold { Some(other.optimal_hold_index) } else { None };
+		self.merge(hold_index, other.provider_calls, other.cache_hits);
+	}
+
+	fn merge(&mut self, hold_index: Option<usize>, provider_calls: u32, cache_hits: u32) {
+		if let Some(index) = hold_index {
+			if !self.is_hold || index < self.optimal_hold_index {
+				self.optimal_hold_index = index;
+			}
+			self.is_hold = true;
+		}
+		self.provider_calls += provider_calls;
+		self.cache_hits += cache_hits;
+	}
+}
+
+/// Resolves the longest holding time of all `(query, addresses)` pairs.
+///
+/// The addresses are resolved one after another with a single client created by
+/// `new_client`: this runs within a task of the stf task worker pool, which already bounds
+/// the concurrency (and thus the TCS) per data provider. The responses are cached as the
+/// responses of `source`.
+pub fn resolve_holding_time<C, F>(
+	source: AssertionSource,
+	new_client: F,
+	queries: Vec<(HoldingQuery, Vec<String>)>,
+) -> core::result::Result<HoldingTime, DataProviderError>
+where
+	C: AchainableHolder,
+	F: FnOnce() -> C,
+{
+	let mut holding_time = HoldingTime::default();
+	if queries.iter().all(|(_, addresses)| addresses.is_empty()) {
+		return Ok(holding_time)
+	}
+
+	let mut client = new_client();
+	for (query, addresses) in queries.iter() {
+		for address in addresses.iter() {
+			// only an earlier date than the current one can improve the holding time
+			let end = if holding_time.is_hold {
+				holding_time.optimal_hold_index
+			} else {
+				ASSERTION_FROM_DATE.len()
+			};
+			let (hold_index, provider_calls, cache_hits) =
+				resolve_address(source, &mut client, query, address, end)?;
+			holding_time.merge(hold_index, provider_calls, cache_hits);
+			// the earliest date can't be improved by the remaining addresses
+			if holding_time.is_hold && holding_time.optimal_hold_index == 0 {
+				return Ok(holding_time)
+			}
+		}
+	}
+
+	Ok(holding_time)
+}
+
+type ResolveResult = core::result::Result<(Option<usize>, u32, u32), DataProviderError>;
+
+/// Binary search for the earliest date of `ASSERTION_FROM_DATE[..end]` that `address` has
+/// been holding since, returns `(hold index, provider calls, cache hits)`.
+fn resolve_address<C: AchainableHolder>(
+	source: AssertionSource,
+	client: &mut C,
+	query: &HoldingQuery,
+	address: &str,
+	end: usize,
+) -> ResolveResult {
+	let (mut provider_calls, mut cache_hits) = (0, 0);
+	let (mut low, mut high) = (0, end);
+	while low < high {
+		let mid = low + (high - low) / 2;
+		// holdings are only asked for built-in networks, the holder assertions don't support
+		// registered ones, see `Assertion::get_supported_web3networks`
+		let holding = ParamsBasicTypeWithAmountHolding::new(
+			&query.network,
+			query.amount.clone(),
+			ASSERTION_FROM_DATE[mid].to_string(),
+			query.token.clone(),
+			&Web3NetworkRegistry::new(),
+		)?;
+		let is_holder = match get_cached(source, address, &holding) {
+			Some(is_holder) => {
+				cache_hits += 1;
+				is_holder
+			},
+			None => {
+				provider_calls += 1;
+				let is_holder = client.is_holder(address, holding.clone())?;
+				put_cached(source, address, &holding, is_holder);
+				is_holder
+			},
+		};
+
+		if is_holder {
+			high = mid;
+		} else {
+			low = mid + 1;
+		}
+	}
+
+	let hold_index = if low < end { Some(low) } else { None };
+	Ok((hold_index, provider_calls, cache_hits))
+}
+
+fn cache_key(
+	source: AssertionSource,
+	address: &str,
+	holding: &ParamsBasicTypeWithAmountHolding,
+) -> CacheKey {
+	(
+		source,
+		address.to_string(),
+		holding.chain.clone(),
+		holding.token.clone(),
+		holding.amount.clone(),
+		holding.date.clone(),
+	)
+}
+
+fn get_cached(
+	source: AssertionSource,
+	address: &str,
+	holding: &ParamsBasicTypeWithAmountHolding,
+) -> Option<bool> {
+	let cache = HOLDING_CACHE.lock().ok()?;
+	cache
+		.get(&cache_key(source, address, holding))
+		.filter(|(_, at)| now_as_secs().saturating_sub(*at) < HOLDING_CACHE_TTL_SECS)
+		.map(|(is_holder, _)| *is_holder)
+}
+
+fn put_cached(
+	source: AssertionSource,
+	address: &str,
+	holding: &ParamsBasicTypeWithAmountHolding,
+	is_holder: bool,
+) {
+	// the cache is an optimisation only, a poisoned lock just disables it
+	if let Ok(mut cache) = HOLDING_CACHE.lock() {
+		let now = now_as_secs();
+		if cache.len() >= HOLDING_CACHE_MAX_ENTRIES {
+			cache.retain(|_, (_, at)| now.saturating_sub(*at) < HOLDING_CACHE_TTL_SECS);
+		}
+		if cache.len() >= HOLDING_CACHE_MAX_ENTRIES {
+			cache.clear();
+		}
+		cache.insert(cache_key(source, address, holding), (is_holder, now));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// holds since `ASSERTION_FROM_DATE[since]` if set
+	struct MockHolder {
+		since: BTreeMap<String, usize>,
+	}
+
+	impl AchainableHolder for MockHolder {
+		fn is_holder(
+			&mut self,
+			address: &str,
+			holding: ParamsBasicTypeWithAmountHolding,
+		) -> core::result::Result<bool, DataProviderError> {
+			let date_index =
+				ASSERTION_FROM_DATE.iter().position(|d| *d == holding.date.as_str()).unwrap();
+			Ok(self.since.get(address).map(|since| *since <= date_index).unwrap_or_default())
+		}
+	}
+
+	fn mock_holder(since: Vec<(&'static str, usize)>) -> impl Fn() -> MockHolder {
+		move || MockHolder { since: since.iter().map(|(a, i)| (a.to_string(), *i)).collect() }
+	}
+
+	fn query(amount: &str) -> HoldingQuery {
+		HoldingQuery { network: Web3Network::Polkadot, token: None, amount: amount.into() }
+	}
+
+	// the cache is global, so every test uses its own amount
+	#[test]
+	fn resolve_holding_time_finds_earliest_date() {
+		for since in 0..ASSERTION_FROM_DATE.len() {
+			let amount = format!("1.{}", since);
+			let result = resolve_holding_time(
+				AssertionSource::Achainable,
+				mock_holder(vec![("0x01", since)]),
+				vec![(query(&amount), vec!["0x01".into()])],
+			)
+			.unwrap();
+			assert!(result.is_hold);
+			assert_eq!(result.optimal_hold_index, since);
+			assert!(result.provider_calls <= 4);
+		}
+	}
+
+	#[test]
+	fn resolve_holding_time_takes_longest_of_all_addresses() {
+		let result = resolve_holding_time(
+			AssertionSource::Achainable,
+			mock_holder(vec![("0x01", 9), ("0x02", 3), ("0x03", 12)]),
+			vec![(query("2"), vec!["0x01".into(), "0x02".into(), "0x03".into(), "0x04".into()])],
+		)
+		.unwrap();
+		assert_eq!((result.is_hold, result.optimal_hold_index), (true, 3),);
+	}
+
+	#[test]
+	fn resolve_holding_time_only_checks_earlier_dates() {
+		let result = resolve_holding_time(
+			AssertionSource::Achainable,
+			mock_holder(vec![("0x01", 3), ("0x02", 9)]),
+			vec![(query("5"), vec!["0x01".into(), "0x02".into()])],
+		)
+		.unwrap();
+		assert_eq!((result.is_hold, result.optimal_hold_index), (true, 3));
+		// 0x01: 7, 3, 1, 2 - 0x02: 1, 2
+		assert_eq!(result.provider_calls, 6);
+	}
+
+	#[test]
+	fn resolve_holding_time_without_addresses_creates_no_client() {
+		let result = resolve_holding_time(
+			AssertionSource::Achainable,
+			|| -> MockHolder { panic!("no client expected") },
+			vec![(query("6"), vec![])],
+		)
+		.unwrap();
+		assert_eq!(result, HoldingTime::default());
+	}
+
+	#[test]
+	fn resolve_holding_time_without_holder_works() {
+		let result = resolve_holding_time(
+			AssertionSource::Achainable,
+			mock_holder(vec![]),
+			vec![(query("3"), vec!["0x01".into(), "0x02".into()])],
+		)
+		.unwrap();
+		assert!(!result.is_hold);
+		assert_eq!(result.optimal_hold_index, 0);
+		// all dates are checked false: 7, 11, 13
+		assert_eq!(result.provider_calls, 6);
+	}
+
+	#[test]
+	fn resolve_holding_time_uses_cache() {
+		let queries = vec![(query("4"), vec!["0x01".into()])];
+		let resolve = |source| {
+			resolve_holding_time(source, mock_holder(vec![("0x01", 5)]), queries.clone()).unwrap()
+		};
+		let first = resolve(AssertionSource::Achainable);
+		let second = resolve(AssertionSource::Achainable);
+		assert_eq!(first.optimal_hold_index, second.optimal_hold_index);
+		assert_eq!(second.provider_calls, 0);
+		assert_eq!(second.cache_hits, first.provider_calls);
+
+		// the responses of another source aren't reused
+		let native = resolve(AssertionSource::Native);
+		assert_eq!(native.cache_hits, 0);
+		assert_eq!(native.provider_calls, first.provider_calls);
+	}
+
+	#[test]
+	fn merge_with_keeps_longest_holding_time() {
+		let mut holding_time =
+			HoldingTime { is_hold: true, optimal_hold_index: 5, provider_calls: 3, cache_hits: 1 };
+		holding_time.merge_with(HoldingTime { provider_calls: 2, ..Default::default() });
+		assert_eq!((holding_time.is_hold, holding_time.optimal_hold_index), (true, 5));
+		holding_time.merge_with(HoldingTime {
+			is_hold: true,
+			optimal_hold_index: 2,
+			provider_calls: 4,
+			cache_hits: 0,
+		});
+		assert_eq!(
+			holding_time,
+			HoldingTime { is_hold: true, optimal_hold_index: 2, provider_calls: 9, cache_hits: 1 }
+		);
+	}
+}
@@ -49,17 +49,18 @@ pub mod a6;
 pub mod a7;
 pub mod a8;
 pub mod achainable;
+pub mod holding_time;
 
 use itp_types::AccountId;
 use itp_utils::stringify::account_id_to_string;
 use lc_credentials::Credential;
 use lc_stf_task_sender::AssertionBuildRequest;
 use litentry_primitives::{
-	AchainableAmount, AchainableAmountHolding, AchainableAmountToken, AchainableAmounts,
-	AchainableBasic, AchainableBetweenPercents, AchainableDate, AchainableDateInterval,
-	AchainableDatePercent, AchainableParams, AchainableToken, Assertion, ErrorDetail, ErrorString,
-	Identity, IdentityNetworkTuple, IntoErrorDetail, ParameterString, VCMPError as Error,
-	Web3Network, ASSERTION_FROM_DATE,
+	format_solana_address, AchainableAmount, AchainableAmountHolding, AchainableAmountToken,
+	AchainableAmounts, AchainableBasic, AchainableBetweenPercents, AchainableDate,
+	AchainableDateInterval, AchainableDatePercent, AchainableParams, AchainableToken, Assertion,
+	ErrorDetail, ErrorString, Identity, IdentityNetworkTuple, IntoErrorDetail, ParameterString,
+	VCMPError as Error, Web3Network, Web3NetworkRegistry, ASSERTION_FROM_DATE,
 };
 use log::*;
 use std::{collections::HashSet, format, string::String, sync::Arc, vec, vec::Vec};
@@ -90,6 +91,14 @@ pub fn transpose_identity(
 					addresses.push((address, n));
 					networks_set.insert(n);
 				},
+				Identity::Bitcoin(address) => {
+					addresses.push((address.format(), n));
+					networks_set.insert(n);
+				},
+				Identity::Solana(address) => {
+					addresses.push((format_solana_address(address), n));
+					networks_set.insert(n);
+				},
 				_ => {},
 			};
 		});
@@ -112,6 +121,7 @@ pub fn transpose_identity(
 mod tests {
 	use super::*;
 	use itp_utils::ToHexPrefixed;
+	use litentry_primitives::BitcoinAddress;
 
 	#[test]
 	fn transpose_identity_works() {
@@ -120,20 +130,27 @@ mod tests {
 		let id2 = [2u8; 32].into();
 		let id3 = [3u8; 32].into();
 		let id4 = [4u8; 20].into();
+		let address5 = BitcoinAddress::P2wpkh([5u8; 20].into());
+		let id5 = Identity::Bitcoin(address5);
+		let id6 = Identity::Solana([6u8; 32].into());
 
 		let network1: Vec<Web3Network> = vec![];
 		let network2 = vec![Web3Network::Polkadot, Web3Network::Litentry];
 		let network3 = vec![Web3Network::Litentry, Web3Network::Khala, Web3Network::Kusama];
 		let network4 = vec![Web3Network::Bsc];
+		let network5 = vec![Web3Network::Bitcoin];
+		let network6 = vec![Web3Network::Solana];
 
 		identities.push((id1, network1));
 		identities.push((id2, network2));
 		identities.push((id3, network3));
 		identities.push((id4, network4));
+		identities.push((id5, network5));
+		identities.push((id6, network6));
 
 		let mut result = transpose_identity(&identities);
 		result.sort();
-		assert_eq!(result.len(), 5);
+		assert_eq!(result.len(), 7);
 		assert_eq!(result.get(0).unwrap(), &(Web3Network::Polkadot, vec![[2u8; 32].to_hex()]));
 		assert_eq!(result.get(1).unwrap(), &(Web3Network::Kusama, vec![[3u8; 32].to_hex()]));
 		assert_eq!(
@@ -142,5 +159,10 @@ mod tests {
 		);
 		assert_eq!(result.get(3).unwrap(), &(Web3Network::Khala, vec![[3u8; 32].to_hex()]));
 		assert_eq!(result.get(4).unwrap(), &(Web3Network::Bsc, vec![[4u8; 20].to_hex()]));
+		assert_eq!(result.get(5).unwrap(), &(Web3Network::Bitcoin, vec![address5.format()]));
+		assert_eq!(
+			result.get(6).unwrap(),
+			&(Web3Network::Solana, vec![format_solana_address(&[6u8; 32].into())])
+		);
 	}
 }
@@ -18,16 +18,26 @@
 // when requesting VCs.
 
 use codec::{Decode, Encode};
+use core::cmp::Ordering;
+use itp_time_utils::from_iso8601;
 use scale_info::TypeInfo;
 use serde::{Deserialize, Serialize};
 use std::{
 	boxed::Box,
+	collections::BTreeMap,
 	fmt::Debug,
 	string::{String, ToString},
 	vec,
 	vec::Vec,
 };
 
+/// Prefix of an operand that refers to a variable instead of a literal, e.g. `$total_txs`.
+pub const VARIABLE_PREFIX: char = '$';
+
+/// Maximum number of fractional digits a decimal value may carry.
+/// It is large enough for 18-decimals EVM tokens and keeps `i128` arithmetic in range.
+pub const MAX_DECIMAL_SCALE: u32 = 24;
+
 #[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, TypeInfo, Copy, Clone)]
 #[serde(rename_all = "lowercase")]
 pub enum Op {
@@ -45,6 +55,282 @@ pub enum Op {
 	NotEq,
 }
 
+impl Op {
+	fn is_ordering(&self) -> bool {
+		!matches!(self, Self::Equal | Self::NotEq)
+	}
+
+	fn matches(&self, ordering: Ordering) -> bool {
+		match self {
+			Self::GreaterThan => ordering == Ordering::Greater,
+			Self::LessThan => ordering == Ordering::Less,
+			Self::GreaterEq => ordering != Ordering::Less,
+			Self::LessEq => ordering != Ordering::Greater,
+			Self::Equal => ordering == Ordering::Equal,
+			Self::NotEq => ordering != Ordering::Equal,
+		}
+	}
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum EvalError {
+	/// A `$variable` operand is not bound in the evaluation context
+	UnboundVariable(String),
+	/// The operands have types that can't be compared with each other
+	TypeMismatch(String, String),
+	/// The operator isn't defined for the operand type, e.g. `>` on booleans
+	UnsupportedOp(Op, String),
+	/// Decimal normalisation overflowed
+	Overflow,
+}
+
+/// Typed operand of an assertion.
+///
+/// Operands are stored as plain strings in the credential (so that the JSON shape stays stable),
+/// and are converted to a `Value` with `Value::parse` before being compared.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Value {
+	Bool(bool),
+	Int(i128),
+	/// Fixed-point decimal: `mantissa * 10^-scale`
+	Decimal {
+		mantissa: i128,
+		scale: u32,
+	},
+	/// ISO-8601 date or datetime, as seconds since the unix epoch (UTC)
+	Date(i64),
+	String(String),
+}
+
+impl Value {
+	/// Infers the type of a literal, the order of precedence is:
+	/// bool -> integer -> decimal -> ISO-8601 date/datetime -> string
+	pub fn parse(s: &str) -> Self {
+		match s {
+			"true" => return Self::Bool(true),
+			"false" => return Self::Bool(false),
+			_ => {},
+		}
+
+		if let Some(v) = parse_int(s) {
+			return Self::Int(v)
+		}
+
+		if let Some((mantissa, scale)) = parse_decimal(s) {
+			return Self::Decimal { mantissa, scale }
+		}
+
+		if let Some(ts) = parse_date(s) {
+			return Self::Date(ts)
+		}
+
+		Self::String(s.to_string())
+	}
+
+	/// Builds a decimal from a raw on-chain token amount and the token precision,
+	/// e.g. `from_token_amount(1_500_000_000_000_000_000, 18)` is `1.5`.
+	pub fn from_token_amount(amount: u128, decimals: u32) -> Result<Self, EvalError> {
+		if decimals > MAX_DECIMAL_SCALE {
+			return Err(EvalError::Overflow)
+		}
+		let mantissa = i128::try_from(amount).map_err(|_| EvalError::Overflow)?;
+		Ok(Self::Decimal { mantissa, scale: decimals })
+	}
+
+	pub fn type_name(&self) -> &'static str {
+		match self {
+			Self::Bool(_) => "bool",
+			Self::Int(_) => "int",
+			Self::Decimal { .. } => "decimal",
+			Self::Date(_) => "date",
+			Self::String(_) => "string",
+		}
+	}
+
+	/// Compares two values of compatible types; integers are promoted to decimals when needed.
+	/// `None` is returned if the types are not comparable.
+	pub fn compare(&self, other: &Self) -> Result<Option<Ordering>, EvalError> {
+		let ordering = match (self, other) {
+			(Self::Bool(a), Self::Bool(b)) => Some(a.cmp(b)),
+			(Self::Int(a), Self::Int(b)) => Some(a.cmp(b)),
+			(Self::Date(a), Self::Date(b)) => Some(a.cmp(b)),
+			(Self::String(a), Self::String(b)) => Some(a.cmp(b)),
+			(Self::Int(a), Self::Decimal { mantissa, scale }) =>
+				Some(cmp_decimal((*a, 0), (*mantissa, *scale))?),
+			(Self::Decimal { mantissa, scale }, Self::Int(b)) =>
+				Some(cmp_decimal((*mantissa, *scale), (*b, 0))?),
+			(
+				Self::Decimal { mantissa: ma, scale: sa },
+				Self::Decimal { mantissa: mb, scale: sb },
+			) => Some(cmp_decimal((*ma, *sa), (*mb, *sb))?),
+			_ => None,
+		};
+		Ok(ordering)
+	}
+
+	/// Applies `op` to `self` (lhs) and `other` (rhs).
+	///
+	/// Equality between values of different types is always `false` (`!=` is `true`),
+	/// ordering operators are only defined for numbers and dates.
+	pub fn apply(&self, op: Op, other: &Self) -> Result<bool, EvalError> {
+		if op.is_ordering() {
+			match self {
+				Self::Bool(_) | Self::String(_) =>
+					return Err(EvalError::UnsupportedOp(op, self.type_name().to_string())),
+				_ => {},
+			}
+		}
+
+		match self.compare(other)? {
+			Some(ordering) => Ok(op.matches(ordering)),
+			None => match op {
+				Op::Equal => Ok(false),
+				Op::NotEq => Ok(true),
+				_ => Err(EvalError::TypeMismatch(
+					self.type_name().to_string(),
+					other.type_name().to_string(),
+				)),
+			},
+		}
+	}
+}
+
+impl From<bool> for Value {
+	fn from(v: bool) -> Self {
+		Self::Bool(v)
+	}
+}
+
+impl From<u64> for Value {
+	fn from(v: u64) -> Self {
+		Self::Int(v as i128)
+	}
+}
+
+impl From<i64> for Value {
+	fn from(v: i64) -> Self {
+		Self::Int(v as i128)
+	}
+}
+
+impl From<&str> for Value {
+	fn from(v: &str) -> Self {
+		Self::parse(v)
+	}
+}
+
+/// Bindings of `$variable` operands used when (re-)evaluating an assertion.
+/// The keys are the variable names without the leading `$`.
+pub type Variables = BTreeMap<String, Value>;
+
+fn parse_int(s: &str) -> Option<i128> {
+	let digits = s.strip_prefix('-').unwrap_or(s);
+	if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+		return None
+	}
+	s.parse::<i128>().ok()
+}
+
+fn parse_decimal(s: &str) -> Option<(i128, u32)> {
+	let (int_part, frac_part) = s.split_once('.')?;
+	let frac_ok = !frac_part.is_empty() && frac_part.bytes().all(|b| b.is_ascii_digit());
+	let int_digits = int_part.strip_prefix('-').unwrap_or(int_part);
+	let int_ok = !int_digits.is_empty() && int_digits.bytes().all(|b| b.is_ascii_digit());
+	if !frac_ok || !int_ok || frac_part.len() as u32 > MAX_DECIMAL_SCALE {
+		return None
+	}
+
+	let mut joined = String::with_capacity(int_part.len() + frac_part.len());
+	joined.push_str(int_part);
+	joined.push_str(frac_part);
+	let mantissa = joined.parse::<i128>().ok()?;
+	Some((mantissa, frac_part.len() as u32))
+}
+
+// `YYYY-MM-DD` is parsed by hand, anything longer has to be a valid RFC 3339 datetime.
+fn parse_date(s: &str) -> Option<i64> {
+	if !s.is_ascii() {
+		return None
+	}
+
+	if s.len() == 10 {
+		let bytes = s.as_bytes();
+		if bytes[4] != b'-' || bytes[7] != b'-' {
+			return None
+		}
+		let year: i64 = parse_fixed_digits(&s[0..4])?;
+		let month: u32 = parse_fixed_digits(&s[5..7])?;
+		let day: u32 = parse_fixed_digits(&s[8..10])?;
+		if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+			return None
+		}
+		return Some(days_from_civil(year, month, day) * 86_400)
+	}
+
+	if s.len() > 10 && s.as_bytes()[10] == b'T' {
+		return from_iso8601(s).map(|dt| dt.timestamp())
+	}
+
+	None
+}
+
+fn parse_fixed_digits<T: core::str::FromStr>(s: &str) -> Option<T> {
+	if !s.bytes().all(|b| b.is_ascii_digit()) {
+		return None
+	}
+	s.parse::<T>().ok()
+}
+
+fn is_leap_year(year: i64) -> bool {
+	(year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+	match month {
+		2 =>
+			if is_leap_year(year) {
+				29
+			} else {
+				28
+			},
+		4 | 6 | 9 | 11 => 30,
+		_ => 31,
+	}
+}
+
+// Days since 1970-01-01 of a proleptic Gregorian date,
+// see http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+	let year = if month <= 2 { year - 1 } else { year };
+	let era = if year >= 0 { year } else { year - 399 } / 400;
+	let yoe = year - era * 400;
+	let mp = (month as i64 + 9) % 12;
+	let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+	let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+	era * 146_097 + doe - 719_468
+}
+
+fn cmp_decimal(a: (i128, u32), b: (i128, u32)) -> Result<Ordering, EvalError> {
+	let scale = a.1.max(b.1);
+	let rescale = |(mantissa, s): (i128, u32)| -> Result<i128, EvalError> {
+		10i128
+			.checked_pow(scale - s)
+			.and_then(|factor| mantissa.checked_mul(factor))
+			.ok_or(EvalError::Overflow)
+	};
+	Ok(rescale(a)?.cmp(&rescale(b)?))
+}
+
+fn resolve(operand: &str, vars: &Variables) -> Result<Value, EvalError> {
+	match operand.strip_prefix(VARIABLE_PREFIX) {
+		Some(name) => vars
+			.get(name)
+			.cloned()
+			.ok_or_else(|| EvalError::UnboundVariable(name.to_string())),
+		None => Ok(Value::parse(operand)),
+	}
+}
+
 #[derive(Serialize, Deserialize, Encode, Decode, PartialEq, Eq, TypeInfo, Debug, Clone)]
 #[serde(untagged)]
 pub enum AssertionLogic {
@@ -61,6 +347,22 @@ pub enum AssertionLogic {
 		#[serde(rename = "or")]
 		items: Vec<Box<AssertionLogic>>,
 	},
+	Not {
+		#[serde(rename = "not")]
+		item: Box<AssertionLogic>,
+	},
+	/// `src` equals any of the listed values
+	In {
+		src: String,
+		#[serde(rename = "in")]
+		dst: Vec<String>,
+	},
+	/// Half-open range: `min <= src < max`
+	Between {
+		src: String,
+		min: String,
+		max: String,
+	},
 }
 
 impl AssertionLogic {
@@ -72,36 +374,77 @@ impl AssertionLogic {
 		Self::Or { items: vec![] }
 	}
 
+	pub fn new_not(item: AssertionLogic) -> Self {
+		Self::Not { item: Box::new(item) }
+	}
+
 	pub fn new_item<T: ToString>(src: T, op: Op, dst: T) -> Self {
 		Self::Item { src: src.to_string(), op, dst: dst.to_string() }
 	}
+
+	pub fn new_in<T: ToString>(src: T, dst: Vec<T>) -> Self {
+		Self::In { src: src.to_string(), dst: dst.iter().map(|v| v.to_string()).collect() }
+	}
+
+	pub fn new_between<T: ToString>(src: T, min: T, max: T) -> Self {
+		Self::Between { src: src.to_string(), min: min.to_string(), max: max.to_string() }
+	}
+
 	pub fn add_item(mut self, item: AssertionLogic) -> Self {
 		match &mut self {
-			Self::Item { .. } => unreachable!(),
 			Self::Or { items } => items.push(Box::new(item)),
 			Self::And { items } => items.push(Box::new(item)),
+			_ => unreachable!(),
 		}
 		self
 	}
 }
 
 pub trait Logic {
-	fn eval(&self) -> bool;
+	/// Evaluates the logic with literal operands only, any error evaluates to `false`.
+	fn eval(&self) -> bool {
+		self.eval_with(&Variables::new()).unwrap_or(false)
+	}
+
+	/// Evaluates the logic, `$variable` operands are looked up in `vars`.
+	fn eval_with(&self, vars: &Variables) -> Result<bool, EvalError>;
 }
 
 impl Logic for AssertionLogic {
-	fn eval(&self) -> bool {
+	fn eval_with(&self, vars: &Variables) -> Result<bool, EvalError> {
 		match self {
-			Self::Item { src, op, dst } => match op {
-				Op::GreaterThan => src > dst,
-				Op::LessThan => src < dst,
-				Op::GreaterEq => src >= dst,
-				Op::LessEq => src <= dst,
-				Op::Equal => src == dst,
-				Op::NotEq => src != dst,
+			Self::Item { src, op, dst } => resolve(src, vars)?.apply(*op, &resolve(dst, vars)?),
+			Self::And { items } => {
+				for item in items {
+					if !item.eval_with(vars)? {
+						return Ok(false)
+					}
+				}
+				Ok(true)
+			},
+			Self::Or { items } => {
+				for item in items {
+					if item.eval_with(vars)? {
+						return Ok(true)
+					}
+				}
+				Ok(false)
+			},
+			Self::Not { item } => Ok(!item.eval_with(vars)?),
+			Self::In { src, dst } => {
+				let src = resolve(src, vars)?;
+				for v in dst {
+					if src.apply(Op::Equal, &resolve(v, vars)?)? {
+						return Ok(true)
+					}
+				}
+				Ok(false)
+			},
+			Self::Between { src, min, max } => {
+				let src = resolve(src, vars)?;
+				Ok(src.apply(Op::GreaterEq, &resolve(min, vars)?)?
+					&& src.apply(Op::LessThan, &resolve(max, vars)?)?)
 			},
-			Self::And { items } => items.iter().all(|item| item.eval()),
-			Self::Or { items } => items.iter().any(|item| item.eval()),
 		}
 	}
 }
@@ -147,4 +490,103 @@ mod tests {
 		let a1 = AssertionLogic::new_or().add_item(web2_item).add_item(web3_item);
 		assert_eq!(a1.eval(), true);
 	}
+
+	#[test]
+	fn numeric_comparison_is_not_lexicographic() {
+		assert!(AssertionLogic::new_item("10", Op::GreaterThan, "9").eval());
+		assert!(AssertionLogic::new_item("-2", Op::LessThan, "1").eval());
+		assert!(AssertionLogic::new_item("1.50", Op::Equal, "1.5").eval());
+		assert!(AssertionLogic::new_item("2", Op::GreaterThan, "1.999").eval());
+		assert!(!AssertionLogic::new_item("0.1", Op::GreaterEq, "1").eval());
+	}
+
+	#[test]
+	fn token_precision_works() {
+		let amount = Value::from_token_amount(1_500_000_000_000_000_000, 18).unwrap();
+		assert_eq!(amount.apply(Op::Equal, &Value::parse("1.5")), Ok(true));
+		assert_eq!(amount.apply(Op::GreaterThan, &Value::parse("1")), Ok(true));
+		assert_eq!(amount.apply(Op::LessThan, &Value::parse("2")), Ok(true));
+	}
+
+	#[test]
+	fn date_comparison_works() {
+		assert_eq!(Value::parse("1970-01-02"), Value::Date(86_400));
+		assert!(AssertionLogic::new_item("2020-02-29", Op::LessThan, "2020-03-01").eval());
+		assert!(AssertionLogic::new_item("2023-01-01T00:00:00Z", Op::Equal, "2023-01-01").eval());
+		assert!(AssertionLogic::new_item("2017-12-31", Op::LessThan, "2018-01-01").eval());
+		// not a real date, falls back to a string
+		assert_eq!(Value::parse("2019-02-29"), Value::String("2019-02-29".into()));
+	}
+
+	#[test]
+	fn type_mismatch_works() {
+		let item = AssertionLogic::new_item("true", Op::GreaterThan, "false");
+		assert_eq!(
+			item.eval_with(&Variables::new()),
+			Err(EvalError::UnsupportedOp(Op::GreaterThan, "bool".into()))
+		);
+		assert!(!item.eval());
+
+		let item = AssertionLogic::new_item("2017-01-01", Op::LessThan, "7");
+		assert_eq!(
+			item.eval_with(&Variables::new()),
+			Err(EvalError::TypeMismatch("date".into(), "int".into()))
+		);
+
+		assert!(!AssertionLogic::new_item("true", Op::Equal, "1").eval());
+		assert!(AssertionLogic::new_item("true", Op::NotEq, "1").eval());
+	}
+
+	#[test]
+	fn not_in_between_works() {
+		let mut vars = Variables::new();
+		vars.insert("total_txs".into(), Value::from(15u64));
+		vars.insert("network".into(), Value::parse("Polkadot"));
+
+		let between = AssertionLogic::new_between("$total_txs", "10", "20");
+		assert_eq!(between.eval_with(&vars), Ok(true));
+		let between = AssertionLogic::new_between("$total_txs", "1", "15");
+		assert_eq!(between.eval_with(&vars), Ok(false));
+
+		let network = AssertionLogic::new_in("$network", vec!["Kusama", "Polkadot"]);
+		assert_eq!(network.eval_with(&vars), Ok(true));
+		assert_eq!(AssertionLogic::new_not(network).eval_with(&vars), Ok(false));
+
+		let unbound = AssertionLogic::new_item("$total_followers", Op::GreaterThan, "0");
+		assert_eq!(
+			unbound.eval_with(&vars),
+			Err(EvalError::UnboundVariable("total_followers".into()))
+		);
+	}
+
+	#[test]
+	fn not_in_between_serde_works() {
+		let logic = r#"
+            {
+                "and":[
+                    {
+                        "not":{
+                            "src":"$network",
+                            "in":["Kusama","Litmus"]
+                        }
+                    },
+                    {
+                        "src":"$total_txs",
+                        "min":"1",
+                        "max":"10"
+                    }
+                ]
+            }
+            "#;
+
+		let from_str: AssertionLogic = serde_json::from_str(logic).unwrap();
+		let from_struct = AssertionLogic::new_and()
+			.add_item(AssertionLogic::new_not(AssertionLogic::new_in(
+				"$network",
+				vec!["Kusama", "Litmus"],
+			)))
+			.add_item(AssertionLogic::new_between("$total_txs", "1", "10"));
+
+		assert_eq!(from_str, from_struct);
+	}
 }
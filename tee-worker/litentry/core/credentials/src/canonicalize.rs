@@ -0,0 +1,135 @@
+// Copyright 2020-2023 Litentry Technologies GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+//
+// JSON Canonicalization Scheme (JCS), RFC 8785
+// https://www.rfc-editor.org/rfc/rfc8785
+
+#[cfg(all(not(feature = "std"), feature = "sgx"))]
+use crate::sgx_reexport_prelude::*;
+
+use crate::Error;
+use serde_json::{Number, Value};
+use std::{string::String, vec::Vec};
+
+/// Serializes `value` according to RFC 8785: object members are sorted by the UTF-16 code units
+/// of their names, no insignificant whitespace is emitted and strings use the minimal escaping of
+/// ECMAScript `JSON.stringify`.
+pub fn canonicalize(value: &Value) -> Result<String, Error> {
+	let mut out = String::new();
+	write_value(value, &mut out)?;
+	Ok(out)
+}
+
+fn write_value(value: &Value, out: &mut String) -> Result<(), Error> {
+	match value {
+		Value::Null => out.push_str("null"),
+		Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+		Value::Number(n) => write_number(n, out)?,
+		Value::String(s) => write_string(s, out)?,
+		Value::Array(items) => {
+			out.push('[');
+			for (i, item) in items.iter().enumerate() {
+				if i > 0 {
+					out.push(',');
+				}
+				write_value(item, out)?;
+			}
+			out.push(']');
+		},
+		Value::Object(map) => {
+			let mut members: Vec<(&String, &Value)> = map.iter().collect();
+			members.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+
+			out.push('{');
+			for (i, (key, item)) in members.into_iter().enumerate() {
+				if i > 0 {
+					out.push(',');
+				}
+				write_string(key, out)?;
+				out.push(':');
+				write_value(item, out)?;
+			}
+			out.push('}');
+		},
+	}
+	Ok(())
+}
+
+// serde_json escapes exactly `"`, `\` and the C0 control characters, using the short forms
+// where they exist and lowercase `\u00xx` otherwise, which is what RFC 8785 mandates.
+fn write_string(s: &str, out: &mut String) -> Result<(), Error> {
+	let escaped = serde_json::to_string(s).map_err(|e| Error::ParseError(format!("{}", e)))?;
+	out.push_str(&escaped);
+	Ok(())
+}
+
+// Integers are serialized as-is. Floating point numbers are only accepted in the range where
+// the ECMAScript number serialization does not switch to exponential notation.
+fn write_number(n: &Number, out: &mut String) -> Result<(), Error> {
+	if n.is_i64() || n.is_u64() {
+		out.push_str(&format!("{}", n));
+		return Ok(())
+	}
+
+	let f = n.as_f64().ok_or_else(|| Error::ParseError(format!("invalid number {}", n)))?;
+	if f == 0.0 {
+		out.push('0');
+	} else if f.abs() >= 1e-6 && f.abs() < 1e21 {
+		out.push_str(&format!("{}", f));
+	} else {
+		return Err(Error::ParseError(format!("number {} can't be canonicalized", n)))
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn sorts_members_and_strips_whitespace() {
+		let value: Value = serde_json::from_str(
+			r#"{ "b": [1, 2, { "z": null, "a": true }], "a": "x", "c": { } }"#,
+		)
+		.unwrap();
+		assert_eq!(
+			canonicalize(&value).unwrap(),
+			r#"{"a":"x","b":[1,2,{"a":true,"z":null}],"c":{}}"#
+		);
+	}
+
+	#[test]
+	fn sorts_members_by_utf16_code_units() {
+		// U+1F600 is encoded as the surrogate pair D83D DE00 and therefore sorts before U+FB33,
+		// although its UTF-8 encoding is greater
+		let value = json!({ "\u{fb33}": 1, "\u{1f600}": 2, "\r": 3 });
+		assert_eq!(canonicalize(&value).unwrap(), "{\"\\r\":3,\"\u{1f600}\":2,\"\u{fb33}\":1}");
+	}
+
+	#[test]
+	fn escapes_strings_minimally() {
+		let value = json!("€$\u{000f}\nA'B\"\\\\\"/");
+		assert_eq!(canonicalize(&value).unwrap(), "\"€$\\u000f\\nA'B\\\"\\\\\\\\\\\"/\"");
+	}
+
+	#[test]
+	fn serializes_numbers() {
+		let value = json!([0, -1, 42, 1.5, 0.0, 1e20]);
+		assert_eq!(canonicalize(&value).unwrap(), "[0,-1,42,1.5,0,100000000000000000000]");
+		assert!(canonicalize(&json!(1e-7)).is_err());
+	}
+}
@@ -33,12 +33,32 @@ pub enum Error {
 	EmptyCredentialSubject,
 	#[error("Empty Issuance Timestamp")]
 	EmptyIssuanceTimestamp,
+	#[error("Invalid Validity Period")]
+	InvalidValidityPeriod,
+	#[error("Credential Not Yet Valid")]
+	CredentialNotYetValid,
+	#[error("Credential Expired")]
+	CredentialExpired,
 	#[error("Invalid Proof")]
 	InvalidProof,
+	#[error("Invalid Signature")]
+	InvalidSignature,
+	#[error("Unknown Issuer: {0}")]
+	UnknownIssuer(String),
+	#[error("Schema Mismatch at {0}")]
+	SchemaMismatch(String),
+	#[error("Invalid SD-JWT: {0}")]
+	InvalidSdJwt(String),
+	#[error("Invalid Disclosure: {0}")]
+	InvalidDisclosure(String),
 	#[error("Credential Is Too Long")]
 	CredentialIsTooLong,
 	#[error("Parse Error: {0}")]
 	ParseError(String),
+	#[error("Assertion Evaluation Error: {0}")]
+	AssertionEvalError(String),
+	#[error("Assertion Mismatch at index {0}")]
+	AssertionMismatch(usize),
 	#[error("Unsupported Assertion")]
 	UnsupportedAssertion,
 	#[error("Runtime Error: {0}")]
@@ -41,11 +41,17 @@ use itp_stf_primitives::types::ShardIdentifier;
 use itp_time_utils::{from_iso8601, now_as_iso8601};
 use itp_types::AccountId;
 use itp_utils::stringify::account_id_to_string;
-use litentry_primitives::{Address20, Address32, Identity, Web3Network};
+use litentry_primitives::{
+	format_solana_address, Address20, Address32, BitcoinAddress, Identity, Web3Network,
+};
 use log::*;
 use scale_info::TypeInfo;
 use serde::{Deserialize, Serialize};
-use sp_core::{hashing::blake2_256, hexdisplay::HexDisplay};
+use serde_json::Value;
+use sp_core::{
+	hashing::{blake2_256, sha2_256},
+	hexdisplay::HexDisplay,
+};
 use std::{
 	fmt::Debug,
 	string::{String, ToString},
@@ -58,40 +64,63 @@ extern crate rust_base58_sgx as rust_base58;
 #[cfg(all(not(feature = "std"), feature = "sgx"))]
 extern crate hex_sgx as hex;
 
+#[cfg(all(not(feature = "std"), feature = "sgx"))]
+extern crate base64_sgx as base64;
+
 extern crate core;
 #[cfg(all(not(feature = "std"), feature = "sgx"))]
 extern crate rand_sgx as rand;
 
 #[cfg(all(not(feature = "std"), feature = "sgx"))]
-use crate::sgx_reexport_prelude::chrono::{offset::Utc as TzUtc, DateTime, NaiveDateTime};
+use crate::sgx_reexport_prelude::chrono::{
+	offset::Utc as TzUtc, DateTime, Duration as ChronoDuration, NaiveDateTime,
+};
 
 #[cfg(all(not(feature = "std"), feature = "sgx"))]
 use std::time::{SystemTime, UNIX_EPOCH};
 
 #[cfg(feature = "std")]
-use chrono::offset::Utc as TzUtc;
+use chrono::{offset::Utc as TzUtc, Duration as ChronoDuration};
 
 use rand::Rng;
-use rust_base58::ToBase58;
+use rust_base58::{FromBase58, ToBase58};
 
 pub mod error;
 pub use error::Error;
 pub mod schema;
 
+pub mod canonicalize;
+use canonicalize::canonicalize;
+
+pub mod verify;
+pub use verify::verify_credential;
+
+pub mod sd_jwt;
+pub use sd_jwt::{verify_sd_jwt, SdJwt};
+
 pub mod assertion_logic;
-use assertion_logic::{AssertionLogic, Op};
+use assertion_logic::{AssertionLogic, Logic, Op, Variables};
 use itp_utils::hex::hex_encode;
 
 pub const LITENTRY_ISSUER_NAME: &str = "Litentry TEE Worker";
 pub const PROOF_PURPOSE: &str = "assertionMethod";
 pub const MAX_CREDENTIAL_SIZE: usize = 2048;
-
-/// Ed25519 Signature 2018, W3C, 23 July 2021, https://w3c-ccg.github.io/lds-ed25519-2018
-/// May be registered in Linked Data Cryptographic Suite Registry, W3C, 29 December 2020
-/// https://w3c-ccg.github.io/ld-cryptosuite-registry
+/// EdDSA Cryptosuites v1.0, W3C, https://www.w3.org/TR/vc-di-eddsa/#eddsa-jcs-2022
+pub const PROOF_CRYPTOSUITE: &str = "eddsa-jcs-2022";
+/// Fragment of the issuer DID URL that refers to the `vc_pubkey` registered in teerex
+pub const VERIFICATION_METHOD_FRAGMENT: &str = "vc-pubkey";
+pub const MULTIBASE_BASE58BTC_PREFIX: &str = "z";
+pub const CREDENTIAL_STATUS_TYPE: &str = "LitentryVCRegistry";
+pub const PAIRWISE_DID_CONTEXT: &[u8] = b"litentry/pairwise-did";
+/// How long an issued credential is valid by default, in days, see `Assertion::validity_period`
+/// for the validity period of the individual assertions
+pub const CREDENTIAL_VALIDITY_DAYS: i64 = 365;
+
+/// Verifiable Credential Data Integrity 1.0, W3C, https://www.w3.org/TR/vc-data-integrity
+/// The concrete algorithm is given by the `cryptosuite` of the proof.
 #[derive(Serialize, Deserialize, Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo)]
 pub enum ProofType {
-	Ed25519Signature2020,
+	DataIntegrityProof,
 }
 
 #[derive(Serialize, Deserialize, Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo)]
@@ -144,6 +173,11 @@ pub struct CredentialSubject {
 	pub assertions: Vec<AssertionLogic>,
 	/// Results of each set of assertions
 	pub values: Vec<bool>,
+	/// Indices of the assertions whose value is not the result of their logic, e.g. the A8 range
+	/// that only describes the bucket of the transaction count. They are not re-evaluated by
+	/// `Credential::verify_assertions`.
+	#[serde(skip)]
+	pub unevaluated_values: Vec<u32>,
 	/// The extrinsic on Parentchain for credential verification purpose
 	pub endpoint: String,
 }
@@ -170,33 +204,67 @@ pub struct CredentialSchema {
 	pub types: String,
 }
 
+/// Points to the entry of the credential in the `VCRegistry` of the Parentchain `VCManagement`
+/// pallet, which holds its current status.
+#[derive(Serialize, Deserialize, Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialStatus {
+	/// The VC index in the registry
+	pub id: String,
+	#[serde(rename = "type")]
+	pub types: String,
+}
+
+impl CredentialStatus {
+	pub fn new(vc_index: &str) -> Self {
+		CredentialStatus { id: vc_index.to_string(), types: CREDENTIAL_STATUS_TYPE.to_string() }
+	}
+}
+
 #[derive(Serialize, Deserialize, Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo)]
 #[serde(rename_all = "camelCase")]
 pub struct Proof {
 	/// The ISO-8601 datetime of signature creation
 	pub created: String,
-	/// The cryptographic signature suite that used to generate signature
+	/// The proof type, the signature suite is identified by `cryptosuite`
 	#[serde(rename = "type")]
 	pub proof_type: ProofType,
+	/// The cryptographic suite that was used to generate the signature
+	pub cryptosuite: String,
 	/// Purpose of this proof, generally it is expected as a fixed value, such as 'assertionMethod'
 	pub proof_purpose: String,
-	/// The digital signature value(signature of hash)
+	/// The multibase (base58btc) encoded signature, empty for an unsigned proof configuration
+	#[serde(skip_serializing_if = "String::is_empty", default)]
 	pub proof_value: String,
-	/// The public key from Issuer
+	/// The key of the Issuer, as DID URL
 	pub verification_method: String,
 }
 
 impl Proof {
-	pub fn new(sig: &Vec<u8>, issuer: &AccountId) -> Self {
+	/// Creates the unsigned proof configuration for the given issuer DID.
+	pub fn new(issuer: &str) -> Self {
 		Proof {
 			created: now_as_iso8601(),
-			proof_type: ProofType::Ed25519Signature2020,
+			proof_type: ProofType::DataIntegrityProof,
+			cryptosuite: PROOF_CRYPTOSUITE.to_string(),
 			proof_purpose: PROOF_PURPOSE.to_string(),
-			proof_value: format!("{}", HexDisplay::from(sig)),
-			verification_method: account_id_to_string(issuer),
+			proof_value: String::new(),
+			verification_method: format!("{}#{}", issuer, VERIFICATION_METHOD_FRAGMENT),
 		}
 	}
 
+	pub fn set_signature(&mut self, sig: &[u8]) {
+		self.proof_value = format!("{}{}", MULTIBASE_BASE58BTC_PREFIX, sig.to_base58());
+	}
+
+	pub fn signature(&self) -> Result<Vec<u8>, Error> {
+		let encoded = self
+			.proof_value
+			.strip_prefix(MULTIBASE_BASE58BTC_PREFIX)
+			.ok_or(Error::InvalidProof)?;
+		encoded.from_base58().map_err(|e| Error::ParseError(format!("{:?}", e)))
+	}
+
 	pub fn is_empty(&self) -> bool {
 		self.proof_value.is_empty()
 	}
@@ -217,7 +285,15 @@ pub struct Credential {
 	pub credential_subject: CredentialSubject,
 	/// The TEE enclave who issued the credential
 	pub issuer: Issuer,
-	pub issuance_date: String,
+	/// The earliest point in time at which the credential is valid
+	pub valid_from: String,
+	/// The point in time after which the credential is no longer valid, called `expirationDate`
+	/// in VC Data Model v1.1
+	#[serde(alias = "expirationDate")]
+	pub valid_until: String,
+	/// The entry in the Parentchain `VCRegistry` that holds the status of the credential
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub credential_status: Option<CredentialStatus>,
 	/// Digital proof with the signature of Issuer
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub proof: Option<Proof>,
@@ -245,18 +321,33 @@ impl Credential {
 		vc.issuer.mrenclave = shard.encode().to_base58();
 		vc.issuer.name = LITENTRY_ISSUER_NAME.to_string();
 		vc.credential_subject.id = DID::try_from(subject)?.format();
-		vc.issuance_date = now_as_iso8601();
+		vc.valid_from = now_as_iso8601();
+		vc.valid_until = from_iso8601(&vc.valid_from)
+			.map(|from| (from + ChronoDuration::days(CREDENTIAL_VALIDITY_DAYS)).to_rfc3339())
+			.ok_or(Error::EmptyIssuanceTimestamp)?;
 		vc.credential_schema = None;
 		vc.proof = None;
 
 		vc.generate_id();
+		vc.credential_status = Some(CredentialStatus::new(&vc.id));
 		vc.validate_unsigned()?;
 
 		Ok(vc)
 	}
 
-	pub fn add_proof(&mut self, sig: &Vec<u8>, issuer: &AccountId) {
-		self.proof = Some(Proof::new(sig, issuer));
+	/// Returns the data that is signed for an `eddsa-jcs-2022` proof with the given (unsigned)
+	/// proof configuration, see `proof_hash_data`.
+	pub fn proof_payload(&self, proof: &Proof) -> Result<Vec<u8>, Error> {
+		let document =
+			serde_json::to_value(self).map_err(|err| Error::ParseError(format!("{}", err)))?;
+		let proof_config =
+			serde_json::to_value(proof).map_err(|err| Error::ParseError(format!("{}", err)))?;
+		proof_hash_data(document, proof_config)
+	}
+
+	pub fn add_proof(&mut self, mut proof: Proof, sig: &[u8]) {
+		proof.set_signature(sig);
+		self.proof = Some(proof);
 	}
 
 	fn generate_id(&mut self) {
@@ -290,7 +381,11 @@ impl Credential {
 			return Err(Error::EmptyCredentialSubject)
 		}
 
-		from_iso8601(&self.issuance_date).ok_or(Error::EmptyIssuanceTimestamp)?;
+		let valid_from = from_iso8601(&self.valid_from).ok_or(Error::EmptyIssuanceTimestamp)?;
+		let valid_until = from_iso8601(&self.valid_until).ok_or(Error::InvalidValidityPeriod)?;
+		if valid_until <= valid_from {
+			return Err(Error::InvalidValidityPeriod)
+		}
 
 		if self.id.is_empty() {
 			return Err(Error::InvalidCredential)
@@ -322,15 +417,64 @@ impl Credential {
 			return Err(Error::InvalidProof)
 		}
 
+		vc.validate_schema()?;
+
 		Ok(())
 	}
 
+	/// Checks that the current time lies within `validFrom` and `validUntil`.
+	pub fn validate_period(&self) -> Result<(), Error> {
+		let now = from_iso8601(&now_as_iso8601()).ok_or(Error::EmptyIssuanceTimestamp)?;
+		let valid_from = from_iso8601(&self.valid_from).ok_or(Error::EmptyIssuanceTimestamp)?;
+		let valid_until = from_iso8601(&self.valid_until).ok_or(Error::InvalidValidityPeriod)?;
+		if now < valid_from {
+			return Err(Error::CredentialNotYetValid)
+		}
+		if now > valid_until {
+			return Err(Error::CredentialExpired)
+		}
+		Ok(())
+	}
+
+	/// Re-evaluates the assertions of the credential subject with the given variable bindings,
+	/// and checks the result against the value that was issued for it.
+	/// Assertions whose value is not the result of their logic (see `unevaluated_values`) are
+	/// skipped.
+	pub fn verify_assertions(&self, vars: &Variables) -> Result<(), Error> {
+		let subject = &self.credential_subject;
+		if subject.assertions.len() != subject.values.len() {
+			return Err(Error::InvalidCredential)
+		}
+
+		for (index, (assertion, value)) in
+			subject.assertions.iter().zip(subject.values.iter()).enumerate()
+		{
+			if subject.unevaluated_values.contains(&(index as u32)) {
+				continue
+			}
+			let result = assertion
+				.eval_with(vars)
+				.map_err(|e| Error::AssertionEvalError(format!("{:?}", e)))?;
+			if result != *value {
+				return Err(Error::AssertionMismatch(index))
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Checks the credential against the bundled Litentry credential schema.
+	/// Only the `required` and `enum` keywords are evaluated.
 	pub fn validate_schema(&self) -> Result<(), Error> {
 		//ToDo: fetch schema from Parentchain and check its status
-		Ok(())
+		let schema: Value = serde_json::from_str(include_str!("templates/credential_schema.json"))
+			.map_err(|err| Error::ParseError(format!("{}", err)))?;
+		let vc = serde_json::to_value(self).map_err(|err| Error::ParseError(format!("{}", err)))?;
+		check_schema(&schema, &vc, "")
 	}
 
 	// Including assertion 4/7/10/11
+	// The logic describes the queried range, `is_hold` is not its result
 	pub fn update_holder(&mut self, is_hold: bool, minimum_amount: &String, from_date: &String) {
 		// from_date's Op is ALWAYS Op::LessThan
 		let from_date_logic = AssertionLogic::new_item("$from_date", Op::LessThan, from_date);
@@ -348,10 +492,42 @@ impl Credential {
 			.add_item(from_date_logic)
 			.add_item(to_date_logic);
 
+		self.credential_subject
+			.unevaluated_values
+			.push(self.credential_subject.values.len() as u32);
 		self.credential_subject.assertions.push(assertion);
 		self.credential_subject.values.push(is_hold);
 	}
 
+	/// Lets the credential expire `secs` seconds after `valid_from`.
+	pub fn set_validity_period(&mut self, secs: u64) -> Result<(), Error> {
+		let valid_from = from_iso8601(&self.valid_from).ok_or(Error::EmptyIssuanceTimestamp)?;
+		let secs = i64::try_from(secs).map_err(|_| Error::InvalidValidityPeriod)?;
+		let valid_until = valid_from
+			.checked_add_signed(ChronoDuration::seconds(secs))
+			.ok_or(Error::InvalidValidityPeriod)?;
+		self.valid_until = valid_until.to_rfc3339();
+		self.validate_unsigned()
+	}
+
+	/// Replaces the subject DID by its pairwise-pseudonymous identifier towards `verifier`,
+	/// see `DID::pairwise`, and returns the pseudonym.
+	///
+	/// The credential id (and thus `jti` and the `VCRegistry` index) is generated anew, so that
+	/// the credentials issued to different verifiers share no identifier.
+	pub fn set_pairwise_subject(
+		&mut self,
+		subject: &Identity,
+		verifier: &str,
+		secret: &[u8],
+	) -> [u8; 32] {
+		let pseudonym = pairwise_pseudonym(subject, verifier, secret);
+		self.credential_subject.id = DID::Pairwise(pseudonym).format();
+		self.generate_id();
+		self.credential_status = Some(CredentialStatus::new(&self.id));
+		pseudonym
+	}
+
 	pub fn add_subject_info(&mut self, subject_description: &str, types: &str) {
 		self.credential_subject.description = subject_description.into();
 		self.credential_subject.types = types.into();
@@ -451,6 +627,10 @@ impl Credential {
 			.add_item(min_item)
 			.add_item(max_item)
 			.add_item(or_logic);
+		// The logic describes the range, the value only tells whether it is not empty
+		self.credential_subject
+			.unevaluated_values
+			.push(self.credential_subject.values.len() as u32);
 		self.credential_subject.assertions.push(assertion);
 		self.credential_subject.values.push(value);
 	}
@@ -527,24 +707,89 @@ impl Credential {
 	}
 }
 
+/// Computes the hash data of the `eddsa-jcs-2022` cryptosuite: the SHA-256 hash of the
+/// JCS-canonicalized proof configuration, followed by the hash of the canonicalized document.
+/// The `proof` of `document` and the `proofValue` of `proof_config` are ignored.
+pub fn proof_hash_data(mut document: Value, mut proof_config: Value) -> Result<Vec<u8>, Error> {
+	let document = document.as_object_mut().ok_or(Error::InvalidCredential)?;
+	document.remove("proof");
+	let proof_config = proof_config.as_object_mut().ok_or(Error::InvalidProof)?;
+	proof_config.remove("proofValue");
+	if let Some(context) = document.get("@context") {
+		proof_config.insert("@context".to_string(), context.clone());
+	}
+
+	let proof_config = canonicalize(&Value::Object(proof_config.clone()))?;
+	let document = canonicalize(&Value::Object(document.clone()))?;
+
+	let mut hash_data = sha2_256(proof_config.as_bytes()).to_vec();
+	hash_data.extend_from_slice(&sha2_256(document.as_bytes()));
+	Ok(hash_data)
+}
+
+fn check_schema(schema: &Value, value: &Value, path: &str) -> Result<(), Error> {
+	if let Some(allowed) = schema.get("enum").and_then(|e| e.as_array()) {
+		if !allowed.contains(value) {
+			return Err(Error::SchemaMismatch(path.to_string()))
+		}
+	}
+
+	let object = match value.as_object() {
+		Some(object) => object,
+		None => return Ok(()),
+	};
+
+	if let Some(required) = schema.get("required").and_then(|r| r.as_array()) {
+		for field in required.iter().filter_map(|f| f.as_str()) {
+			if !object.contains_key(field) {
+				return Err(Error::SchemaMismatch(format!("{}/{}", path, field)))
+			}
+		}
+	}
+
+	if let Some(properties) = schema.get("properties").and_then(|p| p.as_object()) {
+		for (field, sub_schema) in properties {
+			if let Some(sub_value) = object.get(field) {
+				check_schema(sub_schema, sub_value, &format!("{}/{}", path, field))?;
+			}
+		}
+	}
+
+	Ok(())
+}
+
 pub enum DID {
 	Evm(Address20),
 	Substrate(Address32),
+	Bitcoin(BitcoinAddress),
+	Solana(Address32),
 	Twitter(String),
 	Discord(String),
 	Github(String),
+	Pairwise([u8; 32]),
 }
 
 impl DID {
+	/// Derives the pairwise-pseudonymous identifier of `subject` towards `verifier`.
+	///
+	/// Identifiers for different verifiers can't be linked to each other or to the subject
+	/// without knowing `secret`, which must therefore be kept inside the enclave.
+	pub fn pairwise(subject: &Identity, verifier: &str, secret: &[u8]) -> Self {
+		DID::Pairwise(pairwise_pseudonym(subject, verifier, secret))
+	}
+
 	pub fn format(self) -> String {
 		format!(
 			"did:litentry:{}",
 			&match self {
 				Self::Evm(address) => format!("evm:{}", &hex_encode(address.as_ref())),
 				Self::Substrate(address) => format!("substrate:{}", &hex_encode(address.as_ref())),
+				Self::Bitcoin(address) => format!("bitcoin:{}", address.format()),
+				Self::Solana(address) => format!("solana:{}", format_solana_address(&address)),
 				Self::Twitter(handle) => format!("twitter:{}", handle),
 				Self::Discord(handle) => format!("discord:{}", handle),
 				Self::Github(handle) => format!("github:{}", handle),
+				Self::Pairwise(id) => format!("pairwise:{}", &hex_encode(id.as_ref())),
 			}
 		)
 	}
@@ -557,6 +802,8 @@ impl TryFrom<&Identity> for DID {
 		match value {
 			Identity::Substrate(address) => Ok(DID::Substrate(*address)),
 			Identity::Evm(address) => Ok(DID::Evm(*address)),
+			Identity::Bitcoin(address) => Ok(DID::Bitcoin(*address)),
+			Identity::Solana(address) => Ok(DID::Solana(*address)),
 			Identity::Twitter(handle) => {
 				let handle = from_utf8(handle.as_ref())
 					.map_err(|e| Error::ParseError(format!("Conversion error: {}", e)))?
@@ -579,6 +826,10 @@ impl TryFrom<&Identity> for DID {
 	}
 }
 
+fn pairwise_pseudonym(subject: &Identity, verifier: &str, secret: &[u8]) -> [u8; 32] {
+	blake2_256(&(PAIRWISE_DID_CONTEXT, secret, subject, verifier).encode())
+}
+
 /// Assertion To-Date
 pub fn format_assertion_to_date() -> String {
 	#[cfg(feature = "std")]
@@ -697,6 +948,22 @@ mod tests {
 		)
 	}
 
+	#[test]
+	fn test_bitcoin_did_format() {
+		assert_eq!(
+			DID::Bitcoin(BitcoinAddress::P2wpkh([0; 20].into())).format(),
+			"did:litentry:bitcoin:bc1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqq9e75rs"
+		)
+	}
+
+	#[test]
+	fn test_solana_did_format() {
+		assert_eq!(
+			DID::Solana([0; 32].into()).format(),
+			"did:litentry:solana:11111111111111111111111111111111"
+		)
+	}
+
 	#[test]
 	fn test_discord_format() {
 		assert_eq!(
@@ -720,4 +987,114 @@ mod tests {
 			"did:litentry:github:github_handle"
 		)
 	}
+
+	#[test]
+	fn set_validity_period_works() {
+		let identity = AccountId::from([0; 32]).into();
+		let mut vc = Credential::new(&identity, &ShardIdentifier::default()).unwrap();
+		vc.set_validity_period(30 * 24 * 60 * 60).unwrap();
+
+		let valid_from = from_iso8601(&vc.valid_from).unwrap();
+		let valid_until = from_iso8601(&vc.valid_until).unwrap();
+		assert_eq!(valid_until - valid_from, ChronoDuration::days(30));
+		assert!(matches!(vc.set_validity_period(0), Err(Error::InvalidValidityPeriod)));
+	}
+
+	#[test]
+	fn validate_schema_works() {
+		let who = AccountId::from([0; 32]);
+		let identity = who.into();
+		let shard = ShardIdentifier::default();
+
+		let mut vc = Credential::new(&identity, &shard).unwrap();
+		assert_eq!(vc.credential_status, Some(CredentialStatus::new(&vc.id)));
+		assert!(from_iso8601(&vc.valid_until) > from_iso8601(&vc.valid_from));
+		assert!(
+			matches!(vc.validate_schema(), Err(Error::SchemaMismatch(path)) if path == "/proof")
+		);
+
+		let proof = Proof::new("did:litentry:substrate:0x00");
+		vc.add_proof(proof, &[0u8; 64]);
+		assert!(vc.validate_schema().is_ok());
+
+		vc.proof.as_mut().unwrap().cryptosuite = "ecdsa-rdfc-2019".to_string();
+		assert!(
+			matches!(vc.validate_schema(), Err(Error::SchemaMismatch(path)) if path == "/proof/cryptosuite")
+		);
+	}
+
+	#[test]
+	fn test_pairwise_did_format() {
+		let subject: Identity = AccountId::from([0; 32]).into();
+		let did = DID::pairwise(&subject, "https://verifier.example", b"secret").format();
+
+		assert!(did.starts_with("did:litentry:pairwise:0x"));
+		assert_eq!(did, DID::pairwise(&subject, "https://verifier.example", b"secret").format());
+		assert_ne!(did, DID::pairwise(&subject, "https://other.example", b"secret").format());
+		assert_ne!(did, DID::pairwise(&subject, "https://verifier.example", b"other").format());
+	}
+
+	#[test]
+	fn set_pairwise_subject_generates_new_id() {
+		let subject: Identity = AccountId::from([0; 32]).into();
+		let mut vc = Credential::new(&subject, &ShardIdentifier::default()).unwrap();
+		let id = vc.id.clone();
+
+		let pseudonym = vc.set_pairwise_subject(&subject, "https://verifier.example", b"secret");
+
+		assert_eq!(
+			vc.credential_subject.id,
+			DID::pairwise(&subject, "https://verifier.example", b"secret").format()
+		);
+		assert_eq!(vc.credential_subject.id, DID::Pairwise(pseudonym).format());
+		assert_ne!(vc.id, id);
+		assert_eq!(vc.credential_status, Some(CredentialStatus::new(&vc.id)));
+		assert!(vc.validate_unsigned().is_ok());
+	}
+
+	#[test]
+	fn verify_assertions_works() {
+		let who = AccountId::from([0; 32]);
+		let identity = who.into();
+		let shard = ShardIdentifier::default();
+
+		let mut vc = Credential::new(&identity, &shard).unwrap();
+		vc.add_assertion_a6(10, 100);
+
+		let mut vars = Variables::new();
+		vars.insert("total_followers".into(), 42u64.into());
+		assert!(vc.verify_assertions(&vars).is_ok());
+
+		// "9" < "10" must be compared numerically
+		vars.insert("total_followers".into(), 9u64.into());
+		assert!(matches!(vc.verify_assertions(&vars), Err(Error::AssertionMismatch(0))));
+
+		vars.remove("total_followers");
+		assert!(matches!(vc.verify_assertions(&vars), Err(Error::AssertionEvalError(_))));
+	}
+
+	#[test]
+	fn verify_assertions_skips_unevaluated_values() {
+		let who = AccountId::from([0; 32]);
+		let identity = who.into();
+		let shard = ShardIdentifier::default();
+
+		// A8 of an account without any transaction: the range [0, 1) holds, the value is false
+		let mut vc = Credential::new(&identity, &shard).unwrap();
+		vc.add_assertion_a8(vec![Web3Network::Polkadot], 0, 1);
+		// A4 of an account that does not hold the minimum amount
+		vc.update_holder(false, &"10".into(), &"2017-01-01".into());
+		vc.add_assertion_a14(true);
+		assert_eq!(vc.credential_subject.unevaluated_values, vec![0, 1]);
+
+		let vc = Credential::decode(&mut vc.encode().as_slice()).unwrap();
+		let mut vars = Variables::new();
+		vars.insert("network".into(), assertion_logic::Value::parse("Polkadot"));
+		vars.insert("total_txs".into(), 0u64.into());
+		vars.insert("total_governance_action".into(), 3u64.into());
+		assert!(vc.verify_assertions(&vars).is_ok());
+
+		vars.insert("total_governance_action".into(), 0u64.into());
+		assert!(matches!(vc.verify_assertions(&vars), Err(Error::AssertionMismatch(2))));
+	}
 }
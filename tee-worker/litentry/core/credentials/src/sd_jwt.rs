@@ -0,0 +1,529 @@
+// Copyright 2020-2023 Litentry Technologies GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+//
+// Selective Disclosure for JWTs (SD-JWT), IETF draft 05
+// https://datatracker.ietf.org/doc/html/draft-ietf-oauth-selective-disclosure-jwt-05
+
+//! SD-JWT representation of a credential.
+//!
+//! The subject identifier, the subject description/type/data source/endpoint and every single
+//! assertion (together with its value) are selectively disclosable, so that a holder can present
+//! e.g. a single "holds >= X LIT" assertion without revealing the other claims.
+
+#[cfg(all(not(feature = "std"), feature = "sgx"))]
+use crate::sgx_reexport_prelude::*;
+
+use crate::{verify::registered_issuer_key, Credential, CredentialType, Error};
+use itp_time_utils::{from_iso8601, now_as_secs};
+use itp_types::Enclave;
+use rand::Rng;
+use serde_json::{Map, Value};
+use sp_core::{ed25519, hashing::sha2_256, Pair};
+use std::{
+	collections::BTreeMap,
+	string::{String, ToString},
+	vec,
+	vec::Vec,
+};
+
+pub const SD_JWT_TYPE: &str = "vc+sd-jwt";
+pub const SD_ALGORITHM: &str = "sha-256";
+pub const JWS_ALGORITHM: &str = "EdDSA";
+pub const DISCLOSURE_SEPARATOR: char = '~';
+
+const SD_KEY: &str = "_sd";
+const SD_ALGORITHM_KEY: &str = "_sd_alg";
+const ARRAY_ELEMENT_KEY: &str = "...";
+const SALT_LEN: usize = 16;
+
+/// A disclosure of a single claim (`[salt, name, value]`) or array element (`[salt, value]`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Disclosure {
+	pub salt: String,
+	/// The claim name, `None` for an array element
+	pub name: Option<String>,
+	pub value: Value,
+	encoded: String,
+}
+
+impl Disclosure {
+	pub fn new(name: Option<&str>, value: Value) -> Result<Self, Error> {
+		let salt = base64url_encode(&rand::thread_rng().gen::<[u8; SALT_LEN]>());
+		let array = match name {
+			Some(name) => vec![salt.clone().into(), name.into(), value.clone()],
+			None => vec![salt.clone().into(), value.clone()],
+		};
+		let json = serde_json::to_string(&Value::Array(array))
+			.map_err(|err| Error::ParseError(format!("{}", err)))?;
+
+		Ok(Disclosure {
+			salt,
+			name: name.map(|name| name.to_string()),
+			value,
+			encoded: base64url_encode(json.as_bytes()),
+		})
+	}
+
+	pub fn parse(encoded: &str) -> Result<Self, Error> {
+		let invalid = || Error::InvalidDisclosure(encoded.to_string());
+		let json = base64url_decode(encoded)?;
+		let array: Vec<Value> = serde_json::from_slice(&json).map_err(|_| invalid())?;
+		let (salt, name, value) = match array.as_slice() {
+			[Value::String(salt), value] => (salt.clone(), None, value.clone()),
+			[Value::String(salt), Value::String(name), value] => {
+				if name == SD_KEY || name == ARRAY_ELEMENT_KEY {
+					return Err(invalid())
+				}
+				(salt.clone(), Some(name.clone()), value.clone())
+			},
+			_ => return Err(invalid()),
+		};
+
+		Ok(Disclosure { salt, name, value, encoded: encoded.to_string() })
+	}
+
+	/// The base64url encoded SHA-256 hash of the disclosure, as embedded in the JWT.
+	pub fn digest(&self) -> String {
+		base64url_encode(&sha2_256(self.encoded.as_bytes()))
+	}
+
+	pub fn as_str(&self) -> &str {
+		&self.encoded
+	}
+}
+
+/// An issuer-signed JWT together with the disclosures a holder (still) holds.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SdJwt {
+	pub jwt: String,
+	pub disclosures: Vec<Disclosure>,
+}
+
+impl SdJwt {
+	/// Issues `credential` as SD-JWT, `sign` returns the Ed25519 signature of the given
+	/// JWS signing input with the key of the credential issuer.
+	pub fn issue<F>(credential: &Credential, sign: F) -> Result<Self, Error>
+	where
+		F: FnOnce(&[u8]) -> Result<Vec<u8>, Error>,
+	{
+		credential.validate_unsigned()?;
+		let (payload, disclosures) = sd_payload(credential)?;
+
+		let mut header = Map::new();
+		header.insert("alg".to_string(), JWS_ALGORITHM.into());
+		header.insert("typ".to_string(), SD_JWT_TYPE.into());
+
+		let signing_input =
+			format!("{}.{}", encode_json(&Value::Object(header))?, encode_json(&payload)?);
+		let signature = sign(signing_input.as_bytes())?;
+
+		Ok(SdJwt {
+			jwt: format!("{}.{}", signing_input, base64url_encode(&signature)),
+			disclosures,
+		})
+	}
+
+	pub fn parse(sd_jwt: &str) -> Result<Self, Error> {
+		// a trailing key binding JWT would follow the last separator
+		if !sd_jwt.ends_with(DISCLOSURE_SEPARATOR) {
+			return Err(Error::InvalidSdJwt("key binding is not supported".to_string()))
+		}
+
+		let mut parts = sd_jwt.split(DISCLOSURE_SEPARATOR);
+		let jwt = parts
+			.next()
+			.filter(|jwt| !jwt.is_empty())
+			.ok_or_else(|| Error::InvalidSdJwt("missing JWT".to_string()))?;
+		let disclosures = parts
+			.filter(|disclosure| !disclosure.is_empty())
+			.map(Disclosure::parse)
+			.collect::<Result<Vec<_>, _>>()?;
+
+		Ok(SdJwt { jwt: jwt.to_string(), disclosures })
+	}
+
+	/// Returns the payload of the issuer-signed JWT, without verifying the signature.
+	pub fn payload(&self) -> Result<Value, Error> {
+		let (_, payload, _) = split_jwt(&self.jwt)?;
+		decode_json(payload)
+	}
+
+	/// Creates a presentation that discloses only the named `claims` (such as `sub` or
+	/// `description`) and the assertions of the credential subject at the given indices.
+	pub fn present(&self, claims: &[&str], assertions: &[usize]) -> Result<String, Error> {
+		let payload = self.payload()?;
+		let issued_assertions = payload
+			.get("credentialSubject")
+			.and_then(|subject| subject.get("assertions"))
+			.and_then(|assertions| assertions.as_array())
+			.ok_or_else(|| Error::InvalidSdJwt("missing assertions".to_string()))?;
+		let assertion_digests = assertions
+			.iter()
+			.map(|index| {
+				issued_assertions
+					.get(*index)
+					.and_then(array_element_digest)
+					.ok_or(Error::AssertionMismatch(*index))
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+
+		let disclosures = self.disclosures.iter().filter(|disclosure| match &disclosure.name {
+			Some(name) => claims.contains(&name.as_str()),
+			None => assertion_digests.contains(&disclosure.digest().as_str()),
+		});
+		Ok(encode_sd_jwt(&self.jwt, disclosures))
+	}
+
+	pub fn encode(&self) -> String {
+		encode_sd_jwt(&self.jwt, self.disclosures.iter())
+	}
+}
+
+/// Verifies the issuer signature and the disclosures of the SD-JWT (presentation) `sd_jwt`
+/// against the enclave registry of teerex, checks its expiry, and returns the disclosed claims.
+pub fn verify_sd_jwt(sd_jwt: &str, enclaves: &[Enclave]) -> Result<Value, Error> {
+	let sd_jwt = SdJwt::parse(sd_jwt)?;
+	let (header, payload, signature) = split_jwt(&sd_jwt.jwt)?;
+
+	let header_json = decode_json(header)?;
+	if header_json.get("alg").and_then(|alg| alg.as_str()) != Some(JWS_ALGORITHM)
+		|| header_json.get("typ").and_then(|typ| typ.as_str()) != Some(SD_JWT_TYPE)
+	{
+		return Err(Error::InvalidSdJwt("unsupported header".to_string()))
+	}
+
+	let mut claims = decode_json(payload)?;
+	let issuer = claims
+		.get("iss")
+		.and_then(|iss| iss.as_str())
+		.ok_or_else(|| Error::InvalidSdJwt("missing iss".to_string()))?;
+	let issuer_key = registered_issuer_key(issuer, enclaves)?;
+	let signature = ed25519::Signature::from_slice(&base64url_decode(signature)?)
+		.ok_or(Error::InvalidSignature)?;
+	if !ed25519::Pair::verify(&signature, format!("{}.{}", header, payload), &issuer_key) {
+		return Err(Error::InvalidSignature)
+	}
+
+	let claims_object = claims
+		.as_object_mut()
+		.ok_or_else(|| Error::InvalidSdJwt("invalid payload".to_string()))?;
+	if claims_object.remove(SD_ALGORITHM_KEY) != Some(SD_ALGORITHM.into()) {
+		return Err(Error::InvalidSdJwt("unsupported _sd_alg".to_string()))
+	}
+
+	let mut disclosures = BTreeMap::new();
+	for disclosure in sd_jwt.disclosures {
+		if let Some(duplicate) = disclosures.insert(disclosure.digest(), disclosure) {
+			return Err(Error::InvalidDisclosure(duplicate.encoded))
+		}
+	}
+	resolve_disclosures(&mut claims, &mut disclosures)?;
+	// every disclosure must be referenced by a digest of the signed payload
+	if let Some(disclosure) = disclosures.into_values().next() {
+		return Err(Error::InvalidDisclosure(disclosure.encoded))
+	}
+
+	let now = now_as_secs();
+	match claims.get("exp").and_then(|exp| exp.as_u64()) {
+		Some(exp) if now > exp => return Err(Error::CredentialExpired),
+		Some(_) => {},
+		None => return Err(Error::InvalidSdJwt("missing exp".to_string())),
+	}
+	if matches!(claims.get("nbf").and_then(|nbf| nbf.as_u64()), Some(nbf) if now < nbf) {
+		return Err(Error::CredentialNotYetValid)
+	}
+
+	Ok(claims)
+}
+
+fn sd_payload(credential: &Credential) -> Result<(Value, Vec<Disclosure>), Error> {
+	let subject = &credential.credential_subject;
+	if subject.assertions.len() != subject.values.len() {
+		return Err(Error::InvalidCredential)
+	}
+
+	let mut disclosures = Vec::new();
+	let mut disclose = |name: Option<&str>, value: Value| -> Result<String, Error> {
+		let disclosure = Disclosure::new(name, value)?;
+		let digest = disclosure.digest();
+		disclosures.push(disclosure);
+		Ok(digest)
+	};
+
+	let mut subject_digests = vec![
+		disclose(Some("description"), subject.description.clone().into())?,
+		disclose(Some("type"), subject.types.clone().into())?,
+		disclose(Some("endpoint"), subject.endpoint.clone().into())?,
+	];
+	if let Some(data_source) = &subject.data_source {
+		let data_source = serde_json::to_value(data_source)
+			.map_err(|err| Error::ParseError(format!("{}", err)))?;
+		subject_digests.push(disclose(Some("dataSource"), data_source)?);
+	}
+	// the order of the digests must not reveal which claim they belong to
+	subject_digests.sort();
+
+	let mut assertions = Vec::new();
+	for (assertion, value) in subject.assertions.iter().zip(subject.values.iter()) {
+		let mut element = Map::new();
+		element.insert(
+			"assertion".to_string(),
+			serde_json::to_value(assertion).map_err(|err| Error::ParseError(format!("{}", err)))?,
+		);
+		element.insert("value".to_string(), (*value).into());
+
+		let mut digest = Map::new();
+		digest
+			.insert(ARRAY_ELEMENT_KEY.to_string(), disclose(None, Value::Object(element))?.into());
+		assertions.push(Value::Object(digest));
+	}
+
+	let mut credential_subject = Map::new();
+	credential_subject.insert(SD_KEY.to_string(), subject_digests.into());
+	credential_subject.insert("assertions".to_string(), assertions.into());
+
+	let mut payload = Map::new();
+	payload.insert("iss".to_string(), credential.issuer.id.clone().into());
+	payload.insert("jti".to_string(), credential.id.clone().into());
+	payload.insert("iat".to_string(), timestamp(&credential.valid_from)?.into());
+	payload.insert("nbf".to_string(), timestamp(&credential.valid_from)?.into());
+	payload.insert("exp".to_string(), timestamp(&credential.valid_until)?.into());
+	payload.insert("vct".to_string(), format!("{:?}", CredentialType::VerifiableCredential).into());
+	if let Some(status) = &credential.credential_status {
+		payload.insert(
+			"status".to_string(),
+			serde_json::to_value(status).map_err(|err| Error::ParseError(format!("{}", err)))?,
+		);
+	}
+	payload
+		.insert(SD_KEY.to_string(), vec![disclose(Some("sub"), subject.id.clone().into())?].into());
+	payload.insert(SD_ALGORITHM_KEY.to_string(), SD_ALGORITHM.into());
+	payload.insert("credentialSubject".to_string(), Value::Object(credential_subject));
+
+	Ok((Value::Object(payload), disclosures))
+}
+
+/// Replaces the digests in `value` by the claims of the matching `disclosures`, which are
+/// removed from the map. Digests without disclosure are dropped.
+fn resolve_disclosures(
+	value: &mut Value,
+	disclosures: &mut BTreeMap<String, Disclosure>,
+) -> Result<(), Error> {
+	match value {
+		Value::Object(object) => {
+			let digests = match object.remove(SD_KEY) {
+				Some(Value::Array(digests)) => digests,
+				Some(_) => return Err(Error::InvalidSdJwt("invalid _sd".to_string())),
+				None => Vec::new(),
+			};
+			for digest in digests {
+				let digest = digest
+					.as_str()
+					.ok_or_else(|| Error::InvalidSdJwt("invalid _sd".to_string()))?;
+				if let Some(disclosure) = disclosures.remove(digest) {
+					let name = match disclosure.name {
+						Some(name) if !object.contains_key(&name) => name,
+						_ => return Err(Error::InvalidDisclosure(disclosure.encoded)),
+					};
+					object.insert(name, disclosure.value);
+				}
+			}
+			for (_, item) in object.iter_mut() {
+				resolve_disclosures(item, disclosures)?;
+			}
+		},
+		Value::Array(items) => {
+			let mut resolved = Vec::with_capacity(items.len());
+			for mut item in items.drain(..) {
+				match array_element_digest(&item).map(|digest| disclosures.remove(digest)) {
+					Some(Some(disclosure)) => {
+						if disclosure.name.is_some() {
+							return Err(Error::InvalidDisclosure(disclosure.encoded))
+						}
+						let mut element = disclosure.value;
+						resolve_disclosures(&mut element, disclosures)?;
+						resolved.push(element);
+					},
+					Some(None) => {},
+					None => {
+						resolve_disclosures(&mut item, disclosures)?;
+						resolved.push(item);
+					},
+				}
+			}
+			*items = resolved;
+		},
+		_ => {},
+	}
+	Ok(())
+}
+
+fn array_element_digest(item: &Value) -> Option<&str> {
+	let object = item.as_object()?;
+	if object.len() != 1 {
+		return None
+	}
+	object.get(ARRAY_ELEMENT_KEY)?.as_str()
+}
+
+fn encode_sd_jwt<'a>(jwt: &str, disclosures: impl Iterator<Item = &'a Disclosure>) -> String {
+	let mut sd_jwt = jwt.to_string();
+	sd_jwt.push(DISCLOSURE_SEPARATOR);
+	for disclosure in disclosures {
+		sd_jwt.push_str(disclosure.as_str());
+		sd_jwt.push(DISCLOSURE_SEPARATOR);
+	}
+	sd_jwt
+}
+
+fn split_jwt(jwt: &str) -> Result<(&str, &str, &str), Error> {
+	let parts: Vec<&str> = jwt.split('.').collect();
+	match parts.as_slice() {
+		[header, payload, signature] => Ok((header, payload, signature)),
+		_ => Err(Error::InvalidSdJwt("malformed JWT".to_string())),
+	}
+}
+
+fn timestamp(datetime: &str) -> Result<i64, Error> {
+	from_iso8601(datetime)
+		.map(|datetime| datetime.timestamp())
+		.ok_or(Error::InvalidValidityPeriod)
+}
+
+fn encode_json(value: &Value) -> Result<String, Error> {
+	let json = serde_json::to_string(value).map_err(|err| Error::ParseError(format!("{}", err)))?;
+	Ok(base64url_encode(json.as_bytes()))
+}
+
+fn decode_json(encoded: &str) -> Result<Value, Error> {
+	serde_json::from_slice(&base64url_decode(encoded)?)
+		.map_err(|err| Error::ParseError(format!("{}", err)))
+}
+
+fn base64url_encode(data: &[u8]) -> String {
+	base64::encode_config(data, base64::URL_SAFE_NO_PAD)
+}
+
+fn base64url_decode(encoded: &str) -> Result<Vec<u8>, Error> {
+	base64::decode_config(encoded, base64::URL_SAFE_NO_PAD)
+		.map_err(|err| Error::ParseError(format!("{}", err)))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::DID;
+	use itp_stf_primitives::types::ShardIdentifier;
+	use itp_types::AccountId;
+	use litentry_primitives::{Identity, Web3Network};
+
+	fn vc_signer() -> ed25519::Pair {
+		ed25519::Pair::from_seed(&[2u8; 32])
+	}
+
+	fn registry() -> Vec<Enclave> {
+		vec![Enclave::new(
+			AccountId::from([1u8; 32]),
+			[0u8; 32],
+			0,
+			Vec::new(),
+			None,
+			Some(vc_signer().public().to_vec()),
+			Default::default(),
+			Default::default(),
+		)]
+	}
+
+	fn issue() -> SdJwt {
+		let identity = AccountId::from([0; 32]).into();
+		let mut credential = Credential::new(&identity, &ShardIdentifier::default()).unwrap();
+		credential.issuer.id =
+			DID::try_from(&Identity::Substrate(AccountId::from(vc_signer().public()).into()))
+				.unwrap()
+				.format();
+		credential.add_subject_info("holds LIT", "Token Holder");
+		credential.add_assertion_a1(true);
+		credential.add_assertion_a8(vec![Web3Network::Litentry], 1, 10);
+
+		SdJwt::issue(&credential, |payload| Ok(vc_signer().sign(payload).0.to_vec())).unwrap()
+	}
+
+	fn sd_jwt_assertion(index: usize) -> crate::AssertionLogic {
+		let identity = AccountId::from([0; 32]).into();
+		let mut credential = Credential::new(&identity, &ShardIdentifier::default()).unwrap();
+		credential.add_assertion_a1(true);
+		credential.add_assertion_a8(vec![Web3Network::Litentry], 1, 10);
+		credential.credential_subject.assertions[index].clone()
+	}
+
+	#[test]
+	fn verify_sd_jwt_discloses_all_claims() {
+		let sd_jwt = issue();
+
+		let claims = verify_sd_jwt(&sd_jwt.encode(), &registry()).unwrap();
+		assert_eq!(
+			claims["sub"],
+			"did:litentry:substrate:0x0000000000000000000000000000000000000000000000000000000000000000"
+		);
+		assert_eq!(claims["credentialSubject"]["description"], "holds LIT");
+		assert_eq!(claims["credentialSubject"]["assertions"].as_array().unwrap().len(), 2);
+		assert!(claims.get(SD_ALGORITHM_KEY).is_none());
+	}
+
+	#[test]
+	fn present_discloses_selected_claims_only() {
+		let sd_jwt = issue();
+
+		let presentation = sd_jwt.present(&["description"], &[1]).unwrap();
+		assert_eq!(SdJwt::parse(&presentation).unwrap().disclosures.len(), 2);
+
+		let claims = verify_sd_jwt(&presentation, &registry()).unwrap();
+		assert!(claims.get("sub").is_none());
+		let subject = &claims["credentialSubject"];
+		assert_eq!(subject["description"], "holds LIT");
+		assert!(subject.get("type").is_none());
+		let assertions = subject["assertions"].as_array().unwrap();
+		assert_eq!(assertions.len(), 1);
+		assert_eq!(assertions[0]["value"], true);
+		assert_eq!(assertions[0]["assertion"], serde_json::to_value(&sd_jwt_assertion(1)).unwrap());
+	}
+
+	#[test]
+	fn verify_sd_jwt_rejects_forged_disclosure() {
+		let sd_jwt = issue();
+		let forged = Disclosure::new(Some("description"), "holds BTC".into()).unwrap();
+		let presentation = format!("{}{}~", sd_jwt.present(&[], &[]).unwrap(), forged.as_str());
+
+		assert!(matches!(
+			verify_sd_jwt(&presentation, &registry()),
+			Err(Error::InvalidDisclosure(_))
+		));
+	}
+
+	#[test]
+	fn verify_sd_jwt_rejects_unknown_issuer() {
+		let sd_jwt = issue();
+
+		assert!(matches!(verify_sd_jwt(&sd_jwt.encode(), &[]), Err(Error::UnknownIssuer(_))));
+	}
+
+	#[test]
+	fn disclosure_roundtrip_works() {
+		let disclosure = Disclosure::new(Some("type"), "Token Holder".into()).unwrap();
+		assert_eq!(Disclosure::parse(disclosure.as_str()).unwrap(), disclosure);
+		assert!(Disclosure::parse("bm90IGpzb24").is_err());
+	}
+}
@@ -0,0 +1,196 @@
+// Copyright 2020-2023 Litentry Technologies GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Verification of credentials issued by a registered enclave.
+//!
+//! The proof is verified against the raw JSON document the holder presents, not against a
+//! re-serialized `Credential`, as not all fields of the credential survive deserialization.
+
+#[cfg(all(not(feature = "std"), feature = "sgx"))]
+use crate::sgx_reexport_prelude::*;
+
+use crate::{
+	proof_hash_data, Credential, Error, Proof, ProofType, PROOF_CRYPTOSUITE, PROOF_PURPOSE,
+	VERIFICATION_METHOD_FRAGMENT,
+};
+use itp_types::{AccountId, Enclave};
+use serde_json::Value;
+use sp_core::{ed25519, Pair};
+use std::{string::ToString, vec::Vec};
+
+const SUBSTRATE_DID_PREFIX: &str = "did:litentry:substrate:";
+
+/// Verifies the `eddsa-jcs-2022` proof of the credential `vc` and checks its validity period.
+///
+/// The issuer DID is the account of the enclave's VC signing key, which must be registered as
+/// `vc_pubkey` of one of the `enclaves`, the enclave registry of teerex.
+///
+/// Returns the verified credential. Note that its `assertions` are not deserialized.
+pub fn verify_credential(vc: &str, enclaves: &[Enclave]) -> Result<Credential, Error> {
+	let document: Value =
+		serde_json::from_str(vc).map_err(|err| Error::ParseError(format!("{}", err)))?;
+	let credential: Credential = serde_json::from_value(document.clone())
+		.map_err(|err| Error::ParseError(format!("{}", err)))?;
+	credential.validate_unsigned()?;
+
+	let proof = credential.proof.as_ref().ok_or(Error::InvalidProof)?;
+	check_proof_config(&credential, proof)?;
+
+	let vc_pubkey = registered_issuer_key(&credential.issuer.id, enclaves)?;
+
+	let proof_config = document.get("proof").cloned().ok_or(Error::InvalidProof)?;
+	let payload = proof_hash_data(document, proof_config)?;
+	let signature =
+		ed25519::Signature::from_slice(&proof.signature()?).ok_or(Error::InvalidSignature)?;
+	if !ed25519::Pair::verify(&signature, payload, &vc_pubkey) {
+		return Err(Error::InvalidSignature)
+	}
+
+	credential.validate_period()?;
+
+	Ok(credential)
+}
+
+fn check_proof_config(credential: &Credential, proof: &Proof) -> Result<(), Error> {
+	let verification_method = format!("{}#{}", credential.issuer.id, VERIFICATION_METHOD_FRAGMENT);
+	if proof.proof_type != ProofType::DataIntegrityProof
+		|| proof.cryptosuite != PROOF_CRYPTOSUITE
+		|| proof.proof_purpose != PROOF_PURPOSE
+		|| proof.verification_method != verification_method
+	{
+		return Err(Error::InvalidProof)
+	}
+	Ok(())
+}
+
+/// Returns the VC signing key of the issuer DID, if it is registered as `vc_pubkey` of one of the
+/// `enclaves`.
+pub(crate) fn registered_issuer_key(
+	issuer_did: &str,
+	enclaves: &[Enclave],
+) -> Result<ed25519::Public, Error> {
+	let issuer = issuer_account(issuer_did)?;
+	let issuer_key: &[u8] = issuer.as_ref();
+	if !enclaves.iter().any(|enclave| enclave.vc_pubkey.as_deref() == Some(issuer_key)) {
+		return Err(Error::UnknownIssuer(issuer_did.to_string()))
+	}
+	ed25519::Public::try_from(issuer_key).map_err(|_| Error::UnknownIssuer(issuer_did.to_string()))
+}
+
+fn issuer_account(issuer_did: &str) -> Result<AccountId, Error> {
+	let unknown_issuer = || Error::UnknownIssuer(issuer_did.to_string());
+	let address = issuer_did
+		.strip_prefix(SUBSTRATE_DID_PREFIX)
+		.and_then(|address| address.strip_prefix("0x"))
+		.ok_or_else(unknown_issuer)?;
+	let raw: Vec<u8> = hex::decode(address).map_err(|_| unknown_issuer())?;
+	let raw: [u8; 32] = raw.try_into().map_err(|_| unknown_issuer())?;
+	Ok(AccountId::from(raw))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{CredentialStatus, DID};
+	use itp_stf_primitives::types::ShardIdentifier;
+	use litentry_primitives::Identity;
+
+	fn vc_signer() -> ed25519::Pair {
+		ed25519::Pair::from_seed(&[2u8; 32])
+	}
+
+	fn enclave_account() -> AccountId {
+		vc_signer().public().into()
+	}
+
+	fn registry() -> Vec<Enclave> {
+		vec![Enclave::new(
+			AccountId::from([1u8; 32]),
+			[0u8; 32],
+			0,
+			Vec::new(),
+			None,
+			Some(vc_signer().public().to_vec()),
+			Default::default(),
+			Default::default(),
+		)]
+	}
+
+	fn issue(mut credential: Credential) -> String {
+		credential.issuer.id =
+			DID::try_from(&Identity::Substrate(enclave_account().into())).unwrap().format();
+		let proof = Proof::new(&credential.issuer.id);
+		let payload = credential.proof_payload(&proof).unwrap();
+		credential.add_proof(proof, &vc_signer().sign(&payload).0);
+		credential.validate().unwrap();
+		credential.to_json().unwrap()
+	}
+
+	fn new_credential() -> Credential {
+		let identity = AccountId::from([0; 32]).into();
+		let mut credential = Credential::new(&identity, &ShardIdentifier::default()).unwrap();
+		credential.add_assertion_a1(true);
+		credential
+	}
+
+	#[test]
+	fn verify_credential_works() {
+		let credential = new_credential();
+		let vc = issue(credential.clone());
+
+		let verified = verify_credential(&vc, &registry()).unwrap();
+		assert_eq!(verified.id, credential.id);
+		assert_eq!(verified.credential_status, Some(CredentialStatus::new(&credential.id)));
+	}
+
+	#[test]
+	fn verify_credential_is_independent_of_member_order_and_whitespace() {
+		let vc = issue(new_credential());
+		let reformatted =
+			serde_json::to_string_pretty(&serde_json::from_str::<Value>(&vc).unwrap()).unwrap();
+
+		assert!(verify_credential(&reformatted, &registry()).is_ok());
+	}
+
+	#[test]
+	fn verify_credential_fails_for_tampered_credential() {
+		let vc = issue(new_credential());
+		let mut document: Value = serde_json::from_str(&vc).unwrap();
+		document["credentialSubject"]["values"][0] = Value::Bool(false);
+
+		assert!(matches!(
+			verify_credential(&document.to_string(), &registry()),
+			Err(Error::InvalidSignature)
+		));
+	}
+
+	#[test]
+	fn verify_credential_fails_for_unregistered_issuer() {
+		let vc = issue(new_credential());
+
+		assert!(matches!(verify_credential(&vc, &[]), Err(Error::UnknownIssuer(_))));
+	}
+
+	#[test]
+	fn verify_credential_fails_for_expired_credential() {
+		let mut credential = new_credential();
+		credential.valid_from = "2020-01-01T00:00:00+00:00".to_string();
+		credential.valid_until = "2021-01-01T00:00:00+00:00".to_string();
+		let vc = issue(credential);
+
+		assert!(matches!(verify_credential(&vc, &registry()), Err(Error::CredentialExpired)));
+	}
+}
@@ -18,17 +18,15 @@
 use crate::sgx_reexport_prelude::*;
 
 use crate::{
-	build_client, ConvertParameterString, Error, HttpError, GLOBAL_DATA_PROVIDER_CONFIG,
-	LIT_TOKEN_ADDRESS, UNISWAP_TOKEN_ADDRESS, USDT_TOKEN_ADDRESS, WETH_TOKEN_ADDRESS,
+	ConvertParameterString, DataProvider, DataProviderClient, Error, HttpError, LIT_TOKEN_ADDRESS,
+	UNISWAP_TOKEN_ADDRESS, USDT_TOKEN_ADDRESS, WETH_TOKEN_ADDRESS,
 };
-use http::header::{AUTHORIZATION, CONNECTION};
+use http::header::CONNECTION;
 use http_req::response::Headers;
-use itc_rest_client::{
-	http_client::{DefaultSend, HttpClient},
-	rest_client::RestClient,
-	RestPath, RestPost,
+use itc_rest_client::RestPath;
+use litentry_primitives::{
+	AchainableParams, Assertion, IntoErrorDetail, VCMPError, Web3Network, Web3NetworkRegistry,
 };
-use litentry_primitives::{AchainableParams, VCMPError, Web3Network};
 use log::debug;
 use serde::{Deserialize, Serialize};
 use std::{
@@ -37,7 +35,9 @@ use std::{
 	vec::Vec,
 };
 pub struct AchainableClient {
-	client: RestClient<HttpClient<DefaultSend>>,
+	client: DataProviderClient,
+	/// The networks registered on the parentchain of the shard that's queried for
+	registry: Web3NetworkRegistry,
 }
 
 impl Default for AchainableClient {
@@ -48,18 +48,15 @@ impl Default for AchainableClient {
 
 impl AchainableClient {
 	pub fn new() -> Self {
+		Self::with_registry(Web3NetworkRegistry::new())
+	}
+
+	pub fn with_registry(registry: Web3NetworkRegistry) -> Self {
 		let mut headers = Headers::new();
 		headers.insert(CONNECTION.as_str(), "close");
-		headers.insert(
-			AUTHORIZATION.as_str(),
-			GLOBAL_DATA_PROVIDER_CONFIG.read().unwrap().achainable_auth_key.clone().as_str(),
-		);
-		let client = build_client(
-			GLOBAL_DATA_PROVIDER_CONFIG.read().unwrap().achainable_url.clone().as_str(),
-			headers,
-		);
+		let client = DataProviderClient::new(DataProvider::Achainable, headers);
 
-		AchainableClient { client }
+		AchainableClient { client, registry }
 	}
 
 	pub fn query_system_label(&mut self, address: &str, params: Params) -> Result<bool, Error> {
@@ -122,7 +119,7 @@ impl AchainableResultParser for AchainableClient {
 	}
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct SystemLabelReqPath {
 	path: String,
@@ -171,17 +168,40 @@ pub trait AchainableSystemLabelName {
 	fn name(&self) -> String;
 }
 
-pub fn web3_network_to_chain(network: &Web3Network) -> String {
+/// The name of a built-in network at achainable
+pub fn builtin_chain(network: &Web3Network) -> Option<&'static str> {
+	match network {
+		Web3Network::Polkadot => Some("polkadot"),
+		Web3Network::Kusama => Some("kusama"),
+		Web3Network::Litentry => Some("litentry"),
+		Web3Network::Litmus => Some("litmus"),
+		Web3Network::LitentryRococo => Some("litentry_rococo"),
+		Web3Network::Khala => Some("khala"),
+		Web3Network::SubstrateTestnet => Some("substrate_testnet"),
+		Web3Network::Ethereum => Some("ethereum"),
+		Web3Network::Bsc => Some("bsc"),
+		Web3Network::Bitcoin => Some("bitcoin"),
+		Web3Network::Solana => Some("solana"),
+		Web3Network::Registered(..) => None,
+	}
+}
+
+/// The name of `network` at achainable, a registered network has to be registered with one
+pub fn web3_network_to_chain(
+	network: &Web3Network,
+	registry: &Web3NetworkRegistry,
+) -> Result<String, Error> {
 	match network {
-		Web3Network::Polkadot => "polkadot".into(),
-		Web3Network::Kusama => "kusama".into(),
-		Web3Network::Litentry => "litentry".into(),
-		Web3Network::Litmus => "litmus".into(),
-		Web3Network::LitentryRococo => "litentry_rococo".into(),
-		Web3Network::Khala => "khala".into(),
-		Web3Network::SubstrateTestnet => "substrate_testnet".into(),
-		Web3Network::Ethereum => "ethereum".into(),
-		Web3Network::Bsc => "bsc".into(),
+		Web3Network::Registered(id) => registry
+			.get(id)
+			.and_then(|info| info.provider_name("achainable"))
+			.map(|name| String::from_utf8_lossy(name).into_owned())
+			.ok_or_else(|| {
+				Error::AchainableError(format!("web3 network {} has no achainable name", id))
+			}),
+		_ => builtin_chain(network)
+			.map(Into::into)
+			.ok_or_else(|| Error::AchainableError(format!("unknown web3 network {:?}", network))),
 	}
 }
 
@@ -219,9 +239,12 @@ impl AchainableSystemLabelName for Params {
 	}
 }
 
-impl TryFrom<AchainableParams> for Params {
-	type Error = VCMPError;
-	fn try_from(ap: AchainableParams) -> Result<Self, Self::Error> {
+impl Params {
+	/// The request parameters of `ap`, a registered network is named with `registry`
+	pub fn new(ap: AchainableParams, registry: &Web3NetworkRegistry) -> Result<Self, VCMPError> {
+		let map_err = |e: Error| {
+			VCMPError::RequestVCFailed(Assertion::Achainable(ap.clone()), e.into_error_detail())
+		};
 		match ap.clone() {
 			AchainableParams::AmountHolding(p) => {
 				let name = ap.to_string(&p.name)?;
@@ -231,7 +254,10 @@ impl TryFrom<AchainableParams> for Params {
 				let token =
 					if p.token.is_some() { Some(ap.to_string(&p.token.unwrap())?) } else { None };
 
-				let p = ParamsBasicTypeWithAmountHolding::one(name, network, amount, date, token);
+				let p = ParamsBasicTypeWithAmountHolding::one(
+					name, network, amount, date, token, registry,
+				)
+				.map_err(map_err)?;
 				Ok(Params::ParamsBasicTypeWithAmountHolding(p))
 			},
 			AchainableParams::AmountToken(p) => {
@@ -241,7 +267,8 @@ impl TryFrom<AchainableParams> for Params {
 				let token =
 					if p.token.is_some() { Some(ap.to_string(&p.token.unwrap())?) } else { None };
 
-				let p = ParamsBasicTypeWithAmountToken::new(name, network, amount, token);
+				let p = ParamsBasicTypeWithAmountToken::new(name, network, amount, token, registry)
+					.map_err(map_err)?;
 				Ok(Params::ParamsBasicTypeWithAmountToken(p))
 			},
 			AchainableParams::Amount(p) => {
@@ -249,7 +276,8 @@ impl TryFrom<AchainableParams> for Params {
 				let network = &p.chain;
 				let amount = ap.to_string(&p.amount)?;
 
-				let p = ParamsBasicTypeWithAmount::new(name, network, amount);
+				let p = ParamsBasicTypeWithAmount::new(name, network, amount, registry)
+					.map_err(map_err)?;
 				Ok(Params::ParamsBasicTypeWithAmount(p))
 			},
 			AchainableParams::Amounts(p) => {
@@ -258,14 +286,15 @@ impl TryFrom<AchainableParams> for Params {
 				let amount1 = ap.to_string(&p.amount1)?;
 				let amount2 = ap.to_string(&p.amount2)?;
 
-				let p = ParamsBasicTypeWithAmounts::new(name, network, amount1, amount2);
+				let p = ParamsBasicTypeWithAmounts::new(name, network, amount1, amount2, registry)
+					.map_err(map_err)?;
 				Ok(Params::ParamsBasicTypeWithAmounts(p))
 			},
 			AchainableParams::Basic(p) => {
 				let name = ap.to_string(&p.name)?;
 				let network = &p.chain;
 
-				let p = ParamsBasicType::new(name, network);
+				let p = ParamsBasicType::new(name, network, registry).map_err(map_err)?;
 				Ok(Params::ParamsBasicType(p))
 			},
 			AchainableParams::BetweenPercents(p) => {
@@ -279,7 +308,9 @@ impl TryFrom<AchainableParams> for Params {
 					network,
 					greater_than_or_equal_to,
 					less_than_or_equal_to,
-				);
+					registry,
+				)
+				.map_err(map_err)?;
 				Ok(Params::ParamsBasicTypeWithBetweenPercents(p))
 			},
 			AchainableParams::ClassOfYear(p) => {
@@ -293,7 +324,8 @@ impl TryFrom<AchainableParams> for Params {
 				let date1 = "2015-01-01".into();
 				let date2 = "2023-01-01".into();
 
-				let p = ParamsBasicTypeWithClassOfYear::new(name, network, date1, date2);
+				let p = ParamsBasicTypeWithClassOfYear::new(name, network, date1, date2, registry)
+					.map_err(map_err)?;
 				Ok(Params::ParamsBasicTypeWithClassOfYear(p))
 			},
 			AchainableParams::DateInterval(p) => {
@@ -302,7 +334,10 @@ impl TryFrom<AchainableParams> for Params {
 				let start_date = ap.to_string(&p.start_date)?;
 				let end_date = ap.to_string(&p.end_date)?;
 
-				let p = ParamsBasicTypeWithDateInterval::new(name, network, start_date, end_date);
+				let p = ParamsBasicTypeWithDateInterval::new(
+					name, network, start_date, end_date, registry,
+				)
+				.map_err(map_err)?;
 				Ok(Params::ParamsBasicTypeWithDateInterval(p))
 			},
 			AchainableParams::DatePercent(p) => {
@@ -312,7 +347,10 @@ impl TryFrom<AchainableParams> for Params {
 				let date = ap.to_string(&p.date)?;
 				let percent = ap.to_string(&p.percent)?;
 
-				let p = ParamsBasicTypeWithDatePercent::new(name, network, token, date, percent);
+				let p = ParamsBasicTypeWithDatePercent::new(
+					name, network, token, date, percent, registry,
+				)
+				.map_err(map_err)?;
 				Ok(Params::ParamsBasicTypeWithDatePercent(p))
 			},
 			AchainableParams::Date(p) => {
@@ -320,7 +358,8 @@ impl TryFrom<AchainableParams> for Params {
 				let network = &p.chain;
 				let date = ap.to_string(&p.date)?;
 
-				let p = ParamsBasicTypeWithDate::new(name, network, date);
+				let p =
+					ParamsBasicTypeWithDate::new(name, network, date, registry).map_err(map_err)?;
 				Ok(Params::ParamsBasicTypeWithDate(p))
 			},
 			AchainableParams::Token(p) => {
@@ -328,7 +367,8 @@ impl TryFrom<AchainableParams> for Params {
 				let network = &p.chain;
 				let token = ap.to_string(&p.token)?;
 
-				let p = ParamsBasicTypeWithToken::new(name, network, token);
+				let p = ParamsBasicTypeWithToken::new(name, network, token, registry)
+					.map_err(map_err)?;
 				Ok(Params::ParamsBasicTypeWithToken(p))
 			},
 		}
@@ -351,15 +391,21 @@ pub struct ParamsBasicTypeWithAmountHolding {
 }
 
 impl ParamsBasicTypeWithAmountHolding {
-	pub fn new(network: &Web3Network, amount: String, date: String, token: Option<String>) -> Self {
-		let chain = web3_network_to_chain(network);
+	pub fn new(
+		network: &Web3Network,
+		amount: String,
+		date: String,
+		token: Option<String>,
+		registry: &Web3NetworkRegistry,
+	) -> Result<Self, Error> {
+		let chain = web3_network_to_chain(network, registry)?;
 		let name = if token.is_some() {
 			"ERC20 hodling {amount} of {token} since {date}".into()
 		} else {
 			"Balance hodling {amount} since {date}".into()
 		};
 
-		Self { name, chain, amount, date, token }
+		Ok(Self { name, chain, amount, date, token })
 	}
 
 	pub fn one(
@@ -368,9 +414,10 @@ impl ParamsBasicTypeWithAmountHolding {
 		amount: String,
 		date: String,
 		token: Option<String>,
-	) -> Self {
-		let chain = web3_network_to_chain(network);
-		Self { name, chain, amount, date, token }
+		registry: &Web3NetworkRegistry,
+	) -> Result<Self, Error> {
+		let chain = web3_network_to_chain(network, registry)?;
+		Ok(Self { name, chain, amount, date, token })
 	}
 }
 
@@ -388,9 +435,15 @@ pub struct ParamsBasicTypeWithClassOfYear {
 }
 
 impl ParamsBasicTypeWithClassOfYear {
-	pub fn new(name: String, network: &Web3Network, date1: String, date2: String) -> Self {
-		let chain = web3_network_to_chain(network);
-		Self { name, chain, date1, date2 }
+	pub fn new(
+		name: String,
+		network: &Web3Network,
+		date1: String,
+		date2: String,
+		registry: &Web3NetworkRegistry,
+	) -> Result<Self, Error> {
+		let chain = web3_network_to_chain(network, registry)?;
+		Ok(Self { name, chain, date1, date2 })
 	}
 }
 
@@ -450,9 +503,13 @@ pub struct ParamsBasicType {
 }
 
 impl ParamsBasicType {
-	pub fn new(name: String, network: &Web3Network) -> Self {
-		let chain = web3_network_to_chain(network);
-		Self { name, chain }
+	pub fn new(
+		name: String,
+		network: &Web3Network,
+		registry: &Web3NetworkRegistry,
+	) -> Result<Self, Error> {
+		let chain = web3_network_to_chain(network, registry)?;
+		Ok(Self { name, chain })
 	}
 }
 
@@ -468,10 +525,15 @@ pub struct ParamsBasicTypeWithAmount {
 }
 
 impl ParamsBasicTypeWithAmount {
-	pub fn new(name: String, network: &Web3Network, amount: String) -> Self {
-		let chain = web3_network_to_chain(network);
+	pub fn new(
+		name: String,
+		network: &Web3Network,
+		amount: String,
+		registry: &Web3NetworkRegistry,
+	) -> Result<Self, Error> {
+		let chain = web3_network_to_chain(network, registry)?;
 
-		Self { name, chain, amount }
+		Ok(Self { name, chain, amount })
 	}
 }
 
@@ -487,9 +549,14 @@ pub struct ParamsBasicTypeWithDate {
 }
 
 impl ParamsBasicTypeWithDate {
-	pub fn new(name: String, network: &Web3Network, date: String) -> Self {
-		let chain = web3_network_to_chain(network);
-		Self { name, chain, date }
+	pub fn new(
+		name: String,
+		network: &Web3Network,
+		date: String,
+		registry: &Web3NetworkRegistry,
+	) -> Result<Self, Error> {
+		let chain = web3_network_to_chain(network, registry)?;
+		Ok(Self { name, chain, date })
 	}
 }
 
@@ -506,9 +573,15 @@ pub struct ParamsBasicTypeWithAmounts {
 }
 
 impl ParamsBasicTypeWithAmounts {
-	pub fn new(name: String, network: &Web3Network, amount1: String, amount2: String) -> Self {
-		let chain = web3_network_to_chain(network);
-		Self { name, chain, amount1, amount2 }
+	pub fn new(
+		name: String,
+		network: &Web3Network,
+		amount1: String,
+		amount2: String,
+		registry: &Web3NetworkRegistry,
+	) -> Result<Self, Error> {
+		let chain = web3_network_to_chain(network, registry)?;
+		Ok(Self { name, chain, amount1, amount2 })
 	}
 }
 
@@ -525,9 +598,15 @@ pub struct ParamsBasicTypeWithAmountToken {
 }
 
 impl ParamsBasicTypeWithAmountToken {
-	pub fn new(name: String, network: &Web3Network, amount: String, token: Option<String>) -> Self {
-		let chain = web3_network_to_chain(network);
-		Self { name, chain, amount, token }
+	pub fn new(
+		name: String,
+		network: &Web3Network,
+		amount: String,
+		token: Option<String>,
+		registry: &Web3NetworkRegistry,
+	) -> Result<Self, Error> {
+		let chain = web3_network_to_chain(network, registry)?;
+		Ok(Self { name, chain, amount, token })
 	}
 }
 
@@ -550,9 +629,10 @@ impl ParamsBasicTypeWithBetweenPercents {
 		network: &Web3Network,
 		greater_than_or_equal_to: String,
 		less_than_or_equal_to: String,
-	) -> Self {
-		let chain = web3_network_to_chain(network);
-		Self { name, chain, greater_than_or_equal_to, less_than_or_equal_to }
+		registry: &Web3NetworkRegistry,
+	) -> Result<Self, Error> {
+		let chain = web3_network_to_chain(network, registry)?;
+		Ok(Self { name, chain, greater_than_or_equal_to, less_than_or_equal_to })
 	}
 }
 
@@ -570,9 +650,15 @@ pub struct ParamsBasicTypeWithDateInterval {
 }
 
 impl ParamsBasicTypeWithDateInterval {
-	pub fn new(name: String, network: &Web3Network, start_date: String, end_date: String) -> Self {
-		let chain = web3_network_to_chain(network);
-		Self { name, chain, start_date, end_date }
+	pub fn new(
+		name: String,
+		network: &Web3Network,
+		start_date: String,
+		end_date: String,
+		registry: &Web3NetworkRegistry,
+	) -> Result<Self, Error> {
+		let chain = web3_network_to_chain(network, registry)?;
+		Ok(Self { name, chain, start_date, end_date })
 	}
 }
 
@@ -589,9 +675,14 @@ pub struct ParamsBasicTypeWithToken {
 }
 
 impl ParamsBasicTypeWithToken {
-	pub fn new(name: String, network: &Web3Network, token: String) -> Self {
-		let chain = web3_network_to_chain(network);
-		Self { name, chain, token }
+	pub fn new(
+		name: String,
+		network: &Web3Network,
+		token: String,
+		registry: &Web3NetworkRegistry,
+	) -> Result<Self, Error> {
+		let chain = web3_network_to_chain(network, registry)?;
+		Ok(Self { name, chain, token })
 	}
 }
 
@@ -616,9 +707,10 @@ impl ParamsBasicTypeWithDatePercent {
 		token: String,
 		date: String,
 		percent: String,
-	) -> Self {
-		let chain = web3_network_to_chain(network);
-		Self { name, chain, token, date, percent }
+		registry: &Web3NetworkRegistry,
+	) -> Result<Self, Error> {
+		let chain = web3_network_to_chain(network, registry)?;
+		Ok(Self { name, chain, token, date, percent })
 	}
 }
 
@@ -734,13 +826,14 @@ impl AchainableAccountTotalTransactions for AchainableClient {
 		network: &Web3Network,
 		addresses: &[String],
 	) -> Result<u64, Error> {
+		let name = "Account total transactions under {amount}".to_string();
+		let amount = "1".to_string();
+		let param = ParamsBasicTypeWithAmount::new(name, network, amount, &self.registry)?;
+
 		let mut txs = 0_u64;
 		addresses.iter().for_each(|address| {
-			let name = "Account total transactions under {amount}".to_string();
-			let amount = "1".to_string();
-
-			let param = ParamsBasicTypeWithAmount::new(name, network, amount);
-			let body = ReqBody::new(address.into(), Params::ParamsBasicTypeWithAmount(param));
+			let body =
+				ReqBody::new(address.into(), Params::ParamsBasicTypeWithAmount(param.clone()));
 			let tx = self.post(SystemLabelReqPath::default(), &body).and_then(Self::parse_txs);
 			txs += tx.unwrap_or_default();
 		});
@@ -828,7 +921,7 @@ impl AchainableTagAccount for AchainableClient {
 		let name = "Account created after {date}".to_string();
 		let chain = Web3Network::Ethereum;
 		let date = "30D".to_string();
-		let param = ParamsBasicTypeWithDate::new(name, &chain, date);
+		let param = ParamsBasicTypeWithDate::new(name, &chain, date, &self.registry)?;
 		check_achainable_label(self, address, Params::ParamsBasicTypeWithDate(param))
 	}
 
@@ -836,7 +929,7 @@ impl AchainableTagAccount for AchainableClient {
 		let name = "Account created before {date}".to_string();
 		let chain = Web3Network::Ethereum;
 		let date = "2020-01-01T00:00:00.000Z".to_string();
-		let param = ParamsBasicTypeWithDate::new(name, &chain, date);
+		let param = ParamsBasicTypeWithDate::new(name, &chain, date, &self.registry)?;
 		check_achainable_label(self, address, Params::ParamsBasicTypeWithDate(param))
 	}
 
@@ -849,7 +942,11 @@ impl AchainableTagAccount for AchainableClient {
 	}
 
 	fn address_found_on_bsc(&mut self, address: &str) -> Result<bool, Error> {
-		let param = ParamsBasicType::new("Account found on {chain}".to_string(), &Web3Network::Bsc);
+		let param = ParamsBasicType::new(
+			"Account found on {chain}".to_string(),
+			&Web3Network::Bsc,
+			&self.registry,
+		)?;
 		check_achainable_label(self, address, Params::ParamsBasicType(param))
 	}
 
@@ -859,12 +956,14 @@ impl AchainableTagAccount for AchainableClient {
 	}
 
 	fn is_polkadot_validator(&mut self, address: &str) -> Result<bool, Error> {
-		let param = ParamsBasicType::new("Validator".to_string(), &Web3Network::Polkadot);
+		let param =
+			ParamsBasicType::new("Validator".to_string(), &Web3Network::Polkadot, &self.registry)?;
 		check_achainable_label(self, address, Params::ParamsBasicType(param))
 	}
 
 	fn is_kusama_validator(&mut self, address: &str) -> Result<bool, Error> {
-		let param = ParamsBasicType::new("Validator".to_string(), &Web3Network::Kusama);
+		let param =
+			ParamsBasicType::new("Validator".to_string(), &Web3Network::Kusama, &self.registry)?;
 		check_achainable_label(self, address, Params::ParamsBasicType(param))
 	}
 }
@@ -875,7 +974,7 @@ impl AchainableTagBalance for AchainableClient {
 		let chain = Web3Network::Polkadot;
 		let a1 = "0.01".to_string();
 		let a2 = "0.0999999999999999".to_string();
-		let param = ParamsBasicTypeWithBetweenPercents::new(name, &chain, a1, a2);
+		let param = ParamsBasicTypeWithBetweenPercents::new(name, &chain, a1, a2, &self.registry)?;
 		check_achainable_label(self, address, Params::ParamsBasicTypeWithBetweenPercents(param))
 	}
 
@@ -884,7 +983,7 @@ impl AchainableTagBalance for AchainableClient {
 		let chain = Web3Network::Kusama;
 		let a1 = "0.01".to_string();
 		let a2 = "0.0999999999999999".to_string();
-		let param = ParamsBasicTypeWithBetweenPercents::new(name, &chain, a1, a2);
+		let param = ParamsBasicTypeWithBetweenPercents::new(name, &chain, a1, a2, &self.registry)?;
 		check_achainable_label(self, address, Params::ParamsBasicTypeWithBetweenPercents(param))
 	}
 
@@ -893,7 +992,7 @@ impl AchainableTagBalance for AchainableClient {
 		let chain = Web3Network::Polkadot;
 		let a1 = "0.01".to_string();
 		let a2 = "100".to_string();
-		let param = ParamsBasicTypeWithBetweenPercents::new(name, &chain, a1, a2);
+		let param = ParamsBasicTypeWithBetweenPercents::new(name, &chain, a1, a2, &self.registry)?;
 		check_achainable_label(self, address, Params::ParamsBasicTypeWithBetweenPercents(param))
 	}
 
@@ -902,7 +1001,7 @@ impl AchainableTagBalance for AchainableClient {
 		let chain = Web3Network::Kusama;
 		let a1 = "0.01".to_string();
 		let a2 = "100".to_string();
-		let param = ParamsBasicTypeWithBetweenPercents::new(name, &chain, a1, a2);
+		let param = ParamsBasicTypeWithBetweenPercents::new(name, &chain, a1, a2, &self.registry)?;
 		check_achainable_label(self, address, Params::ParamsBasicTypeWithBetweenPercents(param))
 	}
 
@@ -910,7 +1009,7 @@ impl AchainableTagBalance for AchainableClient {
 		let name = "Balance under {amount}".to_string();
 		let chain = Web3Network::Ethereum;
 		let amount = "10".to_string();
-		let param = ParamsBasicTypeWithAmount::new(name, &chain, amount);
+		let param = ParamsBasicTypeWithAmount::new(name, &chain, amount, &self.registry)?;
 		check_achainable_label(self, address, Params::ParamsBasicTypeWithAmount(param))
 	}
 
@@ -918,7 +1017,7 @@ impl AchainableTagBalance for AchainableClient {
 		let name = "Balance under {amount}".to_string();
 		let chain = Web3Network::Litentry;
 		let amount = "10".to_string();
-		let param = ParamsBasicTypeWithAmount::new(name, &chain, amount);
+		let param = ParamsBasicTypeWithAmount::new(name, &chain, amount, &self.registry)?;
 		check_achainable_label(self, address, Params::ParamsBasicTypeWithAmount(param))
 	}
 
@@ -926,7 +1025,7 @@ impl AchainableTagBalance for AchainableClient {
 		let name = "Balance over {amount}".to_string();
 		let chain = Web3Network::Ethereum;
 		let amount = "100".to_string();
-		let param = ParamsBasicTypeWithAmount::new(name, &chain, amount);
+		let param = ParamsBasicTypeWithAmount::new(name, &chain, amount, &self.registry)?;
 		check_achainable_label(self, address, Params::ParamsBasicTypeWithAmount(param))
 	}
 
@@ -936,7 +1035,8 @@ impl AchainableTagBalance for AchainableClient {
 		let chain = Web3Network::Ethereum;
 		let amount1 = "10".to_string();
 		let amount2 = "100".to_string();
-		let param = ParamsBasicTypeWithAmounts::new(name, &chain, amount1, amount2);
+		let param =
+			ParamsBasicTypeWithAmounts::new(name, &chain, amount1, amount2, &self.registry)?;
 		check_achainable_label(self, address, Params::ParamsBasicTypeWithAmounts(param))
 	}
 
@@ -945,7 +1045,7 @@ impl AchainableTagBalance for AchainableClient {
 		let name = "Balance over {amount} dollars".to_string();
 		let chain = Web3Network::Ethereum;
 		let amount = "100".to_string();
-		let param = ParamsBasicTypeWithAmount::new(name, &chain, amount);
+		let param = ParamsBasicTypeWithAmount::new(name, &chain, amount, &self.registry)?;
 		check_achainable_label(self, address, Params::ParamsBasicTypeWithAmount(param))
 	}
 
@@ -954,7 +1054,7 @@ impl AchainableTagBalance for AchainableClient {
 		let name = "Balance over {amount}".to_string();
 		let chain = Web3Network::Ethereum;
 		let amount = "32".to_string();
-		let param = ParamsBasicTypeWithAmount::new(name, &chain, amount);
+		let param = ParamsBasicTypeWithAmount::new(name, &chain, amount, &self.registry)?;
 		check_achainable_label(self, address, Params::ParamsBasicTypeWithAmount(param))
 	}
 
@@ -969,7 +1069,8 @@ impl AchainableTagBalance for AchainableClient {
 			&chain,
 			amount,
 			Some(WETH_TOKEN_ADDRESS.to_string()),
-		);
+			&self.registry,
+		)?;
 		check_achainable_label(self, address, Params::ParamsBasicTypeWithAmountToken(param))
 	}
 
@@ -984,7 +1085,8 @@ impl AchainableTagBalance for AchainableClient {
 			&chain,
 			amount,
 			Some(LIT_TOKEN_ADDRESS.to_string()),
-		);
+			&self.registry,
+		)?;
 		check_achainable_label(self, address, Params::ParamsBasicTypeWithAmountToken(param))
 	}
 
@@ -995,7 +1097,8 @@ impl AchainableTagBalance for AchainableClient {
 			"10".to_string(),
 			"2023-01-01T00:00:00.000Z".to_string(),
 			None,
-		);
+			&self.registry,
+		)?;
 		check_achainable_label(self, address, Params::ParamsBasicTypeWithAmountHolding(param))
 	}
 
@@ -1005,7 +1108,8 @@ impl AchainableTagBalance for AchainableClient {
 			"10".to_string(),
 			"2022-01-01T00:00:00.000Z".to_string(),
 			Some(LIT_TOKEN_ADDRESS.to_string()),
-		);
+			&self.registry,
+		)?;
 		check_achainable_label(self, address, Params::ParamsBasicTypeWithAmountHolding(param))
 	}
 
@@ -1015,7 +1119,8 @@ impl AchainableTagBalance for AchainableClient {
 			"10".to_string(),
 			"2022-01-01T00:00:00.000Z".to_string(),
 			Some(LIT_TOKEN_ADDRESS.to_string()),
-		);
+			&self.registry,
+		)?;
 
 		check_achainable_label(self, address, Params::ParamsBasicTypeWithAmountHolding(param))
 	}
@@ -1023,94 +1128,149 @@ impl AchainableTagBalance for AchainableClient {
 
 impl AchainableTagDotsama for AchainableClient {
 	fn is_polkadot_treasury_proposal_beneficiary(&mut self, address: &str) -> Result<bool, Error> {
-		let param =
-			ParamsBasicType::new("TreasuryProposalBeneficiary".to_string(), &Web3Network::Polkadot);
+		let param = ParamsBasicType::new(
+			"TreasuryProposalBeneficiary".to_string(),
+			&Web3Network::Polkadot,
+			&self.registry,
+		)?;
 		check_achainable_label(self, address, Params::ParamsBasicType(param))
 	}
 
 	fn is_kusama_treasury_proposal_beneficiary(&mut self, address: &str) -> Result<bool, Error> {
-		let param =
-			ParamsBasicType::new("TreasuryProposalBeneficiary".to_string(), &Web3Network::Kusama);
+		let param = ParamsBasicType::new(
+			"TreasuryProposalBeneficiary".to_string(),
+			&Web3Network::Kusama,
+			&self.registry,
+		)?;
 		check_achainable_label(self, address, Params::ParamsBasicType(param))
 	}
 
 	fn is_polkadot_tip_finder(&mut self, address: &str) -> Result<bool, Error> {
-		let param = ParamsBasicType::new("TipFinder".to_string(), &Web3Network::Polkadot);
+		let param =
+			ParamsBasicType::new("TipFinder".to_string(), &Web3Network::Polkadot, &self.registry)?;
 		check_achainable_label(self, address, Params::ParamsBasicType(param))
 	}
 
 	fn is_kusama_tip_finder(&mut self, address: &str) -> Result<bool, Error> {
-		let param = ParamsBasicType::new("TipFinder".to_string(), &Web3Network::Kusama);
+		let param =
+			ParamsBasicType::new("TipFinder".to_string(), &Web3Network::Kusama, &self.registry)?;
 		check_achainable_label(self, address, Params::ParamsBasicType(param))
 	}
 
 	fn is_polkadot_tip_beneficiary(&mut self, address: &str) -> Result<bool, Error> {
-		let param = ParamsBasicType::new("TipBeneficiary".to_string(), &Web3Network::Polkadot);
+		let param = ParamsBasicType::new(
+			"TipBeneficiary".to_string(),
+			&Web3Network::Polkadot,
+			&self.registry,
+		)?;
 		check_achainable_label(self, address, Params::ParamsBasicType(param))
 	}
 
 	fn is_kusama_tip_beneficiary(&mut self, address: &str) -> Result<bool, Error> {
-		let param = ParamsBasicType::new("TipBeneficiary".to_string(), &Web3Network::Kusama);
+		let param = ParamsBasicType::new(
+			"TipBeneficiary".to_string(),
+			&Web3Network::Kusama,
+			&self.registry,
+		)?;
 		check_achainable_label(self, address, Params::ParamsBasicType(param))
 	}
 
 	fn is_polkadot_opengov_proposer(&mut self, address: &str) -> Result<bool, Error> {
-		let param = ParamsBasicType::new("OpenGovProposer".to_string(), &Web3Network::Polkadot);
+		let param = ParamsBasicType::new(
+			"OpenGovProposer".to_string(),
+			&Web3Network::Polkadot,
+			&self.registry,
+		)?;
 		check_achainable_label(self, address, Params::ParamsBasicType(param))
 	}
 
 	fn is_kusama_opengov_proposer(&mut self, address: &str) -> Result<bool, Error> {
-		let param = ParamsBasicType::new("OpenGovProposer".to_string(), &Web3Network::Kusama);
+		let param = ParamsBasicType::new(
+			"OpenGovProposer".to_string(),
+			&Web3Network::Kusama,
+			&self.registry,
+		)?;
 		check_achainable_label(self, address, Params::ParamsBasicType(param))
 	}
 
 	fn is_polkadot_fellowship_proposer(&mut self, address: &str) -> Result<bool, Error> {
-		let param = ParamsBasicType::new("FellowshipProposer".to_string(), &Web3Network::Polkadot);
+		let param = ParamsBasicType::new(
+			"FellowshipProposer".to_string(),
+			&Web3Network::Polkadot,
+			&self.registry,
+		)?;
 		check_achainable_label(self, address, Params::ParamsBasicType(param))
 	}
 
 	fn is_kusama_fellowship_proposer(&mut self, address: &str) -> Result<bool, Error> {
-		let param = ParamsBasicType::new("FellowshipProposer".to_string(), &Web3Network::Kusama);
+		let param = ParamsBasicType::new(
+			"FellowshipProposer".to_string(),
+			&Web3Network::Kusama,
+			&self.registry,
+		)?;
 		check_achainable_label(self, address, Params::ParamsBasicType(param))
 	}
 
 	fn is_polkadot_fellowship_member(&mut self, address: &str) -> Result<bool, Error> {
-		let param = ParamsBasicType::new("FellowshipMember".to_string(), &Web3Network::Polkadot);
+		let param = ParamsBasicType::new(
+			"FellowshipMember".to_string(),
+			&Web3Network::Polkadot,
+			&self.registry,
+		)?;
 		check_achainable_label(self, address, Params::ParamsBasicType(param))
 	}
 
 	fn is_kusama_fellowship_member(&mut self, address: &str) -> Result<bool, Error> {
-		let param = ParamsBasicType::new("FellowshipMember".to_string(), &Web3Network::Kusama);
+		let param = ParamsBasicType::new(
+			"FellowshipMember".to_string(),
+			&Web3Network::Kusama,
+			&self.registry,
+		)?;
 		check_achainable_label(self, address, Params::ParamsBasicType(param))
 	}
 
 	fn is_polkadot_ex_councilor(&mut self, address: &str) -> Result<bool, Error> {
-		let param = ParamsBasicType::new("ExCouncilor".to_string(), &Web3Network::Polkadot);
+		let param = ParamsBasicType::new(
+			"ExCouncilor".to_string(),
+			&Web3Network::Polkadot,
+			&self.registry,
+		)?;
 		check_achainable_label(self, address, Params::ParamsBasicType(param))
 	}
 
 	fn is_kusama_ex_councilor(&mut self, address: &str) -> Result<bool, Error> {
-		let param = ParamsBasicType::new("ExCouncilor".to_string(), &Web3Network::Kusama);
+		let param =
+			ParamsBasicType::new("ExCouncilor".to_string(), &Web3Network::Kusama, &self.registry)?;
 		check_achainable_label(self, address, Params::ParamsBasicType(param))
 	}
 
 	fn is_polkadot_councilor(&mut self, address: &str) -> Result<bool, Error> {
-		let param = ParamsBasicType::new("Councilor".to_string(), &Web3Network::Polkadot);
+		let param =
+			ParamsBasicType::new("Councilor".to_string(), &Web3Network::Polkadot, &self.registry)?;
 		check_achainable_label(self, address, Params::ParamsBasicType(param))
 	}
 
 	fn is_kusama_councilor(&mut self, address: &str) -> Result<bool, Error> {
-		let param = ParamsBasicType::new("Councilor".to_string(), &Web3Network::Kusama);
+		let param =
+			ParamsBasicType::new("Councilor".to_string(), &Web3Network::Kusama, &self.registry)?;
 		check_achainable_label(self, address, Params::ParamsBasicType(param))
 	}
 
 	fn is_polkadot_bounty_curator(&mut self, address: &str) -> Result<bool, Error> {
-		let param = ParamsBasicType::new("BountyCurator".to_string(), &Web3Network::Polkadot);
+		let param = ParamsBasicType::new(
+			"BountyCurator".to_string(),
+			&Web3Network::Polkadot,
+			&self.registry,
+		)?;
 		check_achainable_label(self, address, Params::ParamsBasicType(param))
 	}
 
 	fn is_kusama_bounty_curator(&mut self, address: &str) -> Result<bool, Error> {
-		let param = ParamsBasicType::new("BountyCurator".to_string(), &Web3Network::Kusama);
+		let param = ParamsBasicType::new(
+			"BountyCurator".to_string(),
+			&Web3Network::Kusama,
+			&self.registry,
+		)?;
 		check_achainable_label(self, address, Params::ParamsBasicType(param))
 	}
 }
@@ -1157,7 +1317,13 @@ impl AchainableTagDeFi for AchainableClient {
 		let start_date = "2022-01-01T00:00:00.000Z".to_string();
 		let end_date = "2022-12-31T23:59:59.999Z".to_string();
 
-		let param = ParamsBasicTypeWithDateInterval::new(name, &chain, start_date, end_date);
+		let param = ParamsBasicTypeWithDateInterval::new(
+			name,
+			&chain,
+			start_date,
+			end_date,
+			&self.registry,
+		)?;
 		check_achainable_label(self, address, Params::ParamsBasicTypeWithDateInterval(param))
 	}
 
@@ -1168,7 +1334,13 @@ impl AchainableTagDeFi for AchainableClient {
 		let start_date = "2022-01-01T00:00:00.000Z".to_string();
 		let end_date = "2022-12-31T23:59:59.999Z".to_string();
 
-		let param = ParamsBasicTypeWithDateInterval::new(name, &chain, start_date, end_date);
+		let param = ParamsBasicTypeWithDateInterval::new(
+			name,
+			&chain,
+			start_date,
+			end_date,
+			&self.registry,
+		)?;
 		check_achainable_label(self, address, Params::ParamsBasicTypeWithDateInterval(param))
 	}
 
@@ -1258,7 +1430,13 @@ impl AchainableTagDeFi for AchainableClient {
 		let start_date = "2022-01-01T00:00:00.000Z".to_string();
 		let end_date = "2022-12-31T23:59:59.999Z".to_string();
 
-		let param = ParamsBasicTypeWithDateInterval::new(name, &chain, start_date, end_date);
+		let param = ParamsBasicTypeWithDateInterval::new(
+			name,
+			&chain,
+			start_date,
+			end_date,
+			&self.registry,
+		)?;
 		check_achainable_label(self, address, Params::ParamsBasicTypeWithDateInterval(param))
 	}
 
@@ -1276,7 +1454,13 @@ impl AchainableTagDeFi for AchainableClient {
 		let start_date = "2022-01-01T00:00:00.000Z".to_string();
 		let end_date = "2022-12-31T23:59:59.999Z".to_string();
 
-		let param = ParamsBasicTypeWithDateInterval::new(name, &chain, start_date, end_date);
+		let param = ParamsBasicTypeWithDateInterval::new(
+			name,
+			&chain,
+			start_date,
+			end_date,
+			&self.registry,
+		)?;
 		check_achainable_label(self, address, Params::ParamsBasicTypeWithDateInterval(param))
 	}
 
@@ -1287,7 +1471,13 @@ impl AchainableTagDeFi for AchainableClient {
 		let start_date = "2022-01-01T00:00:00.000Z".to_string();
 		let end_date = "2022-12-31T23:59:59.999Z".to_string();
 
-		let param = ParamsBasicTypeWithDateInterval::new(name, &chain, start_date, end_date);
+		let param = ParamsBasicTypeWithDateInterval::new(
+			name,
+			&chain,
+			start_date,
+			end_date,
+			&self.registry,
+		)?;
 		check_achainable_label(self, address, Params::ParamsBasicTypeWithDateInterval(param))
 	}
 }
@@ -1300,28 +1490,79 @@ fn request_basic_type_with_token(
 	token: Option<&str>,
 ) -> Result<bool, Error> {
 	if let Some(token) = token {
-		let param = ParamsBasicTypeWithToken::new(name.to_string(), network, token.to_string());
+		let param = ParamsBasicTypeWithToken::new(
+			name.to_string(),
+			network,
+			token.to_string(),
+			&client.registry,
+		)?;
 		check_achainable_label(client, address, Params::ParamsBasicTypeWithToken(param))
 	} else {
-		let param = ParamsBasicType::new(name.to_string(), network);
+		let param = ParamsBasicType::new(name.to_string(), network, &client.registry)?;
 		check_achainable_label(client, address, Params::ParamsBasicType(param))
 	}
 }
 
 #[cfg(test)]
 mod tests {
-	use crate::achainable::{
-		AchainableAccountTotalTransactions, AchainableClient, AchainableTagAccount,
-		AchainableTagBalance, AchainableTagDeFi, AchainableTagDotsama, GLOBAL_DATA_PROVIDER_CONFIG,
+	use crate::{
+		achainable::{
+			web3_network_to_chain, AchainableAccountTotalTransactions, AchainableClient,
+			AchainableTagAccount, AchainableTagBalance, AchainableTagDeFi, AchainableTagDotsama,
+			Params,
+		},
+		GLOBAL_DATA_PROVIDER_CONFIG,
 	};
 	use lc_mock_server::{default_getter, run};
-	use litentry_primitives::Web3Network;
+	use litentry_primitives::{
+		AchainableBasic, AchainableParams, Web3Network, Web3NetworkFamily, Web3NetworkInfo,
+		Web3NetworkRegistry,
+	};
 	use std::sync::Arc;
 
 	fn init() {
 		let _ = env_logger::builder().is_test(true).try_init();
 		let url = run(Arc::new(default_getter), 0).unwrap();
-		GLOBAL_DATA_PROVIDER_CONFIG.write().unwrap().set_achainable_url(url);
+		GLOBAL_DATA_PROVIDER_CONFIG.write().unwrap().achainable.endpoints = vec![url];
+	}
+
+	#[test]
+	fn web3_network_to_chain_works() {
+		let mut registry = Web3NetworkRegistry::new();
+		assert_eq!(
+			web3_network_to_chain(&Web3Network::LitentryRococo, &registry).unwrap(),
+			"litentry_rococo"
+		);
+
+		let arbitrum = Web3NetworkInfo {
+			name: b"Arbitrum".to_vec().try_into().unwrap(),
+			family: Web3NetworkFamily::Evm { chain_id: 42161 },
+			providers: vec![(
+				b"achainable".to_vec().try_into().unwrap(),
+				b"arbitrum".to_vec().try_into().unwrap(),
+			)]
+			.try_into()
+			.unwrap(),
+		};
+		registry.try_insert(1, arbitrum).unwrap();
+		assert_eq!(
+			web3_network_to_chain(&Web3Network::Registered(1), &registry).unwrap(),
+			"arbitrum"
+		);
+		assert!(web3_network_to_chain(&Web3Network::Registered(2), &registry).is_err());
+
+		// the request of an assertion over a network achainable doesn't know fails
+		let basic = |chain| {
+			AchainableParams::Basic(AchainableBasic {
+				name: b"Account found on {chain}".to_vec().try_into().unwrap(),
+				chain,
+			})
+		};
+		match Params::new(basic(Web3Network::Registered(1)), &registry).unwrap() {
+			Params::ParamsBasicType(p) => assert_eq!(p.chain, "arbitrum"),
+			p => panic!("unexpected params {:?}", p),
+		}
+		assert!(Params::new(basic(Web3Network::Registered(2)), &registry).is_err());
 	}
 
 	#[test]
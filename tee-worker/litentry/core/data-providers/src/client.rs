@@ -0,0 +1,211 @@
+// Copyright 2020-2023 Litentry Technologies GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+#[cfg(all(not(feature = "std"), feature = "sgx"))]
+use crate::sgx_reexport_prelude::*;
+
+use crate::{
+	build_client_with_timeout, get_provider_config, DataProvider, Error, HttpError, RateLimit,
+	RetryPolicy,
+};
+use http_req::response::Headers;
+use itc_rest_client::{
+	http_client::{DefaultSend, HttpClient},
+	rest_client::RestClient,
+	Query, RestGet, RestPath, RestPost,
+};
+use itp_time_utils::now_as_secs;
+use lazy_static::lazy_static;
+use log::*;
+use std::{collections::BTreeMap, format, string::ToString, thread, vec::Vec};
+
+#[cfg(feature = "std")]
+use std::sync::Mutex;
+#[cfg(feature = "sgx")]
+use std::sync::SgxMutex as Mutex;
+
+lazy_static! {
+	// provider -> (start of the current window in secs, requests within the window)
+	static ref RATE_LIMIT_WINDOWS: Mutex<BTreeMap<DataProvider, (u64, u32)>> =
+		Mutex::new(BTreeMap::new());
+}
+
+/// REST client of a data provider that applies the settings of its `ProviderConfig`:
+/// the endpoints are tried in order, failed requests are retried with a backoff and the
+/// requests are rate limited per provider.
+pub struct DataProviderClient {
+	provider: DataProvider,
+	clients: Vec<RestClient<HttpClient<DefaultSend>>>,
+	retry: RetryPolicy,
+	rate_limit: Option<RateLimit>,
+}
+
+impl DataProviderClient {
+	/// Creates a client with the current configuration of `provider`, the auth header
+	/// is added to `headers`.
+	///
+	/// Unavailable providers don't fail here, but each request does.
+	pub fn new(provider: DataProvider, mut headers: Headers) -> Self {
+		let config = match get_provider_config(provider) {
+			Ok(config) => config,
+			Err(e) => {
+				error!("Failed to get config of {}: {:?}", provider.as_str(), e);
+				return Self::unavailable(provider)
+			},
+		};
+		config.auth.apply(&mut headers);
+
+		let clients = config
+			.endpoints
+			.iter()
+			.filter_map(|endpoint| {
+				build_client_with_timeout(endpoint, headers.clone(), config.timeout())
+					.map_err(|e| error!("Skip endpoint of {}: {:?}", provider.as_str(), e))
+					.ok()
+			})
+			.collect();
+
+		Self { provider, clients, retry: config.retry, rate_limit: config.rate_limit }
+	}
+
+	fn unavailable(provider: DataProvider) -> Self {
+		Self { provider, clients: Vec::new(), retry: RetryPolicy::no_retry(), rate_limit: None }
+	}
+
+	pub fn get<U, T>(&mut self, params: U) -> Result<T, Error>
+	where
+		U: Clone,
+		T: serde::de::DeserializeOwned + RestPath<U>,
+	{
+		self.execute(|client| client.get::<U, T>(params.clone()))
+	}
+
+	pub fn get_with<U, T>(&mut self, params: U, query: &Query<'_>) -> Result<T, Error>
+	where
+		U: Clone,
+		T: serde::de::DeserializeOwned + RestPath<U>,
+	{
+		self.execute(|client| client.get_with::<U, T>(params.clone(), query))
+	}
+
+	pub fn post_capture<U, T, K>(&mut self, params: U, data: &T) -> Result<K, Error>
+	where
+		U: Clone,
+		T: serde::Serialize + RestPath<U>,
+		K: serde::de::DeserializeOwned,
+	{
+		self.execute(|client| client.post_capture::<U, T, K>(params.clone(), data))
+	}
+
+	fn execute<R>(
+		&mut self,
+		mut request: impl FnMut(&mut RestClient<HttpClient<DefaultSend>>) -> Result<R, HttpError>,
+	) -> Result<R, Error> {
+		if self.clients.is_empty() {
+			return Err(Error::ProviderNotConfigured(self.provider.as_str().to_string()))
+		}
+
+		let mut last_error = None;
+		for retry in 0..=self.retry.max_retries {
+			if retry > 0 {
+				thread::sleep(self.retry.backoff(retry - 1));
+			}
+			for (i, client) in self.clients.iter_mut().enumerate() {
+				check_rate_limit(self.provider, self.rate_limit.as_ref())?;
+				match request(client) {
+					Ok(response) => return Ok(response),
+					Err(e) if is_retryable(&e) => {
+						warn!(
+							"Request to {} (endpoint {}) failed, retry {}: {:?}",
+							self.provider.as_str(),
+							i,
+							retry,
+							e
+						);
+						last_error = Some(e);
+					},
+					Err(e) => return Err(Error::RequestError(format!("{:?}", e))),
+				}
+			}
+		}
+		Err(Error::RequestError(format!("{:?}", last_error)))
+	}
+}
+
+// Errors that might go away when the request is sent again or to another endpoint.
+fn is_retryable(e: &HttpError) -> bool {
+	match e {
+		HttpError::HttpError(status, _) => *status == 429 || *status >= 500,
+		HttpError::RequestError
+		| HttpError::TimeoutError
+		| HttpError::HttpReqError(_)
+		| HttpError::IoError(_) => true,
+		_ => false,
+	}
+}
+
+fn check_rate_limit(provider: DataProvider, rate_limit: Option<&RateLimit>) -> Result<(), Error> {
+	let rate_limit = match rate_limit {
+		Some(rate_limit) => rate_limit,
+		None => return Ok(()),
+	};
+	let now = now_as_secs();
+	let mut windows = RATE_LIMIT_WINDOWS.lock().map_err(|_| Error::PoisonLock)?;
+	let (window_start, count) = windows.entry(provider).or_insert((now, 0));
+	if now.saturating_sub(*window_start) >= rate_limit.period_secs {
+		*window_start = now;
+		*count = 0;
+	}
+	if *count >= rate_limit.max_requests {
+		return Err(Error::RateLimited(provider.as_str().to_string()))
+	}
+	*count += 1;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn check_rate_limit_works() {
+		// the windows are global, so use a provider that is not used by other tests
+		let rate_limit = RateLimit { max_requests: 2, period_secs: 3600 };
+		assert!(check_rate_limit(DataProvider::TwitterLitentry, Some(&rate_limit)).is_ok());
+		assert!(check_rate_limit(DataProvider::TwitterLitentry, Some(&rate_limit)).is_ok());
+		assert!(matches!(
+			check_rate_limit(DataProvider::TwitterLitentry, Some(&rate_limit)),
+			Err(Error::RateLimited(_))
+		));
+		assert!(check_rate_limit(DataProvider::TwitterLitentry, None).is_ok());
+	}
+
+	#[test]
+	fn is_retryable_works() {
+		assert!(is_retryable(&HttpError::HttpError(503, "".into())));
+		assert!(is_retryable(&HttpError::HttpError(429, "".into())));
+		assert!(is_retryable(&HttpError::TimeoutError));
+		assert!(!is_retryable(&HttpError::HttpError(404, "".into())));
+		assert!(!is_retryable(&HttpError::UrlError));
+	}
+
+	#[test]
+	fn unavailable_provider_fails() {
+		let mut client = DataProviderClient::unavailable(DataProvider::TwitterLitentry);
+		let result: Result<(), Error> = client.execute(|_| Ok(()));
+		assert!(matches!(result, Err(Error::ProviderNotConfigured(_))));
+	}
+}
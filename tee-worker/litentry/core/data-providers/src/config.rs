@@ -0,0 +1,532 @@
+// Copyright 2020-2023 Litentry Technologies GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+#[cfg(all(not(feature = "std"), feature = "sgx"))]
+use crate::sgx_reexport_prelude::*;
+
+use crate::Error;
+use codec::{Decode, Encode};
+use core::time::Duration;
+use http::header::AUTHORIZATION;
+use http_req::response::Headers;
+use itp_types::ShardIdentifier;
+use litentry_primitives::{Identity, LitentryMultiSignature};
+use serde::{Deserialize, Serialize};
+use std::{
+	format,
+	string::{String, ToString},
+	vec,
+	vec::Vec,
+};
+use url::Url;
+
+pub const DEFAULT_TIMEOUT_MS: u64 = 3_000;
+pub const MAX_TIMEOUT_MS: u64 = 60_000;
+pub const MAX_RETRIES: u32 = 5;
+
+/// Context of the payload that is signed to update the configuration, see
+/// `SignedDataProviderConfig`.
+pub const DATA_PROVIDER_CONFIG_CONTEXT: &[u8] = b"litentry/data-provider-config";
+
+#[derive(
+	Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Encode, Decode, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum DataProvider {
+	TwitterOfficial,
+	TwitterLitentry,
+	DiscordOfficial,
+	DiscordLitentry,
+	GithubOfficial,
+	Achainable,
+	LitentryArchive,
+	LitmusArchive,
+	PolkadotArchive,
+	EthereumRpc,
+	BscRpc,
+}
+
+impl DataProvider {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::TwitterOfficial => "twitter_official",
+			Self::TwitterLitentry => "twitter_litentry",
+			Self::DiscordOfficial => "discord_official",
+			Self::DiscordLitentry => "discord_litentry",
+			Self::GithubOfficial => "github_official",
+			Self::Achainable => "achainable",
+			Self::LitentryArchive => "litentry_archive",
+			Self::LitmusArchive => "litmus_archive",
+			Self::PolkadotArchive => "polkadot_archive",
+			Self::EthereumRpc => "ethereum_rpc",
+			Self::BscRpc => "bsc_rpc",
+		}
+	}
+
+	pub fn all() -> [DataProvider; 11] {
+		[
+			Self::TwitterOfficial,
+			Self::TwitterLitentry,
+			Self::DiscordOfficial,
+			Self::DiscordLitentry,
+			Self::GithubOfficial,
+			Self::Achainable,
+			Self::LitentryArchive,
+			Self::LitmusArchive,
+			Self::PolkadotArchive,
+			Self::EthereumRpc,
+			Self::BscRpc,
+		]
+	}
+
+	pub fn is_archive(&self) -> bool {
+		matches!(self, Self::LitentryArchive | Self::LitmusArchive | Self::PolkadotArchive)
+	}
+}
+
+/// Where the data of an assertion comes from.
+#[derive(
+	Copy,
+	Clone,
+	Debug,
+	Default,
+	PartialEq,
+	Eq,
+	PartialOrd,
+	Ord,
+	Encode,
+	Decode,
+	Serialize,
+	Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum AssertionSource {
+	#[default]
+	Achainable,
+	/// Read from the chains directly: the storage of the parentchain through its `*_archive`
+	/// provider, see `crate::native`, and the EVM chains through the `*_rpc` providers, see
+	/// `crate::evm`.
+	Native,
+}
+
+/// The assertions that can be built from either source.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+pub struct AssertionSources {
+	#[serde(default)]
+	pub a4: AssertionSource,
+	#[serde(default)]
+	pub a7: AssertionSource,
+	#[serde(default)]
+	pub a10: AssertionSource,
+	#[serde(default)]
+	pub a11: AssertionSource,
+	#[serde(default)]
+	pub a14: AssertionSource,
+}
+
+/// How requests to a data provider are authenticated.
+///
+/// Doesn't implement `Debug` on purpose, so that the secrets don't end up in the logs.
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProviderAuth {
+	#[default]
+	None,
+	/// `Authorization: Bearer <token>`
+	Bearer(String),
+	/// `Authorization: <key>`, the key is sent as-is
+	ApiKey(String),
+}
+
+impl ProviderAuth {
+	pub fn apply(&self, headers: &mut Headers) {
+		match self {
+			Self::None => {},
+			Self::Bearer(token) =>
+				headers.insert(AUTHORIZATION.as_str(), format!("Bearer {}", token).as_str()),
+			Self::ApiKey(key) => headers.insert(AUTHORIZATION.as_str(), key.as_str()),
+		}
+	}
+
+	fn validate(&self) -> Result<(), String> {
+		match self {
+			Self::Bearer(secret) | Self::ApiKey(secret) if secret.trim().is_empty() =>
+				Err("empty auth secret, use `none` instead".to_string()),
+			_ => Ok(()),
+		}
+	}
+}
+
+/// Retries with an exponential backoff, starting at `initial_backoff_ms` and capped at
+/// `max_backoff_ms`. A retry round goes through all endpoints of the provider.
+#[derive(PartialEq, Eq, Clone, Debug, Encode, Decode, Serialize, Deserialize)]
+pub struct RetryPolicy {
+	pub max_retries: u32,
+	pub initial_backoff_ms: u64,
+	pub max_backoff_ms: u64,
+}
+
+impl Default for RetryPolicy {
+	fn default() -> Self {
+		Self { max_retries: 2, initial_backoff_ms: 200, max_backoff_ms: 2_000 }
+	}
+}
+
+impl RetryPolicy {
+	pub fn no_retry() -> Self {
+		Self { max_retries: 0, initial_backoff_ms: 0, max_backoff_ms: 0 }
+	}
+
+	/// The backoff before the retry `retry` (starting at 0).
+	pub fn backoff(&self, retry: u32) -> Duration {
+		let factor = 1u64.checked_shl(retry).unwrap_or(u64::MAX);
+		Duration::from_millis(
+			self.initial_backoff_ms.saturating_mul(factor).min(self.max_backoff_ms),
+		)
+	}
+}
+
+/// At most `max_requests` requests within `period_secs` seconds.
+#[derive(PartialEq, Eq, Clone, Debug, Encode, Decode, Serialize, Deserialize)]
+pub struct RateLimit {
+	pub max_requests: u32,
+	pub period_secs: u64,
+}
+
+#[derive(PartialEq, Eq, Clone, Encode, Decode, Serialize, Deserialize)]
+pub struct ProviderConfig {
+	/// Base URLs of the provider, tried in this order until one succeeds.
+	/// The provider is disabled if the list is empty.
+	pub endpoints: Vec<String>,
+	#[serde(default = "default_timeout_ms")]
+	pub timeout_ms: u64,
+	#[serde(default)]
+	pub retry: RetryPolicy,
+	#[serde(default)]
+	pub rate_limit: Option<RateLimit>,
+	#[serde(default)]
+	pub auth: ProviderAuth,
+}
+
+fn default_timeout_ms() -> u64 {
+	DEFAULT_TIMEOUT_MS
+}
+
+impl Default for ProviderConfig {
+	fn default() -> Self {
+		Self {
+			endpoints: vec![],
+			timeout_ms: DEFAULT_TIMEOUT_MS,
+			retry: Default::default(),
+			rate_limit: None,
+			auth: ProviderAuth::None,
+		}
+	}
+}
+
+impl ProviderConfig {
+	pub fn new(endpoint: &str) -> Self {
+		Self { endpoints: vec![endpoint.to_string()], ..Default::default() }
+	}
+
+	pub fn with_auth(mut self, auth: ProviderAuth) -> Self {
+		self.auth = auth;
+		self
+	}
+
+	pub fn timeout(&self) -> Duration {
+		Duration::from_millis(self.timeout_ms)
+	}
+
+	pub fn validate(&self, provider: DataProvider) -> Result<(), Error> {
+		let invalid =
+			|reason: String| Error::InvalidConfig(format!("{}: {}", provider.as_str(), reason));
+
+		for endpoint in self.endpoints.iter() {
+			let url = Url::parse(endpoint)
+				.map_err(|e| invalid(format!("invalid endpoint {}: {}", endpoint, e)))?;
+			if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+				return Err(invalid(format!("invalid endpoint {}", endpoint)))
+			}
+		}
+		if self.timeout_ms == 0 || self.timeout_ms > MAX_TIMEOUT_MS {
+			return Err(invalid(format!("timeout must be within 1..={}ms", MAX_TIMEOUT_MS)))
+		}
+		if self.retry.max_retries > MAX_RETRIES {
+			return Err(invalid(format!("at most {} retries are allowed", MAX_RETRIES)))
+		}
+		if self.retry.initial_backoff_ms > self.retry.max_backoff_ms {
+			return Err(invalid("initial backoff exceeds max backoff".to_string()))
+		}
+		if let Some(rate_limit) = &self.rate_limit {
+			if rate_limit.max_requests == 0 || rate_limit.period_secs == 0 {
+				return Err(invalid("empty rate limit".to_string()))
+			}
+		}
+		self.auth.validate().map_err(invalid)
+	}
+}
+
+/// Registry of the data providers used to verify identities and build assertions.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, Serialize, Deserialize)]
+pub struct DataProviderConfig {
+	/// Increased with every update, an update must have a higher version than the current
+	/// configuration.
+	#[serde(default)]
+	pub version: u64,
+	pub twitter_official: ProviderConfig,
+	#[serde(default)]
+	pub twitter_litentry: ProviderConfig,
+	pub discord_official: ProviderConfig,
+	#[serde(default)]
+	pub discord_litentry: ProviderConfig,
+	pub github_official: ProviderConfig,
+	pub achainable: ProviderConfig,
+	/// Archive nodes (JSON-RPC) of the chains that are read by the native assertion source.
+	#[serde(default)]
+	pub litentry_archive: ProviderConfig,
+	#[serde(default)]
+	pub litmus_archive: ProviderConfig,
+	#[serde(default)]
+	pub polkadot_archive: ProviderConfig,
+	/// JSON-RPC nodes of the EVM chains that are read by the native assertion source.
+	#[serde(default)]
+	pub ethereum_rpc: ProviderConfig,
+	#[serde(default)]
+	pub bsc_rpc: ProviderConfig,
+	/// The archive of the parentchain, its reads are anchored at the block the light client
+	/// imported last instead of the finalized head reported by the node.
+	#[serde(default)]
+	pub parentchain_archive: Option<DataProvider>,
+	#[serde(default)]
+	pub assertion_sources: AssertionSources,
+	#[serde(default)]
+	pub credential_endpoint: String,
+}
+
+impl Default for DataProviderConfig {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl DataProviderConfig {
+	pub fn new() -> Self {
+		DataProviderConfig {
+			version: 0,
+			twitter_official: ProviderConfig::new("https://api.twitter.com"),
+			twitter_litentry: ProviderConfig::default(),
+			discord_official: ProviderConfig::new("https://discordapp.com"),
+			discord_litentry: ProviderConfig::default(),
+			github_official: ProviderConfig::new("https://api.github.com"),
+			achainable: ProviderConfig::new("https://graph.tdf-labs.io/"),
+			litentry_archive: ProviderConfig::default(),
+			litmus_archive: ProviderConfig::default(),
+			polkadot_archive: ProviderConfig::default(),
+			ethereum_rpc: ProviderConfig::default(),
+			bsc_rpc: ProviderConfig::default(),
+			parentchain_archive: None,
+			assertion_sources: AssertionSources::default(),
+			credential_endpoint: "".to_string(),
+		}
+	}
+
+	pub fn provider(&self, provider: DataProvider) -> &ProviderConfig {
+		match provider {
+			DataProvider::TwitterOfficial => &self.twitter_official,
+			DataProvider::TwitterLitentry => &self.twitter_litentry,
+			DataProvider::DiscordOfficial => &self.discord_official,
+			DataProvider::DiscordLitentry => &self.discord_litentry,
+			DataProvider::GithubOfficial => &self.github_official,
+			DataProvider::Achainable => &self.achainable,
+			DataProvider::LitentryArchive => &self.litentry_archive,
+			DataProvider::LitmusArchive => &self.litmus_archive,
+			DataProvider::PolkadotArchive => &self.polkadot_archive,
+			DataProvider::EthereumRpc => &self.ethereum_rpc,
+			DataProvider::BscRpc => &self.bsc_rpc,
+		}
+	}
+
+	pub fn provider_mut(&mut self, provider: DataProvider) -> &mut ProviderConfig {
+		match provider {
+			DataProvider::TwitterOfficial => &mut self.twitter_official,
+			DataProvider::TwitterLitentry => &mut self.twitter_litentry,
+			DataProvider::DiscordOfficial => &mut self.discord_official,
+			DataProvider::DiscordLitentry => &mut self.discord_litentry,
+			DataProvider::GithubOfficial => &mut self.github_official,
+			DataProvider::Achainable => &mut self.achainable,
+			DataProvider::LitentryArchive => &mut self.litentry_archive,
+			DataProvider::LitmusArchive => &mut self.litmus_archive,
+			DataProvider::PolkadotArchive => &mut self.polkadot_archive,
+			DataProvider::EthereumRpc => &mut self.ethereum_rpc,
+			DataProvider::BscRpc => &mut self.bsc_rpc,
+		}
+	}
+
+	pub fn validate(&self) -> Result<(), Error> {
+		DataProvider::all()
+			.into_iter()
+			.try_for_each(|provider| self.provider(provider).validate(provider))?;
+		// A7 and A14 read polkadot, which has no light client in the enclave
+		if self.assertion_sources.a7 == AssertionSource::Native
+			|| self.assertion_sources.a14 == AssertionSource::Native
+		{
+			return Err(Error::InvalidConfig(
+				"assertion sources: a7 and a14 can't be read natively".into(),
+			))
+		}
+		match self.parentchain_archive {
+			Some(provider) if !provider.is_archive() => Err(Error::InvalidConfig(format!(
+				"parentchain archive: {} is not an archive",
+				provider.as_str()
+			))),
+			_ => Ok(()),
+		}
+	}
+}
+
+/// An update of the configuration, signed by the root account of the shard.
+///
+/// It's sent encrypted with the shielding key as it contains the secrets of the providers.
+/// The shard is part of the signed payload, so an update can't be replayed to another shard
+/// with the same root account.
+#[derive(Clone, Encode, Decode)]
+pub struct SignedDataProviderConfig {
+	pub shard: ShardIdentifier,
+	pub config: DataProviderConfig,
+	pub signer: Identity,
+	pub signature: LitentryMultiSignature,
+}
+
+impl SignedDataProviderConfig {
+	pub fn signature_payload(shard: &ShardIdentifier, config: &DataProviderConfig) -> Vec<u8> {
+		(DATA_PROVIDER_CONFIG_CONTEXT, shard, config).encode()
+	}
+
+	pub fn verify_signature(&self) -> bool {
+		self.signature
+			.verify(&Self::signature_payload(&self.shard, &self.config), &self.signer)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn default_config_is_valid() {
+		assert!(DataProviderConfig::new().validate().is_ok());
+	}
+
+	#[test]
+	fn validate_rejects_invalid_provider_config() {
+		let mut config = DataProviderConfig::new();
+		config.achainable.endpoints.push("not a url".to_string());
+		assert!(matches!(config.validate(), Err(Error::InvalidConfig(_))));
+
+		let mut config = DataProviderConfig::new();
+		config.github_official.endpoints = vec!["ftp://api.github.com".to_string()];
+		assert!(matches!(config.validate(), Err(Error::InvalidConfig(_))));
+
+		let mut config = DataProviderConfig::new();
+		config.twitter_official.timeout_ms = 0;
+		assert!(matches!(config.validate(), Err(Error::InvalidConfig(_))));
+
+		let mut config = DataProviderConfig::new();
+		config.discord_official.retry.max_retries = MAX_RETRIES + 1;
+		assert!(matches!(config.validate(), Err(Error::InvalidConfig(_))));
+
+		let mut config = DataProviderConfig::new();
+		config.discord_official.rate_limit = Some(RateLimit { max_requests: 0, period_secs: 1 });
+		assert!(matches!(config.validate(), Err(Error::InvalidConfig(_))));
+
+		let mut config = DataProviderConfig::new();
+		config.achainable.auth = ProviderAuth::ApiKey("".to_string());
+		assert!(matches!(config.validate(), Err(Error::InvalidConfig(_))));
+
+		let mut config = DataProviderConfig::new();
+		config.parentchain_archive = Some(DataProvider::Achainable);
+		assert!(matches!(config.validate(), Err(Error::InvalidConfig(_))));
+
+		let mut config = DataProviderConfig::new();
+		config.assertion_sources.a14 = AssertionSource::Native;
+		assert!(matches!(config.validate(), Err(Error::InvalidConfig(_))));
+	}
+
+	#[test]
+	fn signature_is_bound_to_shard() {
+		use sp_core::{sr25519, Pair};
+
+		let pair = sr25519::Pair::from_seed(&[1u8; 32]);
+		let shard = ShardIdentifier::repeat_byte(1);
+		let config = DataProviderConfig::new();
+		let signature = pair.sign(&SignedDataProviderConfig::signature_payload(&shard, &config));
+		let mut signed_config = SignedDataProviderConfig {
+			shard,
+			config,
+			signer: Identity::Substrate(pair.public().into()),
+			signature: LitentryMultiSignature::Sr25519(signature),
+		};
+		assert!(signed_config.verify_signature());
+
+		signed_config.shard = ShardIdentifier::repeat_byte(2);
+		assert!(!signed_config.verify_signature());
+	}
+
+	#[test]
+	fn backoff_is_exponential_and_capped() {
+		let retry = RetryPolicy { max_retries: 5, initial_backoff_ms: 100, max_backoff_ms: 500 };
+		assert_eq!(retry.backoff(0), Duration::from_millis(100));
+		assert_eq!(retry.backoff(1), Duration::from_millis(200));
+		assert_eq!(retry.backoff(2), Duration::from_millis(400));
+		assert_eq!(retry.backoff(3), Duration::from_millis(500));
+		assert_eq!(retry.backoff(64), Duration::from_millis(500));
+	}
+
+	#[test]
+	fn deserialize_with_defaults_works() {
+		let config: DataProviderConfig = serde_json::from_str(
+			r#"{
+				"twitter_official": { "endpoints": ["https://api.twitter.com"], "auth": { "bearer": "token" } },
+				"discord_official": { "endpoints": ["https://discordapp.com"] },
+				"github_official": { "endpoints": ["https://api.github.com"] },
+				"achainable": {
+					"endpoints": ["https://label-production.graph.tdf-labs.io", "https://graph.tdf-labs.io"],
+					"timeout_ms": 5000,
+					"retry": { "max_retries": 3, "initial_backoff_ms": 100, "max_backoff_ms": 1000 },
+					"rate_limit": { "max_requests": 100, "period_secs": 60 },
+					"auth": { "api_key": "key" }
+				}
+			}"#,
+		)
+		.unwrap();
+
+		assert!(config.validate().is_ok());
+		assert_eq!(config.version, 0);
+		assert!(config.twitter_official.auth == ProviderAuth::Bearer("token".to_string()));
+		assert_eq!(config.twitter_official.timeout_ms, DEFAULT_TIMEOUT_MS);
+		assert_eq!(config.twitter_official.retry, RetryPolicy::default());
+		assert!(config.discord_litentry.endpoints.is_empty());
+		assert!(config.polkadot_archive.endpoints.is_empty());
+		assert!(config.ethereum_rpc.endpoints.is_empty());
+		assert_eq!(config.assertion_sources, AssertionSources::default());
+		assert_eq!(config.achainable.endpoints.len(), 2);
+		assert_eq!(
+			config.achainable.rate_limit,
+			Some(RateLimit { max_requests: 100, period_secs: 60 })
+		);
+	}
+}
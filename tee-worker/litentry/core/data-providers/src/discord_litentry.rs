@@ -17,14 +17,10 @@
 #[cfg(all(not(feature = "std"), feature = "sgx"))]
 use crate::sgx_reexport_prelude::*;
 
-use crate::{build_client, vec_to_string, Error, HttpError, GLOBAL_DATA_PROVIDER_CONFIG};
+use crate::{vec_to_string, DataProvider, DataProviderClient, Error, HttpError};
 use http::header::CONNECTION;
 use http_req::response::Headers;
-use itc_rest_client::{
-	http_client::{DefaultSend, HttpClient},
-	rest_client::RestClient,
-	RestGet, RestPath,
-};
+use itc_rest_client::RestPath;
 use log::*;
 use serde::{Deserialize, Serialize};
 use std::{
@@ -52,7 +48,7 @@ impl RestPath<String> for DiscordResponse {
 }
 
 pub struct DiscordLitentryClient {
-	client: RestClient<HttpClient<DefaultSend>>,
+	client: DataProviderClient,
 }
 
 impl Default for DiscordLitentryClient {
@@ -65,15 +61,7 @@ impl DiscordLitentryClient {
 	pub fn new() -> Self {
 		let mut headers = Headers::new();
 		headers.insert(CONNECTION.as_str(), "close");
-		let client = build_client(
-			GLOBAL_DATA_PROVIDER_CONFIG
-				.write()
-				.unwrap()
-				.discord_litentry_url
-				.clone()
-				.as_str(),
-			headers,
-		);
+		let client = DataProviderClient::new(DataProvider::DiscordLitentry, headers);
 		DiscordLitentryClient { client }
 	}
 
@@ -89,9 +77,7 @@ impl DiscordLitentryClient {
 
 		let path = "/discord/joined".to_string();
 		let query = vec![("guildid", guild_id_s.as_str()), ("handler", handler_s.as_str())];
-		self.client
-			.get_with::<String, DiscordResponse>(path, query.as_slice())
-			.map_err(|e| Error::RequestError(format!("{:?}", e)))
+		self.client.get_with::<String, DiscordResponse>(path, query.as_slice())
 	}
 
 	// user has commented in channel with Role 'ID-Hubber'
@@ -119,10 +105,7 @@ impl DiscordLitentryClient {
 			("handler", handler_s.as_str()),
 		];
 
-		let res = self
-			.client
-			.get_with::<String, DiscordResponse>(path, query.as_slice())
-			.map_err(|e| Error::RequestError(format!("{:?}", e)));
+		let res = self.client.get_with::<String, DiscordResponse>(path, query.as_slice());
 
 		res
 	}
@@ -139,10 +122,7 @@ impl DiscordLitentryClient {
 
 		let path = "/discord/assgin/idhubber".to_string();
 		let query = vec![("guildid", guild_id_s.as_str()), ("handler", handler_s.as_str())];
-		let res = self
-			.client
-			.get_with::<String, DiscordResponse>(path, query.as_slice())
-			.map_err(|e| Error::RequestError(format!("{:?}", e)));
+		let res = self.client.get_with::<String, DiscordResponse>(path, query.as_slice());
 
 		res
 	}
@@ -151,13 +131,14 @@ impl DiscordLitentryClient {
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::GLOBAL_DATA_PROVIDER_CONFIG;
 	use lc_mock_server::{default_getter, run};
 	use std::sync::Arc;
 
 	fn init() {
 		let _ = env_logger::builder().is_test(true).try_init();
 		let url = run(Arc::new(default_getter), 0).unwrap();
-		GLOBAL_DATA_PROVIDER_CONFIG.write().unwrap().set_discord_litentry_url(url);
+		GLOBAL_DATA_PROVIDER_CONFIG.write().unwrap().discord_litentry.endpoints = vec![url];
 	}
 
 	#[test]
@@ -17,14 +17,10 @@
 #[cfg(all(not(feature = "std"), feature = "sgx"))]
 use crate::sgx_reexport_prelude::*;
 
-use crate::{build_client, vec_to_string, Error, HttpError, UserInfo, GLOBAL_DATA_PROVIDER_CONFIG};
-use http::header::{AUTHORIZATION, CONNECTION};
+use crate::{vec_to_string, DataProvider, DataProviderClient, Error, HttpError, UserInfo};
+use http::header::CONNECTION;
 use http_req::response::Headers;
-use itc_rest_client::{
-	http_client::{DefaultSend, HttpClient},
-	rest_client::RestClient,
-	RestGet, RestPath,
-};
+use itc_rest_client::RestPath;
 use log::*;
 use serde::{Deserialize, Serialize};
 use std::{default::Default, format, string::String, vec, vec::Vec};
@@ -75,26 +71,14 @@ impl UserInfo for DiscordMessage {
 }
 
 pub struct DiscordOfficialClient {
-	client: RestClient<HttpClient<DefaultSend>>,
+	client: DataProviderClient,
 }
 
 impl DiscordOfficialClient {
 	pub fn new() -> Self {
 		let mut headers = Headers::new();
 		headers.insert(CONNECTION.as_str(), "close");
-		headers.insert(
-			AUTHORIZATION.as_str(),
-			GLOBAL_DATA_PROVIDER_CONFIG.read().unwrap().discord_auth_token.clone().as_str(),
-		);
-		let client = build_client(
-			GLOBAL_DATA_PROVIDER_CONFIG
-				.read()
-				.unwrap()
-				.discord_official_url
-				.clone()
-				.as_str(),
-			headers,
-		);
+		let client = DataProviderClient::new(DataProvider::DiscordOfficial, headers);
 		DiscordOfficialClient { client }
 	}
 
@@ -109,9 +93,7 @@ impl DiscordOfficialClient {
 
 		let path = format!("/api/channels/{}/messages/{}", channel_id, message_id);
 		let query = vec![];
-		self.client
-			.get_with::<String, DiscordMessage>(path, query.as_slice())
-			.map_err(|e| Error::RequestError(format!("{:?}", e)))
+		self.client.get_with::<String, DiscordMessage>(path, query.as_slice())
 	}
 
 	pub fn get_user_info(&mut self, user_id: String) -> Result<DiscordUser, Error> {
@@ -119,22 +101,21 @@ impl DiscordOfficialClient {
 
 		let path = format!("/api/users/{}", user_id);
 		let query = vec![];
-		self.client
-			.get_with::<String, DiscordUser>(path, query.as_slice())
-			.map_err(|e| Error::RequestError(format!("{:?}", e)))
+		self.client.get_with::<String, DiscordUser>(path, query.as_slice())
 	}
 }
 
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::GLOBAL_DATA_PROVIDER_CONFIG;
 	use lc_mock_server::{default_getter, run};
 	use std::sync::Arc;
 
 	fn init() {
 		let _ = env_logger::builder().is_test(true).try_init();
 		let url = run(Arc::new(default_getter), 0).unwrap();
-		GLOBAL_DATA_PROVIDER_CONFIG.write().unwrap().set_discord_official_url(url);
+		GLOBAL_DATA_PROVIDER_CONFIG.write().unwrap().discord_official.endpoints = vec![url];
 	}
 
 	#[test]
@@ -0,0 +1,646 @@
+// Copyright 2020-2023 Litentry Technologies GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+//! EVM data provider, reads balances and ERC-20 transfers through the JSON-RPC of an
+//! Ethereum or BSC node.
+//!
+//! Unlike `crate::native` there are no proofs to check, the node is trusted for the values it
+//! returns, so only nodes run by the operator or trusted providers should be configured.
+//!
+//! The ERC-20 transfer logs are replayed from the start date, so a token holding is only
+//! reported if the balance never dropped below the amount. The logs are fetched in pages of
+//! at most `MAX_LOG_REQUESTS` requests, an address with more logs than that fails instead of
+//! being judged on part of its history. The native token doesn't emit logs, its balance is
+//! sampled at the start date, every later date of `ASSERTION_FROM_DATE` and now, like in
+//! `crate::native`.
+
+#[cfg(all(not(feature = "std"), feature = "sgx"))]
+use crate::sgx_reexport_prelude::*;
+
+use crate::{
+	achainable::{builtin_chain, AchainableHolder, ParamsBasicTypeWithAmountHolding},
+	jsonrpc::{decode_hex, encode_hex, invalid_response, JsonRpcClient},
+	native::{date_to_timestamp_ms, parse_amount, search_block_before},
+	DataProvider, Error,
+};
+use itp_types::{Balance, H256};
+use lazy_static::lazy_static;
+use litentry_primitives::{Web3Network, ASSERTION_FROM_DATE};
+use log::*;
+use serde_json::{json, Value};
+use std::{collections::BTreeMap, format, vec, vec::Vec};
+
+#[cfg(feature = "std")]
+use std::sync::Mutex;
+#[cfg(feature = "sgx")]
+use std::sync::SgxMutex as Mutex;
+
+/// The chains that can be read through an EVM JSON-RPC.
+pub const EVM_CHAINS: [(Web3Network, DataProvider, u32); 2] = [
+	(Web3Network::Ethereum, DataProvider::EthereumRpc, 18),
+	(Web3Network::Bsc, DataProvider::BscRpc, 18),
+];
+
+/// `keccak256("Transfer(address,address,uint256)")`
+pub const TRANSFER_TOPIC: &str =
+	"0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef";
+/// `balanceOf(address)`
+const BALANCE_OF_SELECTOR: [u8; 4] = [0x70, 0xa0, 0x82, 0x31];
+/// `decimals()`
+const DECIMALS_SELECTOR: [u8; 4] = [0x31, 0x3c, 0xe5, 0x67];
+/// `isValidSignature(bytes32,bytes)`, it's also the magic value returned for a valid signature
+const IS_VALID_SIGNATURE_SELECTOR: [u8; 4] = [0x16, 0x26, 0xba, 0x7e];
+
+/// Upper bound of the `eth_getLogs` requests of one address and direction. A page is halved
+/// whenever the node refuses it, e.g. because it has too many logs, and doubled after it
+/// succeeded.
+pub const MAX_LOG_REQUESTS: u32 = 64;
+
+lazy_static! {
+	// (genesis hash, timestamp) -> the last block before the timestamp, None before genesis
+	static ref DATE_BLOCKS: Mutex<BTreeMap<(H256, u64), Option<u64>>> =
+		Mutex::new(BTreeMap::new());
+}
+
+pub fn is_evm_network(network: &Web3Network) -> bool {
+	EVM_CHAINS.iter().any(|(n, _, _)| n == network)
+}
+
+/// A `Transfer` event of an ERC-20 token.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransferLog {
+	pub block_number: u64,
+	pub log_index: u64,
+	pub from: [u8; 20],
+	pub to: [u8; 20],
+	pub value: Balance,
+}
+
+pub struct EvmRpcClient {
+	rpcs: BTreeMap<Web3Network, JsonRpcClient>,
+	genesis: BTreeMap<Web3Network, H256>,
+	decimals: BTreeMap<(Web3Network, [u8; 20]), u32>,
+}
+
+impl Default for EvmRpcClient {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl EvmRpcClient {
+	/// Creates a client for all `EVM_CHAINS`, reading the configured nodes.
+	pub fn new() -> Self {
+		let rpcs = EVM_CHAINS
+			.iter()
+			.map(|(network, provider, _)| (*network, JsonRpcClient::new(*provider)))
+			.collect();
+		EvmRpcClient { rpcs, genesis: BTreeMap::new(), decimals: BTreeMap::new() }
+	}
+
+	pub fn block_number(&mut self, network: Web3Network) -> Result<u64, Error> {
+		let number = self.call(network, "eth_blockNumber", vec![])?;
+		decode_quantity(&number).map(|number| number as u64)
+	}
+
+	/// The timestamp of block `number`, in ms.
+	pub fn block_timestamp(&mut self, network: Web3Network, number: u64) -> Result<u64, Error> {
+		let block = self.block(network, number)?;
+		let timestamp = block.get("timestamp").ok_or_else(|| invalid_response("timestamp"))?;
+		Ok((decode_quantity(timestamp)? as u64).saturating_mul(1_000))
+	}
+
+	/// The balance of the native token of `address` at block `number`.
+	pub fn get_balance(
+		&mut self,
+		network: Web3Network,
+		address: &[u8; 20],
+		number: u64,
+	) -> Result<Balance, Error> {
+		let balance =
+			self.call(network, "eth_getBalance", vec![encode_hex(address), block_tag(number)])?;
+		decode_quantity(&balance)
+	}
+
+	/// The balance of the ERC-20 `token` of `address` at block `number`.
+	pub fn balance_of(
+		&mut self,
+		network: Web3Network,
+		token: &[u8; 20],
+		address: &[u8; 20],
+		number: u64,
+	) -> Result<Balance, Error> {
+		let mut data = BALANCE_OF_SELECTOR.to_vec();
+		data.extend_from_slice(&address_topic(address));
+		let balance = self.eth_call(network, token, &data, block_tag(number))?;
+		Ok(decode_uint256(&balance))
+	}
+
+	/// The decimals of the ERC-20 `token`.
+	pub fn decimals(&mut self, network: Web3Network, token: &[u8; 20]) -> Result<u32, Error> {
+		if let Some(decimals) = self.decimals.get(&(network, *token)) {
+			return Ok(*decimals)
+		}
+		let data = self.eth_call(network, token, &DECIMALS_SELECTOR, "latest".into())?;
+		let decimals = decode_uint256(&data);
+		if data.is_empty() || decimals > 77 {
+			return Err(invalid_response(format!(
+				"invalid decimals of token 0x{}",
+				hex::encode(token)
+			)))
+		}
+		self.decimals.insert((network, *token), decimals as u32);
+		Ok(decimals as u32)
+	}
+
+	/// The transfers of the ERC-20 `token` from or to `address` within the blocks `from..=to`,
+	/// in the order they happened. Transfers to oneself are left out.
+	pub fn transfer_logs(
+		&mut self,
+		network: Web3Network,
+		token: &[u8; 20],
+		address: &[u8; 20],
+		from: u64,
+		to: u64,
+	) -> Result<Vec<TransferLog>, Error> {
+		let topic = Value::from(encode_hex(&address_topic(address)));
+		let outgoing = vec![TRANSFER_TOPIC.into(), topic.clone()];
+		let incoming = vec![TRANSFER_TOPIC.into(), Value::Null, topic];
+
+		let mut logs = self.get_logs(network, token, outgoing, from, to, MAX_LOG_REQUESTS)?;
+		logs.extend(self.get_logs(network, token, incoming, from, to, MAX_LOG_REQUESTS)?);
+		logs.retain(|log| log.from != log.to);
+		logs.sort_by_key(|log| (log.block_number, log.log_index));
+		Ok(logs)
+	}
+
+	/// The last block before `timestamp` (in ms), within `DATE_TOLERANCE_MS`.
+	/// None if the chain didn't exist yet.
+	pub fn block_at(&mut self, network: Web3Network, timestamp: u64) -> Result<Option<u64>, Error> {
+		let head = self.block_number(network)?;
+		let head_timestamp = self.block_timestamp(network, head)?;
+		if timestamp >= head_timestamp {
+			return Ok(Some(head))
+		}
+
+		let cache_key = (self.genesis(network)?, timestamp);
+		if let Some(block) = DATE_BLOCKS.lock().ok().and_then(|c| c.get(&cache_key).copied()) {
+			return Ok(block)
+		}
+
+		// the genesis block may have no timestamp
+		let low_timestamp = self.block_timestamp(network, 1)?;
+		let block = if timestamp < low_timestamp {
+			None
+		} else {
+			Some(search_block_before(
+				timestamp,
+				(1, 1, low_timestamp),
+				(head, head, head_timestamp),
+				|number| Ok((number, self.block_timestamp(network, number)?)),
+			)?)
+		};
+
+		// the cache is only bounded by the dates that are asked for
+		if let Ok(mut cache) = DATE_BLOCKS.lock() {
+			cache.insert(cache_key, block);
+		}
+		Ok(block)
+	}
+
+	/// Whether the contract wallet `wallet`, e.g. a Safe, accepts `signature` of `hash`,
+	/// see https://eips.ethereum.org/EIPS/eip-1271
+	pub fn is_valid_signature(
+		&mut self,
+		network: Web3Network,
+		wallet: &[u8; 20],
+		hash: &[u8; 32],
+		signature: &[u8],
+	) -> Result<bool, Error> {
+		let mut data = IS_VALID_SIGNATURE_SELECTOR.to_vec();
+		data.extend_from_slice(hash);
+		data.extend_from_slice(&encode_uint256(0x40));
+		data.extend_from_slice(&encode_uint256(signature.len() as u64));
+		data.extend_from_slice(signature);
+		data.resize(data.len() + (32 - signature.len() % 32) % 32, 0);
+		// an account without code returns nothing
+		let result = self.eth_call(network, wallet, &data, "latest".into())?;
+		Ok(result.starts_with(&IS_VALID_SIGNATURE_SELECTOR))
+	}
+
+	fn call(
+		&mut self,
+		network: Web3Network,
+		method: &str,
+		params: Vec<Value>,
+	) -> Result<Value, Error> {
+		self.rpcs
+			.get_mut(&network)
+			.ok_or_else(|| Error::NativeError(format!("unsupported network {:?}", network)))?
+			.call(method, params)
+	}
+
+	fn eth_call(
+		&mut self,
+		network: Web3Network,
+		to: &[u8; 20],
+		data: &[u8],
+		block: Value,
+	) -> Result<Vec<u8>, Error> {
+		let request = json!({ "to": encode_hex(to), "data": encode_hex(data) });
+		decode_hex(&self.call(network, "eth_call", vec![request, block])?)
+	}
+
+	fn block(&mut self, network: Web3Network, number: u64) -> Result<Value, Error> {
+		match self.call(network, "eth_getBlockByNumber", vec![block_tag(number), false.into()])? {
+			Value::Null => Err(invalid_response(format!("unknown block {}", number))),
+			block => Ok(block),
+		}
+	}
+
+	fn genesis(&mut self, network: Web3Network) -> Result<H256, Error> {
+		if let Some(genesis) = self.genesis.get(&network) {
+			return Ok(*genesis)
+		}
+		let hash = decode_hex(self.block(network, 0)?.get("hash").unwrap_or(&Value::Null))?;
+		if hash.len() != 32 {
+			return Err(invalid_response("expected a 32 bytes hash"))
+		}
+		let genesis = H256::from_slice(&hash);
+		self.genesis.insert(network, genesis);
+		Ok(genesis)
+	}
+
+	/// Pages through the logs of the blocks `from..=to`, fails if they can't be fetched with
+	/// `max_requests` requests.
+	fn get_logs(
+		&mut self,
+		network: Web3Network,
+		token: &[u8; 20],
+		topics: Vec<Value>,
+		from: u64,
+		to: u64,
+		max_requests: u32,
+	) -> Result<Vec<TransferLog>, Error> {
+		let mut logs = vec![];
+		let (mut start, mut size) = (from, to.saturating_sub(from).saturating_add(1));
+		let mut requests = 0;
+		while start <= to {
+			if requests == max_requests {
+				return Err(Error::NativeError(format!(
+					"logs of blocks {}..={} need more than {} requests",
+					start, to, max_requests
+				)))
+			}
+			requests += 1;
+			let end = to.min(start.saturating_add(size - 1));
+			let filter = json!({
+				"address": encode_hex(token),
+				"fromBlock": block_tag(start),
+				"toBlock": block_tag(end),
+				"topics": topics,
+			});
+			match self.call(network, "eth_getLogs", vec![filter]) {
+				Ok(Value::Array(values)) => {
+					for value in values.iter() {
+						let log = decode_transfer_log(value)?;
+						if !(start..=end).contains(&log.block_number) {
+							return Err(invalid_response(format!(
+								"log of block {} outside of {}..={}",
+								log.block_number, start, end
+							)))
+						}
+						logs.push(log);
+					}
+					match end.checked_add(1) {
+						Some(next) => start = next,
+						None => break,
+					}
+					size = size.saturating_mul(2);
+				},
+				Ok(_) => return Err(invalid_response("expected a list of logs")),
+				Err(e) if start < end => {
+					debug!("Halve logs of blocks {}..={}: {:?}", start, end, e);
+					size = (end - start + 1) / 2;
+				},
+				Err(e) => return Err(e),
+			}
+		}
+		Ok(logs)
+	}
+}
+
+impl AchainableHolder for EvmRpcClient {
+	/// Whether `address` held the amount of tokens at the date and still holds it. For the
+	/// native token that's whether it held the amount at the date, at every later date of
+	/// `ASSERTION_FROM_DATE` and now.
+	fn is_holder(
+		&mut self,
+		address: &str,
+		amount_holding: ParamsBasicTypeWithAmountHolding,
+	) -> Result<bool, Error> {
+		let (network, native_decimals) = EVM_CHAINS
+			.iter()
+			.find(|(network, _, _)| builtin_chain(network) == Some(amount_holding.chain.as_str()))
+			.map(|(network, _, decimals)| (*network, *decimals))
+			.ok_or_else(|| {
+				Error::NativeError(format!("unsupported chain {}", amount_holding.chain))
+			})?;
+		let token = amount_holding.token.as_deref().map(parse_address).transpose()?;
+		// e.g. a substrate account, it can't hold anything on an evm chain
+		let address = match parse_address(address) {
+			Ok(address) => address,
+			Err(e) => {
+				debug!("Skip {}: {:?}", address, e);
+				return Ok(false)
+			},
+		};
+		let decimals = match &token {
+			Some(token) => self.decimals(network, token)?,
+			None => native_decimals,
+		};
+		let amount = parse_amount(&amount_holding.amount, decimals)?;
+		let since = date_to_timestamp_ms(&amount_holding.date)?;
+
+		let head = self.block_number(network)?;
+		let balance_at = |client: &mut Self, number| match &token {
+			Some(token) => client.balance_of(network, token, &address, number),
+			None => client.get_balance(network, &address, number),
+		};
+		if balance_at(self, head)? < amount {
+			return Ok(false)
+		}
+		let token = match token {
+			Some(token) => token,
+			None => {
+				// the latest dates first, they're the cheapest to fail on
+				let mut dates = ASSERTION_FROM_DATE
+					.iter()
+					.map(|date| date_to_timestamp_ms(date))
+					.collect::<Result<Vec<_>, _>>()?;
+				dates.retain(|date| *date > since);
+				dates.push(since);
+				dates.sort_unstable_by(|a, b| b.cmp(a));
+				for date in dates {
+					match self.block_at(network, date)? {
+						Some(block) if balance_at(self, block)? >= amount => {},
+						_ => return Ok(false),
+					}
+				}
+				return Ok(true)
+			},
+		};
+		let start = match self.block_at(network, since)? {
+			Some(start) => start,
+			None => return Ok(false),
+		};
+		let mut balance = balance_at(self, start)?;
+		if balance < amount {
+			return Ok(false)
+		}
+		for log in self.transfer_logs(network, &token, &address, start + 1, head)? {
+			if log.from == address {
+				balance = balance.saturating_sub(log.value);
+			} else {
+				balance = balance.saturating_add(log.value);
+			}
+			if balance < amount {
+				return Ok(false)
+			}
+		}
+		Ok(true)
+	}
+}
+
+/// Parses a `0x` prefixed hex address, as produced by `transpose_identity`.
+pub fn parse_address(address: &str) -> Result<[u8; 20], Error> {
+	let bytes = hex::decode(address.trim_start_matches("0x"))
+		.map_err(|e| Error::NativeError(format!("invalid address {}: {:?}", address, e)))?;
+	<[u8; 20]>::try_from(bytes.as_slice())
+		.map_err(|_| Error::NativeError(format!("invalid address {}", address)))
+}
+
+fn block_tag(number: u64) -> Value {
+	format!("0x{:x}", number).into()
+}
+
+fn address_topic(address: &[u8; 20]) -> [u8; 32] {
+	let mut topic = [0u8; 32];
+	topic[12..].copy_from_slice(address);
+	topic
+}
+
+/// Decodes a hex quantity, saturating at `Balance::MAX`.
+fn encode_uint256(value: u64) -> [u8; 32] {
+	let mut encoded = [0u8; 32];
+	encoded[24..].copy_from_slice(&value.to_be_bytes());
+	encoded
+}
+
+fn decode_quantity(value: &Value) -> Result<Balance, Error> {
+	let digits = value
+		.as_str()
+		.and_then(|value| value.strip_prefix("0x"))
+		.ok_or_else(|| invalid_response("expected a hex quantity"))?
+		.trim_start_matches('0');
+	if digits.len() > 32 {
+		return Ok(Balance::MAX)
+	}
+	if digits.is_empty() {
+		return Ok(0)
+	}
+	Balance::from_str_radix(digits, 16).map_err(invalid_response)
+}
+
+/// Decodes a big-endian uint256, saturating at `Balance::MAX`.
+fn decode_uint256(bytes: &[u8]) -> Balance {
+	let bytes = &bytes[..bytes.len().min(32)];
+	let (high, low) = bytes.split_at(bytes.len().saturating_sub(16));
+	if high.iter().any(|b| *b != 0) {
+		return Balance::MAX
+	}
+	low.iter().fold(0, |value, b| (value << 8) | *b as Balance)
+}
+
+fn decode_transfer_log(value: &Value) -> Result<TransferLog, Error> {
+	let field = |name: &str| value.get(name).ok_or_else(|| invalid_response(name));
+	let topics = field("topics")?
+		.as_array()
+		.ok_or_else(|| invalid_response("topics"))?
+		.iter()
+		.map(decode_hex)
+		.collect::<Result<Vec<_>, _>>()?;
+	let topic_address = |topic: Option<&Vec<u8>>| -> Result<[u8; 20], Error> {
+		topic
+			.filter(|topic| topic.len() == 32)
+			.and_then(|topic| <[u8; 20]>::try_from(&topic[12..]).ok())
+			.ok_or_else(|| invalid_response("expected an address topic"))
+	};
+
+	Ok(TransferLog {
+		block_number: decode_quantity(field("blockNumber")?)? as u64,
+		log_index: decode_quantity(field("logIndex")?)? as u64,
+		from: topic_address(topics.get(1))?,
+		to: topic_address(topics.get(2))?,
+		value: decode_uint256(&decode_hex(field("data")?)?),
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::GLOBAL_DATA_PROVIDER_CONFIG;
+	use lc_mock_server::{
+		default_getter,
+		evm_rpc::{
+			ALICE, BLOCK_TIME_MS, BOB, CAROL, GENESIS_TIMESTAMP_MS, LATEST_BLOCK, SAFE, TOKEN,
+		},
+		run,
+	};
+	use std::sync::Arc;
+
+	fn init() -> EvmRpcClient {
+		let _ = env_logger::builder().is_test(true).try_init();
+		let url = run(Arc::new(default_getter), 0).unwrap() + "/evm";
+		let mut config = GLOBAL_DATA_PROVIDER_CONFIG.write().unwrap();
+		config.ethereum_rpc.endpoints = vec![url.clone()];
+		config.bsc_rpc.endpoints = vec![url];
+		drop(config);
+		EvmRpcClient::new()
+	}
+
+	fn holding(date: &str, amount: &str, token: Option<&str>) -> ParamsBasicTypeWithAmountHolding {
+		ParamsBasicTypeWithAmountHolding {
+			name: "Balance hodling {amount} since {date}".into(),
+			chain: "ethereum".into(),
+			amount: amount.into(),
+			date: date.into(),
+			token: token.map(Into::into),
+		}
+	}
+
+	#[test]
+	fn block_at_works() {
+		let mut client = init();
+		let network = Web3Network::Ethereum;
+		assert_eq!(client.block_number(network).unwrap(), LATEST_BLOCK);
+
+		let date = date_to_timestamp_ms("2018-07-01").unwrap();
+		let block = client.block_at(network, date).unwrap().unwrap();
+		let timestamp = client.block_timestamp(network, block).unwrap();
+		assert!(timestamp <= date && date - timestamp <= BLOCK_TIME_MS);
+		// cached
+		assert_eq!(client.block_at(network, date).unwrap(), Some(block));
+
+		assert_eq!(client.block_at(network, GENESIS_TIMESTAMP_MS - 1).unwrap(), None);
+		assert_eq!(client.block_at(network, u64::MAX).unwrap(), Some(LATEST_BLOCK));
+	}
+
+	#[test]
+	fn erc20_works() {
+		let mut client = init();
+		let network = Web3Network::Ethereum;
+		let token = parse_address(TOKEN).unwrap();
+		let alice = parse_address(ALICE).unwrap();
+		let bob = parse_address(BOB).unwrap();
+
+		assert_eq!(client.decimals(network, &token).unwrap(), 8);
+		assert_eq!(client.balance_of(network, &token, &alice, 299).unwrap(), 0);
+		assert_eq!(client.balance_of(network, &token, &alice, 300).unwrap(), 1_000_000_000);
+
+		let logs = client.transfer_logs(network, &token, &bob, 0, LATEST_BLOCK).unwrap();
+		assert_eq!(logs.len(), 2);
+		assert!(logs[0].block_number < logs[1].block_number);
+		assert_eq!((logs[0].to, logs[1].from), (bob, bob));
+	}
+
+	#[test]
+	fn get_logs_fails_if_out_of_requests() {
+		let mut client = init();
+		let network = Web3Network::Ethereum;
+		let token = parse_address(TOKEN).unwrap();
+		let topics = vec![TRANSFER_TOPIC.into()];
+
+		// 0..=1000 is refused, 0..=499 succeeds, 500..=1000 is refused, 500..=749 and
+		// 750..=1000 succeed
+		let logs = client.get_logs(network, &token, topics.clone(), 0, LATEST_BLOCK, 5).unwrap();
+		assert_eq!(logs.len(), 3);
+		assert!(client.get_logs(network, &token, topics.clone(), 0, LATEST_BLOCK, 4).is_err());
+		assert!(client.get_logs(network, &token, topics, 1, 0, 0).unwrap().is_empty());
+	}
+
+	#[test]
+	fn is_holder_works() {
+		let mut client = init();
+
+		// alice receives 10 tokens and 10 eth at block 300 (2018-06-19)
+		assert!(client.is_holder(ALICE, holding("2018-07-01", "10", Some(TOKEN))).unwrap());
+		assert!(!client.is_holder(ALICE, holding("2018-01-01", "10", Some(TOKEN))).unwrap());
+		assert!(!client.is_holder(ALICE, holding("2018-07-01", "10.1", Some(TOKEN))).unwrap());
+		assert!(client.is_holder(ALICE, holding("2018-07-01", "10", None)).unwrap());
+		assert!(!client.is_holder(ALICE, holding("2018-01-01", "10", None)).unwrap());
+
+		// carol's eth are gone from block 400 (2019-04-15) to block 500 (2020-02-09)
+		assert!(!client.is_holder(CAROL, holding("2017-01-01", "10", None)).unwrap());
+		assert!(!client.is_holder(CAROL, holding("2019-07-01", "10", None)).unwrap());
+		assert!(client.is_holder(CAROL, holding("2020-07-01", "10", None)).unwrap());
+
+		// bob holds 10 tokens from block 100 to block 600, 1 token is left afterwards
+		assert!(!client.is_holder(BOB, holding("2017-01-01", "10", Some(TOKEN))).unwrap());
+		assert!(client.is_holder(BOB, holding("2017-01-01", "1", Some(TOKEN))).unwrap());
+
+		let substrate = "0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d";
+		assert!(!client.is_holder(substrate, holding("2017-01-01", "1", None)).unwrap());
+
+		let mut unsupported = holding("2017-01-01", "1", None);
+		unsupported.chain = "litentry".into();
+		assert!(client.is_holder(ALICE, unsupported).is_err());
+	}
+
+	#[test]
+	fn is_valid_signature_works() {
+		let mut client = init();
+		let network = Web3Network::Bsc;
+		let safe = parse_address(SAFE).unwrap();
+		let hash = [7u8; 32];
+
+		// the mock wallet accepts the hash itself as its signature
+		assert!(client.is_valid_signature(network, &safe, &hash, &hash).unwrap());
+		assert!(!client.is_valid_signature(network, &safe, &hash, &[7u8; 31]).unwrap());
+		assert!(!client.is_valid_signature(network, &safe, &hash, &[8u8; 65]).unwrap());
+		// not a contract
+		let alice = parse_address(ALICE).unwrap();
+		assert!(!client.is_valid_signature(network, &alice, &hash, &hash).unwrap());
+	}
+
+	#[test]
+	fn decode_works() {
+		assert_eq!(decode_quantity(&"0x0".into()).unwrap(), 0);
+		assert_eq!(decode_quantity(&"0x3e8".into()).unwrap(), 1_000);
+		assert_eq!(decode_quantity(&format!("0x1{}", "0".repeat(32)).into()).unwrap(), u128::MAX);
+		assert!(decode_quantity(&"3e8".into()).is_err());
+
+		let mut uint256 = [0u8; 32];
+		uint256[31] = 1;
+		assert_eq!(decode_uint256(&uint256), 1);
+		uint256[0] = 1;
+		assert_eq!(decode_uint256(&uint256), u128::MAX);
+		assert_eq!(decode_uint256(&[]), 0);
+
+		assert_eq!(parse_address(ALICE).unwrap()[19], 0xa1);
+		assert!(parse_address("0x1234").is_err());
+	}
+}
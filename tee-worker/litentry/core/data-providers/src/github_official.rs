@@ -0,0 +1,187 @@
+// Copyright 2020-2023 Litentry Technologies GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+#[cfg(all(not(feature = "std"), feature = "sgx"))]
+use crate::sgx_reexport_prelude::*;
+
+use crate::{vec_to_string, DataProvider, DataProviderClient, Error, HttpError, UserInfo};
+use http::header::{ACCEPT, CONNECTION, USER_AGENT};
+use http_req::response::Headers;
+use itc_rest_client::RestPath;
+use log::*;
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::BTreeMap,
+	default::Default,
+	format,
+	string::{String, ToString},
+	vec,
+	vec::Vec,
+};
+
+// GitHub rejects requests without a user agent
+const GITHUB_USER_AGENT: &str = "litentry-tee-worker";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GithubUser {
+	pub login: String,
+	pub id: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GithubGistFile {
+	pub filename: String,
+	// omitted by GitHub when the file is too large to be inlined
+	pub content: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GithubGist {
+	pub id: String,
+	pub public: bool,
+	pub owner: GithubUser,
+	pub files: BTreeMap<String, GithubGistFile>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GithubIssueComment {
+	pub id: u64,
+	pub body: String,
+	pub user: GithubUser,
+}
+
+impl RestPath<String> for GithubGist {
+	fn get_path(path: String) -> Result<String, HttpError> {
+		Ok(path)
+	}
+}
+
+impl RestPath<String> for GithubIssueComment {
+	fn get_path(path: String) -> Result<String, HttpError> {
+		Ok(path)
+	}
+}
+
+impl UserInfo for GithubGist {
+	fn get_user_id(&self) -> Option<String> {
+		Some(self.owner.id.to_string())
+	}
+}
+
+impl UserInfo for GithubIssueComment {
+	fn get_user_id(&self) -> Option<String> {
+		Some(self.user.id.to_string())
+	}
+}
+
+impl Default for GithubOfficialClient {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+pub struct GithubOfficialClient {
+	client: DataProviderClient,
+}
+
+/// rate limit: https://docs.github.com/en/rest/overview/resources-in-the-rest-api#rate-limiting
+impl GithubOfficialClient {
+	pub fn new() -> Self {
+		let mut headers = Headers::new();
+		headers.insert(CONNECTION.as_str(), "close");
+		headers.insert(ACCEPT.as_str(), "application/vnd.github+json");
+		headers.insert(USER_AGENT.as_str(), GITHUB_USER_AGENT);
+		let client = DataProviderClient::new(DataProvider::GithubOfficial, headers);
+		GithubOfficialClient { client }
+	}
+
+	pub fn query_gist(&mut self, gist_id: Vec<u8>) -> Result<GithubGist, Error> {
+		let gist_id = vec_to_string(gist_id)?;
+		debug!("github query gist, id: {}", gist_id);
+
+		let path = format!("/gists/{}", gist_id);
+		let query = vec![];
+		self.client.get_with::<String, GithubGist>(path, query.as_slice())
+	}
+
+	pub fn query_issue_comment(
+		&mut self,
+		owner: Vec<u8>,
+		repo: Vec<u8>,
+		comment_id: Vec<u8>,
+	) -> Result<GithubIssueComment, Error> {
+		let owner = vec_to_string(owner)?;
+		let repo = vec_to_string(repo)?;
+		let comment_id = vec_to_string(comment_id)?;
+		debug!(
+			"github query issue comment, owner: {}, repo: {}, comment_id: {}",
+			owner, repo, comment_id
+		);
+
+		let path = format!("/repos/{}/{}/issues/comments/{}", owner, repo, comment_id);
+		let query = vec![];
+		self.client.get_with::<String, GithubIssueComment>(path, query.as_slice())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::GLOBAL_DATA_PROVIDER_CONFIG;
+	use lc_mock_server::{default_getter, run};
+	use std::sync::Arc;
+
+	fn init() {
+		let _ = env_logger::builder().is_test(true).try_init();
+		let url = run(Arc::new(default_getter), 0).unwrap();
+		GLOBAL_DATA_PROVIDER_CONFIG.write().unwrap().github_official.endpoints = vec![url];
+	}
+
+	#[test]
+	fn query_gist_work() {
+		init();
+
+		let gist_id = "1";
+		let mut client = GithubOfficialClient::new();
+		let result = client.query_gist(gist_id.as_bytes().to_vec());
+		assert!(result.is_ok(), "query gist error: {:?}", result);
+
+		let gist = result.unwrap();
+		assert_eq!(gist.id, gist_id);
+		assert!(gist.public);
+		assert_eq!(gist.owner.login, "MOCK_USER");
+		assert_eq!(gist.files.len(), 1);
+		assert!(gist.files.values().all(|f| f.content.is_some()));
+	}
+
+	#[test]
+	fn query_issue_comment_work() {
+		init();
+
+		let mut client = GithubOfficialClient::new();
+		let result = client.query_issue_comment(
+			"litentry".as_bytes().to_vec(),
+			"litentry-parachain".as_bytes().to_vec(),
+			"1".as_bytes().to_vec(),
+		);
+		assert!(result.is_ok(), "query issue comment error: {:?}", result);
+
+		let comment = result.unwrap();
+		assert_eq!(comment.id, 1);
+		assert_eq!(comment.user.login, "MOCK_USER");
+		assert!(!comment.body.is_empty());
+	}
+}
@@ -0,0 +1,235 @@
+// Copyright 2020-2023 Litentry Technologies GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{DataProviderConfig, Error, GLOBAL_DATA_PROVIDER_CONFIG};
+use codec::{Decode, Encode};
+use itp_types::ShardIdentifier;
+use lazy_static::lazy_static;
+use log::*;
+use std::format;
+
+#[cfg(feature = "std")]
+use std::sync::RwLock;
+#[cfg(feature = "sgx")]
+use std::sync::SgxRwLock as RwLock;
+
+#[cfg(feature = "sgx")]
+mod sgx {
+	use super::SealedDataProviderConfig;
+	use crate::Error;
+	pub use codec::{Decode, Encode};
+	pub use itp_settings::files::DATA_PROVIDER_CONFIG_FILE;
+	pub use itp_sgx_io::{seal, unseal, StaticSealedIO};
+	pub use std::sgxfs::SgxFile;
+
+	#[derive(Copy, Clone, Debug)]
+	pub struct DataProviderConfigSeal;
+
+	impl StaticSealedIO for DataProviderConfigSeal {
+		type Error = Error;
+		type Unsealed = SealedDataProviderConfig;
+
+		fn unseal_from_static_file() -> Result<Self::Unsealed, Self::Error> {
+			Ok(unseal(DATA_PROVIDER_CONFIG_FILE).map(|b| Decode::decode(&mut b.as_slice()))??)
+		}
+
+		fn seal_to_static_file(unsealed: &Self::Unsealed) -> Result<(), Self::Error> {
+			Ok(unsealed.using_encoded(|bytes| seal(bytes, DATA_PROVIDER_CONFIG_FILE))?)
+		}
+	}
+}
+
+#[cfg(feature = "sgx")]
+use sgx::*;
+
+lazy_static! {
+	// the shard whose root account may update the configuration
+	static ref DATA_PROVIDER_CONFIG_SHARD: RwLock<Option<ShardIdentifier>> = RwLock::new(None);
+}
+
+/// The configuration as it's sealed, together with the shard it was set up for.
+#[derive(Clone, Encode, Decode)]
+pub struct SealedDataProviderConfig {
+	pub shard: ShardIdentifier,
+	pub config: DataProviderConfig,
+}
+
+/// Initialises the global configuration with `config`, which is passed by the worker at
+/// startup for `shard`.
+///
+/// If `DATA_PROVIDER_CONFIG_FILE` exists and was sealed for `shard`, the sealed configuration
+/// is used unless `config` has a higher version, so that updates done via RPC survive a
+/// restart.
+#[cfg(feature = "sgx")]
+pub fn init_data_provider_config(
+	shard: ShardIdentifier,
+	config: DataProviderConfig,
+) -> Result<(), Error> {
+	let sealed = if SgxFile::open(DATA_PROVIDER_CONFIG_FILE).is_ok() {
+		// a configuration sealed by an older enclave might not decode anymore
+		DataProviderConfigSeal::unseal_from_static_file()
+			.map_err(|e| warn!("[Enclave] Replace unreadable sealed DataProviderConfig: {:?}", e))
+			.ok()
+	} else {
+		None
+	};
+	let config = select_data_provider_config(&shard, config, sealed);
+	set_data_provider_config(shard, config, |sealed| {
+		info!("[Enclave] Seal DataProviderConfig to file, version {}", sealed.config.version);
+		DataProviderConfigSeal::seal_to_static_file(sealed)
+	})
+}
+
+#[cfg(feature = "std")]
+pub fn init_data_provider_config(
+	shard: ShardIdentifier,
+	config: DataProviderConfig,
+) -> Result<(), Error> {
+	set_data_provider_config(shard, config, |_| Ok(()))
+}
+
+/// Replaces the global configuration of `shard` with `config` and seals it, `config` must
+/// have a higher version than the current configuration.
+///
+/// The caller is responsible for the authentication of the update.
+pub fn update_data_provider_config(
+	shard: ShardIdentifier,
+	config: DataProviderConfig,
+) -> Result<(), Error> {
+	config.validate()?;
+
+	// the version is checked and the configuration is sealed under the same lock, so that
+	// concurrent updates can't seal an older version than the one that is in use
+	let mut current = GLOBAL_DATA_PROVIDER_CONFIG.write().map_err(|_| Error::PoisonLock)?;
+	let current_shard = *DATA_PROVIDER_CONFIG_SHARD.read().map_err(|_| Error::PoisonLock)?;
+	if current_shard != Some(shard) {
+		return Err(Error::InvalidConfig(format!("not configured for shard {:?}", shard)))
+	}
+	if config.version <= current.version {
+		return Err(Error::OutdatedConfigVersion(config.version))
+	}
+	info!("Update DataProviderConfig to version {}", config.version);
+
+	#[cfg(feature = "sgx")]
+	DataProviderConfigSeal::seal_to_static_file(&SealedDataProviderConfig {
+		shard,
+		config: config.clone(),
+	})?;
+	*current = config;
+	Ok(())
+}
+
+/// Picks the configuration to use at startup, the sealed one is ignored if it was sealed for
+/// another shard or has a lower version than `config`.
+fn select_data_provider_config(
+	shard: &ShardIdentifier,
+	config: DataProviderConfig,
+	sealed: Option<SealedDataProviderConfig>,
+) -> DataProviderConfig {
+	match sealed {
+		Some(sealed) if &sealed.shard != shard => {
+			warn!(
+				"Ignore DataProviderConfig sealed for shard {:?}, the worker runs shard {:?}",
+				sealed.shard, shard
+			);
+			config
+		},
+		Some(sealed) if sealed.config.version >= config.version => {
+			// the passed configuration comes from env/CLI, make sure that it's visible that it
+			// isn't used
+			warn!(
+				"Use sealed DataProviderConfig version {} instead of the passed version {}, \
+				 increase the version to apply the passed configuration",
+				sealed.config.version, config.version
+			);
+			sealed.config
+		},
+		_ => config,
+	}
+}
+
+fn set_data_provider_config(
+	shard: ShardIdentifier,
+	config: DataProviderConfig,
+	seal: impl FnOnce(&SealedDataProviderConfig) -> Result<(), Error>,
+) -> Result<(), Error> {
+	config.validate()?;
+	let mut current = GLOBAL_DATA_PROVIDER_CONFIG.write().map_err(|_| Error::PoisonLock)?;
+	let sealed = SealedDataProviderConfig { shard, config };
+	seal(&sealed)?;
+	*DATA_PROVIDER_CONFIG_SHARD.write().map_err(|_| Error::PoisonLock)? = Some(shard);
+	*current = sealed.config;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn update_data_provider_config_works() {
+		// the global configuration isn't replaced, the other tests use it concurrently
+		let shard = ShardIdentifier::repeat_byte(1);
+		*DATA_PROVIDER_CONFIG_SHARD.write().unwrap() = Some(shard);
+		let mut config = GLOBAL_DATA_PROVIDER_CONFIG.read().unwrap().clone();
+
+		assert!(matches!(
+			update_data_provider_config(shard, config.clone()),
+			Err(Error::OutdatedConfigVersion(_))
+		));
+
+		config.version += 1;
+		assert!(matches!(
+			update_data_provider_config(ShardIdentifier::repeat_byte(2), config.clone()),
+			Err(Error::InvalidConfig(_))
+		));
+
+		config.achainable.endpoints = vec!["not a url".into()];
+		assert!(matches!(update_data_provider_config(shard, config), Err(Error::InvalidConfig(_))));
+	}
+
+	#[test]
+	fn select_data_provider_config_works() {
+		let shard = ShardIdentifier::repeat_byte(1);
+		let mut passed = DataProviderConfig::new();
+		passed.version = 2;
+		let sealed = |shard, version| {
+			let mut config = DataProviderConfig::new();
+			config.version = version;
+			Some(SealedDataProviderConfig { shard, config })
+		};
+
+		assert_eq!(select_data_provider_config(&shard, passed.clone(), None).version, 2);
+		assert_eq!(
+			select_data_provider_config(&shard, passed.clone(), sealed(shard, 3)).version,
+			3
+		);
+		assert_eq!(
+			select_data_provider_config(&shard, passed.clone(), sealed(shard, 2)).version,
+			2
+		);
+		assert_eq!(
+			select_data_provider_config(&shard, passed.clone(), sealed(shard, 1)).version,
+			2
+		);
+		// a configuration sealed for another shard is never used
+		assert_eq!(
+			select_data_provider_config(&shard, passed, sealed(ShardIdentifier::repeat_byte(2), 3))
+				.version,
+			2
+		);
+	}
+}
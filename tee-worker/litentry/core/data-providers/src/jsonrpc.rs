@@ -0,0 +1,83 @@
+// Copyright 2020-2023 Litentry Technologies GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+#[cfg(all(not(feature = "std"), feature = "sgx"))]
+use crate::sgx_reexport_prelude::*;
+
+use crate::{DataProvider, DataProviderClient, Error, HttpError};
+use http::header::CONNECTION;
+use http_req::response::Headers;
+use itc_rest_client::RestPath;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{format, string::String, vec::Vec};
+
+#[derive(Serialize, Debug)]
+pub struct JsonRpcRequest {
+	jsonrpc: String,
+	id: u32,
+	method: String,
+	params: Vec<Value>,
+}
+
+impl RestPath<String> for JsonRpcRequest {
+	fn get_path(path: String) -> Result<String, HttpError> {
+		Ok(path)
+	}
+}
+
+#[derive(Deserialize, Debug)]
+pub struct JsonRpcResponse {
+	result: Option<Value>,
+	error: Option<Value>,
+}
+
+/// JSON-RPC over HTTP, with the settings of `provider`.
+pub struct JsonRpcClient {
+	client: DataProviderClient,
+}
+
+impl JsonRpcClient {
+	pub fn new(provider: DataProvider) -> Self {
+		let mut headers = Headers::new();
+		headers.insert(CONNECTION.as_str(), "close");
+		JsonRpcClient { client: DataProviderClient::new(provider, headers) }
+	}
+
+	/// Calls `method`, a missing result is returned as `Value::Null`.
+	pub fn call(&mut self, method: &str, params: Vec<Value>) -> Result<Value, Error> {
+		let request =
+			JsonRpcRequest { jsonrpc: "2.0".into(), id: 1, method: method.into(), params };
+		let response: JsonRpcResponse = self.client.post_capture(String::default(), &request)?;
+		match response.error {
+			Some(error) => Err(Error::NativeError(format!("{} failed: {}", method, error))),
+			None => Ok(response.result.unwrap_or(Value::Null)),
+		}
+	}
+}
+
+pub(crate) fn invalid_response(reason: impl core::fmt::Debug) -> Error {
+	Error::NativeError(format!("invalid response: {:?}", reason))
+}
+
+pub(crate) fn encode_hex(bytes: &[u8]) -> Value {
+	format!("0x{}", hex::encode(bytes)).into()
+}
+
+pub(crate) fn decode_hex(value: &Value) -> Result<Vec<u8>, Error> {
+	let value = value.as_str().ok_or_else(|| invalid_response("expected a hex string"))?;
+	hex::decode(value.trim_start_matches("0x")).map_err(invalid_response)
+}
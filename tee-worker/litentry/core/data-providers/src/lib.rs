@@ -35,7 +35,6 @@ pub mod sgx_reexport_prelude {
 #[cfg(all(not(feature = "std"), feature = "sgx"))]
 use crate::sgx_reexport_prelude::*;
 
-use codec::{Decode, Encode};
 use core::time::Duration;
 use http_req::response::Headers;
 use itc_rest_client::{
@@ -45,7 +44,6 @@ use itc_rest_client::{
 };
 use lazy_static::lazy_static;
 use log::debug;
-use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "std")]
 use std::sync::RwLock;
@@ -67,11 +65,20 @@ use url::Url;
 compile_error!("feature \"std\" and feature \"sgx\" cannot be enabled at the same time");
 
 pub mod achainable;
+pub mod client;
+pub mod config;
 pub mod discord_litentry;
 pub mod discord_official;
+pub mod evm;
+pub mod github_official;
+pub mod io;
+pub mod jsonrpc;
+pub mod native;
 pub mod twitter_official;
 
-const TIMEOUT: Duration = Duration::from_secs(3u64);
+pub use client::DataProviderClient;
+pub use config::*;
+pub use io::{init_data_provider_config, update_data_provider_config, SealedDataProviderConfig};
 
 pub const LIT_TOKEN_ADDRESS: &str = "0xb59490ab09a0f526cc7305822ac65f2ab12f9723";
 pub const WBTC_TOKEN_ADDRESS: &str = "0x2260fac5e5542a773aa44fbcfedf7c193bc2c599";
@@ -79,77 +86,6 @@ pub const WETH_TOKEN_ADDRESS: &str = "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2
 pub const UNISWAP_TOKEN_ADDRESS: &str = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48";
 pub const USDT_TOKEN_ADDRESS: &str = "0xdac17f958d2ee523a2206206994597c13d831ec7";
 
-#[derive(PartialEq, Eq, Clone, Encode, Decode, Serialize, Deserialize)]
-pub struct DataProviderConfig {
-	pub twitter_official_url: String,
-	pub twitter_litentry_url: String,
-	pub twitter_auth_token_v2: String,
-	pub discord_official_url: String,
-	pub discord_litentry_url: String,
-	pub discord_auth_token: String,
-	pub achainable_url: String,
-	pub achainable_auth_key: String,
-	pub credential_endpoint: String,
-}
-
-impl Default for DataProviderConfig {
-	fn default() -> Self {
-		Self::new()
-	}
-}
-
-impl DataProviderConfig {
-	pub fn new() -> Self {
-		DataProviderConfig {
-			twitter_official_url: "https://api.twitter.com".to_string(),
-			twitter_litentry_url: "".to_string(),
-			twitter_auth_token_v2: "Bearer ".to_string(),
-			discord_official_url: "https://discordapp.com".to_string(),
-			discord_litentry_url: "".to_string(),
-			discord_auth_token: "".to_string(),
-			achainable_url: "https://graph.tdf-labs.io/".to_string(),
-			achainable_auth_key: "".to_string(),
-			credential_endpoint: "".to_string(),
-		}
-	}
-	pub fn set_twitter_official_url(&mut self, v: String) {
-		debug!("set_twitter_official_url: {:?}", v);
-		self.twitter_official_url = v;
-	}
-	pub fn set_twitter_litentry_url(&mut self, v: String) {
-		debug!("set_twitter_litentry_url: {:?}", v);
-		self.twitter_litentry_url = v;
-	}
-	pub fn set_twitter_auth_token_v2(&mut self, v: String) {
-		debug!("set_twitter_auth_token_v2: {:?}", v);
-		self.twitter_auth_token_v2 = v;
-	}
-	pub fn set_discord_official_url(&mut self, v: String) {
-		debug!("set_discord_official_url: {:?}", v);
-		self.discord_official_url = v;
-	}
-	pub fn set_discord_litentry_url(&mut self, v: String) {
-		debug!("set_discord_litentry_url: {:?}", v);
-		self.discord_litentry_url = v;
-	}
-	pub fn set_discord_auth_token(&mut self, v: String) {
-		debug!("set_discord_auth_token: {:?}", v);
-		self.discord_auth_token = v;
-	}
-	pub fn set_achainable_url(&mut self, v: String) {
-		debug!("set_achainable_url: {:?}", v);
-		self.achainable_url = v;
-	}
-	pub fn set_achainable_auth_key(&mut self, v: String) {
-		debug!("set_achainable_auth_key: {:?}", v);
-		self.achainable_auth_key = v;
-	}
-	pub fn set_credential_endpoint(&mut self, v: String) {
-		debug!("set_credential_endpoint: {:?}", v);
-		self.credential_endpoint = v;
-	}
-}
-
 lazy_static! {
 	pub static ref GLOBAL_DATA_PROVIDER_CONFIG: RwLock<DataProviderConfig> =
 		RwLock::new(DataProviderConfig::new());
@@ -165,6 +101,39 @@ pub enum Error {
 
 	#[error("Achainable error: {0}")]
 	AchainableError(String),
+
+	#[error("Native data provider error: {0}")]
+	NativeError(String),
+
+	#[error("Invalid data provider config: {0}")]
+	InvalidConfig(String),
+
+	#[error("Data provider not configured: {0}")]
+	ProviderNotConfigured(String),
+
+	#[error("Rate limit of {0} exceeded")]
+	RateLimited(String),
+
+	#[error("Outdated data provider config version: {0}")]
+	OutdatedConfigVersion(u64),
+
+	#[error("Sealing error: {0}")]
+	SealingError(String),
+
+	#[error("poison lock")]
+	PoisonLock,
+}
+
+impl From<std::io::Error> for Error {
+	fn from(e: std::io::Error) -> Self {
+		Self::SealingError(format!("{:?}", e))
+	}
+}
+
+impl From<codec::Error> for Error {
+	fn from(e: codec::Error) -> Self {
+		Self::SealingError(format!("{:?}", e))
+	}
 }
 
 impl IntoErrorDetail for Error {
@@ -188,11 +157,43 @@ pub fn vec_to_string(vec: Vec<u8>) -> Result<String, Error> {
 	Ok(tmp.to_string())
 }
 
-pub fn build_client(base_url: &str, headers: Headers) -> RestClient<HttpClient<DefaultSend>> {
+pub fn get_provider_config(provider: DataProvider) -> Result<ProviderConfig, Error> {
+	let config = GLOBAL_DATA_PROVIDER_CONFIG.read().map_err(|_| Error::PoisonLock)?;
+	Ok(config.provider(provider).clone())
+}
+
+pub fn get_credential_endpoint() -> Result<String, Error> {
+	let config = GLOBAL_DATA_PROVIDER_CONFIG.read().map_err(|_| Error::PoisonLock)?;
+	Ok(config.credential_endpoint.clone())
+}
+
+pub fn get_assertion_sources() -> Result<AssertionSources, Error> {
+	let config = GLOBAL_DATA_PROVIDER_CONFIG.read().map_err(|_| Error::PoisonLock)?;
+	Ok(config.assertion_sources.clone())
+}
+
+pub fn get_parentchain_archive() -> Result<Option<DataProvider>, Error> {
+	let config = GLOBAL_DATA_PROVIDER_CONFIG.read().map_err(|_| Error::PoisonLock)?;
+	Ok(config.parentchain_archive)
+}
+
+pub fn build_client(
+	base_url: &str,
+	headers: Headers,
+) -> Result<RestClient<HttpClient<DefaultSend>>, Error> {
+	build_client_with_timeout(base_url, headers, Duration::from_millis(DEFAULT_TIMEOUT_MS))
+}
+
+pub fn build_client_with_timeout(
+	base_url: &str,
+	headers: Headers,
+	timeout: Duration,
+) -> Result<RestClient<HttpClient<DefaultSend>>, Error> {
 	debug!("base_url: {}", base_url);
-	let base_url = Url::parse(base_url).unwrap();
-	let http_client = HttpClient::new(DefaultSend {}, true, Some(TIMEOUT), Some(headers), None);
-	RestClient::new(http_client, base_url)
+	let base_url = Url::parse(base_url)
+		.map_err(|e| Error::InvalidConfig(format!("invalid url {}: {}", base_url, e)))?;
+	let http_client = HttpClient::new(DefaultSend {}, true, Some(timeout), Some(headers), None);
+	Ok(RestClient::new(http_client, base_url))
 }
 
 pub trait ConvertParameterString {
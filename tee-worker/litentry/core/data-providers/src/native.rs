@@ -0,0 +1,1123 @@
+// Copyright 2020-2023 Litentry Technologies GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Native data provider, reads balances, locks and votes straight from the storage of
+//! Substrate chains through the JSON-RPC of an archive node.
+//!
+//! The node isn't trusted for the values it returns: a header has to hash to the block hash
+//! it was requested for, and every value is checked against the state root of the header
+//! with a read proof. The block hashes come from the light client of the parentchain: the
+//! current state is read at the block it imported last, historical state at the checkpoints
+//! it recorded, see `pallet_parentchain::Checkpoints`. Only the parentchain can be read, the
+//! enclave has no light client of the other chains.
+//!
+//! Only what is still in storage can be seen: a holding is sampled at the start date, every
+//! later date of `ASSERTION_FROM_DATE` and now, a balance that dropped in between isn't
+//! noticed. Governance participation is derived from votes and voting locks that haven't
+//! been removed yet.
+
+#[cfg(all(not(feature = "std"), feature = "sgx"))]
+use crate::sgx_reexport_prelude::*;
+
+use crate::{
+	achainable::{builtin_chain, AchainableHolder, ParamsBasicTypeWithAmountHolding},
+	get_parentchain_archive,
+	jsonrpc::{decode_hex, encode_hex, invalid_response, JsonRpcClient},
+	DataProvider, Error,
+};
+use codec::{Compact, Decode};
+use itp_storage::{
+	storage_double_map_key, storage_map_key, storage_value_key, StorageHasher, StorageProof,
+	StorageProofChecker,
+};
+use itp_types::{AccountId, Balance, BlockNumber, Header, H256};
+use lazy_static::lazy_static;
+use litentry_primitives::{Web3Network, ASSERTION_FROM_DATE};
+use log::*;
+use serde_json::Value;
+use sp_runtime::{
+	generic::{Digest, DigestItem},
+	traits::{BlakeTwo256, Header as HeaderT},
+};
+use std::{collections::BTreeMap, format, sync::Arc, vec, vec::Vec};
+
+#[cfg(feature = "std")]
+use std::sync::Mutex;
+#[cfg(feature = "sgx")]
+use std::sync::SgxMutex as Mutex;
+
+/// The chains that can be read natively: (network, archive, decimals of the native token).
+pub const NATIVE_CHAINS: [(Web3Network, DataProvider, u32); 3] = [
+	(Web3Network::Litentry, DataProvider::LitentryArchive, 12),
+	(Web3Network::Litmus, DataProvider::LitmusArchive, 12),
+	(Web3Network::Polkadot, DataProvider::PolkadotArchive, 10),
+];
+
+/// The block of a date is searched until it's closer than this to the date, the checkpoints
+/// the search is limited to can be further apart though.
+pub const DATE_TOLERANCE_MS: u64 = 3_600_000;
+/// Upper bound of the blocks read while searching the block of a date.
+pub const MAX_DATE_SEARCH_STEPS: u32 = 16;
+
+/// The referenda tracks of Polkadot, `pallet_conviction_voting` stores the votes per track.
+const GOVERNANCE_TRACKS: [u16; 16] = [0, 1, 2, 10, 11, 12, 13, 14, 15, 20, 21, 30, 31, 32, 33, 34];
+/// Ids of the locks of `pallet_conviction_voting` and `pallet_democracy`.
+const VOTING_LOCK_IDS: [[u8; 8]; 2] = [*b"pyconvot", *b"democrac"];
+/// `AccountInfo` changed its layout over time, but it always ends with the `AccountData` of
+/// `pallet_balances`: free, reserved and two more balances.
+const ACCOUNT_DATA_LEN: usize = 64;
+
+lazy_static! {
+	// (genesis hash, timestamp) -> the last block before the timestamp, None before genesis
+	static ref DATE_BLOCKS: Mutex<BTreeMap<(H256, u64), Option<VerifiedBlock>>> =
+		Mutex::new(BTreeMap::new());
+}
+
+/// Whether `network` can be read natively, which is only the case for the parentchain.
+pub fn is_native_network(network: &Web3Network) -> bool {
+	parentchain_network() == Some(*network)
+}
+
+/// The network whose archive is configured as `parentchain_archive`.
+fn parentchain_network() -> Option<Web3Network> {
+	let parentchain_archive = get_parentchain_archive().unwrap_or_else(|e| {
+		error!("Failed to get the parentchain archive: {:?}", e);
+		None
+	});
+	NATIVE_CHAINS
+		.iter()
+		.find(|(_, provider, _)| Some(*provider) == parentchain_archive)
+		.map(|(network, _, _)| *network)
+}
+
+fn native_chain(network: Web3Network) -> Result<(DataProvider, u32), Error> {
+	NATIVE_CHAINS
+		.iter()
+		.find(|(n, _, _)| *n == network)
+		.map(|(_, provider, decimals)| (*provider, *decimals))
+		.ok_or_else(|| Error::NativeError(format!("unsupported network {:?}", network)))
+}
+
+/// The JSON-RPC methods of a Substrate node the native provider relies on.
+pub trait SubstrateRpc {
+	fn block_hash(&mut self, number: BlockNumber) -> Result<Option<H256>, Error>;
+
+	fn header(&mut self, hash: H256) -> Result<Option<Header>, Error>;
+
+	fn read_proof(&mut self, keys: &[Vec<u8>], at: H256) -> Result<StorageProof, Error>;
+}
+
+impl SubstrateRpc for JsonRpcClient {
+	fn block_hash(&mut self, number: BlockNumber) -> Result<Option<H256>, Error> {
+		match self.call("chain_getBlockHash", vec![number.into()])? {
+			Value::Null => Ok(None),
+			hash => decode_h256(&hash).map(Some),
+		}
+	}
+
+	fn header(&mut self, hash: H256) -> Result<Option<Header>, Error> {
+		match self.call("chain_getHeader", vec![encode_hex(hash.as_bytes())])? {
+			Value::Null => Ok(None),
+			header => decode_header(&header).map(Some),
+		}
+	}
+
+	fn read_proof(&mut self, keys: &[Vec<u8>], at: H256) -> Result<StorageProof, Error> {
+		let keys = keys.iter().map(|key| encode_hex(key)).collect::<Vec<_>>();
+		let response =
+			self.call("state_getReadProof", vec![keys.into(), encode_hex(at.as_bytes())])?;
+		response
+			.get("proof")
+			.and_then(|proof| proof.as_array())
+			.ok_or_else(|| invalid_response("missing proof"))?
+			.iter()
+			.map(decode_hex)
+			.collect()
+	}
+}
+
+fn decode_h256(value: &Value) -> Result<H256, Error> {
+	let bytes = decode_hex(value)?;
+	if bytes.len() != 32 {
+		return Err(invalid_response("expected a 32 bytes hash"))
+	}
+	Ok(H256::from_slice(&bytes))
+}
+
+// the header is sent as JSON, it's hashed by the caller to make sure it wasn't altered
+fn decode_header(value: &Value) -> Result<Header, Error> {
+	let field = |name: &str| value.get(name).ok_or_else(|| invalid_response(name));
+
+	let number = field("number")?
+		.as_str()
+		.and_then(|number| BlockNumber::from_str_radix(number.trim_start_matches("0x"), 16).ok())
+		.ok_or_else(|| invalid_response("number"))?;
+	let logs = field("digest")?
+		.get("logs")
+		.and_then(|logs| logs.as_array())
+		.ok_or_else(|| invalid_response("digest"))?
+		.iter()
+		.map(|log| DigestItem::decode(&mut decode_hex(log)?.as_slice()).map_err(invalid_response))
+		.collect::<Result<Vec<_>, _>>()?;
+
+	Ok(Header::new(
+		number,
+		decode_h256(field("extrinsicsRoot")?)?,
+		decode_h256(field("stateRoot")?)?,
+		decode_h256(field("parentHash")?)?,
+		Digest { logs },
+	))
+}
+
+/// The checkpoints the light client of the parentchain recorded, see
+/// `pallet_parentchain::Checkpoints`.
+pub trait ParentchainCheckpoints: Send + Sync {
+	/// The numbers of the checkpoints are the multiples of it.
+	fn interval(&self) -> BlockNumber;
+
+	/// The number of the first checkpoint that was recorded.
+	fn first(&self) -> Option<BlockNumber>;
+
+	fn checkpoint(&self, number: BlockNumber) -> Option<H256>;
+}
+
+/// The block the light client of the parentchain imported last, and its checkpoints.
+#[derive(Clone)]
+pub struct ParentchainAnchor {
+	pub number: BlockNumber,
+	pub hash: H256,
+	pub checkpoints: Arc<dyn ParentchainCheckpoints>,
+}
+
+/// A block whose header matches its hash.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct VerifiedBlock {
+	number: BlockNumber,
+	hash: H256,
+	state_root: H256,
+}
+
+pub struct NativeClient<R: SubstrateRpc = JsonRpcClient> {
+	rpcs: BTreeMap<Web3Network, R>,
+	parentchain: Option<Web3Network>,
+	anchor: Option<ParentchainAnchor>,
+	genesis: BTreeMap<Web3Network, H256>,
+	heads: BTreeMap<Web3Network, VerifiedBlock>,
+}
+
+impl NativeClient<JsonRpcClient> {
+	/// Creates a client reading the configured archives, only the one configured as
+	/// `parentchain_archive` can be read, at `anchor`.
+	pub fn new(anchor: Option<ParentchainAnchor>) -> Self {
+		let parentchain = parentchain_network();
+		let rpcs = NATIVE_CHAINS
+			.iter()
+			.map(|(network, provider, _)| (*network, JsonRpcClient::new(*provider)))
+			.collect();
+
+		Self::with_rpcs(rpcs, parentchain, anchor)
+	}
+}
+
+impl<R: SubstrateRpc> NativeClient<R> {
+	pub fn with_rpcs(
+		rpcs: Vec<(Web3Network, R)>,
+		parentchain: Option<Web3Network>,
+		anchor: Option<ParentchainAnchor>,
+	) -> Self {
+		NativeClient {
+			rpcs: rpcs.into_iter().collect(),
+			parentchain,
+			anchor,
+			genesis: BTreeMap::new(),
+			heads: BTreeMap::new(),
+		}
+	}
+
+	/// The free and reserved balance of `account` at the current block.
+	pub fn current_balance(
+		&mut self,
+		network: Web3Network,
+		account: &AccountId,
+	) -> Result<Balance, Error> {
+		let head = self.head(network)?;
+		self.balance_at(network, &head, account)
+	}
+
+	/// Whether `account` has voted or delegated its votes in the referenda of `network`, or
+	/// still has a voting lock from the former democracy.
+	pub fn has_voted(&mut self, network: Web3Network, account: &AccountId) -> Result<bool, Error> {
+		let head = self.head(network)?;
+		let mut keys: Vec<Vec<u8>> = GOVERNANCE_TRACKS
+			.iter()
+			.map(|track| {
+				storage_double_map_key(
+					"ConvictionVoting",
+					"VotingFor",
+					account,
+					&StorageHasher::Twox64Concat,
+					track,
+					&StorageHasher::Twox64Concat,
+				)
+			})
+			.collect();
+		keys.push(storage_map_key("Balances", "Locks", account, &StorageHasher::Blake2_128Concat));
+
+		let mut values = self.read(network, &head, keys)?;
+		if let Some(locks) = values.pop().flatten() {
+			let locks = <Vec<([u8; 8], Balance, u8)>>::decode(&mut locks.as_slice())
+				.map_err(invalid_response)?;
+			if locks.iter().any(|(id, _, _)| VOTING_LOCK_IDS.contains(id)) {
+				return Ok(true)
+			}
+		}
+		for voting in values.into_iter().flatten() {
+			if is_voting(&voting)? {
+				return Ok(true)
+			}
+		}
+		Ok(false)
+	}
+
+	fn rpc(&mut self, network: Web3Network) -> Result<&mut R, Error> {
+		self.rpcs
+			.get_mut(&network)
+			.ok_or_else(|| Error::NativeError(format!("unsupported network {:?}", network)))
+	}
+
+	// the light client of the parentchain is the only source of trusted block hashes
+	fn anchor(&self, network: Web3Network) -> Result<ParentchainAnchor, Error> {
+		if self.parentchain != Some(network) {
+			return Err(Error::NativeError(format!(
+				"{:?} isn't the parentchain, its blocks can't be verified",
+				network
+			)))
+		}
+		self.anchor
+			.clone()
+			.ok_or_else(|| Error::NativeError("missing light client anchor".into()))
+	}
+
+	fn head(&mut self, network: Web3Network) -> Result<VerifiedBlock, Error> {
+		if let Some(head) = self.heads.get(&network) {
+			return Ok(*head)
+		}
+
+		let anchor = self.anchor(network)?;
+		let head = self.verified_block(network, anchor.hash)?;
+		if head.number != anchor.number {
+			return Err(invalid_response("anchor number mismatch"))
+		}
+		self.heads.insert(network, head);
+		Ok(head)
+	}
+
+	fn genesis(&mut self, network: Web3Network) -> Result<H256, Error> {
+		if let Some(genesis) = self.genesis.get(&network) {
+			return Ok(*genesis)
+		}
+		let genesis = self
+			.rpc(network)?
+			.block_hash(0)?
+			.ok_or_else(|| invalid_response("no genesis"))?;
+		self.genesis.insert(network, genesis);
+		Ok(genesis)
+	}
+
+	fn verified_block(&mut self, network: Web3Network, hash: H256) -> Result<VerifiedBlock, Error> {
+		let header = self
+			.rpc(network)?
+			.header(hash)?
+			.ok_or_else(|| invalid_response(format!("unknown block {:?}", hash)))?;
+		if header.hash() != hash {
+			return Err(invalid_response(format!("header doesn't match block {:?}", hash)))
+		}
+		Ok(VerifiedBlock { number: *header.number(), hash, state_root: *header.state_root() })
+	}
+
+	fn checkpoint(
+		&mut self,
+		network: Web3Network,
+		checkpoints: &dyn ParentchainCheckpoints,
+		number: BlockNumber,
+	) -> Result<VerifiedBlock, Error> {
+		let hash = checkpoints
+			.checkpoint(number)
+			.ok_or_else(|| Error::NativeError(format!("no checkpoint at block {}", number)))?;
+		let block = self.verified_block(network, hash)?;
+		if block.number != number {
+			return Err(invalid_response(format!("block {:?} isn't block {}", hash, number)))
+		}
+		Ok(block)
+	}
+
+	/// Reads `keys` at `block`, the values are checked against the state root of the block.
+	fn read(
+		&mut self,
+		network: Web3Network,
+		block: &VerifiedBlock,
+		keys: Vec<Vec<u8>>,
+	) -> Result<Vec<Option<Vec<u8>>>, Error> {
+		let proof = self.rpc(network)?.read_proof(&keys, block.hash)?;
+		let checker = StorageProofChecker::<BlakeTwo256>::new(block.state_root, proof)
+			.map_err(invalid_response)?;
+		keys.iter()
+			.map(|key| checker.read_value(key).map_err(invalid_response))
+			.collect()
+	}
+
+	fn timestamp_at(&mut self, network: Web3Network, block: &VerifiedBlock) -> Result<u64, Error> {
+		let key = storage_value_key("Timestamp", "Now");
+		let value = self
+			.read(network, block, vec![key])?
+			.pop()
+			.flatten()
+			.ok_or_else(|| invalid_response(format!("no timestamp at {:?}", block.hash)))?;
+		u64::decode(&mut value.as_slice()).map_err(invalid_response)
+	}
+
+	fn balance_at(
+		&mut self,
+		network: Web3Network,
+		block: &VerifiedBlock,
+		account: &AccountId,
+	) -> Result<Balance, Error> {
+		let key = storage_map_key("System", "Account", account, &StorageHasher::Blake2_128Concat);
+		match self.read(network, block, vec![key])?.pop().flatten() {
+			Some(info) => decode_total_balance(&info),
+			None => Ok(0),
+		}
+	}
+
+	/// The last checkpoint before `timestamp` (in ms), within `DATE_TOLERANCE_MS` if the
+	/// checkpoints are close enough. None if the chain didn't exist yet.
+	fn block_at(
+		&mut self,
+		network: Web3Network,
+		timestamp: u64,
+	) -> Result<Option<VerifiedBlock>, Error> {
+		let head = self.head(network)?;
+		let head_timestamp = self.timestamp_at(network, &head)?;
+		if timestamp >= head_timestamp {
+			return Ok(Some(head))
+		}
+
+		let cache_key = (self.genesis(network)?, timestamp);
+		if let Some(block) = DATE_BLOCKS.lock().ok().and_then(|c| c.get(&cache_key).copied()) {
+			return Ok(block)
+		}
+
+		let checkpoints = self.anchor(network)?.checkpoints;
+		let interval = checkpoints.interval().max(1);
+		let first = checkpoints
+			.first()
+			.filter(|first| *first <= head.number)
+			.ok_or_else(|| Error::NativeError("no parentchain checkpoints yet".into()))?;
+		let low = self.checkpoint(network, checkpoints.as_ref(), first)?;
+		let low_timestamp = self.timestamp_at(network, &low)?;
+		let block = if timestamp < low_timestamp {
+			// the checkpoints were only introduced later, the date can't be verified
+			if first > interval {
+				return Err(Error::NativeError(format!(
+					"no checkpoint before {} ms, the first is block {}",
+					timestamp, first
+				)))
+			}
+			// within the first interval, the date is treated as before genesis
+			None
+		} else {
+			// the checkpoints are searched by their index, the head usually isn't one and only
+			// bounds the search
+			let high_index = (head.number as u64 + interval as u64 - 1) / interval as u64;
+			Some(search_block_before(
+				timestamp,
+				((first / interval).into(), low, low_timestamp),
+				(high_index.max(first as u64 / interval as u64 + 1), head, head_timestamp),
+				|index| {
+					let number = (index * interval as u64) as BlockNumber;
+					let block = self.checkpoint(network, checkpoints.as_ref(), number)?;
+					Ok((block, self.timestamp_at(network, &block)?))
+				},
+			)?)
+		};
+
+		// historical blocks are final, the cache is only bounded by the dates that are asked for
+		if let Ok(mut cache) = DATE_BLOCKS.lock() {
+			cache.insert(cache_key, block);
+		}
+		Ok(block)
+	}
+}
+
+impl<R: SubstrateRpc> AchainableHolder for NativeClient<R> {
+	/// Whether `address` held the amount of native tokens at the date, at every later date of
+	/// `ASSERTION_FROM_DATE` and still holds it.
+	///
+	/// Checking the later dates makes the answer monotonic: holding since a date implies
+	/// holding since every later date, which the binary search over the dates relies on.
+	fn is_holder(
+		&mut self,
+		address: &str,
+		amount_holding: ParamsBasicTypeWithAmountHolding,
+	) -> Result<bool, Error> {
+		if amount_holding.token.is_some() {
+			return Err(Error::NativeError("only native tokens are supported".into()))
+		}
+		let network = NATIVE_CHAINS
+			.iter()
+			.map(|(network, _, _)| *network)
+			.find(|network| builtin_chain(network) == Some(amount_holding.chain.as_str()))
+			.ok_or_else(|| {
+				Error::NativeError(format!("unsupported chain {}", amount_holding.chain))
+			})?;
+		let (_, decimals) = native_chain(network)?;
+		let amount = parse_amount(&amount_holding.amount, decimals)?;
+		// e.g. an evm address, it can't hold native tokens
+		let account = match parse_account(address) {
+			Ok(account) => account,
+			Err(e) => {
+				debug!("Skip {}: {:?}", address, e);
+				return Ok(false)
+			},
+		};
+		let since = date_to_timestamp_ms(&amount_holding.date)?;
+
+		if self.current_balance(network, &account)? < amount {
+			return Ok(false)
+		}
+		// the latest dates first, they're the cheapest to fail on
+		let mut dates = ASSERTION_FROM_DATE
+			.iter()
+			.map(|date| date_to_timestamp_ms(date))
+			.collect::<Result<Vec<_>, _>>()?;
+		dates.retain(|date| *date > since);
+		dates.push(since);
+		dates.sort_unstable_by(|a, b| b.cmp(a));
+		for date in dates {
+			match self.block_at(network, date)? {
+				Some(block) if self.balance_at(network, &block, &account)? >= amount => {},
+				_ => return Ok(false),
+			}
+		}
+		Ok(true)
+	}
+}
+
+/// Interpolation search of the last block before `timestamp` (in ms), within
+/// `DATE_TOLERANCE_MS`. `low` and `high` are (number, block, timestamp) with
+/// `low <= timestamp < high`, `block_at` fetches a block and its timestamp by number.
+pub(crate) fn search_block_before<B: Copy>(
+	timestamp: u64,
+	low: (u64, B, u64),
+	high: (u64, B, u64),
+	mut block_at: impl FnMut(u64) -> Result<(B, u64), Error>,
+) -> Result<B, Error> {
+	let ((mut low_number, mut low, mut low_timestamp), (mut high_number, _, mut high_timestamp)) =
+		(low, high);
+	// the block time is mostly constant, so interpolating converges quickly
+	for _ in 0..MAX_DATE_SEARCH_STEPS {
+		if high_number - low_number <= 1 || timestamp - low_timestamp <= DATE_TOLERANCE_MS {
+			break
+		}
+		let estimate = low_number as u128
+			+ (high_number - low_number) as u128 * (timestamp - low_timestamp) as u128
+				/ (high_timestamp - low_timestamp).max(1) as u128;
+		let number = (estimate as u64).clamp(low_number + 1, high_number - 1);
+		let (block, block_timestamp) = block_at(number)?;
+		if block_timestamp <= timestamp {
+			(low_number, low, low_timestamp) = (number, block, block_timestamp);
+		} else {
+			(high_number, high_timestamp) = (number, block_timestamp);
+		}
+	}
+	Ok(low)
+}
+
+fn is_voting(voting: &[u8]) -> Result<bool, Error> {
+	let input = &mut &voting[..];
+	match u8::decode(input).map_err(invalid_response)? {
+		// Casting { votes, delegations, prior }
+		0 => {
+			let votes = <Compact<u32>>::decode(input).map_err(invalid_response)?.0;
+			if votes > 0 {
+				return Ok(true)
+			}
+			// the votes are removed once unlocked, the prior lock is what's left until then
+			let (_delegations, (_unlock_at, prior)) =
+				<((Balance, Balance), (BlockNumber, Balance))>::decode(input)
+					.map_err(invalid_response)?;
+			Ok(prior > 0)
+		},
+		// Delegating
+		1 => Ok(true),
+		_ => Err(invalid_response("unknown voting")),
+	}
+}
+
+fn decode_total_balance(info: &[u8]) -> Result<Balance, Error> {
+	let data = info
+		.len()
+		.checked_sub(ACCOUNT_DATA_LEN)
+		.map(|start| &info[start..])
+		.ok_or_else(|| invalid_response("account info too short"))?;
+	let (free, reserved) =
+		<(Balance, Balance)>::decode(&mut &data[..]).map_err(invalid_response)?;
+	Ok(free.saturating_add(reserved))
+}
+
+/// Parses a `0x` prefixed hex account, as produced by `transpose_identity`.
+pub fn parse_account(address: &str) -> Result<AccountId, Error> {
+	let bytes = hex::decode(address.trim_start_matches("0x"))
+		.map_err(|e| Error::NativeError(format!("invalid account {}: {:?}", address, e)))?;
+	<[u8; 32]>::try_from(bytes.as_slice())
+		.map(AccountId::from)
+		.map_err(|_| Error::NativeError(format!("invalid account {}", address)))
+}
+
+/// Converts a decimal amount of tokens, e.g. `0.5`, into the smallest unit.
+pub fn parse_amount(amount: &str, decimals: u32) -> Result<Balance, Error> {
+	let invalid = || Error::NativeError(format!("invalid amount {}", amount));
+	let (integer, fraction) = amount.trim().split_once('.').unwrap_or((amount.trim(), ""));
+	if (integer.is_empty() && fraction.is_empty())
+		|| fraction.len() > decimals as usize
+		|| !integer.bytes().chain(fraction.bytes()).all(|b| b.is_ascii_digit())
+	{
+		return Err(invalid())
+	}
+
+	let parse = |digits: &str| -> Result<Balance, Error> {
+		if digits.is_empty() {
+			Ok(0)
+		} else {
+			digits.parse().map_err(|_| invalid())
+		}
+	};
+	// can't overflow, the fraction has at most `decimals` digits
+	let fraction = parse(fraction)? * 10u128.pow(decimals - fraction.len() as u32);
+	parse(integer)?
+		.checked_mul(10u128.pow(decimals))
+		.and_then(|integer| integer.checked_add(fraction))
+		.ok_or_else(invalid)
+}
+
+/// Milliseconds since the unix epoch of a `YYYY-MM-DD` date, as in `ASSERTION_FROM_DATE`.
+pub fn date_to_timestamp_ms(date: &str) -> Result<u64, Error> {
+	let invalid = || Error::NativeError(format!("invalid date {}", date));
+	let mut parts = date.splitn(3, '-').map(|part| part.parse::<u32>().map_err(|_| invalid()));
+	let (year, month, day) = match (parts.next(), parts.next(), parts.next()) {
+		(Some(year), Some(month), Some(day)) => (year?, month?, day?),
+		_ => return Err(invalid()),
+	};
+	if year < 1970 || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+		return Err(invalid())
+	}
+
+	// see http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+	let year = if month <= 2 { year - 1 } else { year } as u64;
+	let (era, yoe) = (year / 400, year % 400);
+	let doy = (153 * ((month as u64 + 9) % 12) + 2) / 5 + day as u64 - 1;
+	let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+	let days = era * 146_097 + doe - 719_468;
+	Ok(days * 86_400_000)
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::GLOBAL_DATA_PROVIDER_CONFIG;
+	use codec::Encode;
+	use litentry_primitives::Web3NetworkRegistry;
+	use sp_runtime::traits::Hash;
+	use sp_state_machine::{backend::Backend, new_in_mem, prove_read};
+	use sp_trie::HashKey;
+
+	const DAY_MS: u64 = 86_400_000;
+
+	fn alice() -> AccountId {
+		AccountId::from([1u8; 32])
+	}
+
+	fn bob() -> AccountId {
+		AccountId::from([2u8; 32])
+	}
+
+	fn account_info(free: Balance, reserved: Balance) -> Vec<u8> {
+		// nonce, consumers, providers, sufficients, free, reserved, frozen, flags
+		(0u32, 0u32, 1u32, 0u32, free, reserved, 0 as Balance, 0 as Balance).encode()
+	}
+
+	type State = BTreeMap<Vec<u8>, Vec<u8>>;
+
+	fn state_root(state: &State) -> H256 {
+		let mut backend = new_in_mem::<BlakeTwo256, HashKey<BlakeTwo256>>();
+		backend.insert(
+			vec![(None, state.iter().map(|(k, v)| (k.clone(), Some(v.clone()))).collect())],
+			Default::default(),
+		);
+		backend.storage_root(std::iter::empty(), Default::default()).0
+	}
+
+	fn prove(state: &State, keys: &[Vec<u8>]) -> StorageProof {
+		let mut backend = new_in_mem::<BlakeTwo256, HashKey<BlakeTwo256>>();
+		backend.insert(
+			vec![(None, state.iter().map(|(k, v)| (k.clone(), Some(v.clone()))).collect())],
+			Default::default(),
+		);
+		prove_read(backend, keys.iter().map(|k| k.as_slice()))
+			.unwrap()
+			.iter_nodes()
+			.cloned()
+			.collect()
+	}
+
+	// Records the state of every block and serves read proofs of it.
+	struct MockRpc {
+		blocks: Vec<(Header, State)>,
+		tamper_headers: bool,
+	}
+
+	#[derive(Default)]
+	struct MockCheckpoints {
+		interval: BlockNumber,
+		hashes: BTreeMap<BlockNumber, H256>,
+	}
+
+	impl ParentchainCheckpoints for MockCheckpoints {
+		fn interval(&self) -> BlockNumber {
+			self.interval
+		}
+
+		fn first(&self) -> Option<BlockNumber> {
+			self.hashes.keys().next().copied()
+		}
+
+		fn checkpoint(&self, number: BlockNumber) -> Option<H256> {
+			self.hashes.get(&number).copied()
+		}
+	}
+
+	impl MockRpc {
+		// `seed` distinguishes the genesis of the chains, as the date blocks are cached
+		// globally per genesis
+		fn new(seed: &[u8]) -> Self {
+			let mut rpc = MockRpc { blocks: vec![], tamper_headers: false };
+			rpc.push_block(vec![(b":seed".to_vec(), seed.to_vec())]);
+			rpc
+		}
+
+		fn push_block(&mut self, changes: Vec<(Vec<u8>, Vec<u8>)>) {
+			let (mut state, parent_hash) = match self.blocks.last() {
+				Some((header, state)) => (state.clone(), header.hash()),
+				None => (State::new(), Default::default()),
+			};
+			state.extend(changes);
+			let header = Header::new(
+				self.blocks.len() as BlockNumber,
+				BlakeTwo256::hash(b"extrinsics"),
+				state_root(&state),
+				parent_hash,
+				Default::default(),
+			);
+			self.blocks.push((header, state));
+		}
+
+		fn push_block_at(&mut self, timestamp: u64, balances: Vec<(AccountId, Balance)>) {
+			let mut changes = vec![(storage_value_key("Timestamp", "Now"), timestamp.encode())];
+			for (account, free) in balances {
+				let key = storage_map_key(
+					"System",
+					"Account",
+					&account,
+					&StorageHasher::Blake2_128Concat,
+				);
+				changes.push((key, account_info(free, 0)));
+			}
+			self.push_block(changes);
+		}
+
+		fn hash(&self, number: usize) -> H256 {
+			self.blocks[number].0.hash()
+		}
+
+		// the light client imported block `number`, and recorded the checkpoints since `from`
+		fn anchor(&self, number: usize, interval: BlockNumber, from: usize) -> ParentchainAnchor {
+			let hashes = (from..=number)
+				.filter(|n| *n > 0 && *n as BlockNumber % interval == 0)
+				.map(|n| (n as BlockNumber, self.hash(n)))
+				.collect();
+			ParentchainAnchor {
+				number: number as BlockNumber,
+				hash: self.hash(number),
+				checkpoints: Arc::new(MockCheckpoints { interval, hashes }),
+			}
+		}
+
+		// a client reading the chain as the parentchain, anchored at its last block
+		fn into_client(self, interval: BlockNumber) -> NativeClient<MockRpc> {
+			let anchor = self.anchor(self.blocks.len() - 1, interval, 0);
+			NativeClient::with_rpcs(
+				vec![(Web3Network::Litentry, self)],
+				Some(Web3Network::Litentry),
+				Some(anchor),
+			)
+		}
+	}
+
+	impl SubstrateRpc for MockRpc {
+		fn block_hash(&mut self, number: BlockNumber) -> Result<Option<H256>, Error> {
+			Ok(self.blocks.get(number as usize).map(|(header, _)| header.hash()))
+		}
+
+		fn header(&mut self, hash: H256) -> Result<Option<Header>, Error> {
+			let mut header =
+				self.blocks.iter().find(|(h, _)| h.hash() == hash).map(|(h, _)| h.clone());
+			if self.tamper_headers {
+				header.iter_mut().for_each(|h| h.set_state_root(Default::default()));
+			}
+			Ok(header)
+		}
+
+		fn read_proof(&mut self, keys: &[Vec<u8>], at: H256) -> Result<StorageProof, Error> {
+			let (_, state) = self.blocks.iter().find(|(h, _)| h.hash() == at).unwrap();
+			Ok(prove(state, keys))
+		}
+	}
+
+	// a block every 30 days since 2021-01-01, alice holds 10 from 2021-12-01 on,
+	// bob held 10 until 2022-07-01
+	fn holder_chain(seed: &[u8]) -> MockRpc {
+		let start = date_to_timestamp_ms("2021-01-01").unwrap();
+		let (alice_from, bob_until) = (
+			date_to_timestamp_ms("2021-12-01").unwrap(),
+			date_to_timestamp_ms("2022-07-01").unwrap(),
+		);
+		let mut rpc = MockRpc::new(seed);
+		for i in 0..36 {
+			let timestamp = start + i * 30 * DAY_MS;
+			let alice_balance = if timestamp >= alice_from { 10 } else { 0 };
+			let bob_balance = if timestamp < bob_until { 10 } else { 0 };
+			rpc.push_block_at(timestamp, vec![(alice(), alice_balance), (bob(), bob_balance)]);
+		}
+		rpc
+	}
+
+	fn holding(date: &str) -> ParamsBasicTypeWithAmountHolding {
+		// 10 units of a token with 12 decimals
+		ParamsBasicTypeWithAmountHolding::new(
+			&Web3Network::Litentry,
+			"0.00000000001".into(),
+			date.into(),
+			None,
+			&Web3NetworkRegistry::new(),
+		)
+		.unwrap()
+	}
+
+	fn address(account: &AccountId) -> String {
+		format!("0x{}", hex::encode(account))
+	}
+
+	#[test]
+	fn is_holder_works() {
+		let mut client = holder_chain(b"1").into_client(1);
+
+		assert!(client.is_holder(&address(&alice()), holding("2022-07-01")).unwrap());
+		assert!(client.is_holder(&address(&alice()), holding("2022-01-01")).unwrap());
+		assert!(!client.is_holder(&address(&alice()), holding("2021-07-01")).unwrap());
+		// before genesis
+		assert!(!client.is_holder(&address(&alice()), holding("2017-01-01")).unwrap());
+		// doesn't hold anymore
+		assert!(!client.is_holder(&address(&bob()), holding("2021-07-01")).unwrap());
+	}
+
+	#[test]
+	fn is_holder_requires_continuous_holding() {
+		// carol holds 10 except between 2022-06-01 and 2022-08-01
+		let carol = AccountId::from([7u8; 32]);
+		let start = date_to_timestamp_ms("2021-01-01").unwrap();
+		let (gap_from, gap_until) = (
+			date_to_timestamp_ms("2022-06-01").unwrap(),
+			date_to_timestamp_ms("2022-08-01").unwrap(),
+		);
+		let mut rpc = MockRpc::new(b"6");
+		for i in 0..36 {
+			let timestamp = start + i * 30 * DAY_MS;
+			let balance = if (gap_from..gap_until).contains(&timestamp) { 0 } else { 10 };
+			rpc.push_block_at(timestamp, vec![(carol.clone(), balance)]);
+		}
+		let mut client = rpc.into_client(1);
+
+		// it held at both 2021-07-01 and now, but not at 2022-07-01 in between
+		assert!(!client.is_holder(&address(&carol), holding("2021-07-01")).unwrap());
+		assert!(client.is_holder(&address(&carol), holding("2023-01-01")).unwrap());
+	}
+
+	#[test]
+	fn block_at_searches_checkpoints() {
+		let rpc = holder_chain(b"7");
+		let mut client = rpc.into_client(4);
+		let block = client
+			.block_at(Web3Network::Litentry, date_to_timestamp_ms("2022-01-01").unwrap())
+			.unwrap()
+			.unwrap();
+		// block 13 is the last before the date, 12 the last checkpoint
+		assert_eq!(block.number, 12);
+		let block = client
+			.block_at(Web3Network::Litentry, date_to_timestamp_ms("2022-04-01").unwrap())
+			.unwrap()
+			.unwrap();
+		// block 16 is the last before the date and a checkpoint
+		assert_eq!(block.number, 16);
+	}
+
+	#[test]
+	fn block_at_requires_checkpoints() {
+		let rpc = holder_chain(b"8");
+		// the checkpoints were recorded from block 20 on
+		let anchor = rpc.anchor(36, 2, 20);
+		let mut client = NativeClient::with_rpcs(
+			vec![(Web3Network::Litentry, rpc)],
+			Some(Web3Network::Litentry),
+			Some(anchor),
+		);
+		assert!(client
+			.block_at(Web3Network::Litentry, date_to_timestamp_ms("2022-09-01").unwrap())
+			.unwrap()
+			.is_some());
+		assert!(matches!(
+			client.block_at(Web3Network::Litentry, date_to_timestamp_ms("2021-07-01").unwrap()),
+			Err(Error::NativeError(_))
+		));
+	}
+
+	#[test]
+	fn is_holder_rejects_unsupported_queries() {
+		let mut client = holder_chain(b"2").into_client(1);
+		let address = address(&alice());
+
+		let mut erc20 = holding("2022-01-01");
+		erc20.token = Some("0xb59490ab09a0f526cc7305822ac65f2ab12f9723".into());
+		assert!(matches!(client.is_holder(&address, erc20), Err(Error::NativeError(_))));
+
+		let ethereum = ParamsBasicTypeWithAmountHolding::new(
+			&Web3Network::Ethereum,
+			"1".into(),
+			"2022-01-01".into(),
+			None,
+			&Web3NetworkRegistry::new(),
+		)
+		.unwrap();
+		assert!(matches!(client.is_holder(&address, ethereum), Err(Error::NativeError(_))));
+
+		// polkadot isn't the parentchain
+		let polkadot = ParamsBasicTypeWithAmountHolding::new(
+			&Web3Network::Polkadot,
+			"1".into(),
+			"2022-01-01".into(),
+			None,
+			&Web3NetworkRegistry::new(),
+		)
+		.unwrap();
+		assert!(matches!(client.is_holder(&address, polkadot), Err(Error::NativeError(_))));
+
+		let evm_address = "0xb59490ab09a0f526cc7305822ac65f2ab12f9723";
+		assert!(!client.is_holder(evm_address, holding("2022-01-01")).unwrap());
+	}
+
+	#[test]
+	fn tampered_header_is_rejected() {
+		let mut rpc = holder_chain(b"3");
+		rpc.tamper_headers = true;
+		let mut client = rpc.into_client(1);
+		assert!(matches!(
+			client.current_balance(Web3Network::Litentry, &alice()),
+			Err(Error::NativeError(_))
+		));
+	}
+
+	#[test]
+	fn parentchain_is_read_at_anchor() {
+		let rpc = holder_chain(b"4");
+		// bob still holds at block 10
+		let anchor = rpc.anchor(10, 1, 0);
+		let mut client = NativeClient::with_rpcs(
+			vec![(Web3Network::Litentry, rpc)],
+			Some(Web3Network::Litentry),
+			Some(anchor.clone()),
+		);
+		assert_eq!(client.current_balance(Web3Network::Litentry, &bob()).unwrap(), 10);
+
+		// only the parentchain can be read
+		let mut client =
+			NativeClient::with_rpcs(vec![(Web3Network::Litentry, holder_chain(b"4"))], None, None);
+		assert!(client.current_balance(Web3Network::Litentry, &bob()).is_err());
+
+		// the anchor must be known to the archive
+		let unknown = ParentchainAnchor { hash: H256::repeat_byte(1), ..anchor };
+		let mut client = NativeClient::with_rpcs(
+			vec![(Web3Network::Litentry, holder_chain(b"4"))],
+			Some(Web3Network::Litentry),
+			Some(unknown),
+		);
+		assert!(client.current_balance(Web3Network::Litentry, &bob()).is_err());
+
+		let mut client = NativeClient::with_rpcs(
+			vec![(Web3Network::Litentry, holder_chain(b"4"))],
+			Some(Web3Network::Litentry),
+			None,
+		);
+		assert!(client.current_balance(Web3Network::Litentry, &bob()).is_err());
+	}
+
+	#[test]
+	fn has_voted_works() {
+		let (casting, delegating, unlocking, locked) = (
+			AccountId::from([3u8; 32]),
+			AccountId::from([4u8; 32]),
+			AccountId::from([5u8; 32]),
+			AccountId::from([6u8; 32]),
+		);
+		let voting_for = |account: &AccountId, track: u16| {
+			storage_double_map_key(
+				"ConvictionVoting",
+				"VotingFor",
+				account,
+				&StorageHasher::Twox64Concat,
+				&track,
+				&StorageHasher::Twox64Concat,
+			)
+		};
+		// Casting with a standard aye vote on referendum 7
+		let casting_votes = (
+			0u8,
+			vec![(7u32, 0u8, 0x80u8, 100 as Balance)],
+			(0 as Balance, 0 as Balance),
+			(0u32, 0 as Balance),
+		);
+		// Delegating to alice
+		let delegating_votes =
+			(1u8, 100 as Balance, alice(), 1u8, (0 as Balance, 0 as Balance), (0u32, 0 as Balance));
+		// Casting without votes, but a prior lock
+		let unlocking_votes =
+			(0u8, Vec::<u8>::new(), (0 as Balance, 0 as Balance), (99u32, 100 as Balance));
+		let locks = vec![(*b"democrac", 100 as Balance, 2u8)];
+		let staking_locks = vec![(*b"staking ", 100 as Balance, 2u8)];
+
+		let mut rpc = MockRpc::new(b"5");
+		rpc.push_block(vec![
+			(voting_for(&casting, 33), casting_votes.encode()),
+			(voting_for(&delegating, 0), delegating_votes.encode()),
+			(voting_for(&unlocking, 11), unlocking_votes.encode()),
+			(
+				storage_map_key("Balances", "Locks", &locked, &StorageHasher::Blake2_128Concat),
+				locks.encode(),
+			),
+			(
+				storage_map_key("Balances", "Locks", &bob(), &StorageHasher::Blake2_128Concat),
+				staking_locks.encode(),
+			),
+		]);
+		// read as if it were the parentchain
+		let anchor = rpc.anchor(1, 1, 0);
+		let mut client = NativeClient::with_rpcs(
+			vec![(Web3Network::Polkadot, rpc)],
+			Some(Web3Network::Polkadot),
+			Some(anchor),
+		);
+
+		for account in [casting, delegating, unlocking, locked] {
+			assert!(client.has_voted(Web3Network::Polkadot, &account).unwrap());
+		}
+		assert!(!client.has_voted(Web3Network::Polkadot, &bob()).unwrap());
+		assert!(!client.has_voted(Web3Network::Polkadot, &alice()).unwrap());
+	}
+
+	#[test]
+	fn decode_header_works() {
+		let header = Header::new(
+			26,
+			H256::repeat_byte(1),
+			H256::repeat_byte(2),
+			H256::repeat_byte(3),
+			Digest { logs: vec![DigestItem::Other(vec![1, 2, 3])] },
+		);
+		let json = serde_json::json!({
+			"parentHash": format!("0x{}", hex::encode([3u8; 32])),
+			"number": "0x1a",
+			"stateRoot": format!("0x{}", hex::encode([2u8; 32])),
+			"extrinsicsRoot": format!("0x{}", hex::encode([1u8; 32])),
+			"digest": { "logs": [format!("0x{}", hex::encode(DigestItem::Other(vec![1, 2, 3]).encode()))] }
+		});
+		assert_eq!(decode_header(&json).unwrap().hash(), header.hash());
+		assert!(decode_header(&serde_json::json!({ "number": "0x1a" })).is_err());
+	}
+
+	#[test]
+	fn decode_total_balance_works() {
+		assert_eq!(decode_total_balance(&account_info(7, 3)).unwrap(), 10);
+		// the layout before `sufficients` was added
+		let info = (0u32, 0u32, 1u32, 7 as Balance, 3 as Balance, 0 as Balance, 0 as Balance);
+		assert_eq!(decode_total_balance(&info.encode()).unwrap(), 10);
+		assert!(decode_total_balance(&[0u8; 10]).is_err());
+	}
+
+	#[test]
+	fn parse_amount_works() {
+		assert_eq!(parse_amount("1", 12).unwrap(), 1_000_000_000_000);
+		assert_eq!(parse_amount("0.5", 10).unwrap(), 5_000_000_000);
+		assert_eq!(parse_amount(".25", 2).unwrap(), 25);
+		assert_eq!(parse_amount("12.", 2).unwrap(), 1_200);
+		assert!(parse_amount("0.001", 2).is_err());
+		assert!(parse_amount("", 2).is_err());
+		assert!(parse_amount(".", 2).is_err());
+		assert!(parse_amount("-1", 2).is_err());
+		assert!(parse_amount("1e5", 2).is_err());
+		assert!(parse_amount("340282366920938463463374607431768211455", 12).is_err());
+	}
+
+	#[test]
+	fn date_to_timestamp_ms_works() {
+		assert_eq!(date_to_timestamp_ms("1970-01-01").unwrap(), 0);
+		assert_eq!(date_to_timestamp_ms("2017-01-01").unwrap(), 1_483_228_800_000);
+		assert_eq!(date_to_timestamp_ms("2020-03-01").unwrap(), 1_583_020_800_000);
+		assert!(date_to_timestamp_ms("2017-13-01").is_err());
+		assert!(date_to_timestamp_ms("2017-01").is_err());
+	}
+
+	// Reads the balance of //Alice from a local dev node, e.g.
+	// NATIVE_DEV_NODE_URL=http://localhost:9933 cargo test -- --ignored
+	#[test]
+	#[ignore]
+	fn dev_node_works() {
+		let url = std::env::var("NATIVE_DEV_NODE_URL").unwrap();
+		GLOBAL_DATA_PROVIDER_CONFIG.write().unwrap().litentry_archive.endpoints = vec![url];
+		let alice =
+			parse_account("0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d")
+				.unwrap();
+
+		// the finalized head stands in for the light client
+		let mut rpc = JsonRpcClient::new(DataProvider::LitentryArchive);
+		let hash = decode_h256(&rpc.call("chain_getFinalizedHead", vec![]).unwrap()).unwrap();
+		let number = *rpc.header(hash).unwrap().unwrap().number();
+		let anchor =
+			ParentchainAnchor { number, hash, checkpoints: Arc::new(MockCheckpoints::default()) };
+		let mut client = NativeClient::with_rpcs(
+			vec![(Web3Network::Litentry, rpc)],
+			Some(Web3Network::Litentry),
+			Some(anchor),
+		);
+		assert!(client.current_balance(Web3Network::Litentry, &alice).unwrap() > 0);
+	}
+}
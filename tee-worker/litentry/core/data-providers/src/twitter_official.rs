@@ -17,14 +17,10 @@
 #[cfg(all(not(feature = "std"), feature = "sgx"))]
 use crate::sgx_reexport_prelude::*;
 
-use crate::{build_client, vec_to_string, Error, HttpError, UserInfo, GLOBAL_DATA_PROVIDER_CONFIG};
-use http::header::{AUTHORIZATION, CONNECTION};
+use crate::{vec_to_string, DataProvider, DataProviderClient, Error, HttpError, UserInfo};
+use http::header::CONNECTION;
 use http_req::response::Headers;
-use itc_rest_client::{
-	http_client::{DefaultSend, HttpClient},
-	rest_client::RestClient,
-	RestGet, RestPath,
-};
+use itc_rest_client::RestPath;
 use log::*;
 use serde::{Deserialize, Serialize};
 use std::{
@@ -130,7 +126,7 @@ impl UserInfo for Tweet {
 }
 
 pub struct TwitterOfficialClient {
-	client: RestClient<HttpClient<DefaultSend>>,
+	client: DataProviderClient,
 }
 
 pub enum TargetUser {
@@ -158,24 +154,7 @@ impl TwitterOfficialClient {
 	pub fn v2() -> Self {
 		let mut headers = Headers::new();
 		headers.insert(CONNECTION.as_str(), "close");
-		headers.insert(
-			AUTHORIZATION.as_str(),
-			GLOBAL_DATA_PROVIDER_CONFIG
-				.read()
-				.unwrap()
-				.twitter_auth_token_v2
-				.clone()
-				.as_str(),
-		);
-		let client = build_client(
-			GLOBAL_DATA_PROVIDER_CONFIG
-				.read()
-				.unwrap()
-				.twitter_official_url
-				.clone()
-				.as_str(),
-			headers.clone(),
-		);
+		let client = DataProviderClient::new(DataProvider::TwitterOfficial, headers);
 
 		TwitterOfficialClient { client }
 	}
@@ -190,8 +169,7 @@ impl TwitterOfficialClient {
 
 		let resp = self
 			.client
-			.get_with::<String, TwitterAPIV2Response<Tweet>>(path, query.as_slice())
-			.map_err(|e| Error::RequestError(format!("{:?}", e)))?;
+			.get_with::<String, TwitterAPIV2Response<Tweet>>(path, query.as_slice())?;
 
 		let mut tweet = resp.data.ok_or(Error::RequestError("tweet not found".into()))?;
 
@@ -217,10 +195,7 @@ impl TwitterOfficialClient {
 		let path = format!("/2/tweets/{}/retweeted_by", original_tweet_id);
 		let query: Vec<(&str, &str)> = vec![("max_results", "100")];
 
-		let resp = self
-			.client
-			.get_with::<String, Retweeted>(path, query.as_slice())
-			.map_err(|e| Error::RequestError(format!("{:?}", e)))?;
+		let resp = self.client.get_with::<String, Retweeted>(path, query.as_slice())?;
 
 		Ok(resp)
 	}
@@ -231,13 +206,10 @@ impl TwitterOfficialClient {
 		debug!("Twitter query user by name, name: {}", user);
 
 		let query = vec![("user.fields", "public_metrics")];
-		let resp = self
-			.client
-			.get_with::<String, TwitterAPIV2Response<TwitterUser>>(
-				format!("/2/users/by/username/{}", user),
-				query.as_slice(),
-			)
-			.map_err(|e| Error::RequestError(format!("{:?}", e)))?;
+		let resp = self.client.get_with::<String, TwitterAPIV2Response<TwitterUser>>(
+			format!("/2/users/by/username/{}", user),
+			query.as_slice(),
+		)?;
 
 		let user = resp.data.ok_or_else(|| Error::RequestError("user not found".to_string()))?;
 		Ok(user)
@@ -249,13 +221,10 @@ impl TwitterOfficialClient {
 		debug!("Twitter query user by id, id: {}", id);
 
 		let query = vec![("user.fields", "public_metrics")];
-		let resp = self
-			.client
-			.get_with::<String, TwitterAPIV2Response<TwitterUser>>(
-				format!("/2/users/{}", id),
-				query.as_slice(),
-			)
-			.map_err(|e| Error::RequestError(format!("{:?}", e)))?;
+		let resp = self.client.get_with::<String, TwitterAPIV2Response<TwitterUser>>(
+			format!("/2/users/{}", id),
+			query.as_slice(),
+		)?;
 
 		let user = resp.data.ok_or_else(|| Error::RequestError("user not found".to_string()))?;
 		Ok(user)
@@ -265,13 +234,14 @@ impl TwitterOfficialClient {
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::GLOBAL_DATA_PROVIDER_CONFIG;
 	use lc_mock_server::{default_getter, run};
 	use std::sync::Arc;
 
 	fn init() {
 		let _ = env_logger::builder().is_test(true).try_init();
 		let url = run(Arc::new(default_getter), 0).unwrap();
-		GLOBAL_DATA_PROVIDER_CONFIG.write().unwrap().set_twitter_official_url(url);
+		GLOBAL_DATA_PROVIDER_CONFIG.write().unwrap().twitter_official.endpoints = vec![url];
 	}
 
 	#[test]
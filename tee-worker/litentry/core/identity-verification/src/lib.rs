@@ -34,8 +34,11 @@ pub mod sgx_reexport_prelude {
 compile_error!("feature \"std\" and feature \"sgx\" cannot be enabled at the same time");
 
 use frame_support::pallet_prelude::*;
+use itp_time_utils::now_as_millis;
 use lc_stf_task_sender::IdentityVerificationRequest;
-use litentry_primitives::ValidationData;
+use litentry_primitives::{
+	Identity, LinkProof, UserShieldingKeyType, ValidationData, Web3NetworkRegistry,
+};
 
 mod web2;
 mod web3;
@@ -43,11 +46,98 @@ mod web3;
 mod error;
 use error::{Error, Result};
 
-pub fn verify(r: &IdentityVerificationRequest) -> Result<()> {
+/// `mrenclave` is the measurement of this enclave, EIP-712 signatures are bound to it.
+/// `registry` has the networks registered on the parentchain of the shard of `r`.
+pub fn verify(
+	r: &IdentityVerificationRequest,
+	mrenclave: &[u8; 32],
+	registry: &Web3NetworkRegistry,
+) -> Result<()> {
 	match &r.validation_data {
 		ValidationData::Web2(data) =>
 			web2::verify(&r.who, &r.identity, r.sidechain_nonce, r.key, r.key_nonce, data),
 		ValidationData::Web3(data) =>
-			web3::verify(&r.who, &r.identity, r.sidechain_nonce, r.key, r.key_nonce, data),
+			web3::verify(r, mrenclave, registry, data, Some(now_as_millis())),
+	}
+}
+
+/// Verifies again the signature `identity` was linked to `who` with, e.g. when an exported
+/// IDGraph is imported. A link message has long expired by then, so its time isn't checked.
+/// `key` is the current user shielding key of `who`, the legacy raw message is bound to it.
+pub fn verify_link_proof(
+	who: &Identity,
+	identity: &Identity,
+	proof: &LinkProof,
+	key: &UserShieldingKeyType,
+	registry: &Web3NetworkRegistry,
+) -> Result<()> {
+	let r = IdentityVerificationRequest {
+		shard: proof.shard,
+		who: who.clone(),
+		identity: identity.clone(),
+		validation_data: ValidationData::Web3(proof.validation_data.clone()),
+		web3networks: proof.web3networks.clone(),
+		sidechain_nonce: proof.sidechain_nonce,
+		key_nonce: proof.key_nonce,
+		key: *key,
+		top_hash: Default::default(),
+		req_ext_hash: Default::default(),
+	};
+	web3::verify(&r, &proof.mrenclave, registry, &proof.validation_data, None)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ita_stf::helpers::get_expected_raw_message;
+	use litentry_primitives::{
+		LitentryMultiSignature, Web3CommonValidationData, Web3Network, Web3ValidationData,
+	};
+	use sp_core::{sr25519, Pair};
+
+	const KEY: UserShieldingKeyType = [7u8; 32];
+
+	fn who() -> Identity {
+		Identity::Substrate([1u8; 32].into())
+	}
+
+	// the proof `pair` linked its account with, while the user shielding key was `KEY`
+	fn link_proof(pair: &sr25519::Pair) -> LinkProof {
+		let identity = Identity::Substrate(pair.public().into());
+		let raw_msg = get_expected_raw_message(&who(), &identity, 3, KEY, [5u8; 12]);
+		LinkProof {
+			shard: [4u8; 32].into(),
+			mrenclave: [6u8; 32],
+			sidechain_nonce: 3,
+			key_nonce: [5u8; 12],
+			web3networks: vec![Web3Network::Litentry],
+			validation_data: Web3ValidationData::Substrate(Web3CommonValidationData {
+				message: raw_msg.clone().try_into().unwrap(),
+				signature: LitentryMultiSignature::Sr25519(pair.sign(&raw_msg)),
+			}),
+		}
+	}
+
+	#[test]
+	fn verify_link_proof_works() {
+		let pair = sr25519::Pair::from_seed(&[8u8; 32]);
+		let identity = Identity::Substrate(pair.public().into());
+		let proof = link_proof(&pair);
+		let registry = Web3NetworkRegistry::new();
+
+		assert!(verify_link_proof(&who(), &identity, &proof, &KEY, &registry).is_ok());
+
+		// the legacy raw message is bound to the user shielding key
+		assert!(verify_link_proof(&who(), &identity, &proof, &[0u8; 32], &registry).is_err());
+		// and to the nonces, `who` and the identity
+		let replayed = LinkProof { sidechain_nonce: 4, ..proof.clone() };
+		assert!(verify_link_proof(&who(), &identity, &replayed, &KEY, &registry).is_err());
+		let other_key_nonce = LinkProof { key_nonce: [6u8; 12], ..proof.clone() };
+		assert!(verify_link_proof(&who(), &identity, &other_key_nonce, &KEY, &registry).is_err());
+		let other_who = Identity::Substrate([2u8; 32].into());
+		assert!(verify_link_proof(&other_who, &identity, &proof, &KEY, &registry).is_err());
+		let other_identity =
+			Identity::Substrate(sr25519::Pair::from_seed(&[9u8; 32]).public().into());
+		assert!(verify_link_proof(&who(), &other_identity, &proof, &KEY, &registry).is_err());
 	}
 }
@@ -29,12 +29,13 @@ use itp_sgx_crypto::ShieldingCryptoDecrypt;
 use itp_types::Index;
 use lc_data_providers::{
 	discord_official::{DiscordMessage, DiscordOfficialClient},
+	github_official::{GithubGist, GithubIssueComment, GithubOfficialClient},
 	twitter_official::{Tweet, TwitterOfficialClient},
 	UserInfo,
 };
 use litentry_primitives::{
-	DiscordValidationData, ErrorDetail, Identity, IntoErrorDetail, TwitterValidationData,
-	UserShieldingKeyNonceType, UserShieldingKeyType, Web2ValidationData,
+	DiscordValidationData, ErrorDetail, GithubValidationData, Identity, IntoErrorDetail,
+	TwitterValidationData, UserShieldingKeyNonceType, UserShieldingKeyType, Web2ValidationData,
 };
 use log::*;
 use std::{string::ToString, vec::Vec};
@@ -54,6 +55,25 @@ fn payload_from_discord(discord: &DiscordMessage) -> Result<Vec<u8>> {
 		.map_err(|_| Error::LinkIdentityFailed(ErrorDetail::ParseError))
 }
 
+fn payload_from_github_text(text: &str) -> Result<Vec<u8>> {
+	let data = text.trim();
+	hex::decode(data.strip_prefix("0x").unwrap_or(data))
+		.map_err(|_| Error::LinkIdentityFailed(ErrorDetail::ParseError))
+}
+
+// a gist may contain several files, the first one that carries a valid hex payload is taken
+fn payload_from_gist(gist: &GithubGist) -> Result<Vec<u8>> {
+	gist.files
+		.values()
+		.filter_map(|file| file.content.as_ref())
+		.find_map(|content| payload_from_github_text(content).ok())
+		.ok_or(Error::LinkIdentityFailed(ErrorDetail::ParseError))
+}
+
+fn payload_from_github_comment(comment: &GithubIssueComment) -> Result<Vec<u8>> {
+	payload_from_github_text(&comment.body)
+}
+
 pub fn verify(
 	who: &Identity,
 	identity: &Identity,
@@ -65,6 +85,17 @@ pub fn verify(
 	debug!("verify web2 identity, who: {:?}", who);
 
 	ensure!(identity.is_web2(), Error::LinkIdentityFailed(ErrorDetail::InvalidIdentity),);
+	// the validation data must come from the same platform as the identity, otherwise a
+	// handle registered on one platform could be used to link the same handle on another
+	ensure!(
+		matches!(
+			(identity, data),
+			(Identity::Twitter(..), Web2ValidationData::Twitter(..))
+				| (Identity::Discord(..), Web2ValidationData::Discord(..))
+				| (Identity::Github(..), Web2ValidationData::Github(..))
+		),
+		Error::LinkIdentityFailed(ErrorDetail::InvalidIdentity)
+	);
 
 	let (user_name, payload) = match data {
 		Web2ValidationData::Twitter(TwitterValidationData { ref tweet_id }) => {
@@ -108,13 +139,40 @@ pub fn verify(
 			let payload = payload_from_discord(&message)?;
 			Ok((user_name, payload))
 		},
+		Web2ValidationData::Github(GithubValidationData::Gist { ref gist_id }) => {
+			let mut client = GithubOfficialClient::new();
+			let gist: GithubGist = client
+				.query_gist(gist_id.to_vec())
+				.map_err(|e| Error::LinkIdentityFailed(e.into_error_detail()))?;
+
+			// a secret gist can be accessed by anyone who knows the link, but it can't be
+			// discovered on the owner's profile, so we only accept public ones
+			ensure!(gist.public, Error::LinkIdentityFailed(ErrorDetail::UnexpectedMessage));
+
+			let payload = payload_from_gist(&gist)?;
+			Ok((gist.owner.login, payload))
+		},
+		Web2ValidationData::Github(GithubValidationData::IssueComment {
+			ref owner,
+			ref repo,
+			ref comment_id,
+		}) => {
+			let mut client = GithubOfficialClient::new();
+			let comment: GithubIssueComment = client
+				.query_issue_comment(owner.to_vec(), repo.to_vec(), comment_id.to_vec())
+				.map_err(|e| Error::LinkIdentityFailed(e.into_error_detail()))?;
+
+			let payload = payload_from_github_comment(&comment)?;
+			Ok((comment.user.login, payload))
+		},
 	}?;
 
 	// compare the username:
 	// - twitter's username is case insensitive
 	// - discord's username is case sensitive
+	// - github's login is case insensitive
 	match identity {
-		Identity::Twitter(address) => {
+		Identity::Twitter(address) | Identity::Github(address) => {
 			let handle = std::str::from_utf8(address.as_slice())
 				.map_err(|_| Error::LinkIdentityFailed(ErrorDetail::ParseError))?;
 			ensure!(
@@ -136,3 +194,109 @@ pub fn verify(
 	ensure!(payload == expected, Error::LinkIdentityFailed(ErrorDetail::UnexpectedMessage));
 	Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use lc_data_providers::GLOBAL_DATA_PROVIDER_CONFIG;
+	use lc_mock_server::{default_getter, run, MOCK_VERIFICATION_NONCE};
+	use litentry_primitives::{IdentityString, ValidationString};
+	use sp_core::{sr25519::Pair as Sr25519Pair, Pair};
+	use std::{sync::Arc, vec};
+
+	fn init() {
+		let url = run(Arc::new(default_getter), 0).unwrap();
+		GLOBAL_DATA_PROVIDER_CONFIG.write().unwrap().github_official.endpoints = vec![url];
+	}
+
+	fn alice() -> Identity {
+		Sr25519Pair::from_string("//Alice", None).unwrap().public().into()
+	}
+
+	// the mock server returns the login in upper case, which must still match
+	fn github_identity(handle: &str) -> Identity {
+		Identity::Github(IdentityString::try_from(handle.as_bytes().to_vec()).unwrap())
+	}
+
+	fn validation_string(s: &str) -> ValidationString {
+		ValidationString::try_from(s.as_bytes().to_vec()).unwrap()
+	}
+
+	fn gist(gist_id: &str) -> Web2ValidationData {
+		Web2ValidationData::Github(GithubValidationData::Gist {
+			gist_id: validation_string(gist_id),
+		})
+	}
+
+	fn issue_comment(owner: &str, comment_id: &str) -> Web2ValidationData {
+		Web2ValidationData::Github(GithubValidationData::IssueComment {
+			owner: validation_string(owner),
+			repo: validation_string("litentry-parachain"),
+			comment_id: validation_string(comment_id),
+		})
+	}
+
+	fn verify_github(
+		identity: &Identity,
+		sidechain_nonce: Index,
+		data: &Web2ValidationData,
+	) -> Result<()> {
+		verify(
+			&alice(),
+			identity,
+			sidechain_nonce,
+			Default::default(),
+			MOCK_VERIFICATION_NONCE,
+			data,
+		)
+	}
+
+	#[test]
+	fn verify_github_gist_works() {
+		init();
+		// the mock server signs the gist id as sidechain nonce
+		assert!(verify_github(&github_identity("mock_user"), 2, &gist("2")).is_ok());
+	}
+
+	#[test]
+	fn verify_github_issue_comment_works() {
+		init();
+		assert!(verify_github(&github_identity("mock_user"), 3, &issue_comment("litentry", "3"))
+			.is_ok());
+	}
+
+	#[test]
+	fn verify_github_fails_with_wrong_handle() {
+		init();
+		assert_eq!(
+			verify_github(&github_identity("another_user"), 2, &gist("2")),
+			Err(Error::LinkIdentityFailed(ErrorDetail::WrongWeb2Handle))
+		);
+	}
+
+	#[test]
+	fn verify_github_fails_with_unexpected_message() {
+		init();
+		// signed for nonce 2, but nonce 4 is expected
+		assert_eq!(
+			verify_github(&github_identity("mock_user"), 4, &gist("2")),
+			Err(Error::LinkIdentityFailed(ErrorDetail::UnexpectedMessage))
+		);
+	}
+
+	#[test]
+	fn verify_github_fails_with_other_platform_data() {
+		let twitter = Identity::Twitter(IdentityString::try_from(b"mock_user".to_vec()).unwrap());
+		assert_eq!(
+			verify_github(&twitter, 2, &gist("2")),
+			Err(Error::LinkIdentityFailed(ErrorDetail::InvalidIdentity))
+		);
+	}
+
+	#[test]
+	fn verify_github_fails_with_unknown_repo() {
+		init();
+		assert!(verify_github(&github_identity("mock_user"), 3, &issue_comment("unknown", "3"))
+			.is_err());
+	}
+}
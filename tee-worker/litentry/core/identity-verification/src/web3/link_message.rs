@@ -0,0 +1,399 @@
+// Copyright 2020-2023 Litentry Technologies GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+//! A human readable link message modeled on ERC-4361 (Sign-In with Ethereum), so that users
+//! see what they sign instead of the hash of `get_expected_raw_message`, see
+//! https://eips.ethereum.org/EIPS/eip-4361
+//!
+//! It looks like:
+//! ```text
+//! app.litentry.com wants you to sign in with your Ethereum account:
+//! 0x70997970c51812dc3a010c7d01b50e0d17dc79c8
+//!
+//! Link this account to your Litentry identity graph.
+//!
+//! Who: substrate:0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d
+//! Networks: Ethereum, Bsc
+//! Shard: 0x0404040404040404040404040404040404040404040404040404040404040404
+//! Enclave: 0x0505050505050505050505050505050505050505050505050505050505050505
+//! Version: 1
+//! Nonce: 3
+//! Issued At: 2023-07-01T10:00:00Z
+//! Expiration Time: 2023-07-01T10:10:00Z
+//! ```
+//! A Substrate account is shown as its hex public key, e.g. `sign in with your Substrate
+//! account:\n0xd435..`, Bitcoin and Solana accounts as their addresses, e.g. `sign in with
+//! your Bitcoin account:\nbc1q9vza..`. `Nonce` is the sidechain nonce of `Who`, it changes
+//! with each request, so a signed message can't be replayed. `Shard` and `Enclave` (the
+//! mrenclave) bind it to the worker it's meant for, like the domain of an EIP-712 signature.
+
+use crate::{Error, ErrorDetail, Result};
+use core::fmt;
+use itp_time_utils::from_iso8601;
+use itp_utils::hex::hex_encode;
+use lc_stf_task_sender::IdentityVerificationRequest;
+use litentry_primitives::{format_solana_address, identity_name, web3network_name, Identity};
+use log::*;
+use std::{
+	string::{String, ToString},
+	vec::Vec,
+};
+
+/// Apps on this domain, or on one of its subdomains, can ask users to sign a link message.
+pub const LINK_MESSAGE_DOMAIN: &str = "litentry.com";
+pub const LINK_MESSAGE_STATEMENT: &str = "Link this account to your Litentry identity graph.";
+pub const LINK_MESSAGE_VERSION: &str = "1";
+/// A link message can't be valid for longer than an hour.
+pub const MAX_LINK_MESSAGE_VALIDITY_MS: u64 = 60 * 60 * 1000;
+/// How far the clock of a wallet can be ahead of the enclave's.
+pub const MAX_CLOCK_DRIFT_MS: u64 = 5 * 60 * 1000;
+
+const HEADER: &str = " wants you to sign in with your ";
+const FIELDS: [&str; 8] =
+	["Who", "Networks", "Shard", "Enclave", "Version", "Nonce", "Issued At", "Expiration Time"];
+
+/// The fields of a link message, as they are written.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LinkMessage {
+	pub domain: String,
+	/// `Ethereum`, `Substrate`, `Bitcoin` or `Solana`
+	pub chain: String,
+	pub address: String,
+	pub statement: String,
+	pub who: String,
+	pub networks: Vec<String>,
+	pub shard: String,
+	/// the mrenclave
+	pub enclave: String,
+	pub version: String,
+	pub nonce: String,
+	pub issued_at: String,
+	pub expiration_time: String,
+}
+
+impl LinkMessage {
+	/// Parses a message, the fields must be in the order above, without any other lines.
+	pub fn parse(message: &str) -> Option<Self> {
+		let mut lines = message.split('\n');
+		let (domain, chain) = lines.next()?.strip_suffix(" account:")?.split_once(HEADER)?;
+		let address = lines.next()?;
+		let statement = match (lines.next()?, lines.next()?, lines.next()?) {
+			("", statement, "") => statement,
+			_ => return None,
+		};
+		let values: Vec<&str> = FIELDS
+			.iter()
+			.map(|field| lines.next()?.strip_prefix(field)?.strip_prefix(": "))
+			.collect::<Option<_>>()?;
+		if lines.next().is_some() {
+			return None
+		}
+		let networks = match values[1] {
+			"" => Vec::new(),
+			networks => networks.split(", ").map(Into::into).collect(),
+		};
+
+		Some(LinkMessage {
+			domain: domain.into(),
+			chain: chain.into(),
+			address: address.into(),
+			statement: statement.into(),
+			who: values[0].into(),
+			networks,
+			shard: values[2].into(),
+			enclave: values[3].into(),
+			version: values[4].into(),
+			nonce: values[5].into(),
+			issued_at: values[6].into(),
+			expiration_time: values[7].into(),
+		})
+	}
+
+	/// Checks that the message is about the request `r` to the enclave `mrenclave`, and that
+	/// it's valid at `now` (in ms) if given.
+	pub fn validate(
+		&self,
+		r: &IdentityVerificationRequest,
+		mrenclave: &[u8; 32],
+		now: Option<u64>,
+	) -> Result<()> {
+		let domain_ok = self.domain == LINK_MESSAGE_DOMAIN
+			|| self
+				.domain
+				.strip_suffix(LINK_MESSAGE_DOMAIN)
+				.map_or(false, |s| s.ends_with('.'));
+		check(domain_ok, "domain")?;
+
+		let (chain, address) = match &r.identity {
+			Identity::Evm(address) => ("Ethereum", hex_encode(address.as_ref())),
+			Identity::Substrate(address) => ("Substrate", hex_encode(address.as_ref())),
+			Identity::Bitcoin(address) => ("Bitcoin", address.format()),
+			Identity::Solana(address) => ("Solana", format_solana_address(address)),
+			_ => return Err(Error::LinkIdentityFailed(ErrorDetail::InvalidIdentity)),
+		};
+		check(self.chain == chain, "chain")?;
+		// EVM addresses may be checksummed, base58 addresses are case sensitive
+		let address_ok = if r.identity.is_bitcoin() || r.identity.is_solana() {
+			self.address == address
+		} else {
+			self.address.eq_ignore_ascii_case(&address)
+		};
+		check(address_ok, "address")?;
+		check(self.statement == LINK_MESSAGE_STATEMENT, "statement")?;
+		check(self.who == identity_name(&r.who), "who")?;
+
+		let networks: Vec<_> = r.web3networks.iter().map(web3network_name).collect();
+		check(
+			networks.len() == self.networks.len()
+				&& self.networks.iter().all(|network| networks.contains(network))
+				&& networks.iter().all(|network| self.networks.contains(network)),
+			"networks",
+		)?;
+		check(self.shard.eq_ignore_ascii_case(&hex_encode(r.shard.as_bytes())), "shard")?;
+		check(self.enclave.eq_ignore_ascii_case(&hex_encode(mrenclave)), "enclave")?;
+		check(self.version == LINK_MESSAGE_VERSION, "version")?;
+		check(self.nonce == r.sidechain_nonce.to_string(), "nonce")?;
+
+		let now = match now {
+			Some(now) => now,
+			None => return Ok(()),
+		};
+		let issued_at = parse_timestamp(&self.issued_at)?;
+		let expiration_time = parse_timestamp(&self.expiration_time)?;
+		if issued_at > now.saturating_add(MAX_CLOCK_DRIFT_MS)
+			|| expiration_time <= now
+			|| expiration_time.saturating_sub(issued_at) > MAX_LINK_MESSAGE_VALIDITY_MS
+		{
+			debug!("link message is valid from {} to {}", self.issued_at, self.expiration_time);
+			return Err(Error::LinkIdentityFailed(ErrorDetail::ExpiredMessage))
+		}
+		Ok(())
+	}
+}
+
+impl fmt::Display for LinkMessage {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(
+			f,
+			"{}{}{} account:\n{}\n\n{}\n\n",
+			self.domain, HEADER, self.chain, self.address, self.statement
+		)?;
+		let networks = self.networks.join(", ");
+		let values = [
+			&self.who,
+			&networks,
+			&self.shard,
+			&self.enclave,
+			&self.version,
+			&self.nonce,
+			&self.issued_at,
+			&self.expiration_time,
+		];
+		for (i, (field, value)) in FIELDS.iter().zip(values).enumerate() {
+			let separator = if i == 0 { "" } else { "\n" };
+			write!(f, "{}{}: {}", separator, field, value)?;
+		}
+		Ok(())
+	}
+}
+
+fn check(ok: bool, field: &str) -> Result<()> {
+	if !ok {
+		debug!("unexpected {} in link message", field);
+		return Err(Error::LinkIdentityFailed(ErrorDetail::UnexpectedMessage))
+	}
+	Ok(())
+}
+
+// RFC 3339, in ms
+fn parse_timestamp(value: &str) -> Result<u64> {
+	from_iso8601(value)
+		.and_then(|time| u64::try_from(time.timestamp_millis()).ok())
+		.ok_or(Error::LinkIdentityFailed(ErrorDetail::ParseError))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use litentry_primitives::{
+		BitcoinAddress, EthereumSignature, LitentryMultiSignature, ValidationData,
+		Web3CommonValidationData, Web3Network, Web3ValidationData,
+	};
+
+	const NOW: u64 = 1_688_205_600_000; // 2023-07-01T10:00:00Z
+	const MRENCLAVE: [u8; 32] = [5u8; 32];
+
+	fn request() -> IdentityVerificationRequest {
+		IdentityVerificationRequest {
+			shard: [4u8; 32].into(),
+			who: Identity::Substrate([1u8; 32].into()),
+			identity: Identity::Evm([0xabu8; 20].into()),
+			validation_data: ValidationData::Web3(Web3ValidationData::Evm(
+				Web3CommonValidationData {
+					message: Default::default(),
+					signature: LitentryMultiSignature::Ethereum(EthereumSignature([0u8; 65])),
+				},
+			)),
+			web3networks: vec![Web3Network::Ethereum, Web3Network::Bsc],
+			sidechain_nonce: 3,
+			key_nonce: Default::default(),
+			key: Default::default(),
+			top_hash: Default::default(),
+			req_ext_hash: Default::default(),
+		}
+	}
+
+	fn message() -> LinkMessage {
+		LinkMessage {
+			domain: "app.litentry.com".into(),
+			chain: "Ethereum".into(),
+			address: "0xABABABABABABABABABABABABABABABABABABABAB".into(),
+			statement: LINK_MESSAGE_STATEMENT.into(),
+			who: format!("substrate:0x{}", "01".repeat(32)),
+			networks: vec!["Bsc".into(), "Ethereum".into()],
+			shard: format!("0x{}", "04".repeat(32)),
+			enclave: format!("0x{}", "05".repeat(32)),
+			version: "1".into(),
+			nonce: "3".into(),
+			issued_at: "2023-07-01T10:00:00Z".into(),
+			expiration_time: "2023-07-01T12:09:00+02:00".into(),
+		}
+	}
+
+	fn detail(result: Result<()>) -> ErrorDetail {
+		match result {
+			Err(Error::LinkIdentityFailed(detail)) => detail,
+			_ => panic!("unexpected {:?}", result),
+		}
+	}
+
+	#[test]
+	fn parse_works() {
+		let text = message().to_string();
+		assert!(text.starts_with(
+			"app.litentry.com wants you to sign in with your Ethereum account:\n0xABAB"
+		));
+		assert!(text.ends_with(
+			"Nonce: 3\nIssued At: 2023-07-01T10:00:00Z\nExpiration Time: 2023-07-01T12:09:00+02:00"
+		));
+		assert_eq!(LinkMessage::parse(&text), Some(message()));
+
+		assert_eq!(LinkMessage::parse(&(text.clone() + "\n")), None);
+		assert_eq!(LinkMessage::parse(&text.replace("Nonce", "Nonces")), None);
+		assert_eq!(LinkMessage::parse(&text.replace("\n\nWho", "\nWho")), None);
+		let enclave = format!("\nEnclave: 0x{}", "05".repeat(32));
+		assert!(text.contains(&enclave));
+		assert_eq!(LinkMessage::parse(&text.replace(&enclave, "")), None);
+		let no_networks = LinkMessage { networks: vec![], ..message() };
+		assert_eq!(LinkMessage::parse(&no_networks.to_string()), Some(no_networks));
+	}
+
+	#[test]
+	fn validate_works() {
+		let r = request();
+		assert!(message().validate(&r, &MRENCLAVE, Some(NOW)).is_ok());
+		assert!(LinkMessage { domain: "litentry.com".into(), ..message() }
+			.validate(&r, &MRENCLAVE, Some(NOW))
+			.is_ok());
+
+		for m in [
+			LinkMessage { domain: "evillitentry.com".into(), ..message() },
+			LinkMessage { chain: "Substrate".into(), ..message() },
+			LinkMessage { address: format!("0x{}", "ac".repeat(20)), ..message() },
+			LinkMessage { statement: "Sign in".into(), ..message() },
+			LinkMessage { who: format!("substrate:0x{}", "02".repeat(32)), ..message() },
+			LinkMessage { networks: vec!["Ethereum".into()], ..message() },
+			LinkMessage {
+				networks: vec!["Bsc".into(), "Ethereum".into(), "Ether".into()],
+				..message()
+			},
+			LinkMessage { shard: format!("0x{}", "06".repeat(32)), ..message() },
+			LinkMessage { enclave: format!("0x{}", "06".repeat(32)), ..message() },
+			LinkMessage { version: "2".into(), ..message() },
+			// replayed
+			LinkMessage { nonce: "2".into(), ..message() },
+		] {
+			assert_eq!(
+				detail(m.validate(&r, &MRENCLAVE, Some(NOW))),
+				ErrorDetail::UnexpectedMessage
+			);
+		}
+
+		// issued in the future, expired, valid for too long
+		for (issued_at, expiration_time) in [
+			("2023-07-01T10:06:00Z", "2023-07-01T10:10:00Z"),
+			("2023-07-01T09:00:00Z", "2023-07-01T10:00:00Z"),
+			("2023-07-01T09:00:00Z", "2023-07-01T10:00:01Z"),
+		] {
+			let m = LinkMessage {
+				issued_at: issued_at.into(),
+				expiration_time: expiration_time.into(),
+				..message()
+			};
+			assert_eq!(detail(m.validate(&r, &MRENCLAVE, Some(NOW))), ErrorDetail::ExpiredMessage);
+			// the time isn't checked when re-verifying a link proof
+			assert!(m.validate(&r, &MRENCLAVE, None).is_ok());
+		}
+		let m = LinkMessage { issued_at: "yesterday".into(), ..message() };
+		assert_eq!(detail(m.validate(&r, &MRENCLAVE, Some(NOW))), ErrorDetail::ParseError);
+	}
+
+	#[test]
+	fn validate_bitcoin_works() {
+		let address = "bc1q9vza2e8x573nczrlzms0wvx3gsqjx7vavgkx0l";
+		let r = IdentityVerificationRequest {
+			identity: Identity::Bitcoin(BitcoinAddress::parse(address).unwrap()),
+			web3networks: vec![Web3Network::Bitcoin],
+			..request()
+		};
+		let m = LinkMessage {
+			chain: "Bitcoin".into(),
+			address: address.into(),
+			networks: vec!["Bitcoin".into()],
+			..message()
+		};
+		assert!(m.validate(&r, &MRENCLAVE, Some(NOW)).is_ok());
+
+		let other =
+			LinkMessage { address: "14vV3aCHBeStb5bkenkNHbe2YAFinYdXgc".into(), ..m.clone() };
+		assert_eq!(
+			detail(other.validate(&r, &MRENCLAVE, Some(NOW))),
+			ErrorDetail::UnexpectedMessage
+		);
+		let ethereum = LinkMessage { chain: "Ethereum".into(), ..m };
+		assert_eq!(
+			detail(ethereum.validate(&r, &MRENCLAVE, Some(NOW))),
+			ErrorDetail::UnexpectedMessage
+		);
+	}
+
+	#[test]
+	fn validate_registered_network_works() {
+		let r = IdentityVerificationRequest {
+			web3networks: vec![Web3Network::Ethereum, Web3Network::Registered(1)],
+			..request()
+		};
+		let m =
+			LinkMessage { networks: vec!["Ethereum".into(), "Registered#1".into()], ..message() };
+		assert!(m.validate(&r, &MRENCLAVE, Some(NOW)).is_ok());
+
+		let other = LinkMessage { networks: vec!["Ethereum".into(), "Registered#2".into()], ..m };
+		assert_eq!(
+			detail(other.validate(&r, &MRENCLAVE, Some(NOW))),
+			ErrorDetail::UnexpectedMessage
+		);
+	}
+}
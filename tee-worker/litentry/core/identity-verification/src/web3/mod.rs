@@ -14,44 +14,472 @@
 // You should have received a copy of the GNU General Public License
 // along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
 
-use crate::{ensure, Error, Result};
+use crate::{ensure, Error, ErrorDetail, IntoErrorDetail, Result};
 use ita_stf::helpers::get_expected_raw_message;
-use itp_types::Index;
 use itp_utils::stringify::account_id_to_string;
+use lc_data_providers::evm::{is_evm_network, EvmRpcClient};
+use lc_stf_task_sender::IdentityVerificationRequest;
 use litentry_primitives::{
-	ErrorDetail, Identity, UserShieldingKeyNonceType, UserShieldingKeyType, Web3ValidationData,
+	eip712_encode, evm_chain_id, evm_eip191_wrap, Eip712Domain, Identity, LinkIdentityMessage,
+	LitentryMultiSignature, Web3NetworkRegistry, Web3ValidationData,
 };
 use log::*;
+use sp_io::hashing::keccak_256;
+use std::vec::Vec;
 
+mod link_message;
+use link_message::LinkMessage;
+
+/// A link message is checked to be valid at `now` (in ms), unless it's `None`. `registry`
+/// has the networks registered on the parentchain of the shard of `r`.
 pub fn verify(
-	who: &Identity,
-	identity: &Identity,
-	sidechain_nonce: Index,
-	key: UserShieldingKeyType,
-	nonce: UserShieldingKeyNonceType,
+	r: &IdentityVerificationRequest,
+	mrenclave: &[u8; 32],
+	registry: &Web3NetworkRegistry,
 	data: &Web3ValidationData,
+	now: Option<u64>,
 ) -> Result<()> {
-	debug!("verify web3 identity, who: {}", account_id_to_string(&who));
+	debug!("verify web3 identity, who: {}", account_id_to_string(&r.who));
+
+	ensure!(r.identity.is_web3(), Error::LinkIdentityFailed(ErrorDetail::InvalidIdentity),);
 
-	ensure!(identity.is_web3(), Error::LinkIdentityFailed(ErrorDetail::InvalidIdentity),);
+	let raw_msg =
+		get_expected_raw_message(&r.who, &r.identity, r.sidechain_nonce, r.key, r.key_nonce);
 
-	let raw_msg = get_expected_raw_message(who, identity, sidechain_nonce, key, nonce);
+	match data {
+		// the fields are checked instead, see `LinkMessage`
+		Web3ValidationData::Structured(data) => {
+			let message = core::str::from_utf8(&data.message)
+				.ok()
+				.and_then(LinkMessage::parse)
+				.ok_or(Error::LinkIdentityFailed(ErrorDetail::ParseError))?;
+			message.validate(r, mrenclave, now)?;
+		},
+		_ => ensure!(
+			raw_msg.as_slice() == data.message(),
+			Error::LinkIdentityFailed(ErrorDetail::UnexpectedMessage)
+		),
+	}
 
-	ensure!(
-		raw_msg.as_slice() == data.message().as_slice(),
-		Error::LinkIdentityFailed(ErrorDetail::UnexpectedMessage)
-	);
+	let verified = match data {
+		Web3ValidationData::Eip1271(data) => verify_eip1271(r, &raw_msg, &data.signature)?,
+		Web3ValidationData::Substrate(data) | Web3ValidationData::Evm(data) =>
+			verify_signature(r, mrenclave, registry, &raw_msg, &data.signature),
+		Web3ValidationData::Bitcoin(data) => {
+			ensure!(
+				r.identity.is_bitcoin() && is_bitcoin_signature(&data.signature),
+				Error::LinkIdentityFailed(ErrorDetail::WrongSignatureType)
+			);
+			data.signature.verify(&raw_msg, &r.identity)
+		},
+		Web3ValidationData::Solana(data) => {
+			ensure!(
+				r.identity.is_solana()
+					&& matches!(data.signature, LitentryMultiSignature::Ed25519(_)),
+				Error::LinkIdentityFailed(ErrorDetail::WrongSignatureType)
+			);
+			data.signature.verify(&raw_msg, &r.identity)
+		},
+		// the message is signed as it is, like with `personal_sign` or `signRaw`
+		Web3ValidationData::Structured(data) => {
+			ensure!(
+				!matches!(
+					data.signature,
+					LitentryMultiSignature::Eip712(_)
+						| LitentryMultiSignature::EthereumPrettified(_)
+				),
+				Error::LinkIdentityFailed(ErrorDetail::WrongSignatureType)
+			);
+			data.signature.verify(&data.message, &r.identity)
+		},
+	};
 
 	// TODO: just to make it backwards compatible
 	//       will merge it to `VerifyWeb3SignatureFailed` after the campaign
-	if !data.signature().verify(&raw_msg, identity) {
+	if !verified {
 		match data {
 			Web3ValidationData::Substrate(_) =>
 				return Err(Error::LinkIdentityFailed(ErrorDetail::VerifySubstrateSignatureFailed)),
-			Web3ValidationData::Evm(_) =>
+			Web3ValidationData::Evm(_) | Web3ValidationData::Eip1271(_) =>
+				return Err(Error::LinkIdentityFailed(ErrorDetail::VerifyEvmSignatureFailed)),
+			Web3ValidationData::Bitcoin(_) =>
+				return Err(Error::LinkIdentityFailed(ErrorDetail::VerifyBitcoinSignatureFailed)),
+			Web3ValidationData::Solana(_) =>
+				return Err(Error::LinkIdentityFailed(ErrorDetail::VerifySolanaSignatureFailed)),
+			Web3ValidationData::Structured(_) if r.identity.is_substrate() =>
+				return Err(Error::LinkIdentityFailed(ErrorDetail::VerifySubstrateSignatureFailed)),
+			Web3ValidationData::Structured(_) if r.identity.is_bitcoin() =>
+				return Err(Error::LinkIdentityFailed(ErrorDetail::VerifyBitcoinSignatureFailed)),
+			Web3ValidationData::Structured(_) if r.identity.is_solana() =>
+				return Err(Error::LinkIdentityFailed(ErrorDetail::VerifySolanaSignatureFailed)),
+			Web3ValidationData::Structured(_) =>
 				return Err(Error::LinkIdentityFailed(ErrorDetail::VerifyEvmSignatureFailed)),
 		}
 	}
 
 	Ok(())
 }
+
+fn verify_signature(
+	r: &IdentityVerificationRequest,
+	mrenclave: &[u8; 32],
+	registry: &Web3NetworkRegistry,
+	raw_msg: &[u8],
+	signature: &LitentryMultiSignature,
+) -> bool {
+	match signature {
+		LitentryMultiSignature::Eip712(_) => verify_eip712(r, mrenclave, registry, signature),
+		_ => signature.verify(raw_msg, &r.identity),
+	}
+}
+
+fn is_bitcoin_signature(signature: &LitentryMultiSignature) -> bool {
+	matches!(signature, LitentryMultiSignature::Bip137(_) | LitentryMultiSignature::Bip322(_))
+}
+
+// the domain has the chain id of the wallet, which must be one of the networks to link
+fn verify_eip712(
+	r: &IdentityVerificationRequest,
+	mrenclave: &[u8; 32],
+	registry: &Web3NetworkRegistry,
+	signature: &LitentryMultiSignature,
+) -> bool {
+	let message = LinkIdentityMessage {
+		who: &r.who,
+		identity: &r.identity,
+		sidechain_nonce: r.sidechain_nonce,
+		networks: &r.web3networks,
+	};
+	r.web3networks.iter().filter_map(|n| evm_chain_id(n, registry)).any(|chain_id| {
+		let domain = Eip712Domain { chain_id, shard: r.shard.0, mrenclave: *mrenclave };
+		signature.verify(&eip712_encode(&domain, &message), &r.identity)
+	})
+}
+
+// the wallet is asked on every network to link, as the same address can be a different
+// contract, or no contract at all, on another network
+fn verify_eip1271(
+	r: &IdentityVerificationRequest,
+	raw_msg: &[u8],
+	signature: &[u8],
+) -> Result<bool> {
+	let wallet = match &r.identity {
+		Identity::Evm(address) => address.as_ref(),
+		_ => return Err(Error::LinkIdentityFailed(ErrorDetail::WrongSignatureType)),
+	};
+	let networks: Vec<_> = r.web3networks.iter().filter(|n| is_evm_network(n)).collect();
+	if networks.is_empty() {
+		return Ok(false)
+	}
+
+	// what a wallet signs with `personal_sign`
+	let hash = keccak_256(&evm_eip191_wrap(raw_msg));
+	let mut client = EvmRpcClient::new();
+	for network in networks {
+		let valid = client
+			.is_valid_signature(*network, wallet, &hash, signature)
+			.map_err(|e| Error::LinkIdentityFailed(e.into_error_detail()))?;
+		if !valid {
+			debug!("contract wallet rejected the signature on {:?}", network);
+			return Ok(false)
+		}
+	}
+	Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{
+		link_message::{LINK_MESSAGE_DOMAIN, LINK_MESSAGE_STATEMENT, LINK_MESSAGE_VERSION},
+		*,
+	};
+	use lc_data_providers::GLOBAL_DATA_PROVIDER_CONFIG;
+	use lc_mock_server::{
+		default_getter,
+		evm_rpc::{ALICE, SAFE},
+		run,
+	};
+	use litentry_primitives::{
+		format_solana_address, identity_name, recover_evm_address, Eip1271ValidationData,
+		EthereumSignature, StructuredValidationData, ValidationData, Web3CommonValidationData,
+		Web3Network, Web3NetworkFamily, Web3NetworkInfo,
+	};
+	use sp_core::{ecdsa, ed25519, sr25519, Pair};
+	use std::sync::Arc;
+
+	const MRENCLAVE: [u8; 32] = [5u8; 32];
+
+	fn init_evm_rpc() {
+		let url = run(Arc::new(default_getter), 0).unwrap() + "/evm";
+		let mut config = GLOBAL_DATA_PROVIDER_CONFIG.write().unwrap();
+		config.ethereum_rpc.endpoints = vec![url.clone()];
+		config.bsc_rpc.endpoints = vec![url];
+	}
+
+	// arbitrum is registered as 1
+	fn registry() -> Web3NetworkRegistry {
+		let arbitrum = Web3NetworkInfo {
+			name: b"Arbitrum".to_vec().try_into().unwrap(),
+			family: Web3NetworkFamily::Evm { chain_id: 42161 },
+			providers: Default::default(),
+		};
+		let mut registry = Web3NetworkRegistry::new();
+		registry.try_insert(1, arbitrum).unwrap();
+		registry
+	}
+
+	fn evm_identity(address: &str) -> Identity {
+		let address: [u8; 20] = hex::decode(&address[2..]).unwrap().try_into().unwrap();
+		Identity::Evm(address.into())
+	}
+
+	fn request(identity: Identity) -> IdentityVerificationRequest {
+		IdentityVerificationRequest {
+			shard: [4u8; 32].into(),
+			who: Identity::Substrate([1u8; 32].into()),
+			identity,
+			validation_data: ValidationData::Web3(Web3ValidationData::Evm(
+				Web3CommonValidationData {
+					message: Default::default(),
+					signature: LitentryMultiSignature::Ethereum(EthereumSignature([0u8; 65])),
+				},
+			)),
+			web3networks: vec![Web3Network::Ethereum, Web3Network::Bsc],
+			sidechain_nonce: 3,
+			key_nonce: Default::default(),
+			key: Default::default(),
+			top_hash: Default::default(),
+			req_ext_hash: Default::default(),
+		}
+	}
+
+	fn evm_account() -> (ecdsa::Pair, Identity) {
+		let pair = ecdsa::Pair::from_seed(&[9u8; 32]);
+		let hash = keccak_256(b"address");
+		let address = recover_evm_address(&hash, &pair.sign_prehashed(&hash).0).unwrap();
+		(pair, Identity::Evm(address.into()))
+	}
+
+	fn sign_eip712(
+		pair: &ecdsa::Pair,
+		r: &IdentityVerificationRequest,
+		domain: Eip712Domain,
+	) -> LitentryMultiSignature {
+		let message = LinkIdentityMessage {
+			who: &r.who,
+			identity: &r.identity,
+			sidechain_nonce: r.sidechain_nonce,
+			networks: &r.web3networks,
+		};
+		let hash = keccak_256(&eip712_encode(&domain, &message));
+		LitentryMultiSignature::Eip712(EthereumSignature(pair.sign_prehashed(&hash).0))
+	}
+
+	#[test]
+	fn verify_eip712_works() {
+		let (pair, identity) = evm_account();
+		let r = request(identity.clone());
+		let domain = |chain_id| Eip712Domain { chain_id, shard: [4u8; 32], mrenclave: MRENCLAVE };
+
+		// the wallet may be connected to any of the networks to link
+		assert!(verify_eip712(&r, &MRENCLAVE, &registry(), &sign_eip712(&pair, &r, domain(1))));
+		assert!(verify_eip712(&r, &MRENCLAVE, &registry(), &sign_eip712(&pair, &r, domain(56))));
+		assert!(!verify_eip712(&r, &MRENCLAVE, &registry(), &sign_eip712(&pair, &r, domain(137))));
+
+		let other_shard = Eip712Domain { shard: [6u8; 32], ..domain(1) };
+		assert!(!verify_eip712(&r, &MRENCLAVE, &registry(), &sign_eip712(&pair, &r, other_shard)));
+		let other_enclave = Eip712Domain { mrenclave: [6u8; 32], ..domain(1) };
+		assert!(!verify_eip712(
+			&r,
+			&MRENCLAVE,
+			&registry(),
+			&sign_eip712(&pair, &r, other_enclave)
+		));
+
+		let signature = sign_eip712(&pair, &r, domain(1));
+		let replayed = IdentityVerificationRequest { sidechain_nonce: 4, ..request(identity) };
+		assert!(!verify_eip712(&replayed, &MRENCLAVE, &registry(), &signature));
+		let other_who =
+			IdentityVerificationRequest { who: Identity::Substrate([2u8; 32].into()), ..r.clone() };
+		assert!(!verify_eip712(&other_who, &MRENCLAVE, &registry(), &signature));
+		let fewer_networks =
+			IdentityVerificationRequest { web3networks: vec![Web3Network::Ethereum], ..r.clone() };
+		assert!(!verify_eip712(&fewer_networks, &MRENCLAVE, &registry(), &signature));
+
+		// the chain id of a registered network is taken from the registry of the shard
+		let arbitrum = IdentityVerificationRequest {
+			web3networks: vec![Web3Network::Registered(1)],
+			..r.clone()
+		};
+		let signature = sign_eip712(&pair, &arbitrum, domain(42161));
+		assert!(verify_eip712(&arbitrum, &MRENCLAVE, &registry(), &signature));
+		assert!(!verify_eip712(&arbitrum, &MRENCLAVE, &Web3NetworkRegistry::new(), &signature));
+	}
+
+	#[test]
+	fn verify_accepts_eip712_signatures() {
+		let (pair, identity) = evm_account();
+		let r = request(identity);
+		let raw_msg =
+			get_expected_raw_message(&r.who, &r.identity, r.sidechain_nonce, r.key, r.key_nonce);
+		let data = |signature| {
+			Web3ValidationData::Evm(Web3CommonValidationData {
+				message: raw_msg.clone().try_into().unwrap(),
+				signature,
+			})
+		};
+		let domain = Eip712Domain { chain_id: 1, shard: [4u8; 32], mrenclave: MRENCLAVE };
+
+		let signature = sign_eip712(&pair, &r, domain);
+		assert!(verify(&r, &MRENCLAVE, &registry(), &data(signature.clone()), None).is_ok());
+		assert!(matches!(
+			verify(&r, &[6u8; 32], &registry(), &data(signature), None),
+			Err(Error::LinkIdentityFailed(ErrorDetail::VerifyEvmSignatureFailed))
+		));
+	}
+
+	#[test]
+	fn verify_eip1271_works() {
+		init_evm_rpc();
+		let r = request(evm_identity(SAFE));
+		let raw_msg =
+			get_expected_raw_message(&r.who, &r.identity, r.sidechain_nonce, r.key, r.key_nonce);
+		// the mock wallet accepts the hash that was signed as its signature
+		let hash = keccak_256(&evm_eip191_wrap(&raw_msg));
+
+		assert!(verify_eip1271(&r, &raw_msg, &hash).unwrap());
+		assert!(!verify_eip1271(&r, &raw_msg, &keccak_256(&raw_msg)).unwrap());
+		assert!(!verify_eip1271(&r, b"another message", &hash).unwrap());
+
+		// not a contract
+		let eoa = request(evm_identity(ALICE));
+		assert!(!verify_eip1271(&eoa, &raw_msg, &hash).unwrap());
+		// no evm network to ask the wallet on
+		let substrate_networks =
+			IdentityVerificationRequest { web3networks: vec![Web3Network::Litentry], ..r.clone() };
+		assert!(!verify_eip1271(&substrate_networks, &raw_msg, &hash).unwrap());
+		let substrate = request(Identity::Substrate([2u8; 32].into()));
+		assert!(matches!(
+			verify_eip1271(&substrate, &raw_msg, &hash),
+			Err(Error::LinkIdentityFailed(ErrorDetail::WrongSignatureType))
+		));
+
+		let data = |signature: &[u8]| {
+			Web3ValidationData::Eip1271(Eip1271ValidationData {
+				message: raw_msg.clone().try_into().unwrap(),
+				signature: signature.to_vec().try_into().unwrap(),
+			})
+		};
+		assert!(verify(&r, &MRENCLAVE, &registry(), &data(&hash), None).is_ok());
+		assert!(matches!(
+			verify(&r, &MRENCLAVE, &registry(), &data(&[0u8; 65]), None),
+			Err(Error::LinkIdentityFailed(ErrorDetail::VerifyEvmSignatureFailed))
+		));
+	}
+
+	#[test]
+	fn verify_solana_works() {
+		let pair = ed25519::Pair::from_seed(&[7u8; 32]);
+		let identity = Identity::Solana(pair.public().0.into());
+		let r = IdentityVerificationRequest {
+			web3networks: vec![Web3Network::Solana],
+			..request(identity.clone())
+		};
+		let raw_msg =
+			get_expected_raw_message(&r.who, &r.identity, r.sidechain_nonce, r.key, r.key_nonce);
+		let data = |signature| {
+			Web3ValidationData::Solana(Web3CommonValidationData {
+				message: raw_msg.clone().try_into().unwrap(),
+				signature,
+			})
+		};
+		let detail = |result: Result<()>| match result {
+			Err(Error::LinkIdentityFailed(detail)) => detail,
+			_ => panic!("unexpected {:?}", result),
+		};
+
+		let signature = LitentryMultiSignature::Ed25519(pair.sign(&raw_msg));
+		assert!(verify(&r, &MRENCLAVE, &registry(), &data(signature.clone()), None).is_ok());
+
+		let other_message = LitentryMultiSignature::Ed25519(pair.sign(b"another message"));
+		assert_eq!(
+			detail(verify(&r, &MRENCLAVE, &registry(), &data(other_message), None)),
+			ErrorDetail::VerifySolanaSignatureFailed
+		);
+		let sr25519 = sr25519::Pair::from_seed(&[7u8; 32]).sign(&raw_msg);
+		assert_eq!(
+			detail(verify(
+				&r,
+				&MRENCLAVE,
+				&registry(),
+				&data(LitentryMultiSignature::Sr25519(sr25519)),
+				None
+			)),
+			ErrorDetail::WrongSignatureType
+		);
+		// a substrate account with the same public key
+		let substrate = request(Identity::Substrate(pair.public().0.into()));
+		assert_eq!(
+			detail(verify(&substrate, &MRENCLAVE, &registry(), &data(signature), None)),
+			ErrorDetail::WrongSignatureType
+		);
+	}
+
+	#[test]
+	fn verify_structured_solana_works() {
+		let pair = ed25519::Pair::from_seed(&[7u8; 32]);
+		let address = pair.public().0.into();
+		let r = IdentityVerificationRequest {
+			web3networks: vec![Web3Network::Solana],
+			..request(Identity::Solana(address))
+		};
+		let message = LinkMessage {
+			domain: LINK_MESSAGE_DOMAIN.into(),
+			chain: "Solana".into(),
+			address: format_solana_address(&address),
+			statement: LINK_MESSAGE_STATEMENT.into(),
+			who: identity_name(&r.who),
+			networks: vec!["Solana".into()],
+			shard: format!("0x{}", "04".repeat(32)),
+			enclave: format!("0x{}", "05".repeat(32)),
+			version: LINK_MESSAGE_VERSION.into(),
+			nonce: r.sidechain_nonce.to_string(),
+			issued_at: "2023-07-01T10:00:00Z".into(),
+			expiration_time: "2023-07-01T10:10:00Z".into(),
+		}
+		.to_string();
+		let data = |message: &str, signature| {
+			Web3ValidationData::Structured(StructuredValidationData {
+				message: message.as_bytes().to_vec().try_into().unwrap(),
+				signature,
+			})
+		};
+
+		// the message is signed as it is
+		let signature = LitentryMultiSignature::Ed25519(pair.sign(message.as_bytes()));
+		assert!(
+			verify(&r, &MRENCLAVE, &registry(), &data(&message, signature.clone()), None).is_ok()
+		);
+		// expired by now
+		assert!(matches!(
+			verify(
+				&r,
+				&MRENCLAVE,
+				&registry(),
+				&data(&message, signature.clone()),
+				Some(u64::MAX / 2)
+			),
+			Err(Error::LinkIdentityFailed(ErrorDetail::ExpiredMessage))
+		));
+		let replayed = IdentityVerificationRequest { sidechain_nonce: 4, ..r.clone() };
+		assert!(matches!(
+			verify(&replayed, &MRENCLAVE, &registry(), &data(&message, signature), None),
+			Err(Error::LinkIdentityFailed(ErrorDetail::UnexpectedMessage))
+		));
+		let unsigned = LitentryMultiSignature::Ed25519(pair.sign(b"another message"));
+		assert!(matches!(
+			verify(&r, &MRENCLAVE, &registry(), &data(&message, unsigned), None),
+			Err(Error::LinkIdentityFailed(ErrorDetail::VerifySolanaSignatureFailed))
+		));
+	}
+}
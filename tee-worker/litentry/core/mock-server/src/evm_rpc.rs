@@ -0,0 +1,185 @@
+// Copyright 2020-2023 Litentry Technologies GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+//! A canned EVM chain behind a JSON-RPC endpoint at `/evm`:
+//! - block `n` is mined at `GENESIS_TIMESTAMP_MS + n * BLOCK_TIME_MS`, up to `LATEST_BLOCK`
+//! - ALICE receives 10 ETH and 10 TOKEN at block 300
+//! - BOB receives 10 TOKEN at block 100 and sends 9 of them to CAROL at block 600
+//! - CAROL holds 10 ETH from block 100, except for the blocks 400..=500
+//! - SAFE is an EIP-1271 contract wallet, the only signature of a hash it accepts is the hash
+
+use serde_json::{json, Value};
+use warp::{http::Response, Filter};
+
+pub const ALICE: &str = "0x00000000000000000000000000000000000000a1";
+pub const BOB: &str = "0x00000000000000000000000000000000000000b0";
+pub const CAROL: &str = "0x00000000000000000000000000000000000000c0";
+/// An ERC-20 token with 8 decimals.
+pub const TOKEN: &str = "0x0000000000000000000000000000000000007070";
+/// A contract wallet.
+pub const SAFE: &str = "0x0000000000000000000000000000000000005afe";
+
+/// 2016-01-01
+pub const GENESIS_TIMESTAMP_MS: u64 = 1_451_606_400_000;
+pub const BLOCK_TIME_MS: u64 = 3 * 86_400_000;
+pub const LATEST_BLOCK: u64 = 1000;
+/// `eth_getLogs` fails for larger ranges, like public nodes do.
+pub const MAX_LOG_RANGE: u64 = 500;
+
+const ZERO: &str = "0x0000000000000000000000000000000000000000";
+const TRANSFER_TOPIC: &str = "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef";
+const ETH: u128 = 1_000_000_000_000_000_000;
+const TOKEN_UNIT: u128 = 100_000_000;
+
+// (block, log index, from, to, value)
+const TRANSFERS: [(u64, u64, &str, &str, u128); 3] = [
+	(100, 0, ZERO, BOB, 10 * TOKEN_UNIT),
+	(300, 1, ZERO, ALICE, 10 * TOKEN_UNIT),
+	(600, 0, BOB, CAROL, 9 * TOKEN_UNIT),
+];
+
+pub(crate) fn query() -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+	warp::post().and(warp::path!("evm")).and(warp::body::json()).map(|body: Value| {
+		let params = body["params"].as_array().cloned().unwrap_or_default();
+		let response = match respond(body["method"].as_str().unwrap_or_default(), &params) {
+			Ok(result) => json!({ "jsonrpc": "2.0", "id": body["id"], "result": result }),
+			Err(message) => json!({
+				"jsonrpc": "2.0",
+				"id": body["id"],
+				"error": { "code": -32000, "message": message },
+			}),
+		};
+		Response::builder().body(response.to_string())
+	})
+}
+
+fn respond(method: &str, params: &[Value]) -> Result<Value, &'static str> {
+	match method {
+		"eth_chainId" => Ok(quantity(1)),
+		"eth_blockNumber" => Ok(quantity(LATEST_BLOCK as u128)),
+		"eth_getBlockByNumber" => {
+			let number = block_number(params.first())?;
+			if number > LATEST_BLOCK {
+				return Ok(Value::Null)
+			}
+			let timestamp = (GENESIS_TIMESTAMP_MS + number * BLOCK_TIME_MS) / 1_000;
+			Ok(json!({
+				"number": quantity(number as u128),
+				"hash": format!("0x{:064x}", number + 1),
+				"timestamp": quantity(timestamp as u128),
+			}))
+		},
+		"eth_getBalance" => {
+			let address = params.first().and_then(|a| a.as_str()).ok_or("invalid address")?;
+			let number = block_number(params.get(1))?;
+			let balance = match address {
+				ALICE if number >= 300 => 10 * ETH,
+				CAROL if number >= 100 && !(400..=500).contains(&number) => 10 * ETH,
+				_ => 0,
+			};
+			Ok(quantity(balance))
+		},
+		"eth_call" => {
+			let call = params.first().ok_or("invalid call")?;
+			let data = call["data"].as_str().ok_or("invalid data")?;
+			let number = block_number(params.get(1))?;
+			match (call["to"].as_str(), &data[..10.min(data.len())]) {
+				(Some(TOKEN), "0x313ce567") => Ok(uint256(8)),
+				(Some(TOKEN), "0x70a08231") if data.len() == 74 => {
+					let address = format!("0x{}", &data[34..]);
+					Ok(uint256(token_balance(&address, number)))
+				},
+				// (hash, offset, length, signature)
+				(Some(SAFE), "0x1626ba7e") if data.len() >= 202 => {
+					let (hash, signature) = (&data[10..74], &data[202..]);
+					let length = u64::from_str_radix(&data[138..202], 16).unwrap_or_default();
+					let magic = if length == 32 && signature == hash { 0x1626ba7e } else { 0 };
+					Ok(format!("0x{:08x}{}", magic, "0".repeat(56)).into())
+				},
+				(Some(TOKEN | SAFE), _) => Err("execution reverted"),
+				_ => Ok("0x".into()),
+			}
+		},
+		"eth_getLogs" => {
+			let filter = params.first().ok_or("invalid filter")?;
+			let from = block_number(filter.get("fromBlock"))?;
+			let to = block_number(filter.get("toBlock"))?;
+			if to.saturating_sub(from) >= MAX_LOG_RANGE {
+				return Err("query returned more than 10000 results")
+			}
+			let topics = filter["topics"].as_array().cloned().unwrap_or_default();
+			let matches = |index: usize, address: &str| match topics.get(index) {
+				Some(Value::String(topic)) => *topic == address_topic(address),
+				_ => true,
+			};
+			let logs = TRANSFERS
+				.iter()
+				.filter(|(block, ..)| (from..=to).contains(block))
+				.filter(|(_, _, from, to, _)| {
+					filter["address"].as_str() == Some(TOKEN)
+						&& matches(0, TRANSFER_TOPIC)
+						&& matches(1, from) && matches(2, to)
+				})
+				.map(|(block, index, from, to, value)| {
+					json!({
+						"address": TOKEN,
+						"blockNumber": quantity(*block as u128),
+						"logIndex": quantity(*index as u128),
+						"topics": [TRANSFER_TOPIC, address_topic(from), address_topic(to)],
+						"data": uint256(*value),
+					})
+				})
+				.collect();
+			Ok(Value::Array(logs))
+		},
+		_ => Err("method not found"),
+	}
+}
+
+fn token_balance(address: &str, number: u64) -> u128 {
+	TRANSFERS.iter().filter(|(block, ..)| *block <= number).fold(
+		0,
+		|balance, (_, _, from, to, value)| {
+			if *to == address {
+				balance + value
+			} else if *from == address {
+				balance - value
+			} else {
+				balance
+			}
+		},
+	)
+}
+
+fn block_number(tag: Option<&Value>) -> Result<u64, &'static str> {
+	match tag.and_then(|tag| tag.as_str()) {
+		Some("latest") | None => Ok(LATEST_BLOCK),
+		Some(tag) => u64::from_str_radix(tag.trim_start_matches("0x"), 16)
+			.map_err(|_| "invalid block number"),
+	}
+}
+
+fn quantity(value: u128) -> Value {
+	format!("0x{:x}", value).into()
+}
+
+fn uint256(value: u128) -> Value {
+	format!("0x{:064x}", value).into()
+}
+
+fn address_topic(address: &str) -> String {
+	format!("0x{:0>64}", address.trim_start_matches("0x"))
+}
@@ -0,0 +1,95 @@
+// Copyright 2020-2023 Litentry Technologies GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+#![allow(opaque_hidden_inferred_bound)]
+use crate::{UserShieldingKeyType, MOCK_VERIFICATION_NONCE};
+use ita_stf::helpers::get_expected_raw_message;
+use lc_data_providers::github_official::*;
+use litentry_primitives::{Identity, IdentityString};
+use sp_core::{sr25519::Pair as Sr25519Pair, Pair};
+use std::{collections::BTreeMap, sync::Arc};
+use warp::{http::Response, Filter};
+
+const GITHUB_MOCK_USER_NAME: &str = "mock_user";
+const GITHUB_MOCK_USER_ID: u64 = 1001;
+
+// the gist_id/comment_id is used as sidechain_nonce, see `twitter_official::query_tweet`
+fn expected_payload<F>(func: &Arc<F>, sidechain_nonce: u32) -> String
+where
+	F: Fn(&Sr25519Pair) -> UserShieldingKeyType + Send + Sync + 'static,
+{
+	let alice = Sr25519Pair::from_string("//Alice", None).unwrap();
+	let github_identity = Identity::Github(
+		IdentityString::try_from(GITHUB_MOCK_USER_NAME.as_bytes().to_vec()).unwrap(),
+	);
+	let key = func(&alice);
+	hex::encode(get_expected_raw_message(
+		&alice.public().into(),
+		&github_identity,
+		sidechain_nonce,
+		key,
+		MOCK_VERIFICATION_NONCE,
+	))
+}
+
+fn mock_user() -> GithubUser {
+	// intentionally return login with a different case, GitHub logins are case insensitive
+	GithubUser { login: GITHUB_MOCK_USER_NAME.to_uppercase(), id: GITHUB_MOCK_USER_ID }
+}
+
+pub(crate) fn query_gist<F>(
+	func: Arc<F>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+where
+	F: Fn(&Sr25519Pair) -> UserShieldingKeyType + Send + Sync + 'static,
+{
+	warp::get().and(warp::path!("gists" / u32)).map(move |gist_id: u32| {
+		println!("query_gist, gist_id: {}", gist_id);
+		let payload = expected_payload(&func, gist_id);
+		println!("query_gist, payload: {}", payload);
+
+		let mut files = BTreeMap::new();
+		files.insert(
+			"litentry.txt".to_string(),
+			GithubGistFile { filename: "litentry.txt".to_string(), content: Some(payload) },
+		);
+		let body = GithubGist { id: gist_id.to_string(), public: true, owner: mock_user(), files };
+		Response::builder().body(serde_json::to_string(&body).unwrap())
+	})
+}
+
+pub(crate) fn query_issue_comment<F>(
+	func: Arc<F>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+where
+	F: Fn(&Sr25519Pair) -> UserShieldingKeyType + Send + Sync + 'static,
+{
+	warp::get()
+		.and(warp::path!("repos" / String / String / "issues" / "comments" / u32))
+		.map(move |owner: String, repo: String, comment_id: u32| {
+			println!(
+				"query_issue_comment, owner: {}, repo: {}, comment_id: {}",
+				owner, repo, comment_id
+			);
+			if owner != "litentry" {
+				return Response::builder().status(404).body(String::from("Not Found"))
+			}
+			let payload = expected_payload(&func, comment_id);
+
+			let body =
+				GithubIssueComment { id: comment_id as u64, body: payload, user: mock_user() };
+			Response::builder().body(serde_json::to_string(&body).unwrap())
+		})
+}
@@ -26,6 +26,8 @@ use warp::Filter;
 pub mod achainable;
 pub mod discord_litentry;
 pub mod discord_official;
+pub mod evm_rpc;
+pub mod github_official;
 pub mod twitter_litentry;
 pub mod twitter_official;
 
@@ -72,6 +74,9 @@ where
 					.or(discord_official::query_message())
 					.or(discord_litentry::check_id_hubber())
 					.or(discord_litentry::check_join())
+					.or(github_official::query_gist(getter.clone()))
+					.or(github_official::query_issue_comment(getter.clone()))
+					.or(evm_rpc::query())
 					.or(achainable::query())
 					.boxed(),
 			)
@@ -15,19 +15,31 @@
 // along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
 
 use crate::{handler::TaskHandler, EnclaveOnChainOCallApi, StfTaskContext, TrustedCall, H256};
-use ita_sgx_runtime::Hash;
+use frame_support::traits::Get;
+use ita_sgx_runtime::{pallet_parentchain, Hash, Parentchain, Runtime};
 use itp_sgx_crypto::{ShieldingCryptoDecrypt, ShieldingCryptoEncrypt};
 use itp_sgx_externalities::SgxExternalitiesTrait;
 use itp_stf_executor::traits::StfEnclaveSigning;
 use itp_stf_state_handler::handle_state::HandleState;
 use itp_top_pool_author::traits::AuthorApi;
-use lc_credentials::DID;
-use lc_data_providers::GLOBAL_DATA_PROVIDER_CONFIG;
+use itp_types::{AccountId, BlockNumber};
+use lc_credentials::{Credential, Error as CredentialError, Proof, SdJwt, DID};
+use lc_data_providers::{
+	get_credential_endpoint,
+	native::{ParentchainAnchor, ParentchainCheckpoints},
+};
 use lc_stf_task_sender::AssertionBuildRequest;
-use litentry_primitives::{Assertion, ErrorDetail, ErrorString, Identity, VCMPError};
+use litentry_primitives::{
+	Assertion, ErrorDetail, ErrorString, Identity, IntoErrorDetail, VCMPError,
+};
 use log::*;
 use sp_core::hashing::blake2_256;
-use std::{format, sync::Arc, vec::Vec};
+use std::{format, str::from_utf8, sync::Arc, vec::Vec};
+
+#[cfg(feature = "std")]
+use std::sync::Mutex;
+#[cfg(feature = "sgx")]
+use std::sync::SgxMutex as Mutex;
 
 pub(crate) struct AssertionHandler<
 	K: ShieldingCryptoDecrypt + ShieldingCryptoEncrypt + Clone,
@@ -46,13 +58,21 @@ where
 	A: AuthorApi<Hash, Hash>,
 	S: StfEnclaveSigning,
 	H: HandleState,
-	H::StateT: SgxExternalitiesTrait,
+	H::StateT: SgxExternalitiesTrait + Send + 'static,
 	O: EnclaveOnChainOCallApi,
 {
 	type Error = VCMPError;
-	type Result = (H256, H256, Vec<u8>); // (vc_index, vc_hash, vc_byte_array)
+	// (vc_index, vc_hash, vc_byte_array, pseudonym of a pairwise VC)
+	type Result = (H256, H256, Vec<u8>, Option<AccountId>);
 
 	fn on_process(&self) -> Result<Self::Result, Self::Error> {
+		let registry = self.context.web3network_registry(&self.req.shard).map_err(|e| {
+			VCMPError::RequestVCFailed(
+				self.req.assertion.clone(),
+				ErrorDetail::StfError(ErrorString::truncate_from(format!("{e:?}").into())),
+			)
+		})?;
+
 		// create the initial credential
 		// TODO: maybe we can further simplify this
 		let mut credential = match self.req.assertion.clone() {
@@ -63,14 +83,16 @@ where
 			Assertion::A3(guild_id, channel_id, role_id) =>
 				lc_assertion_build::a3::build(&self.req, guild_id, channel_id, role_id),
 
-			Assertion::A4(min_balance) => lc_assertion_build::a4::build(&self.req, min_balance),
+			Assertion::A4(min_balance) =>
+				lc_assertion_build::a4::build(&self.req, min_balance, self.parentchain_anchor()),
 
 			Assertion::A6 => lc_assertion_build::a6::build(&self.req),
 
-			Assertion::A7(min_balance) => lc_assertion_build::a7::build(&self.req, min_balance),
+			Assertion::A7(min_balance) =>
+				lc_assertion_build::a7::build(&self.req, min_balance, self.parentchain_anchor()),
 
 			// no need to pass `networks` again because it's the same as the `get_supported_web3networks`
-			Assertion::A8(_networks) => lc_assertion_build::a8::build(&self.req),
+			Assertion::A8(_networks) => lc_assertion_build::a8::build(&self.req, &registry),
 
 			Assertion::A10(min_balance) => lc_assertion_build::a10::build(&self.req, min_balance),
 
@@ -79,9 +101,10 @@ where
 			Assertion::A13(owner) =>
 				lc_assertion_build::a13::build(&self.req, self.context.ocall_api.clone(), &owner),
 
-			Assertion::A14 => lc_assertion_build::a14::build(&self.req),
+			Assertion::A14 => lc_assertion_build::a14::build(&self.req, self.parentchain_anchor()),
 
-			Assertion::Achainable(param) => lc_assertion_build::achainable::build(&self.req, param),
+			Assertion::Achainable(param) =>
+				lc_assertion_build::achainable::build(&self.req, param, &registry),
 
 			_ => {
 				unimplemented!()
@@ -97,10 +120,20 @@ where
 			)
 		})?;
 
-		let credential_endpoint =
-			GLOBAL_DATA_PROVIDER_CONFIG.read().unwrap().credential_endpoint.clone();
+		let credential_endpoint = get_credential_endpoint().map_err(|e| {
+			VCMPError::RequestVCFailed(self.req.assertion.clone(), e.into_error_detail())
+		})?;
 		credential.credential_subject.set_endpoint(credential_endpoint);
 
+		credential
+			.set_validity_period(self.req.assertion.validity_period())
+			.map_err(|e| {
+				VCMPError::RequestVCFailed(
+					self.req.assertion.clone(),
+					ErrorDetail::StfError(ErrorString::truncate_from(format!("{e:?}").into())),
+				)
+			})?;
+
 		credential.issuer.id = DID::try_from(&Identity::Substrate(enclave_account.into()))
 			.map_err(|e| {
 				VCMPError::RequestVCFailed(
@@ -109,8 +142,19 @@ where
 				)
 			})?
 			.format();
-		let payload = credential.issuer.mrenclave.clone();
-		let (enclave_account, sig) = signer.sign_vc_with_self(payload.as_bytes()).map_err(|e| {
+
+		if let Some(verifier) = &self.req.verifier {
+			return self.issue_pairwise(credential, verifier)
+		}
+
+		let proof = Proof::new(&credential.issuer.id);
+		let payload = credential.proof_payload(&proof).map_err(|e| {
+			VCMPError::RequestVCFailed(
+				self.req.assertion.clone(),
+				ErrorDetail::StfError(ErrorString::truncate_from(format!("{e:?}").into())),
+			)
+		})?;
+		let (_, sig) = signer.sign_vc_with_self(&payload).map_err(|e| {
 			VCMPError::RequestVCFailed(
 				self.req.assertion.clone(),
 				ErrorDetail::StfError(ErrorString::truncate_from(format!("{e:?}").into())),
@@ -118,7 +162,7 @@ where
 		})?;
 		debug!("Credential Payload signature: {:?}", sig);
 
-		credential.add_proof(&sig, &enclave_account);
+		credential.add_proof(proof, &sig);
 		credential.validate().map_err(|e| {
 			VCMPError::RequestVCFailed(
 				self.req.assertion.clone(),
@@ -141,14 +185,14 @@ where
 		debug!("Credential: {}, length: {}", credential_str, credential_str.len());
 		let vc_hash = blake2_256(credential_str.as_bytes()).into();
 		debug!("VC hash: {:?}", vc_hash);
-		Ok((vc_index, vc_hash, credential_str.as_bytes().to_vec()))
+		Ok((vc_index, vc_hash, credential_str.as_bytes().to_vec(), None))
 	}
 
 	fn on_success(&self, result: Self::Result) {
 		debug!("Assertion build OK");
 		// we shouldn't have the maximum text length limit in normal RSA3072 encryption, as the payload
 		// using enclave's shielding key is encrypted in chunks
-		let (vc_index, vc_hash, vc_payload) = result;
+		let (vc_index, vc_hash, vc_payload, pseudonym) = result;
 		if let Ok(enclave_signer) = self.context.enclave_signer.get_enclave_account() {
 			let c = TrustedCall::request_vc_callback(
 				enclave_signer.into(),
@@ -157,6 +201,8 @@ where
 				vc_index,
 				vc_hash,
 				vc_payload,
+				pseudonym,
+				self.req.renewed_index,
 				self.req.req_ext_hash,
 			);
 			let _ = self
@@ -186,3 +232,94 @@ where
 		}
 	}
 }
+
+impl<K, A, S, H, O> AssertionHandler<K, A, S, H, O>
+where
+	K: ShieldingCryptoDecrypt + ShieldingCryptoEncrypt + Clone,
+	A: AuthorApi<Hash, Hash>,
+	S: StfEnclaveSigning,
+	H: HandleState,
+	H::StateT: SgxExternalitiesTrait + Send + 'static,
+	O: EnclaveOnChainOCallApi,
+{
+	// Issues the credential about the pairwise-pseudonymous DID of `who` towards `verifier` as
+	// SD-JWT, so that the holder can further choose which claims to present.
+	fn issue_pairwise(
+		&self,
+		mut credential: Credential,
+		verifier: &[u8],
+	) -> Result<<Self as TaskHandler>::Result, VCMPError> {
+		let verifier = from_utf8(verifier).map_err(|_| {
+			VCMPError::RequestVCFailed(self.req.assertion.clone(), ErrorDetail::ParseError)
+		})?;
+		let pseudonym =
+			credential.set_pairwise_subject(&self.req.who, verifier, &self.context.pairwise_secret);
+
+		let signer = self.context.enclave_signer.as_ref();
+		let sd_jwt = SdJwt::issue(&credential, |signing_input| {
+			signer
+				.sign_vc_with_self(signing_input)
+				.map(|(_, sig)| sig)
+				.map_err(|e| CredentialError::RuntimeError(format!("{e:?}")))
+		})
+		.map_err(|e| {
+			VCMPError::RequestVCFailed(
+				self.req.assertion.clone(),
+				ErrorDetail::StfError(ErrorString::truncate_from(format!("{e:?}").into())),
+			)
+		})?;
+
+		let vc_index = credential
+			.get_index()
+			.map_err(|e| {
+				VCMPError::RequestVCFailed(
+					self.req.assertion.clone(),
+					ErrorDetail::StfError(ErrorString::truncate_from(format!("{e:?}").into())),
+				)
+			})?
+			.into();
+		let sd_jwt = sd_jwt.encode();
+		debug!("SD-JWT credential length: {}", sd_jwt.len());
+		let vc_hash = blake2_256(sd_jwt.as_bytes()).into();
+		Ok((vc_index, vc_hash, sd_jwt.into_bytes(), Some(pseudonym.into())))
+	}
+
+	// The parentchain block the light client imported last, the native data provider reads
+	// the parentchain at this block.
+	fn parentchain_anchor(&self) -> Option<ParentchainAnchor> {
+		let (mut state, _) = self
+			.context
+			.state_handler
+			.load_cloned(&self.req.shard)
+			.map_err(|e| warn!("Failed to load the state of {:?}: {:?}", self.req.shard, e))
+			.ok()?;
+		let (number, hash) =
+			state.execute_with(|| (Parentchain::block_number(), Parentchain::block_hash()));
+		// no block imported yet
+		if number == 0 {
+			return None
+		}
+		Some(ParentchainAnchor {
+			number,
+			hash,
+			checkpoints: Arc::new(StateCheckpoints(Mutex::new(state))),
+		})
+	}
+}
+
+// The checkpoints of the parentchain in the state the anchor was read from.
+struct StateCheckpoints<State>(Mutex<State>);
+
+impl<State: SgxExternalitiesTrait + Send> ParentchainCheckpoints for StateCheckpoints<State> {
+	fn interval(&self) -> BlockNumber {
+		<Runtime as pallet_parentchain::Config>::CheckpointInterval::get()
+	}
+
+	fn first(&self) -> Option<BlockNumber> {
+		self.0.lock().ok()?.execute_with(Parentchain::first_checkpoint)
+	}
+
+	fn checkpoint(&self, number: BlockNumber) -> Option<H256> {
+		self.0.lock().ok()?.execute_with(|| Parentchain::checkpoint(number))
+	}
+}
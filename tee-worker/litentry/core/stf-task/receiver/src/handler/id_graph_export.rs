@@ -0,0 +1,101 @@
+// Copyright 2020-2023 Litentry Technologies GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{handler::TaskHandler, EnclaveOnChainOCallApi, StfTaskContext, TrustedCall};
+use codec::Encode;
+use ita_sgx_runtime::Hash;
+use itp_sgx_crypto::{ShieldingCryptoDecrypt, ShieldingCryptoEncrypt};
+use itp_sgx_externalities::SgxExternalitiesTrait;
+use itp_stf_executor::traits::StfEnclaveSigning;
+use itp_stf_state_handler::handle_state::HandleState;
+use itp_top_pool_author::traits::AuthorApi;
+use lc_stf_task_sender::IDGraphExportRequest;
+use litentry_primitives::{ErrorDetail, ErrorString, IMPError, SignedIDGraphExport};
+use log::*;
+use std::{format, sync::Arc};
+
+pub(crate) struct IDGraphExportHandler<
+	K: ShieldingCryptoDecrypt + ShieldingCryptoEncrypt + Clone,
+	A: AuthorApi<Hash, Hash>,
+	S: StfEnclaveSigning,
+	H: HandleState,
+	O: EnclaveOnChainOCallApi,
+> {
+	pub(crate) req: IDGraphExportRequest,
+	pub(crate) context: Arc<StfTaskContext<K, A, S, H, O>>,
+}
+
+impl<K, A, S, H, O> TaskHandler for IDGraphExportHandler<K, A, S, H, O>
+where
+	K: ShieldingCryptoDecrypt + ShieldingCryptoEncrypt + Clone,
+	A: AuthorApi<Hash, Hash>,
+	S: StfEnclaveSigning,
+	H: HandleState,
+	H::StateT: SgxExternalitiesTrait,
+	O: EnclaveOnChainOCallApi,
+{
+	type Error = IMPError;
+	type Result = SignedIDGraphExport;
+
+	fn on_process(&self) -> Result<Self::Result, Self::Error> {
+		let (signer, signature) = self
+			.context
+			.enclave_signer
+			.sign_vc_with_self(&self.req.export.encode())
+			.map_err(|e| {
+				IMPError::LinkIdentityFailed(ErrorDetail::StfError(ErrorString::truncate_from(
+					format!("{e:?}").into(),
+				)))
+			})?;
+		Ok(SignedIDGraphExport { export: self.req.export.clone(), signer, signature })
+	}
+
+	fn on_success(&self, result: Self::Result) {
+		debug!("sign the IDGraph export of {} identities OK", result.export.identities.len());
+		if let Ok(enclave_signer) = self.context.enclave_signer.get_enclave_account() {
+			let c = TrustedCall::export_id_graph_callback(
+				enclave_signer.into(),
+				self.req.who.clone(),
+				result,
+				self.req.req_ext_hash,
+			);
+			let _ = self
+				.context
+				.submit_trusted_call(&self.req.shard, &self.req.top_hash, &c)
+				.map_err(|e| error!("submit_trusted_call failed: {:?}", e));
+		} else {
+			error!("can't get enclave signer");
+		}
+	}
+
+	fn on_failure(&self, error: Self::Error) {
+		error!("export id_graph failed:{:?}", error);
+		if let Ok(enclave_signer) = self.context.enclave_signer.get_enclave_account() {
+			let c = TrustedCall::handle_imp_error(
+				enclave_signer.into(),
+				Some(self.req.who.clone()),
+				error,
+				self.req.req_ext_hash,
+			);
+			let _ = self
+				.context
+				.submit_trusted_call(&self.req.shard, &self.req.top_hash, &c)
+				.map_err(|e| error!("submit_trusted_call failed: {:?}", e));
+		} else {
+			error!("can't get enclave signer");
+		}
+	}
+}
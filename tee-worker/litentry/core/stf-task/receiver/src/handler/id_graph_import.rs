@@ -0,0 +1,269 @@
+// Copyright 2020-2023 Litentry Technologies GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{handler::TaskHandler, EnclaveOnChainOCallApi, Error, StfTaskContext, TrustedCall};
+use ita_sgx_runtime::Hash;
+use itp_sgx_crypto::{ShieldingCryptoDecrypt, ShieldingCryptoEncrypt};
+use itp_sgx_externalities::SgxExternalitiesTrait;
+use itp_stf_executor::traits::StfEnclaveSigning;
+use itp_stf_state_handler::handle_state::HandleState;
+use itp_top_pool_author::traits::AuthorApi;
+use itp_types::AccountId;
+use lc_stf_task_sender::IDGraphImportRequest;
+use litentry_primitives::{
+	ErrorDetail, ErrorString, IMPError, IdentityExport, Web3NetworkRegistry,
+};
+use log::*;
+use std::{format, sync::Arc, vec::Vec};
+
+pub(crate) struct IDGraphImportHandler<
+	K: ShieldingCryptoDecrypt + ShieldingCryptoEncrypt + Clone,
+	A: AuthorApi<Hash, Hash>,
+	S: StfEnclaveSigning,
+	H: HandleState,
+	O: EnclaveOnChainOCallApi,
+> {
+	pub(crate) req: IDGraphImportRequest,
+	pub(crate) context: Arc<StfTaskContext<K, A, S, H, O>>,
+}
+
+fn stf_error(e: Error) -> IMPError {
+	IMPError::LinkIdentityFailed(ErrorDetail::StfError(ErrorString::truncate_from(
+		format!("{e:?}").into(),
+	)))
+}
+
+// Returns the identities of the export in `req` whose link proofs are still valid. The export
+// must be of `req.who` and signed by an enclave, `is_registered_enclave` tells whether its signer
+// is registered on the parentchain.
+fn verify_export(
+	req: &IDGraphImportRequest,
+	registry: &Web3NetworkRegistry,
+	is_registered_enclave: impl FnOnce(&AccountId) -> Result<bool, Error>,
+) -> Result<Vec<IdentityExport>, IMPError> {
+	let signed = &req.export;
+	if signed.export.who != req.who || !signed.verify_signature() {
+		return Err(IMPError::LinkIdentityFailed(ErrorDetail::InvalidIDGraphExport))
+	}
+	if !is_registered_enclave(&signed.signer).map_err(stf_error)? {
+		warn!("the IDGraph export is signed by the unregistered {:?}", signed.signer);
+		return Err(IMPError::LinkIdentityFailed(ErrorDetail::InvalidIDGraphExport))
+	}
+
+	let verified = signed
+		.export
+		.identities
+		.iter()
+		.filter(|e| match &e.proof {
+			Some(proof) => match lc_identity_verification::verify_link_proof(
+				&req.who,
+				&e.identity,
+				proof,
+				&req.key,
+				registry,
+			) {
+				Ok(()) => true,
+				Err(err) => {
+					warn!("skip importing {:?}: {:?}", e.identity, err);
+					false
+				},
+			},
+			None => false,
+		})
+		.cloned()
+		.collect();
+	Ok(verified)
+}
+
+impl<K, A, S, H, O> TaskHandler for IDGraphImportHandler<K, A, S, H, O>
+where
+	K: ShieldingCryptoDecrypt + ShieldingCryptoEncrypt + Clone,
+	A: AuthorApi<Hash, Hash>,
+	S: StfEnclaveSigning,
+	H: HandleState,
+	H::StateT: SgxExternalitiesTrait,
+	O: EnclaveOnChainOCallApi,
+{
+	type Error = IMPError;
+	// the identities whose proofs are still valid
+	type Result = Vec<IdentityExport>;
+
+	fn on_process(&self) -> Result<Self::Result, Self::Error> {
+		let registry = self.context.web3network_registry(&self.req.shard).map_err(stf_error)?;
+		verify_export(&self.req, &registry, |signer| {
+			self.context.is_registered_enclave(&self.req.shard, signer)
+		})
+	}
+
+	fn on_success(&self, result: Self::Result) {
+		debug!(
+			"verify {} of {} link proofs OK",
+			result.len(),
+			self.req.export.export.identities.len()
+		);
+		if let Ok(enclave_signer) = self.context.enclave_signer.get_enclave_account() {
+			let c = TrustedCall::import_id_graph_callback(
+				enclave_signer.into(),
+				self.req.who.clone(),
+				result,
+				self.req.req_ext_hash,
+			);
+			let _ = self
+				.context
+				.submit_trusted_call(&self.req.shard, &self.req.top_hash, &c)
+				.map_err(|e| error!("submit_trusted_call failed: {:?}", e));
+		} else {
+			error!("can't get enclave signer");
+		}
+	}
+
+	fn on_failure(&self, error: Self::Error) {
+		error!("import id_graph failed:{:?}", error);
+		if let Ok(enclave_signer) = self.context.enclave_signer.get_enclave_account() {
+			let c = TrustedCall::handle_imp_error(
+				enclave_signer.into(),
+				Some(self.req.who.clone()),
+				error,
+				self.req.req_ext_hash,
+			);
+			let _ = self
+				.context
+				.submit_trusted_call(&self.req.shard, &self.req.top_hash, &c)
+				.map_err(|e| error!("submit_trusted_call failed: {:?}", e));
+		} else {
+			error!("can't get enclave signer");
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::Encode;
+	use ita_stf::helpers::get_expected_raw_message;
+	use litentry_primitives::{
+		IDGraphExport, Identity, IdentityStatus, LinkProof, LitentryMultiSignature,
+		SignedIDGraphExport, UserShieldingKeyType, Web3CommonValidationData, Web3Network,
+		Web3ValidationData,
+	};
+	use sp_core::{ed25519, sr25519, Pair};
+
+	const KEY: UserShieldingKeyType = [7u8; 32];
+
+	fn who() -> Identity {
+		Identity::Substrate([1u8; 32].into())
+	}
+
+	fn enclave() -> ed25519::Pair {
+		ed25519::Pair::from_seed(&[2u8; 32])
+	}
+
+	// `pair` linked with a proof signed while the user shielding key was `key`
+	fn identity_export(pair: &sr25519::Pair, key: UserShieldingKeyType) -> IdentityExport {
+		let identity = Identity::Substrate(pair.public().into());
+		let raw_msg = get_expected_raw_message(&who(), &identity, 3, key, [5u8; 12]);
+		IdentityExport {
+			identity,
+			web3networks: vec![Web3Network::Litentry],
+			status: IdentityStatus::Active,
+			contract_wallet: false,
+			proof: Some(LinkProof {
+				shard: [4u8; 32].into(),
+				mrenclave: [6u8; 32],
+				sidechain_nonce: 3,
+				key_nonce: [5u8; 12],
+				web3networks: vec![Web3Network::Litentry],
+				validation_data: Web3ValidationData::Substrate(Web3CommonValidationData {
+					message: raw_msg.clone().try_into().unwrap(),
+					signature: LitentryMultiSignature::Sr25519(pair.sign(&raw_msg)),
+				}),
+			}),
+		}
+	}
+
+	fn request(identities: Vec<IdentityExport>) -> IDGraphImportRequest {
+		let export = IDGraphExport { who: who(), identities };
+		IDGraphImportRequest {
+			shard: Default::default(),
+			who: who(),
+			export: SignedIDGraphExport {
+				signature: enclave().sign(export.encode().as_slice()).0.to_vec(),
+				export,
+				signer: enclave().public().0.into(),
+			},
+			key: KEY,
+			top_hash: Default::default(),
+			req_ext_hash: Default::default(),
+		}
+	}
+
+	fn registered(account: &AccountId) -> Result<bool, Error> {
+		Ok(*account == AccountId::from(enclave().public().0))
+	}
+
+	#[test]
+	fn verify_export_works() {
+		let valid = identity_export(&sr25519::Pair::from_seed(&[8u8; 32]), KEY);
+		let req = request(vec![valid.clone()]);
+		assert_eq!(verify_export(&req, &Web3NetworkRegistry::new(), registered), Ok(vec![valid]));
+	}
+
+	#[test]
+	fn verify_export_skips_invalid_proofs() {
+		let valid = identity_export(&sr25519::Pair::from_seed(&[8u8; 32]), KEY);
+		// signed with an old user shielding key
+		let outdated = identity_export(&sr25519::Pair::from_seed(&[9u8; 32]), [0u8; 32]);
+		let unproven = IdentityExport {
+			proof: None,
+			..identity_export(&sr25519::Pair::from_seed(&[10u8; 32]), KEY)
+		};
+		let req = request(vec![outdated, valid.clone(), unproven]);
+		assert_eq!(verify_export(&req, &Web3NetworkRegistry::new(), registered), Ok(vec![valid]));
+	}
+
+	#[test]
+	fn verify_export_rejects_invalid_signature() {
+		let mut req = request(vec![identity_export(&sr25519::Pair::from_seed(&[8u8; 32]), KEY)]);
+		req.export.export.identities.clear();
+		assert_eq!(
+			verify_export(&req, &Web3NetworkRegistry::new(), registered),
+			Err(IMPError::LinkIdentityFailed(ErrorDetail::InvalidIDGraphExport))
+		);
+	}
+
+	#[test]
+	fn verify_export_rejects_export_of_other_identity() {
+		let mut req = request(vec![]);
+		req.who = Identity::Substrate([3u8; 32].into());
+		assert_eq!(
+			verify_export(&req, &Web3NetworkRegistry::new(), registered),
+			Err(IMPError::LinkIdentityFailed(ErrorDetail::InvalidIDGraphExport))
+		);
+	}
+
+	#[test]
+	fn verify_export_rejects_unregistered_enclave() {
+		let req = request(vec![identity_export(&sr25519::Pair::from_seed(&[8u8; 32]), KEY)]);
+		assert_eq!(
+			verify_export(&req, &Web3NetworkRegistry::new(), |_| Ok(false)),
+			Err(IMPError::LinkIdentityFailed(ErrorDetail::InvalidIDGraphExport))
+		);
+		assert!(verify_export(&req, &Web3NetworkRegistry::new(), |_| Err(Error::OtherError(
+			"no parentchain block was imported yet".into()
+		)))
+		.is_err());
+	}
+}
@@ -22,9 +22,9 @@ use itp_stf_executor::traits::StfEnclaveSigning;
 use itp_stf_state_handler::handle_state::HandleState;
 use itp_top_pool_author::traits::AuthorApi;
 use lc_stf_task_sender::IdentityVerificationRequest;
-use litentry_primitives::IMPError;
+use litentry_primitives::{ErrorDetail, ErrorString, IMPError, LinkProof, ValidationData};
 use log::*;
-use std::sync::Arc;
+use std::{format, sync::Arc};
 
 pub(crate) struct IdentityVerificationHandler<
 	K: ShieldingCryptoDecrypt + ShieldingCryptoEncrypt + Clone,
@@ -47,13 +47,35 @@ where
 	O: EnclaveOnChainOCallApi,
 {
 	type Error = IMPError;
-	type Result = ();
+	// what a web3 identity is linked with, it's kept for the IDGraph export
+	type Result = Option<LinkProof>;
 
 	fn on_process(&self) -> Result<Self::Result, Self::Error> {
-		lc_identity_verification::verify(&self.req)
+		let stf_error = |e: &dyn core::fmt::Debug| {
+			IMPError::LinkIdentityFailed(ErrorDetail::StfError(ErrorString::truncate_from(
+				format!("{e:?}").into(),
+			)))
+		};
+		let registry =
+			self.context.web3network_registry(&self.req.shard).map_err(|e| stf_error(&e))?;
+		let mrenclave = self.context.enclave_signer.get_mrenclave().map_err(|e| stf_error(&e))?;
+		lc_identity_verification::verify(&self.req, &mrenclave, &registry)?;
+
+		let proof = match &self.req.validation_data {
+			ValidationData::Web3(data) => Some(LinkProof {
+				shard: self.req.shard,
+				mrenclave,
+				sidechain_nonce: self.req.sidechain_nonce,
+				key_nonce: self.req.key_nonce,
+				web3networks: self.req.web3networks.clone(),
+				validation_data: data.clone(),
+			}),
+			ValidationData::Web2(_) => None,
+		};
+		Ok(proof)
 	}
 
-	fn on_success(&self, _result: Self::Result) {
+	fn on_success(&self, result: Self::Result) {
 		debug!("verify identity OK");
 		if let Ok(enclave_signer) = self.context.enclave_signer.get_enclave_account() {
 			let c = TrustedCall::link_identity_callback(
@@ -61,6 +83,8 @@ where
 				self.req.who.clone(),
 				self.req.identity.clone(),
 				self.req.web3networks.clone(),
+				self.req.validation_data.is_contract_wallet(),
+				result,
 				self.req.req_ext_hash,
 			);
 			let _ = self
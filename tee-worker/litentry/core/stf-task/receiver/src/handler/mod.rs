@@ -15,6 +15,8 @@
 // along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
 
 pub mod assertion;
+pub mod id_graph_export;
+pub mod id_graph_import;
 pub mod identity_verification;
 
 pub trait TaskHandler {
@@ -35,25 +35,35 @@ use crate::sgx_reexport_prelude::*;
 compile_error!("feature \"std\" and feature \"sgx\" cannot be enabled at the same time");
 
 mod handler;
+pub mod worker_pool;
 
 use codec::Encode;
-use frame_support::sp_tracing::warn;
+use frame_support::{sp_tracing::warn, traits::Get};
 use futures::executor;
 use handler::{
-	assertion::AssertionHandler, identity_verification::IdentityVerificationHandler, TaskHandler,
+	assertion::AssertionHandler, id_graph_export::IDGraphExportHandler,
+	id_graph_import::IDGraphImportHandler, identity_verification::IdentityVerificationHandler,
+	TaskHandler,
 };
-use ita_sgx_runtime::Hash;
+use ita_sgx_runtime::{Hash, Parentchain, ParentchainWeb3Networks};
 use ita_stf::{hash::Hash as TopHash, TrustedCall, TrustedOperation};
-use itp_ocall_api::EnclaveOnChainOCallApi;
+use itp_enclave_metrics::{EnclaveMetric, StfTaskPoolMetric};
+use itp_ocall_api::{EnclaveMetricsOCallApi, EnclaveOnChainOCallApi};
 use itp_sgx_crypto::{ShieldingCryptoDecrypt, ShieldingCryptoEncrypt};
 use itp_sgx_externalities::SgxExternalitiesTrait;
 use itp_stf_executor::traits::StfEnclaveSigning;
 use itp_stf_state_handler::handle_state::HandleState;
+use itp_teerex_storage::{TeeRexStorage, TeerexStorageKeys};
 use itp_top_pool_author::traits::AuthorApi;
-use itp_types::{ShardIdentifier, H256};
+use itp_types::{AccountId, ShardIdentifier, H256};
 use lc_stf_task_sender::{stf_task_sender, RequestType};
+use litentry_primitives::Web3NetworkRegistry;
 use log::{debug, error};
 use std::{format, string::String, sync::Arc};
+use worker_pool::{
+	ordering_key, Provider, TaskMetricsSnapshot, WorkerPool, WorkerPoolConfig,
+	GLOBAL_STF_TASK_METRICS,
+};
 
 #[derive(Debug, thiserror::Error, Clone)]
 pub enum Error {
@@ -80,6 +90,8 @@ pub struct StfTaskContext<
 	enclave_signer: Arc<S>,
 	pub state_handler: Arc<H>,
 	pub ocall_api: Arc<O>,
+	// the secret pairwise-pseudonymous subject DIDs are derived with, see `DID::pairwise`
+	pairwise_secret: [u8; 32],
 }
 
 impl<
@@ -90,7 +102,7 @@ impl<
 		O: EnclaveOnChainOCallApi,
 	> StfTaskContext<K, A, S, H, O>
 where
-	H::StateT: SgxExternalitiesTrait,
+	H::StateT: SgxExternalitiesTrait + Send + 'static,
 {
 	pub fn new(
 		shielding_key: K,
@@ -98,8 +110,16 @@ where
 		enclave_signer: Arc<S>,
 		state_handler: Arc<H>,
 		ocall_api: Arc<O>,
+		pairwise_secret: [u8; 32],
 	) -> Self {
-		Self { shielding_key, author_api, enclave_signer, state_handler, ocall_api }
+		Self {
+			shielding_key,
+			author_api,
+			enclave_signer,
+			state_handler,
+			ocall_api,
+			pairwise_secret,
+		}
 	}
 
 	fn submit_trusted_call(
@@ -157,6 +177,41 @@ where
 
 		Ok(())
 	}
+
+	fn load_state(&self, shard: &ShardIdentifier) -> Result<H::StateT, Error> {
+		let (state, _) = self
+			.state_handler
+			.load_cloned(shard)
+			.map_err(|e| Error::OtherError(format!("load state of {:?}: {:?}", shard, e)))?;
+		Ok(state)
+	}
+
+	// the registered web3 networks of the parentchain, as mirrored into the state of `shard`,
+	// the handlers pass them on to the data providers and the identity verification
+	fn web3network_registry(&self, shard: &ShardIdentifier) -> Result<Web3NetworkRegistry, Error> {
+		Ok(self.load_state(shard)?.execute_with(ParentchainWeb3Networks::get))
+	}
+
+	// whether `account` is registered as an enclave on the parentchain, which is read with a
+	// storage proof at the last block the light client of `shard` imported
+	fn is_registered_enclave(
+		&self,
+		shard: &ShardIdentifier,
+		account: &AccountId,
+	) -> Result<bool, Error> {
+		let header = self
+			.load_state(shard)?
+			.execute_with(Parentchain::last_header)
+			.ok_or_else(|| Error::OtherError("no parentchain block was imported yet".into()))?;
+		let index: Option<u64> = self
+			.ocall_api
+			.get_storage_verified(TeeRexStorage::enclave_index(account.as_ref()), &header)
+			.map_err(|e| Error::OtherError(format!("read enclave index: {:?}", e)))?
+			.into_tuple()
+			.1;
+		// the indices start with 1
+		Ok(index.map_or(false, |index| index > 0))
+	}
 }
 
 // lifetime elision: StfTaskContext is guaranteed to outlive the fn
@@ -164,26 +219,72 @@ pub fn run_stf_task_receiver<K, A, S, H, O>(
 	context: Arc<StfTaskContext<K, A, S, H, O>>,
 ) -> Result<(), Error>
 where
-	K: ShieldingCryptoDecrypt + ShieldingCryptoEncrypt + Clone,
-	A: AuthorApi<Hash, Hash>,
-	S: StfEnclaveSigning,
-	H: HandleState,
-	H::StateT: SgxExternalitiesTrait,
-	O: EnclaveOnChainOCallApi,
+	K: ShieldingCryptoDecrypt + ShieldingCryptoEncrypt + Clone + Send + Sync + 'static,
+	A: AuthorApi<Hash, Hash> + Send + Sync + 'static,
+	S: StfEnclaveSigning + Send + Sync + 'static,
+	H: HandleState + Send + Sync + 'static,
+	H::StateT: SgxExternalitiesTrait + Send + 'static,
+	O: EnclaveOnChainOCallApi + EnclaveMetricsOCallApi + 'static,
+{
+	run_stf_task_receiver_with_config(context, WorkerPoolConfig::default())
+}
+
+/// Receive the stf tasks and process them in a bounded worker pool, see [`WorkerPool`].
+pub fn run_stf_task_receiver_with_config<K, A, S, H, O>(
+	context: Arc<StfTaskContext<K, A, S, H, O>>,
+	config: WorkerPoolConfig,
+) -> Result<(), Error>
+where
+	K: ShieldingCryptoDecrypt + ShieldingCryptoEncrypt + Clone + Send + Sync + 'static,
+	A: AuthorApi<Hash, Hash> + Send + Sync + 'static,
+	S: StfEnclaveSigning + Send + Sync + 'static,
+	H: HandleState + Send + Sync + 'static,
+	H::StateT: SgxExternalitiesTrait + Send + 'static,
+	O: EnclaveOnChainOCallApi + EnclaveMetricsOCallApi + 'static,
 {
 	let receiver = stf_task_sender::init_stf_task_sender_storage()
 		.map_err(|e| Error::OtherError(format!("read storage error:{:?}", e)))?;
 
+	let ocall_api = context.ocall_api.clone();
+	let pool = WorkerPool::new(
+		config,
+		&GLOBAL_STF_TASK_METRICS,
+		Arc::new(move |snapshot| {
+			let metric = EnclaveMetric::StfTaskPool(to_pool_metric(snapshot));
+			if let Err(e) = ocall_api.update_metric(metric) {
+				warn!("Failed to update the stf task metrics: {:?}", e);
+			}
+		}),
+	)?;
+
 	loop {
 		let req = receiver
 			.recv()
 			.map_err(|e| Error::OtherError(format!("receiver error:{:?}", e)))?;
 
-		match &req {
+		let provider = Provider::from(&req);
+		let key = ordering_key(&req).clone();
+		let context = context.clone();
+
+		// a single failed dispatch mustn't stop the receiver
+		if let Err(e) = pool.dispatch(&key, provider, move || match req {
 			RequestType::IdentityVerification(req) =>
-				IdentityVerificationHandler { req: req.clone(), context: context.clone() }.start(),
-			RequestType::AssertionVerification(req) =>
-				AssertionHandler { req: req.clone(), context: context.clone() }.start(),
+				IdentityVerificationHandler { req, context }.start(),
+			RequestType::AssertionVerification(req) => AssertionHandler { req, context }.start(),
+			RequestType::IDGraphImport(req) => IDGraphImportHandler { req, context }.start(),
+			RequestType::IDGraphExport(req) => IDGraphExportHandler { req, context }.start(),
+		}) {
+			error!("Failed to dispatch stf task: {:?}", e);
 		}
 	}
 }
+
+fn to_pool_metric(snapshot: &TaskMetricsSnapshot) -> StfTaskPoolMetric {
+	StfTaskPoolMetric {
+		queued: snapshot.queued as u64,
+		in_flight: snapshot.in_flight.iter().map(|(_, n)| *n as u64).sum(),
+		processed: snapshot.processed as u64,
+		throttled: snapshot.throttled as u64,
+		panicked: snapshot.panicked as u64,
+	}
+}
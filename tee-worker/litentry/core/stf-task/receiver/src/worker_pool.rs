@@ -0,0 +1,717 @@
+// Copyright 2020-2023 Litentry Technologies GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+//! A bounded worker pool to process stf tasks concurrently.
+//!
+//! Every task is dispatched to one of `worker_count` workers based on the hash of its
+//! ordering key (the `who` of the request). Each worker owns a bounded queue and processes
+//! its tasks one after another, so requests of the same identity are always handled (and
+//! their callbacks submitted) in the order they were received.
+//!
+//! On top of that, a per-provider semaphore limits how many tasks may talk to the same
+//! data provider at the same time, so that one slow provider can't occupy all workers.
+//! A task whose provider has no free permit is put aside, and the worker goes on with the
+//! tasks behind it, unless they have the same ordering key as a task that was put aside.
+//!
+//! A panicking task is caught, so it doesn't take its worker down.
+//!
+//! When the queue of a worker is full, `dispatch` blocks until there's space again, which
+//! propagates the back-pressure to the stf task channel.
+
+use crate::Error;
+use codec::Encode;
+use lazy_static::lazy_static;
+use lc_assertion_build::a4::queries_achainable;
+use lc_data_providers::{get_assertion_sources, AssertionSource, AssertionSources};
+use lc_stf_task_sender::RequestType;
+use litentry_primitives::{Assertion, Identity, ValidationData, Web2ValidationData};
+use log::*;
+use sp_core::blake2_256;
+use std::{
+	boxed::Box,
+	collections::VecDeque,
+	format,
+	panic::{catch_unwind, AssertUnwindSafe},
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		mpsc::{sync_channel, Receiver, RecvTimeoutError, SyncSender, TrySendError},
+		Arc,
+	},
+	thread,
+	time::Duration,
+	vec::Vec,
+};
+
+#[cfg(feature = "std")]
+use std::sync::{Condvar, Mutex};
+
+#[cfg(feature = "sgx")]
+use std::sync::{SgxCondvar as Condvar, SgxMutex as Mutex};
+
+/// Default number of workers, it must stay well below the TCS number of the enclave.
+pub const DEFAULT_WORKER_COUNT: usize = 4;
+/// Default capacity of the queue of each worker.
+pub const DEFAULT_QUEUE_CAPACITY: usize = 64;
+/// Default number of tasks that may concurrently use the same remote data provider.
+pub const DEFAULT_PROVIDER_CONCURRENCY: usize = 2;
+/// How often a worker retries the tasks it put aside while no new task arrives.
+const DEFERRED_RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+lazy_static! {
+	/// Queueing metrics of the stf task receiver.
+	pub static ref GLOBAL_STF_TASK_METRICS: TaskMetrics = TaskMetrics::default();
+}
+
+/// The (remote) data provider a task depends on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Provider {
+	Twitter,
+	Discord,
+	Github,
+	Achainable,
+	/// The chains read directly, through the parentchain archive and the EVM RPC providers.
+	Native,
+	/// Tasks that don't call any remote data provider, e.g. web3 signature verification.
+	Local,
+}
+
+impl Provider {
+	pub const ALL: [Provider; 6] =
+		[Self::Twitter, Self::Discord, Self::Github, Self::Achainable, Self::Native, Self::Local];
+
+	fn index(&self) -> usize {
+		match self {
+			Self::Twitter => 0,
+			Self::Discord => 1,
+			Self::Github => 2,
+			Self::Achainable => 3,
+			Self::Native => 4,
+			Self::Local => 5,
+		}
+	}
+
+	// the same selection as the assertion builders, which fail themselves if the
+	// config can't be read
+	fn from_assertion_source(source: impl FnOnce(AssertionSources) -> AssertionSource) -> Self {
+		match get_assertion_sources().map(source) {
+			Ok(AssertionSource::Native) => Self::Native,
+			Ok(AssertionSource::Achainable) => Self::Achainable,
+			Err(e) => {
+				warn!("Failed to get the assertion sources: {:?}", e);
+				Self::Achainable
+			},
+		}
+	}
+}
+
+impl From<&RequestType> for Provider {
+	fn from(req: &RequestType) -> Self {
+		match req {
+			RequestType::IdentityVerification(req) => match &req.validation_data {
+				ValidationData::Web2(Web2ValidationData::Twitter(_)) => Self::Twitter,
+				ValidationData::Web2(Web2ValidationData::Discord(_)) => Self::Discord,
+				ValidationData::Web2(Web2ValidationData::Github(_)) => Self::Github,
+				ValidationData::Web3(_) => Self::Local,
+			},
+			RequestType::AssertionVerification(req) => match &req.assertion {
+				Assertion::A2(..) | Assertion::A3(..) => Self::Discord,
+				Assertion::A6 => Self::Twitter,
+				Assertion::A4(..) => Self::from_assertion_source(|sources| {
+					if queries_achainable(sources.a4, &req.identities) {
+						AssertionSource::Achainable
+					} else {
+						AssertionSource::Native
+					}
+				}),
+				Assertion::A7(..) => Self::from_assertion_source(|sources| sources.a7),
+				Assertion::A10(..) => Self::from_assertion_source(|sources| sources.a10),
+				Assertion::A11(..) => Self::from_assertion_source(|sources| sources.a11),
+				Assertion::A14 => Self::from_assertion_source(|sources| sources.a14),
+				Assertion::A8(..) | Assertion::Achainable(..) => Self::Achainable,
+				Assertion::A1 | Assertion::A9 | Assertion::A13(..) => Self::Local,
+			},
+			// only web3 identities are imported
+			RequestType::IDGraphImport(_) | RequestType::IDGraphExport(_) => Self::Local,
+		}
+	}
+}
+
+/// The key used to preserve the processing order, tasks with the same key are
+/// always processed sequentially by the same worker.
+pub fn ordering_key(req: &RequestType) -> &Identity {
+	match req {
+		RequestType::IdentityVerification(req) => &req.who,
+		RequestType::AssertionVerification(req) => &req.who,
+		RequestType::IDGraphImport(req) => &req.who,
+		RequestType::IDGraphExport(req) => &req.who,
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkerPoolConfig {
+	pub worker_count: usize,
+	pub queue_capacity: usize,
+	/// concurrency limit per provider, indexed in the order of `Provider::ALL`
+	pub provider_limits: [usize; 6],
+}
+
+impl Default for WorkerPoolConfig {
+	fn default() -> Self {
+		Self {
+			worker_count: DEFAULT_WORKER_COUNT,
+			queue_capacity: DEFAULT_QUEUE_CAPACITY,
+			provider_limits: [
+				DEFAULT_PROVIDER_CONCURRENCY,
+				DEFAULT_PROVIDER_CONCURRENCY,
+				DEFAULT_PROVIDER_CONCURRENCY,
+				DEFAULT_PROVIDER_CONCURRENCY,
+				DEFAULT_PROVIDER_CONCURRENCY,
+				DEFAULT_WORKER_COUNT,
+			],
+		}
+	}
+}
+
+impl WorkerPoolConfig {
+	pub fn with_provider_limit(mut self, provider: Provider, limit: usize) -> Self {
+		self.provider_limits[provider.index()] = limit;
+		self
+	}
+
+	fn validate(&self) -> Result<(), Error> {
+		if self.worker_count == 0 || self.queue_capacity == 0 {
+			return Err(Error::OtherError(format!("invalid worker pool config: {:?}", self)))
+		}
+		if self.provider_limits.iter().any(|l| *l == 0) {
+			return Err(Error::OtherError(format!("provider limit can't be 0: {:?}", self)))
+		}
+		Ok(())
+	}
+}
+
+/// Counters describing the state of the worker pool.
+#[derive(Default)]
+pub struct TaskMetrics {
+	queued: AtomicUsize,
+	max_queued: AtomicUsize,
+	in_flight: [AtomicUsize; 6],
+	processed: AtomicUsize,
+	/// number of times a dispatch had to wait because the worker queue was full
+	throttled: AtomicUsize,
+	panicked: AtomicUsize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TaskMetricsSnapshot {
+	pub queued: usize,
+	pub max_queued: usize,
+	pub in_flight: Vec<(Provider, usize)>,
+	pub processed: usize,
+	pub throttled: usize,
+	pub panicked: usize,
+}
+
+impl TaskMetrics {
+	pub fn snapshot(&self) -> TaskMetricsSnapshot {
+		TaskMetricsSnapshot {
+			queued: self.queued.load(Ordering::Relaxed),
+			max_queued: self.max_queued.load(Ordering::Relaxed),
+			in_flight: Provider::ALL
+				.iter()
+				.map(|p| (*p, self.in_flight[p.index()].load(Ordering::Relaxed)))
+				.collect(),
+			processed: self.processed.load(Ordering::Relaxed),
+			throttled: self.throttled.load(Ordering::Relaxed),
+			panicked: self.panicked.load(Ordering::Relaxed),
+		}
+	}
+
+	fn on_enqueued(&self) {
+		let queued = self.queued.fetch_add(1, Ordering::Relaxed) + 1;
+		self.max_queued.fetch_max(queued, Ordering::Relaxed);
+	}
+
+	fn on_dequeued(&self) {
+		self.queued.fetch_sub(1, Ordering::Relaxed);
+	}
+
+	fn on_started(&self, provider: Provider) {
+		self.in_flight[provider.index()].fetch_add(1, Ordering::Relaxed);
+	}
+
+	fn on_finished(&self, provider: Provider) {
+		self.in_flight[provider.index()].fetch_sub(1, Ordering::Relaxed);
+		self.processed.fetch_add(1, Ordering::Relaxed);
+	}
+}
+
+/// A simple counting semaphore, the permit is released when dropped.
+struct Semaphore {
+	permits: Mutex<usize>,
+	condvar: Condvar,
+}
+
+impl Semaphore {
+	fn new(permits: usize) -> Self {
+		Self { permits: Mutex::new(permits), condvar: Condvar::new() }
+	}
+
+	fn acquire(&self) -> SemaphorePermit<'_> {
+		#[allow(clippy::unwrap_used)]
+		let mut permits = self.permits.lock().unwrap();
+		while *permits == 0 {
+			#[allow(clippy::unwrap_used)]
+			{
+				permits = self.condvar.wait(permits).unwrap();
+			}
+		}
+		*permits -= 1;
+		SemaphorePermit { semaphore: self }
+	}
+
+	fn try_acquire(&self) -> Option<SemaphorePermit<'_>> {
+		let mut permits = self.permits.lock().ok()?;
+		if *permits == 0 {
+			return None
+		}
+		*permits -= 1;
+		Some(SemaphorePermit { semaphore: self })
+	}
+}
+
+struct SemaphorePermit<'a> {
+	semaphore: &'a Semaphore,
+}
+
+impl Drop for SemaphorePermit<'_> {
+	fn drop(&mut self) {
+		if let Ok(mut permits) = self.semaphore.permits.lock() {
+			*permits += 1;
+		}
+		self.semaphore.condvar.notify_one();
+	}
+}
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+/// Called with the current metrics whenever a task is finished.
+pub type MetricsReporter = Arc<dyn Fn(&TaskMetricsSnapshot) + Send + Sync + 'static>;
+
+struct Task {
+	/// the hash of the ordering key
+	key: u64,
+	provider: Provider,
+	job: Job,
+}
+
+pub struct WorkerPool {
+	queues: Vec<SyncSender<Task>>,
+	metrics: &'static TaskMetrics,
+}
+
+impl WorkerPool {
+	pub fn new(
+		config: WorkerPoolConfig,
+		metrics: &'static TaskMetrics,
+		reporter: MetricsReporter,
+	) -> Result<Self, Error> {
+		config.validate()?;
+
+		let limits: Arc<Vec<Semaphore>> =
+			Arc::new(config.provider_limits.iter().map(|l| Semaphore::new(*l)).collect());
+
+		let mut queues = Vec::with_capacity(config.worker_count);
+		for _ in 0..config.worker_count {
+			let (sender, receiver) = sync_channel::<Task>(config.queue_capacity);
+			let worker = Worker {
+				limits: limits.clone(),
+				max_deferred: config.queue_capacity,
+				metrics,
+				reporter: reporter.clone(),
+			};
+			thread::spawn(move || worker.run(receiver));
+			queues.push(sender);
+		}
+
+		Ok(Self { queues, metrics })
+	}
+
+	/// Dispatch a job to the worker responsible for `key`.
+	///
+	/// Blocks if the queue of this worker is full.
+	pub fn dispatch<F>(&self, key: &Identity, provider: Provider, job: F) -> Result<(), Error>
+	where
+		F: FnOnce() + Send + 'static,
+	{
+		let key = key_hash(key);
+		let index = (key % self.queues.len() as u64) as usize;
+		let task = Task { key, provider, job: Box::new(job) };
+
+		self.metrics.on_enqueued();
+		let result = match self.queues[index].try_send(task) {
+			Ok(()) => Ok(()),
+			Err(TrySendError::Full(task)) => {
+				self.metrics.throttled.fetch_add(1, Ordering::Relaxed);
+				warn!("stf task worker {} is busy, waiting for free queue slot", index);
+				self.queues[index].send(task).map_err(|_| worker_gone(index))
+			},
+			Err(TrySendError::Disconnected(_)) => Err(worker_gone(index)),
+		};
+		if result.is_err() {
+			self.metrics.on_dequeued();
+		}
+		result
+	}
+
+	#[cfg(test)]
+	fn worker_index(&self, key: &Identity) -> usize {
+		(key_hash(key) % self.queues.len() as u64) as usize
+	}
+}
+
+fn key_hash(key: &Identity) -> u64 {
+	let hash = blake2_256(&key.encode());
+	let mut bytes = [0u8; 8];
+	bytes.copy_from_slice(&hash[..8]);
+	u64::from_le_bytes(bytes)
+}
+
+fn worker_gone(index: usize) -> Error {
+	Error::OtherError(format!("stf task worker {} has stopped", index))
+}
+
+struct Worker {
+	limits: Arc<Vec<Semaphore>>,
+	/// at most this many tasks are put aside, then the worker waits for the oldest one
+	max_deferred: usize,
+	metrics: &'static TaskMetrics,
+	reporter: MetricsReporter,
+}
+
+impl Worker {
+	fn run(&self, receiver: Receiver<Task>) {
+		// the tasks whose provider had no free permit, in the order they were received
+		let mut deferred: VecDeque<Task> = VecDeque::new();
+		loop {
+			if self.run_next_deferred(&mut deferred) {
+				continue
+			}
+			let received = if deferred.is_empty() {
+				receiver.recv().map_err(|_| RecvTimeoutError::Disconnected)
+			} else if deferred.len() >= self.max_deferred {
+				if let Some(task) = deferred.pop_front() {
+					let permit = self.limits[task.provider.index()].acquire();
+					self.run_task(task, permit);
+				}
+				continue
+			} else {
+				receiver.recv_timeout(DEFERRED_RETRY_INTERVAL)
+			};
+			match received {
+				Ok(task) => {
+					self.metrics.on_dequeued();
+					deferred.push_back(task);
+				},
+				Err(RecvTimeoutError::Timeout) => continue,
+				Err(RecvTimeoutError::Disconnected) => break,
+			}
+		}
+
+		while let Some(task) = deferred.pop_front() {
+			let permit = self.limits[task.provider.index()].acquire();
+			self.run_task(task, permit);
+		}
+	}
+
+	// runs the oldest task that has a free permit and doesn't have to wait for an older
+	// task with the same key, returns false if there's none
+	fn run_next_deferred(&self, deferred: &mut VecDeque<Task>) -> bool {
+		let mut waiting_keys = Vec::new();
+		for i in 0..deferred.len() {
+			let task = &deferred[i];
+			if waiting_keys.contains(&task.key) {
+				continue
+			}
+			match self.limits[task.provider.index()].try_acquire() {
+				Some(permit) => {
+					if let Some(task) = deferred.remove(i) {
+						self.run_task(task, permit);
+					}
+					return true
+				},
+				None => waiting_keys.push(task.key),
+			}
+		}
+		false
+	}
+
+	fn run_task(&self, task: Task, _permit: SemaphorePermit<'_>) {
+		let Task { provider, job, .. } = task;
+		self.metrics.on_started(provider);
+		if catch_unwind(AssertUnwindSafe(job)).is_err() {
+			self.metrics.panicked.fetch_add(1, Ordering::Relaxed);
+			error!("stf task panicked, provider: {:?}", provider);
+		}
+		self.metrics.on_finished(provider);
+
+		let snapshot = self.metrics.snapshot();
+		debug!("stf task finished, provider: {:?}, metrics: {:?}", provider, snapshot);
+		(self.reporter)(&snapshot);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use itp_types::AccountId;
+	use lc_data_providers::GLOBAL_DATA_PROVIDER_CONFIG;
+	use lc_stf_task_sender::AssertionBuildRequest;
+	use litentry_primitives::Web3Network;
+	use sp_core::{sr25519, Pair, H256};
+	use std::sync::mpsc::{channel, Sender};
+
+	fn alice() -> Identity {
+		sr25519::Pair::from_string("//Alice", None).unwrap().public().into()
+	}
+
+	fn bob() -> Identity {
+		sr25519::Pair::from_string("//Bob", None).unwrap().public().into()
+	}
+
+	fn leak_metrics() -> &'static TaskMetrics {
+		Box::leak(Box::new(TaskMetrics::default()))
+	}
+
+	fn no_report() -> MetricsReporter {
+		Arc::new(|_| {})
+	}
+
+	// occupies the worker of `who` until the returned sender is dropped or sent to
+	fn block_worker(pool: &WorkerPool, who: &Identity, provider: Provider) -> Sender<()> {
+		let (started_sender, started_receiver) = channel();
+		let (release_sender, release_receiver) = channel::<()>();
+		pool.dispatch(who, provider, move || {
+			started_sender.send(()).unwrap();
+			let _ = release_receiver.recv();
+		})
+		.unwrap();
+		started_receiver.recv().unwrap();
+		release_sender
+	}
+
+	#[test]
+	fn invalid_config_is_rejected() {
+		let config = WorkerPoolConfig { worker_count: 0, ..Default::default() };
+		assert!(WorkerPool::new(config, leak_metrics(), no_report()).is_err());
+
+		let config = WorkerPoolConfig::default().with_provider_limit(Provider::Twitter, 0);
+		assert!(WorkerPool::new(config, leak_metrics(), no_report()).is_err());
+	}
+
+	#[test]
+	fn tasks_of_same_identity_are_processed_in_order() {
+		let metrics = leak_metrics();
+		let pool = WorkerPool::new(WorkerPoolConfig::default(), metrics, no_report()).unwrap();
+		let (sender, receiver) = channel();
+
+		// all tasks are queued before the worker can take any of them
+		let release = block_worker(&pool, &alice(), Provider::Local);
+		for i in 0..20u32 {
+			let sender = sender.clone();
+			pool.dispatch(&alice(), Provider::Local, move || {
+				// later tasks are quicker, they would overtake if run concurrently
+				thread::sleep(Duration::from_millis((20 - i) as u64));
+				sender.send(i).unwrap();
+			})
+			.unwrap();
+		}
+		assert_eq!(metrics.snapshot().max_queued, 20);
+		release.send(()).unwrap();
+
+		let received: Vec<u32> = (0..20).map(|_| receiver.recv().unwrap()).collect();
+		assert_eq!(received, (0..20).collect::<Vec<_>>());
+	}
+
+	#[test]
+	fn provider_concurrency_is_limited() {
+		let metrics = leak_metrics();
+		let config = WorkerPoolConfig { worker_count: 8, ..Default::default() }
+			.with_provider_limit(Provider::Twitter, 1);
+		let pool = WorkerPool::new(config, metrics, no_report()).unwrap();
+
+		let running = Arc::new(AtomicUsize::new(0));
+		let max_running = Arc::new(AtomicUsize::new(0));
+		let (sender, receiver) = channel();
+
+		// use distinct identities so that the tasks spread across workers
+		let identities: Vec<Identity> = (0..16u8)
+			.map(|i| sr25519::Pair::from_seed(&[i; 32]).public().into())
+			.chain([alice(), bob()])
+			.collect();
+
+		for who in identities.iter() {
+			let (running, max_running, sender) =
+				(running.clone(), max_running.clone(), sender.clone());
+			pool.dispatch(who, Provider::Twitter, move || {
+				let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+				max_running.fetch_max(now, Ordering::SeqCst);
+				thread::sleep(Duration::from_millis(5));
+				running.fetch_sub(1, Ordering::SeqCst);
+				sender.send(()).unwrap();
+			})
+			.unwrap();
+		}
+
+		for _ in identities.iter() {
+			receiver.recv().unwrap();
+		}
+		assert_eq!(max_running.load(Ordering::SeqCst), 1);
+	}
+
+	#[test]
+	fn full_queue_applies_back_pressure() {
+		let metrics = leak_metrics();
+		let config = WorkerPoolConfig { worker_count: 1, queue_capacity: 1, ..Default::default() };
+		let pool = WorkerPool::new(config, metrics, no_report()).unwrap();
+		let (sender, receiver) = channel();
+
+		for i in 0..5u32 {
+			let sender = sender.clone();
+			pool.dispatch(&alice(), Provider::Local, move || {
+				thread::sleep(Duration::from_millis(5));
+				sender.send(i).unwrap();
+			})
+			.unwrap();
+		}
+
+		for _ in 0..5 {
+			receiver.recv().unwrap();
+		}
+		assert!(metrics.snapshot().throttled > 0);
+	}
+
+	#[test]
+	fn panicking_task_does_not_stop_worker() {
+		let metrics = leak_metrics();
+		let config = WorkerPoolConfig { worker_count: 1, ..Default::default() };
+		let pool = WorkerPool::new(config, metrics, no_report()).unwrap();
+		let (sender, receiver) = channel();
+
+		pool.dispatch(&alice(), Provider::Local, || panic!("task panicked")).unwrap();
+		pool.dispatch(&alice(), Provider::Local, move || sender.send(()).unwrap())
+			.unwrap();
+
+		// the worker only takes the second task once the first one is finished
+		receiver.recv().unwrap();
+		let snapshot = metrics.snapshot();
+		assert_eq!(snapshot.panicked, 1);
+		assert!(snapshot.processed >= 1);
+	}
+
+	#[test]
+	fn busy_provider_does_not_block_other_tasks() {
+		let metrics = leak_metrics();
+		let config = WorkerPoolConfig { worker_count: 2, ..Default::default() }
+			.with_provider_limit(Provider::Twitter, 1);
+		let pool = WorkerPool::new(config, metrics, no_report()).unwrap();
+
+		// identities handled by the first and by the second worker
+		let identities: Vec<Identity> =
+			(0..32u8).map(|i| sr25519::Pair::from_seed(&[i; 32]).public().into()).collect();
+		let mut first = identities.iter().filter(|who| pool.worker_index(who) == 0);
+		let mut second = identities.iter().filter(|who| pool.worker_index(who) == 1);
+		let (slow, waiting, other) =
+			(first.next().unwrap(), second.next().unwrap(), second.next().unwrap());
+
+		// the only twitter permit is taken by the first worker
+		let release = block_worker(&pool, slow, Provider::Twitter);
+
+		let (sender, receiver) = channel();
+		let (twitter_sender, local_sender, after_twitter_sender) =
+			(sender.clone(), sender.clone(), sender);
+		pool.dispatch(waiting, Provider::Twitter, move || twitter_sender.send("twitter").unwrap())
+			.unwrap();
+		pool.dispatch(other, Provider::Local, move || local_sender.send("local").unwrap())
+			.unwrap();
+		// must wait for the twitter task of the same identity
+		pool.dispatch(waiting, Provider::Local, move || {
+			after_twitter_sender.send("after twitter").unwrap()
+		})
+		.unwrap();
+
+		assert_eq!(receiver.recv().unwrap(), "local");
+		release.send(()).unwrap();
+		assert_eq!(receiver.recv().unwrap(), "twitter");
+		assert_eq!(receiver.recv().unwrap(), "after twitter");
+	}
+
+	#[test]
+	fn metrics_are_reported() {
+		let reported = Arc::new(AtomicUsize::new(0));
+		let reporter: MetricsReporter = {
+			let reported = reported.clone();
+			Arc::new(move |snapshot| reported.store(snapshot.processed, Ordering::SeqCst))
+		};
+		let pool = WorkerPool::new(WorkerPoolConfig::default(), leak_metrics(), reporter).unwrap();
+		let (sender, receiver) = channel();
+
+		pool.dispatch(&alice(), Provider::Local, move || sender.send(()).unwrap())
+			.unwrap();
+		receiver.recv().unwrap();
+
+		// reported right after the task, so wait for it
+		while reported.load(Ordering::SeqCst) == 0 {
+			thread::yield_now();
+		}
+		assert_eq!(reported.load(Ordering::SeqCst), 1);
+	}
+
+	#[test]
+	fn assertion_provider_follows_the_assertion_source() {
+		let request = |assertion, networks| {
+			RequestType::AssertionVerification(AssertionBuildRequest {
+				shard: Default::default(),
+				signer: AccountId::from([0; 32]),
+				enclave_account: AccountId::from([0; 32]),
+				who: alice(),
+				assertion,
+				identities: vec![(alice(), networks)],
+				verifier: None,
+				renewed_index: None,
+				top_hash: H256::default(),
+				req_ext_hash: H256::default(),
+			})
+		};
+		let a4 = |networks| request(Assertion::A4(Default::default()), networks);
+		let a7 = request(Assertion::A7(Default::default()), vec![Web3Network::Polkadot]);
+
+		assert_eq!(Provider::from(&a7), Provider::Achainable);
+		assert_eq!(Provider::from(&a4(vec![Web3Network::Ethereum])), Provider::Achainable);
+
+		{
+			let mut config = GLOBAL_DATA_PROVIDER_CONFIG.write().unwrap();
+			config.assertion_sources.a4 = AssertionSource::Native;
+			config.assertion_sources.a7 = AssertionSource::Native;
+		}
+		assert_eq!(Provider::from(&a7), Provider::Native);
+		assert_eq!(Provider::from(&a4(vec![Web3Network::Ethereum])), Provider::Native);
+		// no parentchain archive is configured, so Litmus is still queried from Achainable
+		assert_eq!(
+			Provider::from(&a4(vec![Web3Network::Ethereum, Web3Network::Litmus])),
+			Provider::Achainable
+		);
+	}
+}
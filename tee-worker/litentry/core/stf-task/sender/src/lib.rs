@@ -38,8 +38,8 @@ pub use error::Result;
 use itp_stf_primitives::types::ShardIdentifier;
 use itp_types::Index;
 use litentry_primitives::{
-	Assertion, Identity, IdentityNetworkTuple, UserShieldingKeyNonceType, UserShieldingKeyType,
-	ValidationData, Web3Network,
+	Assertion, IDGraphExport, Identity, IdentityNetworkTuple, SignedIDGraphExport,
+	UserShieldingKeyNonceType, UserShieldingKeyType, ValidationData, Web3Network,
 };
 use sp_runtime::traits::ConstU32;
 use sp_std::prelude::Vec;
@@ -92,6 +92,33 @@ pub struct AssertionBuildRequest {
 	pub who: Identity,
 	pub assertion: Assertion,
 	pub identities: Vec<IdentityNetworkTuple>,
+	/// The verifier a pairwise-pseudonymous credential is requested for, `None` for a
+	/// credential about the DID of `who`
+	pub verifier: Option<Vec<u8>>,
+	/// The index of the VC that is renewed by this request
+	pub renewed_index: Option<H256>,
+	pub top_hash: H256,
+	pub req_ext_hash: H256,
+}
+
+/// The IDGraph of `who` to be signed by the enclave
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq)]
+pub struct IDGraphExportRequest {
+	pub shard: ShardIdentifier,
+	pub who: Identity,
+	pub export: IDGraphExport,
+	pub top_hash: H256,
+	pub req_ext_hash: H256,
+}
+
+/// An exported IDGraph whose signature and link proofs are to be verified again before its
+/// identities are linked to `who`, `key` is the user shielding key of `who`
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq)]
+pub struct IDGraphImportRequest {
+	pub shard: ShardIdentifier,
+	pub who: Identity,
+	pub export: SignedIDGraphExport,
+	pub key: UserShieldingKeyType,
 	pub top_hash: H256,
 	pub req_ext_hash: H256,
 }
@@ -100,6 +127,8 @@ pub struct AssertionBuildRequest {
 pub enum RequestType {
 	IdentityVerification(IdentityVerificationRequest),
 	AssertionVerification(AssertionBuildRequest),
+	IDGraphImport(IDGraphImportRequest),
+	IDGraphExport(IDGraphExportRequest),
 }
 
 impl From<IdentityVerificationRequest> for RequestType {
@@ -113,3 +142,15 @@ impl From<AssertionBuildRequest> for RequestType {
 		RequestType::AssertionVerification(r)
 	}
 }
+
+impl From<IDGraphImportRequest> for RequestType {
+	fn from(r: IDGraphImportRequest) -> Self {
+		RequestType::IDGraphImport(r)
+	}
+}
+
+impl From<IDGraphExportRequest> for RequestType {
+	fn from(r: IDGraphExportRequest) -> Self {
+		RequestType::IDGraphExport(r)
+	}
+}
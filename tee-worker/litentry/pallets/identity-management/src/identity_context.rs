@@ -16,16 +16,10 @@
 
 use crate::{BlockNumberOf, Config, Web3Network};
 use codec::{Decode, Encode};
+pub use litentry_primitives::IdentityStatus;
 use scale_info::TypeInfo;
 use sp_std::vec::Vec;
 
-#[derive(Clone, Eq, PartialEq, Default, Debug, Encode, Decode, TypeInfo)]
-pub enum IdentityStatus {
-	#[default]
-	Active,
-	Inactive,
-}
-
 // The context associated with the (litentry-account, did) pair
 #[derive(Clone, Eq, PartialEq, Default, Debug, Encode, Decode, TypeInfo)]
 #[scale_info(skip_type_params(T))]
@@ -37,11 +31,18 @@ pub struct IdentityContext<T: Config> {
 	pub web3networks: Vec<Web3Network>,
 	// the identity status
 	pub status: IdentityStatus,
+	// whether the identity is a smart-contract wallet, whose signatures were verified with EIP-1271
+	pub contract_wallet: bool,
 }
 
 impl<T: Config> IdentityContext<T> {
 	pub fn new(link_block: BlockNumberOf<T>, web3networks: Vec<Web3Network>) -> Self {
-		Self { link_block, web3networks: Self::dedup(web3networks), status: IdentityStatus::Active }
+		Self {
+			link_block,
+			web3networks: Self::dedup(web3networks),
+			status: IdentityStatus::Active,
+			contract_wallet: false,
+		}
 	}
 
 	pub fn set_web3networks(&mut self, web3networks: Vec<Web3Network>) {
@@ -42,7 +42,8 @@ use frame_support::{pallet_prelude::*, traits::StorageVersion};
 use frame_system::pallet_prelude::*;
 
 pub use litentry_primitives::{
-	all_substrate_web3networks, Identity, ParentchainBlockNumber, UserShieldingKeyType, Web3Network,
+	all_substrate_web3networks, IDGraphExport, Identity, IdentityExport, LinkProof,
+	ParentchainBlockNumber, UserShieldingKeyType, Web3Network, Web3NetworkRegistry,
 };
 use sp_std::vec::Vec;
 
@@ -54,7 +55,7 @@ pub mod pallet {
 	use super::*;
 	use log::debug;
 
-	const STORAGE_VERSION: StorageVersion = StorageVersion::new(0);
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
 
 	#[pallet::pallet]
 	#[pallet::storage_version(STORAGE_VERSION)]
@@ -70,6 +71,8 @@ pub mod pallet {
 		/// maximum number of identities an account can have, if you change this value to lower some accounts may exceed this limit
 		#[pallet::constant]
 		type MaxIDGraphLength: Get<u32>;
+		/// the web3 networks registered on the parentchain
+		type Web3NetworkRegistry: Get<Web3NetworkRegistry>;
 	}
 
 	#[pallet::event]
@@ -83,6 +86,8 @@ pub mod pallet {
 		IdentityDeactivated { who: Identity, identity: Identity },
 		/// an identity was activated
 		IdentityActivated { who: Identity, identity: Identity },
+		/// an identity was removed
+		IdentityRemoved { who: Identity, identity: Identity },
 	}
 
 	#[pallet::error]
@@ -95,12 +100,16 @@ pub mod pallet {
 		LinkPrimeIdentityDisallowed,
 		/// deactivate prime identity should be disallowed
 		DeactivatePrimeIdentityDisallowed,
+		/// remove prime identity should be disallowed
+		RemovePrimeIdentityDisallowed,
 		/// IDGraph len limit reached
 		IDGraphLenLimitReached,
 		/// identity doesn't match the network types
 		WrongWeb3NetworkTypes,
 		/// identity cannot be used to build prime identity
 		NotSupportedIdentity,
+		/// only evm identities can be contract wallets
+		ContractWalletNotEvm,
 	}
 
 	#[pallet::storage]
@@ -127,6 +136,19 @@ pub mod pallet {
 	#[pallet::storage]
 	pub type IDGraphLens<T: Config> = StorageMap<_, Blake2_128Concat, Identity, u32, ValueQuery>;
 
+	/// the proofs web3 identities were linked with, they are exported along with the IDGraph
+	#[pallet::storage]
+	#[pallet::getter(fn link_proofs)]
+	pub type LinkProofs<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		Identity,
+		Blake2_128Concat,
+		Identity,
+		LinkProof,
+		OptionQuery,
+	>;
+
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		#[pallet::call_index(0)]
@@ -142,7 +164,7 @@ pub mod pallet {
 			ensure!(!who.is_web2(), Error::<T>::NotSupportedIdentity);
 			if IDGraphs::<T>::get(&who, &who).is_none() {
 				ensure!(
-					who.matches_web3networks(networks.as_ref()),
+					who.matches_web3networks(networks.as_ref(), &T::Web3NetworkRegistry::get()),
 					Error::<T>::WrongWeb3NetworkTypes
 				);
 				let context =
@@ -163,22 +185,15 @@ pub mod pallet {
 			who: Identity,
 			identity: Identity,
 			web3networks: Vec<Web3Network>,
+			contract_wallet: bool,
 		) -> DispatchResult {
 			T::ManageOrigin::ensure_origin(origin)?;
+			Self::ensure_linkable(&who, &identity, &web3networks, contract_wallet)?;
 
-			ensure!(
-				!LinkedIdentities::<T>::contains_key(&identity),
-				Error::<T>::IdentityAlreadyLinked
-			);
-			ensure!(identity != who, Error::<T>::LinkPrimeIdentityDisallowed);
-
-			ensure!(
-				identity.matches_web3networks(web3networks.as_ref()),
-				Error::<T>::WrongWeb3NetworkTypes
-			);
-
-			let context =
-				<IdentityContext<T>>::new(<frame_system::Pallet<T>>::block_number(), web3networks);
+			let context = IdentityContext {
+				contract_wallet,
+				..<IdentityContext<T>>::new(<frame_system::Pallet<T>>::block_number(), web3networks)
+			};
 			Self::insert_identity_with_limit(&who, &identity, context)?;
 			Self::deposit_event(Event::IdentityLinked { who, identity });
 			Ok(())
@@ -237,7 +252,10 @@ pub mod pallet {
 			IDGraphs::<T>::try_mutate(&who, &identity, |context| {
 				let mut c = context.take().ok_or(Error::<T>::IdentityNotExist)?;
 				ensure!(
-					identity.matches_web3networks(web3networks.as_ref()),
+					identity.matches_web3networks(
+						web3networks.as_ref(),
+						&T::Web3NetworkRegistry::get()
+					),
 					Error::<T>::WrongWeb3NetworkTypes
 				);
 				c.set_web3networks(web3networks);
@@ -245,9 +263,92 @@ pub mod pallet {
 				Ok(())
 			})
 		}
+
+		/// Remove an identity from the IDGraph of `who`, after that the identity is free to be
+		/// linked again, either to the same or to another account
+		#[pallet::call_index(5)]
+		#[pallet::weight(15_000_000)]
+		pub fn remove_identity(
+			origin: OriginFor<T>,
+			who: Identity,
+			identity: Identity,
+		) -> DispatchResult {
+			T::ManageOrigin::ensure_origin(origin)?;
+			ensure!(IDGraphs::<T>::contains_key(&who, &identity), Error::<T>::IdentityNotExist);
+			ensure!(identity != who, Error::<T>::RemovePrimeIdentityDisallowed);
+
+			Self::remove_identity_from_graph(&who, &identity);
+			Self::deposit_event(Event::IdentityRemoved { who, identity });
+			Ok(())
+		}
+
+		/// Keep the proof a linked identity was verified with
+		#[pallet::call_index(6)]
+		#[pallet::weight(15_000_000)]
+		pub fn set_link_proof(
+			origin: OriginFor<T>,
+			who: Identity,
+			identity: Identity,
+			proof: LinkProof,
+		) -> DispatchResult {
+			T::ManageOrigin::ensure_origin(origin)?;
+			ensure!(IDGraphs::<T>::contains_key(&who, &identity), Error::<T>::IdentityNotExist);
+
+			LinkProofs::<T>::insert(&who, &identity, proof);
+			Ok(())
+		}
+
+		/// Link an identity of an imported IDGraph along with the proof it was verified with
+		/// and its status. Everything is checked before anything is written, as the storage
+		/// isn't transactional in the enclave, so an identity is either imported fully or not
+		/// at all.
+		#[pallet::call_index(7)]
+		#[pallet::weight(15_000_000)]
+		pub fn import_identity(
+			origin: OriginFor<T>,
+			who: Identity,
+			identity: Identity,
+			web3networks: Vec<Web3Network>,
+			contract_wallet: bool,
+			status: IdentityStatus,
+			proof: LinkProof,
+		) -> DispatchResult {
+			T::ManageOrigin::ensure_origin(origin)?;
+			Self::ensure_linkable(&who, &identity, &web3networks, contract_wallet)?;
+
+			let context = IdentityContext {
+				contract_wallet,
+				status,
+				..<IdentityContext<T>>::new(<frame_system::Pallet<T>>::block_number(), web3networks)
+			};
+			Self::insert_identity_with_limit(&who, &identity, context)?;
+			LinkProofs::<T>::insert(&who, &identity, proof);
+			Self::deposit_event(Event::IdentityLinked { who, identity });
+			Ok(())
+		}
 	}
 
 	impl<T: Config> Pallet<T> {
+		fn ensure_linkable(
+			who: &Identity,
+			identity: &Identity,
+			web3networks: &[Web3Network],
+			contract_wallet: bool,
+		) -> DispatchResult {
+			ensure!(
+				!LinkedIdentities::<T>::contains_key(identity),
+				Error::<T>::IdentityAlreadyLinked
+			);
+			ensure!(identity != who, Error::<T>::LinkPrimeIdentityDisallowed);
+
+			ensure!(
+				identity.matches_web3networks(web3networks, &T::Web3NetworkRegistry::get()),
+				Error::<T>::WrongWeb3NetworkTypes
+			);
+			ensure!(!contract_wallet || identity.is_evm(), Error::<T>::ContractWalletNotEvm);
+			Ok(())
+		}
+
 		fn insert_identity_with_limit(
 			owner: &Identity,
 			identity: &Identity,
@@ -266,6 +367,13 @@ pub mod pallet {
 			Ok(())
 		}
 
+		fn remove_identity_from_graph(owner: &Identity, identity: &Identity) {
+			IDGraphLens::<T>::mutate(owner, |len| *len = len.saturating_sub(1));
+			LinkedIdentities::<T>::remove(identity);
+			IDGraphs::<T>::remove(owner, identity);
+			LinkProofs::<T>::remove(owner, identity);
+		}
+
 		// get the most recent `max_len` elements in IDGraph
 		pub fn get_id_graph(who: &Identity, max_len: usize) -> IDGraph<T> {
 			let mut id_graph = IDGraphs::iter_prefix(who).collect::<IDGraph<T>>();
@@ -274,6 +382,21 @@ pub mod pallet {
 			id_graph
 		}
 
+		// get the whole IDGraph with the link proofs, in the same order as `get_id_graph`
+		pub fn export_id_graph(who: &Identity) -> IDGraphExport {
+			let identities = Self::get_id_graph(who, usize::MAX)
+				.into_iter()
+				.map(|(identity, context)| IdentityExport {
+					proof: LinkProofs::<T>::get(who, &identity),
+					identity,
+					web3networks: context.web3networks,
+					status: context.status,
+					contract_wallet: context.contract_wallet,
+				})
+				.collect();
+			IDGraphExport { who: who.clone(), identities }
+		}
+
 		// get count of all keys account + identity in the IDGraphs
 		pub fn id_graph_stats() -> Option<Vec<(Identity, u32)>> {
 			let stats = IDGraphLens::<T>::iter().collect();
@@ -1,10 +1,13 @@
+use crate::{BlockNumberOf, IDGraphs, IdentityContext, IdentityStatus, Web3Network};
+use codec::{Decode, Encode};
 use frame_support::{
 	storage::migration,
-	traits::{GetStorageVersion, PalletInfoAccess, StorageVersion},
+	traits::{Get, GetStorageVersion, PalletInfoAccess, StorageVersion},
 	weights::Weight,
 	Hashable,
 };
 use litentry_primitives::UserShieldingKeyType;
+use sp_std::vec::Vec;
 
 // This is just an example of how to write a custom migration
 pub fn migrate_to_v1<T: crate::Config, P: GetStorageVersion + PalletInfoAccess>() -> Weight {
@@ -44,3 +47,34 @@ pub fn migrate_to_v2<T: crate::Config, P: GetStorageVersion + PalletInfoAccess>(
 	}
 	Weight::zero()
 }
+
+/// The `IdentityContext` before `contract_wallet` was added.
+#[derive(Encode, Decode)]
+pub struct IdentityContextV2<T: crate::Config> {
+	pub link_block: BlockNumberOf<T>,
+	pub web3networks: Vec<Web3Network>,
+	pub status: IdentityStatus,
+}
+
+// the identities linked so far were all verified with a signature of their own key
+pub fn migrate_to_v3<T: crate::Config, P: GetStorageVersion + PalletInfoAccess>() -> Weight {
+	let on_chain_storage_version = <P as GetStorageVersion>::on_chain_storage_version();
+	if on_chain_storage_version >= 3 {
+		return Weight::zero()
+	}
+	log::info!("Doing migrations now for IMT version {:?}", on_chain_storage_version);
+
+	let mut translated = 0u64;
+	IDGraphs::<T>::translate::<IdentityContextV2<T>, _>(|_, _, old| {
+		translated += 1;
+		Some(IdentityContext {
+			link_block: old.link_block,
+			web3networks: old.web3networks,
+			status: old.status,
+			contract_wallet: false,
+		})
+	});
+	StorageVersion::new(3).put::<P>();
+	log::info!("Migrated {} IDGraph entries", translated);
+	T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+}
@@ -18,11 +18,14 @@ use crate as pallet_tee_identity_management;
 use crate::{all_substrate_web3networks, UserShieldingKeyType};
 use frame_support::{
 	ord_parameter_types, parameter_types,
-	traits::{ConstU128, ConstU16, ConstU32},
+	traits::{ConstU128, ConstU16, ConstU32, Get},
 };
 use frame_system as system;
 use frame_system::EnsureSignedBy;
-use litentry_primitives::{Identity, IdentityString, USER_SHIELDING_KEY_LEN};
+use litentry_primitives::{
+	Identity, IdentityString, Web3NetworkFamily, Web3NetworkInfo, Web3NetworkRegistry,
+	USER_SHIELDING_KEY_LEN,
+};
 use sp_core::H256;
 use sp_runtime::{
 	testing::Header,
@@ -94,10 +97,26 @@ ord_parameter_types! {
 	pub const One: AccountId32 = AccountId32::new([1u8; 32]);
 }
 
+// a substrate network is registered as 1
+pub struct MockWeb3NetworkRegistry;
+impl Get<Web3NetworkRegistry> for MockWeb3NetworkRegistry {
+	fn get() -> Web3NetworkRegistry {
+		let astar = Web3NetworkInfo {
+			name: b"Astar".to_vec().try_into().unwrap(),
+			family: Web3NetworkFamily::Substrate { ss58_prefix: 5 },
+			providers: Default::default(),
+		};
+		let mut registry = Web3NetworkRegistry::new();
+		registry.try_insert(1, astar).unwrap();
+		registry
+	}
+}
+
 impl pallet_tee_identity_management::Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type ManageOrigin = EnsureSignedBy<One, AccountId32>;
 	type MaxIDGraphLength = ConstU32<64>;
+	type Web3NetworkRegistry = MockWeb3NetworkRegistry;
 }
 
 const ALICE_KEY: &str = "0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d";
@@ -19,7 +19,11 @@ use crate::{
 	UserShieldingKeyType, Web3Network,
 };
 use frame_support::{assert_err, assert_noop, assert_ok, traits::Get};
-use litentry_primitives::USER_SHIELDING_KEY_LEN;
+use litentry_primitives::{
+	LinkProof, LitentryMultiSignature, Web3CommonValidationData, Web3ValidationData,
+	USER_SHIELDING_KEY_LEN,
+};
+use sp_core::sr25519;
 use sp_runtime::AccountId32;
 
 pub const ALICE: AccountId32 = AccountId32::new([1u8; 32]);
@@ -58,10 +62,16 @@ fn link_twitter_identity_works() {
 			who.clone(),
 			alice_twitter_identity(1),
 			vec![],
+			false,
 		));
 		assert_eq!(
 			IMT::id_graphs(who.clone(), alice_twitter_identity(1)).unwrap(),
-			IdentityContext { link_block: 1, web3networks: vec![], status: IdentityStatus::Active }
+			IdentityContext {
+				link_block: 1,
+				web3networks: vec![],
+				status: IdentityStatus::Active,
+				contract_wallet: false
+			}
 		);
 		assert_eq!(crate::IDGraphLens::<Test>::get(&who), 2);
 	});
@@ -77,15 +87,62 @@ fn link_substrate_identity_works() {
 			who.clone(),
 			alice_substrate_identity(),
 			web3networks.clone(),
+			false,
 		));
 		assert_eq!(
 			IMT::id_graphs(who.clone(), alice_substrate_identity()).unwrap(),
-			IdentityContext { link_block: 1, web3networks, status: IdentityStatus::Active }
+			IdentityContext {
+				link_block: 1,
+				web3networks,
+				status: IdentityStatus::Active,
+				contract_wallet: false
+			}
 		);
 		assert_eq!(crate::IDGraphLens::<Test>::get(&who), 2);
 	});
 }
 
+#[test]
+fn link_identity_with_registered_network_works() {
+	new_test_ext(true).execute_with(|| {
+		let who: Identity = BOB.into();
+		// 2 isn't registered
+		assert_noop!(
+			IMT::link_identity(
+				RuntimeOrigin::signed(ALICE),
+				who.clone(),
+				alice_substrate_identity(),
+				vec![Web3Network::Litentry, Web3Network::Registered(2)],
+				false,
+			),
+			Error::<Test>::WrongWeb3NetworkTypes
+		);
+		// 1 is a substrate network
+		assert_noop!(
+			IMT::link_identity(
+				RuntimeOrigin::signed(ALICE),
+				who.clone(),
+				alice_evm_identity(),
+				vec![Web3Network::Registered(1)],
+				false,
+			),
+			Error::<Test>::WrongWeb3NetworkTypes
+		);
+		let web3networks = vec![Web3Network::Litentry, Web3Network::Registered(1)];
+		assert_ok!(IMT::link_identity(
+			RuntimeOrigin::signed(ALICE),
+			who.clone(),
+			alice_substrate_identity(),
+			web3networks.clone(),
+			false,
+		));
+		assert_eq!(
+			IMT::id_graphs(who, alice_substrate_identity()).unwrap().web3networks,
+			web3networks
+		);
+	});
+}
+
 #[test]
 fn link_evm_identity_works() {
 	new_test_ext(true).execute_with(|| {
@@ -96,10 +153,16 @@ fn link_evm_identity_works() {
 			who.clone(),
 			alice_evm_identity(),
 			web3networks.clone(),
+			false,
 		));
 		assert_eq!(
 			IMT::id_graphs(who.clone(), alice_evm_identity()).unwrap(),
-			IdentityContext { link_block: 1, web3networks, status: IdentityStatus::Active }
+			IdentityContext {
+				link_block: 1,
+				web3networks,
+				status: IdentityStatus::Active,
+				contract_wallet: false
+			}
 		);
 		assert_eq!(crate::IDGraphLens::<Test>::get(&who), 2);
 	});
@@ -116,12 +179,54 @@ fn link_identity_with_wrong_network_fails() {
 				who,
 				alice_substrate_identity(),
 				web3networks,
+				false,
 			),
 			Error::<Test>::WrongWeb3NetworkTypes
 		);
 	});
 }
 
+#[test]
+fn link_contract_wallet_works() {
+	new_test_ext(true).execute_with(|| {
+		let web3networks: Vec<Web3Network> = vec![Web3Network::Ethereum];
+		let who: Identity = BOB.into();
+		assert_ok!(IMT::link_identity(
+			RuntimeOrigin::signed(ALICE),
+			who.clone(),
+			alice_evm_identity(),
+			web3networks.clone(),
+			true,
+		));
+		assert_eq!(
+			IMT::id_graphs(who, alice_evm_identity()).unwrap(),
+			IdentityContext {
+				link_block: 1,
+				web3networks,
+				status: IdentityStatus::Active,
+				contract_wallet: true
+			}
+		);
+	});
+}
+
+#[test]
+fn link_non_evm_contract_wallet_fails() {
+	new_test_ext(true).execute_with(|| {
+		let who: Identity = BOB.into();
+		assert_noop!(
+			IMT::link_identity(
+				RuntimeOrigin::signed(ALICE),
+				who,
+				alice_substrate_identity(),
+				vec![Web3Network::Litentry],
+				true,
+			),
+			Error::<Test>::ContractWalletNotEvm
+		);
+	});
+}
+
 #[test]
 fn cannot_link_identity_again() {
 	new_test_ext(true).execute_with(|| {
@@ -131,14 +236,16 @@ fn cannot_link_identity_again() {
 			RuntimeOrigin::signed(ALICE),
 			who_bob.clone(),
 			alice_substrate_identity(),
-			web3networks.clone()
+			web3networks.clone(),
+			false
 		));
 		assert_eq!(
 			IMT::id_graphs(who_bob.clone(), alice_substrate_identity()).unwrap(),
 			IdentityContext {
 				link_block: 1,
 				status: IdentityStatus::Active,
-				web3networks: web3networks.clone()
+				web3networks: web3networks.clone(),
+				contract_wallet: false,
 			}
 		);
 		assert_eq!(crate::IDGraphLens::<Test>::get(&who_bob), 2);
@@ -150,7 +257,8 @@ fn cannot_link_identity_again() {
 				RuntimeOrigin::signed(ALICE),
 				who_alice.clone(),
 				alice_substrate_identity(),
-				web3networks
+				web3networks,
+				false
 			),
 			Error::<Test>::IdentityAlreadyLinked
 		);
@@ -171,6 +279,7 @@ fn cannot_create_more_identities_for_account_than_limit() {
 				who.clone(),
 				alice_twitter_identity(i),
 				vec![],
+				false,
 			));
 		}
 		assert_err!(
@@ -179,6 +288,7 @@ fn cannot_create_more_identities_for_account_than_limit() {
 				who.clone(),
 				alice_twitter_identity(65),
 				vec![],
+				false,
 			),
 			Error::<Test>::IDGraphLenLimitReached
 		);
@@ -210,13 +320,15 @@ fn deactivate_identity_works() {
 			who.clone(),
 			alice_substrate_identity(),
 			vec![Web3Network::Litentry].try_into().unwrap(),
+			false,
 		));
 		assert_eq!(
 			IMT::id_graphs(who.clone(), alice_substrate_identity()).unwrap(),
 			IdentityContext {
 				link_block: 1,
 				web3networks: vec![Web3Network::Litentry].try_into().unwrap(),
-				status: IdentityStatus::Active
+				status: IdentityStatus::Active,
+				contract_wallet: false
 			}
 		);
 
@@ -234,7 +346,8 @@ fn deactivate_identity_works() {
 			IdentityContext {
 				link_block: 1,
 				web3networks: vec![Web3Network::Litentry].try_into().unwrap(),
-				status: IdentityStatus::Inactive
+				status: IdentityStatus::Inactive,
+				contract_wallet: false
 			}
 		);
 
@@ -276,13 +389,15 @@ fn activate_identity_works() {
 			who.clone(),
 			alice_substrate_identity(),
 			vec![Web3Network::Litentry].try_into().unwrap(),
+			false,
 		));
 		assert_eq!(
 			IMT::id_graphs(who.clone(), alice_substrate_identity()).unwrap(),
 			IdentityContext {
 				link_block: 1,
 				web3networks: vec![Web3Network::Litentry].try_into().unwrap(),
-				status: IdentityStatus::Active
+				status: IdentityStatus::Active,
+				contract_wallet: false
 			}
 		);
 		let id_graph = IMT::get_id_graph(&who.clone(), usize::MAX);
@@ -299,7 +414,8 @@ fn activate_identity_works() {
 			IdentityContext {
 				link_block: 1,
 				web3networks: vec![Web3Network::Litentry].try_into().unwrap(),
-				status: IdentityStatus::Inactive
+				status: IdentityStatus::Inactive,
+				contract_wallet: false
 			}
 		);
 		let id_graph = IMT::get_id_graph(&who.clone(), usize::MAX)
@@ -324,6 +440,102 @@ fn activate_identity_works() {
 	});
 }
 
+#[test]
+fn remove_identity_works() {
+	new_test_ext(false).execute_with(|| {
+		let who: Identity = BOB.into();
+		let shielding_key: UserShieldingKeyType = [0u8; USER_SHIELDING_KEY_LEN];
+
+		assert_ok!(IMT::set_user_shielding_key(
+			RuntimeOrigin::signed(ALICE),
+			who.clone(),
+			shielding_key,
+			all_substrate_web3networks(),
+		));
+		assert_noop!(
+			IMT::remove_identity(
+				RuntimeOrigin::signed(ALICE),
+				who.clone(),
+				alice_substrate_identity()
+			),
+			Error::<Test>::IdentityNotExist
+		);
+		assert_ok!(IMT::link_identity(
+			RuntimeOrigin::signed(ALICE),
+			who.clone(),
+			alice_substrate_identity(),
+			vec![Web3Network::Litentry].try_into().unwrap(),
+			false,
+		));
+		assert_eq!(IMT::get_id_graph(&who.clone(), usize::MAX).len(), 2);
+		assert_eq!(crate::IDGraphLens::<Test>::get(&who.clone()), 2);
+
+		assert_ok!(IMT::remove_identity(
+			RuntimeOrigin::signed(ALICE),
+			who.clone(),
+			alice_substrate_identity(),
+		));
+		System::assert_last_event(RuntimeEvent::IMT(crate::Event::IdentityRemoved {
+			who: who.clone(),
+			identity: alice_substrate_identity(),
+		}));
+		assert_eq!(IMT::id_graphs(who.clone(), alice_substrate_identity()), None);
+		assert!(!crate::LinkedIdentities::<Test>::contains_key(alice_substrate_identity()));
+		assert_eq!(IMT::get_id_graph(&who.clone(), usize::MAX).len(), 1);
+		assert_eq!(crate::IDGraphLens::<Test>::get(&who.clone()), 1);
+
+		assert_noop!(
+			IMT::remove_identity(
+				RuntimeOrigin::signed(ALICE),
+				who.clone(),
+				bob_substrate_identity()
+			),
+			Error::<Test>::RemovePrimeIdentityDisallowed
+		);
+	});
+}
+
+#[test]
+fn removed_identity_can_be_linked_to_another_account() {
+	new_test_ext(false).execute_with(|| {
+		let bob: Identity = BOB.into();
+		let charlie: Identity = AccountId32::new([3u8; 32]).into();
+
+		assert_ok!(IMT::link_identity(
+			RuntimeOrigin::signed(ALICE),
+			bob.clone(),
+			alice_twitter_identity(1),
+			vec![],
+			false,
+		));
+		assert_noop!(
+			IMT::link_identity(
+				RuntimeOrigin::signed(ALICE),
+				charlie.clone(),
+				alice_twitter_identity(1),
+				vec![],
+				false,
+			),
+			Error::<Test>::IdentityAlreadyLinked
+		);
+
+		assert_ok!(IMT::remove_identity(
+			RuntimeOrigin::signed(ALICE),
+			bob.clone(),
+			alice_twitter_identity(1),
+		));
+		assert_ok!(IMT::link_identity(
+			RuntimeOrigin::signed(ALICE),
+			charlie.clone(),
+			alice_twitter_identity(1),
+			vec![],
+			false,
+		));
+		assert!(IMT::id_graphs(charlie, alice_twitter_identity(1)).is_some());
+		assert_eq!(IMT::id_graphs(bob, alice_twitter_identity(1)), None);
+	});
+}
+
 #[test]
 fn set_identity_networks_works() {
 	new_test_ext(false).execute_with(|| {
@@ -349,13 +561,15 @@ fn set_identity_networks_works() {
 			who.clone(),
 			alice_substrate_identity(),
 			vec![Web3Network::Litentry].try_into().unwrap(),
+			false,
 		));
 		assert_eq!(
 			IMT::id_graphs(who.clone(), alice_substrate_identity()).unwrap(),
 			IdentityContext {
 				link_block: 1,
 				web3networks: vec![Web3Network::Litentry].try_into().unwrap(),
-				status: IdentityStatus::Active
+				status: IdentityStatus::Active,
+				contract_wallet: false
 			}
 		);
 
@@ -398,13 +612,15 @@ fn set_identity_networks_with_wrong_network_fails() {
 			who.clone(),
 			alice_substrate_identity(),
 			vec![Web3Network::Litentry].try_into().unwrap(),
+			false,
 		));
 		assert_eq!(
 			IMT::id_graphs(who.clone(), alice_substrate_identity()).unwrap(),
 			IdentityContext {
 				link_block: 1,
 				web3networks: vec![Web3Network::Litentry].try_into().unwrap(),
-				status: IdentityStatus::Active
+				status: IdentityStatus::Active,
+				contract_wallet: false
 			}
 		);
 
@@ -435,6 +651,7 @@ fn get_id_graph_works() {
 				who.clone(),
 				alice_twitter_identity(i.try_into().unwrap()),
 				vec![],
+				false,
 			));
 		}
 		// the full id_graph should have 22 elements, including the prime_id
@@ -476,12 +693,14 @@ fn id_graph_stats_works() {
 			alice.clone(),
 			alice_substrate_identity(),
 			vec![Web3Network::Litentry].try_into().unwrap(),
+			false,
 		));
 		assert_ok!(IMT::link_identity(
 			RuntimeOrigin::signed(ALICE),
 			alice.clone(),
 			alice_twitter_identity(1),
 			vec![],
+			false,
 		));
 
 		let stats = IMT::id_graph_stats().unwrap();
@@ -491,3 +710,193 @@ fn id_graph_stats_works() {
 		assert!(stats.contains(&(bob.clone(), 1)));
 	});
 }
+
+fn link_proof() -> LinkProof {
+	LinkProof {
+		shard: Default::default(),
+		mrenclave: [1u8; 32],
+		sidechain_nonce: 1,
+		key_nonce: Default::default(),
+		web3networks: vec![Web3Network::Litentry],
+		validation_data: Web3ValidationData::Substrate(Web3CommonValidationData {
+			message: Default::default(),
+			signature: LitentryMultiSignature::Sr25519(sr25519::Signature([0u8; 64])),
+		}),
+	}
+}
+
+#[test]
+fn export_id_graph_works() {
+	new_test_ext(true).execute_with(|| {
+		let who: Identity = BOB.into();
+		let proof = link_proof();
+
+		// only linked identities can have proofs
+		assert_noop!(
+			IMT::set_link_proof(
+				RuntimeOrigin::signed(ALICE),
+				who.clone(),
+				alice_substrate_identity(),
+				proof.clone()
+			),
+			Error::<Test>::IdentityNotExist
+		);
+
+		System::set_block_number(2);
+		assert_ok!(IMT::link_identity(
+			RuntimeOrigin::signed(ALICE),
+			who.clone(),
+			alice_substrate_identity(),
+			vec![Web3Network::Litentry],
+			false,
+		));
+		assert_ok!(IMT::set_link_proof(
+			RuntimeOrigin::signed(ALICE),
+			who.clone(),
+			alice_substrate_identity(),
+			proof.clone()
+		));
+		assert_ok!(IMT::deactivate_identity(
+			RuntimeOrigin::signed(ALICE),
+			who.clone(),
+			alice_substrate_identity(),
+		));
+
+		let export = IMT::export_id_graph(&who);
+		assert_eq!(export.who, who);
+		assert_eq!(export.identities.len(), 2);
+		let linked = &export.identities[0];
+		assert_eq!(linked.identity, alice_substrate_identity());
+		assert_eq!(linked.web3networks, vec![Web3Network::Litentry]);
+		assert_eq!(linked.status, IdentityStatus::Inactive);
+		assert_eq!(linked.proof, Some(proof));
+		// the prime identity
+		assert_eq!(export.identities[1].identity, who);
+		assert_eq!(export.identities[1].proof, None);
+
+		// the proof is removed along with the identity
+		assert_ok!(IMT::remove_identity(
+			RuntimeOrigin::signed(ALICE),
+			who.clone(),
+			alice_substrate_identity(),
+		));
+		assert_eq!(IMT::link_proofs(&who, alice_substrate_identity()), None);
+	});
+}
+
+#[test]
+fn import_identity_works() {
+	new_test_ext(true).execute_with(|| {
+		let who: Identity = BOB.into();
+		System::set_block_number(3);
+
+		assert_ok!(IMT::import_identity(
+			RuntimeOrigin::signed(ALICE),
+			who.clone(),
+			alice_substrate_identity(),
+			vec![Web3Network::Litentry, Web3Network::Polkadot],
+			false,
+			IdentityStatus::Inactive,
+			link_proof(),
+		));
+		System::assert_last_event(RuntimeEvent::IMT(crate::Event::IdentityLinked {
+			who: who.clone(),
+			identity: alice_substrate_identity(),
+		}));
+		let context = IMT::id_graphs(&who, alice_substrate_identity()).unwrap();
+		assert_eq!(context.link_block, 3);
+		assert_eq!(context.web3networks, vec![Web3Network::Polkadot, Web3Network::Litentry]);
+		assert_eq!(context.status, IdentityStatus::Inactive);
+		assert!(!context.contract_wallet);
+		assert_eq!(IMT::link_proofs(&who, alice_substrate_identity()), Some(link_proof()));
+		assert_eq!(crate::IDGraphLens::<Test>::get(&who), 2);
+	});
+}
+
+#[test]
+fn import_identity_writes_nothing_if_it_fails() {
+	new_test_ext(true).execute_with(|| {
+		let who: Identity = BOB.into();
+		let import = |identity: Identity, web3networks: Vec<Web3Network>, contract_wallet| {
+			IMT::import_identity(
+				RuntimeOrigin::signed(ALICE),
+				who.clone(),
+				identity,
+				web3networks,
+				contract_wallet,
+				IdentityStatus::Active,
+				link_proof(),
+			)
+		};
+
+		assert_noop!(
+			import(alice_evm_identity(), vec![Web3Network::Litentry], false),
+			Error::<Test>::WrongWeb3NetworkTypes
+		);
+		assert_noop!(
+			import(alice_substrate_identity(), vec![Web3Network::Litentry], true),
+			Error::<Test>::ContractWalletNotEvm
+		);
+		assert_noop!(
+			import(who.clone(), vec![Web3Network::Litentry], false),
+			Error::<Test>::LinkPrimeIdentityDisallowed
+		);
+
+		assert_ok!(import(alice_substrate_identity(), vec![Web3Network::Litentry], false));
+		// it's linked already, the proof and the status aren't touched
+		assert_ok!(IMT::deactivate_identity(
+			RuntimeOrigin::signed(ALICE),
+			who.clone(),
+			alice_substrate_identity(),
+		));
+		assert_noop!(
+			import(alice_substrate_identity(), vec![Web3Network::Litentry], false),
+			Error::<Test>::IdentityAlreadyLinked
+		);
+		assert_eq!(
+			IMT::id_graphs(&who, alice_substrate_identity()).unwrap().status,
+			IdentityStatus::Inactive
+		);
+	});
+}
+
+#[test]
+fn migrate_to_v3_works() {
+	new_test_ext(false).execute_with(|| {
+		use crate::migrations::{migrate_to_v3, IdentityContextV2};
+		use codec::Encode;
+		use frame_support::{
+			storage::unhashed,
+			traits::{GetStorageVersion, StorageVersion},
+		};
+
+		StorageVersion::new(1).put::<IMT>();
+		let who: Identity = BOB.into();
+		let old = IdentityContextV2::<Test> {
+			link_block: 3,
+			web3networks: vec![Web3Network::Litentry],
+			status: IdentityStatus::Inactive,
+		};
+		let key = crate::IDGraphs::<Test>::hashed_key_for(&who, alice_substrate_identity());
+		unhashed::put_raw(&key, &old.encode());
+
+		migrate_to_v3::<Test, IMT>();
+		assert_eq!(IMT::on_chain_storage_version(), 3);
+		assert_eq!(
+			IMT::id_graphs(&who, alice_substrate_identity()).unwrap(),
+			IdentityContext {
+				link_block: 3,
+				web3networks: vec![Web3Network::Litentry],
+				status: IdentityStatus::Inactive,
+				contract_wallet: false
+			}
+		);
+
+		// it only runs once
+		crate::IDGraphs::<Test>::mutate(&who, alice_substrate_identity(), |context| {
+			context.as_mut().unwrap().contract_wallet = true
+		});
+		migrate_to_v3::<Test, IMT>();
+		assert!(IMT::id_graphs(&who, alice_substrate_identity()).unwrap().contract_wallet);
+	});
+}
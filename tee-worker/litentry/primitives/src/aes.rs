@@ -21,6 +21,7 @@ extern crate sgx_tstd as std;
 extern crate rand_sgx as rand;
 
 use crate::{AesOutput, UserShieldingKeyNonceType, UserShieldingKeyType, NONCE_LEN};
+use sp_std::vec::Vec;
 
 use rand::Rng;
 
@@ -83,6 +84,22 @@ pub fn aes_encrypt_nonce(
 	AesOutput::default()
 }
 
+// the counterpart of `aes_encrypt_default`, `None` if the data wasn't encrypted with `key`
+// or has been tampered with, as the tag is checked too
+pub fn aes_decrypt(key: &UserShieldingKeyType, data: &AesOutput) -> Option<Vec<u8>> {
+	let mut in_out = data.ciphertext.clone();
+	let unbound_key = UnboundKey::new(&AES_256_GCM, key.as_slice()).ok()?;
+	let less_safe_key = LessSafeKey::new(unbound_key);
+	let plaintext = less_safe_key
+		.open_in_place(
+			Nonce::assume_unique_for_key(data.nonce),
+			Aad::from(data.aad.as_slice()),
+			&mut in_out,
+		)
+		.ok()?;
+	Some(plaintext.to_vec())
+}
+
 #[derive(Clone)]
 pub struct RingAeadNonceSequence {
 	pub nonce: UserShieldingKeyNonceType,
@@ -102,3 +119,20 @@ impl NonceSequence for RingAeadNonceSequence {
 		Ok(nonce)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn aes_decrypt_works() {
+		let key = [1u8; 32];
+		let encrypted = aes_encrypt_default(&key, b"Hello World");
+		assert_eq!(aes_decrypt(&key, &encrypted), Some(b"Hello World".to_vec()));
+		assert_eq!(aes_decrypt(&[2u8; 32], &encrypted), None);
+
+		let mut tampered = encrypted;
+		tampered.ciphertext[0] ^= 1;
+		assert_eq!(aes_decrypt(&key, &tampered), None);
+	}
+}
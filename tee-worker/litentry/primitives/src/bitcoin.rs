@@ -0,0 +1,412 @@
+// Copyright 2020-2023 Litentry Technologies GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Bitcoin addresses and message signatures, the network is always the mainnet.
+//!
+//! Two signature formats are supported:
+//! - BIP-137, the `signmessage` format of legacy wallets, for P2PKH and P2WPKH addresses,
+//!   see https://github.com/bitcoin/bips/blob/master/bip-0137.mediawiki
+//! - BIP-322 "simple", for P2WPKH and P2TR addresses,
+//!   see https://github.com/bitcoin/bips/blob/master/bip-0322.mediawiki
+
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+use crate::{Address20, Address32};
+use bech32::{FromBase32, ToBase32, Variant};
+use codec::{Decode, Encode, MaxEncodedLen};
+use k256::{
+	ecdsa::{self, signature::hazmat::PrehashVerifier, RecoveryId},
+	schnorr,
+};
+use ripemd::{Digest, Ripemd160};
+use scale_info::TypeInfo;
+use sp_io::hashing::sha2_256;
+use sp_runtime::{traits::ConstU32, BoundedVec};
+use std::{string::String, vec, vec::Vec};
+
+/// A BIP-322 "simple" signature, the consensus encoded witness stack of `to_sign`
+pub type Bip322Signature = BoundedVec<u8, ConstU32<128>>;
+
+const BECH32_HRP: &str = "bc";
+const P2PKH_VERSION: u8 = 0x00;
+const SIGNED_MESSAGE_PREFIX: &[u8] = b"\x18Bitcoin Signed Message:\n";
+const BIP322_TAG: &[u8] = b"BIP0322-signed-message";
+const TAP_SIGHASH_TAG: &[u8] = b"TapSighash";
+const SIGHASH_DEFAULT: u8 = 0x00;
+const SIGHASH_ALL: u8 = 0x01;
+const OP_RETURN: u8 = 0x6a;
+
+#[derive(Encode, Decode, Copy, Clone, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum BitcoinAddress {
+	/// A legacy `1..` address, the hash160 of a public key
+	P2pkh(Address20),
+	/// A native segwit `bc1q..` address, the hash160 of a compressed public key
+	P2wpkh(Address20),
+	/// A taproot `bc1p..` address, the x-only output key
+	P2tr(Address32),
+}
+
+impl Default for BitcoinAddress {
+	fn default() -> Self {
+		Self::P2pkh(Default::default())
+	}
+}
+
+impl BitcoinAddress {
+	pub fn parse(address: &str) -> Option<Self> {
+		if address.get(..3).map_or(false, |prefix| prefix.eq_ignore_ascii_case("bc1")) {
+			let (hrp, data, variant) = bech32::decode(address).ok()?;
+			let (version, program) = data.split_first()?;
+			let program = Vec::<u8>::from_base32(program).ok()?;
+			return match (hrp.as_str(), version.to_u8(), variant) {
+				(BECH32_HRP, 0, Variant::Bech32) =>
+					Some(Self::P2wpkh(<[u8; 20]>::try_from(program).ok()?.into())),
+				(BECH32_HRP, 1, Variant::Bech32m) =>
+					Some(Self::P2tr(<[u8; 32]>::try_from(program).ok()?.into())),
+				_ => None,
+			}
+		}
+
+		let data = bs58::decode(address).into_vec().ok()?;
+		if data.len() != 25
+			|| data[0] != P2PKH_VERSION
+			|| data[21..] != double_sha256(&data[..21])[..4]
+		{
+			return None
+		}
+		Some(Self::P2pkh(<[u8; 20]>::try_from(&data[1..21]).ok()?.into()))
+	}
+
+	pub fn format(&self) -> String {
+		match self {
+			Self::P2pkh(hash) => {
+				let mut data = [&[P2PKH_VERSION][..], hash.as_ref()].concat();
+				let checksum = double_sha256(&data);
+				data.extend_from_slice(&checksum[..4]);
+				bs58::encode(data).into_string()
+			},
+			Self::P2wpkh(hash) => segwit_address(0, hash.as_ref(), Variant::Bech32),
+			Self::P2tr(key) => segwit_address(1, key.as_ref(), Variant::Bech32m),
+		}
+	}
+
+	/// The script of an output paying to the address.
+	pub fn script_pubkey(&self) -> Vec<u8> {
+		match self {
+			// OP_DUP OP_HASH160 <hash> OP_EQUALVERIFY OP_CHECKSIG
+			Self::P2pkh(hash) => [&[0x76, 0xa9, 0x14][..], hash.as_ref(), &[0x88, 0xac]].concat(),
+			// OP_0 <hash>
+			Self::P2wpkh(hash) => [&[0x00, 0x14][..], hash.as_ref()].concat(),
+			// OP_1 <key>
+			Self::P2tr(key) => [&[0x51, 0x20][..], key.as_ref()].concat(),
+		}
+	}
+}
+
+/// Verifies a BIP-137 signature `header ++ r ++ s` of `message`.
+pub fn verify_bip137(message: &[u8], signature: &[u8; 65], address: &BitcoinAddress) -> bool {
+	let header = signature[0];
+	if !(27..=42).contains(&header) {
+		return false
+	}
+	// 27-30: uncompressed P2PKH, 31-34: compressed P2PKH, 35-38: P2SH-P2WPKH, 39-42: P2WPKH,
+	// some wallets use 31-34 for segwit addresses as well, so only the key matters
+	let compressed = header >= 31;
+	let public = match recover(&signed_message_hash(message), &signature[1..], (header - 27) % 4) {
+		Some(public) => public,
+		None => return false,
+	};
+	let hash = hash160(public.to_encoded_point(compressed).as_bytes());
+	match address {
+		BitcoinAddress::P2pkh(expected) => expected.as_ref() == &hash,
+		BitcoinAddress::P2wpkh(expected) => compressed && expected.as_ref() == &hash,
+		BitcoinAddress::P2tr(_) => false,
+	}
+}
+
+/// Verifies a BIP-322 "simple" signature of `message`, which is the consensus encoded witness
+/// stack of the virtual `to_sign` transaction.
+pub fn verify_bip322(message: &[u8], signature: &[u8], address: &BitcoinAddress) -> bool {
+	let witness = match parse_witness(signature) {
+		Some(witness) => witness,
+		None => return false,
+	};
+	let script_pubkey = address.script_pubkey();
+	let to_spend = to_spend_txid(message, &script_pubkey);
+
+	match (address, witness.as_slice()) {
+		(BitcoinAddress::P2wpkh(hash), [signature, public]) => {
+			let (hash_type, signature) = match signature.split_last() {
+				Some((&SIGHASH_ALL, signature)) => (SIGHASH_ALL, signature),
+				_ => return false,
+			};
+			if &hash160(public) != hash.as_ref() {
+				return false
+			}
+			let sighash = segwit_v0_sighash(&to_spend, hash.as_ref(), hash_type);
+			match (
+				ecdsa::VerifyingKey::from_sec1_bytes(public),
+				ecdsa::Signature::from_der(signature),
+			) {
+				(Ok(public), Ok(signature)) => public.verify_prehash(&sighash, &signature).is_ok(),
+				_ => false,
+			}
+		},
+		(BitcoinAddress::P2tr(key), [signature]) => {
+			let (hash_type, signature) = match signature.len() {
+				64 => (SIGHASH_DEFAULT, &signature[..]),
+				65 if signature[64] == SIGHASH_ALL => (SIGHASH_ALL, &signature[..64]),
+				_ => return false,
+			};
+			let sighash = taproot_sighash(&to_spend, &script_pubkey, hash_type);
+			match (
+				schnorr::VerifyingKey::from_bytes(key.as_ref()),
+				schnorr::Signature::try_from(signature),
+			) {
+				(Ok(public), Ok(signature)) => public.verify_prehash(&sighash, &signature).is_ok(),
+				_ => false,
+			}
+		},
+		_ => false,
+	}
+}
+
+fn segwit_address(version: u8, program: &[u8], variant: Variant) -> String {
+	let mut data = vec![bech32::u5::try_from_u8(version).expect("version is less than 32")];
+	data.extend(program.to_base32());
+	bech32::encode(BECH32_HRP, data, variant).expect("hrp is valid")
+}
+
+fn recover(digest: &[u8; 32], signature: &[u8], recovery_id: u8) -> Option<ecdsa::VerifyingKey> {
+	let signature = ecdsa::Signature::from_slice(signature).ok()?;
+	let recovery_id = RecoveryId::from_byte(recovery_id)?;
+	ecdsa::VerifyingKey::recover_from_prehash(digest, &signature, recovery_id).ok()
+}
+
+fn signed_message_hash(message: &[u8]) -> [u8; 32] {
+	let mut data = SIGNED_MESSAGE_PREFIX.to_vec();
+	write_compact_size(&mut data, message.len() as u64);
+	data.extend_from_slice(message);
+	double_sha256(&data)
+}
+
+// txid of `to_spend`, its output pays to `script_pubkey`
+fn to_spend_txid(message: &[u8], script_pubkey: &[u8]) -> [u8; 32] {
+	let mut tx = Vec::new();
+	tx.extend_from_slice(&0u32.to_le_bytes()); // version
+	tx.push(1); // inputs
+	tx.extend_from_slice(&[0u8; 32]);
+	tx.extend_from_slice(&u32::MAX.to_le_bytes());
+	// OP_0 <message hash>
+	tx.extend_from_slice(&[0x22, 0x00, 0x20]);
+	tx.extend_from_slice(&tagged_hash(BIP322_TAG, message));
+	tx.extend_from_slice(&0u32.to_le_bytes()); // sequence
+	tx.push(1); // outputs
+	tx.extend_from_slice(&0u64.to_le_bytes());
+	write_compact_size(&mut tx, script_pubkey.len() as u64);
+	tx.extend_from_slice(script_pubkey);
+	tx.extend_from_slice(&0u32.to_le_bytes()); // lock time
+	double_sha256(&tx)
+}
+
+// the only output of `to_sign`: 0 sat to OP_RETURN
+fn to_sign_outputs() -> Vec<u8> {
+	[&0u64.to_le_bytes()[..], &[1, OP_RETURN]].concat()
+}
+
+// the prevout of the only input of `to_sign`
+fn to_sign_prevout(to_spend: &[u8; 32]) -> Vec<u8> {
+	[&to_spend[..], &0u32.to_le_bytes()].concat()
+}
+
+// see https://github.com/bitcoin/bips/blob/master/bip-0143.mediawiki
+fn segwit_v0_sighash(to_spend: &[u8; 32], hash: &[u8; 20], hash_type: u8) -> [u8; 32] {
+	let prevout = to_sign_prevout(to_spend);
+	let mut data = Vec::new();
+	data.extend_from_slice(&0u32.to_le_bytes()); // version
+	data.extend_from_slice(&double_sha256(&prevout));
+	data.extend_from_slice(&double_sha256(&0u32.to_le_bytes())); // sequences
+	data.extend_from_slice(&prevout);
+	data.extend_from_slice(&[0x19, 0x76, 0xa9, 0x14]);
+	data.extend_from_slice(hash);
+	data.extend_from_slice(&[0x88, 0xac]);
+	data.extend_from_slice(&0u64.to_le_bytes()); // amount
+	data.extend_from_slice(&0u32.to_le_bytes()); // sequence
+	data.extend_from_slice(&double_sha256(&to_sign_outputs()));
+	data.extend_from_slice(&0u32.to_le_bytes()); // lock time
+	data.extend_from_slice(&u32::from(hash_type).to_le_bytes());
+	double_sha256(&data)
+}
+
+// key path spending, see https://github.com/bitcoin/bips/blob/master/bip-0341.mediawiki
+fn taproot_sighash(to_spend: &[u8; 32], script_pubkey: &[u8], hash_type: u8) -> [u8; 32] {
+	let mut script_pubkeys = Vec::new();
+	write_compact_size(&mut script_pubkeys, script_pubkey.len() as u64);
+	script_pubkeys.extend_from_slice(script_pubkey);
+
+	let mut data = vec![0x00, hash_type]; // epoch, hash type
+	data.extend_from_slice(&0u32.to_le_bytes()); // version
+	data.extend_from_slice(&0u32.to_le_bytes()); // lock time
+	data.extend_from_slice(&sha2_256(&to_sign_prevout(to_spend)));
+	data.extend_from_slice(&sha2_256(&0u64.to_le_bytes())); // amounts
+	data.extend_from_slice(&sha2_256(&script_pubkeys));
+	data.extend_from_slice(&sha2_256(&0u32.to_le_bytes())); // sequences
+	data.extend_from_slice(&sha2_256(&to_sign_outputs()));
+	data.push(0x00); // spend type, without annex
+	data.extend_from_slice(&0u32.to_le_bytes()); // input index
+	tagged_hash(TAP_SIGHASH_TAG, &data)
+}
+
+fn parse_witness(data: &[u8]) -> Option<Vec<&[u8]>> {
+	let mut data = data;
+	let count = read_compact_size(&mut data)?;
+	let mut items = Vec::new();
+	for _ in 0..count {
+		let len = read_compact_size(&mut data)? as usize;
+		if len > data.len() {
+			return None
+		}
+		let (item, rest) = data.split_at(len);
+		items.push(item);
+		data = rest;
+	}
+	data.is_empty().then_some(items)
+}
+
+fn write_compact_size(data: &mut Vec<u8>, value: u64) {
+	match value {
+		0..=0xfc => data.push(value as u8),
+		0xfd..=0xffff => {
+			data.push(0xfd);
+			data.extend_from_slice(&(value as u16).to_le_bytes());
+		},
+		0x10000..=0xffff_ffff => {
+			data.push(0xfe);
+			data.extend_from_slice(&(value as u32).to_le_bytes());
+		},
+		_ => {
+			data.push(0xff);
+			data.extend_from_slice(&value.to_le_bytes());
+		},
+	}
+}
+
+fn read_compact_size(data: &mut &[u8]) -> Option<u64> {
+	let (first, rest) = data.split_first()?;
+	let len = match first {
+		0xfd => 2,
+		0xfe => 4,
+		0xff => 8,
+		value => {
+			*data = rest;
+			return Some(u64::from(*value))
+		},
+	};
+	if rest.len() < len {
+		return None
+	}
+	let mut bytes = [0u8; 8];
+	bytes[..len].copy_from_slice(&rest[..len]);
+	*data = &rest[len..];
+	Some(u64::from_le_bytes(bytes))
+}
+
+fn hash160(data: &[u8]) -> [u8; 20] {
+	Ripemd160::digest(sha2_256(data)).into()
+}
+
+fn double_sha256(data: &[u8]) -> [u8; 32] {
+	sha2_256(&sha2_256(data))
+}
+
+fn tagged_hash(tag: &[u8], data: &[u8]) -> [u8; 32] {
+	let tag = sha2_256(tag);
+	sha2_256(&[&tag[..], &tag, data].concat())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// the test vectors of BIP-322
+	const P2WPKH: &str = "bc1q9vza2e8x573nczrlzms0wvx3gsqjx7vavgkx0l";
+	const P2TR: &str = "bc1ppv609nr0vr25u07u95waq5lucwfm6tde4nydujnu8npg4q75mr5sxq8lt3";
+
+	fn decode(signature: &str) -> Vec<u8> {
+		base64::decode(signature).unwrap()
+	}
+
+	#[test]
+	fn address_works() {
+		for address in [P2WPKH, P2TR, "1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2"] {
+			assert_eq!(BitcoinAddress::parse(address).unwrap().format(), address);
+		}
+		assert!(matches!(BitcoinAddress::parse(P2WPKH), Some(BitcoinAddress::P2wpkh(_))));
+		assert!(matches!(BitcoinAddress::parse(P2TR), Some(BitcoinAddress::P2tr(_))));
+		// checksum
+		assert_eq!(BitcoinAddress::parse("1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN3"), None);
+		assert_eq!(BitcoinAddress::parse("bc1q9vza2e8x573nczrlzms0wvx3gsqjx7vavgkx0m"), None);
+		// testnet
+		assert_eq!(BitcoinAddress::parse("tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx"), None);
+	}
+
+	#[test]
+	fn verify_bip137_works() {
+		let signature = |value: &str| -> [u8; 65] { decode(value).try_into().unwrap() };
+		let compressed = signature("IOW2xi+ebJLeBtr674l4QH76dqDoVjLV80R9EFKFQX5rBrlCXPIZaYs8Yuayg0ZqjyiCbLy9pzZIS7JWT65/nsU=");
+		let uncompressed = signature("HOW2xi+ebJLeBtr674l4QH76dqDoVjLV80R9EFKFQX5rBrlCXPIZaYs8Yuayg0ZqjyiCbLy9pzZIS7JWT65/nsU=");
+		let p2pkh = BitcoinAddress::parse("14vV3aCHBeStb5bkenkNHbe2YAFinYdXgc").unwrap();
+		let p2wpkh = BitcoinAddress::parse(P2WPKH).unwrap();
+		let uncompressed_p2pkh =
+			BitcoinAddress::parse("169ojqRJ3d4f7aNMu86nAAwGJyeykmByFU").unwrap();
+
+		assert!(verify_bip137(b"Hello World", &compressed, &p2pkh));
+		assert!(verify_bip137(b"Hello World", &compressed, &p2wpkh));
+		assert!(verify_bip137(b"Hello World", &uncompressed, &uncompressed_p2pkh));
+		assert!(!verify_bip137(b"Hello World", &uncompressed, &p2wpkh));
+		assert!(!verify_bip137(b"Hello", &compressed, &p2pkh));
+		assert!(!verify_bip137(b"Hello World", &compressed, &BitcoinAddress::parse(P2TR).unwrap()));
+	}
+
+	#[test]
+	fn bip322_message_hash_works() {
+		assert_eq!(
+			hex::encode(tagged_hash(BIP322_TAG, b"")),
+			"c90c269c4f8fcbe6880f72a721ddfbf1914268a794cbb21cfafee13770ae19f1"
+		);
+		assert_eq!(
+			hex::encode(tagged_hash(BIP322_TAG, b"Hello World")),
+			"f0eb03b1a75ac6d9847f55c624a99169b5dccba2a31f5b23bea77ba270de0a7a"
+		);
+	}
+
+	#[test]
+	fn verify_bip322_works() {
+		let address = BitcoinAddress::parse(P2WPKH).unwrap();
+		let empty = decode("AkcwRAIgM2gBAQqvZX15ZiysmKmQpDrG83avLIT492QBzLnQIxYCIBaTpOaD20qRlEylyxFSeEA2ba9YOixpX8z46TSDtS40ASECx/EgAxlkQpQ9hYjgGu6EBCPMVPwVIVJqO4XCsMvViHI=");
+		let hello = decode("AkcwRAIgZRfIY3p7/DoVTty6YZbWS71bc5Vct9p9Fia83eRmw2QCICK/ENGfwLtptFluMGs2KsqoNSk89pO7F29zJLUx9a/sASECx/EgAxlkQpQ9hYjgGu6EBCPMVPwVIVJqO4XCsMvViHI=");
+		assert!(verify_bip322(b"", &empty, &address));
+		assert!(verify_bip322(b"Hello World", &hello, &address));
+		assert!(!verify_bip322(b"Hello World", &empty, &address));
+
+		let address = BitcoinAddress::parse(P2TR).unwrap();
+		let hello = decode("AUHd69PrJQEv+oKTfZ8l+WROBHuy9HKrbFCJu7U1iK2iiEy1vMU5EfMtjc+VSHM7aU0SDbak5IUZRVno2P5mjSafAQ==");
+		assert!(verify_bip322(b"Hello World", &hello, &address));
+		assert!(!verify_bip322(b"Hello", &hello, &address));
+	}
+}
@@ -0,0 +1,320 @@
+// Copyright 2020-2023 Litentry Technologies GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+//! EIP-712 typed data of a link request, so that wallets show what is signed instead of an
+//! opaque hex string, see https://eips.ethereum.org/EIPS/eip-712
+//!
+//! The typed data a wallet is asked to sign looks like:
+//! ```json
+//! {
+//!   "types": {
+//!     "EIP712Domain": [
+//!       { "name": "name", "type": "string" },
+//!       { "name": "version", "type": "string" },
+//!       { "name": "chainId", "type": "uint256" },
+//!       { "name": "shard", "type": "bytes32" },
+//!       { "name": "mrenclave", "type": "bytes32" }
+//!     ],
+//!     "LinkIdentity": [
+//!       { "name": "who", "type": "string" },
+//!       { "name": "identity", "type": "string" },
+//!       { "name": "sidechainNonce", "type": "uint32" },
+//!       { "name": "networks", "type": "string[]" }
+//!     ]
+//!   },
+//!   "primaryType": "LinkIdentity",
+//!   "domain": { "name": "Litentry", "version": "1", "chainId": 1, "shard": "0x..", "mrenclave": "0x.." },
+//!   "message": {
+//!     "who": "substrate:0xd435..",
+//!     "identity": "evm:0x7099..",
+//!     "sidechainNonce": 3,
+//!     "networks": ["Ethereum", "Bsc"]
+//!   }
+//! }
+//! ```
+
+use crate::{format_solana_address, Identity, Web3Network, Web3NetworkFamily, Web3NetworkRegistry};
+use itp_utils::hex::hex_encode;
+use sp_io::hashing::keccak_256;
+use std::{format, string::String, vec::Vec};
+
+pub const EIP712_DOMAIN_NAME: &str = "Litentry";
+pub const EIP712_DOMAIN_VERSION: &str = "1";
+
+const EIP712_DOMAIN_TYPE: &str =
+	"EIP712Domain(string name,string version,uint256 chainId,bytes32 shard,bytes32 mrenclave)";
+const LINK_IDENTITY_TYPE: &str =
+	"LinkIdentity(string who,string identity,uint32 sidechainNonce,string[] networks)";
+
+/// The domain binds a signature to the chain the wallet is connected to and to the enclave
+/// and shard that verify it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Eip712Domain {
+	pub chain_id: u64,
+	pub shard: [u8; 32],
+	pub mrenclave: [u8; 32],
+}
+
+impl Eip712Domain {
+	pub fn separator(&self) -> [u8; 32] {
+		hash_struct(
+			EIP712_DOMAIN_TYPE,
+			&[
+				encode_string(EIP712_DOMAIN_NAME),
+				encode_string(EIP712_DOMAIN_VERSION),
+				encode_uint(self.chain_id),
+				self.shard,
+				self.mrenclave,
+			],
+		)
+	}
+}
+
+/// The message of a request to link `identity` to `who`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LinkIdentityMessage<'a> {
+	pub who: &'a Identity,
+	pub identity: &'a Identity,
+	pub sidechain_nonce: u32,
+	pub networks: &'a [Web3Network],
+}
+
+impl LinkIdentityMessage<'_> {
+	pub fn hash_struct(&self) -> [u8; 32] {
+		let networks: Vec<u8> = self
+			.networks
+			.iter()
+			.flat_map(|network| encode_string(&web3network_name(network)))
+			.collect();
+		hash_struct(
+			LINK_IDENTITY_TYPE,
+			&[
+				encode_string(&identity_name(self.who)),
+				encode_string(&identity_name(self.identity)),
+				encode_uint(self.sidechain_nonce.into()),
+				keccak_256(&networks),
+			],
+		)
+	}
+}
+
+/// `0x1901 ++ domainSeparator ++ hashStruct(message)`, what's signed is its keccak256.
+pub fn eip712_encode(domain: &Eip712Domain, message: &LinkIdentityMessage) -> Vec<u8> {
+	[&[0x19, 0x01][..], &domain.separator(), &message.hash_struct()].concat()
+}
+
+/// The chain id of a built-in or registered EVM network, used as `chainId` of the domain.
+pub fn evm_chain_id(network: &Web3Network, registry: &Web3NetworkRegistry) -> Option<u64> {
+	match network.family(registry) {
+		Some(Web3NetworkFamily::Evm { chain_id }) => Some(chain_id),
+		_ => None,
+	}
+}
+
+/// How an identity is shown in the typed data, e.g. `evm:0x7099..`
+pub fn identity_name(identity: &Identity) -> String {
+	match identity {
+		Identity::Twitter(handle) => format!("twitter:{}", String::from_utf8_lossy(handle)),
+		Identity::Discord(handle) => format!("discord:{}", String::from_utf8_lossy(handle)),
+		Identity::Github(handle) => format!("github:{}", String::from_utf8_lossy(handle)),
+		Identity::Substrate(address) => format!("substrate:{}", hex_encode(address.as_ref())),
+		Identity::Evm(address) => format!("evm:{}", hex_encode(address.as_ref())),
+		Identity::Bitcoin(address) => format!("bitcoin:{}", address.format()),
+		Identity::Solana(address) => format!("solana:{}", format_solana_address(address)),
+	}
+}
+
+/// How a network is shown in the typed data, it's part of what's signed, so it must not change.
+/// A registered network is shown by its id, as its registered name may be updated.
+pub fn web3network_name(network: &Web3Network) -> String {
+	let name = match network {
+		Web3Network::Polkadot => "Polkadot",
+		Web3Network::Kusama => "Kusama",
+		Web3Network::Litentry => "Litentry",
+		Web3Network::Litmus => "Litmus",
+		Web3Network::LitentryRococo => "LitentryRococo",
+		Web3Network::Khala => "Khala",
+		Web3Network::SubstrateTestnet => "SubstrateTestnet",
+		Web3Network::Ethereum => "Ethereum",
+		Web3Network::Bsc => "Bsc",
+		Web3Network::Bitcoin => "Bitcoin",
+		Web3Network::Solana => "Solana",
+		Web3Network::Registered(id) => return format!("Registered#{}", id),
+	};
+	name.into()
+}
+
+fn hash_struct(type_string: &str, fields: &[[u8; 32]]) -> [u8; 32] {
+	let mut data = keccak_256(type_string.as_bytes()).to_vec();
+	fields.iter().for_each(|field| data.extend_from_slice(field));
+	keccak_256(&data)
+}
+
+fn encode_string(value: &str) -> [u8; 32] {
+	keccak_256(value.as_bytes())
+}
+
+fn encode_uint(value: u64) -> [u8; 32] {
+	let mut encoded = [0u8; 32];
+	encoded[24..].copy_from_slice(&value.to_be_bytes());
+	encoded
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{recover_evm_address, EthereumSignature, LitentryMultiSignature};
+	use sp_core::{ecdsa, Pair};
+
+	fn decode(value: &str) -> [u8; 32] {
+		hex::decode(value).unwrap().try_into().unwrap()
+	}
+
+	fn domain() -> Eip712Domain {
+		Eip712Domain { chain_id: 1, shard: [4u8; 32], mrenclave: [5u8; 32] }
+	}
+
+	// the example of the EIP
+	#[test]
+	fn hash_struct_works() {
+		let address = |value: &str| {
+			let mut encoded = [0u8; 32];
+			encoded[12..].copy_from_slice(&hex::decode(value).unwrap());
+			encoded
+		};
+		let domain = hash_struct(
+			"EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)",
+			&[
+				encode_string("Ether Mail"),
+				encode_string("1"),
+				encode_uint(1),
+				address("CcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"),
+			],
+		);
+		assert_eq!(
+			domain,
+			decode("f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f")
+		);
+
+		let person_type = "Person(string name,address wallet)";
+		let person = |name: &str, wallet: &str| {
+			hash_struct(person_type, &[encode_string(name), address(wallet)])
+		};
+		let mail = hash_struct(
+			&("Mail(Person from,Person to,string contents)".to_owned() + person_type),
+			&[
+				person("Cow", "CD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"),
+				person("Bob", "bBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"),
+				encode_string("Hello, Bob!"),
+			],
+		);
+		assert_eq!(
+			mail,
+			decode("c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e")
+		);
+		assert_eq!(
+			keccak_256(&[&[0x19, 0x01][..], &domain, &mail].concat()),
+			decode("be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2")
+		);
+	}
+
+	#[test]
+	fn link_identity_encoding_works() {
+		let (who, identity) =
+			(Identity::Substrate([1u8; 32].into()), Identity::Evm([2u8; 20].into()));
+		let networks = [Web3Network::Ethereum, Web3Network::Bsc];
+		let message = LinkIdentityMessage {
+			who: &who,
+			identity: &identity,
+			sidechain_nonce: 3,
+			networks: &networks,
+		};
+
+		assert_eq!(
+			domain().separator(),
+			decode("f82b647721192c7a17c194b022c207a8f8816b297c422500b7f6073a40fe4fe9")
+		);
+		assert_eq!(
+			message.hash_struct(),
+			decode("e82317dc4b2732e0e100f7fea440f4808eed44164c28650ba04ae80b54d13953")
+		);
+		assert_eq!(
+			keccak_256(&eip712_encode(&domain(), &message)),
+			decode("bdb169c46850dbd67faf8727e942ffaede9d915c2be48e61f9e70326f50eae00")
+		);
+	}
+
+	#[test]
+	fn verify_eip712_signature_works() {
+		let who = Identity::Substrate([1u8; 32].into());
+		let networks = [Web3Network::Ethereum];
+		let pair = ecdsa::Pair::from_seed(&[9u8; 32]);
+		let message = |identity| LinkIdentityMessage {
+			who: &who,
+			identity,
+			sidechain_nonce: 3,
+			networks: &networks,
+		};
+
+		// the address is only known after signing, the message doesn't depend on it here
+		let unknown = Identity::Evm([0u8; 20].into());
+		let encoded = eip712_encode(&domain(), &message(&unknown));
+		let signature = EthereumSignature(pair.sign_prehashed(&keccak_256(&encoded)).0);
+		let signer =
+			Identity::Evm(recover_evm_address(&keccak_256(&encoded), &signature.0).unwrap().into());
+
+		let eip712 = LitentryMultiSignature::Eip712(signature.clone());
+		assert!(eip712.verify(&encoded, &signer));
+
+		let other_domain = Eip712Domain { mrenclave: [6u8; 32], ..domain() };
+		assert!(!eip712.verify(&eip712_encode(&other_domain, &message(&unknown)), &signer));
+		assert!(!eip712.verify(&encoded, &Identity::Evm([3u8; 20].into())));
+		assert!(!eip712.verify(&encoded, &who));
+		// not wrapped like the other evm signatures
+		assert!(!LitentryMultiSignature::EthereumPrettified(signature).verify(&encoded, &signer));
+	}
+
+	#[test]
+	fn identity_name_works() {
+		assert_eq!(
+			identity_name(&Identity::Evm([0xabu8; 20].into())),
+			"evm:0xabababababababababababababababababababab"
+		);
+		assert_eq!(
+			identity_name(&Identity::Github(b"litentry".to_vec().try_into().unwrap())),
+			"github:litentry"
+		);
+	}
+
+	#[test]
+	fn web3network_works() {
+		let mut registry = Web3NetworkRegistry::new();
+		let arbitrum = crate::Web3NetworkInfo {
+			name: b"Arbitrum".to_vec().try_into().unwrap(),
+			family: Web3NetworkFamily::Evm { chain_id: 42161 },
+			providers: Default::default(),
+		};
+		registry.try_insert(1, arbitrum).unwrap();
+
+		assert_eq!(evm_chain_id(&Web3Network::Bsc, &registry), Some(56));
+		assert_eq!(evm_chain_id(&Web3Network::Registered(1), &registry), Some(42161));
+		assert_eq!(evm_chain_id(&Web3Network::Registered(2), &registry), None);
+		assert_eq!(evm_chain_id(&Web3Network::Polkadot, &registry), None);
+
+		assert_eq!(web3network_name(&Web3Network::LitentryRococo), "LitentryRococo");
+		assert_eq!(web3network_name(&Web3Network::Registered(1)), "Registered#1");
+	}
+}
@@ -0,0 +1,121 @@
+// Copyright 2020-2023 Litentry Technologies GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+//! The portable format of an IDGraph, used to move it to another shard or worker.
+
+use crate::{
+	Identity, ParentchainAccountId, UserShieldingKeyNonceType, Web3Network, Web3ValidationData,
+};
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use sp_core::{ed25519, H256};
+use sp_runtime::traits::Verify;
+use sp_std::vec::Vec;
+
+#[derive(Clone, Eq, PartialEq, Default, Debug, Encode, Decode, TypeInfo)]
+pub enum IdentityStatus {
+	#[default]
+	Active,
+	Inactive,
+}
+
+/// Everything a web3 identity was linked with, so that its signature can be verified again
+/// later, e.g. when the IDGraph is imported into another shard.
+///
+/// The signed message is bound to the shard and the enclave at the time of linking, which is
+/// why they are kept along with the signature. The legacy raw message is bound to the user
+/// shielding key too, the key isn't kept though: it's verified with the key of the importing
+/// shard, so such a proof can't be imported anymore once the key was changed.
+#[derive(Clone, Eq, PartialEq, Debug, Encode, Decode, TypeInfo)]
+pub struct LinkProof {
+	pub shard: H256,
+	pub mrenclave: [u8; 32],
+	pub sidechain_nonce: u32,
+	pub key_nonce: UserShieldingKeyNonceType,
+	/// the networks which were signed, they may have been changed after linking
+	pub web3networks: Vec<Web3Network>,
+	pub validation_data: Web3ValidationData,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Encode, Decode, TypeInfo)]
+pub struct IdentityExport {
+	pub identity: Identity,
+	pub web3networks: Vec<Web3Network>,
+	pub status: IdentityStatus,
+	pub contract_wallet: bool,
+	/// `None` for the prime identity, web2 identities and identities linked before the proofs
+	/// were kept, they can't be imported
+	pub proof: Option<LinkProof>,
+}
+
+/// The IDGraph of `who`, see `SignedIDGraphExport`.
+#[derive(Clone, Eq, PartialEq, Debug, Encode, Decode, TypeInfo)]
+pub struct IDGraphExport {
+	pub who: Identity,
+	pub identities: Vec<IdentityExport>,
+}
+
+/// An `IDGraphExport` signed by the enclave that exported it. It's returned encrypted with the
+/// user shielding key by `TrustedCall::export_id_graph` and imported with
+/// `TrustedCall::import_id_graph`, which only accepts the exports of enclaves registered on
+/// the parentchain.
+#[derive(Clone, Eq, PartialEq, Debug, Encode, Decode, TypeInfo)]
+pub struct SignedIDGraphExport {
+	pub export: IDGraphExport,
+	/// the account of the enclave, which is its ed25519 public key
+	pub signer: ParentchainAccountId,
+	/// the ed25519 signature of the encoded `export`
+	pub signature: Vec<u8>,
+}
+
+impl SignedIDGraphExport {
+	pub fn verify_signature(&self) -> bool {
+		let signer = ed25519::Public::from_raw(self.signer.clone().into());
+		match ed25519::Signature::from_slice(&self.signature) {
+			Some(signature) => signature.verify(self.export.encode().as_slice(), &signer),
+			None => false,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::Pair;
+
+	#[test]
+	fn verify_signature_works() {
+		let pair = ed25519::Pair::from_seed(&[1u8; 32]);
+		let export =
+			IDGraphExport { who: Identity::Substrate([2u8; 32].into()), identities: vec![] };
+		let mut signed = SignedIDGraphExport {
+			signature: pair.sign(export.encode().as_slice()).0.to_vec(),
+			export,
+			signer: pair.public().0.into(),
+		};
+		assert!(signed.verify_signature());
+
+		let mut tampered = signed.clone();
+		tampered.export.who = Identity::Substrate([3u8; 32].into());
+		assert!(!tampered.verify_signature());
+
+		signed.signer = ed25519::Pair::from_seed(&[4u8; 32]).public().0.into();
+		assert!(!signed.verify_signature());
+
+		signed.signature.truncate(63);
+		assert!(!signed.verify_signature());
+	}
+}
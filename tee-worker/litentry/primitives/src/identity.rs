@@ -20,9 +20,10 @@ extern crate sgx_tstd as std;
 #[cfg(all(not(feature = "sgx"), feature = "std"))]
 use serde::{Deserialize, Serialize};
 
+use crate::BitcoinAddress;
 use codec::{Decode, Encode, MaxEncodedLen};
 use pallet_evm::{AddressMapping, HashedAddressMapping as GenericHashedAddressMapping};
-use parentchain_primitives::{AccountId, Web3Network};
+use parentchain_primitives::{AccountId, Web3Network, Web3NetworkRegistry};
 use scale_info::TypeInfo;
 use sp_core::{crypto::AccountId32, ed25519, sr25519, ByteArray, H160};
 use sp_runtime::{
@@ -113,6 +114,8 @@ pub enum Identity {
 	// web3
 	Substrate(Address32),
 	Evm(Address20),
+	Bitcoin(BitcoinAddress),
+	Solana(Address32),
 }
 
 impl Identity {
@@ -121,7 +124,7 @@ impl Identity {
 	}
 
 	pub fn is_web3(&self) -> bool {
-		matches!(self, Self::Substrate(..) | Self::Evm(..))
+		matches!(self, Self::Substrate(..) | Self::Evm(..) | Self::Bitcoin(..) | Self::Solana(..))
 	}
 
 	pub fn is_substrate(&self) -> bool {
@@ -132,10 +135,31 @@ impl Identity {
 		matches!(self, Self::Evm(..))
 	}
 
-	// check if the given web3networks match the identity
-	pub fn matches_web3networks(&self, networks: &Vec<Web3Network>) -> bool {
-		(self.is_substrate() && !networks.is_empty() && networks.iter().all(|n| n.is_substrate()))
-			|| (self.is_evm() && !networks.is_empty() && networks.iter().all(|n| n.is_evm()))
+	pub fn is_bitcoin(&self) -> bool {
+		matches!(self, Self::Bitcoin(..))
+	}
+
+	pub fn is_solana(&self) -> bool {
+		matches!(self, Self::Solana(..))
+	}
+
+	// check if the given web3networks match the identity, the family of registered networks is
+	// looked up in `registry`
+	pub fn matches_web3networks(
+		&self,
+		networks: &[Web3Network],
+		registry: &Web3NetworkRegistry,
+	) -> bool {
+		(self.is_substrate()
+			&& !networks.is_empty()
+			&& networks.iter().all(|n| n.is_substrate_in(registry)))
+			|| (self.is_evm()
+				&& !networks.is_empty()
+				&& networks.iter().all(|n| n.is_evm_in(registry)))
+			|| (self.is_bitcoin()
+				&& !networks.is_empty()
+				&& networks.iter().all(|n| n.is_bitcoin()))
+			|| (self.is_solana() && !networks.is_empty() && networks.iter().all(|n| n.is_solana()))
 			|| (self.is_web2() && networks.is_empty())
 	}
 
@@ -202,6 +226,7 @@ impl From<[u8; 20]> for Identity {
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use parentchain_primitives::{Web3NetworkFamily, Web3NetworkInfo};
 	use sp_std::vec;
 	use strum::IntoEnumIterator;
 
@@ -216,6 +241,8 @@ mod tests {
 					Identity::Github(..) => true,
 					Identity::Substrate(..) => false,
 					Identity::Evm(..) => false,
+					Identity::Bitcoin(..) => false,
+					Identity::Solana(..) => false,
 				}
 			)
 		})
@@ -232,6 +259,8 @@ mod tests {
 					Identity::Github(..) => false,
 					Identity::Substrate(..) => true,
 					Identity::Evm(..) => true,
+					Identity::Bitcoin(..) => true,
+					Identity::Solana(..) => true,
 				}
 			)
 		})
@@ -248,6 +277,8 @@ mod tests {
 					Identity::Github(..) => false,
 					Identity::Substrate(..) => true,
 					Identity::Evm(..) => false,
+					Identity::Bitcoin(..) => false,
+					Identity::Solana(..) => false,
 				}
 			)
 		})
@@ -264,6 +295,44 @@ mod tests {
 					Identity::Github(..) => false,
 					Identity::Substrate(..) => false,
 					Identity::Evm(..) => true,
+					Identity::Bitcoin(..) => false,
+					Identity::Solana(..) => false,
+				}
+			)
+		})
+	}
+
+	#[test]
+	fn is_bitcoin_works() {
+		Identity::iter().for_each(|identity| {
+			assert_eq!(
+				identity.is_bitcoin(),
+				match identity {
+					Identity::Twitter(..) => false,
+					Identity::Discord(..) => false,
+					Identity::Github(..) => false,
+					Identity::Substrate(..) => false,
+					Identity::Evm(..) => false,
+					Identity::Bitcoin(..) => true,
+					Identity::Solana(..) => false,
+				}
+			)
+		})
+	}
+
+	#[test]
+	fn is_solana_works() {
+		Identity::iter().for_each(|identity| {
+			assert_eq!(
+				identity.is_solana(),
+				match identity {
+					Identity::Twitter(..) => false,
+					Identity::Discord(..) => false,
+					Identity::Github(..) => false,
+					Identity::Substrate(..) => false,
+					Identity::Evm(..) => false,
+					Identity::Bitcoin(..) => false,
+					Identity::Solana(..) => true,
 				}
 			)
 		})
@@ -271,29 +340,60 @@ mod tests {
 
 	#[test]
 	fn matches_web3networks_works() {
+		let mut registry = Web3NetworkRegistry::new();
+		let arbitrum = Web3NetworkInfo {
+			name: b"Arbitrum".to_vec().try_into().unwrap(),
+			family: Web3NetworkFamily::Evm { chain_id: 42161 },
+			providers: Default::default(),
+		};
+		registry.try_insert(1, arbitrum).unwrap();
+
 		// web2 identity
 		let mut id = Identity::Twitter("alice".as_bytes().to_vec().try_into().unwrap());
 		let mut networks: Vec<Web3Network> = vec![];
-		assert!(id.matches_web3networks(&networks));
+		assert!(id.matches_web3networks(&networks, &registry));
 		networks = vec![Web3Network::Litentry];
-		assert!(!id.matches_web3networks(&networks));
+		assert!(!id.matches_web3networks(&networks, &registry));
 
 		// substrate identity
 		id = Identity::Substrate(Default::default());
 		networks = vec![];
-		assert!(!id.matches_web3networks(&networks));
+		assert!(!id.matches_web3networks(&networks, &registry));
 		networks = vec![Web3Network::Bsc, Web3Network::Litentry];
-		assert!(!id.matches_web3networks(&networks));
+		assert!(!id.matches_web3networks(&networks, &registry));
 		networks = vec![Web3Network::Litentry, Web3Network::Kusama];
-		assert!(id.matches_web3networks(&networks));
+		assert!(id.matches_web3networks(&networks, &registry));
 
 		// evm identity
 		id = Identity::Evm(Default::default());
 		networks = vec![];
-		assert!(!id.matches_web3networks(&networks));
+		assert!(!id.matches_web3networks(&networks, &registry));
 		networks = vec![Web3Network::Bsc, Web3Network::Litentry];
-		assert!(!id.matches_web3networks(&networks));
+		assert!(!id.matches_web3networks(&networks, &registry));
 		networks = vec![Web3Network::Bsc, Web3Network::Ethereum];
-		assert!(id.matches_web3networks(&networks));
+		assert!(id.matches_web3networks(&networks, &registry));
+		networks = vec![Web3Network::Ethereum, Web3Network::Registered(1)];
+		assert!(id.matches_web3networks(&networks, &registry));
+		// not registered
+		networks = vec![Web3Network::Ethereum, Web3Network::Registered(2)];
+		assert!(!id.matches_web3networks(&networks, &registry));
+
+		// bitcoin identity
+		id = Identity::Bitcoin(Default::default());
+		networks = vec![];
+		assert!(!id.matches_web3networks(&networks, &registry));
+		networks = vec![Web3Network::Bitcoin, Web3Network::Ethereum];
+		assert!(!id.matches_web3networks(&networks, &registry));
+		networks = vec![Web3Network::Bitcoin];
+		assert!(id.matches_web3networks(&networks, &registry));
+
+		// solana identity
+		id = Identity::Solana(Default::default());
+		networks = vec![];
+		assert!(!id.matches_web3networks(&networks, &registry));
+		networks = vec![Web3Network::Solana, Web3Network::Polkadot];
+		assert!(!id.matches_web3networks(&networks, &registry));
+		networks = vec![Web3Network::Solana];
+		assert!(id.matches_web3networks(&networks, &registry));
 	}
 }
@@ -23,13 +23,21 @@ extern crate sgx_tstd as std;
 compile_error!("feature \"std\" and feature \"sgx\" cannot be enabled at the same time");
 
 mod aes;
+mod bitcoin;
+mod eip712;
 mod ethereum_signature;
+mod id_graph;
 mod identity;
+mod solana;
 mod validation_data;
 
 pub use aes::*;
+pub use bitcoin::*;
+pub use eip712::*;
 pub use ethereum_signature::*;
+pub use id_graph::*;
 pub use identity::*;
+pub use solana::*;
 use sp_std::vec::Vec;
 pub use validation_data::*;
 
@@ -37,7 +45,8 @@ use codec::{Decode, Encode, MaxEncodedLen};
 use itp_utils::hex::hex_encode;
 use log::error;
 pub use parentchain_primitives::{
-	all_evm_web3networks, all_substrate_web3networks, all_web3networks,
+	all_bitcoin_web3networks, all_evm_web3networks, all_solana_web3networks,
+	all_substrate_web3networks, all_web3networks, web3network_registry_key,
 	AccountId as ParentchainAccountId, AchainableAmount, AchainableAmountHolding,
 	AchainableAmountToken, AchainableAmounts, AchainableBasic, AchainableBetweenPercents,
 	AchainableClassOfYear, AchainableDate, AchainableDateInterval, AchainableDatePercent,
@@ -46,7 +55,8 @@ pub use parentchain_primitives::{
 	Hash as ParentchainHash, Header as ParentchainHeader, IMPError, Index as ParentchainIndex,
 	IntoErrorDetail, ParameterString, SchemaContentString, SchemaIdString,
 	Signature as ParentchainSignature, UserShieldingKeyNonceType, UserShieldingKeyType, VCMPError,
-	Web3Network, ASSERTION_FROM_DATE, MAX_TAG_LEN, MINUTES, NONCE_LEN, USER_SHIELDING_KEY_LEN,
+	Web3Network, Web3NetworkFamily, Web3NetworkId, Web3NetworkInfo, Web3NetworkRegistry,
+	ASSERTION_FROM_DATE, MAX_TAG_LEN, MINUTES, NONCE_LEN, USER_SHIELDING_KEY_LEN,
 };
 use scale_info::TypeInfo;
 use sp_core::{ecdsa, ed25519, sr25519, ByteArray};
@@ -70,6 +80,13 @@ pub enum LitentryMultiSignature {
 	Ethereum(EthereumSignature),
 	/// Same as the above, but the payload bytes are hex-encoded and prepended with a readable prefix
 	EthereumPrettified(EthereumSignature),
+	/// An Ethereum signature of EIP-712 typed data, the payload is the EIP-712 encoding of the
+	/// typed data, see `eip712_encode`
+	Eip712(EthereumSignature),
+	/// A Bitcoin `signmessage` signature, `header ++ r ++ s`, see BIP-137
+	Bip137(EthereumSignature),
+	/// A Bitcoin "simple" signature, see BIP-322
+	Bip322(Bip322Signature),
 }
 
 impl LitentryMultiSignature {
@@ -79,6 +96,14 @@ impl LitentryMultiSignature {
 				self.verify_substrate(substrate_wrap(msg).as_slice(), address)
 					|| self.verify_substrate(msg, address),
 			Identity::Evm(address) => self.verify_evm(msg, address),
+			// wallets sign strings, binary payloads are signed as their hex encoding
+			Identity::Bitcoin(address) =>
+				self.verify_bitcoin(msg, address)
+					|| self.verify_bitcoin(hex_encode(msg).as_bytes(), address),
+			Identity::Solana(address) => match self {
+				Self::Ed25519(ref sig) => verify_solana_signature(msg, sig, address),
+				_ => false,
+			},
 			_ => false,
 		}
 	}
@@ -120,6 +145,15 @@ impl LitentryMultiSignature {
 				return verify_evm_signature(evm_eip191_wrap(data).as_slice(), sig, signer)
 					|| verify_evm_signature(data, sig, signer)
 			},
+			Self::Eip712(ref sig) => verify_evm_signature(msg, sig, signer),
+			_ => false,
+		}
+	}
+
+	fn verify_bitcoin(&self, msg: &[u8], signer: &BitcoinAddress) -> bool {
+		match self {
+			Self::Bip137(ref sig) => verify_bip137(msg, sig.as_ref(), signer),
+			Self::Bip322(ref sig) => verify_bip322(msg, sig, signer),
 			_ => false,
 		}
 	}
@@ -172,7 +206,7 @@ fn substrate_wrap(msg: &[u8]) -> Vec<u8> {
 }
 
 // see https://github.com/litentry/litentry-parachain/issues/1970
-fn evm_eip191_wrap(msg: &[u8]) -> Vec<u8> {
+pub fn evm_eip191_wrap(msg: &[u8]) -> Vec<u8> {
 	["\x19Ethereum Signed Message:\n".as_bytes(), msg.len().to_string().as_bytes(), msg].concat()
 }
 
@@ -0,0 +1,84 @@
+// Copyright 2020-2023 Litentry Technologies GmbH.
+// This file is part of Litentry.
+//
+// Litentry is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Litentry is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Litentry.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Solana addresses, which are base58 encoded ed25519 public keys.
+//!
+//! Wallets sign messages as they are, e.g. Phantom's `signMessage`, unlike Substrate wallets
+//! they don't wrap them in `<Bytes>`.
+
+use crate::Address32;
+use sp_core::ed25519;
+use sp_runtime::traits::Verify;
+use std::string::String;
+
+pub fn parse_solana_address(address: &str) -> Option<Address32> {
+	let data = bs58::decode(address).into_vec().ok()?;
+	<[u8; 32]>::try_from(data).ok().map(Into::into)
+}
+
+pub fn format_solana_address(address: &Address32) -> String {
+	bs58::encode(address.as_ref()).into_string()
+}
+
+/// Verifies an ed25519 signature of `message` by the account `address`.
+pub fn verify_solana_signature(
+	message: &[u8],
+	signature: &ed25519::Signature,
+	address: &Address32,
+) -> bool {
+	signature.verify(message, &ed25519::Public(*address.as_ref()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Identity, LitentryMultiSignature};
+	use sp_core::Pair;
+
+	#[test]
+	fn address_works() {
+		let token_program: [u8; 32] =
+			hex::decode("06ddf6e1d765a193d9cbe146ceeb79ac1cb485ed5f5b37913a8cf5857eff00a9")
+				.unwrap()
+				.try_into()
+				.unwrap();
+		let address = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+		assert_eq!(parse_solana_address(address), Some(token_program.into()));
+		assert_eq!(format_solana_address(&token_program.into()), address);
+		assert_eq!(format_solana_address(&[0u8; 32].into()), "11111111111111111111111111111111");
+
+		// not base58, too short
+		assert_eq!(parse_solana_address("0x06ddf6e1d765a193d9cbe146ceeb79ac"), None);
+		assert_eq!(parse_solana_address("1111111111111111111111111111111"), None);
+	}
+
+	#[test]
+	fn verify_solana_signature_works() {
+		let pair = ed25519::Pair::from_seed(&[7u8; 32]);
+		let address = pair.public().0.into();
+		let signature = pair.sign(b"Hello World");
+
+		assert!(verify_solana_signature(b"Hello World", &signature, &address));
+		assert!(!verify_solana_signature(b"Hello", &signature, &address));
+		assert!(!verify_solana_signature(b"Hello World", &signature, &[1u8; 32].into()));
+
+		let identity = Identity::Solana(address);
+		assert!(LitentryMultiSignature::Ed25519(signature).verify(b"Hello World", &identity));
+		// not wrapped like substrate messages
+		let wrapped = pair.sign(b"<Bytes>Hello World</Bytes>");
+		assert!(!LitentryMultiSignature::Ed25519(wrapped).verify(b"Hello World", &identity));
+	}
+}
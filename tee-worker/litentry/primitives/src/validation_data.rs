@@ -24,6 +24,10 @@ use sp_runtime::{traits::ConstU32, BoundedVec};
 
 pub type MaxStringLength = ConstU32<64>;
 pub type ValidationString = BoundedVec<u8, MaxStringLength>;
+pub type MaxContractSignatureLength = ConstU32<1024>;
+pub type ContractSignature = BoundedVec<u8, MaxContractSignatureLength>;
+pub type MaxStructuredMessageLength = ConstU32<1024>;
+pub type StructuredMessage = BoundedVec<u8, MaxStructuredMessageLength>;
 
 #[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
@@ -39,6 +43,16 @@ pub struct DiscordValidationData {
 	pub guild_id: ValidationString,
 }
 
+/// Where the user posted the verification message on GitHub
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum GithubValidationData {
+	/// A public gist, any file of it can contain the message
+	Gist { gist_id: ValidationString },
+	/// A comment on a public issue, e.g. https://github.com/{owner}/{repo}/issues/1#issuecomment-{comment_id}
+	IssueComment { owner: ValidationString, repo: ValidationString, comment_id: ValidationString },
+}
+
 #[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub struct Web3CommonValidationData {
@@ -46,12 +60,31 @@ pub struct Web3CommonValidationData {
 	pub signature: LitentryMultiSignature,
 }
 
+/// A human readable message modeled on ERC-4361 (Sign-In with Ethereum), signed by a
+/// Substrate or an EVM account.
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct StructuredValidationData {
+	pub message: StructuredMessage,
+	pub signature: LitentryMultiSignature,
+}
+
+/// A message signed by a smart-contract wallet, e.g. a Safe. The signature is opaque, it's
+/// checked by the wallet itself with `isValidSignature` (EIP-1271) on each of the EVM networks.
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct Eip1271ValidationData {
+	pub message: ValidationString,
+	pub signature: ContractSignature,
+}
+
 #[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 #[allow(non_camel_case_types)]
 pub enum Web2ValidationData {
 	Twitter(TwitterValidationData),
 	Discord(DiscordValidationData),
+	Github(GithubValidationData),
 }
 
 #[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
@@ -60,22 +93,41 @@ pub enum Web2ValidationData {
 pub enum Web3ValidationData {
 	Substrate(Web3CommonValidationData),
 	Evm(Web3CommonValidationData),
+	Eip1271(Eip1271ValidationData),
+	Structured(StructuredValidationData),
+	/// A BIP-137 or BIP-322 signature of the raw message by a Bitcoin address
+	Bitcoin(Web3CommonValidationData),
+	/// An ed25519 signature of the raw message by a Solana account
+	Solana(Web3CommonValidationData),
 }
 
 impl Web3ValidationData {
-	pub fn message(&self) -> &ValidationString {
+	pub fn message(&self) -> &[u8] {
 		match self {
 			Self::Substrate(data) => &data.message,
 			Self::Evm(data) => &data.message,
+			Self::Eip1271(data) => &data.message,
+			Self::Structured(data) => &data.message,
+			Self::Bitcoin(data) => &data.message,
+			Self::Solana(data) => &data.message,
 		}
 	}
 
-	pub fn signature(&self) -> &LitentryMultiSignature {
+	/// The signature of an externally owned account, a contract wallet has none.
+	pub fn signature(&self) -> Option<&LitentryMultiSignature> {
 		match self {
-			Self::Substrate(data) => &data.signature,
-			Self::Evm(data) => &data.signature,
+			Self::Substrate(data) => Some(&data.signature),
+			Self::Evm(data) => Some(&data.signature),
+			Self::Structured(data) => Some(&data.signature),
+			Self::Bitcoin(data) => Some(&data.signature),
+			Self::Solana(data) => Some(&data.signature),
+			Self::Eip1271(_) => None,
 		}
 	}
+
+	pub fn is_contract_wallet(&self) -> bool {
+		matches!(self, Self::Eip1271(_))
+	}
 }
 
 #[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
@@ -84,3 +136,9 @@ pub enum ValidationData {
 	Web2(Web2ValidationData),
 	Web3(Web3ValidationData),
 }
+
+impl ValidationData {
+	pub fn is_contract_wallet(&self) -> bool {
+		matches!(self, Self::Web3(data) if data.is_contract_wallet())
+	}
+}
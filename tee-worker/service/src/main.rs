@@ -76,7 +76,7 @@ use its_peer_fetch::{
 };
 use its_primitives::types::block::SignedBlock as SignedSidechainBlock;
 use its_storage::{interface::FetchBlocks, BlockPruner, SidechainStorageLock};
-use lc_data_providers::DataProviderConfig;
+use lc_data_providers::{DataProviderConfig, ProviderAuth};
 use litentry_primitives::{Identity, ParentchainHeader as Header, UserShieldingKeyType};
 use log::*;
 use my_node_runtime::{Hash, RuntimeEvent};
@@ -206,7 +206,8 @@ fn main() {
 		},
 	};
 
-	let data_provider_config = get_data_provider_config(&config);
+	let data_provider_config = get_data_provider_config(&config)
+		.unwrap_or_else(|e| panic!("Failed to set up the data provider config: {}", e));
 
 	if let Some(run_config) = config.run_config() {
 		let shard = extract_shard(run_config.shard(), enclave.as_ref());
@@ -643,8 +644,11 @@ fn start_worker<E, T, D, InitializationHandler, WorkerModeProvider>(
 	// Start stf task handler thread
 	let enclave_api_stf_task_handler = enclave.clone();
 	let data_provider_config = data_provider_config.clone();
+	let stf_task_handler_shard = *shard;
 	thread::spawn(move || {
-		enclave_api_stf_task_handler.run_stf_task_handler(data_provider_config).unwrap();
+		enclave_api_stf_task_handler
+			.run_stf_task_handler(&stf_task_handler_shard, data_provider_config)
+			.unwrap();
 	});
 
 	// ------------------------------------------------------------------------
@@ -1067,52 +1071,106 @@ fn check_we_are_primary_validateer(
 	Ok(enclave_count_of_previous_block == 0)
 }
 
-fn get_data_provider_config(config: &Config) -> DataProviderConfig {
+fn get_data_provider_config(config: &Config) -> Result<DataProviderConfig, Error> {
 	let built_in_modes = vec!["dev", "staging", "prod", "mock"];
-	let built_in_config: Value =
-		serde_json::from_slice(include_bytes!("running-mode-config.json")).unwrap();
+	let invalid = |e: String| Error::Custom(format!("Invalid data provider config: {}", e).into());
 
 	let mut data_provider_config = if built_in_modes.contains(&config.running_mode.as_str()) {
-		let config = built_in_config.get(config.running_mode.as_str()).unwrap();
-		serde_json::from_value::<DataProviderConfig>(config.clone()).unwrap()
+		let built_in_config: Value =
+			serde_json::from_slice(include_bytes!("running-mode-config.json"))?;
+		let config = built_in_config
+			.get(config.running_mode.as_str())
+			.ok_or_else(|| invalid(format!("no built-in config for {}", config.running_mode)))?;
+		serde_json::from_value::<DataProviderConfig>(config.clone())?
 	} else {
 		let file_path = config.running_mode.as_str();
-		let mut file = File::open(file_path)
-			.map_err(|e| format!("{:?}, file:{}", e, file_path))
-			.unwrap();
 		let mut data = String::new();
-		file.read_to_string(&mut data).unwrap();
-		serde_json::from_str::<DataProviderConfig>(data.as_str()).unwrap()
+		File::open(file_path)
+			.and_then(|mut file| file.read_to_string(&mut data))
+			.map_err(|e| invalid(format!("{:?}, file: {}", e, file_path)))?;
+		serde_json::from_str::<DataProviderConfig>(data.as_str())?
 	};
+	if let Ok(v) = env::var("DATA_PROVIDER_CONFIG_VERSION") {
+		data_provider_config.version = v
+			.parse()
+			.map_err(|e| invalid(format!("DATA_PROVIDER_CONFIG_VERSION {}: {:?}", v, e)))?;
+	}
 	if let Ok(v) = env::var("TWITTER_OFFICIAL_URL") {
-		data_provider_config.set_twitter_official_url(v);
+		data_provider_config.twitter_official.endpoints = parse_endpoints(&v);
 	}
 	if let Ok(v) = env::var("TWITTER_LITENTRY_URL") {
-		data_provider_config.set_twitter_litentry_url(v);
+		data_provider_config.twitter_litentry.endpoints = parse_endpoints(&v);
 	}
 	// Bearer Token is as same as App only Access Token on Twitter (https://developer.twitter.com/en/docs/authentication/oauth-2-0/application-only),
 	// that is for developers that just need read-only access to public information.
 	if let Ok(v) = env::var("TWITTER_AUTH_TOKEN_V2") {
-		data_provider_config.set_twitter_auth_token_v2(v);
+		let token = v.trim_start_matches("Bearer ").to_string();
+		data_provider_config.twitter_official.auth = parse_auth(token, ProviderAuth::Bearer);
 	}
 	if let Ok(v) = env::var("DISCORD_OFFICIAL_URL") {
-		data_provider_config.set_discord_official_url(v);
+		data_provider_config.discord_official.endpoints = parse_endpoints(&v);
 	}
 	if let Ok(v) = env::var("DISCORD_LITENTRY_URL") {
-		data_provider_config.set_discord_litentry_url(v);
+		data_provider_config.discord_litentry.endpoints = parse_endpoints(&v);
 	}
 	if let Ok(v) = env::var("DISCORD_AUTH_TOKEN") {
-		data_provider_config.set_discord_auth_token(v);
+		data_provider_config.discord_official.auth = parse_auth(v, ProviderAuth::ApiKey);
+	}
+	if let Ok(v) = env::var("GITHUB_OFFICIAL_URL") {
+		data_provider_config.github_official.endpoints = parse_endpoints(&v);
+	}
+	// A token is optional for public gists and comments, but it raises the rate limit
+	if let Ok(v) = env::var("GITHUB_AUTH_TOKEN") {
+		data_provider_config.github_official.auth = parse_auth(v, ProviderAuth::Bearer);
 	}
 	if let Ok(v) = env::var("ACHAINABLE_URL") {
-		data_provider_config.set_achainable_url(v);
+		data_provider_config.achainable.endpoints = parse_endpoints(&v);
 	}
 	if let Ok(v) = env::var("ACHAINABLE_AUTH_KEY") {
-		data_provider_config.set_achainable_auth_key(v);
+		data_provider_config.achainable.auth = parse_auth(v, ProviderAuth::ApiKey);
+	}
+	if let Ok(v) = env::var("LITENTRY_ARCHIVE_URL") {
+		data_provider_config.litentry_archive.endpoints = parse_endpoints(&v);
+	}
+	if let Ok(v) = env::var("LITMUS_ARCHIVE_URL") {
+		data_provider_config.litmus_archive.endpoints = parse_endpoints(&v);
+	}
+	if let Ok(v) = env::var("POLKADOT_ARCHIVE_URL") {
+		data_provider_config.polkadot_archive.endpoints = parse_endpoints(&v);
+	}
+	if let Ok(v) = env::var("ETHEREUM_RPC_URL") {
+		data_provider_config.ethereum_rpc.endpoints = parse_endpoints(&v);
+	}
+	if let Ok(v) = env::var("BSC_RPC_URL") {
+		data_provider_config.bsc_rpc.endpoints = parse_endpoints(&v);
 	}
 	if let Ok(v) = env::var("CREDENTIAL_ENDPOINT") {
-		data_provider_config.set_credential_endpoint(v);
+		data_provider_config.credential_endpoint = v;
 	}
 
-	data_provider_config
+	data_provider_config.validate().map_err(|e| invalid(format!("{:?}", e)))?;
+	// the enclave keeps using its sealed config unless this one has a higher version
+	info!(
+		"Data provider config version {}, the enclave prefers a sealed config of the same shard \
+		 with a higher or equal version",
+		data_provider_config.version
+	);
+	Ok(data_provider_config)
+}
+
+// A comma separated list of endpoints, tried in the given order.
+fn parse_endpoints(v: &str) -> Vec<String> {
+	v.split(',')
+		.map(str::trim)
+		.filter(|e| !e.is_empty())
+		.map(String::from)
+		.collect()
+}
+
+fn parse_auth(secret: String, auth: fn(String) -> ProviderAuth) -> ProviderAuth {
+	if secret.trim().is_empty() {
+		ProviderAuth::None
+	} else {
+		auth(secret)
+	}
 }
@@ -35,7 +35,7 @@ use itc_rest_client::{
 	rest_client::{RestClient, Url as URL},
 	RestGet, RestPath,
 };
-use itp_enclave_metrics::EnclaveMetric;
+use itp_enclave_metrics::{EnclaveMetric, StfTaskPoolMetric};
 use lazy_static::lazy_static;
 use log::*;
 use prometheus::{proto::MetricFamily, register_int_gauge, IntGauge};
@@ -55,6 +55,21 @@ lazy_static! {
 	static ref ENCLAVE_SIDECHAIN_TOP_POOL_SIZE: IntGauge =
 		register_int_gauge!("litentry_worker_enclave_sidechain_top_pool_size", "Enclave sidechain top pool size")
 			.unwrap();
+	static ref ENCLAVE_STF_TASK_QUEUED: IntGauge =
+		register_int_gauge!("litentry_worker_enclave_stf_task_queued", "Stf tasks waiting for a worker")
+			.unwrap();
+	static ref ENCLAVE_STF_TASK_IN_FLIGHT: IntGauge =
+		register_int_gauge!("litentry_worker_enclave_stf_task_in_flight", "Stf tasks being processed")
+			.unwrap();
+	static ref ENCLAVE_STF_TASK_PROCESSED: IntGauge =
+		register_int_gauge!("litentry_worker_enclave_stf_task_processed", "Stf tasks processed since start")
+			.unwrap();
+	static ref ENCLAVE_STF_TASK_THROTTLED: IntGauge =
+		register_int_gauge!("litentry_worker_enclave_stf_task_throttled", "Stf tasks that waited for a full worker queue")
+			.unwrap();
+	static ref ENCLAVE_STF_TASK_PANICKED: IntGauge =
+		register_int_gauge!("litentry_worker_enclave_stf_task_panicked", "Stf tasks that panicked")
+			.unwrap();
 }
 
 pub async fn start_metrics_server<MetricsHandler>(
@@ -177,11 +192,20 @@ impl ReceiveEnclaveMetrics for EnclaveMetricsReceiver {
 			EnclaveMetric::ExchangeRateOracle(_) => {
 				error!("Received Teeracle metric, but Teeracle feature is not enabled, ignoring metric item.")
 			},
+			EnclaveMetric::StfTaskPool(m) => update_stf_task_pool_metrics(m),
 		}
 		Ok(())
 	}
 }
 
+fn update_stf_task_pool_metrics(metric: StfTaskPoolMetric) {
+	ENCLAVE_STF_TASK_QUEUED.set(metric.queued as i64);
+	ENCLAVE_STF_TASK_IN_FLIGHT.set(metric.in_flight as i64);
+	ENCLAVE_STF_TASK_PROCESSED.set(metric.processed as i64);
+	ENCLAVE_STF_TASK_THROTTLED.set(metric.throttled as i64);
+	ENCLAVE_STF_TASK_PANICKED.set(metric.panicked as i64);
+}
+
 // Data structure that matches with REST API JSON
 
 #[derive(Serialize, Deserialize, Debug)]
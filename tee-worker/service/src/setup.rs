@@ -20,8 +20,8 @@ use crate::error::{Error, ServiceResult};
 use codec::Encode;
 use itp_enclave_api::{enclave_base::EnclaveBase, Enclave};
 use itp_settings::files::{
-	LIGHT_CLIENT_DB_PATH, SCHEDULED_ENCLAVE_FILE, SHARDS_PATH, SHIELDING_KEY_FILE,
-	SIDECHAIN_STORAGE_PATH, SIGNING_KEY_FILE,
+	DATA_PROVIDER_CONFIG_FILE, LIGHT_CLIENT_DB_PATH, SCHEDULED_ENCLAVE_FILE, SHARDS_PATH,
+	SHIELDING_KEY_FILE, SIDECHAIN_STORAGE_PATH, SIGNING_KEY_FILE,
 };
 use itp_types::ShardIdentifier;
 use log::*;
@@ -117,6 +117,7 @@ fn purge_files(root_directory: &Path) -> ServiceResult<()> {
 	remove_dir_if_it_exists(root_directory, LIGHT_CLIENT_DB_PATH)?;
 
 	remove_file_if_it_exists(root_directory, SCHEDULED_ENCLAVE_FILE)?;
+	remove_file_if_it_exists(root_directory, DATA_PROVIDER_CONFIG_FILE)?;
 	Ok(())
 }
 
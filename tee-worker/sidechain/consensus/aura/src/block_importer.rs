@@ -20,8 +20,12 @@
 // Reexport BlockImport trait which implements fn block_import()
 pub use its_consensus_common::BlockImport;
 
+#[cfg(all(not(feature = "std"), feature = "sgx"))]
+use crate::sgx_reexport_prelude::*;
+
 use crate::{AuraVerifier, EnclaveOnChainOCallApi, SidechainBlockTrait};
-use ita_stf::hash::TrustedOperationOrHash;
+use codec::{Decode, Encode};
+use ita_stf::{hash::TrustedOperationOrHash, TrustedOperation};
 use itc_parentchain_block_import_dispatcher::triggered_dispatcher::TriggerParentchainBlockImport;
 use itp_enclave_metrics::EnclaveMetric;
 use itp_ocall_api::{EnclaveMetricsOCallApi, EnclaveSidechainOCallApi};
@@ -31,7 +35,7 @@ use itp_sgx_externalities::SgxExternalities;
 use itp_stf_state_handler::handle_state::HandleState;
 use itp_top_pool_author::traits::{AuthorApi, OnBlockImported};
 use itp_types::H256;
-use its_consensus_common::Error as ConsensusError;
+use its_consensus_common::{Error as ConsensusError, RetainedOperation, SidechainForkTrees};
 use its_primitives::traits::{
 	BlockData, Header as HeaderTrait, ShardIdentifierFor, SignedBlock as SignedBlockTrait,
 };
@@ -42,7 +46,7 @@ use sp_runtime::{
 	generic::SignedBlock as SignedParentchainBlock,
 	traits::{Block as ParentchainBlockTrait, Header},
 };
-use std::{marker::PhantomData, sync::Arc};
+use std::{marker::PhantomData, sync::Arc, vec::Vec};
 
 /// Implements `BlockImport`.
 #[derive(Clone)]
@@ -61,6 +65,7 @@ pub struct BlockImporter<
 	top_pool_author: Arc<TopPoolAuthor>,
 	parentchain_block_importer: Arc<ParentchainBlockImporter>,
 	ocall_api: Arc<OCallApi>,
+	fork_trees: Arc<SidechainForkTrees<SignedSidechainBlock>>,
 	_phantom: PhantomData<(Authority, ParentchainBlock, SignedSidechainBlock)>,
 }
 
@@ -96,7 +101,7 @@ impl<
 		+ EnclaveMetricsOCallApi
 		+ Send
 		+ Sync,
-	StateHandler: HandleState<StateT = SgxExternalities>,
+	StateHandler: HandleState<StateT = SgxExternalities, HashType = H256>,
 	StateKeyRepository: AccessKey,
 	<StateKeyRepository as AccessKey>::KeyType: StateCrypto,
 	TopPoolAuthor: AuthorApi<H256, H256> + OnBlockImported<Hash = H256>,
@@ -110,6 +115,7 @@ impl<
 		top_pool_author: Arc<TopPoolAuthor>,
 		parentchain_block_importer: Arc<ParentchainBlockImporter>,
 		ocall_api: Arc<OCallApi>,
+		fork_trees: Arc<SidechainForkTrees<SignedSidechainBlock>>,
 	) -> Self {
 		Self {
 			state_handler,
@@ -117,11 +123,35 @@ impl<
 			top_pool_author,
 			parentchain_block_importer,
 			ocall_api,
+			fork_trees,
 			_phantom: Default::default(),
 		}
 	}
 
+	/// Keep the operations of an imported block that we hold in our pool, so they can be
+	/// re-submitted in case the block is retracted.
+	fn retain_operations(&self, sidechain_block: &SignedSidechainBlock::Block) {
+		let shard = sidechain_block.header().shard_id();
+		let top_hashes = sidechain_block.block_data().signed_top_hashes();
+		let operations: Vec<RetainedOperation> = self
+			.top_pool_author
+			.get_pending_trusted_calls(shard)
+			.into_iter()
+			.map(|operation| (self.top_pool_author.hash_of(&operation), operation))
+			.filter(|(hash, _)| top_hashes.contains(hash))
+			.map(|(hash, operation)| (hash, operation.encode()))
+			.collect();
+
+		if let Err(e) = self.fork_trees.with_fork_tree(&shard, |tree| {
+			tree.retain_operations(&sidechain_block.hash(), operations)
+		}) {
+			warn!("Failed to retain trusted operations of sidechain block: {:?}", e);
+		}
+	}
+
 	fn update_top_pool(&self, sidechain_block: &SignedSidechainBlock::Block) {
+		self.retain_operations(sidechain_block);
+
 		// Notify pool about imported block for status updates of the calls.
 		self.top_pool_author.on_block_imported(
 			sidechain_block.block_data().signed_top_hashes(),
@@ -181,7 +211,7 @@ impl<
 		+ EnclaveMetricsOCallApi
 		+ Send
 		+ Sync,
-	StateHandler: HandleState<StateT = SgxExternalities>,
+	StateHandler: HandleState<StateT = SgxExternalities, HashType = H256>,
 	StateKeyRepository: AccessKey,
 	<StateKeyRepository as AccessKey>::KeyType: StateCrypto,
 	TopPoolAuthor: AuthorApi<H256, H256> + OnBlockImported<Hash = H256>,
@@ -241,6 +271,46 @@ impl<
 			.map_err(|e| ConsensusError::Other(format!("{:?}", e).into()))?
 	}
 
+	fn fork_trees(&self) -> &SidechainForkTrees<SignedSidechainBlock> {
+		&self.fork_trees
+	}
+
+	fn state_hash(
+		&self,
+		shard: &ShardIdentifierFor<SignedSidechainBlock>,
+	) -> Result<H256, ConsensusError> {
+		self.state_handler
+			.execute_on_current(shard, |_, state_hash| state_hash)
+			.map_err(|e| ConsensusError::Other(format!("{:?}", e).into()))
+	}
+
+	fn revert_state(
+		&self,
+		shard: &ShardIdentifierFor<SignedSidechainBlock>,
+		state_hash: &H256,
+	) -> Result<(), ConsensusError> {
+		self.state_handler
+			.revert_to(shard, state_hash)
+			.map(|_| ())
+			.map_err(|e| ConsensusError::Other(format!("{:?}", e).into()))
+	}
+
+	fn resubmit_operations(
+		&self,
+		shard: &ShardIdentifierFor<SignedSidechainBlock>,
+		operations: Vec<RetainedOperation>,
+	) -> Result<(), ConsensusError> {
+		for (hash, encoded_operation) in operations {
+			let operation = TrustedOperation::decode(&mut encoded_operation.as_slice())?;
+			if let Err(e) =
+				futures::executor::block_on(self.top_pool_author.resubmit_top(operation, *shard))
+			{
+				warn!("Failed to re-submit trusted operation {:?}: {:?}", hash, e);
+			}
+		}
+		Ok(())
+	}
+
 	fn state_key(&self) -> Result<Self::StateCrypto, ConsensusError> {
 		self.state_key_repository
 			.retrieve_key()
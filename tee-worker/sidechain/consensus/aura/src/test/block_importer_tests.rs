@@ -88,6 +88,7 @@ fn test_fixtures(
 		top_pool_author.clone(),
 		parentchain_block_import_trigger,
 		ocall_api,
+		Arc::new(Default::default()),
 	);
 
 	(block_importer, state_handler, top_pool_author)
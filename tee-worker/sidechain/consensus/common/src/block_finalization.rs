@@ -0,0 +1,173 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Finalization of sidechain blocks, driven by the import confirmations that are part of
+//! finalized parentchain blocks.
+
+use crate::{error::Result, Error, SidechainForkTrees};
+use codec::{Decode, Encode};
+use itp_ocall_api::EnclaveOnChainOCallApi;
+use itp_storage::{storage_map_key, StorageHasher};
+use itp_types::H256;
+use its_primitives::traits::{ShardIdentifierFor, SignedBlock as SignedSidechainBlockTrait};
+use log::*;
+use sp_runtime::traits::Header as ParentchainHeaderTrait;
+use std::vec::Vec;
+
+// FIXME: Copied from the `sidechain-primitives` of the parachain, which the worker does not depend on.
+/// Latest sidechain block of a shard the parentchain considers final.
+#[derive(Encode, Decode, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SidechainBlockConfirmation {
+	pub block_number: u64,
+	pub block_header_hash: H256,
+	/// The state of the shard after the block was imported
+	pub state_root: H256,
+}
+
+/// Storage key of `Sidechain::LatestSidechainBlockConfirmation` for a shard.
+pub fn latest_sidechain_block_confirmation_key<Shard: Encode>(shard: &Shard) -> Vec<u8> {
+	storage_map_key(
+		"Sidechain",
+		"LatestSidechainBlockConfirmation",
+		shard,
+		&StorageHasher::Blake2_128Concat,
+	)
+}
+
+/// Finalize the latest block of each shard that the parentchain confirmed as of
+/// `parentchain_header`.
+///
+/// `parentchain_header` has to be a finalized header of the light client, otherwise a
+/// parentchain reorg could still revert the confirmation.
+pub fn finalize_confirmed_blocks<SignedSidechainBlock, OnchainStorage, ParentchainHeader>(
+	fork_trees: &SidechainForkTrees<SignedSidechainBlock>,
+	onchain_storage: &OnchainStorage,
+	shards: &[ShardIdentifierFor<SignedSidechainBlock>],
+	parentchain_header: &ParentchainHeader,
+) -> Result<()>
+where
+	SignedSidechainBlock: SignedSidechainBlockTrait,
+	OnchainStorage: EnclaveOnChainOCallApi,
+	ParentchainHeader: ParentchainHeaderTrait<Hash = H256>,
+{
+	for shard in shards {
+		let confirmation: Option<SidechainBlockConfirmation> = onchain_storage
+			.get_storage_verified(
+				latest_sidechain_block_confirmation_key(shard),
+				parentchain_header,
+			)
+			.map_err(|e| Error::Other(format!("{:?}", e).into()))?
+			.into_tuple()
+			.1;
+
+		if let Some(confirmation) = confirmation {
+			debug!(
+				"Parentchain confirmed sidechain block {} ({:?})",
+				confirmation.block_number, confirmation.block_header_hash
+			);
+			fork_trees.finalize(
+				shard,
+				&confirmation.block_header_hash,
+				confirmation.block_number,
+			)?;
+		}
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use itc_parentchain_test::ParentchainHeaderBuilder;
+	use itp_test::mock::onchain_mock::OnchainMock;
+	use its_primitives::types::{header::SidechainHeader, BlockHash, BlockNumber, SignedBlock};
+	use its_test::{
+		sidechain_block_builder::{SidechainBlockBuilder, SidechainBlockBuilderTrait},
+		sidechain_header_builder::SidechainHeaderBuilder,
+	};
+
+	fn block(number: BlockNumber, parent_hash: BlockHash) -> SignedBlock {
+		let header: SidechainHeader = SidechainHeaderBuilder::random()
+			.with_block_number(number)
+			.with_parent_hash(parent_hash)
+			.with_shard(H256::default())
+			.build();
+		SidechainBlockBuilder::random().with_header(header).build_signed()
+	}
+
+	#[test]
+	fn block_is_finalized_once_its_confirmation_is_on_the_parentchain() {
+		let shard = H256::default();
+		let genesis = block(1, BlockHash::default());
+		let block_a = block(2, genesis.hash());
+		let block_b = block(2, genesis.hash());
+
+		let fork_trees = SidechainForkTrees::<SignedBlock>::default();
+		fork_trees.on_canonical_block(genesis.clone(), H256::random()).unwrap();
+		fork_trees.on_canonical_block(block_a.clone(), H256::random()).unwrap();
+		fork_trees
+			.with_fork_tree(&shard, |tree| tree.import(block_b.clone(), None))
+			.unwrap()
+			.unwrap();
+
+		let header = ParentchainHeaderBuilder::default().build();
+		let confirmation = SidechainBlockConfirmation {
+			block_number: 2,
+			block_header_hash: block_a.hash(),
+			state_root: H256::random(),
+		};
+		let onchain_mock = OnchainMock::default().with_storage_entries_at_header(
+			&header,
+			vec![(latest_sidechain_block_confirmation_key(&shard), confirmation)],
+		);
+
+		finalize_confirmed_blocks(&fork_trees, &onchain_mock, &[shard], &header).unwrap();
+
+		fork_trees
+			.with_fork_tree(&shard, |tree| {
+				assert_eq!(tree.finalized(), Some(block_a.hash()));
+				assert!(!tree.contains(&block_b.hash()));
+			})
+			.unwrap();
+	}
+
+	#[test]
+	fn nothing_is_finalized_without_confirmation() {
+		let shard = H256::default();
+		let genesis = block(1, BlockHash::default());
+		let block_a = block(2, genesis.hash());
+		let block_b = block(2, genesis.hash());
+
+		let fork_trees = SidechainForkTrees::<SignedBlock>::default();
+		fork_trees.on_canonical_block(genesis, H256::random()).unwrap();
+		fork_trees.on_canonical_block(block_a, H256::random()).unwrap();
+		fork_trees
+			.with_fork_tree(&shard, |tree| tree.import(block_b.clone(), None))
+			.unwrap()
+			.unwrap();
+
+		let header = ParentchainHeaderBuilder::default().build();
+		finalize_confirmed_blocks(&fork_trees, &OnchainMock::default(), &[shard], &header).unwrap();
+
+		fork_trees
+			.with_fork_tree(&shard, |tree| {
+				assert_eq!(tree.finalized(), None);
+				assert!(tree.contains(&block_b.hash()));
+			})
+			.unwrap();
+	}
+}
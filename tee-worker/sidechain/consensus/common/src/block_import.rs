@@ -17,10 +17,11 @@
 
 //! Abstraction around block import
 
-use crate::{Error, Verifier};
+use crate::{Error, RetainedOperation, SidechainForkTrees, TreeRoute, Verifier};
 use codec::Decode;
 use itp_ocall_api::EnclaveSidechainOCallApi;
 use itp_sgx_crypto::StateCrypto;
+use itp_types::H256;
 use its_primitives::traits::{
 	Block as SidechainBlockTrait, BlockData, Header as HeaderTrait, ShardIdentifierFor,
 	SignedBlock as SignedSidechainBlockTrait,
@@ -105,11 +106,191 @@ where
 	/// Cleanup task after import is done.
 	fn cleanup(&self, signed_sidechain_block: &SignedSidechainBlock) -> Result<(), Error>;
 
-	/// Import a sidechain block and mutate state by `apply_state_update`.
+	/// Fork trees tracking the competing sidechain blocks of each shard.
+	fn fork_trees(&self) -> &SidechainForkTrees<SignedSidechainBlock>;
+
+	/// Hash of the current state of a shard.
+	fn state_hash(&self, shard: &ShardIdentifierFor<SignedSidechainBlock>) -> Result<H256, Error>;
+
+	/// Revert the state of a shard to a previous snapshot, identified by its state hash.
+	fn revert_state(
+		&self,
+		shard: &ShardIdentifierFor<SignedSidechainBlock>,
+		state_hash: &H256,
+	) -> Result<(), Error>;
+
+	/// Submit the trusted operations of retracted blocks to the pool again.
+	fn resubmit_operations(
+		&self,
+		shard: &ShardIdentifierFor<SignedSidechainBlock>,
+		operations: Vec<RetainedOperation>,
+	) -> Result<(), Error>;
+
+	/// Import a sidechain block.
+	///
+	/// Blocks building on our last imported block are applied directly. A block that builds on
+	/// another known block is tracked as fork candidate, and in case the fork-choice rule prefers
+	/// its branch, the state is reverted to the common ancestor and the branch is applied.
 	fn import_block(
 		&self,
 		signed_sidechain_block: SignedSidechainBlock,
 		parentchain_header: &ParentchainBlock::Header,
+	) -> Result<ParentchainBlock::Header, Error> {
+		let shard = signed_sidechain_block.block().header().shard_id();
+		let block_hash = signed_sidechain_block.hash();
+		let parent_hash = signed_sidechain_block.block().header().parent_hash();
+
+		match self.import_on_head(signed_sidechain_block.clone(), parentchain_header) {
+			Err(e @ Error::BlockAncestryMismatch(..))
+			| Err(e @ Error::BlockAlreadyImported(..)) => {
+				let is_fork_candidate = self.fork_trees().with_fork_tree(&shard, |tree| {
+					tree.head().is_some()
+						&& tree.contains(&parent_hash)
+						&& !tree.contains(&block_hash)
+				})?;
+				if !is_fork_candidate {
+					return Err(e)
+				}
+				self.import_fork_block(signed_sidechain_block, parentchain_header)
+			},
+			result => result,
+		}
+	}
+
+	/// Track a block that does not build on our head in the fork tree and reorg to its branch
+	/// if the fork-choice rule prefers it.
+	fn import_fork_block(
+		&self,
+		signed_sidechain_block: SignedSidechainBlock,
+		parentchain_header: &ParentchainBlock::Header,
+	) -> Result<ParentchainBlock::Header, Error> {
+		let shard = signed_sidechain_block.block().header().shard_id();
+		let block_hash = signed_sidechain_block.hash();
+		let block_number = signed_sidechain_block.block().header().block_number();
+
+		let maybe_route = self.fork_trees().with_fork_tree(&shard, |tree| {
+			tree.import(signed_sidechain_block, None)?;
+			match (tree.head(), tree.best_block()) {
+				(Some(head), Some((best, _))) if best == block_hash =>
+					tree.route(&head, &block_hash).map(Some),
+				_ => Ok(None),
+			}
+		})??;
+
+		match maybe_route {
+			Some(route) => self.reorg(&shard, route, parentchain_header),
+			None => {
+				info!(
+					"Sidechain block {} ({:?}) does not win the fork choice, keeping it as fork candidate",
+					block_number, block_hash
+				);
+				Err(Error::NonCanonicalBlock(block_number, block_hash))
+			},
+		}
+	}
+
+	/// Revert the state to the common ancestor of the route and apply the enacted blocks.
+	///
+	/// The trusted operations of the retracted blocks that are not part of the enacted branch are
+	/// submitted to the pool again. In case an enacted block fails to import, its branch is
+	/// dropped and the retracted blocks are applied again.
+	fn reorg(
+		&self,
+		shard: &ShardIdentifierFor<SignedSidechainBlock>,
+		route: TreeRoute,
+		parentchain_header: &ParentchainBlock::Header,
+	) -> Result<ParentchainBlock::Header, Error> {
+		let (ancestor_state_hash, enacted, retracted, retracted_operations) =
+			self.fork_trees().with_fork_tree(shard, |tree| {
+				let blocks = |hashes: &[H256]| -> Vec<SignedSidechainBlock> {
+					hashes.iter().filter_map(|h| tree.block(h).cloned()).collect()
+				};
+				let mut retracted = blocks(&route.retracted);
+				retracted.reverse();
+				let retracted_operations: Vec<RetainedOperation> = route
+					.retracted
+					.iter()
+					.flat_map(|h| tree.operations(h).iter().cloned())
+					.collect();
+				(
+					tree.state_hash(&route.common_ancestor),
+					blocks(&route.enacted),
+					retracted,
+					retracted_operations,
+				)
+			})?;
+
+		let ancestor_state_hash = match ancestor_state_hash {
+			Some(hash) => hash,
+			None => {
+				warn!(
+					"No state known for common ancestor {:?}, cannot reorg",
+					route.common_ancestor
+				);
+				return Err(Error::ForkTree("State of common ancestor is unknown".into()))
+			},
+		};
+
+		// Nothing is changed in case the snapshot is no longer available.
+		self.revert_state(shard, &ancestor_state_hash)?;
+		self.fork_trees()
+			.with_fork_tree(shard, |tree| tree.set_head(route.common_ancestor))?;
+
+		info!(
+			"Sidechain reorg: retracting {} and enacting {} block(s) on top of {:?}",
+			retracted.len(),
+			enacted.len(),
+			route.common_ancestor
+		);
+
+		let enacted_operation_hashes: Vec<H256> = enacted
+			.iter()
+			.flat_map(|block| block.block().block_data().signed_top_hashes().iter().cloned())
+			.collect();
+
+		let mut latest_parentchain_header = parentchain_header.clone();
+		for block in enacted {
+			let hash = block.hash();
+			match self.import_on_head(block, &latest_parentchain_header) {
+				Ok(header) => latest_parentchain_header = header,
+				Err(e) => {
+					error!("Failed to import sidechain block {:?} while reorging: {:?}", hash, e);
+					self.fork_trees().with_fork_tree(shard, |tree| tree.remove_branch(&hash))?;
+					self.revert_state(shard, &ancestor_state_hash)?;
+					self.fork_trees()
+						.with_fork_tree(shard, |tree| tree.set_head(route.common_ancestor))?;
+					for retracted_block in retracted {
+						latest_parentchain_header =
+							self.import_on_head(retracted_block, &latest_parentchain_header)?;
+					}
+					return Err(e)
+				},
+			}
+		}
+
+		let operations_to_resubmit: Vec<RetainedOperation> = retracted_operations
+			.into_iter()
+			.filter(|(hash, _)| !enacted_operation_hashes.contains(hash))
+			.collect();
+		if !operations_to_resubmit.is_empty() {
+			info!(
+				"Re-submitting {} trusted operation(s) of retracted sidechain blocks",
+				operations_to_resubmit.len()
+			);
+			if let Err(e) = self.resubmit_operations(shard, operations_to_resubmit) {
+				warn!("Failed to re-submit trusted operations of retracted blocks: {:?}", e);
+			}
+		}
+
+		Ok(latest_parentchain_header)
+	}
+
+	/// Import a sidechain block that builds on our last imported block and mutate state by
+	/// `apply_state_update`.
+	fn import_on_head(
+		&self,
+		signed_sidechain_block: SignedSidechainBlock,
+		parentchain_header: &ParentchainBlock::Header,
 	) -> Result<ParentchainBlock::Header, Error> {
 		let start_time = Instant::now();
 
@@ -170,6 +351,10 @@ where
 			state_update_start_time.elapsed().as_millis()
 		);
 
+		let state_hash = self.state_hash(&shard)?;
+		self.fork_trees()
+			.on_canonical_block(signed_sidechain_block.clone(), state_hash)?;
+
 		self.cleanup(&signed_sidechain_block)?;
 
 		// Store block in storage.
@@ -190,3 +375,155 @@ fn state_update_from_encrypted<Key: StateCrypto, StateUpdate: Decode>(
 
 	Ok(Decode::decode(&mut payload.as_slice())?)
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::test::mocks::block_importer_mock::BlockImportMock;
+	use core::assert_matches::assert_matches;
+	use itc_parentchain_test::ParentchainHeaderBuilder;
+	use itp_types::Block as ParentchainBlock;
+	use its_primitives::types::{header::SidechainHeader, BlockHash, BlockNumber, SignedBlock};
+	use its_test::{
+		sidechain_block_builder::{SidechainBlockBuilder, SidechainBlockBuilderTrait},
+		sidechain_block_data_builder::SidechainBlockDataBuilder,
+		sidechain_header_builder::SidechainHeaderBuilder,
+	};
+
+	type TestBlockImport = BlockImportMock<ParentchainBlock, SignedBlock>;
+
+	fn block(number: BlockNumber, parent_hash: BlockHash, top_hashes: Vec<H256>) -> SignedBlock {
+		let header: SidechainHeader = SidechainHeaderBuilder::random()
+			.with_block_number(number)
+			.with_parent_hash(parent_hash)
+			.with_shard(H256::default())
+			.build();
+		let block_data =
+			SidechainBlockDataBuilder::random().with_signed_top_hashes(top_hashes).build();
+		SidechainBlockBuilder::random()
+			.with_header(header)
+			.with_block_data(block_data)
+			.build_signed()
+	}
+
+	/// Canonical chain genesis <- a, competing fork genesis <- b <- b2, where `a` wins the tie
+	/// against `b`. `b` carries the trusted operation `top_hashes[1]`.
+	fn forked_blocks(top_hashes: &[H256; 2]) -> [SignedBlock; 4] {
+		let genesis = block(1, BlockHash::default(), vec![]);
+		let block_b = block(2, genesis.hash(), vec![top_hashes[1]]);
+		let block_a = loop {
+			let candidate = block(2, genesis.hash(), vec![]);
+			if candidate.hash() < block_b.hash() {
+				break candidate
+			}
+		};
+		let block_b2 = block(3, block_b.hash(), vec![]);
+		[genesis, block_a, block_b, block_b2]
+	}
+
+	fn import_canonical_chain(importer: &TestBlockImport, blocks: &[SignedBlock]) {
+		let parentchain_header = ParentchainHeaderBuilder::default().build();
+		for block in blocks {
+			importer.import_block(block.clone(), &parentchain_header).unwrap();
+		}
+	}
+
+	fn head(importer: &TestBlockImport) -> Option<BlockHash> {
+		importer
+			.fork_trees()
+			.with_fork_tree(&H256::default(), |tree| tree.head())
+			.unwrap()
+	}
+
+	#[test]
+	fn block_losing_the_fork_choice_is_kept_as_candidate() {
+		let top_hashes = [H256::random(), H256::random()];
+		let [genesis, block_a, block_b, _] = forked_blocks(&top_hashes);
+		let importer = TestBlockImport::default().with_ancestry_check();
+		import_canonical_chain(&importer, &[genesis, block_a.clone()]);
+
+		let result =
+			importer.import_block(block_b.clone(), &ParentchainHeaderBuilder::default().build());
+
+		assert_matches!(result, Err(Error::NonCanonicalBlock(2, hash)) if hash == block_b.hash());
+		assert_eq!(head(&importer), Some(block_a.hash()));
+		assert_eq!(importer.get_state_hash(), block_a.hash());
+	}
+
+	#[test]
+	fn longer_fork_reorgs_and_resubmits_retracted_operations() {
+		let top_hashes = [H256::random(), H256::random()];
+		let [genesis, block_a, block_b, block_b2] = forked_blocks(&top_hashes);
+		let importer = TestBlockImport::default().with_ancestry_check();
+		import_canonical_chain(&importer, &[genesis, block_a.clone()]);
+		importer
+			.fork_trees()
+			.with_fork_tree(&H256::default(), |tree| {
+				tree.retain_operations(
+					&block_a.hash(),
+					vec![(top_hashes[0], vec![0u8]), (top_hashes[1], vec![1u8])],
+				)
+			})
+			.unwrap();
+
+		let parentchain_header = ParentchainHeaderBuilder::default().build();
+		let _ = importer.import_block(block_b.clone(), &parentchain_header);
+		importer.import_block(block_b2.clone(), &parentchain_header).unwrap();
+
+		assert_eq!(head(&importer), Some(block_b2.hash()));
+		assert_eq!(importer.get_state_hash(), block_b2.hash());
+		// The operation that is part of the enacted branch is not submitted again.
+		assert_eq!(importer.get_resubmitted_operations(), vec![(top_hashes[0], vec![0u8])]);
+	}
+
+	#[test]
+	fn failed_reorg_restores_retracted_blocks() {
+		let top_hashes = [H256::random(), H256::random()];
+		let [genesis, block_a, block_b, block_b2] = forked_blocks(&top_hashes);
+		let importer = TestBlockImport::default()
+			.with_ancestry_check()
+			.with_failing_block(block_b2.hash());
+		import_canonical_chain(&importer, &[genesis, block_a.clone()]);
+		importer
+			.fork_trees()
+			.with_fork_tree(&H256::default(), |tree| {
+				tree.retain_operations(&block_a.hash(), vec![(top_hashes[0], vec![0u8])])
+			})
+			.unwrap();
+
+		let parentchain_header = ParentchainHeaderBuilder::default().build();
+		let _ = importer.import_block(block_b.clone(), &parentchain_header);
+		let result = importer.import_block(block_b2.clone(), &parentchain_header);
+
+		assert!(result.is_err());
+		assert_eq!(head(&importer), Some(block_a.hash()));
+		assert_eq!(importer.get_state_hash(), block_a.hash());
+		assert!(importer.get_resubmitted_operations().is_empty());
+		importer
+			.fork_trees()
+			.with_fork_tree(&H256::default(), |tree| assert!(!tree.contains(&block_b2.hash())))
+			.unwrap();
+	}
+
+	#[test]
+	fn reorg_fails_without_state_of_common_ancestor() {
+		let top_hashes = [H256::random(), H256::random()];
+		let [genesis, block_a, block_b, block_b2] = forked_blocks(&top_hashes);
+		let importer = TestBlockImport::default().with_ancestry_check();
+		import_canonical_chain(&importer, &[block_a.clone()]);
+		// The common ancestor is only known as fork candidate, without a state.
+		importer
+			.fork_trees()
+			.with_fork_tree(&H256::default(), |tree| tree.import(genesis, None))
+			.unwrap()
+			.unwrap();
+
+		let parentchain_header = ParentchainHeaderBuilder::default().build();
+		let _ = importer.import_block(block_b, &parentchain_header);
+		let result = importer.import_block(block_b2, &parentchain_header);
+
+		assert_matches!(result, Err(Error::ForkTree(_)));
+		assert_eq!(head(&importer), Some(block_a.hash()));
+		assert_eq!(importer.get_state_hash(), block_a.hash());
+	}
+}
@@ -23,7 +23,7 @@ use itp_extrinsics_factory::CreateExtrinsics;
 use itp_node_api_metadata::{pallet_sidechain::SidechainCallIndexes, NodeMetadataTrait};
 use itp_node_api_metadata_provider::AccessNodeMetadata;
 use itp_settings::worker::BLOCK_NUMBER_FINALIZATION_DIFF;
-use itp_types::{OpaqueCall, ShardIdentifier};
+use itp_types::{OpaqueCall, ShardIdentifier, H256};
 use its_primitives::traits::Header as HeaderTrait;
 use log::*;
 use sp_runtime::traits::Block as ParentchainBlockTrait;
@@ -31,7 +31,14 @@ use std::{marker::PhantomData, sync::Arc};
 
 /// Trait to confirm a sidechain block import.
 pub trait ConfirmBlockImport<SidechainHeader> {
-	fn confirm_import(&self, header: &SidechainHeader, shard: &ShardIdentifier) -> Result<()>;
+	/// `state_root` is the hash of the state of `shard` after importing the block, the block is
+	/// only finalized when enough enclaves confirm the same one.
+	fn confirm_import(
+		&self,
+		header: &SidechainHeader,
+		state_root: &H256,
+		shard: &ShardIdentifier,
+	) -> Result<()>;
 }
 
 /// Creates and sends a sidechain block import confirmation extrsinic to the parentchain.
@@ -99,7 +106,12 @@ impl<
 	ExtrinsicsFactory: CreateExtrinsics,
 	ValidatorAccessor: ValidatorAccess<ParentchainBlock> + Send + Sync + 'static,
 {
-	fn confirm_import(&self, header: &SidechainHeader, shard: &ShardIdentifier) -> Result<()> {
+	fn confirm_import(
+		&self,
+		header: &SidechainHeader,
+		state_root: &H256,
+		shard: &ShardIdentifier,
+	) -> Result<()> {
 		let call = self
 			.metadata_repository
 			.get_from_metadata(|m| m.confirm_imported_sidechain_block_indexes())
@@ -113,6 +125,7 @@ impl<
 				header.block_number(),
 				header.next_finalization_block_number() + BLOCK_NUMBER_FINALIZATION_DIFF,
 				header.hash(),
+				state_root,
 			));
 
 			let xts = self
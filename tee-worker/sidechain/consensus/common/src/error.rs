@@ -60,6 +60,10 @@ pub enum Error {
 	InvalidFirstBlock(BlockNumber, String),
 	#[error("Could not import block (number: {0}). A block with this number is already imported (current state block number: {1})")]
 	BlockAlreadyImported(BlockNumber, BlockNumber),
+	#[error("Sidechain block (number: {0}, hash: {1}) is kept as fork candidate, it does not extend the canonical chain")]
+	NonCanonicalBlock(BlockNumber, SidechainBlockHash),
+	#[error("Fork tree error: {0}")]
+	ForkTree(String),
 	#[error("Failed to pop from block import queue: {0}")]
 	FailedToPopBlockImportQueue(#[from] itp_import_queue::error::Error),
 	#[error("Verification Error: {0}")]
@@ -0,0 +1,620 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Tracking of competing sidechain blocks (forks) and the fork-choice rule.
+//!
+//! Every shard has its own fork tree. The canonical head is the block with the highest block
+//! number, ties are broken by the lowest block hash. Once the confirmation of a block
+//! (`confirm_imported_sidechain_block`) is part of a finalized parentchain block, the sidechain
+//! block is finalized and all branches that do not contain it are pruned.
+//!
+//! The trees are bounded: fork candidates are rejected once a tree tracks
+//! [`MAX_FORK_TREE_BLOCKS`] blocks, and ancestors of the head deeper than [`MAX_REORG_DEPTH`]
+//! are pruned, even if the parentchain did not finalize them (yet).
+
+#[cfg(feature = "sgx")]
+use std::sync::SgxRwLock as RwLock;
+
+#[cfg(feature = "std")]
+use std::sync::RwLock;
+
+use crate::error::{Error, Result};
+use core::cmp::Reverse;
+use fork_tree::{FilterAction, ForkTree};
+use itp_types::H256;
+use its_primitives::{
+	traits::{
+		Block as BlockTrait, Header as HeaderTrait, ShardIdentifierFor,
+		SignedBlock as SignedSidechainBlockTrait,
+	},
+	types::{BlockHash, BlockNumber},
+};
+use log::*;
+use std::{
+	collections::{HashMap, HashSet},
+	vec::Vec,
+};
+
+type HeaderFor<SignedSidechainBlock> =
+	<<SignedSidechainBlock as SignedSidechainBlockTrait>::Block as BlockTrait>::HeaderType;
+
+/// Maximum number of blocks tracked per shard, fork candidates beyond it are rejected.
+pub const MAX_FORK_TREE_BLOCKS: usize = 1024;
+
+/// Number of blocks below the head that can still be retracted by a reorg.
+pub const MAX_REORG_DEPTH: BlockNumber = 256;
+
+/// Trusted operation hash and encoded trusted operation.
+pub type RetainedOperation = (H256, Vec<u8>);
+
+/// Path between two blocks of a fork tree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TreeRoute {
+	/// Last block both blocks have in common.
+	pub common_ancestor: BlockHash,
+	/// Blocks to retract, starting at the old head (newest first).
+	pub retracted: Vec<BlockHash>,
+	/// Blocks to enact, ending with the new head (oldest first).
+	pub enacted: Vec<BlockHash>,
+}
+
+struct TrackedBlock<SignedSidechainBlock> {
+	block: SignedSidechainBlock,
+	/// Hash of the state after this block was applied. `None` for fork candidates that have
+	/// never been applied to our state.
+	state_hash: Option<H256>,
+	/// Trusted operations of the block that were taken from our pool, so they can be
+	/// re-submitted in case the block is retracted.
+	operations: Vec<RetainedOperation>,
+}
+
+/// Fork tree of a single shard.
+pub struct SidechainForkTree<SignedSidechainBlock> {
+	tree: ForkTree<BlockHash, BlockNumber, ()>,
+	blocks: HashMap<BlockHash, TrackedBlock<SignedSidechainBlock>>,
+	head: Option<BlockHash>,
+	finalized: Option<BlockHash>,
+}
+
+impl<SignedSidechainBlock> Default for SidechainForkTree<SignedSidechainBlock> {
+	fn default() -> Self {
+		SidechainForkTree {
+			tree: ForkTree::new(),
+			blocks: HashMap::new(),
+			head: None,
+			finalized: None,
+		}
+	}
+}
+
+impl<SignedSidechainBlock> SidechainForkTree<SignedSidechainBlock>
+where
+	SignedSidechainBlock: SignedSidechainBlockTrait,
+{
+	/// Add a block to the tree.
+	///
+	/// `state_hash` is the hash of the state after the block has been applied, or `None` if the
+	/// block is only tracked as fork candidate. Adding a known block updates its state hash.
+	///
+	/// Fork candidates are rejected if the tree is full or if they are too deep below the head
+	/// to ever be enacted.
+	pub fn import(&mut self, block: SignedSidechainBlock, state_hash: Option<H256>) -> Result<()> {
+		let hash = block.hash();
+		if let Some(tracked) = self.blocks.get_mut(&hash) {
+			if state_hash.is_some() {
+				tracked.state_hash = state_hash;
+			}
+			return Ok(())
+		}
+
+		let number = block.block().header().block_number();
+		if state_hash.is_none() {
+			if self.blocks.len() >= MAX_FORK_TREE_BLOCKS {
+				return Err(Error::ForkTree(format!(
+					"Fork tree is full, rejecting fork candidate {:?}",
+					hash
+				)))
+			}
+			let too_deep = self
+				.head
+				.and_then(|head| self.blocks.get(&head))
+				.map(|tracked| tracked.block.block().header().block_number())
+				.map_or(false, |head_number| number.saturating_add(MAX_REORG_DEPTH) < head_number);
+			if too_deep {
+				return Err(Error::ForkTree(format!(
+					"Fork candidate {:?} (number {}) is too far behind the head",
+					hash, number
+				)))
+			}
+		}
+
+		self.blocks
+			.insert(hash, TrackedBlock { block, state_hash, operations: Vec::new() });
+
+		let blocks = &self.blocks;
+		if let Err(e) = self
+			.tree
+			.import(hash, number, (), &|base, head| is_descendent_of(blocks, base, head))
+		{
+			self.blocks.remove(&hash);
+			return Err(Error::ForkTree(format!("Failed to import block {:?}: {:?}", hash, e)))
+		}
+		Ok(())
+	}
+
+	pub fn contains(&self, hash: &BlockHash) -> bool {
+		self.blocks.contains_key(hash)
+	}
+
+	pub fn block(&self, hash: &BlockHash) -> Option<&SignedSidechainBlock> {
+		self.blocks.get(hash).map(|tracked| &tracked.block)
+	}
+
+	pub fn state_hash(&self, hash: &BlockHash) -> Option<H256> {
+		self.blocks.get(hash).and_then(|tracked| tracked.state_hash)
+	}
+
+	/// Keep the trusted operations a block executed, to re-submit them if it is retracted.
+	pub fn retain_operations(&mut self, hash: &BlockHash, operations: Vec<RetainedOperation>) {
+		if let Some(tracked) = self.blocks.get_mut(hash) {
+			tracked.operations = operations;
+		}
+	}
+
+	pub fn operations(&self, hash: &BlockHash) -> &[RetainedOperation] {
+		self.blocks
+			.get(hash)
+			.map(|tracked| tracked.operations.as_slice())
+			.unwrap_or_default()
+	}
+
+	/// The last finalized block.
+	pub fn finalized(&self) -> Option<BlockHash> {
+		self.finalized
+	}
+
+	/// The block our state currently builds on.
+	pub fn head(&self) -> Option<BlockHash> {
+		self.head
+	}
+
+	pub fn set_head(&mut self, hash: BlockHash) {
+		self.head = Some(hash);
+	}
+
+	/// The block the fork-choice rule picks as canonical head: highest block number first,
+	/// lowest block hash on equal numbers.
+	pub fn best_block(&self) -> Option<(BlockHash, BlockNumber)> {
+		self.tree
+			.iter()
+			.map(|(hash, number, _)| (*hash, *number))
+			.max_by_key(|(hash, number)| (*number, Reverse(*hash)))
+	}
+
+	/// Route from block `from` to block `to`. Both blocks and their common ancestor must be known.
+	pub fn route(&self, from: &BlockHash, to: &BlockHash) -> Result<TreeRoute> {
+		let (mut from_hash, mut to_hash) = (*from, *to);
+		let mut from_header = self.header(&from_hash)?;
+		let mut to_header = self.header(&to_hash)?;
+		let mut retracted = Vec::new();
+		let mut enacted = Vec::new();
+
+		while from_header.block_number() > to_header.block_number() {
+			retracted.push(from_hash);
+			from_hash = from_header.parent_hash();
+			from_header = self.header(&from_hash)?;
+		}
+
+		while to_header.block_number() > from_header.block_number() {
+			enacted.push(to_hash);
+			to_hash = to_header.parent_hash();
+			to_header = self.header(&to_hash)?;
+		}
+
+		while from_hash != to_hash {
+			retracted.push(from_hash);
+			enacted.push(to_hash);
+			from_hash = from_header.parent_hash();
+			to_hash = to_header.parent_hash();
+			from_header = self.header(&from_hash)?;
+			to_header = self.header(&to_hash)?;
+		}
+
+		enacted.reverse();
+		Ok(TreeRoute { common_ancestor: from_hash, retracted, enacted })
+	}
+
+	/// Remove a block and all its descendants from the tree.
+	pub fn remove_branch(&mut self, hash: &BlockHash) -> Vec<BlockHash> {
+		let removed: Vec<BlockHash> = self
+			.tree
+			.drain_filter(|node_hash, _, _| {
+				if node_hash == hash {
+					FilterAction::Remove
+				} else {
+					FilterAction::KeepNode
+				}
+			})
+			.map(|(removed_hash, _, _)| removed_hash)
+			.collect();
+
+		for removed_hash in removed.iter() {
+			self.blocks.remove(removed_hash);
+		}
+		removed
+	}
+
+	/// Finalize a block, pruning all branches that do not contain it.
+	///
+	/// The finalized block is kept as anchor for blocks building on it. Returns the hashes of
+	/// the pruned (non-canonical) blocks.
+	pub fn finalize(&mut self, hash: &BlockHash, number: BlockNumber) -> Result<Vec<BlockHash>> {
+		let blocks = &self.blocks;
+		self.tree
+			.finalize_with_ancestors(hash, number, &|base, head| {
+				is_descendent_of(blocks, base, head)
+			})
+			.map_err(|e| {
+				Error::ForkTree(format!("Failed to finalize block {:?}: {:?}", hash, e))
+			})?;
+
+		let mut finalized_chain = HashSet::new();
+		let mut current = *hash;
+		while let Some(tracked) = self.blocks.get(&current) {
+			finalized_chain.insert(current);
+			current = tracked.block.block().header().parent_hash();
+		}
+
+		let in_tree: HashSet<BlockHash> = self.tree.iter().map(|(h, _, _)| *h).collect();
+		let mut pruned = Vec::new();
+		self.blocks.retain(|block_hash, _| {
+			let keep = block_hash == hash || in_tree.contains(block_hash);
+			if !keep && !finalized_chain.contains(block_hash) {
+				pruned.push(*block_hash);
+			}
+			keep
+		});
+
+		if let Some(head) = self.head {
+			if !self.blocks.contains_key(&head) {
+				warn!("Head {:?} was pruned upon finalizing block {:?}", head, hash);
+				self.head = None;
+			}
+		}
+		self.finalized = Some(*hash);
+		Ok(pruned)
+	}
+
+	/// Prune the ancestors of the head that are more than [`MAX_REORG_DEPTH`] blocks deep.
+	///
+	/// This bounds the tree in case the parentchain does not finalize sidechain blocks, at the
+	/// cost of no longer being able to reorg beyond that depth.
+	pub fn prune_deep_ancestors(&mut self) -> Result<Vec<BlockHash>> {
+		let mut current = match self.head {
+			Some(head) => head,
+			None => return Ok(Vec::new()),
+		};
+		for _ in 0..MAX_REORG_DEPTH {
+			current = match self.blocks.get(&current) {
+				Some(tracked) => tracked.block.block().header().parent_hash(),
+				None => return Ok(Vec::new()),
+			};
+		}
+
+		let number = match self.blocks.get(&current) {
+			Some(tracked) if self.finalized != Some(current) =>
+				tracked.block.block().header().block_number(),
+			_ => return Ok(Vec::new()),
+		};
+		warn!(
+			"Sidechain block {} ({:?}) is not finalized on the parentchain yet, but is {} blocks deep, pruning its competitors",
+			number, current, MAX_REORG_DEPTH
+		);
+		self.finalize(&current, number)
+	}
+
+	fn header(&self, hash: &BlockHash) -> Result<&HeaderFor<SignedSidechainBlock>> {
+		self.blocks
+			.get(hash)
+			.map(|tracked| tracked.block.block().header())
+			.ok_or_else(|| Error::ForkTree(format!("Unknown sidechain block {:?}", hash)))
+	}
+}
+
+/// `is_descendent_of` for the fork tree: `true` if `head` is a descendant of `base`.
+fn is_descendent_of<SignedSidechainBlock: SignedSidechainBlockTrait>(
+	blocks: &HashMap<BlockHash, TrackedBlock<SignedSidechainBlock>>,
+	base: &BlockHash,
+	head: &BlockHash,
+) -> Result<bool> {
+	let base_number = match blocks.get(base) {
+		Some(tracked) => tracked.block.block().header().block_number(),
+		None => return Ok(false),
+	};
+
+	let mut current = *head;
+	while let Some(tracked) = blocks.get(&current) {
+		let header = tracked.block.block().header();
+		if header.block_number() <= base_number {
+			return Ok(false)
+		}
+		if header.parent_hash() == *base {
+			return Ok(true)
+		}
+		current = header.parent_hash();
+	}
+	Ok(false)
+}
+
+/// Fork trees of all shards.
+pub struct SidechainForkTrees<SignedSidechainBlock: SignedSidechainBlockTrait> {
+	trees: RwLock<
+		HashMap<ShardIdentifierFor<SignedSidechainBlock>, SidechainForkTree<SignedSidechainBlock>>,
+	>,
+}
+
+impl<SignedSidechainBlock: SignedSidechainBlockTrait> Default
+	for SidechainForkTrees<SignedSidechainBlock>
+{
+	fn default() -> Self {
+		SidechainForkTrees { trees: RwLock::new(HashMap::new()) }
+	}
+}
+
+impl<SignedSidechainBlock> SidechainForkTrees<SignedSidechainBlock>
+where
+	SignedSidechainBlock: SignedSidechainBlockTrait,
+{
+	/// Execute a function on the fork tree of a shard.
+	pub fn with_fork_tree<F, R>(
+		&self,
+		shard: &ShardIdentifierFor<SignedSidechainBlock>,
+		f: F,
+	) -> Result<R>
+	where
+		F: FnOnce(&mut SidechainForkTree<SignedSidechainBlock>) -> R,
+	{
+		let mut trees = self.trees.write().map_err(|_| Error::LockPoisoning)?;
+		Ok(f(trees.entry(*shard).or_default()))
+	}
+
+	/// Track a block that became the head of its shard, either imported or authored by us.
+	pub fn on_canonical_block(&self, block: SignedSidechainBlock, state_hash: H256) -> Result<()> {
+		let shard = block.block().header().shard_id();
+		let hash = block.hash();
+		self.with_fork_tree(&shard, |tree| {
+			tree.import(block, Some(state_hash))?;
+			tree.set_head(hash);
+			tree.prune_deep_ancestors().map(|_| ())
+		})?
+	}
+
+	/// Finalize a block whose confirmation is final on the parentchain, pruning all competing
+	/// branches. Unknown and already finalized blocks are ignored.
+	pub fn finalize(
+		&self,
+		shard: &ShardIdentifierFor<SignedSidechainBlock>,
+		hash: &BlockHash,
+		number: BlockNumber,
+	) -> Result<()> {
+		let pruned = self.with_fork_tree(shard, |tree| {
+			if !tree.contains(hash) || tree.finalized() == Some(*hash) {
+				return Ok(Vec::new())
+			}
+			tree.finalize(hash, number)
+		})??;
+
+		if !pruned.is_empty() {
+			info!(
+				"Pruned {} non-canonical sidechain block(s) upon finalizing block {}",
+				pruned.len(),
+				number
+			);
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use its_primitives::types::{header::SidechainHeader, SignedBlock};
+	use its_test::{
+		sidechain_block_builder::{SidechainBlockBuilder, SidechainBlockBuilderTrait},
+		sidechain_header_builder::SidechainHeaderBuilder,
+	};
+
+	fn block(number: BlockNumber, parent: &SignedBlock) -> SignedBlock {
+		block_with_parent_hash(number, parent.hash())
+	}
+
+	fn block_with_parent_hash(number: BlockNumber, parent_hash: BlockHash) -> SignedBlock {
+		let header: SidechainHeader = SidechainHeaderBuilder::random()
+			.with_block_number(number)
+			.with_parent_hash(parent_hash)
+			.with_shard(H256::default())
+			.build();
+		SidechainBlockBuilder::random().with_header(header).build_signed()
+	}
+
+	/// Genesis with two competing blocks 1a and 1b, 1a extended by 2a.
+	fn forked_tree() -> (SidechainForkTree<SignedBlock>, [SignedBlock; 4]) {
+		let genesis = block_with_parent_hash(1, BlockHash::default());
+		let block_a = block(2, &genesis);
+		let block_b = block(2, &genesis);
+		let block_a2 = block(3, &block_a);
+
+		let mut tree = SidechainForkTree::default();
+		tree.import(genesis.clone(), Some(H256::random())).unwrap();
+		tree.import(block_a.clone(), Some(H256::random())).unwrap();
+		tree.import(block_b.clone(), None).unwrap();
+		tree.import(block_a2.clone(), Some(H256::random())).unwrap();
+		tree.set_head(block_a2.hash());
+
+		(tree, [genesis, block_a, block_b, block_a2])
+	}
+
+	#[test]
+	fn best_block_is_longest_chain() {
+		let (tree, [_, _, _, block_a2]) = forked_tree();
+		assert_eq!(tree.best_block(), Some((block_a2.hash(), 3)));
+	}
+
+	#[test]
+	fn best_block_ties_are_broken_by_lowest_hash() {
+		let genesis = block_with_parent_hash(1, BlockHash::default());
+		let block_a = block(2, &genesis);
+		let block_b = block(2, &genesis);
+
+		let mut tree = SidechainForkTree::default();
+		tree.import(genesis, None).unwrap();
+		tree.import(block_a.clone(), None).unwrap();
+		tree.import(block_b.clone(), None).unwrap();
+
+		let expected = core::cmp::min(block_a.hash(), block_b.hash());
+		assert_eq!(tree.best_block(), Some((expected, 2)));
+	}
+
+	#[test]
+	fn route_between_forks_retracts_and_enacts() {
+		let (tree, [genesis, block_a, block_b, block_a2]) = forked_tree();
+
+		let route = tree.route(&block_a2.hash(), &block_b.hash()).unwrap();
+
+		assert_eq!(route.common_ancestor, genesis.hash());
+		assert_eq!(route.retracted, vec![block_a2.hash(), block_a.hash()]);
+		assert_eq!(route.enacted, vec![block_b.hash()]);
+	}
+
+	#[test]
+	fn finalize_prunes_competing_branches() {
+		let (mut tree, [genesis, block_a, block_b, block_a2]) = forked_tree();
+
+		let pruned = tree.finalize(&block_a.hash(), 2).unwrap();
+
+		assert_eq!(pruned, vec![block_b.hash()]);
+		assert!(!tree.contains(&block_b.hash()));
+		assert!(!tree.contains(&genesis.hash()));
+		assert!(tree.contains(&block_a.hash()));
+		assert_eq!(tree.head(), Some(block_a2.hash()));
+		assert_eq!(tree.best_block(), Some((block_a2.hash(), 3)));
+	}
+
+	#[test]
+	fn blocks_can_build_on_finalized_block() {
+		let (mut tree, [_, block_a, _, _]) = forked_tree();
+		tree.finalize(&block_a.hash(), 2).unwrap();
+
+		let competitor = block(3, &block_a);
+		tree.import(competitor.clone(), None).unwrap();
+
+		let route = tree.route(&tree.head().unwrap(), &competitor.hash()).unwrap();
+		assert_eq!(route.common_ancestor, block_a.hash());
+	}
+
+	#[test]
+	fn remove_branch_removes_descendants() {
+		let (mut tree, [genesis, block_a, _, block_a2]) = forked_tree();
+
+		let removed = tree.remove_branch(&block_a.hash());
+
+		assert_eq!(removed.len(), 2);
+		assert!(!tree.contains(&block_a.hash()));
+		assert!(!tree.contains(&block_a2.hash()));
+		assert!(tree.contains(&genesis.hash()));
+	}
+
+	#[test]
+	fn fork_trees_track_canonical_head_per_shard() {
+		let fork_trees = SidechainForkTrees::<SignedBlock>::default();
+		let genesis = block_with_parent_hash(1, BlockHash::default());
+		let state_hash = H256::random();
+
+		fork_trees.on_canonical_block(genesis.clone(), state_hash).unwrap();
+
+		fork_trees
+			.with_fork_tree(&H256::default(), |tree| {
+				assert_eq!(tree.head(), Some(genesis.hash()));
+				assert_eq!(tree.state_hash(&genesis.hash()), Some(state_hash));
+			})
+			.unwrap();
+	}
+
+	#[test]
+	fn deep_ancestors_of_the_head_are_pruned() {
+		let fork_trees = SidechainForkTrees::<SignedBlock>::default();
+		let genesis = block_with_parent_hash(1, BlockHash::default());
+		let competitor = block(2, &genesis);
+		fork_trees.on_canonical_block(genesis.clone(), H256::random()).unwrap();
+		fork_trees
+			.with_fork_tree(&H256::default(), |tree| tree.import(competitor.clone(), None))
+			.unwrap()
+			.unwrap();
+
+		let mut parent = genesis.clone();
+		for number in 2..=MAX_REORG_DEPTH + 2 {
+			let next = block(number, &parent);
+			fork_trees.on_canonical_block(next.clone(), H256::random()).unwrap();
+			parent = next;
+		}
+
+		fork_trees
+			.with_fork_tree(&H256::default(), |tree| {
+				assert!(!tree.contains(&genesis.hash()));
+				assert!(!tree.contains(&competitor.hash()));
+				assert!(tree.finalized().is_some());
+				assert_eq!(tree.head(), Some(parent.hash()));
+			})
+			.unwrap();
+	}
+
+	#[test]
+	fn fork_candidates_are_rejected_when_the_tree_is_full() {
+		let genesis = block_with_parent_hash(1, BlockHash::default());
+		let mut tree = SidechainForkTree::default();
+		tree.import(genesis.clone(), Some(H256::random())).unwrap();
+		tree.set_head(genesis.hash());
+
+		for _ in 1..MAX_FORK_TREE_BLOCKS {
+			tree.import(block(2, &genesis), None).unwrap();
+		}
+
+		assert!(tree.import(block(2, &genesis), None).is_err());
+		// Canonical blocks are still tracked.
+		assert!(tree.import(block(2, &genesis), Some(H256::random())).is_ok());
+	}
+
+	#[test]
+	fn finalizing_unknown_or_finalized_block_is_ignored() {
+		let fork_trees = SidechainForkTrees::<SignedBlock>::default();
+		let genesis = block_with_parent_hash(1, BlockHash::default());
+		let block_a = block(2, &genesis);
+		fork_trees.on_canonical_block(genesis.clone(), H256::random()).unwrap();
+		fork_trees.on_canonical_block(block_a.clone(), H256::random()).unwrap();
+
+		fork_trees.finalize(&H256::default(), &H256::random(), 5).unwrap();
+		fork_trees.finalize(&H256::default(), &block_a.hash(), 2).unwrap();
+		fork_trees.finalize(&H256::default(), &block_a.hash(), 2).unwrap();
+
+		fork_trees
+			.with_fork_tree(&H256::default(), |tree| {
+				assert_eq!(tree.finalized(), Some(block_a.hash()));
+				assert!(!tree.contains(&genesis.hash()));
+			})
+			.unwrap();
+	}
+}
@@ -32,10 +32,12 @@ use its_primitives::traits::{ShardIdentifierFor, SignedBlock as SignedSidechainB
 use sp_runtime::traits::Block as ParentchainBlockTrait;
 use std::{time::Duration, vec::Vec};
 
+mod block_finalization;
 mod block_import;
 mod block_import_confirmation_handler;
 mod block_import_queue_worker;
 mod error;
+mod fork_choice;
 mod header_db;
 mod peer_block_sync;
 
@@ -46,10 +48,12 @@ mod is_descendant_of_builder;
 #[cfg(test)]
 mod test;
 
+pub use block_finalization::*;
 pub use block_import::*;
 pub use block_import_confirmation_handler::*;
 pub use block_import_queue_worker::*;
 pub use error::*;
+pub use fork_choice::*;
 pub use peer_block_sync::*;
 
 pub trait Verifier<ParentchainBlock, SignedSidechainBlock>: Send + Sync
@@ -198,6 +198,11 @@ where
 						to_import_block_number, last_known_block_number);
 					Ok(current_parentchain_header.clone())
 				},
+				Error::NonCanonicalBlock(block_number, block_hash) => {
+					info!("Sidechain block from queue (number: {}, hash: {:?}) is tracked as fork candidate, not importing it for now.",
+						block_number, block_hash);
+					Ok(current_parentchain_header.clone())
+				},
 				_ => Err(e),
 			},
 			Ok(latest_parentchain_header) => {
@@ -206,7 +211,17 @@ where
 
 				// We confirm the successful block import. Only in this case, not when we're in
 				// on-boarding and importing blocks that were fetched from a peer.
-				if let Err(e) = self.import_confirmation_handler.confirm_import(sidechain_block.block().header(), &shard_identifier) {
+				let header = sidechain_block.block().header();
+				let confirmed = self.importer.state_hash(&shard_identifier).and_then(|state_root| {
+					self.import_confirmation_handler.confirm_import(
+						header,
+						&state_root,
+						&shard_identifier,
+					)
+				});
+				// The block is only finalized once the confirmation is part of a finalized
+				// parentchain block, see `finalize_confirmed_blocks`.
+				if let Err(e) = confirmed {
 					error!("Failed to confirm sidechain block import: {:?}", e);
 				}
 
@@ -310,6 +325,30 @@ mod tests {
 		assert_eq!(1, sidechain_ocall_api.number_of_fetch_calls());
 	}
 
+	#[test]
+	fn non_canonical_block_is_ignored_without_peer_fetching() {
+		let block_importer_mock = Arc::new(
+			BlockImportMock::<ParentchainBlock, _>::default()
+				.with_import_result_once(Err(Error::NonCanonicalBlock(1, H256::random()))),
+		);
+
+		let sidechain_ocall_api =
+			Arc::new(SidechainOCallApiMock::<SignedSidechainBlock>::default());
+
+		let peer_syncer =
+			create_peer_syncer(block_importer_mock.clone(), sidechain_ocall_api.clone());
+
+		let parentchain_header = ParentchainHeaderBuilder::default().build();
+		let signed_sidechain_block = SidechainBlockBuilder::default().build_signed();
+
+		let latest_header =
+			peer_syncer.sync_block(signed_sidechain_block, &parentchain_header).unwrap();
+
+		assert_eq!(latest_header, parentchain_header);
+		assert_eq!(1, block_importer_mock.get_imported_blocks().len());
+		assert_eq!(0, sidechain_ocall_api.number_of_fetch_calls());
+	}
+
 	fn create_peer_syncer(
 		block_importer: Arc<TestBlockImport>,
 		ocall_api: Arc<TestOCallApi>,
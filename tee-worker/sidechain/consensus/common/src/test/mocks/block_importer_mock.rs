@@ -15,18 +15,27 @@
 
 */
 
-use crate::{test::mocks::verifier_mock::VerifierMock, BlockImport, Error, Result};
+use crate::{
+	test::mocks::verifier_mock::VerifierMock, BlockImport, Error, Result, RetainedOperation,
+	SidechainForkTrees,
+};
 use core::marker::PhantomData;
 use itp_sgx_crypto::aes::Aes;
 use itp_sgx_externalities::SgxExternalities;
 use itp_test::mock::onchain_mock::OnchainMock;
 use itp_types::H256;
-use its_primitives::traits::{ShardIdentifierFor, SignedBlock as SignedSidechainBlockTrait};
+use its_primitives::traits::{
+	Block as SidechainBlockTrait, Header as HeaderTrait, ShardIdentifierFor,
+	SignedBlock as SignedSidechainBlockTrait,
+};
 use sp_core::Pair;
 use sp_runtime::traits::Block as ParentchainBlockTrait;
 use std::{collections::VecDeque, sync::RwLock};
 
 /// Block importer mock.
+///
+/// Imported blocks become the head of the fork tree, their hash serves as state hash. Queued
+/// import results take precedence and leave the fork tree untouched.
 pub struct BlockImportMock<ParentchainBlock, SignedSidechainBlock>
 where
 	ParentchainBlock: ParentchainBlockTrait<Hash = H256>,
@@ -35,6 +44,11 @@ where
 {
 	import_result: RwLock<VecDeque<Result<ParentchainBlock::Header>>>,
 	imported_blocks: RwLock<Vec<SignedSidechainBlock>>,
+	fork_trees: SidechainForkTrees<SignedSidechainBlock>,
+	state_hash: RwLock<H256>,
+	check_ancestry: bool,
+	failing_blocks: RwLock<Vec<H256>>,
+	resubmitted_operations: RwLock<Vec<RetainedOperation>>,
 	_phantom: PhantomData<(ParentchainBlock, SignedSidechainBlock)>,
 }
 
@@ -62,9 +76,29 @@ where
 		self
 	}
 
+	/// Only import blocks that build on the head of the fork tree.
+	pub fn with_ancestry_check(mut self) -> Self {
+		self.check_ancestry = true;
+		self
+	}
+
+	/// Let the import of the block with the given hash fail.
+	pub fn with_failing_block(self, hash: H256) -> Self {
+		self.failing_blocks.write().unwrap().push(hash);
+		self
+	}
+
 	pub fn get_imported_blocks(&self) -> Vec<SignedSidechainBlock> {
 		(*self.imported_blocks.read().unwrap()).clone()
 	}
+
+	pub fn get_state_hash(&self) -> H256 {
+		*self.state_hash.read().unwrap()
+	}
+
+	pub fn get_resubmitted_operations(&self) -> Vec<RetainedOperation> {
+		(*self.resubmitted_operations.read().unwrap()).clone()
+	}
 }
 
 impl<ParentchainBlock, SignedSidechainBlock> Default
@@ -78,6 +112,11 @@ where
 		BlockImportMock {
 			import_result: RwLock::default(),
 			imported_blocks: RwLock::default(),
+			fork_trees: SidechainForkTrees::default(),
+			state_hash: RwLock::default(),
+			check_ancestry: false,
+			failing_blocks: RwLock::default(),
+			resubmitted_operations: RwLock::default(),
 			_phantom: Default::default(),
 		}
 	}
@@ -153,15 +192,62 @@ where
 		todo!()
 	}
 
-	fn import_block(
+	fn fork_trees(&self) -> &SidechainForkTrees<SignedSidechainBlock> {
+		&self.fork_trees
+	}
+
+	fn state_hash(&self, _shard: &ShardIdentifierFor<SignedSidechainBlock>) -> Result<H256> {
+		Ok(self.get_state_hash())
+	}
+
+	fn revert_state(
+		&self,
+		_shard: &ShardIdentifierFor<SignedSidechainBlock>,
+		state_hash: &H256,
+	) -> Result<()> {
+		*self.state_hash.write().unwrap() = *state_hash;
+		Ok(())
+	}
+
+	fn resubmit_operations(
+		&self,
+		_shard: &ShardIdentifierFor<SignedSidechainBlock>,
+		mut operations: Vec<RetainedOperation>,
+	) -> Result<()> {
+		self.resubmitted_operations.write().unwrap().append(&mut operations);
+		Ok(())
+	}
+
+	fn import_on_head(
 		&self,
 		signed_sidechain_block: SignedSidechainBlock,
 		parentchain_header: &ParentchainBlock::Header,
 	) -> Result<ParentchainBlock::Header> {
-		let mut imported_blocks_lock = self.imported_blocks.write().unwrap();
-		imported_blocks_lock.push(signed_sidechain_block);
+		if let Some(result) = self.import_result.write().unwrap().pop_front() {
+			self.imported_blocks.write().unwrap().push(signed_sidechain_block);
+			return result
+		}
 
-		let mut imported_results_lock = self.import_result.write().unwrap();
-		imported_results_lock.pop_front().unwrap_or(Ok(parentchain_header.clone()))
+		let header = signed_sidechain_block.block().header();
+		let hash = signed_sidechain_block.hash();
+		let shard = header.shard_id();
+		let head = self.fork_trees.with_fork_tree(&shard, |tree| tree.head())?;
+		if let (true, Some(head)) = (self.check_ancestry, head) {
+			if head != header.parent_hash() {
+				return Err(Error::BlockAncestryMismatch(
+					header.block_number(),
+					head,
+					"Parent is not the head".into(),
+				))
+			}
+		}
+		if self.failing_blocks.read().unwrap().contains(&hash) {
+			return Err(Error::InvalidAuthority("Block is set to fail".into()))
+		}
+
+		*self.state_hash.write().unwrap() = hash;
+		self.fork_trees.on_canonical_block(signed_sidechain_block.clone(), hash)?;
+		self.imported_blocks.write().unwrap().push(signed_sidechain_block);
+		Ok(parentchain_header.clone())
 	}
 }
@@ -16,14 +16,19 @@
 */
 
 use crate::{error::Result, ConfirmBlockImport};
-use itp_types::ShardIdentifier;
+use itp_types::{ShardIdentifier, H256};
 use its_primitives::types::header::SidechainHeader;
 
 /// Mock implementation of the `ConfirmBlockImport` trait.
 pub struct ConfirmBlockImportMock;
 
 impl ConfirmBlockImport<SidechainHeader> for ConfirmBlockImportMock {
-	fn confirm_import(&self, _header: &SidechainHeader, _shard: &ShardIdentifier) -> Result<()> {
+	fn confirm_import(
+		&self,
+		_header: &SidechainHeader,
+		_state_root: &H256,
+		_shard: &ShardIdentifier,
+	) -> Result<()> {
 		Ok(())
 	}
 }
@@ -257,7 +257,14 @@ impl<SignedBlock: SignedBlockT> SidechainStorage<SignedBlock> {
 	) -> Result<()> {
 		let shard = &signed_block.block().header().shard_id();
 		if self.shards.contains(shard) {
-			if !self.verify_block_ancestry(signed_block.block()) {
+			if self.is_fork_of_stored_chain(signed_block.block())? {
+				// The enclave switched to a competing branch, replace the blocks it retracted.
+				self.retract_blocks_from(
+					batch,
+					shard,
+					signed_block.block().header().block_number(),
+				)?;
+			} else if !self.verify_block_ancestry(signed_block.block()) {
 				// Do not include block if its not a direct ancestor of the last block in line.
 				return Err(Error::HeaderAncestryMismatch)
 			}
@@ -289,6 +296,50 @@ impl<SignedBlock: SignedBlockT> SidechainStorage<SignedBlock> {
 		true
 	}
 
+	/// Checks if a block competes with a stored block, i.e. it builds on a stored block that is
+	/// not the last block of the shard.
+	fn is_fork_of_stored_chain(
+		&self,
+		block: &<SignedBlock as SignedBlockT>::Block,
+	) -> Result<bool> {
+		let shard = &block.header().shard_id();
+		let block_number = block.header().block_number();
+		let last_block = match self.last_block_of_shard(shard) {
+			Some(last_block) => *last_block,
+			None => return Ok(false),
+		};
+
+		if block_number == 0 || block_number > last_block.number {
+			return Ok(false)
+		}
+		if self.get_block_hash(shard, block_number)? == Some(block.hash()) {
+			// Already stored, not a fork.
+			return Ok(false)
+		}
+		Ok(self.get_block_hash(shard, block_number - 1)? == Some(block.header().parent_hash()))
+	}
+
+	/// Adds delete commands for all blocks of a shard, starting from `block_number` up to the
+	/// last block.
+	fn retract_blocks_from(
+		&mut self,
+		batch: &mut WriteBatch,
+		shard: &ShardIdentifierFor<SignedBlock>,
+		block_number: BlockNumber,
+	) -> Result<()> {
+		let last_block = self.get_last_block_of_shard(shard)?;
+		for number in block_number..=last_block.number {
+			if let Some(block_hash) = self.get_block_hash(shard, number)? {
+				self.delete_block(batch, &block_hash, &number, shard);
+			}
+		}
+		info!(
+			"[Sidechain DB] Retracted blocks {}..={} of shard {:?} in favour of a competing branch",
+			block_number, last_block.number, *shard
+		);
+		Ok(())
+	}
+
 	/// Implementations of helper functions, not meant for pub use
 	/// gets the previous block of given shard and block number, if there is one.
 	fn get_previous_block(
@@ -389,7 +440,8 @@ impl<SignedBlock: SignedBlockT> SidechainStorage<SignedBlock> {
 mod test {
 	use super::*;
 	use crate::test_utils::{
-		create_signed_block_with_shard as create_signed_block, create_temp_dir, get_storage,
+		create_signed_block_with_parenthash, create_signed_block_with_shard as create_signed_block,
+		create_temp_dir, default_shard, get_storage,
 	};
 	use itp_types::ShardIdentifier;
 	use its_primitives::{traits::SignedBlock as SignedBlockT, types::SignedBlock};
@@ -927,6 +979,66 @@ mod test {
 		}
 	}
 
+	#[test]
+	fn competing_block_replaces_retracted_blocks() {
+		let temp_dir = create_temp_dir();
+		let shard = default_shard();
+		let block_one = create_signed_block_with_parenthash(1, H256::default());
+		let block_two = create_signed_block_with_parenthash(2, block_one.hash());
+		let block_three = create_signed_block_with_parenthash(3, block_two.hash());
+		let competing_block_two = create_signed_block_with_parenthash(2, block_one.hash());
+
+		{
+			let mut sidechain_db = get_storage(temp_dir.path().to_path_buf());
+			sidechain_db
+				.store_blocks(vec![block_one.clone(), block_two.clone(), block_three.clone()])
+				.unwrap();
+		}
+		{
+			let mut sidechain_db = get_storage(temp_dir.path().to_path_buf());
+			sidechain_db.store_blocks(vec![competing_block_two.clone()]).unwrap();
+		}
+
+		{
+			let updated_sidechain_db = get_storage(temp_dir.path().to_path_buf());
+			let last_block = updated_sidechain_db.last_block_of_shard(&shard).unwrap();
+			assert_eq!(last_block.number, 2);
+			assert_eq!(last_block.hash, competing_block_two.hash());
+			assert_eq!(
+				updated_sidechain_db.get_block_hash(&shard, 2).unwrap(),
+				Some(competing_block_two.hash())
+			);
+			assert!(updated_sidechain_db.get_block_hash(&shard, 3).unwrap().is_none());
+			assert!(updated_sidechain_db.get_block(&block_two.hash()).unwrap().is_none());
+			assert!(updated_sidechain_db.get_block(&block_three.hash()).unwrap().is_none());
+			assert_eq!(
+				updated_sidechain_db.get_block(&block_one.hash()).unwrap().unwrap(),
+				block_one
+			);
+		}
+	}
+
+	#[test]
+	fn block_not_building_on_stored_block_does_not_get_accepted() {
+		let temp_dir = create_temp_dir();
+		let shard = default_shard();
+		let block_one = create_signed_block_with_parenthash(1, H256::default());
+		let block_two = create_signed_block_with_parenthash(2, block_one.hash());
+		let unknown_parent_block = create_signed_block_with_parenthash(2, H256::random());
+
+		{
+			let mut sidechain_db = get_storage(temp_dir.path().to_path_buf());
+			sidechain_db.store_blocks(vec![block_one, block_two.clone()]).unwrap();
+			sidechain_db.store_blocks(vec![unknown_parent_block]).unwrap();
+		}
+
+		{
+			let updated_sidechain_db = get_storage(temp_dir.path().to_path_buf());
+			let last_block = updated_sidechain_db.last_block_of_shard(&shard).unwrap();
+			assert_eq!(last_block.hash, block_two.hash());
+		}
+	}
+
 	#[test]
 	fn get_previous_block_returns_correct_block() {
 		let temp_dir = create_temp_dir();
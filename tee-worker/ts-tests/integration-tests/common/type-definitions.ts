@@ -63,6 +63,7 @@ export enum IdentityStatus {
 export type IdentityContext = {
     link_block: number;
     status: IdentityStatus;
+    contract_wallet: boolean;
 };
 
 //vc types
@@ -77,6 +78,7 @@ export enum RequestEvent {
     SetUserShieldingKeyRequested = 'SetUserShieldingKeyRequested',
     DeactivateIdentityRequested = 'DeactivateIdentityRequested',
     ActivateIdentityRequested = 'ActivateIdentityRequested',
+    RemoveIdentityRequested = 'RemoveIdentityRequested',
     VCRequested = 'VCRequested',
     ItemCompleted = 'ItemCompleted',
     BatchCompleted = 'BatchCompleted',
@@ -125,14 +127,29 @@ export const jsonSchema = {
                 name: {
                     type: 'string',
                 },
-                shard: {
+                mrenclave: {
                     type: 'string',
                 },
             },
         },
-        issuanceDate: {
+        validFrom: {
             type: 'string',
         },
+        validUntil: {
+            type: 'string',
+        },
+        credentialStatus: {
+            type: 'object',
+            properties: {
+                id: {
+                    type: 'string',
+                },
+                type: {
+                    enum: ['LitentryVCRegistry'],
+                },
+            },
+            required: ['id', 'type'],
+        },
         credentialSubject: {
             type: 'object',
             properties: {
@@ -173,7 +190,10 @@ export const jsonSchema = {
                     type: 'string',
                 },
                 type: {
-                    enum: ['Ed25519Signature2020'],
+                    enum: ['DataIntegrityProof'],
+                },
+                cryptosuite: {
+                    enum: ['eddsa-jcs-2022'],
                 },
                 proofPurpose: {
                     enum: ['assertionMethod'],
@@ -187,5 +207,5 @@ export const jsonSchema = {
             },
         },
     },
-    required: ['id', 'type', 'credentialSubject', 'issuer', 'issuanceDate', 'proof'],
+    required: ['id', 'type', 'credentialSubject', 'issuer', 'validFrom', 'validUntil', 'credentialStatus', 'proof'],
 };
@@ -4,6 +4,8 @@ import { hexToU8a, u8aToHex } from '@polkadot/util';
 import Ajv from 'ajv';
 import { assert, expect } from 'chai';
 import * as ed from '@noble/ed25519';
+import * as base58 from 'micro-base58';
+import { createHash } from 'crypto';
 import { buildIdentityHelper, parseIdGraph, parseIdentity } from './identity-helper';
 import type { LitentryPrimitivesIdentity, PalletIdentityManagementTeeError } from 'sidechain-api';
 import type { EnclaveResult, IntegrationTestContext } from '../type-definitions';
@@ -225,13 +227,40 @@ export async function checkErrorDetail(events: Event[], expectedDetail: string)
     });
 }
 
+// JSON Canonicalization Scheme (RFC 8785), sufficient for the integer-only values of a VC
+export function canonicalize(value: any): string {
+    if (value === null || typeof value !== 'object') {
+        return JSON.stringify(value);
+    }
+    if (Array.isArray(value)) {
+        return `[${value.map(canonicalize).join(',')}]`;
+    }
+    // the default sort compares UTF-16 code units, as required by JCS
+    const keys = Object.keys(value)
+        .filter((key) => value[key] !== undefined)
+        .sort();
+    return `{${keys.map((key) => `${JSON.stringify(key)}:${canonicalize(value[key])}`).join(',')}}`;
+}
+
+// hash data of the eddsa-jcs-2022 cryptosuite
+export function proofHashData(data: any, proofJson: any): Buffer {
+    const proofConfig = { ...proofJson, '@context': data['@context'] };
+    delete proofConfig.proofValue;
+    const sha256 = (value: any) => createHash('sha256').update(canonicalize(value)).digest();
+    return Buffer.concat([sha256(proofConfig), sha256(data)]);
+}
+
 export async function verifySignature(data: any, index: HexString, proofJson: any, api: ApiPromise) {
     const count = await api.query.teerex.enclaveCount();
     const res = (await api.query.teerex.enclaveRegistry(count)).toHuman() as EnclaveResult;
     // Check vc index
     expect(index).to.be.eq(data.id);
-    const signature = Buffer.from(hexToU8a(`0x${proofJson.proofValue}`));
-    const message = Buffer.from(data.issuer.mrenclave);
+    expect(data.credentialStatus.id).to.be.eq(data.id);
+    // the issuer DID is the account of the vc signing key
+    expect(data.issuer.id).to.be.eq(`did:litentry:substrate:${res.vcPubkey}`);
+    expect(proofJson.verificationMethod).to.be.eq(`${data.issuer.id}#vc-pubkey`);
+    const signature = Buffer.from(base58.decode(proofJson.proofValue.slice(1)));
+    const message = proofHashData(data, proofJson);
     const vcPubkey = Buffer.from(hexToU8a(`${res.vcPubkey}`));
 
     const isValid = await ed.verify(signature, message, vcPubkey);
@@ -258,6 +287,8 @@ export async function checkJson(vc: any, proofJson: any): Promise<boolean> {
     const validate = ajv.compile(jsonSchema);
     const isValid = validate(vc);
     expect(isValid).to.be.true;
-    expect(vc.type[0] === 'VerifiableCredential' && proofJson.type === 'Ed25519Signature2020').to.be.true;
+    expect(vc.type[0] === 'VerifiableCredential' && proofJson.type === 'DataIntegrityProof').to.be.true;
+    expect(proofJson.cryptosuite).to.be.eq('eddsa-jcs-2022');
+    expect(new Date(vc.validFrom) < new Date(vc.validUntil)).to.be.true;
     return true;
 }
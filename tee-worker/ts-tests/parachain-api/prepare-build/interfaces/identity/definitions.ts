@@ -62,6 +62,11 @@ export default {
                     "(LitentryIdentity, LitentryIdentity, LitentryIdentity, Vec<Web3Network>, H256)",
                 set_user_shielding_key_with_networks:
                     "(LitentryIdentity, LitentryIdentity, UserShieldingKeyType, Vec<Web3Network>, H256)",
+                remove_identity: "(LitentryIdentity, LitentryIdentity, LitentryIdentity, H256)",
+                export_id_graph: "(LitentryIdentity, LitentryIdentity, H256)",
+                import_id_graph: "(LitentryIdentity, LitentryIdentity, AesOutput, H256)",
+                request_pairwise_vc: "(LitentryIdentity, LitentryIdentity, Assertion, Bytes, H256)",
+                renew_vc: "(LitentryIdentity, LitentryIdentity, Assertion, H256, H256)",
             },
         },
         UserShieldingKeyType: "[u8; 32]",
@@ -97,23 +102,35 @@ export default {
                 Github: "IdentityString",
                 Substrate: "Address32",
                 Evm: "Address20",
+                Bitcoin: "BitcoinAddress",
+                Solana: "Address32",
             },
         },
         Address32: "[u8;32]",
         Address20: "[u8;20]",
+        BitcoinAddress: {
+            _enum: {
+                P2pkh: "Address20",
+                P2wpkh: "Address20",
+                P2tr: "Address32",
+            },
+        },
         IdentityString: "Vec<u8>",
         Web3Network: {
-            _enum: [
-                "Polkadot",
-                "Kusama",
-                "Litentry",
-                "Litmus",
-                "LitentryRococo",
-                "Khala",
-                "SubstrateTestnet",
-                "Ethereum",
-                "Bsc",
-            ],
+            _enum: {
+                Polkadot: null,
+                Kusama: null,
+                Litentry: null,
+                Litmus: null,
+                LitentryRococo: null,
+                Khala: null,
+                SubstrateTestnet: null,
+                Ethereum: null,
+                Bsc: null,
+                Bitcoin: null,
+                Solana: null,
+                Registered: "u16",
+            },
         },
         LitentryValidationData: {
             _enum: {
@@ -125,6 +142,7 @@ export default {
             _enum: {
                 Twitter: "TwitterValidationData",
                 Discord: "DiscordValidationData",
+                Github: "GithubValidationData",
             },
         },
         TwitterValidationData: {
@@ -135,16 +153,40 @@ export default {
             message_id: "Vec<u8>",
             guild_id: "Vec<u8>",
         },
+        GithubValidationData: {
+            _enum: {
+                Gist: {
+                    gist_id: "Vec<u8>",
+                },
+                IssueComment: {
+                    owner: "Vec<u8>",
+                    repo: "Vec<u8>",
+                    comment_id: "Vec<u8>",
+                },
+            },
+        },
         Web3ValidationData: {
             _enum: {
                 Substrate: "Web3CommonValidationData",
                 Evm: "Web3CommonValidationData",
+                Eip1271: "Eip1271ValidationData",
+                Structured: "StructuredValidationData",
+                Bitcoin: "Web3CommonValidationData",
+                Solana: "Web3CommonValidationData",
             },
         },
         Web3CommonValidationData: {
             message: "Vec<u8>",
             signature: "LitentryMultiSignature",
         },
+        StructuredValidationData: {
+            message: "Vec<u8>",
+            signature: "LitentryMultiSignature",
+        },
+        Eip1271ValidationData: {
+            message: "Vec<u8>",
+            signature: "Vec<u8>",
+        },
 
         LitentryMultiSignature: {
             _enum: {
@@ -153,6 +195,9 @@ export default {
                 Ecdsa: "ecdsa::Signature",
                 Ethereum: "EthereumSignature",
                 EthereumPrettified: "EthereumSignature",
+                Eip712: "EthereumSignature",
+                Bip137: "EthereumSignature",
+                Bip322: "Vec<u8>",
             },
         },
         EthereumSignature: "([u8; 65])",
@@ -171,9 +216,35 @@ export default {
             link_block: "BlockNumber",
             web3networks: "BoundedWeb3Network",
             status: "IdentityStatus",
+            contract_wallet: "bool",
         },
         BoundedWeb3Network: "BoundedVec<Web3Network, ConstU32<128>>",
 
+        LinkProof: {
+            shard: "H256",
+            mrenclave: "[u8; 32]",
+            sidechain_nonce: "u32",
+            key_nonce: "UserShieldingKeyNonceType",
+            web3networks: "Vec<Web3Network>",
+            validation_data: "Web3ValidationData",
+        },
+        IdentityExport: {
+            identity: "LitentryIdentity",
+            web3networks: "Vec<Web3Network>",
+            status: "IdentityStatus",
+            contract_wallet: "bool",
+            proof: "Option<LinkProof>",
+        },
+        IDGraphExport: {
+            who: "LitentryIdentity",
+            identities: "Vec<IdentityExport>",
+        },
+        SignedIDGraphExport: {
+            export: "IDGraphExport",
+            signer: "AccountId",
+            signature: "Bytes",
+        },
+
         // teerex
         ShardIdentifier: "H256",
         Request: {
@@ -227,6 +298,21 @@ export default {
             identity: "AesOutput",
             req_ext_hash: "H256",
         },
+        RemoveIdentityResponse: {
+            account: "AccountId",
+            identity: "AesOutput",
+            req_ext_hash: "H256",
+        },
+        ExportIDGraphResponse: {
+            account: "AccountId",
+            export: "AesOutput",
+            req_ext_hash: "H256",
+        },
+        ImportIDGraphResponse: {
+            account: "AccountId",
+            identities: "AesOutput",
+            req_ext_hash: "H256",
+        },
         SetIdentityNetworksResponse: {
             req_ext_hash: "H256",
         },